@@ -0,0 +1,148 @@
+//! ECS scalability on synthetic worlds: component lookups, transform propagation, subtree
+//! removal, draw cache rebuilds and draw cache upkeep under churn, each at a few world sizes.
+//!
+//! `cargo bench --bench ecs` (add `-- propagation` to run one group).
+
+use criterion::{BatchSize, BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use little_cat::engine::ecs::ComponentId;
+use little_cat::engine::ecs::component::TransformComponent;
+use little_cat::engine::ecs::synthetic::SyntheticWorld;
+use little_cat::engine::graphics::NullUploader;
+use little_cat::engine::{RendererBackend, Universe, UniverseBuilder};
+use std::hint::black_box;
+
+const SIZES: [usize; 3] = [100, 1_000, 10_000];
+
+/// A universe holding `synthetic`, fully registered and uploaded.
+fn synthetic_universe(synthetic: &SyntheticWorld) -> (Universe, Vec<ComponentId>) {
+    let mut u = UniverseBuilder::new()
+        .renderer(RendererBackend::None)
+        .repl(false)
+        .build();
+    let roots = synthetic.spawn(&mut u.context());
+    u.systems
+        .process_commands(&mut u.world, &mut u.visuals, &mut u.command_queue);
+    u.prepare_render_with(&mut NullUploader::default());
+    u.visuals.prepare_draw_cache();
+    (u, roots)
+}
+
+fn world_access(c: &mut Criterion) {
+    let mut group = c.benchmark_group("world_access");
+    for n in SIZES {
+        let (u, roots) = synthetic_universe(&SyntheticWorld::new(n));
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(n), &roots, |b, roots| {
+            b.iter(|| {
+                roots
+                    .iter()
+                    .filter_map(|&cid| u.world.get_component_by_id_as::<TransformComponent>(cid))
+                    .map(|t| t.transform.translation.x)
+                    .sum::<f32>()
+            })
+        });
+    }
+    group.finish();
+}
+
+/// Move every root of a 4-deep hierarchy and apply the queued commands.
+fn propagation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("propagation");
+    for n in SIZES {
+        let (mut u, roots) = synthetic_universe(&SyntheticWorld::new(n).with_depth(4));
+        let mut frame = 0.0f32;
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_function(BenchmarkId::from_parameter(n), |b| {
+            b.iter(|| {
+                frame += 1.0;
+                for &root in &roots {
+                    let Some(t) = u
+                        .world
+                        .get_component_by_id_as_mut::<TransformComponent>(root)
+                    else {
+                        continue;
+                    };
+                    let [x, _, z] = t.transform.translation.to_array();
+                    t.set_position(&mut u.command_queue, x, frame.sin(), z);
+                }
+                u.systems
+                    .process_commands(&mut u.world, &mut u.visuals, &mut u.command_queue);
+            })
+        });
+    }
+    group.finish();
+}
+
+/// Remove every root of a 4-deep hierarchy together with its descendants.
+fn subtree_removal(c: &mut Criterion) {
+    let mut group = c.benchmark_group("subtree_removal");
+    for n in SIZES {
+        let synthetic = SyntheticWorld::new(n).with_depth(4);
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_function(BenchmarkId::from_parameter(n), |b| {
+            b.iter_batched(
+                || synthetic_universe(&synthetic),
+                |(mut u, roots)| {
+                    for root in roots {
+                        let _ = u.world.remove_component_subtree(root);
+                    }
+                    u
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+/// Re-sort every instance into draw buckets, as after a material's blend mode changes.
+fn prepare_draw_cache(c: &mut Criterion) {
+    let mut group = c.benchmark_group("prepare_draw_cache");
+    for n in SIZES {
+        let (mut u, _) = synthetic_universe(&SyntheticWorld::new(n));
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_function(BenchmarkId::from_parameter(n), |b| {
+            b.iter(|| {
+                u.visuals.invalidate_draw_cache();
+                black_box(u.visuals.prepare_draw_cache())
+            })
+        });
+    }
+    group.finish();
+}
+
+/// Move 1% of the instances to another z-order, then bring the draw cache up to date.
+fn draw_cache_churn(c: &mut Criterion) {
+    let mut group = c.benchmark_group("draw_cache_churn");
+    for n in SIZES {
+        let (mut u, roots) = synthetic_universe(&SyntheticWorld::new(n));
+        let handles: Vec<_> = roots
+            .iter()
+            .filter_map(|&root| u.world.children_of(root).first().copied())
+            .filter_map(|cid| u.visuals.instance_for_component(cid))
+            .collect();
+        let step = 100;
+        let mut frame = 0usize;
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_function(BenchmarkId::from_parameter(n), |b| {
+            b.iter(|| {
+                frame += 1;
+                for &handle in handles.iter().skip(frame % step).step_by(step) {
+                    u.visuals.update_z_order(handle, (frame % 3) as i32);
+                }
+                black_box(u.visuals.prepare_draw_cache())
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    world_access,
+    propagation,
+    subtree_removal,
+    prepare_draw_cache,
+    draw_cache_churn
+);
+criterion_main!(benches);
@@ -0,0 +1,218 @@
+//! The 7-shapes demo scene: colored 2D shapes, a WASD/QE-driven triangle carrying a point
+//! light, and a textured square.
+//!
+//! `cargo run --example demo`
+
+use little_cat::engine::ecs::component::{
+    BackgroundComponent, ColorComponent, InputComponent, PointLightComponent, RenderableComponent,
+    TextureComponent, TransformComponent,
+};
+use little_cat::engine::graphics::mesh::MeshFactory;
+use little_cat::engine::graphics::primitives::{MaterialHandle, Outline};
+use little_cat::engine::user_input::UserInput;
+use little_cat::engine::{InitialScene, Universe, UniverseBuilder, Windowing, ecs};
+use little_cat::utils::color::Color;
+use little_cat::utils::{crash_report, logger};
+use std::f32::consts::PI;
+
+fn main() {
+    logger::init();
+    crash_report::install_panic_hook();
+
+    let universe = UniverseBuilder::new()
+        .scene(InitialScene::custom(build_demo_scene_7_shapes))
+        .build();
+
+    Windowing::run_app(universe, UserInput::new()).expect("Windowing failed");
+}
+
+fn build_demo_scene_7_shapes(u: &mut Universe) {
+    u.visuals
+        .set_material_outline(MaterialHandle::TOON_MESH, Some(Outline::DEFAULT));
+
+    // Register CPU meshes once and reuse handles.
+    let tri_mesh = u.render_assets.register_mesh(MeshFactory::triangle_2d());
+    let square_mesh = u.render_assets.register_mesh(MeshFactory::quad_2d());
+
+    #[allow(clippy::too_many_arguments)]
+    fn spawn(
+        world: &mut ecs::World,
+        queue: &mut ecs::CommandQueue,
+        mesh: little_cat::engine::graphics::primitives::CpuMeshHandle,
+        x: f32,
+        y: f32,
+        s: f32,
+        r: f32,
+        color: Color,
+        input_driven: bool,
+    ) -> ecs::ComponentId {
+        let transform = world.add_component(
+            TransformComponent::new()
+                .with_position(x, y, 0.0)
+                .with_scale(s, s, 1.0)
+                .with_rotation_euler(0.0, 0.0, r),
+        );
+        let renderable = world.add_component(RenderableComponent::new(
+            little_cat::engine::graphics::primitives::Renderable::new(
+                mesh,
+                MaterialHandle::TOON_MESH,
+            ),
+        ));
+        let color_c = world.add_component(ColorComponent::color(color));
+
+        // Topology: (optional Input) -> Transform -> Renderable
+        let _ = world.add_child(transform, renderable);
+        let _ = world.add_child(renderable, color_c);
+
+        if input_driven {
+            let input = world.add_component(InputComponent::new().with_speed(0.5));
+            let _ = world.add_child(input, transform);
+            world.init_component_tree(input, queue);
+        } else {
+            world.init_component_tree(transform, queue);
+        }
+
+        transform
+    }
+
+    // Spawn shapes.
+    // One triangle is input-driven (WASD/QE). The point light is attached under the same
+    // transform so it moves with the triangle.
+    let tri_root_transform = u
+        .world
+        .add_component(TransformComponent::new().with_position(0.5, 0.50, 0.0));
+
+    // Visual transform under the root; this is where we apply rotation/scale.
+    // Rotating by PI should visually flip the triangle while leaving its input-driven
+    // movement (on the root transform) unchanged.
+    let tri_visual_transform = u.world.add_component(
+        TransformComponent::new()
+            .with_scale(0.30, 0.30, 1.0)
+            .with_rotation_euler(0.0, 0.0, (2.0 * PI / 3.0) + PI),
+    );
+    let tri_renderable = u.world.add_component(RenderableComponent::new(
+        little_cat::engine::graphics::primitives::Renderable::new(
+            tri_mesh,
+            MaterialHandle::TOON_MESH,
+        ),
+    ));
+    let tri_color = u
+        .world
+        .add_component(ColorComponent::rgba(0.2, 1.0, 0.2, 1.0));
+    let tri_light = u.world.add_component(
+        PointLightComponent::new()
+            .with_distance(10.0)
+            .with_color(1.0, 0.0, 0.0),
+    );
+
+    let _ = u.world.add_child(tri_root_transform, tri_visual_transform);
+    let _ = u.world.add_child(tri_visual_transform, tri_renderable);
+    let _ = u.world.add_child(tri_renderable, tri_color);
+    let _ = u.world.add_child(tri_root_transform, tri_light);
+
+    let tri_input = u.world.add_component(InputComponent::new().with_speed(0.5));
+    let _ = u.world.add_child(tri_input, tri_root_transform);
+    u.world.init_component_tree(tri_input, &mut u.command_queue);
+
+    spawn(
+        &mut u.world,
+        &mut u.command_queue,
+        square_mesh,
+        -0.80,
+        -0.30,
+        0.25,
+        0.0,
+        Color::rgb(1.0, 0.2, 0.2),
+        false,
+    );
+    spawn(
+        &mut u.world,
+        &mut u.command_queue,
+        square_mesh,
+        -0.40,
+        -0.30,
+        0.25,
+        0.0,
+        Color::rgb(1.0, 0.6, 0.2),
+        false,
+    );
+    spawn(
+        &mut u.world,
+        &mut u.command_queue,
+        square_mesh,
+        0.00,
+        -0.30,
+        0.25,
+        0.0,
+        Color::rgb(1.0, 1.0, 0.2),
+        false,
+    );
+    spawn(
+        &mut u.world,
+        &mut u.command_queue,
+        square_mesh,
+        0.40,
+        -0.30,
+        0.25,
+        0.0,
+        Color::rgb(0.2, 0.6, 1.0),
+        false,
+    );
+    spawn(
+        &mut u.world,
+        &mut u.command_queue,
+        square_mesh,
+        0.80,
+        -0.30,
+        0.25,
+        0.0,
+        Color::rgb(0.8, 0.2, 1.0),
+        false,
+    );
+    spawn(
+        &mut u.world,
+        &mut u.command_queue,
+        tri_mesh,
+        0.30,
+        0.35,
+        0.30,
+        -PI,
+        Color::rgb(1.0, 1.0, 1.0),
+        false,
+    );
+
+    // Textured square.
+    let tex_transform = u.world.add_component(
+        TransformComponent::new()
+            .with_position(0.0, 0.10, 0.0)
+            .with_scale(0.45, 0.45, 1.0),
+    );
+    let tex_renderable = u.world.add_component(RenderableComponent::new(
+        little_cat::engine::graphics::primitives::Renderable::new(
+            square_mesh,
+            MaterialHandle::TOON_MESH,
+        ),
+    ));
+    let tex_color = u
+        .world
+        .add_component(ColorComponent::rgba(1.0, 1.0, 1.0, 1.0));
+    let tex = u
+        .world
+        .add_component(TextureComponent::from_png("assets/cat-face-neutral.png"));
+
+    let _ = u.world.add_child(tex_transform, tex_renderable);
+    let _ = u.world.add_child(tex_renderable, tex_color);
+    let _ = u.world.add_child(tex_renderable, tex);
+    u.world
+        .init_component_tree(tex_transform, &mut u.command_queue);
+
+    let background = u.world.add_component(BackgroundComponent::gradient(
+        Color::rgb(0.05, 0.05, 0.15),
+        Color::rgb(0.25, 0.1, 0.2),
+    ));
+    u.world
+        .init_component_tree(background, &mut u.command_queue);
+
+    // NOTE: This demo intentionally does not spawn a camera.
+    // VisualWorld defaults to an identity 2D camera transform.
+}
@@ -0,0 +1,231 @@
+//! Derive macros for `little_cat`.
+//!
+//! `#[derive(CatComponent)]` implements `ComponentData` (type name, `Any` access, encode) and
+//! `ComponentType` (decode) for a component struct, and generates its `SCHEMA`:
+//!
+//! ```ignore
+//! #[derive(Debug, Default, CatComponent)]
+//! #[cat(name = "health")]
+//! pub struct HealthComponent {
+//!     /// Hit points left.
+//!     #[cat(range = 0.0..=f32::INFINITY)]
+//!     pub hp: f32,
+//!     #[cat(skip)]
+//!     cached: Option<ComponentId>,
+//! }
+//! ```
+//!
+//! Every field not marked `#[cat(skip)]` must implement `FieldCodec`. Decoding starts from
+//! `Default::default()` and overwrites the fields present in the data; `#[cat(after_decode =
+//! method)]` on the struct names a `fn(&mut self)` run afterwards (e.g. to mark caches dirty).
+//!
+//! `SCHEMA` lists the encoded fields with their doc comments. Field types come from
+//! `SchemaField`, or `#[cat(ty = FieldType::Rgb)]` where the Rust type says too little;
+//! `#[cat(range = min..=max)]` limits the values `set` accepts, and `#[cat(hidden)]` keeps a
+//! field that is saved but not meant to be edited out of the schema.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::parse::ParseStream;
+use syn::{Data, DeriveInput, Expr, Fields, Ident, LitStr, Token, parse_macro_input};
+
+#[proc_macro_derive(CatComponent, attributes(cat))]
+pub fn derive_cat_component(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let ident = &input.ident;
+
+    let mut name: Option<LitStr> = None;
+    let mut after_decode: Option<Ident> = None;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("cat")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                name = Some(meta.value()?.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("after_decode") {
+                after_decode = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `name = \"...\"` or `after_decode = method`"))
+            }
+        })?;
+    }
+    let Some(name) = name else {
+        return Err(syn::Error::new_spanned(
+            ident,
+            "CatComponent needs #[cat(name = \"...\")]",
+        ));
+    };
+
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            ident,
+            "CatComponent can only be derived for structs",
+        ));
+    };
+    let schema = quote! { ::little_cat::engine::ecs::component::schema };
+    let mut fields = Vec::new();
+    let mut schema_fields = Vec::new();
+    match &data.fields {
+        Fields::Named(named) => {
+            for field in &named.named {
+                let mut skip = false;
+                let mut hidden = false;
+                let mut range: Option<(Expr, Expr)> = None;
+                let mut ty: Option<Expr> = None;
+                for attr in field.attrs.iter().filter(|a| a.path().is_ident("cat")) {
+                    attr.parse_nested_meta(|meta| {
+                        if meta.path.is_ident("skip") {
+                            skip = true;
+                        } else if meta.path.is_ident("hidden") {
+                            hidden = true;
+                        } else if meta.path.is_ident("range") {
+                            range = Some(parse_range(meta.value()?)?);
+                        } else if meta.path.is_ident("ty") {
+                            ty = Some(meta.value()?.parse()?);
+                        } else {
+                            return Err(meta.error(
+                                "expected `skip`, `hidden`, `range = min..=max` or `ty = ...`",
+                            ));
+                        }
+                        Ok(())
+                    })?;
+                }
+                if skip {
+                    continue;
+                }
+                let ident = field.ident.clone().expect("named field");
+                if !hidden {
+                    let key = ident.to_string();
+                    let doc = doc_comment(&field.attrs);
+                    let field_ty = &field.ty;
+                    let ty = ty.map_or_else(
+                        || quote! { <#field_ty as #schema::SchemaField>::FIELD_TYPE },
+                        |ty| quote! {{ use #schema::FieldType; #ty }},
+                    );
+                    let range = range.map(|(min, max)| quote! { .with_range(#min, #max) });
+                    schema_fields.push(quote! {
+                        #schema::FieldSchema::new(#key, #ty, #doc) #range
+                    });
+                }
+                fields.push(ident);
+            }
+        }
+        Fields::Unit => {}
+        Fields::Unnamed(_) => {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "CatComponent needs named fields (or a unit struct)",
+            ));
+        }
+    }
+    let keys: Vec<String> = fields.iter().map(|f| f.to_string()).collect();
+
+    let after_decode = after_decode.map(|m| quote! { out.#m(); });
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let codec = quote! { ::little_cat::engine::ecs::codec };
+    let component = quote! { ::little_cat::engine::ecs::component };
+
+    Ok(quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// Encoded fields, generated by `#[derive(CatComponent)]`.
+            pub const SCHEMA: #schema::ComponentSchema = #schema::ComponentSchema {
+                type_name: #name,
+                fields: &[#(#schema_fields),*],
+            };
+        }
+
+        impl #impl_generics #component::ComponentData for #ident #ty_generics #where_clause {
+            fn type_name(&self) -> &'static str {
+                #name
+            }
+
+            fn as_any(&self) -> &dyn ::std::any::Any {
+                self
+            }
+
+            fn as_any_mut(&mut self) -> &mut dyn ::std::any::Any {
+                self
+            }
+
+            fn encode(&self) -> #codec::Value {
+                #[allow(unused_mut)]
+                let mut map = #codec::Map::new();
+                #(
+                    map.insert(
+                        #keys.to_string(),
+                        #codec::FieldCodec::encode(&self.#fields),
+                    );
+                )*
+                #codec::Value::Object(map)
+            }
+        }
+
+        impl #impl_generics #component::ComponentType for #ident #ty_generics #where_clause {
+            const TYPE_NAME: &'static str = #name;
+
+            fn decode(value: &#codec::Value) -> ::std::result::Result<Self, #codec::CodecError> {
+                if !value.is_object() {
+                    return Err(#codec::CodecError::NotAnObject(#name));
+                }
+                #[allow(unused_mut)]
+                let mut out = <Self as ::std::default::Default>::default();
+                #(
+                    if let Some(v) = value.get(#keys) {
+                        out.#fields = #codec::FieldCodec::decode(v).ok_or(
+                            #codec::CodecError::Field {
+                                type_name: #name,
+                                field: #keys,
+                            },
+                        )?;
+                    }
+                )*
+                #after_decode
+                Ok(out)
+            }
+        }
+    })
+}
+
+/// `min..=max`, each a number or a constant path, optionally negated.
+fn parse_range(input: ParseStream) -> syn::Result<(Expr, Expr)> {
+    let min = parse_bound(input)?;
+    input.parse::<Token![..=]>()?;
+    let max = parse_bound(input)?;
+    Ok((min, max))
+}
+
+fn parse_bound(input: ParseStream) -> syn::Result<Expr> {
+    let neg = input.parse::<Option<Token![-]>>()?;
+    let value: proc_macro2::TokenStream = if input.peek(syn::Lit) {
+        let lit: syn::Lit = input.parse()?;
+        quote! { #lit }
+    } else {
+        let path: syn::Path = input.parse()?;
+        quote! { #path }
+    };
+    syn::parse2(quote! { #neg #value })
+}
+
+/// The `///` lines on a field, joined into one line.
+fn doc_comment(attrs: &[syn::Attribute]) -> String {
+    let mut lines = Vec::new();
+    for attr in attrs.iter().filter(|a| a.path().is_ident("doc")) {
+        if let syn::Meta::NameValue(nv) = &attr.meta
+            && let Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(s),
+                ..
+            }) = &nv.value
+        {
+            lines.push(s.value().trim().to_string());
+        }
+    }
+    lines.join(" ")
+}
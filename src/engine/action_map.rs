@@ -0,0 +1,408 @@
+//! Named input actions (keys/buttons -> "move_up", "jump", ...).
+//!
+//! Gameplay code asks for actions and axes instead of hard-coding keys, so bindings can change
+//! without touching systems. `InputSystem` owns the map used by `InputComponent`s.
+//!
+//! Bindings can be loaded from / saved to a JSON file (`INPUT_BINDINGS_PATH`) mapping action
+//! names to binding names:
+//!
+//! ```json
+//! { "move_up": ["KeyW", "ArrowUp"], "fire": ["MouseLeft"] }
+//! ```
+//!
+//! XR controller inputs bind the same way (`XrLeftTrigger`, `XrRightThumbstickUp`, ...), so
+//! gameplay code reading actions works unchanged with controllers. Analog inputs count as held
+//! from `XR_PRESS_THRESHOLD` and feed axes with their analog value.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use winit::event::MouseButton;
+use winit::keyboard::{Key, NamedKey};
+
+use crate::engine::AssetError;
+use crate::engine::user_input::{InputState, XrController, XrHand};
+
+/// Bindings file loaded at startup (relative to the working directory or crate root).
+pub const INPUT_BINDINGS_PATH: &str = "assets/input-bindings.json";
+
+/// Named keys accepted in binding names, besides `KeyA`..`KeyZ`, `Digit0`..`Digit9` and
+/// `F1`..`F12`.
+const NAMED_KEYS: &[(&str, NamedKey)] = &[
+    ("Space", NamedKey::Space),
+    ("Enter", NamedKey::Enter),
+    ("Escape", NamedKey::Escape),
+    ("Tab", NamedKey::Tab),
+    ("Backspace", NamedKey::Backspace),
+    ("ArrowUp", NamedKey::ArrowUp),
+    ("ArrowDown", NamedKey::ArrowDown),
+    ("ArrowLeft", NamedKey::ArrowLeft),
+    ("ArrowRight", NamedKey::ArrowRight),
+    ("Shift", NamedKey::Shift),
+    ("Control", NamedKey::Control),
+    ("Alt", NamedKey::Alt),
+];
+
+const FUNCTION_KEYS: [NamedKey; 12] = [
+    NamedKey::F1,
+    NamedKey::F2,
+    NamedKey::F3,
+    NamedKey::F4,
+    NamedKey::F5,
+    NamedKey::F6,
+    NamedKey::F7,
+    NamedKey::F8,
+    NamedKey::F9,
+    NamedKey::F10,
+    NamedKey::F11,
+    NamedKey::F12,
+];
+
+/// Analog XR inputs (trigger, grip, thumbstick directions) at or above this count as held.
+pub const XR_PRESS_THRESHOLD: f32 = 0.5;
+
+const XR_HANDS: &[(&str, XrHand)] = &[("Left", XrHand::Left), ("Right", XrHand::Right)];
+
+const XR_INPUTS: &[(&str, XrInput)] = &[
+    ("Trigger", XrInput::Trigger),
+    ("Grip", XrInput::Grip),
+    ("Primary", XrInput::Primary),
+    ("Secondary", XrInput::Secondary),
+    ("ThumbstickClick", XrInput::ThumbstickClick),
+    ("ThumbstickUp", XrInput::ThumbstickUp),
+    ("ThumbstickDown", XrInput::ThumbstickDown),
+    ("ThumbstickLeft", XrInput::ThumbstickLeft),
+    ("ThumbstickRight", XrInput::ThumbstickRight),
+];
+
+const MOUSE_BUTTONS: &[(&str, MouseButton)] = &[
+    ("MouseLeft", MouseButton::Left),
+    ("MouseRight", MouseButton::Right),
+    ("MouseMiddle", MouseButton::Middle),
+    ("MouseBack", MouseButton::Back),
+    ("MouseForward", MouseButton::Forward),
+];
+
+/// One input of an XR controller; thumbstick directions are separate so they bind like keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum XrInput {
+    Trigger,
+    Grip,
+    Primary,
+    Secondary,
+    ThumbstickClick,
+    ThumbstickUp,
+    ThumbstickDown,
+    ThumbstickLeft,
+    ThumbstickRight,
+}
+
+impl XrInput {
+    /// `0..=1`: analog inputs as reported, buttons as 0 or 1.
+    fn value(self, controller: &XrController) -> f32 {
+        let stick = controller.thumbstick;
+        let value = match self {
+            XrInput::Trigger => controller.trigger,
+            XrInput::Grip => controller.grip,
+            XrInput::Primary => controller.primary as u8 as f32,
+            XrInput::Secondary => controller.secondary as u8 as f32,
+            XrInput::ThumbstickClick => controller.thumbstick_click as u8 as f32,
+            XrInput::ThumbstickUp => stick.y,
+            XrInput::ThumbstickDown => -stick.y,
+            XrInput::ThumbstickLeft => -stick.x,
+            XrInput::ThumbstickRight => stick.x,
+        };
+        if controller.connected {
+            value.clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// A single physical input an action can be bound to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum InputBinding {
+    /// Logical key. Character keys match regardless of case (Shift doesn't break bindings).
+    Key(Key),
+    Mouse(MouseButton),
+    Xr(XrHand, XrInput),
+}
+
+impl InputBinding {
+    pub fn character(c: &str) -> Self {
+        InputBinding::Key(Key::Character(c.to_lowercase().into()))
+    }
+
+    /// Parse a binding name (`KeyW`, `Digit1`, `Space`, `F5`, `MouseLeft`, `XrLeftGrip`, ...).
+    ///
+    /// A single character is accepted as shorthand for that character key.
+    pub fn parse(name: &str) -> Option<Self> {
+        if let Some(rest) = name.strip_prefix("Xr") {
+            let &(hand_name, hand) = XR_HANDS.iter().find(|(n, _)| rest.starts_with(n))?;
+            let input_name = &rest[hand_name.len()..];
+            let &(_, input) = XR_INPUTS.iter().find(|(n, _)| *n == input_name)?;
+            return Some(InputBinding::Xr(hand, input));
+        }
+        if let Some(&(_, button)) = MOUSE_BUTTONS.iter().find(|(n, _)| *n == name) {
+            return Some(InputBinding::Mouse(button));
+        }
+        if let Some(&(_, key)) = NAMED_KEYS.iter().find(|(n, _)| *n == name) {
+            return Some(InputBinding::Key(Key::Named(key)));
+        }
+        if let Some(n) = name.strip_prefix('F').and_then(|n| n.parse::<usize>().ok()) {
+            return FUNCTION_KEYS
+                .get(n.wrapping_sub(1))
+                .map(|&key| InputBinding::Key(Key::Named(key)));
+        }
+        let c = name
+            .strip_prefix("Key")
+            .filter(|c| c.len() == 1 && c.chars().all(|c| c.is_ascii_alphabetic()))
+            .or_else(|| {
+                name.strip_prefix("Digit")
+                    .filter(|c| c.len() == 1 && c.chars().all(|c| c.is_ascii_digit()))
+            })
+            .or_else(|| (name.chars().count() == 1).then_some(name))?;
+        Some(InputBinding::character(c))
+    }
+
+    /// Binding name as accepted by `parse`, or `None` for keys without a name.
+    pub fn name(&self) -> Option<String> {
+        match self {
+            InputBinding::Mouse(button) => MOUSE_BUTTONS
+                .iter()
+                .find(|(_, b)| b == button)
+                .map(|(n, _)| n.to_string()),
+            InputBinding::Key(Key::Character(c)) => {
+                let mut chars = c.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(ch), None) if ch.is_ascii_alphabetic() => {
+                        Some(format!("Key{}", ch.to_ascii_uppercase()))
+                    }
+                    (Some(ch), None) if ch.is_ascii_digit() => Some(format!("Digit{ch}")),
+                    _ => Some(c.to_string()),
+                }
+            }
+            InputBinding::Key(Key::Named(key)) => NAMED_KEYS
+                .iter()
+                .find(|(_, k)| k == key)
+                .map(|(n, _)| n.to_string())
+                .or_else(|| {
+                    FUNCTION_KEYS
+                        .iter()
+                        .position(|k| k == key)
+                        .map(|i| format!("F{}", i + 1))
+                }),
+            InputBinding::Key(_) => None,
+            InputBinding::Xr(hand, input) => {
+                let hand = XR_HANDS.iter().find(|(_, h)| h == hand)?.0;
+                let input = XR_INPUTS.iter().find(|(_, i)| i == input)?.0;
+                Some(format!("Xr{hand}{input}"))
+            }
+        }
+    }
+
+    /// Character keys belong to whoever holds text focus while it is held.
+    fn suppressed(&self, input: &InputState) -> bool {
+        matches!(self, InputBinding::Key(Key::Character(_))) && input.text_focus().is_some()
+    }
+
+    fn down(&self, input: &InputState) -> bool {
+        if self.suppressed(input) {
+            return false;
+        }
+        match self {
+            InputBinding::Key(Key::Character(c)) => {
+                input.key_down(&Key::Character(c.to_lowercase().into()))
+                    || input.key_down(&Key::Character(c.to_uppercase().into()))
+            }
+            InputBinding::Key(key) => input.key_down(key),
+            InputBinding::Mouse(button) => input.mouse_down.contains(button),
+            InputBinding::Xr(hand, xr) => {
+                xr.value(input.xr_controller(*hand)) >= XR_PRESS_THRESHOLD
+            }
+        }
+    }
+
+    /// `0..=1`: analog XR inputs as reported, everything else 1 while held.
+    fn value(&self, input: &InputState) -> f32 {
+        match self {
+            InputBinding::Xr(hand, xr) => xr.value(input.xr_controller(*hand)),
+            _ => self.down(input) as u8 as f32,
+        }
+    }
+
+    fn pressed(&self, input: &InputState) -> bool {
+        if self.suppressed(input) {
+            return false;
+        }
+        match self {
+            InputBinding::Key(Key::Character(c)) => {
+                input.key_pressed(&Key::Character(c.to_lowercase().into()))
+                    || input.key_pressed(&Key::Character(c.to_uppercase().into()))
+            }
+            InputBinding::Key(key) => input.key_pressed(key),
+            InputBinding::Mouse(button) => input.mouse_pressed.contains(button),
+            InputBinding::Xr(hand, xr) => {
+                xr.value(input.xr_controller(*hand)) >= XR_PRESS_THRESHOLD
+                    && xr.value(input.xr_controller_before(*hand)) < XR_PRESS_THRESHOLD
+            }
+        }
+    }
+}
+
+/// Action name -> bindings, plus named axes built from a (negative, positive) action pair.
+#[derive(Debug, Clone, Default)]
+pub struct ActionMap {
+    bindings: HashMap<String, Vec<InputBinding>>,
+    axes: HashMap<String, (String, String)>,
+}
+
+impl ActionMap {
+    /// Empty map (no actions, no axes).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The engine's default layout: WASD movement, Q/E roll and Space to jump.
+    ///
+    /// Axes: `move_x` (left/right), `move_y` (up/down, +Y down like screen space), `roll`.
+    pub fn with_default_bindings() -> Self {
+        let mut map = Self::new();
+        map.bind("move_up", InputBinding::character("w"));
+        map.bind("move_down", InputBinding::character("s"));
+        map.bind("move_left", InputBinding::character("a"));
+        map.bind("move_right", InputBinding::character("d"));
+        map.bind("roll_left", InputBinding::character("q"));
+        map.bind("roll_right", InputBinding::character("e"));
+        map.bind("jump", InputBinding::Key(Key::Named(NamedKey::Space)));
+        map.set_axis("move_x", "move_left", "move_right");
+        map.set_axis("move_y", "move_up", "move_down");
+        map.set_axis("roll", "roll_left", "roll_right");
+        map
+    }
+
+    /// Add a binding to an action (an action can have several).
+    pub fn bind(&mut self, action: impl Into<String>, binding: InputBinding) {
+        let list = self.bindings.entry(action.into()).or_default();
+        if !list.contains(&binding) {
+            list.push(binding);
+        }
+    }
+
+    /// Replace an action's bindings with a single named binding (REPL: `bind move_up KeyW`).
+    pub fn rebind(&mut self, action: &str, binding_name: &str) -> Result<(), String> {
+        let binding = InputBinding::parse(binding_name)
+            .ok_or_else(|| format!("unknown binding '{binding_name}'"))?;
+        self.bindings.insert(action.to_string(), vec![binding]);
+        Ok(())
+    }
+
+    /// Remove all bindings of an action.
+    pub fn unbind(&mut self, action: &str) {
+        self.bindings.remove(action);
+    }
+
+    pub fn bindings(&self, action: &str) -> &[InputBinding] {
+        self.bindings.get(action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Action names with at least one binding.
+    pub fn actions(&self) -> impl Iterator<Item = &str> {
+        self.bindings.keys().map(String::as_str)
+    }
+
+    /// Define `axis` as `positive - negative`.
+    pub fn set_axis(
+        &mut self,
+        axis: impl Into<String>,
+        negative: impl Into<String>,
+        positive: impl Into<String>,
+    ) {
+        self.axes
+            .insert(axis.into(), (negative.into(), positive.into()));
+    }
+
+    /// Bindings as `action -> [binding names]`, sorted for stable files.
+    pub fn to_json(&self) -> String {
+        let named: BTreeMap<&str, Vec<String>> = self
+            .bindings
+            .iter()
+            .map(|(action, list)| {
+                (
+                    action.as_str(),
+                    list.iter().filter_map(InputBinding::name).collect(),
+                )
+            })
+            .collect();
+        serde_json::to_string_pretty(&named).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    /// Replace the bindings of every action listed in `json`; other actions and all axes are
+    /// kept. Unknown binding names are skipped with a warning.
+    pub fn apply_json(&mut self, json: &str) -> Result<(), AssetError> {
+        let named: HashMap<String, Vec<String>> = serde_json::from_str(json)?;
+        for (action, names) in named {
+            let mut list = Vec::with_capacity(names.len());
+            for name in names {
+                match InputBinding::parse(&name) {
+                    Some(binding) => list.push(binding),
+                    None => crate::log_warn!("unknown binding '{name}' for '{action}'"),
+                }
+            }
+            self.bindings.insert(action, list);
+        }
+        Ok(())
+    }
+
+    /// Apply a bindings file on top of the current map (see `apply_json`).
+    pub fn load(&mut self, path: impl AsRef<Path>) -> Result<(), AssetError> {
+        let path = resolve_path(path.as_ref());
+        let json = std::fs::read_to_string(&path).map_err(|e| AssetError::io(path, e))?;
+        self.apply_json(&json)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), AssetError> {
+        let path = resolve_path(path.as_ref());
+        std::fs::write(&path, self.to_json()).map_err(|e| AssetError::io(path, e))
+    }
+
+    pub fn action_down(&self, input: &InputState, action: &str) -> bool {
+        self.bindings(action).iter().any(|b| b.down(input))
+    }
+
+    pub fn action_pressed(&self, input: &InputState, action: &str) -> bool {
+        self.bindings(action).iter().any(|b| b.pressed(input))
+    }
+
+    /// Strongest of an action's bindings in `0..=1` (1 for a held key, the analog value of a
+    /// trigger or thumbstick direction).
+    pub fn action_value(&self, input: &InputState, action: &str) -> f32 {
+        self.bindings(action)
+            .iter()
+            .map(|b| b.value(input))
+            .fold(0.0, f32::max)
+    }
+
+    /// Axis value in `-1..=1`. Unknown axes read as 0.
+    pub fn axis(&self, input: &InputState, axis: &str) -> f32 {
+        let Some((negative, positive)) = self.axes.get(axis) else {
+            return 0.0;
+        };
+        self.action_value(input, positive) - self.action_value(input, negative)
+    }
+}
+
+/// Relative paths resolve against the working directory, falling back to the crate root
+/// (same lookup as `TextureSystem`).
+fn resolve_path(path: &Path) -> PathBuf {
+    if path.is_absolute() || path.exists() {
+        return path.to_path_buf();
+    }
+    let in_crate = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(path);
+    if in_crate.exists() || in_crate.parent().is_some_and(Path::exists) {
+        in_crate
+    } else {
+        path.to_path_buf()
+    }
+}
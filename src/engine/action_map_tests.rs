@@ -0,0 +1,106 @@
+#[cfg(test)]
+mod tests {
+    use crate::engine::action_map::{ActionMap, InputBinding};
+    use winit::event::MouseButton;
+    use winit::keyboard::{Key, NamedKey};
+
+    #[test]
+    fn binding_names_round_trip() {
+        for name in [
+            "KeyW",
+            "Digit7",
+            "Space",
+            "ArrowLeft",
+            "F5",
+            "F12",
+            "MouseLeft",
+        ] {
+            let binding = InputBinding::parse(name).unwrap();
+            assert_eq!(binding.name().as_deref(), Some(name));
+        }
+        assert_eq!(
+            InputBinding::parse("KeyW"),
+            Some(InputBinding::Key(Key::Character("w".into())))
+        );
+        assert_eq!(
+            InputBinding::parse("F1"),
+            Some(InputBinding::Key(Key::Named(NamedKey::F1)))
+        );
+        assert_eq!(InputBinding::parse("F13"), None);
+        assert_eq!(InputBinding::parse("NotAKey"), None);
+    }
+
+    #[test]
+    fn json_overrides_listed_actions_only() {
+        let mut map = ActionMap::with_default_bindings();
+        map.apply_json(r#"{ "move_up": ["ArrowUp", "MouseRight"] }"#)
+            .unwrap();
+
+        assert_eq!(
+            map.bindings("move_up"),
+            &[
+                InputBinding::Key(Key::Named(NamedKey::ArrowUp)),
+                InputBinding::Mouse(MouseButton::Right),
+            ]
+        );
+        assert_eq!(map.bindings("move_down"), &[InputBinding::character("s")]);
+
+        let mut reloaded = ActionMap::new();
+        reloaded.apply_json(&map.to_json()).unwrap();
+        assert_eq!(reloaded.bindings("move_up"), map.bindings("move_up"));
+
+        assert!(map.rebind("move_up", "KeyI").is_ok());
+        assert!(map.rebind("move_up", "Nope").is_err());
+        assert_eq!(map.bindings("move_up"), &[InputBinding::character("i")]);
+    }
+
+    #[test]
+    fn xr_controllers_drive_actions_and_analog_axes() {
+        use crate::engine::action_map::XrInput;
+        use crate::engine::user_input::{InputState, XrController, XrHand};
+        use glam::Vec2;
+
+        assert_eq!(
+            InputBinding::parse("XrRightThumbstickUp"),
+            Some(InputBinding::Xr(XrHand::Right, XrInput::ThumbstickUp))
+        );
+        assert_eq!(
+            InputBinding::parse("XrLeftTrigger")
+                .unwrap()
+                .name()
+                .as_deref(),
+            Some("XrLeftTrigger")
+        );
+        assert_eq!(InputBinding::parse("XrMiddleTrigger"), None);
+
+        let mut map = ActionMap::with_default_bindings();
+        map.apply_json(r#"{ "move_left": ["KeyA", "XrLeftThumbstickLeft"], "move_right": ["XrLeftThumbstickRight"], "fire": ["XrRightTrigger"] }"#)
+            .unwrap();
+        let mut input = InputState::default();
+        let controller = XrController {
+            connected: true,
+            trigger: 0.7,
+            thumbstick: Vec2::new(0.25, 0.0),
+            ..Default::default()
+        };
+        input.set_xr_controller(XrHand::Left, controller);
+        input.set_xr_controller(XrHand::Right, controller);
+        assert_eq!(map.axis(&input, "move_x"), 0.25);
+        assert!(!map.action_down(&input, "move_right"));
+        assert!(map.action_pressed(&input, "fire"));
+
+        input.end_frame();
+        assert!(map.action_down(&input, "fire"));
+        assert!(!map.action_pressed(&input, "fire"));
+
+        // A controller the runtime lost reads as idle.
+        input.set_xr_controller(
+            XrHand::Left,
+            XrController {
+                connected: false,
+                ..controller
+            },
+        );
+        assert_eq!(map.axis(&input, "move_x"), 0.0);
+    }
+}
@@ -0,0 +1,271 @@
+//! Navigation for NPCs in 2D scenes: a walkability grid over the XY plane and A* paths on it.
+//!
+//! Build a `NavGrid` once the level is in the world and store it as a resource; every
+//! `NavAgentComponent` then walks along paths on it:
+//!
+//! ```ignore
+//! let grid = NavGrid::from_world(&world, Aabb::new([0.0, 0.0, 0.0], [64.0, 48.0, 0.0]), 0.5);
+//! world.insert_resource(grid);
+//! ```
+//!
+//! Cells under a `ColliderComponent` or a non-empty tile of a `solid` tilemap are blocked. The
+//! grid doesn't follow later changes: rebuild it, or patch it with `block_aabb` / `set_blocked`.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::engine::ecs::World;
+use crate::engine::ecs::component::{ColliderComponent, TilemapComponent};
+use crate::engine::ecs::system::TransformSystem;
+use crate::engine::spatial::Aabb;
+
+/// Grid cell `(column, row)`; row 0 is at the grid's minimum Y.
+pub type Cell = (u32, u32);
+
+/// Walkable/blocked cells covering a rectangle of the XY plane.
+#[derive(Debug, Clone)]
+pub struct NavGrid {
+    origin: [f32; 2],
+    cell_size: f32,
+    width: u32,
+    height: u32,
+    blocked: Vec<bool>,
+}
+
+impl NavGrid {
+    /// All-walkable grid covering `bounds` in XY (Z is ignored).
+    pub fn new(bounds: Aabb, cell_size: f32) -> Self {
+        let cell_size = cell_size.max(1e-3);
+        let cells = |axis: usize| {
+            ((bounds.max[axis] - bounds.min[axis]) / cell_size)
+                .ceil()
+                .max(1.0) as u32
+        };
+        let (width, height) = (cells(0), cells(1));
+        Self {
+            origin: [bounds.min[0], bounds.min[1]],
+            cell_size,
+            width,
+            height,
+            blocked: vec![false; (width * height) as usize],
+        }
+    }
+
+    /// Grid over `bounds` with the world's colliders and solid tilemaps blocked.
+    pub fn from_world(world: &World, bounds: Aabb, cell_size: f32) -> Self {
+        let mut grid = Self::new(bounds, cell_size);
+        for (cid, _) in world.all_components() {
+            let Some(model) = TransformSystem::world_model(world, cid) else {
+                continue;
+            };
+            if let Some(collider) = world.get_component_by_id_as::<ColliderComponent>(cid) {
+                let h = collider.half_extents;
+                grid.block_aabb(&Aabb::new([-h[0], -h[1], -h[2]], h).transformed(&model));
+            } else if let Some(tilemap) = world.get_component_by_id_as::<TilemapComponent>(cid)
+                && tilemap.solid
+            {
+                grid.block_tilemap(tilemap, &model);
+            }
+        }
+        grid
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Cell containing world point `p`, or `None` outside the grid.
+    pub fn cell_at(&self, p: [f32; 2]) -> Option<Cell> {
+        let x = ((p[0] - self.origin[0]) / self.cell_size).floor();
+        let y = ((p[1] - self.origin[1]) / self.cell_size).floor();
+        (x >= 0.0 && y >= 0.0 && x < self.width as f32 && y < self.height as f32)
+            .then_some((x as u32, y as u32))
+    }
+
+    pub fn cell_center(&self, (x, y): Cell) -> [f32; 2] {
+        [
+            self.origin[0] + (x as f32 + 0.5) * self.cell_size,
+            self.origin[1] + (y as f32 + 0.5) * self.cell_size,
+        ]
+    }
+
+    /// Cells outside the grid count as blocked.
+    pub fn is_blocked(&self, (x, y): Cell) -> bool {
+        x >= self.width || y >= self.height || self.blocked[(y * self.width + x) as usize]
+    }
+
+    pub fn set_blocked(&mut self, (x, y): Cell, blocked: bool) {
+        if x < self.width && y < self.height {
+            self.blocked[(y * self.width + x) as usize] = blocked;
+        }
+    }
+
+    /// Block every cell the box overlaps (in XY; touching an edge doesn't count).
+    pub fn block_aabb(&mut self, bounds: &Aabb) {
+        const EPS: f32 = 1e-4;
+        let span = |axis: usize, cells: u32| {
+            let lo = (bounds.min[axis] - self.origin[axis]) / self.cell_size + EPS;
+            let hi = (bounds.max[axis] - self.origin[axis]) / self.cell_size - EPS;
+            let lo = lo.floor().max(0.0) as u32;
+            let hi = hi.ceil().min(cells as f32) as u32;
+            lo..hi
+        };
+        let (xs, ys) = (span(0, self.width), span(1, self.height));
+        for y in ys {
+            for x in xs.clone() {
+                self.blocked[(y * self.width + x) as usize] = true;
+            }
+        }
+    }
+
+    /// Block the cells under every non-empty tile; `model` is the tilemap's world model.
+    pub fn block_tilemap(&mut self, tilemap: &TilemapComponent, model: &[[f32; 4]; 4]) {
+        let [tw, th] = tilemap.tile_size;
+        for y in 0..tilemap.height {
+            for x in 0..tilemap.width {
+                if tilemap.tile(x, y).is_none() {
+                    continue;
+                }
+                let (x0, y0) = (x as f32 * tw, y as f32 * th);
+                let tile = Aabb::new([x0, y0, 0.0], [x0 + tw, y0 + th, 0.0]);
+                self.block_aabb(&tile.transformed(model));
+            }
+        }
+    }
+
+    /// Shortest 8-connected path from `from` to `to`, as world points to walk through in
+    /// order, ending at `to`. Diagonal steps never cut a blocked corner, and points along a
+    /// straight run are dropped.
+    ///
+    /// `None` if either point is outside the grid, `to` is blocked, or nothing connects them.
+    /// `from` may be blocked (an agent pressed into a wall still finds its way out).
+    pub fn find_path(&self, from: [f32; 2], to: [f32; 2]) -> Option<Vec<[f32; 2]>> {
+        let start = self.cell_at(from)?;
+        let goal = self.cell_at(to)?;
+        if self.is_blocked(goal) {
+            return None;
+        }
+        let cells = self.astar(start, goal)?;
+
+        let mut path: Vec<[f32; 2]> = Vec::new();
+        for pair in cells.windows(3) {
+            let d0 = (
+                pair[1].0 as i64 - pair[0].0 as i64,
+                pair[1].1 as i64 - pair[0].1 as i64,
+            );
+            let d1 = (
+                pair[2].0 as i64 - pair[1].0 as i64,
+                pair[2].1 as i64 - pair[1].1 as i64,
+            );
+            if d0 != d1 {
+                path.push(self.cell_center(pair[1]));
+            }
+        }
+        path.push(to);
+        Some(path)
+    }
+
+    /// Cells from `start` to `goal`, both included.
+    fn astar(&self, start: Cell, goal: Cell) -> Option<Vec<Cell>> {
+        let index = |(x, y): Cell| (y * self.width + x) as usize;
+        let heuristic = |(x, y): Cell| {
+            let dx = x.abs_diff(goal.0) as f32;
+            let dy = y.abs_diff(goal.1) as f32;
+            dx.max(dy) + (std::f32::consts::SQRT_2 - 1.0) * dx.min(dy)
+        };
+
+        let mut cost = vec![f32::INFINITY; self.blocked.len()];
+        let mut came_from: Vec<Option<Cell>> = vec![None; self.blocked.len()];
+        let mut open = BinaryHeap::new();
+        cost[index(start)] = 0.0;
+        open.push(Open {
+            estimate: heuristic(start),
+            cell: start,
+        });
+
+        while let Some(Open { cell, .. }) = open.pop() {
+            if cell == goal {
+                let mut cells = vec![goal];
+                let mut cur = goal;
+                while let Some(prev) = came_from[index(cur)] {
+                    cells.push(prev);
+                    cur = prev;
+                }
+                cells.reverse();
+                return Some(cells);
+            }
+            let here = cost[index(cell)];
+            for (next, step) in self.neighbors(cell) {
+                let through = here + step;
+                if through < cost[index(next)] {
+                    cost[index(next)] = through;
+                    came_from[index(next)] = Some(cell);
+                    open.push(Open {
+                        estimate: through + heuristic(next),
+                        cell: next,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// Walkable neighbors of `cell` with the cost of stepping there.
+    fn neighbors(&self, (x, y): Cell) -> impl Iterator<Item = (Cell, f32)> + '_ {
+        const DIRS: [(i32, i32); 8] = [
+            (1, 0),
+            (-1, 0),
+            (0, 1),
+            (0, -1),
+            (1, 1),
+            (1, -1),
+            (-1, 1),
+            (-1, -1),
+        ];
+        let offset = move |dx: i32, dy: i32| {
+            let nx = x.checked_add_signed(dx)?;
+            let ny = y.checked_add_signed(dy)?;
+            (!self.is_blocked((nx, ny))).then_some((nx, ny))
+        };
+        DIRS.into_iter().filter_map(move |(dx, dy)| {
+            let next = offset(dx, dy)?;
+            if dx != 0 && dy != 0 {
+                // No squeezing between two blocked corners.
+                offset(dx, 0)?;
+                offset(0, dy)?;
+                Some((next, std::f32::consts::SQRT_2))
+            } else {
+                Some((next, 1.0))
+            }
+        })
+    }
+}
+
+/// Open-set entry, ordered so `BinaryHeap` pops the lowest estimate first.
+#[derive(Debug, PartialEq)]
+struct Open {
+    estimate: f32,
+    cell: Cell,
+}
+
+impl Eq for Open {}
+
+impl Ord for Open {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.total_cmp(&self.estimate)
+    }
+}
+
+impl PartialOrd for Open {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod tests {
+    use crate::engine::ai::NavGrid;
+    use crate::engine::ecs::World;
+    use crate::engine::ecs::component::{ColliderComponent, TilemapComponent, TransformComponent};
+    use crate::engine::spatial::Aabb;
+
+    fn grid(w: f32, h: f32) -> NavGrid {
+        NavGrid::new(Aabb::new([0.0; 3], [w, h, 0.0]), 1.0)
+    }
+
+    #[test]
+    fn paths_go_around_walls_without_cutting_corners() {
+        // 5x5 with a wall along x = 2 from y = 0 to 3.
+        let mut nav = grid(5.0, 5.0);
+        for y in 0..4 {
+            nav.set_blocked((2, y), true);
+        }
+
+        let path = nav.find_path([0.5, 0.5], [4.5, 0.5]).unwrap();
+        assert_eq!(path.last(), Some(&[4.5, 0.5]));
+        // Walking the straight segments between waypoints never enters the wall, not even
+        // diagonally past its end at (2, 3): the path rounds it through (2, 4).
+        let mut from = glam::Vec2::new(0.5, 0.5);
+        let mut through_gap = false;
+        for &to in &path {
+            let to = glam::Vec2::from(to);
+            for i in 0..=20 {
+                let cell = nav
+                    .cell_at(from.lerp(to, i as f32 / 20.0).to_array())
+                    .unwrap();
+                assert!(!nav.is_blocked(cell), "{path:?} crosses {cell:?}");
+                through_gap |= cell == (2, 4);
+            }
+            from = to;
+        }
+        assert!(through_gap, "{path:?}");
+
+        // Blocked or unreachable goals have no path.
+        assert!(nav.find_path([0.5, 0.5], [2.5, 1.5]).is_none());
+        nav.set_blocked((2, 4), true);
+        assert!(nav.find_path([0.5, 0.5], [4.5, 0.5]).is_none());
+        assert!(nav.find_path([0.5, 0.5], [9.0, 0.5]).is_none());
+    }
+
+    #[test]
+    fn from_world_blocks_colliders_and_solid_tilemaps() {
+        let mut world = World::default();
+        let t = world.add_component(TransformComponent::new().with_position(1.5, 1.5, 0.0));
+        let c = world.add_component(ColliderComponent::new(0.5, 0.5, 0.5));
+        world.add_child(t, c).unwrap();
+
+        let mut tiles = vec![None; 4 * 4];
+        tiles[3 * 4 + 3] = Some(0);
+        let solid = world.add_component(TransformComponent::new());
+        let map = world.add_component(TilemapComponent::new(4, 4, [1.0, 1.0]).with_tiles(tiles));
+        world.add_child(solid, map).unwrap();
+
+        let nav = NavGrid::from_world(&world, Aabb::new([0.0; 3], [4.0, 4.0, 0.0]), 1.0);
+        assert!(nav.is_blocked((1, 1)));
+        assert!(!nav.is_blocked((0, 1)) && !nav.is_blocked((2, 1)));
+        // Tilemaps block only when marked solid.
+        assert!(!nav.is_blocked((3, 3)));
+        world
+            .get_component_by_id_as_mut::<TilemapComponent>(map)
+            .unwrap()
+            .solid = true;
+        let nav = NavGrid::from_world(&world, Aabb::new([0.0; 3], [4.0, 4.0, 0.0]), 1.0);
+        assert!(nav.is_blocked((3, 3)));
+        assert_eq!(nav.width(), 4);
+        assert_eq!(nav.height(), 4);
+    }
+}
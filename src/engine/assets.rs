@@ -0,0 +1,349 @@
+//! Asset loading off the frame thread, and the scene -> asset dependency graph.
+//!
+//! `AssetServer` resolves asset paths, decodes images on a small worker pool and remembers
+//! which assets each loaded scene needs. `Universe::preload` computes a scene's
+//! `AssetDependencies` and starts every decode before the scene is instantiated, so the first
+//! frame that shows it finds its textures ready instead of decoding them one by one.
+//!
+//! Meshes are registered in memory (`RenderAssets`) and shaders are built into the renderer;
+//! they are tracked in the graph but need no loading.
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::engine::ecs::World;
+use crate::engine::ecs::codec::{ComponentDataNode, Scene};
+use crate::engine::error::AssetError;
+use crate::engine::graphics::color_grading::parse_cube_lut;
+use crate::engine::graphics::primitives::{CpuMeshHandle, MaterialHandle};
+
+/// Upper bound on decode threads.
+pub const MAX_ASSET_WORKERS: usize = 4;
+
+/// Assets referenced by a scene (or a single component, see `Component::dependencies`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AssetDependencies {
+    /// Texture URIs (see `TextureComponent`).
+    pub textures: BTreeSet<String>,
+    pub meshes: BTreeSet<CpuMeshHandle>,
+    /// Materials select the shader pipelines a scene draws with.
+    pub materials: BTreeSet<MaterialHandle>,
+    /// Prefab scene files (see `ecs::prefab`).
+    pub prefabs: BTreeSet<String>,
+}
+
+impl AssetDependencies {
+    /// Everything `scene` needs, including the contents of the prefabs it references.
+    ///
+    /// Prefabs are loaded into `world.prefabs()`; nodes of unknown types are skipped (the
+    /// scene will fail to instantiate anyway).
+    pub fn of_scene(scene: &Scene, world: &mut World) -> Result<Self, AssetError> {
+        let mut deps = Self::default();
+        for root in &scene.roots {
+            let resolved = world.prefabs_mut().resolve(root)?;
+            deps.collect(world, &resolved);
+        }
+        Ok(deps)
+    }
+
+    fn collect(&mut self, world: &World, node: &ComponentDataNode) {
+        if let Some(instance) = &node.prefab {
+            self.prefabs.insert(instance.source.clone());
+        }
+        if let Ok(component) = world.registry().create(&node.type_name, &node.data) {
+            component.dependencies(self);
+        }
+        for child in &node.children {
+            self.collect(world, child);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.textures.is_empty()
+            && self.meshes.is_empty()
+            && self.materials.is_empty()
+            && self.prefabs.is_empty()
+    }
+}
+
+/// RGBA8 pixels of a decoded image.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedImage {
+    pub rgba: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Clone)]
+enum ImageSlot {
+    Loading,
+    Ready(Arc<DecodedImage>),
+    Failed(String),
+}
+
+type DecodeJob = (String, PathBuf);
+type DecodeResult = (String, Result<DecodedImage, AssetError>);
+
+struct Workers {
+    jobs: Sender<DecodeJob>,
+    results: Receiver<DecodeResult>,
+}
+
+impl std::fmt::Debug for Workers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Workers").finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct AssetServer {
+    /// Where relative paths resolve; `None` tries the working directory, then the crate root.
+    root: Option<PathBuf>,
+    images: HashMap<String, ImageSlot>,
+    /// Started on the first background request.
+    workers: Option<Workers>,
+    /// Scene file -> assets it needs.
+    scenes: HashMap<PathBuf, AssetDependencies>,
+}
+
+impl AssetServer {
+    pub fn new(root: Option<PathBuf>) -> Self {
+        Self {
+            root,
+            ..Self::default()
+        }
+    }
+
+    pub fn root(&self) -> Option<&Path> {
+        self.root.as_deref()
+    }
+
+    /// Filesystem path of `uri` (a path, optionally prefixed with `file://`).
+    pub fn resolve(&self, uri: &str) -> Result<PathBuf, AssetError> {
+        let raw = Path::new(uri.strip_prefix("file://").unwrap_or(uri));
+        let candidates: Vec<PathBuf> = if raw.is_absolute() {
+            vec![raw.to_path_buf()]
+        } else if let Some(root) = &self.root {
+            vec![root.join(raw)]
+        } else {
+            // Working directory first, then the crate root (works even if CWD is target/...).
+            let mut c = Vec::new();
+            if let Ok(cwd) = std::env::current_dir() {
+                c.push(cwd.join(raw));
+            }
+            c.push(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(raw));
+            c
+        };
+        match candidates.iter().find(|p| p.exists()) {
+            Some(p) => Ok(p.clone()),
+            None => Err(AssetError::NotFound {
+                uri: uri.to_string(),
+                tried: candidates,
+            }),
+        }
+    }
+
+    /// Start decoding `uri` in the background unless it is already loading or loaded.
+    pub fn request_image(&mut self, uri: &str) {
+        if self.images.contains_key(uri) {
+            return;
+        }
+        let path = match self.resolve(uri) {
+            Ok(path) => path,
+            Err(e) => {
+                self.images
+                    .insert(uri.to_string(), ImageSlot::Failed(e.to_string()));
+                return;
+            }
+        };
+        let workers = self.workers.get_or_insert_with(spawn_workers);
+        if workers.jobs.send((uri.to_string(), path)).is_ok() {
+            self.images.insert(uri.to_string(), ImageSlot::Loading);
+        }
+    }
+
+    /// Pixels of `uri`, waiting for a background decode in flight or decoding inline if it
+    /// was never requested.
+    pub fn load_image(&mut self, uri: &str) -> Result<Arc<DecodedImage>, String> {
+        self.poll();
+        if !self.images.contains_key(uri) {
+            let result = self.resolve(uri).and_then(|path| decode_image(uri, &path));
+            self.store(uri.to_string(), result);
+        }
+        while matches!(self.images.get(uri), Some(ImageSlot::Loading)) {
+            let Some(result) = self.workers.as_ref().and_then(|w| w.results.recv().ok()) else {
+                self.images.insert(
+                    uri.to_string(),
+                    ImageSlot::Failed("asset workers stopped".to_string()),
+                );
+                break;
+            };
+            self.store(result.0, result.1);
+        }
+        match self.images.get(uri) {
+            Some(ImageSlot::Ready(image)) => Ok(image.clone()),
+            Some(ImageSlot::Failed(e)) => Err(e.clone()),
+            _ => Err(format!("{uri}: not loaded")),
+        }
+    }
+
+    /// Pixels of `uri` if its decode finished, without waiting. `None` while it is loading or
+    /// if it was never requested.
+    pub fn try_image(&mut self, uri: &str) -> Option<Result<Arc<DecodedImage>, String>> {
+        self.poll();
+        match self.images.get(uri)? {
+            ImageSlot::Loading => None,
+            ImageSlot::Ready(image) => Some(Ok(image.clone())),
+            ImageSlot::Failed(e) => Some(Err(e.clone())),
+        }
+    }
+
+    /// Drop the decoded pixels of `uri` (e.g. once they are on the GPU).
+    pub fn release_image(&mut self, uri: &str) {
+        if let Some(ImageSlot::Ready(_)) = self.images.get(uri) {
+            self.images.remove(uri);
+        }
+    }
+
+    /// Collect finished background decodes.
+    pub fn poll(&mut self) {
+        let Some(workers) = &self.workers else {
+            return;
+        };
+        let finished: Vec<DecodeResult> = workers.results.try_iter().collect();
+        for (uri, result) in finished {
+            self.store(uri, result);
+        }
+    }
+
+    fn store(&mut self, uri: String, result: Result<DecodedImage, AssetError>) {
+        let slot = match result {
+            Ok(image) => ImageSlot::Ready(Arc::new(image)),
+            Err(e) => ImageSlot::Failed(e.to_string()),
+        };
+        self.images.insert(uri, slot);
+    }
+
+    /// Number of background decodes not collected yet.
+    pub fn loading_count(&self) -> usize {
+        self.images
+            .values()
+            .filter(|s| matches!(s, ImageSlot::Loading))
+            .count()
+    }
+
+    /// Block until every background decode finished or `timeout` passed. Returns `true` when
+    /// nothing is loading anymore.
+    pub fn wait_idle(&mut self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        loop {
+            self.poll();
+            if self.loading_count() == 0 {
+                return true;
+            }
+            let Some(left) = deadline.checked_duration_since(Instant::now()) else {
+                return false;
+            };
+            let Some(workers) = &self.workers else {
+                return false;
+            };
+            if let Ok((uri, result)) = workers.results.recv_timeout(left) {
+                self.store(uri, result);
+            }
+        }
+    }
+
+    /// Start loading everything in `deps` that needs loading.
+    pub fn preload(&mut self, deps: &AssetDependencies) {
+        for uri in &deps.textures {
+            self.request_image(uri);
+        }
+    }
+
+    /// Remember what a scene file needs (replacing what was recorded for it before).
+    pub fn record_scene(&mut self, scene: impl Into<PathBuf>, deps: AssetDependencies) {
+        self.scenes.insert(scene.into(), deps);
+    }
+
+    pub fn scene_dependencies(&self, scene: &Path) -> Option<&AssetDependencies> {
+        self.scenes.get(scene)
+    }
+
+    /// Scene files that use the texture `uri`, sorted.
+    pub fn scenes_using_texture(&self, uri: &str) -> Vec<&Path> {
+        let mut scenes: Vec<&Path> = self
+            .scenes
+            .iter()
+            .filter(|(_, deps)| deps.textures.contains(uri))
+            .map(|(path, _)| path.as_path())
+            .collect();
+        scenes.sort();
+        scenes
+    }
+}
+
+fn spawn_workers() -> Workers {
+    let (jobs, job_rx) = mpsc::channel::<DecodeJob>();
+    let (result_tx, results) = mpsc::channel();
+    let job_rx = Arc::new(Mutex::new(job_rx));
+    let count = std::thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(MAX_ASSET_WORKERS);
+    for i in 0..count {
+        let job_rx = Arc::clone(&job_rx);
+        let result_tx = result_tx.clone();
+        let spawned = std::thread::Builder::new()
+            .name(format!("asset-loader-{i}"))
+            .spawn(move || {
+                loop {
+                    // Exits once the AssetServer (and with it the job sender) is dropped.
+                    let job = job_rx
+                        .lock()
+                        .map_err(|_| ())
+                        .and_then(|rx| rx.recv().map_err(|_| ()));
+                    let Ok((uri, path)) = job else {
+                        return;
+                    };
+                    let result = decode_image(&uri, &path);
+                    if result_tx.send((uri, result)).is_err() {
+                        return;
+                    }
+                }
+            });
+        if let Err(e) = spawned {
+            crate::log_warn!("asset worker {i}: {e}");
+        }
+    }
+    Workers { jobs, results }
+}
+
+fn decode_image(uri: &str, path: &Path) -> Result<DecodedImage, AssetError> {
+    let bytes = std::fs::read(path).map_err(|e| AssetError::io(path, e))?;
+    // `.cube` color grading LUTs decode to the strip layout LUT images use.
+    if path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("cube"))
+    {
+        let lut = |message: String| AssetError::Lut {
+            uri: uri.to_string(),
+            message,
+        };
+        let text = String::from_utf8(bytes).map_err(|e| lut(e.to_string()))?;
+        return parse_cube_lut(&text).map_err(lut);
+    }
+    let rgba = image::load_from_memory(&bytes)
+        .map_err(|source| AssetError::Image {
+            uri: uri.to_string(),
+            source,
+        })?
+        .to_rgba8();
+    let (width, height) = rgba.dimensions();
+    Ok(DecodedImage {
+        rgba: rgba.into_raw(),
+        width,
+        height,
+    })
+}
@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::engine::assets::{AssetDependencies, AssetServer};
+    use crate::engine::ecs::World;
+    use crate::engine::ecs::codec::{ComponentCodec, Scene};
+    use crate::engine::ecs::component::{
+        RenderableComponent, TextureComponent, TransformComponent,
+    };
+    use crate::engine::graphics::primitives::{CpuMeshHandle, MaterialHandle, Renderable};
+
+    #[test]
+    fn scene_dependencies_include_textures_meshes_and_materials() {
+        let mut w = World::default();
+        let root = w.add_component(TransformComponent::new());
+        let renderable = w.add_component(RenderableComponent::new(Renderable::new(
+            CpuMeshHandle(3),
+            MaterialHandle::TOON_MESH,
+        )));
+        let texture = w.add_component(TextureComponent::new("textures/cat.png"));
+        w.add_child(root, renderable).unwrap();
+        w.add_child(renderable, texture).unwrap();
+        let scene = ComponentCodec::encode_world(&w);
+
+        let deps = AssetDependencies::of_scene(&scene, &mut World::default()).unwrap();
+        assert_eq!(
+            deps.textures.iter().collect::<Vec<_>>(),
+            ["textures/cat.png"]
+        );
+        assert!(deps.meshes.contains(&CpuMeshHandle(3)));
+        assert!(deps.materials.contains(&MaterialHandle::TOON_MESH));
+        assert!(deps.prefabs.is_empty());
+        assert!(
+            AssetDependencies::of_scene(&Scene::default(), &mut w)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn preloaded_images_decode_in_the_background() {
+        let dir = std::env::temp_dir().join(format!("little-cat-assets-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        image::RgbaImage::from_pixel(2, 3, image::Rgba([255, 0, 0, 255]))
+            .save(dir.join("red.png"))
+            .unwrap();
+
+        let mut assets = AssetServer::new(Some(dir.clone()));
+        let mut deps = AssetDependencies::default();
+        deps.textures.insert("red.png".to_string());
+        deps.textures.insert("missing.png".to_string());
+        assets.preload(&deps);
+        assert!(assets.wait_idle(Duration::from_secs(10)));
+
+        let image = assets.load_image("red.png").unwrap();
+        assert_eq!((image.width, image.height), (2, 3));
+        assert_eq!(&image.rgba[..4], &[255, 0, 0, 255]);
+        assert!(
+            assets
+                .load_image("missing.png")
+                .unwrap_err()
+                .contains("not found")
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
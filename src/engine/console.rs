@@ -0,0 +1,240 @@
+//! Drop-down in-game console state (input line + scrollback).
+//!
+//! `Universe` toggles it with `` ` ``, feeds submitted lines through `ReplBackend` and pushes
+//! the output back here. While open it holds text focus (`CONSOLE_TEXT_FOCUS`), so typing
+//! doesn't trigger gameplay actions. Ctrl+V pastes the clipboard (e.g. output copied with
+//! `... | copy`) into the input line.
+//!
+//! While open, `draw` lays the scrollback and input line out with `graphics::text` as a
+//! panel pinned to the top of the screen.
+
+use std::collections::VecDeque;
+
+use slotmap::Key as _;
+use winit::keyboard::{Key, NamedKey};
+
+use crate::engine::ecs::ComponentId;
+use crate::engine::ecs::component::ScreenAnchor;
+use crate::engine::ecs::system::ScreenAnchorSystem;
+use crate::engine::graphics::primitives::InstanceHandle;
+use crate::engine::graphics::text::{self, LINE_HEIGHT, TextMeshBuilder};
+use crate::engine::graphics::{
+    ALL_VISIBILITY_LAYERS, CpuMesh, GpuRenderable, MAX_DEPTH_Z_ORDER, MaterialHandle, MeshHandle,
+    RenderUploader, RendererError, TextureHandle, Transform, VisualWorld,
+};
+use crate::engine::user_input::InputState;
+use crate::utils::clipboard;
+
+/// Text-focus owner name used while the console is open.
+pub const CONSOLE_TEXT_FOCUS: &str = "console";
+
+/// Scrollback lines kept.
+pub const CONSOLE_SCROLLBACK: usize = 200;
+
+/// Lines shown when the console is dropped down.
+pub const CONSOLE_VISIBLE_LINES: usize = 12;
+
+/// Screen pixels per font pixel.
+const CONSOLE_TEXT_SCALE: f32 = 2.0;
+
+/// Gap between the panel edge and the text, in screen pixels.
+const CONSOLE_PADDING_PX: f32 = 8.0;
+
+#[derive(Debug, Default)]
+pub struct Console {
+    open: bool,
+    input: String,
+    lines: VecDeque<String>,
+    overlay: Overlay,
+}
+
+/// What `Console::draw` put into the renderer and `VisualWorld`.
+#[derive(Debug, Default)]
+struct Overlay {
+    atlas: Option<TextureHandle>,
+    mesh: Option<MeshHandle>,
+    instance: Option<InstanceHandle>,
+    /// Viewport the mesh was laid out for; `None` once the text changed.
+    laid_out: Option<[f32; 2]>,
+    /// Set after an upload failed, so it isn't retried (and logged) every frame.
+    failed: bool,
+}
+
+impl Console {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Current (unsubmitted) input line.
+    pub fn input_line(&self) -> &str {
+        &self.input
+    }
+
+    /// Most recent scrollback lines, oldest first.
+    pub fn visible_lines(&self) -> impl Iterator<Item = &str> {
+        let skip = self.lines.len().saturating_sub(CONSOLE_VISIBLE_LINES);
+        self.lines.iter().skip(skip).map(String::as_str)
+    }
+
+    /// Append an output line to the scrollback.
+    pub fn push_line(&mut self, line: impl Into<String>) {
+        if self.lines.len() == CONSOLE_SCROLLBACK {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line.into());
+        self.overlay.laid_out = None;
+    }
+
+    /// Insert `text` at the end of the input line; pasted lines become `;`-separated commands.
+    pub fn paste(&mut self, text: &str) {
+        let commands: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+        self.input.push_str(&commands.join("; "));
+        self.overlay.laid_out = None;
+    }
+
+    /// Handle toggle/editing keys for this frame. Returns a submitted command line, if any.
+    pub fn process_input(&mut self, input: &mut InputState) -> Option<String> {
+        if input.key_pressed(&Key::Character("`".into())) {
+            self.open = !self.open;
+            if self.open {
+                input.set_text_focus(CONSOLE_TEXT_FOCUS);
+            } else {
+                input.release_text_focus(CONSOLE_TEXT_FOCUS);
+            }
+            return None;
+        }
+        if !self.open {
+            return None;
+        }
+        if input.key_pressed(&Key::Named(NamedKey::Escape)) {
+            self.open = false;
+            input.release_text_focus(CONSOLE_TEXT_FOCUS);
+            return None;
+        }
+
+        let typed = input.text_for(CONSOLE_TEXT_FOCUS);
+        if !typed.is_empty() {
+            self.input.extend(typed.chars().filter(|&c| c != '`'));
+            self.overlay.laid_out = None;
+        }
+        if input.key_down(&Key::Named(NamedKey::Control))
+            && input.key_pressed(&Key::Character("v".into()))
+        {
+            match clipboard::paste() {
+                Ok(text) => self.paste(&text),
+                Err(e) => self.push_line(format!("paste: {e}")),
+            }
+        }
+        if input.key_pressed(&Key::Named(NamedKey::Backspace)) && self.input.pop().is_some() {
+            self.overlay.laid_out = None;
+        }
+        if input.key_pressed(&Key::Named(NamedKey::Enter)) {
+            let line = std::mem::take(&mut self.input);
+            self.push_line(format!("> {line}"));
+            return Some(line);
+        }
+        None
+    }
+
+    /// Show the console on screen while it is open, and take it down once it closes.
+    ///
+    /// Call once per frame before drawing; the text is laid out again only when it or the
+    /// viewport changed, but the panel is re-pinned to the screen every frame so it stays put
+    /// while the camera moves.
+    pub fn draw(&mut self, visuals: &mut VisualWorld, uploader: &mut dyn RenderUploader) {
+        if !self.open || self.overlay.failed {
+            self.hide(visuals, uploader);
+            return;
+        }
+        if let Err(e) = self.layout(visuals, uploader) {
+            crate::log_warn!("console overlay: {e}");
+            self.overlay.failed = true;
+            self.hide(visuals, uploader);
+            return;
+        }
+        if let Some(handle) = self.overlay.instance
+            && let Some(model) =
+                ScreenAnchorSystem::anchor_model(visuals, ScreenAnchor::TopLeft, [0.0, 0.0])
+        {
+            visuals.update_model(handle, model);
+        }
+    }
+
+    /// Upload the font (once) and a fresh mesh if the text or viewport changed.
+    fn layout(
+        &mut self,
+        visuals: &mut VisualWorld,
+        uploader: &mut dyn RenderUploader,
+    ) -> Result<(), RendererError> {
+        let viewport = visuals.viewport();
+        if self.overlay.instance.is_some() && self.overlay.laid_out == Some(viewport) {
+            return Ok(());
+        }
+        let atlas = match self.overlay.atlas {
+            Some(atlas) => atlas,
+            None => {
+                let font = text::font_atlas();
+                let atlas = uploader.upload_texture_rgba8(&font.rgba, font.width, font.height)?;
+                self.overlay.atlas = Some(atlas);
+                atlas
+            }
+        };
+        let mesh = uploader.upload_mesh(&self.overlay_mesh(viewport[0]))?;
+        self.hide(visuals, uploader);
+
+        // No component owns the overlay, so it is registered under the null id.
+        let handle = visuals.register(
+            ComponentId::null(),
+            GpuRenderable::new(mesh, MaterialHandle::UNLIT_MESH),
+            Transform::default(),
+            [1.0; 4],
+            Some(atlas),
+        );
+        visuals.update_visibility_layers(handle, ALL_VISIBILITY_LAYERS);
+        visuals.update_z_order(handle, MAX_DEPTH_Z_ORDER);
+        self.overlay.mesh = Some(mesh);
+        self.overlay.instance = Some(handle);
+        self.overlay.laid_out = Some(viewport);
+        Ok(())
+    }
+
+    /// Remove the overlay instance and free its mesh; the font stays uploaded.
+    fn hide(&mut self, visuals: &mut VisualWorld, uploader: &mut dyn RenderUploader) {
+        if let Some(handle) = self.overlay.instance.take() {
+            visuals.remove(handle);
+        }
+        if let Some(mesh) = self.overlay.mesh.take()
+            && let Err(e) = uploader.release_mesh(mesh)
+        {
+            crate::log_warn!("console overlay: {e}");
+        }
+        self.overlay.laid_out = None;
+    }
+
+    /// Panel as wide as the viewport, the scrollback bottom-aligned in it and the input line
+    /// (with a cursor) underneath, in screen pixels from the top-left corner.
+    fn overlay_mesh(&self, width: f32) -> CpuMesh {
+        let line_height = LINE_HEIGHT as f32 * CONSOLE_TEXT_SCALE;
+        let input_top = CONSOLE_PADDING_PX + CONSOLE_VISIBLE_LINES as f32 * line_height;
+
+        let mut mesh = TextMeshBuilder::new(CONSOLE_TEXT_SCALE);
+        mesh.panel(
+            [0.0, 0.0],
+            [width, input_top + line_height + CONSOLE_PADDING_PX],
+        );
+        let shown = self.visible_lines().count();
+        mesh.lines(
+            [CONSOLE_PADDING_PX, input_top - shown as f32 * line_height],
+            self.visible_lines(),
+        );
+        mesh.line(
+            [CONSOLE_PADDING_PX, input_top],
+            &format!("> {}_", self.input),
+        );
+        mesh.build()
+    }
+}
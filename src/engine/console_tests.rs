@@ -0,0 +1,62 @@
+#[cfg(test)]
+mod tests {
+    use winit::keyboard::{Key, NamedKey};
+
+    use crate::engine::Universe;
+    use crate::engine::console::{CONSOLE_TEXT_FOCUS, Console};
+    use crate::engine::ecs::World;
+    use crate::engine::graphics::{MAX_DEPTH_Z_ORDER, NullUploader};
+    use crate::engine::user_input::InputState;
+
+    fn frame(universe: &mut Universe, uploader: &mut NullUploader, input: &mut InputState) {
+        universe.update(1.0 / 60.0, input);
+        universe.prepare_render_with(uploader);
+        input.end_frame();
+    }
+
+    #[test]
+    fn backquote_opens_an_overlay_that_runs_typed_commands() {
+        let mut universe = Universe::new(World::default());
+        universe.visuals.set_viewport(800.0, 600.0);
+        let mut uploader = NullUploader::default();
+        let mut input = InputState::default();
+
+        frame(&mut universe, &mut uploader, &mut input);
+        assert!(universe.visuals.instances().is_empty());
+
+        input.keys_pressed.insert(Key::Character("`".into()));
+        frame(&mut universe, &mut uploader, &mut input);
+        assert!(universe.console.is_open());
+        assert!(input.has_text_focus(CONSOLE_TEXT_FOCUS));
+        let overlay = universe.visuals.instances()[0];
+        assert!(overlay.texture.is_some());
+        assert_eq!(overlay.z_order, MAX_DEPTH_Z_ORDER);
+
+        // The toggle key itself is never typed into the line.
+        input.push_text("`say hi; chat");
+        frame(&mut universe, &mut uploader, &mut input);
+        assert_eq!(universe.console.input_line(), "say hi; chat");
+        let mesh = universe.visuals.instances()[0].renderable.mesh;
+        assert_ne!(mesh, overlay.renderable.mesh);
+
+        input.keys_pressed.insert(Key::Named(NamedKey::Enter));
+        frame(&mut universe, &mut uploader, &mut input);
+        assert_eq!(universe.console.input_line(), "");
+        let lines: Vec<_> = universe.console.visible_lines().collect();
+        assert_eq!(lines, ["> say hi; chat", "<0> hi"]);
+        assert_eq!(universe.visuals.instances().len(), 1);
+
+        input.keys_pressed.insert(Key::Named(NamedKey::Escape));
+        frame(&mut universe, &mut uploader, &mut input);
+        assert!(!universe.console.is_open());
+        assert!(!input.has_text_focus(CONSOLE_TEXT_FOCUS));
+        assert!(universe.visuals.instances().is_empty());
+    }
+
+    #[test]
+    fn pasted_lines_become_separate_commands() {
+        let mut console = Console::new();
+        console.paste("say a\n\nchat\n");
+        assert_eq!(console.input_line(), "say a; chat");
+    }
+}
@@ -0,0 +1,774 @@
+//! Component (de)serialization.
+//!
+//! Every component encodes to a JSON object of its fields (`ComponentData::encode`, usually
+//! generated by `#[derive(CatComponent)]`). A subtree becomes a `ComponentDataNode`, and a
+//! `Scene` is the list of root nodes, written by the REPL `save` command as JSON or, for large
+//! worlds, in the binary format of `scene_binary`.
+//!
+//! Runtime state (renderer handles, cached ids, dirty flags) is not encoded; it is rebuilt
+//! when the decoded components are initialized.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+pub use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::engine::ecs::component::Component;
+use crate::engine::ecs::prefab::{self, PrefabInstance, PrefabOverrides};
+use crate::engine::ecs::registry::ComponentRegistry;
+use crate::engine::ecs::system::camera_system::CameraEffects;
+use crate::engine::ecs::{CommandQueue, ComponentId, World};
+use crate::engine::error::AssetError;
+use crate::engine::graphics::primitives::{CpuMeshHandle, MaterialHandle, Renderable, Transform};
+use crate::utils::color::Color;
+use crate::utils::curve::Curve;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CodecError {
+    #[error("unknown component type '{0}'")]
+    UnknownType(String),
+
+    #[error("{0}: expected a JSON object")]
+    NotAnObject(&'static str),
+
+    #[error("{type_name}.{field}: invalid value")]
+    Field {
+        type_name: &'static str,
+        field: &'static str,
+    },
+
+    #[error("malformed scene: {0}")]
+    Malformed(String),
+
+    #[error("prefab {path}: {error}")]
+    Prefab {
+        path: String,
+        error: Box<AssetError>,
+    },
+}
+
+/// JSON conversion for one component field.
+pub trait FieldCodec: Sized {
+    fn encode(&self) -> Value;
+
+    /// `None` if `value` has the wrong shape.
+    fn decode(value: &Value) -> Option<Self>;
+}
+
+impl FieldCodec for bool {
+    fn encode(&self) -> Value {
+        Value::Bool(*self)
+    }
+
+    fn decode(value: &Value) -> Option<Self> {
+        value.as_bool()
+    }
+}
+
+impl FieldCodec for u8 {
+    fn encode(&self) -> Value {
+        Value::from(*self)
+    }
+
+    fn decode(value: &Value) -> Option<Self> {
+        value.as_u64()?.try_into().ok()
+    }
+}
+
+impl FieldCodec for u32 {
+    fn encode(&self) -> Value {
+        Value::from(*self)
+    }
+
+    fn decode(value: &Value) -> Option<Self> {
+        value.as_u64()?.try_into().ok()
+    }
+}
+
+impl FieldCodec for i32 {
+    fn encode(&self) -> Value {
+        Value::from(*self)
+    }
+
+    fn decode(value: &Value) -> Option<Self> {
+        value.as_i64()?.try_into().ok()
+    }
+}
+
+impl FieldCodec for f32 {
+    fn encode(&self) -> Value {
+        // Non-finite values become `null` and fail to decode, rather than silently turning
+        // into something else.
+        Value::from(*self)
+    }
+
+    fn decode(value: &Value) -> Option<Self> {
+        // Numbers beyond f32's range would turn into infinities.
+        value.as_f64().map(|v| v as f32).filter(|v| v.is_finite())
+    }
+}
+
+impl FieldCodec for String {
+    fn encode(&self) -> Value {
+        Value::String(self.clone())
+    }
+
+    fn decode(value: &Value) -> Option<Self> {
+        value.as_str().map(str::to_string)
+    }
+}
+
+impl<T: FieldCodec> FieldCodec for Option<T> {
+    fn encode(&self) -> Value {
+        self.as_ref().map_or(Value::Null, T::encode)
+    }
+
+    fn decode(value: &Value) -> Option<Self> {
+        match value {
+            Value::Null => Some(None),
+            v => T::decode(v).map(Some),
+        }
+    }
+}
+
+impl<T: FieldCodec> FieldCodec for Vec<T> {
+    fn encode(&self) -> Value {
+        Value::Array(self.iter().map(T::encode).collect())
+    }
+
+    fn decode(value: &Value) -> Option<Self> {
+        value.as_array()?.iter().map(T::decode).collect()
+    }
+}
+
+impl<T: FieldCodec, const N: usize> FieldCodec for [T; N] {
+    fn encode(&self) -> Value {
+        Value::Array(self.iter().map(T::encode).collect())
+    }
+
+    fn decode(value: &Value) -> Option<Self> {
+        let items: Vec<T> = Vec::decode(value)?;
+        items.try_into().ok()
+    }
+}
+
+impl<A: FieldCodec, B: FieldCodec> FieldCodec for (A, B) {
+    fn encode(&self) -> Value {
+        Value::Array(vec![self.0.encode(), self.1.encode()])
+    }
+
+    fn decode(value: &Value) -> Option<Self> {
+        match value.as_array()?.as_slice() {
+            [a, b] => Some((A::decode(a)?, B::decode(b)?)),
+            _ => None,
+        }
+    }
+}
+
+/// As its display form (`3v1`). Only meaningful within the world it was encoded from.
+impl FieldCodec for ComponentId {
+    fn encode(&self) -> Value {
+        Value::String(self.to_string())
+    }
+
+    fn decode(value: &Value) -> Option<Self> {
+        value.as_str()?.parse().ok()
+    }
+}
+
+impl FieldCodec for CpuMeshHandle {
+    fn encode(&self) -> Value {
+        self.0.encode()
+    }
+
+    fn decode(value: &Value) -> Option<Self> {
+        u32::decode(value).map(CpuMeshHandle)
+    }
+}
+
+impl FieldCodec for MaterialHandle {
+    fn encode(&self) -> Value {
+        self.0.encode()
+    }
+
+    fn decode(value: &Value) -> Option<Self> {
+        u32::decode(value).map(MaterialHandle)
+    }
+}
+
+impl FieldCodec for Renderable {
+    fn encode(&self) -> Value {
+        serde_json::json!({
+            "mesh": self.mesh.encode(),
+            "material": self.material.encode(),
+        })
+    }
+
+    fn decode(value: &Value) -> Option<Self> {
+        Some(Renderable::new(
+            CpuMeshHandle::decode(value.get("mesh")?)?,
+            MaterialHandle::decode(value.get("material")?)?,
+        ))
+    }
+}
+
+/// Translation, rotation (`[x, y, z, w]`) and scale; the model matrix is recomputed.
+impl FieldCodec for Transform {
+    fn encode(&self) -> Value {
+        serde_json::json!({
+            "translation": self.translation.to_array().encode(),
+            "rotation": self.rotation.to_array().encode(),
+            "scale": self.scale.to_array().encode(),
+        })
+    }
+
+    fn decode(value: &Value) -> Option<Self> {
+        let mut t = Transform::default();
+        if let Some(v) = value.get("translation") {
+            t.translation = <[f32; 3]>::decode(v)?.into();
+        }
+        if let Some(v) = value.get("rotation") {
+            t.rotation = glam::Quat::from_array(<[f32; 4]>::decode(v)?);
+        }
+        if let Some(v) = value.get("scale") {
+            t.scale = <[f32; 3]>::decode(v)?.into();
+        }
+        t.recompute_model();
+        Some(t)
+    }
+}
+
+impl FieldCodec for CameraEffects {
+    fn encode(&self) -> Value {
+        serde_json::json!({
+            "smoothing": self.smoothing,
+            "smoothing_curve": self.smoothing_curve.encode(),
+            "trauma": self.trauma,
+            "trauma_decay": self.trauma_decay,
+            "shake_offset": self.shake_offset,
+            "shake_roll": self.shake_roll,
+            "shake_frequency": self.shake_frequency,
+            "fov_kick": self.fov_kick,
+            "fov_kick_decay": self.fov_kick_decay,
+        })
+    }
+
+    fn decode(value: &Value) -> Option<Self> {
+        let mut fx = CameraEffects::default();
+        for (key, field) in [
+            ("smoothing", &mut fx.smoothing),
+            ("trauma", &mut fx.trauma),
+            ("trauma_decay", &mut fx.trauma_decay),
+            ("shake_offset", &mut fx.shake_offset),
+            ("shake_roll", &mut fx.shake_roll),
+            ("shake_frequency", &mut fx.shake_frequency),
+            ("fov_kick", &mut fx.fov_kick),
+            ("fov_kick_decay", &mut fx.fov_kick_decay),
+        ] {
+            if let Some(v) = value.get(key) {
+                *field = f32::decode(v)?;
+            }
+        }
+        if let Some(v) = value.get("smoothing_curve") {
+            fx.smoothing_curve = Option::decode(v)?;
+        }
+        Some(fx)
+    }
+}
+
+/// As its list of keys (see `utils::curve`).
+impl FieldCodec for Curve {
+    fn encode(&self) -> Value {
+        serde_json::to_value(self).unwrap_or(Value::Null)
+    }
+
+    fn decode(value: &Value) -> Option<Self> {
+        serde_json::from_value(value.clone()).ok()
+    }
+}
+
+/// As linear `[r, g, b, a]`; decodes that, `[r, g, b]`, or a string `Color` parses
+/// (`"#ffcc00"`, `"red"`).
+impl FieldCodec for Color {
+    fn encode(&self) -> Value {
+        self.to_array().encode()
+    }
+
+    fn decode(value: &Value) -> Option<Self> {
+        match value {
+            Value::String(s) => s.parse().ok(),
+            _ => <[f32; 4]>::decode(value)
+                .map(Color::from)
+                .or_else(|| <[f32; 3]>::decode(value).map(Color::from)),
+        }
+    }
+}
+
+/// One encoded component and its encoded children.
+///
+/// A prefab instance (`prefab` set) carries only its type and overrides; `data` and
+/// `children` stay empty until `PrefabLibrary::resolve` fills them in from the prefab.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentDataNode {
+    pub type_name: String,
+    pub data: Value,
+    pub children: Vec<ComponentDataNode>,
+    pub prefab: Option<PrefabInstance>,
+    /// `ComponentNode::guid` to restore; `None` gives the decoded component a fresh one.
+    pub guid: Option<Uuid>,
+}
+
+impl ComponentDataNode {
+    pub fn to_json(&self) -> Value {
+        let mut json = self.to_json_without_guid();
+        if let Some(guid) = self.guid {
+            json["guid"] = Value::from(guid.to_string());
+        }
+        json
+    }
+
+    fn to_json_without_guid(&self) -> Value {
+        match &self.prefab {
+            Some(instance) => {
+                let mut json = serde_json::json!({
+                    "type": self.type_name,
+                    "prefab": instance.source,
+                });
+                if !instance.overrides.is_empty() {
+                    json["overrides"] = Value::Object(
+                        instance
+                            .overrides
+                            .iter()
+                            .map(|(path, fields)| (path.clone(), Value::Object(fields.clone())))
+                            .collect(),
+                    );
+                }
+                json
+            }
+            None => serde_json::json!({
+                "type": self.type_name,
+                "data": self.data,
+                "children": self.children.iter().map(Self::to_json).collect::<Vec<_>>(),
+            }),
+        }
+    }
+
+    pub fn from_json(value: &Value) -> Result<Self, CodecError> {
+        let type_name = value
+            .get("type")
+            .and_then(Value::as_str)
+            .ok_or_else(|| CodecError::Malformed("node without a 'type'".into()))?;
+        let guid = match value.get("guid") {
+            None => None,
+            Some(guid) => Some(
+                guid.as_str()
+                    .and_then(|s| Uuid::parse_str(s).ok())
+                    .ok_or_else(|| {
+                        CodecError::Malformed(format!("{type_name}: 'guid' is not a uuid"))
+                    })?,
+            ),
+        };
+        if let Some(source) = value.get("prefab") {
+            let source = source.as_str().ok_or_else(|| {
+                CodecError::Malformed(format!("{type_name}: 'prefab' is not a path"))
+            })?;
+            let overrides = match value.get("overrides") {
+                None => PrefabOverrides::new(),
+                Some(Value::Object(paths)) => paths
+                    .iter()
+                    .map(|(path, fields)| match fields {
+                        Value::Object(fields) => Ok((path.clone(), fields.clone())),
+                        _ => Err(CodecError::Malformed(format!(
+                            "{type_name}: override '{path}' is not an object"
+                        ))),
+                    })
+                    .collect::<Result<_, _>>()?,
+                Some(_) => {
+                    return Err(CodecError::Malformed(format!(
+                        "{type_name}: 'overrides' is not an object"
+                    )));
+                }
+            };
+            return Ok(Self {
+                guid,
+                ..Self::prefab_instance(
+                    type_name,
+                    PrefabInstance {
+                        source: source.to_string(),
+                        overrides,
+                    },
+                )
+            });
+        }
+        let children = match value.get("children") {
+            None => Vec::new(),
+            Some(Value::Array(children)) => children
+                .iter()
+                .map(Self::from_json)
+                .collect::<Result<_, _>>()?,
+            Some(_) => {
+                return Err(CodecError::Malformed(format!(
+                    "{type_name}: 'children' is not an array"
+                )));
+            }
+        };
+        Ok(Self {
+            type_name: type_name.to_string(),
+            data: value.get("data").cloned().unwrap_or(Value::Null),
+            children,
+            prefab: None,
+            guid,
+        })
+    }
+
+    /// Unresolved reference to a prefab. `type_name` may be empty to accept any prefab root.
+    pub fn prefab_instance(type_name: impl Into<String>, instance: PrefabInstance) -> Self {
+        Self {
+            type_name: type_name.into(),
+            data: Value::Null,
+            children: Vec::new(),
+            prefab: Some(instance),
+            guid: None,
+        }
+    }
+}
+
+/// File extension selecting the binary scene format (`level.lcscene`); anything else is JSON.
+pub const SCENE_BINARY_EXTENSION: &str = "lcscene";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SceneFormat {
+    Json,
+    Binary,
+}
+
+impl SceneFormat {
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(SCENE_BINARY_EXTENSION) => SceneFormat::Binary,
+            _ => SceneFormat::Json,
+        }
+    }
+}
+
+/// A saved set of component trees.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Scene {
+    pub roots: Vec<ComponentDataNode>,
+}
+
+impl Scene {
+    pub fn to_json(&self) -> Value {
+        serde_json::json!({
+            "roots": self.roots.iter().map(ComponentDataNode::to_json).collect::<Vec<_>>(),
+        })
+    }
+
+    pub fn from_json(value: &Value) -> Result<Self, CodecError> {
+        let roots = value
+            .get("roots")
+            .and_then(Value::as_array)
+            .ok_or_else(|| CodecError::Malformed("missing 'roots' array".into()))?;
+        Ok(Self {
+            roots: roots
+                .iter()
+                .map(ComponentDataNode::from_json)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Write in the format chosen by the file extension (see `SceneFormat::from_path`).
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), AssetError> {
+        let path = path.as_ref();
+        self.save_as(path, SceneFormat::from_path(path))
+    }
+
+    pub fn save_as(&self, path: impl AsRef<Path>, format: SceneFormat) -> Result<(), AssetError> {
+        let path = path.as_ref();
+        let bytes = match format {
+            SceneFormat::Json => serde_json::to_string_pretty(&self.to_json())?.into_bytes(),
+            SceneFormat::Binary => self.to_binary(),
+        };
+        std::fs::write(path, bytes).map_err(|e| AssetError::io(path, e))
+    }
+
+    /// Read a scene in the format chosen by the file extension.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AssetError> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).map_err(|e| AssetError::io(path, e))?;
+        match SceneFormat::from_path(path) {
+            SceneFormat::Json => Ok(Self::from_json(&serde_json::from_slice(&bytes)?)?),
+            SceneFormat::Binary => Ok(Self::from_binary(&bytes)?),
+        }
+    }
+}
+
+/// A `ComponentRef` whose target guid is not in the world (see `ComponentCodec::resolve_refs`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DanglingRef {
+    /// Component holding the reference.
+    pub component: ComponentId,
+    pub guid: Uuid,
+}
+
+impl std::fmt::Display for DanglingRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} refers to {}, which is not in the world",
+            self.component, self.guid
+        )
+    }
+}
+
+type PendingComponent = (
+    Box<dyn Component>,
+    Option<usize>,
+    Option<String>,
+    Option<Uuid>,
+);
+
+/// Converts between `World` subtrees and `ComponentDataNode`s.
+pub struct ComponentCodec;
+
+impl ComponentCodec {
+    /// Encode a subtree. Prefab instances become prefab + overrides when their prefab is
+    /// loaded in `world.prefabs()` and still has the same shape.
+    pub fn encode(world: &World, root: ComponentId) -> Option<ComponentDataNode> {
+        let node = world.get_component_record(root)?;
+        if let Some(source) = &node.prefab
+            && let Some(base) = world.prefabs().get(source)
+        {
+            let full = Self::encode_plain(world, root)?;
+            // Against the prefab as instanced here, so references inside it aren't overrides.
+            let base = prefab::with_instance_guids(base.clone(), node.guid);
+            match prefab::diff(&base, &full) {
+                Some(overrides) => {
+                    return Some(ComponentDataNode {
+                        guid: Some(node.guid),
+                        ..ComponentDataNode::prefab_instance(
+                            full.type_name,
+                            PrefabInstance {
+                                source: source.clone(),
+                                overrides,
+                            },
+                        )
+                    });
+                }
+                None => {
+                    crate::log_warn!("{root} no longer matches prefab {source}; saving it in full")
+                }
+            }
+        }
+        Some(ComponentDataNode {
+            type_name: node.component.type_name().to_string(),
+            data: node.component.encode(),
+            children: world
+                .children_of(root)
+                .iter()
+                .filter_map(|&c| Self::encode(world, c))
+                .collect(),
+            prefab: None,
+            guid: Some(node.guid),
+        })
+    }
+
+    /// Every component of the subtree spelled out, ignoring prefab instances. Only compared
+    /// against prefabs, so without guids.
+    fn encode_plain(world: &World, root: ComponentId) -> Option<ComponentDataNode> {
+        let node = world.get_component_record(root)?;
+        Some(ComponentDataNode {
+            type_name: node.component.type_name().to_string(),
+            data: node.component.encode(),
+            children: world
+                .children_of(root)
+                .iter()
+                .filter_map(|&c| Self::encode_plain(world, c))
+                .collect(),
+            prefab: None,
+            guid: None,
+        })
+    }
+
+    /// Every root component of `world`, oldest first, so saves of the same scene diff cleanly.
+    pub fn encode_world(world: &World) -> Scene {
+        Scene {
+            roots: world
+                .root_components()
+                .into_iter()
+                .filter_map(|root| Self::encode(world, root))
+                .collect(),
+        }
+    }
+
+    /// Add a decoded subtree to `world` without initializing it. Types are looked up in
+    /// `world.registry()`, prefabs in `world.prefabs()`. On error nothing is added.
+    ///
+    /// Saved guids are restored unless a component in `world` already has them (the same
+    /// scene loaded twice, a prefab instanced again); those components keep their fresh guid
+    /// and references to them from inside the subtree follow. `ComponentRef`s are not
+    /// resolved yet: call `resolve_refs` once every tree they may point into is added.
+    pub fn decode(world: &mut World, node: &ComponentDataNode) -> Result<ComponentId, CodecError> {
+        let mut renamed = HashMap::new();
+        let root = Self::decode_renaming(world, node, &mut renamed)?;
+        Self::rename_refs(world, &[root], &renamed);
+        Ok(root)
+    }
+
+    /// `decode`, recording the saved guids that were taken in `renamed` (saved -> fresh)
+    /// instead of rewriting references to them.
+    fn decode_renaming(
+        world: &mut World,
+        node: &ComponentDataNode,
+        renamed: &mut HashMap<Uuid, Uuid>,
+    ) -> Result<ComponentId, CodecError> {
+        let resolved = world.prefabs_mut().resolve(node)?;
+
+        // Build every component first so a bad node deep in the tree leaves the world untouched.
+        let mut pending = Vec::new();
+        Self::build_all(world.registry(), &resolved, &mut pending)?;
+
+        let mut ids: Vec<ComponentId> = Vec::with_capacity(pending.len());
+        for (component, parent, prefab, guid) in pending {
+            let cid = world.add_component_boxed(component);
+            if let Some(node) = world.get_component_record_mut(cid) {
+                node.component.set_id(cid);
+                node.prefab = prefab;
+            }
+            if let Some(guid) = guid
+                && world.set_guid(cid, guid).is_err()
+                && let Some(node) = world.get_component_record(cid)
+            {
+                renamed.insert(guid, node.guid);
+            }
+            if let Some(parent) = parent {
+                let _ = world.add_child(ids[parent], cid);
+            }
+            ids.push(cid);
+        }
+        Ok(ids[0])
+    }
+
+    /// Point references in the subtrees under `roots` at the fresh guids of renamed targets.
+    fn rename_refs(world: &mut World, roots: &[ComponentId], renamed: &HashMap<Uuid, Uuid>) {
+        if renamed.is_empty() {
+            return;
+        }
+        let mut stack = roots.to_vec();
+        while let Some(cid) = stack.pop() {
+            stack.extend_from_slice(world.children_of(cid));
+            let Some(node) = world.get_component_record_mut(cid) else {
+                continue;
+            };
+            for r in node.component.component_refs_mut() {
+                if let Some(guid) = r.guid.and_then(|g| renamed.get(&g)) {
+                    r.guid = Some(*guid);
+                }
+            }
+        }
+    }
+
+    /// Fail the way `decode` would, without adding anything to `world`.
+    pub(crate) fn check(world: &mut World, node: &ComponentDataNode) -> Result<(), CodecError> {
+        let resolved = world.prefabs_mut().resolve(node)?;
+        Self::build_all(world.registry(), &resolved, &mut Vec::new())
+    }
+
+    /// Depth-first list of (component, index of parent in the list, prefab source, guid).
+    fn build_all(
+        registry: &ComponentRegistry,
+        node: &ComponentDataNode,
+        out: &mut Vec<PendingComponent>,
+    ) -> Result<(), CodecError> {
+        let mut stack = vec![(node, None)];
+        while let Some((node, parent)) = stack.pop() {
+            let prefab = node.prefab.as_ref().map(|p| p.source.clone());
+            out.push((
+                registry.create(&node.type_name, &node.data)?,
+                parent,
+                prefab,
+                node.guid,
+            ));
+            let index = out.len() - 1;
+            stack.extend(node.children.iter().rev().map(|c| (c, Some(index))));
+        }
+        Ok(())
+    }
+
+    /// Point the `ComponentRef`s in the subtrees under `roots` at the components holding their
+    /// guids. Returns (and logs) the ones whose target is missing; those are left empty.
+    pub fn resolve_refs(world: &mut World, roots: &[ComponentId]) -> Vec<DanglingRef> {
+        let mut dangling = Vec::new();
+        let mut stack = roots.to_vec();
+        while let Some(cid) = stack.pop() {
+            stack.extend_from_slice(world.children_of(cid));
+            let Some(node) = world.get_component_record_mut(cid) else {
+                continue;
+            };
+            let guids: Vec<_> = node
+                .component
+                .component_refs_mut()
+                .iter()
+                .map(|r| r.guid)
+                .collect();
+            if guids.iter().all(Option::is_none) {
+                continue;
+            }
+            let ids: Vec<_> = guids
+                .iter()
+                .map(|guid| guid.and_then(|g| world.component_id_by_guid(g)))
+                .collect();
+            let Some(node) = world.get_component_record_mut(cid) else {
+                continue;
+            };
+            for ((r, guid), id) in node
+                .component
+                .component_refs_mut()
+                .into_iter()
+                .zip(guids)
+                .zip(ids)
+            {
+                r.id = id.unwrap_or_default();
+                if let (Some(guid), None) = (guid, id) {
+                    let missing = DanglingRef {
+                        component: cid,
+                        guid,
+                    };
+                    crate::log_warn!("{missing}");
+                    dangling.push(missing);
+                }
+            }
+        }
+        dangling
+    }
+
+    /// Decode every root of `scene` into `world`, resolve references between components (a
+    /// target may sit in any tree of the scene) and initialize the new trees.
+    pub fn instantiate(
+        world: &mut World,
+        queue: &mut CommandQueue,
+        scene: &Scene,
+    ) -> Result<Vec<ComponentId>, CodecError> {
+        let mut roots = Vec::with_capacity(scene.roots.len());
+        // Renames from every root, since a reference may cross into another tree.
+        let mut renamed = HashMap::new();
+        for node in &scene.roots {
+            match Self::decode_renaming(world, node, &mut renamed) {
+                Ok(root) => roots.push(root),
+                Err(e) => {
+                    for root in roots {
+                        let _ = world.remove_component_subtree(root);
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        Self::rename_refs(world, &roots, &renamed);
+        Self::resolve_refs(world, &roots);
+        for &root in &roots {
+            world.init_component_tree(root, queue);
+        }
+        Ok(roots)
+    }
+}
@@ -0,0 +1,421 @@
+#[cfg(test)]
+mod tests {
+    use crate::engine::ecs::codec::{CodecError, ComponentCodec, Scene};
+    use crate::engine::ecs::component::{
+        ColorComponent, ComponentType, InputComponent, InputTarget, NineSliceComponent,
+        TilemapComponent, TransformComponent,
+    };
+    use crate::engine::ecs::{CommandQueue, ComponentId, World};
+
+    #[test]
+    fn scene_round_trips_through_json() {
+        let mut w = World::default();
+        let root = w.add_component(
+            TransformComponent::new()
+                .with_position(1.0, 2.0, 3.0)
+                .with_rotation_euler(0.0, 0.0, 0.5),
+        );
+        let color = w.add_component(ColorComponent::rgba(0.25, 0.5, 0.75, 1.0));
+        let input = w.add_component(
+            InputComponent::new()
+                .with_roll_axis(None)
+                .with_target(InputTarget::Camera),
+        );
+        let tilemap = w.add_component(TilemapComponent::new(2, 2, [8.0, 8.0]).with_tiles(vec![
+            Some(1),
+            None,
+            Some(3),
+        ]));
+        for c in [color, input, tilemap] {
+            w.add_child(root, c).unwrap();
+        }
+
+        let scene = ComponentCodec::encode_world(&w);
+        let reparsed = Scene::from_json(&scene.to_json()).unwrap();
+        assert_eq!(reparsed, scene);
+
+        let mut w2 = World::default();
+        let mut queue = CommandQueue::new();
+        let roots = ComponentCodec::instantiate(&mut w2, &mut queue, &reparsed).unwrap();
+        assert_eq!(roots.len(), 1);
+        assert_eq!(w2.component_count(), 4);
+
+        let t = w2
+            .get_component_by_id_as::<TransformComponent>(roots[0])
+            .unwrap();
+        assert_eq!(
+            t.transform.model,
+            w.get_component_by_id_as::<TransformComponent>(root)
+                .unwrap()
+                .transform
+                .model
+        );
+
+        let children = w2.children_of(roots[0]).to_vec();
+        let input = w2
+            .get_component_by_id_as::<InputComponent>(children[1])
+            .unwrap();
+        assert_eq!(input.target, InputTarget::Camera);
+        assert_eq!(input.roll_axis, None);
+        let tilemap = w2
+            .get_component_by_id_as::<TilemapComponent>(children[2])
+            .unwrap();
+        assert_eq!(tilemap.tile(0, 1), Some(3));
+        assert!(tilemap.has_dirty_chunks());
+
+        // Re-encoding the decoded world gives the same scene.
+        assert_eq!(ComponentCodec::encode_world(&w2), scene);
+
+        let binary = scene.to_binary();
+        assert_eq!(Scene::from_binary(&binary).unwrap(), scene);
+        assert!(binary.len() < scene.to_json().to_string().len());
+        assert!(Scene::from_binary(&binary[..binary.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn guids_survive_save_and_load() {
+        let mut w = World::default();
+        let root = w.add_component(TransformComponent::new());
+        let child = w.add_component(ColorComponent::rgba(1.0, 0.0, 0.0, 1.0));
+        w.add_child(root, child).unwrap();
+        let guid = w.get_component_record(child).unwrap().guid;
+        assert_eq!(w.component_id_by_guid(guid), Some(child));
+
+        let scene = ComponentCodec::encode_world(&w);
+        assert_eq!(scene.roots[0].children[0].guid, Some(guid));
+        for reparsed in [
+            Scene::from_json(&scene.to_json()).unwrap(),
+            Scene::from_binary(&scene.to_binary()).unwrap(),
+        ] {
+            let mut w2 = World::default();
+            let mut queue = CommandQueue::new();
+            let root2 = ComponentCodec::instantiate(&mut w2, &mut queue, &reparsed).unwrap()[0];
+            let child2 = w2.component_id_by_guid(guid).unwrap();
+            assert_eq!(w2.parent_of(child2), Some(root2));
+
+            // A second copy of the scene gets guids of its own.
+            let copy = ComponentCodec::instantiate(&mut w2, &mut queue, &reparsed).unwrap()[0];
+            let copy_child = w2.children_of(copy)[0];
+            assert_ne!(w2.get_component_record(copy_child).unwrap().guid, guid);
+            assert_eq!(w2.component_id_by_guid(guid), Some(child2));
+
+            w2.remove_component_subtree(root2).unwrap();
+            assert_eq!(w2.component_id_by_guid(guid), None);
+        }
+    }
+
+    #[test]
+    fn component_refs_resolve_by_guid() {
+        use crate::engine::ecs::component::AttachmentComponent;
+
+        // target -> follower -> attachment(target)
+        let mut w = World::default();
+        let target = w.add_component(TransformComponent::new());
+        let follower = w.add_component(TransformComponent::new());
+        let attachment = w.add_component(AttachmentComponent::new(w.component_ref(target)));
+        w.add_child(target, follower).unwrap();
+        w.add_child(follower, attachment).unwrap();
+        let scene = Scene::from_json(&ComponentCodec::encode_world(&w).to_json()).unwrap();
+
+        let mut w2 = World::default();
+        let mut queue = CommandQueue::new();
+        let attached_to = |w: &World, root| {
+            let attachment = w.children_of(w.children_of(root)[0])[0];
+            w.get_component_by_id_as::<AttachmentComponent>(attachment)
+                .unwrap()
+                .target
+        };
+        let first = ComponentCodec::instantiate(&mut w2, &mut queue, &scene).unwrap()[0];
+        assert_eq!(attached_to(&w2, first), w2.component_ref(first));
+        // The copy's guids are new, and its reference follows them.
+        let copy = ComponentCodec::instantiate(&mut w2, &mut queue, &scene).unwrap()[0];
+        assert_eq!(attached_to(&w2, copy), w2.component_ref(copy));
+
+        // Without the target, the reference is reported and left empty.
+        let mut w3 = World::default();
+        let lone = ComponentCodec::decode(&mut w3, &scene.roots[0].children[0]).unwrap();
+        let dangling = ComponentCodec::resolve_refs(&mut w3, &[lone]);
+        assert_eq!(dangling.len(), 1);
+        assert_eq!(Some(dangling[0].guid), scene.roots[0].guid);
+        let attachment = w3.children_of(lone)[0];
+        let target = w3
+            .get_component_by_id_as::<AttachmentComponent>(attachment)
+            .unwrap()
+            .target;
+        assert_eq!(target.id, Default::default());
+    }
+
+    #[test]
+    fn refs_across_roots_follow_each_copy() {
+        use crate::engine::ecs::component::AttachmentComponent;
+
+        // Root A holds an attachment to root B.
+        let mut w = World::default();
+        let a = w.add_component(TransformComponent::new());
+        let b = w.add_component(TransformComponent::new());
+        let attachment = w.add_component(AttachmentComponent::new(w.component_ref(b)));
+        w.add_child(a, attachment).unwrap();
+        let scene = Scene::from_json(&ComponentCodec::encode_world(&w).to_json()).unwrap();
+
+        let mut w2 = World::default();
+        let mut queue = CommandQueue::new();
+        let target_of = |w: &World, roots: &[ComponentId]| {
+            let attachment = w.children_of(roots[0])[0];
+            w.get_component_by_id_as::<AttachmentComponent>(attachment)
+                .unwrap()
+                .target
+        };
+        let first = ComponentCodec::instantiate(&mut w2, &mut queue, &scene).unwrap();
+        let copy = ComponentCodec::instantiate(&mut w2, &mut queue, &scene).unwrap();
+        assert_eq!(target_of(&w2, &first), w2.component_ref(first[1]));
+        assert_eq!(target_of(&w2, &copy), w2.component_ref(copy[1]));
+        assert_ne!(first[1], copy[1]);
+    }
+
+    #[test]
+    fn unknown_types_leave_the_world_untouched() {
+        let json = serde_json::json!({
+            "roots": [{
+                "type": "transform",
+                "children": [{ "type": "no_such_component" }],
+            }],
+        });
+        let scene = Scene::from_json(&json).unwrap();
+        let mut w = World::default();
+        let mut queue = CommandQueue::new();
+        assert!(matches!(
+            ComponentCodec::instantiate(&mut w, &mut queue, &scene),
+            Err(CodecError::UnknownType(t)) if t == "no_such_component"
+        ));
+        assert_eq!(w.component_count(), 0);
+    }
+
+    #[test]
+    fn floats_beyond_f32_range_fail_to_decode() {
+        let ok = serde_json::json!({ "size": [64.0, 32.0] });
+        assert_eq!(NineSliceComponent::decode(&ok).unwrap().size, [64.0, 32.0]);
+        let huge = serde_json::json!({ "size": [1e300, 32.0] });
+        assert!(matches!(
+            NineSliceComponent::decode(&huge),
+            Err(CodecError::Field {
+                type_name: "nine_slice",
+                field: "size"
+            })
+        ));
+    }
+
+    #[test]
+    fn schema_fields_are_encoded_keys() {
+        let w = World::default();
+        for ty in w.registry().types() {
+            let schema = ty
+                .schema
+                .unwrap_or_else(|| panic!("{} has no schema", ty.type_name));
+            let encoded = w
+                .registry()
+                .create(ty.type_name, &serde_json::Value::Null)
+                .unwrap()
+                .encode();
+            for field in schema.fields {
+                assert!(
+                    encoded.get(field.name).is_some(),
+                    "{}.{} is not encoded (keys: {encoded})",
+                    ty.type_name,
+                    field.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn registered_types_decode_by_name() {
+        use crate::engine::ecs::component::{CatComponent, Component};
+
+        #[derive(Debug, Clone, Default, CatComponent)]
+        #[cat(name = "health")]
+        struct HealthComponent {
+            hp: u32,
+        }
+        impl Component for HealthComponent {}
+
+        let json = serde_json::json!({
+            "roots": [{ "type": "health", "data": { "hp": 7 } }],
+        });
+        let scene = Scene::from_json(&json).unwrap();
+        let mut w = World::default();
+        let mut queue = CommandQueue::new();
+        assert!(ComponentCodec::instantiate(&mut w, &mut queue, &scene).is_err());
+
+        w.registry_mut().register::<HealthComponent>();
+        assert!(w.registry().find("HealthComponent").is_some());
+        let roots = ComponentCodec::instantiate(&mut w, &mut queue, &scene).unwrap();
+        let health = w
+            .get_component_by_id_as::<HealthComponent>(roots[0])
+            .unwrap();
+        assert_eq!(health.hp, 7);
+        // The scene had no guid, so the component got a fresh one.
+        let mut saved = ComponentCodec::encode_world(&w);
+        assert!(saved.roots[0].guid.take().is_some());
+        assert_eq!(saved, scene);
+    }
+
+    #[test]
+    fn derive_generates_schema_from_fields() {
+        use crate::engine::ecs::component::schema::{FieldSchema, FieldType};
+        use crate::engine::ecs::component::{CatComponent, Component, ComponentData};
+
+        #[derive(Debug, Clone, Default, CatComponent)]
+        #[cat(name = "health")]
+        struct HealthComponent {
+            /// Hit points left,
+            /// never negative.
+            #[cat(range = 0.0..=f32::INFINITY)]
+            hp: f32,
+            #[cat(ty = FieldType::Rgb, range = -1.0..=1.0)]
+            tint: [f32; 3],
+            armor: Option<Vec<u32>>,
+            #[cat(hidden)]
+            history: Vec<f32>,
+            #[cat(skip)]
+            _regen: f32,
+        }
+        impl Component for HealthComponent {}
+
+        assert_eq!(HealthComponent::SCHEMA.type_name, "health");
+        assert_eq!(
+            HealthComponent::SCHEMA.fields,
+            [
+                FieldSchema::new("hp", FieldType::F32, "Hit points left, never negative.")
+                    .with_range(0.0, f32::INFINITY),
+                FieldSchema::new("tint", FieldType::Rgb, "").with_range(-1.0, 1.0),
+                FieldSchema::new(
+                    "armor",
+                    FieldType::Optional(&FieldType::List(&FieldType::U32)),
+                    "",
+                ),
+            ]
+        );
+        assert!(HealthComponent::default().encode().get("history").is_some());
+    }
+
+    #[test]
+    fn prefab_instances_save_only_overrides() {
+        use crate::engine::ecs::component::PointLightComponent;
+
+        let lamp = |color: [f32; 3]| {
+            let mut w = World::default();
+            let root = w.add_component(TransformComponent::new());
+            let mut light = PointLightComponent::new();
+            light.color = color;
+            let light = w.add_component(light);
+            w.add_child(root, light).unwrap();
+            ComponentCodec::encode(&w, root).unwrap()
+        };
+
+        let json = serde_json::json!({
+            "roots": [{
+                "type": "transform",
+                "prefab": "lamp",
+                "overrides": { "": { "transform": { "translation": [4.0, 0.0, 0.0] } } },
+            }],
+        });
+        let scene = Scene::from_json(&json).unwrap();
+        let mut w = World::default();
+        let mut queue = CommandQueue::new();
+        w.prefabs_mut()
+            .insert("lamp", &lamp([1.0, 0.0, 0.0]))
+            .unwrap();
+        let root = ComponentCodec::instantiate(&mut w, &mut queue, &scene).unwrap()[0];
+        let light = w.children_of(root)[0];
+        assert_eq!(
+            w.get_component_by_id_as::<TransformComponent>(root)
+                .unwrap()
+                .transform
+                .translation
+                .x,
+            4.0
+        );
+        w.get_component_by_id_as_mut::<PointLightComponent>(light)
+            .unwrap()
+            .intensity = 9.0;
+
+        // Saved: the prefab reference, the root's transform and the light's intensity.
+        let saved = ComponentCodec::encode_world(&w);
+        let instance = saved.roots[0].prefab.as_ref().unwrap();
+        assert_eq!(instance.source, "lamp");
+        assert_eq!(instance.overrides.len(), 2);
+        assert_eq!(
+            instance.overrides["0"].keys().collect::<Vec<_>>(),
+            ["intensity"]
+        );
+        assert!(saved.to_json()["roots"][0].get("children").is_none());
+        assert_eq!(Scene::from_binary(&saved.to_binary()).unwrap(), saved);
+
+        // Prefab edits reach the instance on the next load; its overrides still apply.
+        let mut w2 = World::default();
+        w2.prefabs_mut()
+            .insert("lamp", &lamp([0.0, 0.0, 1.0]))
+            .unwrap();
+        let root = ComponentCodec::instantiate(&mut w2, &mut queue, &saved).unwrap()[0];
+        let light = w2
+            .get_component_by_id_as::<PointLightComponent>(w2.children_of(root)[0])
+            .unwrap();
+        assert_eq!(light.color, [0.0, 0.0, 1.0]);
+        assert_eq!(light.intensity, 9.0);
+    }
+
+    #[test]
+    fn refs_into_prefab_children_survive_a_save() {
+        use crate::engine::ecs::component::AttachmentComponent;
+
+        // Prefab: a transform with a bulb and an attachment to that bulb.
+        let lamp = {
+            let mut w = World::default();
+            let root = w.add_component(TransformComponent::new());
+            let bulb = w.add_component(TransformComponent::new());
+            let attachment = w.add_component(AttachmentComponent::new(w.component_ref(bulb)));
+            w.add_child(root, bulb).unwrap();
+            w.add_child(root, attachment).unwrap();
+            ComponentCodec::encode(&w, root).unwrap()
+        };
+        let target_of = |w: &World, attachment: ComponentId| {
+            w.get_component_by_id_as::<AttachmentComponent>(attachment)
+                .unwrap()
+                .target
+        };
+
+        // Two instances: the second can't take the prefab's own guids.
+        let lamp_node = serde_json::json!({ "type": "transform", "prefab": "lamp" });
+        let json = serde_json::json!({ "roots": [lamp_node.clone(), lamp_node] });
+        let scene = Scene::from_json(&json).unwrap();
+        let mut w = World::default();
+        let mut queue = CommandQueue::new();
+        w.prefabs_mut().insert("lamp", &lamp).unwrap();
+        let instance = ComponentCodec::instantiate(&mut w, &mut queue, &scene).unwrap()[1];
+        let bulb = w.children_of(instance)[0];
+
+        // Outside the instance: something attached to its bulb.
+        let follower = w.add_component(TransformComponent::new());
+        let attachment = w.add_component(AttachmentComponent::new(w.component_ref(bulb)));
+        w.add_child(follower, attachment).unwrap();
+
+        // Instances save no children and, with their internal reference renamed like their
+        // guids, no overrides.
+        let saved = Scene::from_json(&ComponentCodec::encode_world(&w).to_json()).unwrap();
+        assert!(saved.roots[1].children.is_empty());
+        assert!(saved.roots[1].prefab.as_ref().unwrap().overrides.is_empty());
+
+        let mut w2 = World::default();
+        w2.prefabs_mut().insert("lamp", &lamp).unwrap();
+        let roots = ComponentCodec::instantiate(&mut w2, &mut queue, &saved).unwrap();
+        let bulb2 = w2.children_of(roots[1])[0];
+        assert_eq!(
+            w2.get_component_record(bulb2).unwrap().guid,
+            w.get_component_record(bulb).unwrap().guid
+        );
+        let attachment2 = w2.children_of(roots[2])[0];
+        assert_eq!(target_of(&w2, attachment2), w2.component_ref(bulb2));
+        let inner = w2.children_of(roots[1])[1];
+        assert_eq!(target_of(&w2, inner), w2.component_ref(bulb2));
+    }
+}
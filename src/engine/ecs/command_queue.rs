@@ -23,6 +23,14 @@ impl CommandQueue {
         });
     }
 
+    /// Queue a remove renderable command.
+    pub fn queue_remove_renderable(&mut self, component_id: crate::engine::ecs::ComponentId) {
+        self.commands.push(ComponentCommand {
+            component_id,
+            command: Command::REMOVE_RENDERABLE { component_id },
+        });
+    }
+
     /// Queue a register transform command.
     pub fn queue_register_transform(&mut self, component_id: crate::engine::ecs::ComponentId) {
         self.commands.push(ComponentCommand {
@@ -102,6 +110,158 @@ impl CommandQueue {
         });
     }
 
+    /// Queue a register LOD command.
+    pub fn queue_register_lod(&mut self, component_id: crate::engine::ecs::ComponentId) {
+        self.commands.push(ComponentCommand {
+            component_id,
+            command: Command::REGISTER_LOD { component_id },
+        });
+    }
+
+    /// Queue a register static command.
+    pub fn queue_register_static(&mut self, component_id: crate::engine::ecs::ComponentId) {
+        self.commands.push(ComponentCommand {
+            component_id,
+            command: Command::REGISTER_STATIC { component_id },
+        });
+    }
+
+    /// Queue a register tilemap command.
+    pub fn queue_register_tilemap(&mut self, component_id: crate::engine::ecs::ComponentId) {
+        self.commands.push(ComponentCommand {
+            component_id,
+            command: Command::REGISTER_TILEMAP { component_id },
+        });
+    }
+
+    /// Queue a register nine-slice command.
+    pub fn queue_register_nine_slice(&mut self, component_id: crate::engine::ecs::ComponentId) {
+        self.commands.push(ComponentCommand {
+            component_id,
+            command: Command::REGISTER_NINE_SLICE { component_id },
+        });
+    }
+
+    /// Queue a register screen anchor command.
+    pub fn queue_register_screen_anchor(&mut self, component_id: crate::engine::ecs::ComponentId) {
+        self.commands.push(ComponentCommand {
+            component_id,
+            command: Command::REGISTER_SCREEN_ANCHOR { component_id },
+        });
+    }
+
+    /// Queue a register attachment command.
+    pub fn queue_register_attachment(&mut self, component_id: crate::engine::ecs::ComponentId) {
+        self.commands.push(ComponentCommand {
+            component_id,
+            command: Command::REGISTER_ATTACHMENT { component_id },
+        });
+    }
+
+    /// Queue a register velocity command.
+    pub fn queue_register_velocity(&mut self, component_id: crate::engine::ecs::ComponentId) {
+        self.commands.push(ComponentCommand {
+            component_id,
+            command: Command::REGISTER_VELOCITY { component_id },
+        });
+    }
+
+    /// Queue a register trigger volume command.
+    pub fn queue_register_trigger(&mut self, component_id: crate::engine::ecs::ComponentId) {
+        self.commands.push(ComponentCommand {
+            component_id,
+            command: Command::REGISTER_TRIGGER { component_id },
+        });
+    }
+
+    /// Queue a register character controller command.
+    pub fn queue_register_character(&mut self, component_id: crate::engine::ecs::ComponentId) {
+        self.commands.push(ComponentCommand {
+            component_id,
+            command: Command::REGISTER_CHARACTER { component_id },
+        });
+    }
+
+    /// Queue a register collider command.
+    pub fn queue_register_collider(&mut self, component_id: crate::engine::ecs::ComponentId) {
+        self.commands.push(ComponentCommand {
+            component_id,
+            command: Command::REGISTER_COLLIDER { component_id },
+        });
+    }
+
+    /// Queue a register nav agent command.
+    pub fn queue_register_nav_agent(&mut self, component_id: crate::engine::ecs::ComponentId) {
+        self.commands.push(ComponentCommand {
+            component_id,
+            command: Command::REGISTER_NAV_AGENT { component_id },
+        });
+    }
+
+    /// Queue a register state machine command.
+    pub fn queue_register_state_machine(&mut self, component_id: crate::engine::ecs::ComponentId) {
+        self.commands.push(ComponentCommand {
+            component_id,
+            command: Command::REGISTER_STATE_MACHINE { component_id },
+        });
+    }
+
+    /// Queue a register XR pose command.
+    pub fn queue_register_xr_pose(&mut self, component_id: crate::engine::ecs::ComponentId) {
+        self.commands.push(ComponentCommand {
+            component_id,
+            command: Command::REGISTER_XR_POSE { component_id },
+        });
+    }
+
+    /// Queue a register background command.
+    pub fn queue_register_background(&mut self, component_id: crate::engine::ecs::ComponentId) {
+        self.commands.push(ComponentCommand {
+            component_id,
+            command: Command::REGISTER_BACKGROUND { component_id },
+        });
+    }
+
+    /// Queue a remove background command.
+    pub fn queue_remove_background(&mut self, component_id: crate::engine::ecs::ComponentId) {
+        self.commands.push(ComponentCommand {
+            component_id,
+            command: Command::REMOVE_BACKGROUND { component_id },
+        });
+    }
+
+    /// Queue a register clip rect command.
+    pub fn queue_register_clip_rect(&mut self, component_id: crate::engine::ecs::ComponentId) {
+        self.commands.push(ComponentCommand {
+            component_id,
+            command: Command::REGISTER_CLIP_RECT { component_id },
+        });
+    }
+
+    /// Queue a remove clip rect command.
+    pub fn queue_remove_clip_rect(&mut self, component_id: crate::engine::ecs::ComponentId) {
+        self.commands.push(ComponentCommand {
+            component_id,
+            command: Command::REMOVE_CLIP_RECT { component_id },
+        });
+    }
+
+    /// Queue a register color grading command.
+    pub fn queue_register_color_grading(&mut self, component_id: crate::engine::ecs::ComponentId) {
+        self.commands.push(ComponentCommand {
+            component_id,
+            command: Command::REGISTER_COLOR_GRADING { component_id },
+        });
+    }
+
+    /// Queue a remove color grading command.
+    pub fn queue_remove_color_grading(&mut self, component_id: crate::engine::ecs::ComponentId) {
+        self.commands.push(ComponentCommand {
+            component_id,
+            command: Command::REMOVE_COLOR_GRADING { component_id },
+        });
+    }
+
     /// Queue a register texture command.
     pub fn queue_register_texture(&mut self, component_id: crate::engine::ecs::ComponentId) {
         self.commands.push(ComponentCommand {
@@ -159,8 +319,65 @@ impl CommandQueue {
                 Command::REGISTER_TEXTURE { component_id } => {
                     systems.register_texture(world, visuals, component_id);
                 }
-                Command::REMOVE_RENDERABLE { component_id: _ } => {
-                    // TODO: implement when needed
+                Command::REGISTER_LOD { component_id } => {
+                    systems.register_lod(world, visuals, component_id);
+                }
+                Command::REGISTER_STATIC { component_id } => {
+                    systems.register_static(world, visuals, component_id);
+                }
+                Command::REGISTER_TILEMAP { component_id } => {
+                    systems.register_tilemap(world, visuals, component_id);
+                }
+                Command::REGISTER_NINE_SLICE { component_id } => {
+                    systems.register_nine_slice(world, visuals, component_id);
+                }
+                Command::REGISTER_SCREEN_ANCHOR { component_id } => {
+                    systems.register_screen_anchor(world, visuals, component_id);
+                }
+                Command::REGISTER_ATTACHMENT { component_id } => {
+                    systems.register_attachment(world, component_id);
+                }
+                Command::REGISTER_VELOCITY { component_id } => {
+                    systems.register_velocity(world, component_id);
+                }
+                Command::REGISTER_TRIGGER { component_id } => {
+                    systems.register_trigger(world, component_id);
+                }
+                Command::REGISTER_CHARACTER { component_id } => {
+                    systems.register_character(world, component_id);
+                }
+                Command::REGISTER_COLLIDER { component_id } => {
+                    systems.register_collider(world, component_id);
+                }
+                Command::REGISTER_NAV_AGENT { component_id } => {
+                    systems.register_nav_agent(world, component_id);
+                }
+                Command::REGISTER_STATE_MACHINE { component_id } => {
+                    systems.register_state_machine(world, component_id);
+                }
+                Command::REGISTER_XR_POSE { component_id } => {
+                    systems.register_xr_pose(world, component_id);
+                }
+                Command::REGISTER_BACKGROUND { component_id } => {
+                    systems.register_background(world, visuals, component_id);
+                }
+                Command::REMOVE_RENDERABLE { component_id } => {
+                    systems.remove_renderable(world, visuals, component_id);
+                }
+                Command::REMOVE_BACKGROUND { component_id } => {
+                    systems.remove_background(visuals, component_id);
+                }
+                Command::REGISTER_CLIP_RECT { component_id } => {
+                    systems.register_clip_rect(world, visuals, component_id);
+                }
+                Command::REMOVE_CLIP_RECT { component_id } => {
+                    systems.remove_clip_rect(visuals, component_id);
+                }
+                Command::REGISTER_COLOR_GRADING { component_id } => {
+                    systems.register_color_grading(world, visuals, component_id);
+                }
+                Command::REMOVE_COLOR_GRADING { component_id } => {
+                    systems.remove_color_grading(visuals, component_id);
                 }
                 Command::REMOVE_CAMERA { component_id: _ } => {
                     // TODO: implement when needed
@@ -170,7 +387,7 @@ impl CommandQueue {
     }
 }
 
-pub struct ComponentCommand {
+struct ComponentCommand {
     component_id: crate::engine::ecs::ComponentId,
     command: Command,
     //
@@ -204,9 +421,66 @@ enum Command {
     REGISTER_TEXTURE {
         component_id: crate::engine::ecs::ComponentId,
     },
+    REGISTER_LOD {
+        component_id: crate::engine::ecs::ComponentId,
+    },
+    REGISTER_STATIC {
+        component_id: crate::engine::ecs::ComponentId,
+    },
+    REGISTER_TILEMAP {
+        component_id: crate::engine::ecs::ComponentId,
+    },
+    REGISTER_NINE_SLICE {
+        component_id: crate::engine::ecs::ComponentId,
+    },
+    REGISTER_SCREEN_ANCHOR {
+        component_id: crate::engine::ecs::ComponentId,
+    },
+    REGISTER_ATTACHMENT {
+        component_id: crate::engine::ecs::ComponentId,
+    },
+    REGISTER_VELOCITY {
+        component_id: crate::engine::ecs::ComponentId,
+    },
+    REGISTER_TRIGGER {
+        component_id: crate::engine::ecs::ComponentId,
+    },
+    REGISTER_CHARACTER {
+        component_id: crate::engine::ecs::ComponentId,
+    },
+    REGISTER_COLLIDER {
+        component_id: crate::engine::ecs::ComponentId,
+    },
+    REGISTER_NAV_AGENT {
+        component_id: crate::engine::ecs::ComponentId,
+    },
+    REGISTER_STATE_MACHINE {
+        component_id: crate::engine::ecs::ComponentId,
+    },
+    REGISTER_XR_POSE {
+        component_id: crate::engine::ecs::ComponentId,
+    },
+    REGISTER_BACKGROUND {
+        component_id: crate::engine::ecs::ComponentId,
+    },
     REMOVE_RENDERABLE {
         component_id: crate::engine::ecs::ComponentId,
     },
+    REMOVE_BACKGROUND {
+        component_id: crate::engine::ecs::ComponentId,
+    },
+    REGISTER_CLIP_RECT {
+        component_id: crate::engine::ecs::ComponentId,
+    },
+    REMOVE_CLIP_RECT {
+        component_id: crate::engine::ecs::ComponentId,
+    },
+    REGISTER_COLOR_GRADING {
+        component_id: crate::engine::ecs::ComponentId,
+    },
+    REMOVE_COLOR_GRADING {
+        component_id: crate::engine::ecs::ComponentId,
+    },
     REMOVE_TRANSFORM {
         component_id: crate::engine::ecs::ComponentId,
     },
@@ -0,0 +1,58 @@
+use crate::engine::ecs::component::schema::ComponentSchema;
+use crate::engine::ecs::component::{CatComponent, Component};
+use crate::engine::ecs::{ComponentId, ComponentRef};
+
+/// Makes its parent Transform follow another component's world position.
+///
+/// Intended topology: `TransformComponent -> AttachmentComponent`, with the follower's content
+/// (health bar, name tag, carried item, ...) under the same Transform.
+///
+/// Every frame `TransformSystem` moves the parent Transform so its world-space origin sits at
+/// the target's world position plus `world_offset`. Only translation is driven; the follower
+/// keeps its own rotation and scale. `target` may be any component with a Transform at or
+/// above it; while it has none (or was removed) the follower stays where it is. Scenes save
+/// the target by guid, so it can live in another tree of the same scene.
+#[derive(Debug, Clone, Copy, CatComponent)]
+#[cat(name = "attachment")]
+pub struct AttachmentComponent {
+    /// Component whose world position is followed.
+    pub target: ComponentRef,
+    /// Added to the target position, world space.
+    pub world_offset: [f32; 3],
+}
+
+/// Detached (empty target) until `target` is set; used when decoding.
+impl Default for AttachmentComponent {
+    fn default() -> Self {
+        Self::new(ComponentRef::default())
+    }
+}
+
+impl AttachmentComponent {
+    /// `target` from `World::component_ref`.
+    pub fn new(target: ComponentRef) -> Self {
+        Self {
+            target,
+            world_offset: [0.0, 0.0, 0.0],
+        }
+    }
+
+    pub fn with_world_offset(mut self, x: f32, y: f32, z: f32) -> Self {
+        self.world_offset = [x, y, z];
+        self
+    }
+}
+
+impl Component for AttachmentComponent {
+    fn schema(&self) -> Option<&'static ComponentSchema> {
+        Some(&Self::SCHEMA)
+    }
+
+    fn init(&mut self, queue: &mut crate::engine::ecs::CommandQueue, component: ComponentId) {
+        queue.queue_register_attachment(component);
+    }
+
+    fn component_refs_mut(&mut self) -> Vec<&mut ComponentRef> {
+        vec![&mut self.target]
+    }
+}
@@ -0,0 +1,138 @@
+use crate::engine::ecs::ComponentId;
+use crate::engine::ecs::codec::{FieldCodec, Value};
+use crate::engine::ecs::component::schema::{ComponentSchema, FieldType, SchemaField};
+use crate::engine::ecs::component::{CatComponent, Component};
+use crate::utils::color::Color;
+
+/// What `BackgroundComponent` draws behind the scene.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BackgroundMode {
+    /// Clear to `color`.
+    #[default]
+    Solid,
+    /// Screen-space linear gradient from `color` to `color_end` along `direction`.
+    GradientXY,
+    /// Cubemap from `skybox`, seen through the active camera.
+    Skybox,
+}
+
+impl BackgroundMode {
+    const NAMES: [(BackgroundMode, &'static str); 3] = [
+        (BackgroundMode::Solid, "solid"),
+        (BackgroundMode::GradientXY, "gradient_xy"),
+        (BackgroundMode::Skybox, "skybox"),
+    ];
+}
+
+impl FieldCodec for BackgroundMode {
+    fn encode(&self) -> Value {
+        let (_, name) = Self::NAMES.iter().find(|(m, _)| m == self).unwrap();
+        Value::from(*name)
+    }
+
+    fn decode(value: &Value) -> Option<Self> {
+        let name = value.as_str()?;
+        Self::NAMES
+            .iter()
+            .find(|(_, n)| *n == name)
+            .map(|(m, _)| *m)
+    }
+}
+
+impl SchemaField for BackgroundMode {
+    const FIELD_TYPE: FieldType = FieldType::Enum(&["solid", "gradient_xy", "skybox"]);
+}
+
+/// Environment behind every renderable: a solid color, a gradient or a skybox.
+///
+/// Place one anywhere in the world; if several are registered, the last one wins. The
+/// skybox image is a horizontal strip of six square faces in the order +X, -X, +Y, -Y, +Z, -Z
+/// (so it is six times as wide as it is tall). Skyboxes follow the camera's rotation, which
+/// only the 3D camera has; under a 2D camera they show a single direction.
+#[derive(Debug, Clone, CatComponent)]
+#[cat(name = "background")]
+pub struct BackgroundComponent {
+    pub mode: BackgroundMode,
+    /// Solid color, or where the gradient starts.
+    #[cat(range = 0.0..=1.0)]
+    pub color: Color,
+    /// Where the gradient ends.
+    #[cat(range = 0.0..=1.0)]
+    pub color_end: Color,
+    /// Gradient direction in screen space (+Y down).
+    pub direction: [f32; 2],
+    /// Skybox strip image URI (+X -X +Y -Y +Z -Z faces side by side).
+    pub skybox: String,
+}
+
+impl BackgroundComponent {
+    pub fn solid(r: f32, g: f32, b: f32) -> Self {
+        Self {
+            color: Color::rgb(r, g, b),
+            ..Self::default()
+        }
+    }
+
+    /// Vertical gradient from `top` to `bottom`.
+    pub fn gradient(top: Color, bottom: Color) -> Self {
+        Self {
+            mode: BackgroundMode::GradientXY,
+            color: top,
+            color_end: bottom,
+            ..Self::default()
+        }
+    }
+
+    pub fn skybox(uri: impl Into<String>) -> Self {
+        Self {
+            mode: BackgroundMode::Skybox,
+            skybox: uri.into(),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_direction(mut self, x: f32, y: f32) -> Self {
+        self.direction = [x, y];
+        self
+    }
+}
+
+impl Default for BackgroundComponent {
+    fn default() -> Self {
+        Self {
+            mode: BackgroundMode::Solid,
+            color: Color::BLACK,
+            color_end: Color::BLACK,
+            direction: [0.0, 1.0],
+            skybox: String::new(),
+        }
+    }
+}
+
+impl Component for BackgroundComponent {
+    fn schema(&self) -> Option<&'static ComponentSchema> {
+        Some(&Self::SCHEMA)
+    }
+
+    fn set_field(&mut self, name: &str, value: &Value) -> bool {
+        match name {
+            "color" => Color::decode(value).map(|c| self.color = c).is_some(),
+            "color_end" => Color::decode(value).map(|c| self.color_end = c).is_some(),
+            _ => false,
+        }
+    }
+
+    fn dependencies(&self, deps: &mut crate::engine::assets::AssetDependencies) {
+        if self.mode == BackgroundMode::Skybox && !self.skybox.is_empty() {
+            deps.textures.insert(self.skybox.clone());
+        }
+    }
+
+    fn init(&mut self, queue: &mut crate::engine::ecs::CommandQueue, component: ComponentId) {
+        queue.queue_register_background(component);
+    }
+
+    fn cleanup(&mut self, queue: &mut crate::engine::ecs::CommandQueue, component: ComponentId) {
+        queue.queue_remove_background(component);
+    }
+}
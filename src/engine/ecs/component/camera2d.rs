@@ -1,34 +1,91 @@
 use crate::engine::ecs::ComponentId;
-use crate::engine::ecs::component::Component;
+use crate::engine::ecs::component::schema::ComponentSchema;
+use crate::engine::ecs::component::{CatComponent, Component};
 
 /// 2D camera component.
 ///
 /// This is a sibling of `Camera3DComponent` (3D-ish view/proj camera).
 /// The 2D camera drives a global NDC translation used by the mesh vertex shader.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, CatComponent)]
+#[cat(name = "camera2d")]
 pub struct Camera2DComponent {
+    #[cat(skip)]
     pub handle: Option<crate::engine::ecs::system::camera_system::CameraHandle>,
+
+    /// Layers this camera renders (e.g. main view vs. minimap vs. UI).
+    pub visibility_layers: u32,
+
+    /// Smoothing / shake, advanced by `CameraSystem::tick`.
+    #[cat(hidden)]
+    pub effects: crate::engine::ecs::system::camera_system::CameraEffects,
+
+    /// Opt-in: drag with the middle mouse button to pan (moves the parent Transform).
+    pub pan_with_middle_mouse: bool,
+    /// Opt-in: mouse wheel zooms toward the cursor (scales the parent Transform).
+    pub zoom_with_wheel: bool,
+    /// Zoom factor per wheel notch, as an exponent: `scale *= exp(-notches * zoom_speed)`.
+    #[cat(range = 0.0..=f32::INFINITY)]
+    pub zoom_speed: f32,
+    /// Allowed parent Transform scale range (`min`, `max`); larger scale shows more world.
+    #[cat(range = 0.0..=f32::INFINITY)]
+    pub zoom_range: (f32, f32),
 }
 
 impl Camera2DComponent {
     pub fn new() -> Self {
-        Self { handle: None }
+        Self {
+            handle: None,
+            visibility_layers: crate::engine::graphics::ALL_VISIBILITY_LAYERS,
+            effects: Default::default(),
+            pan_with_middle_mouse: false,
+            zoom_with_wheel: false,
+            zoom_speed: 0.1,
+            zoom_range: (0.05, 20.0),
+        }
     }
-}
 
-impl Component for Camera2DComponent {
-    fn name(&self) -> &'static str {
-        "camera2d"
+    /// Enable middle-mouse drag panning and wheel zoom.
+    pub fn with_mouse_navigation(mut self) -> Self {
+        self.pan_with_middle_mouse = true;
+        self.zoom_with_wheel = true;
+        self
     }
 
-    fn as_any(&self) -> &dyn std::any::Any {
+    pub fn with_mouse_pan(mut self, enabled: bool) -> Self {
+        self.pan_with_middle_mouse = enabled;
         self
     }
 
-    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+    pub fn with_wheel_zoom(mut self, enabled: bool) -> Self {
+        self.zoom_with_wheel = enabled;
         self
     }
 
+    pub fn with_effects(
+        mut self,
+        effects: crate::engine::ecs::system::camera_system::CameraEffects,
+    ) -> Self {
+        self.effects = effects;
+        self
+    }
+
+    pub fn with_visibility_layers(mut self, mask: u32) -> Self {
+        self.visibility_layers = mask;
+        self
+    }
+}
+
+impl Default for Camera2DComponent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Component for Camera2DComponent {
+    fn schema(&self) -> Option<&'static ComponentSchema> {
+        Some(&Self::SCHEMA)
+    }
+
     fn init(&mut self, queue: &mut crate::engine::ecs::CommandQueue, component: ComponentId) {
         queue.queue_register_camera2d(component);
     }
@@ -1,5 +1,47 @@
 use crate::engine::ecs::ComponentId;
-use crate::engine::ecs::component::Component;
+use crate::engine::ecs::codec::{FieldCodec, Value};
+use crate::engine::ecs::component::schema::{ComponentSchema, FieldType, SchemaField};
+use crate::engine::ecs::component::{CatComponent, Component};
+
+/// How `Camera3DComponent` projects the scene.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CameraProjection {
+    /// Identity view and projection whatever the Transform: the debug camera, which draws
+    /// clip space as is.
+    #[default]
+    Identity,
+    /// `fov_y_radians`; distant things look smaller.
+    Perspective,
+    /// `ortho_size`; parallel lines stay parallel (isometric games, editor views).
+    Orthographic,
+}
+
+impl CameraProjection {
+    const NAMES: [(CameraProjection, &'static str); 3] = [
+        (CameraProjection::Identity, "identity"),
+        (CameraProjection::Perspective, "perspective"),
+        (CameraProjection::Orthographic, "orthographic"),
+    ];
+}
+
+impl FieldCodec for CameraProjection {
+    fn encode(&self) -> Value {
+        let (_, name) = Self::NAMES.iter().find(|(p, _)| p == self).unwrap();
+        Value::from(*name)
+    }
+
+    fn decode(value: &Value) -> Option<Self> {
+        let name = value.as_str()?;
+        Self::NAMES
+            .iter()
+            .find(|(_, n)| *n == name)
+            .map(|(p, _)| *p)
+    }
+}
+
+impl SchemaField for CameraProjection {
+    const FIELD_TYPE: FieldType = FieldType::Enum(&["identity", "perspective", "orthographic"]);
+}
 
 /// 3D camera component.
 ///
@@ -7,15 +49,75 @@ use crate::engine::ecs::component::Component;
 /// - On init, registers a camera with `CameraSystem`.
 /// - The most recently registered camera becomes active.
 /// - Call `make_active_camera()` to explicitly set this camera active.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, CatComponent)]
+#[cat(name = "camera3d")]
 pub struct Camera3DComponent {
     // Handle owned by CameraSystem. Filled in during init.
+    #[cat(skip)]
     pub handle: Option<crate::engine::ecs::system::camera_system::CameraHandle>,
+
+    /// Layers this camera renders (e.g. main view vs. minimap vs. UI).
+    pub visibility_layers: u32,
+
+    pub projection: CameraProjection,
+    /// Vertical field of view in radians (perspective).
+    #[cat(range = 0.0..=std::f32::consts::PI)]
+    pub fov_y_radians: f32,
+    /// Half the view height in world units (orthographic); the width follows the aspect ratio.
+    #[cat(range = 0.0..=f32::INFINITY)]
+    pub ortho_size: f32,
+    #[cat(range = 0.0..=f32::INFINITY)]
+    pub z_near: f32,
+    #[cat(range = 0.0..=f32::INFINITY)]
+    pub z_far: f32,
+
+    /// Smoothing / shake / FOV kick, advanced by `CameraSystem::tick`.
+    #[cat(hidden)]
+    pub effects: crate::engine::ecs::system::camera_system::CameraEffects,
 }
 
 impl Camera3DComponent {
     pub fn new() -> Self {
-        Self { handle: None }
+        Self {
+            handle: None,
+            visibility_layers: crate::engine::graphics::ALL_VISIBILITY_LAYERS,
+            projection: CameraProjection::Identity,
+            fov_y_radians: 60.0f32.to_radians(),
+            ortho_size: 5.0,
+            z_near: 0.1,
+            z_far: 100.0,
+            effects: Default::default(),
+        }
+    }
+
+    pub fn with_perspective(mut self, fov_y_radians: f32, z_near: f32, z_far: f32) -> Self {
+        self.projection = CameraProjection::Perspective;
+        self.fov_y_radians = fov_y_radians;
+        self.z_near = z_near;
+        self.z_far = z_far;
+        self
+    }
+
+    /// Orthographic projection `2 * size` world units tall.
+    pub fn with_orthographic(mut self, size: f32, z_near: f32, z_far: f32) -> Self {
+        self.projection = CameraProjection::Orthographic;
+        self.ortho_size = size;
+        self.z_near = z_near;
+        self.z_far = z_far;
+        self
+    }
+
+    pub fn with_effects(
+        mut self,
+        effects: crate::engine::ecs::system::camera_system::CameraEffects,
+    ) -> Self {
+        self.effects = effects;
+        self
+    }
+
+    pub fn with_visibility_layers(mut self, mask: u32) -> Self {
+        self.visibility_layers = mask;
+        self
     }
 
     /// Ask the CameraSystem to make this the active camera.
@@ -37,16 +139,27 @@ impl Default for Camera3DComponent {
 }
 
 impl Component for Camera3DComponent {
-    fn name(&self) -> &'static str {
-        "camera3d"
-    }
-
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
+    fn schema(&self) -> Option<&'static ComponentSchema> {
+        Some(&Self::SCHEMA)
     }
 
-    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
-        self
+    fn set_field(&mut self, name: &str, value: &Value) -> bool {
+        if name == "projection" {
+            return CameraProjection::decode(value)
+                .map(|p| self.projection = p)
+                .is_some();
+        }
+        let Some(v) = f32::decode(value).filter(|v| v.is_finite()) else {
+            return false;
+        };
+        match name {
+            "fov_y_radians" => self.fov_y_radians = v,
+            "ortho_size" if v > 0.0 => self.ortho_size = v,
+            "z_near" if v > 0.0 && v < self.z_far => self.z_near = v,
+            "z_far" if v > self.z_near => self.z_far = v,
+            _ => return false,
+        }
+        true
     }
 
     fn init(&mut self, queue: &mut crate::engine::ecs::CommandQueue, component: ComponentId) {
@@ -0,0 +1,173 @@
+use crate::engine::ecs::ComponentId;
+use crate::engine::ecs::codec::{FieldCodec, Value};
+use crate::engine::ecs::component::schema::{ComponentSchema, FieldType, SchemaField};
+use crate::engine::ecs::component::{CatComponent, Component};
+
+/// Plane a `CharacterControllerComponent` walks in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MovePlane {
+    /// 2D scenes: `move_x` -> +X, `move_y` -> +Y. Down is +Y, like the 2D demo scenes.
+    XY,
+    /// 3D scenes: `move_x` -> +X, `move_y` -> +Z (so `move_up` walks toward -Z). Down is -Y.
+    #[default]
+    XZ,
+}
+
+impl MovePlane {
+    const NAMES: [(MovePlane, &'static str); 2] = [(MovePlane::XY, "xy"), (MovePlane::XZ, "xz")];
+}
+
+impl FieldCodec for MovePlane {
+    fn encode(&self) -> Value {
+        let (_, name) = Self::NAMES.iter().find(|(p, _)| p == self).unwrap();
+        Value::from(*name)
+    }
+
+    fn decode(value: &Value) -> Option<Self> {
+        let name = value.as_str()?;
+        Self::NAMES
+            .iter()
+            .find(|(_, n)| *n == name)
+            .map(|(p, _)| *p)
+    }
+}
+
+impl SchemaField for MovePlane {
+    const FIELD_TYPE: FieldType = FieldType::Enum(&["xy", "xz"]);
+}
+
+/// Kinematic capsule that walks its parent Transform from action-map input, sliding along
+/// `ColliderComponent`s instead of passing through them.
+///
+/// Intended topology: `TransformComponent -> CharacterControllerComponent`. The capsule stands
+/// upright (along Y) centered on the Transform. `CharacterSystem` reads `move_axes` from the
+/// action map, moves in `plane` at `speed`, then pushes the capsule out of every collider it
+/// overlaps. With `gravity` set the character falls, lands (`grounded`), walks up ledges no
+/// taller than `step_height` and jumps on `jump_action`; the axis along gravity is then not
+/// driven by input, which makes `MovePlane::XY` a side-on platformer. Without gravity it's a
+/// top-down mover.
+///
+/// Movement follows the world axes, not the Transform's rotation.
+#[derive(Debug, Clone, CatComponent)]
+#[cat(name = "character_controller")]
+pub struct CharacterControllerComponent {
+    /// Capsule radius.
+    #[cat(range = 0.0..=f32::INFINITY)]
+    pub radius: f32,
+    /// Capsule length tip to tip (at least `2 * radius`).
+    #[cat(range = 0.0..=f32::INFINITY)]
+    pub height: f32,
+    /// Walking speed in units per second.
+    #[cat(range = 0.0..=f32::INFINITY)]
+    pub speed: f32,
+    /// Tallest ledge walked onto without jumping.
+    #[cat(range = 0.0..=f32::INFINITY)]
+    pub step_height: f32,
+    /// Downward acceleration in units per second squared; 0 disables falling and jumping.
+    #[cat(range = 0.0..=f32::INFINITY)]
+    pub gravity: f32,
+    /// Upward speed given by a jump.
+    #[cat(range = 0.0..=f32::INFINITY)]
+    pub jump_speed: f32,
+    /// `xy` for 2D (+Y down), `xz` for 3D (+Y up).
+    pub plane: MovePlane,
+    /// Axis names for (x, y) movement.
+    pub move_axes: (String, String),
+    /// Action that jumps while grounded; `None` disables jumping.
+    pub jump_action: Option<String>,
+
+    /// Speed along the up axis, kept between ticks for falling and jumping.
+    #[cat(skip)]
+    pub vertical_speed: f32,
+    /// Standing on a collider as of the last tick.
+    #[cat(skip)]
+    pub grounded: bool,
+}
+
+impl Default for CharacterControllerComponent {
+    fn default() -> Self {
+        Self::walker_3d()
+    }
+}
+
+impl CharacterControllerComponent {
+    /// Human-sized walker for 3D scenes (Y up, walks in XZ).
+    pub fn walker_3d() -> Self {
+        Self {
+            radius: 0.4,
+            height: 1.8,
+            speed: 4.0,
+            step_height: 0.3,
+            gravity: 20.0,
+            jump_speed: 7.0,
+            plane: MovePlane::XZ,
+            move_axes: ("move_x".into(), "move_y".into()),
+            jump_action: Some("jump".into()),
+            vertical_speed: 0.0,
+            grounded: false,
+        }
+    }
+
+    /// Side-on 2D platformer: walks along X, falls toward +Y.
+    pub fn platformer_2d() -> Self {
+        Self {
+            radius: 0.5,
+            height: 1.0,
+            step_height: 0.25,
+            plane: MovePlane::XY,
+            ..Self::walker_3d()
+        }
+    }
+
+    /// Top-down 2D mover: walks in XY, no gravity.
+    pub fn top_down_2d() -> Self {
+        Self {
+            gravity: 0.0,
+            jump_action: None,
+            step_height: 0.0,
+            ..Self::platformer_2d()
+        }
+    }
+
+    pub fn with_size(mut self, radius: f32, height: f32) -> Self {
+        self.radius = radius;
+        self.height = height;
+        self
+    }
+
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    pub fn with_step_height(mut self, step_height: f32) -> Self {
+        self.step_height = step_height;
+        self
+    }
+
+    pub fn with_gravity(mut self, gravity: f32) -> Self {
+        self.gravity = gravity;
+        self
+    }
+
+    pub fn with_move_axes(mut self, x: impl Into<String>, y: impl Into<String>) -> Self {
+        self.move_axes = (x.into(), y.into());
+        self
+    }
+
+    pub fn with_jump(mut self, action: Option<&str>, jump_speed: f32) -> Self {
+        self.jump_action = action.map(Into::into);
+        self.jump_speed = jump_speed;
+        self
+    }
+}
+
+impl Component for CharacterControllerComponent {
+    fn schema(&self) -> Option<&'static ComponentSchema> {
+        Some(&Self::SCHEMA)
+    }
+
+    fn init(&mut self, queue: &mut crate::engine::ecs::CommandQueue, component: ComponentId) {
+        queue.queue_register_character(component);
+    }
+}
@@ -0,0 +1,50 @@
+use crate::engine::ecs::ComponentId;
+use crate::engine::ecs::component::schema::ComponentSchema;
+use crate::engine::ecs::component::{CatComponent, Component};
+
+/// Clips every renderable below it to a rectangle (scrollable lists, minimaps).
+///
+/// Intended topology: `TransformComponent -> ClipRectComponent -> (content)`. The rectangle
+/// is given in the local units of the parent Transform, so under a `ScreenAnchorComponent` it
+/// is in pixels (+Y down) and follows the anchored panel. `ClipRectSystem` projects it to the
+/// screen each frame and the renderer applies it as a scissor; a rotated rectangle clips to
+/// its screen-space bounding box. Nested clips intersect.
+#[derive(Debug, Clone, Copy, CatComponent)]
+#[cat(name = "clip_rect")]
+pub struct ClipRectComponent {
+    /// Corner with the smallest coordinates, in parent-local units.
+    pub min: [f32; 2],
+    /// Opposite corner.
+    pub max: [f32; 2],
+}
+
+impl ClipRectComponent {
+    pub fn new(min: [f32; 2], max: [f32; 2]) -> Self {
+        Self { min, max }
+    }
+
+    /// `size` units from the origin: `[0, 0]..size` (top-left to bottom-right in HUD pixels).
+    pub fn sized(width: f32, height: f32) -> Self {
+        Self::new([0.0, 0.0], [width, height])
+    }
+}
+
+impl Default for ClipRectComponent {
+    fn default() -> Self {
+        Self::sized(100.0, 100.0)
+    }
+}
+
+impl Component for ClipRectComponent {
+    fn schema(&self) -> Option<&'static ComponentSchema> {
+        Some(&Self::SCHEMA)
+    }
+
+    fn init(&mut self, queue: &mut crate::engine::ecs::CommandQueue, component: ComponentId) {
+        queue.queue_register_clip_rect(component);
+    }
+
+    fn cleanup(&mut self, queue: &mut crate::engine::ecs::CommandQueue, component: ComponentId) {
+        queue.queue_remove_clip_rect(component);
+    }
+}
@@ -0,0 +1,41 @@
+use crate::engine::ecs::ComponentId;
+use crate::engine::ecs::component::schema::ComponentSchema;
+use crate::engine::ecs::component::{CatComponent, Component};
+
+/// Solid, static box that character controllers can't pass through.
+///
+/// Intended topology: `TransformComponent -> ColliderComponent`. Like a `TriggerVolumeComponent`
+/// the box is centered on the parent Transform and collides as the world-space box enclosing
+/// it, so rotated colliders grow rather than turn. Moving the Transform moves the collider;
+/// nothing pushes the collider itself.
+#[derive(Debug, Clone, Copy, CatComponent)]
+#[cat(name = "collider")]
+pub struct ColliderComponent {
+    /// Half the box size along each axis, in the parent Transform's space.
+    #[cat(range = 0.0..=f32::INFINITY)]
+    pub half_extents: [f32; 3],
+}
+
+impl Default for ColliderComponent {
+    fn default() -> Self {
+        Self::new(0.5, 0.5, 0.5)
+    }
+}
+
+impl ColliderComponent {
+    pub fn new(half_x: f32, half_y: f32, half_z: f32) -> Self {
+        Self {
+            half_extents: [half_x, half_y, half_z],
+        }
+    }
+}
+
+impl Component for ColliderComponent {
+    fn schema(&self) -> Option<&'static ComponentSchema> {
+        Some(&Self::SCHEMA)
+    }
+
+    fn init(&mut self, queue: &mut crate::engine::ecs::CommandQueue, component: ComponentId) {
+        queue.queue_register_collider(component);
+    }
+}
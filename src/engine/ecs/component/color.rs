@@ -1,27 +1,41 @@
 use crate::engine::ecs::ComponentId;
-use crate::engine::ecs::component::Component;
+use crate::engine::ecs::codec::{FieldCodec, Value};
+use crate::engine::ecs::component::schema::ComponentSchema;
+use crate::engine::ecs::component::{CatComponent, Component};
+use crate::utils::color::Color;
 
 /// Per-instance color for a renderable.
 ///
 /// Intended to be attached as a descendant of a `RenderableComponent`.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, CatComponent)]
+#[cat(name = "color")]
 pub struct ColorComponent {
-    pub rgba: [f32; 4],
+    /// Per-instance color.
+    #[cat(range = 0.0..=1.0)]
+    pub rgba: Color,
 }
 
 impl ColorComponent {
     pub fn new() -> Self {
-        Self {
-            rgba: [1.0, 1.0, 1.0, 1.0],
-        }
+        Self::color(Color::WHITE)
+    }
+
+    pub fn color(rgba: Color) -> Self {
+        Self { rgba }
     }
 
+    /// Linear components.
     pub fn rgba(r: f32, g: f32, b: f32, a: f32) -> Self {
-        Self { rgba: [r, g, b, a] }
+        Self::color(Color::rgba(r, g, b, a))
+    }
+
+    /// `#rgb`/`#rrggbb`/`#rrggbbaa` in sRGB; `None` if `hex` isn't one.
+    pub fn hex(hex: &str) -> Option<Self> {
+        Color::from_hex(hex).map(Self::color)
     }
 
     pub fn with_rgba(mut self, r: f32, g: f32, b: f32, a: f32) -> Self {
-        self.rgba = [r, g, b, a];
+        self.rgba = Color::rgba(r, g, b, a);
         self
     }
 }
@@ -33,16 +47,20 @@ impl Default for ColorComponent {
 }
 
 impl Component for ColorComponent {
-    fn name(&self) -> &'static str {
-        "color"
+    fn schema(&self) -> Option<&'static ComponentSchema> {
+        Some(&Self::SCHEMA)
     }
 
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
+    fn property(&self, name: &str) -> Option<f32> {
+        let i = ["r", "g", "b", "a"].iter().position(|c| *c == name)?;
+        Some(self.rgba.to_array()[i])
     }
 
-    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
-        self
+    fn set_field(&mut self, name: &str, value: &Value) -> bool {
+        match name {
+            "rgba" => Color::decode(value).map(|c| self.rgba = c).is_some(),
+            _ => false,
+        }
     }
 
     fn init(&mut self, queue: &mut crate::engine::ecs::CommandQueue, component: ComponentId) {
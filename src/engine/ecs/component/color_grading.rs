@@ -0,0 +1,59 @@
+use crate::engine::ecs::ComponentId;
+use crate::engine::ecs::component::schema::ComponentSchema;
+use crate::engine::ecs::component::{CatComponent, Component};
+
+/// Color grading LUT applied to the final image, after tonemapping.
+///
+/// `lut` is either an Adobe/Resolve `.cube` file (`LUT_3D_SIZE` up to 64) or an image strip of
+/// N slices of N x N side by side (e.g. 256x16 or 1024x32): red grows along each slice, green
+/// downwards and blue from slice to slice. Both map display (sRGB-encoded) colors to display
+/// colors. Place one anywhere in the world; the last registered one wins.
+#[derive(Debug, Clone, CatComponent)]
+#[cat(name = "color_grading")]
+pub struct ColorGradingComponent {
+    /// `.cube` file or LUT strip image URI.
+    pub lut: String,
+    /// 0 leaves the image as is, 1 applies the LUT fully.
+    #[cat(range = 0.0..=1.0)]
+    pub strength: f32,
+}
+
+impl ColorGradingComponent {
+    pub fn new(lut: impl Into<String>) -> Self {
+        Self {
+            lut: lut.into(),
+            strength: 1.0,
+        }
+    }
+
+    pub fn with_strength(mut self, strength: f32) -> Self {
+        self.strength = strength;
+        self
+    }
+}
+
+impl Default for ColorGradingComponent {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+impl Component for ColorGradingComponent {
+    fn schema(&self) -> Option<&'static ComponentSchema> {
+        Some(&Self::SCHEMA)
+    }
+
+    fn dependencies(&self, deps: &mut crate::engine::assets::AssetDependencies) {
+        if !self.lut.is_empty() {
+            deps.textures.insert(self.lut.clone());
+        }
+    }
+
+    fn init(&mut self, queue: &mut crate::engine::ecs::CommandQueue, component: ComponentId) {
+        queue.queue_register_color_grading(component);
+    }
+
+    fn cleanup(&mut self, queue: &mut crate::engine::ecs::CommandQueue, component: ComponentId) {
+        queue.queue_remove_color_grading(component);
+    }
+}
@@ -1,35 +1,107 @@
 use crate::engine::ecs::ComponentId;
-use crate::engine::ecs::component::Component;
+use crate::engine::ecs::codec::{FieldCodec, Value};
+use crate::engine::ecs::component::schema::{ComponentSchema, FieldType, SchemaField};
+use crate::engine::ecs::component::{CatComponent, Component};
 
-/// Input component that responds to keyboard input (WASD).
-#[derive(Debug, Clone, Default)]
+/// Which Transform an `InputComponent` drives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InputTarget {
+    /// The InputComponent's own child Transform: `InputComponent -> TransformComponent`.
+    #[default]
+    OwnTransform,
+    /// The nearest Transform above the InputComponent, e.g.
+    /// `TransformComponent -> RenderableComponent -> InputComponent`.
+    ParentTransform,
+    /// The nearest Transform above the active camera (2D or 3D).
+    Camera,
+}
+
+impl InputTarget {
+    const NAMES: [(InputTarget, &'static str); 3] = [
+        (InputTarget::OwnTransform, "own_transform"),
+        (InputTarget::ParentTransform, "parent_transform"),
+        (InputTarget::Camera, "camera"),
+    ];
+}
+
+impl FieldCodec for InputTarget {
+    fn encode(&self) -> Value {
+        let (_, name) = Self::NAMES.iter().find(|(t, _)| t == self).unwrap();
+        Value::from(*name)
+    }
+
+    fn decode(value: &Value) -> Option<Self> {
+        let name = value.as_str()?;
+        Self::NAMES
+            .iter()
+            .find(|(_, n)| *n == name)
+            .map(|(t, _)| *t)
+    }
+}
+
+impl SchemaField for InputTarget {
+    const FIELD_TYPE: FieldType = FieldType::Enum(&["own_transform", "parent_transform", "camera"]);
+}
+
+/// Moves a Transform from action-map axes (see `engine::action_map`).
+///
+/// Defaults to the engine's WASD/QE layout: `move_x`/`move_y` translate in the target's local
+/// (rolled) axes and `roll` rotates around Z.
+#[derive(Debug, Clone, CatComponent)]
+#[cat(name = "input")]
 pub struct InputComponent {
+    /// Units per second.
+    #[cat(range = 0.0..=f32::INFINITY)]
     pub speed: f32,
+    /// Axis names for (x, y) movement.
+    pub move_axes: (String, String),
+    /// Axis name for Z roll; `None` disables rolling.
+    pub roll_axis: Option<String>,
+    /// Which Transform moves.
+    pub target: InputTarget,
 }
 
 impl InputComponent {
     pub fn new() -> Self {
-        Self { speed: 0.01 }
+        Self {
+            speed: 0.01,
+            move_axes: ("move_x".into(), "move_y".into()),
+            roll_axis: Some("roll".into()),
+            target: InputTarget::OwnTransform,
+        }
     }
 
     pub fn with_speed(mut self, speed: f32) -> Self {
         self.speed = speed;
         self
     }
-}
 
-impl Component for InputComponent {
-    fn name(&self) -> &'static str {
-        "input"
+    pub fn with_move_axes(mut self, x: impl Into<String>, y: impl Into<String>) -> Self {
+        self.move_axes = (x.into(), y.into());
+        self
     }
 
-    fn as_any(&self) -> &dyn std::any::Any {
+    pub fn with_roll_axis(mut self, axis: Option<&str>) -> Self {
+        self.roll_axis = axis.map(Into::into);
         self
     }
 
-    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+    pub fn with_target(mut self, target: InputTarget) -> Self {
+        self.target = target;
         self
     }
+}
+
+impl Default for InputComponent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Component for InputComponent {
+    fn schema(&self) -> Option<&'static ComponentSchema> {
+        Some(&Self::SCHEMA)
+    }
 
     fn init(&mut self, queue: &mut crate::engine::ecs::CommandQueue, component: ComponentId) {
         queue.queue_register_input(component);
@@ -1,12 +1,14 @@
-use super::Component;
+use super::{CatComponent, Component};
 use crate::engine::ecs::ComponentId;
+use crate::engine::ecs::component::schema::ComponentSchema;
 
 /// Per-instance voxel lighting/shading metadata.
 ///
 /// Intended usage:
 /// - A CPU system computes `shade_level` / `emissive` for many voxels.
 /// - The renderer consumes a GPU buffer (SSBO) indexed by `gl_InstanceIndex`.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, CatComponent)]
+#[cat(name = "lit_voxel")]
 pub struct LitVoxelComponent {
     /// Quantized shade level (0 = fully lit).
     pub shade_level: u8,
@@ -14,6 +16,7 @@ pub struct LitVoxelComponent {
     /// Secondary effect; if true, voxel emits light / glows.
     pub emissive: bool,
 
+    #[cat(skip)]
     component: Option<ComponentId>,
 }
 
@@ -48,19 +51,11 @@ impl Default for LitVoxelComponent {
 }
 
 impl Component for LitVoxelComponent {
-    fn name(&self) -> &'static str {
-        "lit_voxel"
+    fn schema(&self) -> Option<&'static ComponentSchema> {
+        Some(&Self::SCHEMA)
     }
 
     fn set_id(&mut self, component: ComponentId) {
         self.component = Some(component);
     }
-
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
-    }
-
-    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
-        self
-    }
 }
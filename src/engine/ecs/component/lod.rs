@@ -0,0 +1,74 @@
+use crate::engine::ecs::ComponentId;
+use crate::engine::ecs::component::schema::ComponentSchema;
+use crate::engine::ecs::component::{CatComponent, Component};
+use crate::engine::graphics::primitives::CpuMeshHandle;
+
+/// Default distance (world units) the camera or object must move before LOD is re-evaluated.
+pub const DEFAULT_LOD_HYSTERESIS: f32 = 0.25;
+
+/// Level-of-detail meshes for a renderable.
+///
+/// Intended to be attached as a descendant of a `RenderableComponent`.
+///
+/// Each entry is `(mesh, distance)`: the mesh is used once the distance from the active camera
+/// to the renderable is at least `distance`. The entry with the largest distance not exceeding
+/// the current camera distance wins; closer than every entry selects the first one.
+///
+/// Selection is re-evaluated by `RenderableSystem` only after the camera or the object has moved
+/// more than `hysteresis` world units since the last evaluation.
+#[derive(Debug, Clone, CatComponent)]
+#[cat(name = "lod")]
+pub struct LodComponent {
+    /// Levels as `(mesh, distance)`, each used from `distance` on.
+    #[cat(ty = FieldType::List(&FieldType::Object("[mesh, distance]")))]
+    pub meshes: Vec<(CpuMeshHandle, f32)>,
+    /// World units the camera or object moves before the level is re-evaluated.
+    #[cat(range = 0.0..=f32::INFINITY)]
+    pub hysteresis: f32,
+}
+
+impl Default for LodComponent {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl LodComponent {
+    pub fn new(meshes: Vec<(CpuMeshHandle, f32)>) -> Self {
+        let mut meshes = meshes;
+        meshes.sort_by(|a, b| a.1.total_cmp(&b.1));
+        Self {
+            meshes,
+            hysteresis: DEFAULT_LOD_HYSTERESIS,
+        }
+    }
+
+    pub fn with_level(mut self, mesh: CpuMeshHandle, distance: f32) -> Self {
+        self.meshes.push((mesh, distance));
+        self.meshes.sort_by(|a, b| a.1.total_cmp(&b.1));
+        self
+    }
+
+    pub fn with_hysteresis(mut self, hysteresis: f32) -> Self {
+        self.hysteresis = hysteresis.max(0.0);
+        self
+    }
+
+    /// Index into `meshes` for a camera distance.
+    pub fn level_for_distance(&self, distance: f32) -> usize {
+        self.meshes
+            .iter()
+            .rposition(|(_, d)| *d <= distance)
+            .unwrap_or(0)
+    }
+}
+
+impl Component for LodComponent {
+    fn schema(&self) -> Option<&'static ComponentSchema> {
+        Some(&Self::SCHEMA)
+    }
+
+    fn init(&mut self, queue: &mut crate::engine::ecs::CommandQueue, component: ComponentId) {
+        queue.queue_register_lod(component);
+    }
+}
@@ -0,0 +1,70 @@
+use crate::engine::ai::Cell;
+use crate::engine::ecs::component::schema::ComponentSchema;
+use crate::engine::ecs::component::{CatComponent, Component};
+use crate::engine::ecs::{ComponentId, ComponentRef};
+
+/// Walks its parent Transform toward another component, around obstacles, in the XY plane.
+///
+/// Intended topology: `TransformComponent -> NavAgentComponent`. `NavAgentSystem` plans a path
+/// to `target`'s world position on the world's `ai::NavGrid` resource and moves the Transform
+/// along it at `speed`, replanning when the target crosses into another grid cell. Without a
+/// grid the agent heads straight for the target. It stops within `stop_distance`, and stands
+/// still while the target is missing or unreachable.
+#[derive(Debug, Clone, CatComponent)]
+#[cat(name = "nav_agent")]
+pub struct NavAgentComponent {
+    /// Component walked toward.
+    pub target: ComponentRef,
+    /// Units per second.
+    #[cat(range = 0.0..=f32::INFINITY)]
+    pub speed: f32,
+    /// How close to the target is close enough.
+    #[cat(range = 0.0..=f32::INFINITY)]
+    pub stop_distance: f32,
+
+    /// Remaining waypoints, world XY.
+    #[cat(skip)]
+    pub path: Vec<[f32; 2]>,
+    /// Grid cell of the target when `path` was planned.
+    #[cat(skip)]
+    pub(crate) planned_for: Option<Cell>,
+}
+
+/// Idle (empty target) until `target` is set; used when decoding.
+impl Default for NavAgentComponent {
+    fn default() -> Self {
+        Self::new(ComponentRef::default(), 2.0)
+    }
+}
+
+impl NavAgentComponent {
+    /// `target` from `World::component_ref`.
+    pub fn new(target: ComponentRef, speed: f32) -> Self {
+        Self {
+            target,
+            speed,
+            stop_distance: 0.1,
+            path: Vec::new(),
+            planned_for: None,
+        }
+    }
+
+    pub fn with_stop_distance(mut self, stop_distance: f32) -> Self {
+        self.stop_distance = stop_distance;
+        self
+    }
+}
+
+impl Component for NavAgentComponent {
+    fn schema(&self) -> Option<&'static ComponentSchema> {
+        Some(&Self::SCHEMA)
+    }
+
+    fn init(&mut self, queue: &mut crate::engine::ecs::CommandQueue, component: ComponentId) {
+        queue.queue_register_nav_agent(component);
+    }
+
+    fn component_refs_mut(&mut self) -> Vec<&mut ComponentRef> {
+        vec![&mut self.target]
+    }
+}
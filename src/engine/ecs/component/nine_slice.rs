@@ -0,0 +1,101 @@
+use crate::engine::ecs::ComponentId;
+use crate::engine::ecs::component::schema::ComponentSchema;
+use crate::engine::ecs::component::{CatComponent, Component};
+
+/// Scalable UI panel rendered as a 9-patch.
+///
+/// Intended topology: `TransformComponent -> NineSliceComponent`.
+/// `NineSliceSystem` spawns a child `RenderableComponent` (plus a `TextureComponent` for
+/// `texture`) and rebuilds its mesh whenever `size`/`border_px` change via the setters.
+///
+/// Units:
+/// - The generated mesh is in pixels, centered on the parent Transform. Scale the Transform (or
+///   use a screen anchor) to map pixels into world/clip space.
+/// - `border_px` is the corner size on screen. UV corners come from `source_size_px` (the
+///   texture's pixel size); without it the image is assumed to be split into equal thirds.
+#[derive(Debug, Clone, CatComponent)]
+#[cat(name = "nine_slice")]
+pub struct NineSliceComponent {
+    /// Texture URI.
+    pub texture: String,
+    #[cat(range = 0.0..=f32::INFINITY)]
+    pub border_px: f32,
+    /// Panel size in local units.
+    #[cat(range = 0.0..=f32::INFINITY)]
+    pub size: [f32; 2],
+    /// Texture size in pixels.
+    pub source_size_px: Option<[f32; 2]>,
+
+    #[cat(skip)]
+    dirty: bool,
+}
+
+impl Default for NineSliceComponent {
+    fn default() -> Self {
+        Self::new(String::new(), 0.0, [0.0, 0.0])
+    }
+}
+
+impl NineSliceComponent {
+    pub fn new(texture: impl Into<String>, border_px: f32, size: [f32; 2]) -> Self {
+        Self {
+            texture: texture.into(),
+            border_px,
+            size,
+            source_size_px: None,
+            dirty: true,
+        }
+    }
+
+    pub fn with_source_size(mut self, width_px: f32, height_px: f32) -> Self {
+        self.source_size_px = Some([width_px, height_px]);
+        self.dirty = true;
+        self
+    }
+
+    pub fn set_size(&mut self, width_px: f32, height_px: f32) {
+        if self.size != [width_px, height_px] {
+            self.size = [width_px, height_px];
+            self.dirty = true;
+        }
+    }
+
+    pub fn set_border_px(&mut self, border_px: f32) {
+        if self.border_px != border_px {
+            self.border_px = border_px;
+            self.dirty = true;
+        }
+    }
+
+    /// Corner size in UV space.
+    pub fn uv_border(&self) -> [f32; 2] {
+        match self.source_size_px {
+            Some([w, h]) if w > 0.0 && h > 0.0 => [
+                (self.border_px / w).clamp(0.0, 0.5),
+                (self.border_px / h).clamp(0.0, 0.5),
+            ],
+            _ => [1.0 / 3.0, 1.0 / 3.0],
+        }
+    }
+
+    /// Consume the "mesh needs rebuilding" flag.
+    pub fn take_dirty(&mut self) -> bool {
+        std::mem::take(&mut self.dirty)
+    }
+}
+
+impl Component for NineSliceComponent {
+    fn schema(&self) -> Option<&'static ComponentSchema> {
+        Some(&Self::SCHEMA)
+    }
+
+    fn dependencies(&self, deps: &mut crate::engine::assets::AssetDependencies) {
+        if !self.texture.is_empty() {
+            deps.textures.insert(self.texture.clone());
+        }
+    }
+
+    fn init(&mut self, queue: &mut crate::engine::ecs::CommandQueue, component: ComponentId) {
+        queue.queue_register_nine_slice(component);
+    }
+}
@@ -0,0 +1,33 @@
+use crate::engine::ecs::component::schema::ComponentSchema;
+use crate::engine::ecs::component::{CatComponent, Component};
+
+/// Marks its parent for save games (see `engine::save_game`).
+///
+/// Intended topology: `<component to keep> -> PersistentComponent`. `SaveGame::save` writes
+/// the parent's fields under its guid, and loading puts them back into the live component of
+/// the same guid, so the component must come from the level (scene guids are stable), not be
+/// spawned at runtime. With `subtree` the parent's children are saved too and replaced
+/// wholesale on load: the way to keep things that come and go, like inventory items.
+#[derive(Debug, Clone, Copy, Default, CatComponent)]
+#[cat(name = "persistent")]
+pub struct PersistentComponent {
+    /// Save the parent's children too.
+    pub subtree: bool,
+}
+
+impl PersistentComponent {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep the parent's children as well.
+    pub fn subtree() -> Self {
+        Self { subtree: true }
+    }
+}
+
+impl Component for PersistentComponent {
+    fn schema(&self) -> Option<&'static ComponentSchema> {
+        Some(&Self::SCHEMA)
+    }
+}
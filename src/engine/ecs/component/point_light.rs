@@ -1,16 +1,23 @@
-use super::Component;
+use super::{CatComponent, Component};
 use crate::engine::ecs::ComponentId;
+use crate::engine::ecs::component::schema::ComponentSchema;
 
 /// Point light (local omnidirectional light).
 ///
 /// This is purely an ECS representation for now; renderer integration will come later.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, CatComponent)]
+#[cat(name = "point_light")]
 pub struct PointLightComponent {
+    #[cat(range = 0.0..=f32::INFINITY)]
     pub intensity: f32,
+    /// Falloff distance.
+    #[cat(range = 0.0..=f32::INFINITY)]
     pub distance: f32,
     /// Linear RGB color in 0..1.
+    #[cat(ty = FieldType::Rgb, range = 0.0..=1.0)]
     pub color: [f32; 3],
 
+    #[cat(skip)]
     component: Option<ComponentId>,
 }
 
@@ -51,12 +58,12 @@ impl Default for PointLightComponent {
 }
 
 impl Component for PointLightComponent {
-    fn set_id(&mut self, component: ComponentId) {
-        self.component = Some(component);
+    fn schema(&self) -> Option<&'static ComponentSchema> {
+        Some(&Self::SCHEMA)
     }
 
-    fn name(&self) -> &'static str {
-        "point_light"
+    fn set_id(&mut self, component: ComponentId) {
+        self.component = Some(component);
     }
 
     fn init(
@@ -67,11 +74,14 @@ impl Component for PointLightComponent {
         queue.queue_register_light(component);
     }
 
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
-    }
-
-    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
-        self
+    fn property(&self, name: &str) -> Option<f32> {
+        Some(match name {
+            "intensity" => self.intensity,
+            "distance" => self.distance,
+            "r" => self.color[0],
+            "g" => self.color[1],
+            "b" => self.color[2],
+            _ => return None,
+        })
     }
 }
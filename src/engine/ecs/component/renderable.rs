@@ -1,28 +1,61 @@
 use crate::engine::ecs::ComponentId;
-use crate::engine::ecs::component::Component;
+use crate::engine::ecs::component::schema::ComponentSchema;
+use crate::engine::ecs::component::{CatComponent, Component};
 use crate::engine::graphics::mesh::MeshFactory;
 use crate::engine::graphics::primitives::{InstanceHandle, MaterialHandle, Renderable};
 
 /// Renderable component.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, CatComponent)]
+#[cat(name = "renderable")]
 pub struct RenderableComponent {
+    #[cat(hidden)]
     pub renderable: Renderable,
 
     /// VisualWorld instance handle created for this renderable.
+    #[cat(skip)]
     pub handle: Option<InstanceHandle>,
 
+    /// Layers this renderable is drawn on; only cameras with an overlapping mask see it.
+    pub visibility_layers: u32,
+
+    /// 2D stacking order; higher draws on top of coplanar renderables with a lower value,
+    /// whatever their material. Equal values keep registration order.
+    pub z_order: i32,
+
+    #[cat(skip)]
     component: Option<ComponentId>,
 }
 
+impl Default for RenderableComponent {
+    fn default() -> Self {
+        Self::new(Renderable::new(
+            crate::engine::graphics::primitives::CpuMeshHandle(0),
+            MaterialHandle::TOON_MESH,
+        ))
+    }
+}
+
 impl RenderableComponent {
     pub fn new(renderable: Renderable) -> Self {
         Self {
             renderable,
             handle: None,
+            visibility_layers: crate::engine::graphics::DEFAULT_VISIBILITY_LAYERS,
+            z_order: 0,
             component: None,
         }
     }
 
+    pub fn with_visibility_layers(mut self, mask: u32) -> Self {
+        self.visibility_layers = mask;
+        self
+    }
+
+    pub fn with_z_order(mut self, z_order: i32) -> Self {
+        self.z_order = z_order;
+        self
+    }
+
     fn from_cpu_mesh_handle(
         h: crate::engine::graphics::primitives::CpuMeshHandle,
         material: MaterialHandle,
@@ -66,24 +99,25 @@ impl RenderableComponent {
 }
 
 impl Component for RenderableComponent {
-    fn name(&self) -> &'static str {
-        "renderable"
+    fn schema(&self) -> Option<&'static ComponentSchema> {
+        Some(&Self::SCHEMA)
     }
 
-    fn set_id(&mut self, component: ComponentId) {
-        self.component = Some(component);
-    }
-
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
+    fn dependencies(&self, deps: &mut crate::engine::assets::AssetDependencies) {
+        deps.meshes.insert(self.renderable.mesh);
+        deps.materials.insert(self.renderable.material);
     }
 
-    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
-        self
+    fn set_id(&mut self, component: ComponentId) {
+        self.component = Some(component);
     }
 
     fn init(&mut self, queue: &mut crate::engine::ecs::CommandQueue, component: ComponentId) {
         // Queue registration command instead of immediately registering
         queue.queue_register_renderable(component);
     }
+
+    fn cleanup(&mut self, queue: &mut crate::engine::ecs::CommandQueue, component: ComponentId) {
+        queue.queue_remove_renderable(component);
+    }
 }
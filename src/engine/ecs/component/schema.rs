@@ -0,0 +1,179 @@
+//! Static descriptions of component fields, for the REPL `describe` command and editor UIs.
+//!
+//! `#[derive(CatComponent)]` generates each component's schema (`XComponent::SCHEMA`) from
+//! its encoded fields, their `SchemaField` types and doc comments; runtime state such as
+//! handles and dirty flags is left out, as are fields marked `#[cat(hidden)]`.
+
+use std::fmt;
+
+use crate::engine::graphics::primitives::Transform;
+use crate::utils::color::Color;
+
+/// Value type of a component field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    Bool,
+    U8,
+    U32,
+    I32,
+    F32,
+    Vec2,
+    Vec3,
+    Quat,
+    /// Linear RGB in 0..1.
+    Rgb,
+    /// Linear RGBA in 0..1 (a `Color`); also set from a hex code or color name.
+    Rgba,
+    String,
+    /// Reference to another component (a `ComponentRef`, saved as the target's guid).
+    ComponentId,
+    /// JSON object of the named shape (see the field's description).
+    Object(&'static str),
+    /// One of a fixed set of names.
+    Enum(&'static [&'static str]),
+    /// `Option` of the inner type.
+    Optional(&'static FieldType),
+    /// Variable-length list of the inner type.
+    List(&'static FieldType),
+}
+
+impl fmt::Display for FieldType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldType::Bool => f.write_str("bool"),
+            FieldType::U8 => f.write_str("u8"),
+            FieldType::U32 => f.write_str("u32"),
+            FieldType::I32 => f.write_str("i32"),
+            FieldType::F32 => f.write_str("f32"),
+            FieldType::Vec2 => f.write_str("vec2"),
+            FieldType::Vec3 => f.write_str("vec3"),
+            FieldType::Quat => f.write_str("quat"),
+            FieldType::Rgb => f.write_str("rgb"),
+            FieldType::Rgba => f.write_str("rgba"),
+            FieldType::String => f.write_str("string"),
+            FieldType::ComponentId => f.write_str("component"),
+            FieldType::Object(shape) => write!(f, "{shape}"),
+            FieldType::Enum(variants) => write!(f, "enum({})", variants.join("|")),
+            FieldType::Optional(inner) => write!(f, "{inner}?"),
+            FieldType::List(inner) => write!(f, "[{inner}]"),
+        }
+    }
+}
+
+/// Schema type of a field type, for the fields `#[derive(CatComponent)]` describes.
+pub trait SchemaField {
+    const FIELD_TYPE: FieldType;
+}
+
+macro_rules! schema_field {
+    ($($ty:ty => $field_type:expr),* $(,)?) => {
+        $(impl SchemaField for $ty {
+            const FIELD_TYPE: FieldType = $field_type;
+        })*
+    };
+}
+
+schema_field! {
+    bool => FieldType::Bool,
+    u8 => FieldType::U8,
+    u32 => FieldType::U32,
+    i32 => FieldType::I32,
+    f32 => FieldType::F32,
+    String => FieldType::String,
+    [f32; 2] => FieldType::Vec2,
+    [f32; 3] => FieldType::Vec3,
+    (f32, f32) => FieldType::Vec2,
+    (String, String) => FieldType::List(&FieldType::String),
+    Color => FieldType::Rgba,
+    Transform => FieldType::Object("{translation: vec3, rotation: quat, scale: vec3}"),
+}
+
+impl<T: SchemaField> SchemaField for Option<T> {
+    const FIELD_TYPE: FieldType = FieldType::Optional(&T::FIELD_TYPE);
+}
+
+impl<T: SchemaField> SchemaField for Vec<T> {
+    const FIELD_TYPE: FieldType = FieldType::List(&T::FIELD_TYPE);
+}
+
+/// One editable field.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FieldSchema {
+    pub name: &'static str,
+    pub ty: FieldType,
+    /// Inclusive numeric range applying to every scalar of the field, if limited.
+    pub range: Option<(f32, f32)>,
+    pub doc: &'static str,
+}
+
+impl FieldSchema {
+    pub const fn new(name: &'static str, ty: FieldType, doc: &'static str) -> Self {
+        Self {
+            name,
+            ty,
+            range: None,
+            doc,
+        }
+    }
+
+    pub const fn with_range(mut self, min: f32, max: f32) -> Self {
+        self.range = Some((min, max));
+        self
+    }
+}
+
+impl fmt::Display for FieldSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.name, self.ty)?;
+        if let Some((min, max)) = self.range {
+            write!(f, " [{min}..{max}]")?;
+        }
+        if !self.doc.is_empty() {
+            write!(f, "  {}", self.doc)?;
+        }
+        Ok(())
+    }
+}
+
+/// Fields of one component type.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ComponentSchema {
+    /// Same as `Component::name`.
+    pub type_name: &'static str,
+    pub fields: &'static [FieldSchema],
+}
+
+impl ComponentSchema {
+    pub fn field(&self, name: &str) -> Option<&FieldSchema> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+}
+
+/// Schemas of the engine's built-in component types.
+pub fn builtin() -> &'static [ComponentSchema] {
+    macro_rules! schemas {
+        ($($ty:path),* $(,)?) => {
+            &[$(<$ty>::SCHEMA),*]
+        };
+    }
+    const BUILTIN: &[ComponentSchema] = super::for_each_builtin_component!(schemas);
+    BUILTIN
+}
+
+/// Built-in schema by type name (`renderable`, `RenderableComponent`, `screen_anchor`, ...).
+pub fn find(type_name: &str) -> Option<&'static ComponentSchema> {
+    let wanted = normalize_type_name(type_name);
+    builtin()
+        .iter()
+        .find(|s| normalize_type_name(s.type_name) == wanted)
+}
+
+/// `RenderableComponent`, `renderable`, `screen_anchor` and `ScreenAnchor` all normalize to
+/// the same string.
+pub fn normalize_type_name(ty: &str) -> String {
+    let ty = ty.strip_suffix("Component").unwrap_or(ty);
+    ty.chars()
+        .filter(|c| *c != '_')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
@@ -0,0 +1,126 @@
+use crate::engine::ecs::ComponentId;
+use crate::engine::ecs::codec::{FieldCodec, Value};
+use crate::engine::ecs::component::schema::{ComponentSchema, FieldType, SchemaField};
+use crate::engine::ecs::component::{CatComponent, Component};
+
+/// Point of the viewport a `ScreenAnchorComponent` is pinned to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScreenAnchor {
+    #[default]
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl ScreenAnchor {
+    /// Anchor position as a fraction of the viewport (`[0, 0]` = top-left, `[1, 1]` = bottom-right).
+    pub fn factor(self) -> [f32; 2] {
+        match self {
+            ScreenAnchor::TopLeft => [0.0, 0.0],
+            ScreenAnchor::Top => [0.5, 0.0],
+            ScreenAnchor::TopRight => [1.0, 0.0],
+            ScreenAnchor::Left => [0.0, 0.5],
+            ScreenAnchor::Center => [0.5, 0.5],
+            ScreenAnchor::Right => [1.0, 0.5],
+            ScreenAnchor::BottomLeft => [0.0, 1.0],
+            ScreenAnchor::Bottom => [0.5, 1.0],
+            ScreenAnchor::BottomRight => [1.0, 1.0],
+        }
+    }
+}
+
+impl ScreenAnchor {
+    const NAMES: [(ScreenAnchor, &'static str); 9] = [
+        (ScreenAnchor::TopLeft, "top_left"),
+        (ScreenAnchor::Top, "top"),
+        (ScreenAnchor::TopRight, "top_right"),
+        (ScreenAnchor::Left, "left"),
+        (ScreenAnchor::Center, "center"),
+        (ScreenAnchor::Right, "right"),
+        (ScreenAnchor::BottomLeft, "bottom_left"),
+        (ScreenAnchor::Bottom, "bottom"),
+        (ScreenAnchor::BottomRight, "bottom_right"),
+    ];
+}
+
+impl FieldCodec for ScreenAnchor {
+    fn encode(&self) -> Value {
+        let (_, name) = Self::NAMES.iter().find(|(a, _)| a == self).unwrap();
+        Value::from(*name)
+    }
+
+    fn decode(value: &Value) -> Option<Self> {
+        let name = value.as_str()?;
+        Self::NAMES
+            .iter()
+            .find(|(_, n)| *n == name)
+            .map(|(a, _)| *a)
+    }
+}
+
+impl SchemaField for ScreenAnchor {
+    const FIELD_TYPE: FieldType = FieldType::Enum(&[
+        "top_left",
+        "top",
+        "top_right",
+        "left",
+        "center",
+        "right",
+        "bottom_left",
+        "bottom",
+        "bottom_right",
+    ]);
+}
+
+/// Pins its parent Transform to a point of the screen (HUD elements).
+///
+/// Intended topology: `TransformComponent -> ScreenAnchorComponent`, with the HUD content
+/// (renderables, 9-slice panels, ...) as further children of the Transform.
+///
+/// `ScreenAnchorSystem` rewrites the parent Transform's model matrix whenever the viewport or
+/// active camera changes, so that:
+/// - 1 local unit = 1 physical pixel, +Y down (same as screen coordinates).
+/// - The local origin sits at `anchor` plus `offset_px` (use negative offsets for right/bottom
+///   anchors).
+/// - The Transform's own translation/rotation/scale still apply, in pixels, on top of that.
+///
+/// The result is exact for the 2D camera path; perspective Camera3D projections are not
+/// supported.
+#[derive(Debug, Clone, Copy, Default, CatComponent)]
+#[cat(name = "screen_anchor")]
+pub struct ScreenAnchorComponent {
+    /// Viewport point.
+    pub anchor: ScreenAnchor,
+    /// Pixels from the anchor, +Y down.
+    pub offset_px: [f32; 2],
+}
+
+impl ScreenAnchorComponent {
+    pub fn new(anchor: ScreenAnchor) -> Self {
+        Self {
+            anchor,
+            offset_px: [0.0, 0.0],
+        }
+    }
+
+    pub fn with_offset_px(mut self, x: f32, y: f32) -> Self {
+        self.offset_px = [x, y];
+        self
+    }
+}
+
+impl Component for ScreenAnchorComponent {
+    fn schema(&self) -> Option<&'static ComponentSchema> {
+        Some(&Self::SCHEMA)
+    }
+
+    fn init(&mut self, queue: &mut crate::engine::ecs::CommandQueue, component: ComponentId) {
+        queue.queue_register_screen_anchor(component);
+    }
+}
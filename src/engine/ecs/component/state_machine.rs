@@ -0,0 +1,390 @@
+use crate::engine::ecs::ComponentId;
+use crate::engine::ecs::codec::{FieldCodec, Map, Value};
+use crate::engine::ecs::component::schema::{ComponentSchema, FieldType, SchemaField};
+use crate::engine::ecs::component::{CatComponent, Component};
+use crate::engine::ecs::query::Query;
+
+/// One state of a `StateMachineComponent`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MachineState {
+    pub name: String,
+    /// Enclosing state; `None` for a top-level state. Entering a state with children enters
+    /// the first child listed.
+    pub parent: Option<String>,
+    /// REPL command lines run on entry, outermost state first. `$self` stands for the state
+    /// machine's id and `$owner` for its parent's.
+    pub on_enter: Vec<String>,
+    /// REPL command lines run on exit, innermost state first.
+    pub on_exit: Vec<String>,
+}
+
+impl MachineState {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_parent(mut self, parent: impl Into<String>) -> Self {
+        self.parent = Some(parent.into());
+        self
+    }
+
+    pub fn on_enter(mut self, command: impl Into<String>) -> Self {
+        self.on_enter.push(command.into());
+        self
+    }
+
+    pub fn on_exit(mut self, command: impl Into<String>) -> Self {
+        self.on_exit.push(command.into());
+        self
+    }
+}
+
+impl FieldCodec for MachineState {
+    fn encode(&self) -> Value {
+        let mut map = Map::new();
+        map.insert("name".into(), self.name.encode());
+        if let Some(parent) = &self.parent {
+            map.insert("parent".into(), parent.encode());
+        }
+        if !self.on_enter.is_empty() {
+            map.insert("on_enter".into(), self.on_enter.encode());
+        }
+        if !self.on_exit.is_empty() {
+            map.insert("on_exit".into(), self.on_exit.encode());
+        }
+        Value::Object(map)
+    }
+
+    fn decode(value: &Value) -> Option<Self> {
+        let mut state = Self::new(String::decode(value.get("name")?)?);
+        if let Some(v) = value.get("parent") {
+            state.parent = Option::decode(v)?;
+        }
+        if let Some(v) = value.get("on_enter") {
+            state.on_enter = Vec::decode(v)?;
+        }
+        if let Some(v) = value.get("on_exit") {
+            state.on_exit = Vec::decode(v)?;
+        }
+        Some(state)
+    }
+}
+
+impl SchemaField for MachineState {
+    const FIELD_TYPE: FieldType = FieldType::Object("{name, parent?, on_enter?, on_exit?}");
+}
+
+/// Move from `from` (or any state inside it) to `to` once every condition that is set holds.
+/// A transition without conditions fires on the next tick.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Transition {
+    pub from: String,
+    pub to: String,
+    /// Fires when this event was sent since the last tick (`StateMachineComponent::send`,
+    /// REPL `fsm <id> send <event>`).
+    pub event: Option<String>,
+    /// `find` terms the machine's parent must match, e.g. `x>10` or `has=velocity`.
+    pub condition: Option<String>,
+    /// Seconds since the last transition.
+    pub after: Option<f32>,
+}
+
+impl Transition {
+    pub fn new(from: impl Into<String>, to: impl Into<String>) -> Self {
+        Self {
+            from: from.into(),
+            to: to.into(),
+            ..Self::default()
+        }
+    }
+
+    pub fn on_event(mut self, event: impl Into<String>) -> Self {
+        self.event = Some(event.into());
+        self
+    }
+
+    pub fn when(mut self, condition: impl Into<String>) -> Self {
+        self.condition = Some(condition.into());
+        self
+    }
+
+    pub fn after(mut self, seconds: f32) -> Self {
+        self.after = Some(seconds);
+        self
+    }
+
+    /// `condition` parsed; `Ok(None)` without one.
+    pub fn query(&self) -> Result<Option<Query>, String> {
+        self.condition
+            .as_deref()
+            .map(|c| Query::parse(&c.split_whitespace().collect::<Vec<_>>()))
+            .transpose()
+    }
+}
+
+impl FieldCodec for Transition {
+    fn encode(&self) -> Value {
+        let mut map = Map::new();
+        map.insert("from".into(), self.from.encode());
+        map.insert("to".into(), self.to.encode());
+        if let Some(event) = &self.event {
+            map.insert("event".into(), event.encode());
+        }
+        if let Some(condition) = &self.condition {
+            map.insert("condition".into(), condition.encode());
+        }
+        if let Some(after) = self.after {
+            map.insert("after".into(), after.encode());
+        }
+        Value::Object(map)
+    }
+
+    fn decode(value: &Value) -> Option<Self> {
+        let mut t = Self::new(
+            String::decode(value.get("from")?)?,
+            String::decode(value.get("to")?)?,
+        );
+        if let Some(v) = value.get("event") {
+            t.event = Option::decode(v)?;
+        }
+        if let Some(v) = value.get("condition") {
+            t.condition = Option::decode(v)?;
+        }
+        if let Some(v) = value.get("after") {
+            t.after = Option::decode(v)?;
+        }
+        Some(t)
+    }
+}
+
+impl SchemaField for Transition {
+    const FIELD_TYPE: FieldType = FieldType::Object("{from, to, event?, condition?, after?}");
+}
+
+/// Hierarchical state machine for gameplay and AI logic declared in scene data.
+///
+/// Intended topology: `<owner> -> StateMachineComponent`, where the owner (an NPC's Transform,
+/// a door, ...) is what transition conditions test and what `$owner` names in hooks.
+/// `StateMachineSystem` enters `initial` on the first tick, then fires at most one transition
+/// per tick, checking the active state's transitions before its parents'. Leaving and
+/// entering states queues their hook commands, which `Universe` runs after the tick like
+/// typed REPL commands. REPL `fsm <id>` shows the active states.
+#[derive(Debug, Clone, Default, CatComponent)]
+#[cat(name = "state_machine")]
+pub struct StateMachineComponent {
+    /// States; nested ones name their parent.
+    pub states: Vec<MachineState>,
+    /// At most one fires per tick.
+    pub transitions: Vec<Transition>,
+    /// Top-level state to start in; the first one listed when `None`.
+    pub initial: Option<String>,
+
+    /// Innermost active state; `None` until the first tick.
+    #[cat(skip)]
+    active: Option<String>,
+    #[cat(skip)]
+    time_in_state: f32,
+    /// Events sent since the last tick.
+    #[cat(skip)]
+    events: Vec<String>,
+}
+
+impl StateMachineComponent {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_state(mut self, state: MachineState) -> Self {
+        self.states.push(state);
+        self
+    }
+
+    pub fn with_transition(mut self, transition: Transition) -> Self {
+        self.transitions.push(transition);
+        self
+    }
+
+    pub fn with_initial(mut self, state: impl Into<String>) -> Self {
+        self.initial = Some(state.into());
+        self
+    }
+
+    pub fn state(&self, name: &str) -> Option<&MachineState> {
+        self.states.iter().find(|s| s.name == name)
+    }
+
+    /// Deliver `event` to the transitions checked on the next tick.
+    pub fn send(&mut self, event: impl Into<String>) {
+        self.events.push(event.into());
+    }
+
+    pub fn events(&self) -> &[String] {
+        &self.events
+    }
+
+    pub(crate) fn take_events(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Innermost active state.
+    pub fn active(&self) -> Option<&str> {
+        self.active.as_deref()
+    }
+
+    /// Active states, innermost first.
+    pub fn active_path(&self) -> Vec<&str> {
+        self.active().map_or_else(Vec::new, |s| self.path(s))
+    }
+
+    /// `name` is active, directly or through one of its children.
+    pub fn is_in(&self, name: &str) -> bool {
+        self.active_path().contains(&name)
+    }
+
+    /// Seconds since the last transition.
+    pub fn time_in_state(&self) -> f32 {
+        self.time_in_state
+    }
+
+    pub(crate) fn advance_time(&mut self, dt_sec: f32) {
+        self.time_in_state += dt_sec;
+    }
+
+    /// Enter the initial state; returns the hook commands to run.
+    pub(crate) fn start(&mut self) -> Vec<String> {
+        let initial = self
+            .initial
+            .clone()
+            .or_else(|| self.states.first().map(|s| s.name.clone()));
+        match initial {
+            Some(initial) if self.state(&initial).is_some() => self.go_to(&initial),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Leave the active states up to the one enclosing `target`, then enter `target` and its
+    /// first children down to a leaf. Returns the hook commands to run, exits first.
+    pub fn go_to(&mut self, target: &str) -> Vec<String> {
+        if self.state(target).is_none() {
+            return Vec::new();
+        }
+        let leaf = self.leaf_of(target);
+        let mut enclosing = self.path(target);
+        enclosing.remove(0);
+
+        let mut hooks: Vec<String> = Vec::new();
+        for name in self.active_path() {
+            if enclosing.contains(&name) {
+                break;
+            }
+            hooks.extend(self.state(name).into_iter().flat_map(|s| s.on_exit.clone()));
+        }
+        let entered: Vec<&str> = self
+            .path(&leaf)
+            .into_iter()
+            .take_while(|name| !enclosing.contains(name))
+            .collect();
+        for name in entered.into_iter().rev() {
+            hooks.extend(
+                self.state(name)
+                    .into_iter()
+                    .flat_map(|s| s.on_enter.clone()),
+            );
+        }
+
+        self.active = Some(leaf);
+        self.time_in_state = 0.0;
+        hooks
+    }
+
+    /// `name` and its ancestors, innermost first. Stops at unknown parents and cycles.
+    pub fn path<'a>(&'a self, name: &'a str) -> Vec<&'a str> {
+        let mut path = vec![name];
+        let mut cur = self.state(name);
+        while let Some(parent) = cur.and_then(|s| s.parent.as_deref()) {
+            if path.contains(&parent) || path.len() > self.states.len() {
+                break;
+            }
+            path.push(parent);
+            cur = self.state(parent);
+        }
+        path
+    }
+
+    pub fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a MachineState> {
+        self.states
+            .iter()
+            .filter(move |s| s.parent.as_deref() == Some(name))
+    }
+
+    /// Follow first children down from `name`.
+    fn leaf_of(&self, name: &str) -> String {
+        let mut leaf = name;
+        for _ in 0..self.states.len() {
+            match self.children(leaf).next() {
+                Some(child) => leaf = &child.name,
+                None => break,
+            }
+        }
+        leaf.to_string()
+    }
+
+    /// Problems that keep states or transitions from working, one line each.
+    pub fn problems(&self) -> Vec<String> {
+        let mut out = Vec::new();
+        for (i, s) in self.states.iter().enumerate() {
+            if self.states[..i].iter().any(|o| o.name == s.name) {
+                out.push(format!("state '{}' is listed twice", s.name));
+            }
+            if let Some(parent) = &s.parent
+                && self.state(parent).is_none()
+            {
+                out.push(format!("state '{}': no parent state '{parent}'", s.name));
+            }
+            let path = self.path(&s.name);
+            if let Some(parent) = path
+                .last()
+                .and_then(|&top| self.state(top)?.parent.as_deref())
+                && path.contains(&parent)
+            {
+                out.push(format!("state '{}': parents form a cycle", s.name));
+            }
+        }
+        if let Some(initial) = &self.initial
+            && self.state(initial).is_none()
+        {
+            out.push(format!("no initial state '{initial}'"));
+        }
+        for t in &self.transitions {
+            for name in [&t.from, &t.to] {
+                if self.state(name).is_none() {
+                    out.push(format!("{} -> {}: no state '{name}'", t.from, t.to));
+                }
+            }
+            if let Err(e) = t.query() {
+                out.push(format!("{} -> {}: {e}", t.from, t.to));
+            }
+        }
+        out
+    }
+}
+
+impl Component for StateMachineComponent {
+    fn schema(&self) -> Option<&'static ComponentSchema> {
+        Some(&Self::SCHEMA)
+    }
+
+    fn property(&self, name: &str) -> Option<f32> {
+        match name {
+            "time_in_state" => Some(self.time_in_state),
+            _ => None,
+        }
+    }
+
+    fn init(&mut self, queue: &mut crate::engine::ecs::CommandQueue, component: ComponentId) {
+        queue.queue_register_state_machine(component);
+    }
+}
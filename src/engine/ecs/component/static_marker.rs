@@ -0,0 +1,31 @@
+use crate::engine::ecs::ComponentId;
+use crate::engine::ecs::component::schema::ComponentSchema;
+use crate::engine::ecs::component::{CatComponent, Component};
+
+/// Marks a renderable as immobile so it can be merged into a static batch.
+///
+/// Intended to be attached as a descendant of a `RenderableComponent`.
+///
+/// Once the renderable (and its texture, if any) reaches `VisualWorld`, `StaticBatchSystem`
+/// bakes its world-space geometry into a combined mesh shared with other static renderables of
+/// the same material/texture/color, and drops the individual instance. Transform changes to
+/// static renderables are not tracked after batching.
+#[derive(Debug, Clone, Copy, Default, CatComponent)]
+#[cat(name = "static")]
+pub struct StaticComponent;
+
+impl StaticComponent {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Component for StaticComponent {
+    fn schema(&self) -> Option<&'static ComponentSchema> {
+        Some(&Self::SCHEMA)
+    }
+
+    fn init(&mut self, queue: &mut crate::engine::ecs::CommandQueue, component: ComponentId) {
+        queue.queue_register_static(component);
+    }
+}
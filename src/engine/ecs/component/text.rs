@@ -0,0 +1,59 @@
+use crate::engine::ecs::codec::{FieldCodec, Value};
+use crate::engine::ecs::component::schema::ComponentSchema;
+use crate::engine::ecs::component::{CatComponent, Component};
+use crate::engine::i18n;
+
+/// A line of UI text: a literal, or a localization key (see `engine::i18n`).
+///
+/// With a `key`, `display` looks it up in the current locale on every call, so switching
+/// locale changes what every text shows at once; `i18n::current().generation()` tells a
+/// renderer when to lay the text out again.
+///
+/// NOTE: nothing draws these yet; `display` is what a renderer would lay out with
+/// `graphics::text` (which the console already uses).
+#[derive(Debug, Clone, Default, PartialEq, CatComponent)]
+#[cat(name = "text")]
+pub struct TextComponent {
+    /// Shown as is when there is no `key`.
+    pub text: String,
+    /// String table key, e.g. `menu.start`; wins over `text`.
+    pub key: Option<String>,
+}
+
+impl TextComponent {
+    pub fn literal(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            key: None,
+        }
+    }
+
+    pub fn localized(key: impl Into<String>) -> Self {
+        Self {
+            text: String::new(),
+            key: Some(key.into()),
+        }
+    }
+
+    /// The text to show in the current locale.
+    pub fn display(&self) -> String {
+        match &self.key {
+            Some(key) => i18n::tr(key),
+            None => self.text.clone(),
+        }
+    }
+}
+
+impl Component for TextComponent {
+    fn schema(&self) -> Option<&'static ComponentSchema> {
+        Some(&Self::SCHEMA)
+    }
+
+    fn set_field(&mut self, name: &str, value: &Value) -> bool {
+        match name {
+            "text" => String::decode(value).map(|t| self.text = t).is_some(),
+            "key" => Option::decode(value).map(|k| self.key = k).is_some(),
+            _ => false,
+        }
+    }
+}
@@ -1,16 +1,25 @@
 use crate::engine::ecs::ComponentId;
-use crate::engine::ecs::component::Component;
+use crate::engine::ecs::component::schema::ComponentSchema;
+use crate::engine::ecs::component::{CatComponent, Component};
 
 /// Reference to a texture image by URI.
 ///
 /// This component is intended to be attached as a descendant of a `RenderableComponent`.
 /// The URI is stored in `TextureSystem`; loading, decoding, and GPU upload happen when the
 /// system sees the texture is attached to a renderable.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, CatComponent)]
+#[cat(name = "texture")]
 pub struct TextureComponent {
+    /// Image path relative to the asset root.
     pub uri: String,
 }
 
+impl Default for TextureComponent {
+    fn default() -> Self {
+        Self::new(String::new())
+    }
+}
+
 impl TextureComponent {
     pub fn new(uri: impl Into<String>) -> Self {
         Self { uri: uri.into() }
@@ -26,16 +35,14 @@ impl TextureComponent {
 }
 
 impl Component for TextureComponent {
-    fn name(&self) -> &'static str {
-        "texture"
-    }
-
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
+    fn schema(&self) -> Option<&'static ComponentSchema> {
+        Some(&Self::SCHEMA)
     }
 
-    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
-        self
+    fn dependencies(&self, deps: &mut crate::engine::assets::AssetDependencies) {
+        if !self.uri.is_empty() {
+            deps.textures.insert(self.uri.clone());
+        }
     }
 
     fn init(&mut self, queue: &mut crate::engine::ecs::CommandQueue, component: ComponentId) {
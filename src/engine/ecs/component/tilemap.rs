@@ -0,0 +1,215 @@
+use std::collections::HashSet;
+
+use crate::engine::ecs::ComponentId;
+use crate::engine::ecs::component::schema::ComponentSchema;
+use crate::engine::ecs::component::{CatComponent, Component};
+use crate::engine::graphics::mesh::{CpuMesh, CpuVertex};
+
+/// Tiles per chunk edge. Each chunk becomes one mesh / one `VisualWorld` instance.
+pub const TILEMAP_CHUNK_SIZE: u32 = 32;
+
+/// 2D tile grid rendered from a texture atlas.
+///
+/// Intended topology: `TransformComponent -> TilemapComponent`.
+/// `TilemapSystem` spawns one child `RenderableComponent` per non-empty 32x32 chunk (plus a
+/// `TextureComponent` for the atlas) and rebuilds only chunks touched by `set_tile`.
+///
+/// Layout:
+/// - Tile `(x, y)` covers `[x, x + 1] * tile_size.x` by `[y, y + 1] * tile_size.y` in the
+///   tilemap's local space (+Y down, like the 2D demo scenes).
+/// - Tile indices address the atlas row-major: `column = index % atlas_columns`.
+#[derive(Debug, Clone, CatComponent)]
+#[cat(name = "tilemap", after_decode = after_decode)]
+pub struct TilemapComponent {
+    /// In tiles.
+    pub width: u32,
+    /// In tiles.
+    pub height: u32,
+    /// Local units per tile.
+    #[cat(range = 0.0..=f32::INFINITY)]
+    pub tile_size: [f32; 2],
+
+    /// Atlas texture URI (see `TextureComponent`). `None` renders untextured tiles.
+    pub atlas: Option<String>,
+    pub atlas_columns: u32,
+    pub atlas_rows: u32,
+    /// Non-empty tiles are walls for navigation (`ai::NavGrid::from_world`).
+    pub solid: bool,
+
+    #[cat(hidden)]
+    tiles: Vec<Option<u32>>,
+    #[cat(skip)]
+    dirty_chunks: HashSet<(u32, u32)>,
+}
+
+impl Default for TilemapComponent {
+    fn default() -> Self {
+        Self::new(0, 0, [1.0, 1.0])
+    }
+}
+
+impl TilemapComponent {
+    pub fn new(width: u32, height: u32, tile_size: [f32; 2]) -> Self {
+        let mut t = Self {
+            width,
+            height,
+            tile_size,
+            atlas: None,
+            atlas_columns: 1,
+            atlas_rows: 1,
+            solid: false,
+            tiles: vec![None; (width * height) as usize],
+            dirty_chunks: HashSet::new(),
+        };
+        t.mark_all_dirty();
+        t
+    }
+
+    pub fn with_atlas(mut self, uri: impl Into<String>, columns: u32, rows: u32) -> Self {
+        self.atlas = Some(uri.into());
+        self.atlas_columns = columns.max(1);
+        self.atlas_rows = rows.max(1);
+        self.mark_all_dirty();
+        self
+    }
+
+    pub fn with_solid(mut self, solid: bool) -> Self {
+        self.solid = solid;
+        self
+    }
+
+    /// Builder-style: fill from row-major tile indices (`None` = empty).
+    pub fn with_tiles(mut self, tiles: Vec<Option<u32>>) -> Self {
+        let len = self.tiles.len();
+        self.tiles = tiles;
+        self.tiles.resize(len, None);
+        self.mark_all_dirty();
+        self
+    }
+
+    pub fn tile(&self, x: u32, y: u32) -> Option<u32> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.tiles[(y * self.width + x) as usize]
+    }
+
+    /// Set (or clear) a tile. Only the containing chunk is rebuilt on the next render.
+    pub fn set_tile(&mut self, x: u32, y: u32, tile: Option<u32>) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+        let idx = (y * self.width + x) as usize;
+        if self.tiles[idx] == tile {
+            return;
+        }
+        self.tiles[idx] = tile;
+        self.dirty_chunks
+            .insert((x / TILEMAP_CHUNK_SIZE, y / TILEMAP_CHUNK_SIZE));
+    }
+
+    /// Number of chunks along X and Y.
+    pub fn chunk_counts(&self) -> (u32, u32) {
+        (
+            self.width.div_ceil(TILEMAP_CHUNK_SIZE),
+            self.height.div_ceil(TILEMAP_CHUNK_SIZE),
+        )
+    }
+
+    pub fn has_dirty_chunks(&self) -> bool {
+        !self.dirty_chunks.is_empty()
+    }
+
+    /// Drain the set of chunks that need rebuilding.
+    pub fn take_dirty_chunks(&mut self) -> Vec<(u32, u32)> {
+        self.dirty_chunks.drain().collect()
+    }
+
+    /// Decoded data may carry any number of tiles; fit them to the size and rebuild everything.
+    fn after_decode(&mut self) {
+        self.tiles.resize((self.width * self.height) as usize, None);
+        self.atlas_columns = self.atlas_columns.max(1);
+        self.atlas_rows = self.atlas_rows.max(1);
+        self.dirty_chunks.clear();
+        self.mark_all_dirty();
+    }
+
+    fn mark_all_dirty(&mut self) {
+        let (cx, cy) = self.chunk_counts();
+        for y in 0..cy {
+            for x in 0..cx {
+                self.dirty_chunks.insert((x, y));
+            }
+        }
+    }
+
+    /// Build the mesh for one chunk. Returns `None` if the chunk has no tiles.
+    pub fn build_chunk_mesh(&self, chunk: (u32, u32)) -> Option<CpuMesh> {
+        let x0 = chunk.0 * TILEMAP_CHUNK_SIZE;
+        let y0 = chunk.1 * TILEMAP_CHUNK_SIZE;
+        let x1 = (x0 + TILEMAP_CHUNK_SIZE).min(self.width);
+        let y1 = (y0 + TILEMAP_CHUNK_SIZE).min(self.height);
+
+        let [tw, th] = self.tile_size;
+        let du = 1.0 / self.atlas_columns as f32;
+        let dv = 1.0 / self.atlas_rows as f32;
+
+        let mut vertices: Vec<CpuVertex> = Vec::new();
+        let mut indices: Vec<u32> = Vec::new();
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let Some(tile) = self.tiles[(y * self.width + x) as usize] else {
+                    continue;
+                };
+                let u0 = (tile % self.atlas_columns) as f32 * du;
+                let v0 = (tile / self.atlas_columns) as f32 * dv;
+                let (px0, py0) = (x as f32 * tw, y as f32 * th);
+                let (px1, py1) = (px0 + tw, py0 + th);
+
+                // Same corner order / winding as `MeshFactory::quad_2d`.
+                let base = vertices.len() as u32;
+                vertices.extend([
+                    CpuVertex {
+                        pos: [px0, py0, 0.0],
+                        uv: [u0, v0],
+                    },
+                    CpuVertex {
+                        pos: [px1, py0, 0.0],
+                        uv: [u0 + du, v0],
+                    },
+                    CpuVertex {
+                        pos: [px1, py1, 0.0],
+                        uv: [u0 + du, v0 + dv],
+                    },
+                    CpuVertex {
+                        pos: [px0, py1, 0.0],
+                        uv: [u0, v0 + dv],
+                    },
+                ]);
+                indices.extend([base, base + 1, base + 2, base, base + 2, base + 3]);
+            }
+        }
+
+        if indices.is_empty() {
+            None
+        } else {
+            Some(CpuMesh::new(vertices, indices))
+        }
+    }
+}
+
+impl Component for TilemapComponent {
+    fn schema(&self) -> Option<&'static ComponentSchema> {
+        Some(&Self::SCHEMA)
+    }
+
+    fn dependencies(&self, deps: &mut crate::engine::assets::AssetDependencies) {
+        if let Some(atlas) = &self.atlas {
+            deps.textures.insert(atlas.clone());
+        }
+    }
+
+    fn init(&mut self, queue: &mut crate::engine::ecs::CommandQueue, component: ComponentId) {
+        queue.queue_register_tilemap(component);
+    }
+}
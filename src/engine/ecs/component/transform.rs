@@ -1,13 +1,18 @@
-use super::Component;
+use super::{CatComponent, Component};
 use crate::engine::ecs::CommandQueue;
 use crate::engine::ecs::ComponentId;
+use crate::engine::ecs::component::schema::ComponentSchema;
 use crate::engine::graphics::primitives::Transform;
+use glam::{EulerRot, Mat3, Quat, Vec3};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, CatComponent)]
+#[cat(name = "transform")]
 pub struct TransformComponent {
-    /// Engine-wide transform type (also used by renderer/VisualWorld).
+    /// Engine-wide transform type (also used by renderer/VisualWorld); each part may be left
+    /// out when setting it.
     pub transform: Transform,
 
+    #[cat(skip)]
     component: Option<ComponentId>,
 }
 
@@ -25,13 +30,13 @@ impl TransformComponent {
     }
 
     pub fn with_position(mut self, x: f32, y: f32, z: f32) -> Self {
-        self.transform.translation = [x, y, z];
+        self.transform.translation = Vec3::new(x, y, z);
         self.recompute_model();
         self
     }
 
     pub fn with_scale(mut self, x: f32, y: f32, z: f32) -> Self {
-        self.transform.scale = [x, y, z];
+        self.transform.scale = Vec3::new(x, y, z);
         self.recompute_model();
         self
     }
@@ -44,30 +49,8 @@ impl TransformComponent {
 
     /// Private helper: computes and sets quaternion from euler angles, then recomputes model.
     fn set_rotation_euler_internal(&mut self, pitch_x: f32, yaw_y: f32, roll_z: f32) {
-        // Minimal Euler->quat (XYZ intrinsic) implementation.
-        let (sx, cx) = (0.5 * pitch_x).sin_cos();
-        let (sy, cy) = (0.5 * yaw_y).sin_cos();
-        let (sz, cz) = (0.5 * roll_z).sin_cos();
-
-        // q = qx * qy * qz
-        let qx = [sx, 0.0, 0.0, cx];
-        let qy = [0.0, sy, 0.0, cy];
-        let qz = [0.0, 0.0, sz, cz];
-
-        fn quat_mul(a: [f32; 4], b: [f32; 4]) -> [f32; 4] {
-            let (ax, ay, az, aw) = (a[0], a[1], a[2], a[3]);
-            let (bx, by, bz, bw) = (b[0], b[1], b[2], b[3]);
-            [
-                aw * bx + ax * bw + ay * bz - az * by,
-                aw * by - ax * bz + ay * bw + az * bx,
-                aw * bz + ax * by - ay * bx + az * bw,
-                aw * bw - ax * bx - ay * by - az * bz,
-            ]
-        }
-
-        let qxy = quat_mul(qx, qy);
-        let q = quat_mul(qxy, qz);
-        self.transform.rotation = q;
+        // XYZ intrinsic: q = qx * qy * qz
+        self.transform.rotation = Quat::from_euler(EulerRot::XYZ, pitch_x, yaw_y, roll_z);
         self.recompute_model();
     }
 
@@ -80,27 +63,83 @@ impl TransformComponent {
         roll_z: f32,
     ) {
         self.set_rotation_euler_internal(pitch_x, yaw_y, roll_z);
+        self.queue_update(queue);
+    }
 
-        let Some(cid) = self.component else {
-            return;
-        };
-        queue.queue_update_transform(cid, self.transform);
+    /// Set rotation from a quaternion and queue update.
+    pub fn set_rotation(&mut self, queue: &mut CommandQueue, rotation: Quat) {
+        self.transform.rotation = rotation;
+        self.recompute_model();
+        self.queue_update(queue);
     }
 
     /// Set translation and queue update.
     pub fn set_position(&mut self, queue: &mut CommandQueue, x: f32, y: f32, z: f32) {
-        self.transform.translation = [x, y, z];
+        self.transform.translation = Vec3::new(x, y, z);
         self.recompute_model();
-        let Some(cid) = self.component else {
-            return;
-        };
-        queue.queue_update_transform(cid, self.transform);
+        self.queue_update(queue);
     }
 
     /// Set non-uniform scale and queue update.
     pub fn set_scale(&mut self, queue: &mut CommandQueue, x: f32, y: f32, z: f32) {
-        self.transform.scale = [x, y, z];
+        self.transform.scale = Vec3::new(x, y, z);
         self.recompute_model();
+        self.queue_update(queue);
+    }
+
+    /// Local -Z in parent space (the direction cameras look along).
+    pub fn forward(&self) -> Vec3 {
+        self.transform.rotation * Vec3::NEG_Z
+    }
+
+    /// Local +X in parent space.
+    pub fn right(&self) -> Vec3 {
+        self.transform.rotation * Vec3::X
+    }
+
+    /// Local +Y in parent space.
+    pub fn up(&self) -> Vec3 {
+        self.transform.rotation * Vec3::Y
+    }
+
+    /// Rotate so `forward()` points at `target` (parent space) with `up()` as close to `up`
+    /// as possible, and queue update.
+    ///
+    /// Does nothing if `target` is the current position or lies straight along `up`.
+    pub fn look_at(&mut self, queue: &mut CommandQueue, target: Vec3, up: Vec3) {
+        let Some(forward) = (target - self.transform.translation).try_normalize() else {
+            return;
+        };
+        let Some(right) = forward.cross(up).try_normalize() else {
+            return;
+        };
+        let up = right.cross(forward);
+        self.transform.rotation = Quat::from_mat3(&Mat3::from_cols(right, up, -forward));
+        self.recompute_model();
+        self.queue_update(queue);
+    }
+
+    /// Move by `delta` expressed in the transform's own axes (rotation applied, scale not),
+    /// and queue update.
+    pub fn translate_local(&mut self, queue: &mut CommandQueue, delta: Vec3) {
+        self.transform.translation += self.transform.rotation * delta;
+        self.recompute_model();
+        self.queue_update(queue);
+    }
+
+    /// Rotate by `angle` (radians) around `axis` (parent space, through the transform's own
+    /// origin), and queue update. A zero axis is ignored.
+    pub fn rotate_around(&mut self, queue: &mut CommandQueue, axis: Vec3, angle: f32) {
+        let Some(axis) = axis.try_normalize() else {
+            return;
+        };
+        self.transform.rotation =
+            (Quat::from_axis_angle(axis, angle) * self.transform.rotation).normalize();
+        self.recompute_model();
+        self.queue_update(queue);
+    }
+
+    fn queue_update(&self, queue: &mut CommandQueue) {
         let Some(cid) = self.component else {
             return;
         };
@@ -109,20 +148,25 @@ impl TransformComponent {
 }
 
 impl Component for TransformComponent {
-    fn name(&self) -> &'static str {
-        "transform"
+    fn schema(&self) -> Option<&'static ComponentSchema> {
+        Some(&Self::SCHEMA)
     }
 
     fn set_id(&mut self, component: ComponentId) {
         self.component = Some(component);
     }
 
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
-    }
-
-    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
-        self
+    fn property(&self, name: &str) -> Option<f32> {
+        let t = &self.transform;
+        Some(match name {
+            "x" => t.translation.x,
+            "y" => t.translation.y,
+            "z" => t.translation.z,
+            "sx" => t.scale.x,
+            "sy" => t.scale.y,
+            "sz" => t.scale.z,
+            _ => return None,
+        })
     }
 
     fn init(&mut self, queue: &mut CommandQueue, component: ComponentId) {
@@ -0,0 +1,51 @@
+use crate::engine::ecs::ComponentId;
+use crate::engine::ecs::component::schema::ComponentSchema;
+use crate::engine::ecs::component::{CatComponent, Component};
+
+/// Sensor box that reports what enters and leaves it, without pushing anything back.
+///
+/// Intended topology: `TransformComponent -> TriggerVolumeComponent`. The box is centered on
+/// the parent Transform and follows its world position, rotation and scale (as the enclosing
+/// world-space box). `TriggerSystem` tests it against the bounds of every renderable in the
+/// spatial index, except those under the same Transform, and records enter/exit events in the
+/// world's `TriggerEvents` resource. `tag` names what the volume is for (`checkpoint`,
+/// `pickup`, ...), so zone logic can be declared in scene files and matched by gameplay code.
+#[derive(Debug, Clone, CatComponent)]
+#[cat(name = "trigger_volume")]
+pub struct TriggerVolumeComponent {
+    /// Half the box size along each axis, in the parent Transform's space.
+    #[cat(range = 0.0..=f32::INFINITY)]
+    pub half_extents: [f32; 3],
+    /// What the volume is for.
+    pub tag: String,
+}
+
+impl Default for TriggerVolumeComponent {
+    fn default() -> Self {
+        Self::new(0.5, 0.5, 0.5)
+    }
+}
+
+impl TriggerVolumeComponent {
+    pub fn new(half_x: f32, half_y: f32, half_z: f32) -> Self {
+        Self {
+            half_extents: [half_x, half_y, half_z],
+            tag: String::new(),
+        }
+    }
+
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.tag = tag.into();
+        self
+    }
+}
+
+impl Component for TriggerVolumeComponent {
+    fn schema(&self) -> Option<&'static ComponentSchema> {
+        Some(&Self::SCHEMA)
+    }
+
+    fn init(&mut self, queue: &mut crate::engine::ecs::CommandQueue, component: ComponentId) {
+        queue.queue_register_trigger(component);
+    }
+}
@@ -1,5 +1,6 @@
 use crate::engine::ecs::ComponentId;
-use crate::engine::ecs::component::Component;
+use crate::engine::ecs::component::schema::ComponentSchema;
+use crate::engine::ecs::component::{CatComponent, Component};
 
 /// Per-vertex UVs for a renderable.
 ///
@@ -8,8 +9,10 @@ use crate::engine::ecs::component::Component;
 /// Lifecycle note:
 /// - UV overrides are applied when the renderable is flushed into `VisualWorld` / uploaded.
 /// - If fewer UVs are provided than the mesh's vertex count, the missing UVs are filled with 0.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, CatComponent)]
+#[cat(name = "uv")]
 pub struct UVComponent {
+    /// Per-vertex texture coordinates.
     pub uvs: Vec<[f32; 2]>,
 }
 
@@ -45,16 +48,8 @@ impl Default for UVComponent {
 }
 
 impl Component for UVComponent {
-    fn name(&self) -> &'static str {
-        "uv"
-    }
-
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
-    }
-
-    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
-        self
+    fn schema(&self) -> Option<&'static ComponentSchema> {
+        Some(&Self::SCHEMA)
     }
 
     fn init(&mut self, queue: &mut crate::engine::ecs::CommandQueue, component: ComponentId) {
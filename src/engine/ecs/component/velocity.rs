@@ -0,0 +1,59 @@
+use crate::engine::ecs::ComponentId;
+use crate::engine::ecs::component::schema::ComponentSchema;
+use crate::engine::ecs::component::{CatComponent, Component};
+
+/// Moves its parent Transform at a constant rate, without collision or forces.
+///
+/// Intended topology: `TransformComponent -> VelocityComponent`. `MovementSystem` integrates
+/// the parent Transform on a fixed timestep; enough for projectiles and drifting props until
+/// the physics module lands.
+#[derive(Debug, Clone, Copy, Default, CatComponent)]
+#[cat(name = "velocity")]
+pub struct VelocityComponent {
+    /// Units per second, in the Transform's parent space.
+    pub linear: [f32; 3],
+    /// Rotation axis scaled by radians per second, in the Transform's parent space.
+    pub angular: [f32; 3],
+    /// Exponential decay rate per second applied to both velocities (0 = none).
+    #[cat(range = 0.0..=f32::INFINITY)]
+    pub damping: f32,
+}
+
+impl VelocityComponent {
+    pub fn new(x: f32, y: f32, z: f32) -> Self {
+        Self {
+            linear: [x, y, z],
+            ..Self::default()
+        }
+    }
+
+    pub fn with_angular(mut self, x: f32, y: f32, z: f32) -> Self {
+        self.angular = [x, y, z];
+        self
+    }
+
+    pub fn with_damping(mut self, damping: f32) -> Self {
+        self.damping = damping;
+        self
+    }
+}
+
+impl Component for VelocityComponent {
+    fn schema(&self) -> Option<&'static ComponentSchema> {
+        Some(&Self::SCHEMA)
+    }
+
+    fn property(&self, name: &str) -> Option<f32> {
+        Some(match name {
+            "vx" => self.linear[0],
+            "vy" => self.linear[1],
+            "vz" => self.linear[2],
+            "damping" => self.damping,
+            _ => return None,
+        })
+    }
+
+    fn init(&mut self, queue: &mut crate::engine::ecs::CommandQueue, component: ComponentId) {
+        queue.queue_register_velocity(component);
+    }
+}
@@ -0,0 +1,52 @@
+use crate::engine::ecs::ComponentId;
+use crate::engine::ecs::component::schema::ComponentSchema;
+use crate::engine::ecs::component::{CatComponent, Component};
+use crate::engine::user_input::XrHand;
+
+/// Device paths an `XrPoseComponent` can follow.
+const PATHS: [(&str, XrHand); 2] = [("left_hand", XrHand::Left), ("right_hand", XrHand::Right)];
+
+/// Makes its parent Transform follow a tracked XR device.
+///
+/// Intended topology: `TransformComponent (stage) -> TransformComponent -> XrPoseComponent`,
+/// with the hand's content (model, pointer ray, grab volume) under the middle Transform. Each
+/// frame `XrPoseSystem` copies the device pose (stage space) into that Transform's translation
+/// and rotation, so the outer Transform places the play area in the world. While the device
+/// isn't tracked the Transform keeps its last pose.
+#[derive(Debug, Clone, CatComponent)]
+#[cat(name = "xr_pose")]
+pub struct XrPoseComponent {
+    /// `left_hand` or `right_hand`.
+    pub path: String,
+}
+
+/// Follows the left hand; used when decoding.
+impl Default for XrPoseComponent {
+    fn default() -> Self {
+        Self::new("left_hand")
+    }
+}
+
+impl XrPoseComponent {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// The hand `path` names, or `None` for an unknown path.
+    pub fn hand(&self) -> Option<XrHand> {
+        PATHS
+            .iter()
+            .find(|(name, _)| *name == self.path)
+            .map(|&(_, hand)| hand)
+    }
+}
+
+impl Component for XrPoseComponent {
+    fn schema(&self) -> Option<&'static ComponentSchema> {
+        Some(&Self::SCHEMA)
+    }
+
+    fn init(&mut self, queue: &mut crate::engine::ecs::CommandQueue, component: ComponentId) {
+        queue.queue_register_xr_pose(component);
+    }
+}
@@ -0,0 +1,55 @@
+//! References from one component to another that survive scene save and load.
+//!
+//! A `ComponentId` is only meaningful in the world that handed it out, so a field pointing at
+//! another component holds a `ComponentRef`: the id for lookups at runtime plus the target's
+//! guid (`ComponentNode::guid`), which is what scene files store. After decoding,
+//! `ComponentCodec::resolve_refs` turns guids back into ids of the new world and reports the
+//! ones whose target isn't there.
+
+use uuid::Uuid;
+
+use crate::engine::ecs::ComponentId;
+use crate::engine::ecs::codec::{FieldCodec, Value};
+use crate::engine::ecs::component::schema::{FieldType, SchemaField};
+
+/// `Default` refers to nothing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ComponentRef {
+    /// Target in this world; null until resolved, or if the target is missing.
+    pub id: ComponentId,
+    /// Target's guid; `None` for an empty reference.
+    pub guid: Option<Uuid>,
+}
+
+impl ComponentRef {
+    pub fn is_none(&self) -> bool {
+        self.guid.is_none()
+    }
+}
+
+/// As the target's guid, or `null` for an empty reference. Scenes saved before references
+/// had guids hold a `ComponentId` string instead, which can't be resolved and decodes as empty.
+impl FieldCodec for ComponentRef {
+    fn encode(&self) -> Value {
+        self.guid
+            .map_or(Value::Null, |guid| Value::String(guid.to_string()))
+    }
+
+    fn decode(value: &Value) -> Option<Self> {
+        match value {
+            Value::Null => Some(Self::default()),
+            Value::String(s) => match Uuid::parse_str(s) {
+                Ok(guid) => Some(Self {
+                    guid: Some(guid),
+                    ..Self::default()
+                }),
+                Err(_) => s.parse::<ComponentId>().ok().map(|_| Self::default()),
+            },
+            _ => None,
+        }
+    }
+}
+
+impl SchemaField for ComponentRef {
+    const FIELD_TYPE: FieldType = FieldType::ComponentId;
+}
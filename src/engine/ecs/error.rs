@@ -0,0 +1,35 @@
+use uuid::Uuid;
+
+use crate::engine::ecs::ComponentId;
+
+/// Failures of component-graph operations on `World`.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum EcsError {
+    #[error("component {0:?} does not exist")]
+    ComponentNotFound(ComponentId),
+
+    #[error("cannot parent component {0:?} to itself")]
+    SelfParent(ComponentId),
+
+    #[error("parenting {child:?} under {parent:?} would create a cycle")]
+    Cycle {
+        parent: ComponentId,
+        child: ComponentId,
+    },
+
+    #[error("component {0:?} has children; use remove_component_subtree or detach children first")]
+    HasChildren(ComponentId),
+
+    #[error("component {component:?} is a {found}, expected a {expected}")]
+    WrongComponentType {
+        component: ComponentId,
+        expected: &'static str,
+        found: &'static str,
+    },
+
+    #[error("ancestors of transform {0:?} have a singular world matrix")]
+    SingularTransform(ComponentId),
+
+    #[error("guid {guid} already belongs to component {owner:?}")]
+    DuplicateGuid { guid: Uuid, owner: ComponentId },
+}
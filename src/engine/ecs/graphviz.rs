@@ -0,0 +1,71 @@
+//! Graphviz (DOT) export of the component graph, for debugging topology.
+//!
+//! Render with e.g. `dot -Tsvg world.dot -o world.svg`.
+
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::engine::ecs::component::{RenderableComponent, TransformComponent};
+use crate::engine::ecs::{ComponentId, World};
+use crate::engine::error::AssetError;
+
+impl World {
+    /// The component graph as DOT: one node per component labeled with its type name and id,
+    /// one edge per parent -> child link.
+    ///
+    /// Renderables without an ancestor Transform (which never get a world model) are drawn red.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph world {\n    node [shape=box, fontname=monospace];\n");
+        let ordered = self.components_in_order();
+        let nodes = || {
+            ordered
+                .iter()
+                .filter_map(|&cid| Some((cid, self.get_component_record(cid)?)))
+        };
+        for (cid, node) in nodes() {
+            let style = if self.is_orphaned_renderable(cid) {
+                ", color=red, fontcolor=red"
+            } else {
+                ""
+            };
+            let _ = writeln!(
+                out,
+                "    \"{cid}\" [label=\"{}\\n{cid}\"{style}];",
+                node.name
+            );
+        }
+        for (cid, node) in nodes() {
+            for child in &node.children {
+                let _ = writeln!(out, "    \"{cid}\" -> \"{child}\";");
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Write `to_dot` to `path`.
+    pub fn export_dot(&self, path: impl AsRef<Path>) -> Result<(), AssetError> {
+        let path = path.as_ref();
+        std::fs::write(path, self.to_dot()).map_err(|e| AssetError::io(path, e))
+    }
+
+    fn is_orphaned_renderable(&self, cid: ComponentId) -> bool {
+        if self
+            .get_component_by_id_as::<RenderableComponent>(cid)
+            .is_none()
+        {
+            return false;
+        }
+        let mut cur = cid;
+        while let Some(parent) = self.parent_of(cur) {
+            if self
+                .get_component_by_id_as::<TransformComponent>(parent)
+                .is_some()
+            {
+                return false;
+            }
+            cur = parent;
+        }
+        true
+    }
+}
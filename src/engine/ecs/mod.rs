@@ -1,31 +1,78 @@
+pub mod codec;
 pub mod command_queue;
 pub mod component;
+pub mod component_ref;
+pub mod error;
+pub mod graphviz;
+pub mod prefab;
+pub mod query;
+pub mod registry;
+pub mod resources;
+pub mod scene_binary;
+pub mod synthetic;
 pub mod system;
 
+#[cfg(test)]
+mod codec_tests;
+#[cfg(test)]
+mod resources_tests;
+#[cfg(test)]
+mod synthetic_tests;
 #[cfg(test)]
 mod world_graph_tests;
 
+use crate::engine::ecs::component::{Component, TransformComponent};
+use crate::engine::ecs::system::TransformSystem;
 use crate::engine::graphics::{RenderAssets, VisualWorld};
+use glam::{Mat4, Quat, Vec3};
 use slotmap::{SlotMap, new_key_type};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
 
 new_key_type! {
     /// Global component identity (dense arena key).
     pub struct ComponentId;
 }
 
+/// `<index>v<version>`, e.g. `3v1`; what the REPL prints and accepts.
+impl std::fmt::Display for ComponentId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ffi = slotmap::Key::data(self).as_ffi();
+        write!(f, "{}v{}", ffi & 0xffff_ffff, ffi >> 32)
+    }
+}
+
+impl std::str::FromStr for ComponentId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (index, version) = s
+            .split_once('v')
+            .and_then(|(i, v)| Some((i.parse::<u32>().ok()?, v.parse::<u32>().ok()?)))
+            .ok_or_else(|| format!("'{s}' is not a component id (expected e.g. 3v1)"))?;
+        let ffi = (u64::from(version) << 32) | u64::from(index);
+        Ok(slotmap::KeyData::from_ffi(ffi).into())
+    }
+}
+
 // Re-export these so other modules can use `crate::engine::ecs::Transform`
 // and `crate::engine::ecs::Renderable` consistently.
 pub use crate::engine::graphics::primitives::{Renderable, Transform};
 
 pub use command_queue::CommandQueue;
-pub use system::{System, SystemWorld};
+pub use component_ref::ComponentRef;
+pub use error::EcsError;
+pub use resources::Resources;
+pub use system::{EnabledSystems, System, SystemWorld};
 
 /// Bundle of mutable engine state passed to component mutation APIs.
 ///
 /// This exists to avoid threading `&mut World`, `&mut SystemWorld`, and `&mut VisualWorld`
-/// through every component call.
+/// through every component call. The helpers below queue commands like the component setters
+/// do, so changes are applied on the next `process_commands`.
 pub struct WorldContext<'a> {
     pub world: &'a mut World,
+    pub queue: &'a mut CommandQueue,
     pub systems: &'a mut SystemWorld,
     pub visuals: &'a mut VisualWorld,
     pub render_assets: &'a mut RenderAssets,
@@ -34,23 +81,170 @@ pub struct WorldContext<'a> {
 impl<'a> WorldContext<'a> {
     pub fn new(
         world: &'a mut World,
+        queue: &'a mut CommandQueue,
         systems: &'a mut SystemWorld,
         visuals: &'a mut VisualWorld,
         render_assets: &'a mut RenderAssets,
     ) -> Self {
         Self {
             world,
+            queue,
             systems,
             visuals,
             render_assets,
         }
     }
+
+    /// Add a root component and initialize it (queues its registration).
+    pub fn spawn<T: Component>(&mut self, component: T) -> ComponentId {
+        let cid = self.world.add_component(component);
+        self.world.init_component_tree(cid, self.queue);
+        cid
+    }
+
+    /// Add a component under `parent` and initialize it.
+    pub fn spawn_child<T: Component>(
+        &mut self,
+        parent: ComponentId,
+        component: T,
+    ) -> Result<ComponentId, EcsError> {
+        if self.world.get_component_record(parent).is_none() {
+            return Err(EcsError::ComponentNotFound(parent));
+        }
+        let cid = self.world.add_component(component);
+        self.world.add_child(parent, cid)?;
+        self.world.init_component_tree(cid, self.queue);
+        Ok(cid)
+    }
+
+    /// Clean up and remove a component and its descendants.
+    pub fn despawn(&mut self, cid: ComponentId) -> Result<(), EcsError> {
+        if self.world.get_component_record(cid).is_none() {
+            return Err(EcsError::ComponentNotFound(cid));
+        }
+        self.world.cleanup_component_tree(cid, self.queue);
+        self.world.remove_component_subtree(cid)
+    }
+
+    pub fn set_position(&mut self, cid: ComponentId, position: [f32; 3]) -> Result<(), EcsError> {
+        let [x, y, z] = position;
+        transform_mut(self.world, cid)?.set_position(self.queue, x, y, z);
+        Ok(())
+    }
+
+    pub fn set_scale(&mut self, cid: ComponentId, scale: [f32; 3]) -> Result<(), EcsError> {
+        let [x, y, z] = scale;
+        transform_mut(self.world, cid)?.set_scale(self.queue, x, y, z);
+        Ok(())
+    }
+
+    /// Euler angles in radians, XYZ order (see `TransformComponent::set_rotation_euler`).
+    pub fn set_rotation_euler(
+        &mut self,
+        cid: ComponentId,
+        euler: [f32; 3],
+    ) -> Result<(), EcsError> {
+        let [x, y, z] = euler;
+        transform_mut(self.world, cid)?.set_rotation_euler(self.queue, x, y, z);
+        Ok(())
+    }
+
+    /// Place a Transform at `position` in world space, whatever its parent chain.
+    ///
+    /// The local translation is back-computed from the ancestors' current world matrix.
+    pub fn set_world_position(
+        &mut self,
+        cid: ComponentId,
+        position: [f32; 3],
+    ) -> Result<(), EcsError> {
+        let world_pos = Vec3::from(position);
+        let local = match self.parent_world_matrix(cid)? {
+            Some(parent) => parent.inverse().transform_point3(world_pos),
+            None => world_pos,
+        };
+        transform_mut(self.world, cid)?.set_position(self.queue, local.x, local.y, local.z);
+        Ok(())
+    }
+
+    /// Orient a Transform to `rotation` in world space, whatever its parent chain.
+    ///
+    /// Exact as long as the ancestors don't combine non-uniform scale with rotation (shear).
+    pub fn set_world_rotation(&mut self, cid: ComponentId, rotation: Quat) -> Result<(), EcsError> {
+        let local = match self.parent_world_matrix(cid)? {
+            Some(parent) => {
+                let (_, parent_rotation, _) = parent.to_scale_rotation_translation();
+                parent_rotation.inverse() * rotation
+            }
+            None => rotation,
+        };
+        transform_mut(self.world, cid)?.set_rotation(self.queue, local.normalize());
+        Ok(())
+    }
+
+    /// World matrix of `cid`'s ancestor Transforms; `None` at the root. Errors if `cid` isn't a
+    /// Transform or the ancestors collapse to a singular matrix.
+    fn parent_world_matrix(&mut self, cid: ComponentId) -> Result<Option<Mat4>, EcsError> {
+        transform_mut(self.world, cid)?;
+        let Some(parent) = TransformSystem::world_model(self.world, cid) else {
+            return Ok(None);
+        };
+        let parent = Mat4::from_cols_array_2d(&parent);
+        if parent.determinant().abs() < 1e-12 {
+            return Err(EcsError::SingularTransform(cid));
+        }
+        Ok(Some(parent))
+    }
+}
+
+fn transform_mut(world: &mut World, cid: ComponentId) -> Result<&mut TransformComponent, EcsError> {
+    let node = world
+        .get_component_record_mut(cid)
+        .ok_or(EcsError::ComponentNotFound(cid))?;
+    let name = node.component.name();
+    node.component
+        .as_any_mut()
+        .downcast_mut::<TransformComponent>()
+        .ok_or(EcsError::WrongComponentType {
+            component: cid,
+            expected: "transform",
+            found: name,
+        })
+}
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for &b in bytes {
+        hash ^= u64::from(b);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
 }
 
 /// World: owns all global components.
 #[derive(Default)]
 pub struct World {
     components: SlotMap<ComponentId, crate::engine::ecs::component::ComponentNode>,
+
+    /// Component types this world can construct by name (scene loading, REPL `spawn`).
+    registry: registry::ComponentRegistry,
+
+    /// Prefabs read while decoding; `ComponentCodec::encode` diffs instances against them.
+    prefabs: prefab::PrefabLibrary,
+
+    /// Roots of subtrees attached or detached since the last `take_moved_subtrees`.
+    moved_subtrees: Vec<ComponentId>,
+    /// Same ids as `moved_subtrees`, so recording one stays O(1) while building large worlds.
+    moved_subtree_set: HashSet<ComponentId>,
+
+    /// `ComponentNode::order` of the next component added.
+    next_order: u64,
+
+    /// `ComponentNode::guid` -> id, for every live component.
+    guids: HashMap<Uuid, ComponentId>,
+
+    /// Singletons by type (see `ecs::resources`).
+    resources: Resources,
 }
 
 impl World {
@@ -75,8 +269,7 @@ impl World {
         &mut self,
         c: Box<dyn crate::engine::ecs::component::Component>,
     ) -> ComponentId {
-        self.components
-            .insert(crate::engine::ecs::component::ComponentNode::new(c))
+        self.insert_node(crate::engine::ecs::component::ComponentNode::new(c))
     }
 
     /// Add a new boxed component with an explicit stored name.
@@ -85,10 +278,54 @@ impl World {
         name: &'static str,
         c: Box<dyn crate::engine::ecs::component::Component>,
     ) -> ComponentId {
-        self.components
-            .insert(crate::engine::ecs::component::ComponentNode::new_named(
-                name, c,
-            ))
+        self.insert_node(crate::engine::ecs::component::ComponentNode::new_named(
+            name, c,
+        ))
+    }
+
+    fn insert_node(
+        &mut self,
+        mut node: crate::engine::ecs::component::ComponentNode,
+    ) -> ComponentId {
+        node.order = self.next_order;
+        self.next_order += 1;
+        let guid = node.guid;
+        let id = self.components.insert(node);
+        self.guids.insert(guid, id);
+        id
+    }
+
+    /// The component with `guid`, if it is still in the world.
+    pub fn component_id_by_guid(&self, guid: Uuid) -> Option<ComponentId> {
+        self.guids.get(&guid).copied()
+    }
+
+    /// A reference to `c` for a component field; empty if `c` doesn't exist.
+    pub fn component_ref(&self, c: ComponentId) -> ComponentRef {
+        match self.components.get(c) {
+            Some(node) => ComponentRef {
+                id: c,
+                guid: Some(node.guid),
+            },
+            None => ComponentRef::default(),
+        }
+    }
+
+    /// Give `c` a new guid (scene loading restores saved ones). Fails if another component
+    /// already has it.
+    pub fn set_guid(&mut self, c: ComponentId, guid: Uuid) -> Result<(), EcsError> {
+        let Some(node) = self.components.get_mut(c) else {
+            return Err(EcsError::ComponentNotFound(c));
+        };
+        match self.guids.get(&guid) {
+            Some(&owner) if owner == c => return Ok(()),
+            Some(&owner) => return Err(EcsError::DuplicateGuid { guid, owner }),
+            None => {}
+        }
+        self.guids.remove(&node.guid);
+        node.guid = guid;
+        self.guids.insert(guid, c);
+        Ok(())
     }
 
     /// Temporary alias during migration.
@@ -113,6 +350,170 @@ impl World {
         self.components.get_mut(id)
     }
 
+    /// Iterate all component records in the world, in arena order: fast, but slots are reused,
+    /// so the order changes from run to run. Listings use `components_in_order`.
+    pub fn all_components(
+        &self,
+    ) -> impl Iterator<Item = (ComponentId, &crate::engine::ecs::component::ComponentNode)> {
+        self.components.iter()
+    }
+
+    pub fn registry(&self) -> &registry::ComponentRegistry {
+        &self.registry
+    }
+
+    pub fn registry_mut(&mut self) -> &mut registry::ComponentRegistry {
+        &mut self.registry
+    }
+
+    pub fn prefabs(&self) -> &prefab::PrefabLibrary {
+        &self.prefabs
+    }
+
+    pub fn prefabs_mut(&mut self) -> &mut prefab::PrefabLibrary {
+        &mut self.prefabs
+    }
+
+    /// Store the `T` singleton, returning the one it replaced.
+    pub fn insert_resource<T: std::any::Any>(&mut self, value: T) -> Option<T> {
+        self.resources.insert(value)
+    }
+
+    pub fn resource<T: std::any::Any>(&self) -> Option<&T> {
+        self.resources.get()
+    }
+
+    pub fn resource_mut<T: std::any::Any>(&mut self) -> Option<&mut T> {
+        self.resources.get_mut()
+    }
+
+    pub fn remove_resource<T: std::any::Any>(&mut self) -> Option<T> {
+        self.resources.remove()
+    }
+
+    /// Every resource, for the rarer calls (`contains`, `get_or_insert_with`).
+    pub fn resources(&self) -> &Resources {
+        &self.resources
+    }
+
+    pub fn resources_mut(&mut self) -> &mut Resources {
+        &mut self.resources
+    }
+
+    /// Number of components currently stored in the world.
+    pub fn component_count(&self) -> usize {
+        self.components.len()
+    }
+
+    /// Hash of every component's encoded data and the tree structure, for asserting that two
+    /// runs (replays, network peers) ended in the same state without diffing JSON dumps.
+    ///
+    /// Children are hashed in order; roots are not, since their order follows arena slots
+    /// rather than anything in the scene. Ids, guids and `#[cat(skip)]` runtime state are left
+    /// out: a `ComponentRef` counts as its target's position in the traversal, with roots
+    /// ordered by their hash without references. The hash is FNV-1a, so it is the same across
+    /// processes and builds.
+    pub fn state_hash(&self) -> u64 {
+        let unnumbered = HashMap::new();
+        let mut roots: Vec<(u64, ComponentId)> = self
+            .root_components()
+            .into_iter()
+            .map(|root| (self.subtree_hash(root, &unnumbered), root))
+            .collect();
+        roots.sort_by_key(|&(h, _)| h);
+        let mut positions = HashMap::new();
+        for &(_, root) in &roots {
+            self.number_subtree(root, &mut positions);
+        }
+        let mut hashes: Vec<u64> = roots
+            .iter()
+            .map(|&(_, root)| self.subtree_hash(root, &positions))
+            .collect();
+        hashes.sort_unstable();
+        hashes.iter().fold(
+            fnv1a(FNV_OFFSET, &(hashes.len() as u64).to_le_bytes()),
+            |h, r| fnv1a(h, &r.to_le_bytes()),
+        )
+    }
+
+    fn number_subtree(&self, cid: ComponentId, positions: &mut HashMap<ComponentId, u64>) {
+        positions.insert(cid, positions.len() as u64);
+        for &child in self.children_of(cid) {
+            self.number_subtree(child, positions);
+        }
+    }
+
+    /// `positions` stands in for the guids of referenced components (`null` if unnumbered).
+    fn subtree_hash(&self, cid: ComponentId, positions: &HashMap<ComponentId, u64>) -> u64 {
+        let Some(node) = self.get_component_record(cid) else {
+            return FNV_OFFSET;
+        };
+        let mut data = node.component.encode();
+        self.replace_guids(&mut data, positions);
+        let mut h = fnv1a(FNV_OFFSET, node.component.type_name().as_bytes());
+        h = fnv1a(h, &[0]);
+        h = fnv1a(h, data.to_string().as_bytes());
+        h = fnv1a(h, &(node.children.len() as u64).to_le_bytes());
+        for &child in &node.children {
+            h = fnv1a(h, &self.subtree_hash(child, positions).to_le_bytes());
+        }
+        h
+    }
+
+    fn replace_guids(&self, value: &mut codec::Value, positions: &HashMap<ComponentId, u64>) {
+        match value {
+            codec::Value::String(s) => {
+                if let Ok(guid) = Uuid::parse_str(s)
+                    && let Some(id) = self.component_id_by_guid(guid)
+                {
+                    *value = positions
+                        .get(&id)
+                        .map_or(codec::Value::Null, |&p| codec::Value::from(p));
+                }
+            }
+            codec::Value::Array(items) => {
+                for item in items {
+                    self.replace_guids(item, positions);
+                }
+            }
+            codec::Value::Object(map) => {
+                for item in map.values_mut() {
+                    self.replace_guids(item, positions);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Components without a parent (the roots of each component tree), oldest first.
+    pub fn root_components(&self) -> Vec<ComponentId> {
+        let mut roots: Vec<_> = self
+            .components
+            .iter()
+            .filter(|(_, node)| node.parent.is_none())
+            .map(|(id, node)| (node.order, id))
+            .collect();
+        roots.sort_unstable();
+        roots.into_iter().map(|(_, id)| id).collect()
+    }
+
+    /// Every component, oldest first. Loading a scene adds its trees in file order, so saving
+    /// it again keeps that order.
+    pub fn components_in_order(&self) -> Vec<ComponentId> {
+        let mut all: Vec<_> = self
+            .components
+            .iter()
+            .map(|(id, node)| (node.order, id))
+            .collect();
+        all.sort_unstable();
+        all.into_iter().map(|(_, id)| id).collect()
+    }
+
+    /// Sort `ids` oldest first; removed ids go last.
+    pub fn sort_by_insertion(&self, ids: &mut [ComponentId]) {
+        ids.sort_by_key(|&id| self.get_component_record(id).map_or(u64::MAX, |n| n.order));
+    }
+
     // --- Topology helpers (component-graph) ---
     pub fn parent_of(&self, c: ComponentId) -> Option<ComponentId> {
         self.get_component_record(c)?.parent
@@ -170,22 +571,18 @@ impl World {
     /// - Both ids must exist.
     /// - `child` is detached from its current parent first.
     /// - Cycles are rejected.
-    pub fn add_child(
-        &mut self,
-        parent: ComponentId,
-        child: ComponentId,
-    ) -> Result<(), &'static str> {
+    pub fn add_child(&mut self, parent: ComponentId, child: ComponentId) -> Result<(), EcsError> {
         if self.get_component_record(parent).is_none() {
-            return Err("parent does not exist");
+            return Err(EcsError::ComponentNotFound(parent));
         }
         if self.get_component_record(child).is_none() {
-            return Err("child does not exist");
+            return Err(EcsError::ComponentNotFound(child));
         }
         if parent == child {
-            return Err("cannot parent component to itself");
+            return Err(EcsError::SelfParent(child));
         }
         if self.is_ancestor_of(child, parent) {
-            return Err("cycle detected");
+            return Err(EcsError::Cycle { parent, child });
         }
 
         self.detach_from_parent(child);
@@ -194,18 +591,19 @@ impl World {
         {
             let child_node = self
                 .get_component_record_mut(child)
-                .ok_or("child missing")?;
+                .ok_or(EcsError::ComponentNotFound(child))?;
             child_node.parent = Some(parent);
         }
         // Push into parent's children list.
         {
             let parent_node = self
                 .get_component_record_mut(parent)
-                .ok_or("parent missing")?;
+                .ok_or(EcsError::ComponentNotFound(parent))?;
             if !parent_node.children.contains(&child) {
                 parent_node.children.push(child);
             }
         }
+        self.mark_moved(child);
 
         Ok(())
     }
@@ -217,7 +615,7 @@ impl World {
         &mut self,
         child: ComponentId,
         new_parent: Option<ComponentId>,
-    ) -> Result<(), &'static str> {
+    ) -> Result<(), EcsError> {
         match new_parent {
             None => {
                 self.detach_from_parent(child);
@@ -244,31 +642,48 @@ impl World {
         if let Some(parent_node) = self.get_component_record_mut(old_parent) {
             parent_node.children.retain(|&c| c != child);
         }
+        self.mark_moved(child);
+    }
+
+    fn mark_moved(&mut self, root: ComponentId) {
+        if self.moved_subtree_set.insert(root) {
+            self.moved_subtrees.push(root);
+        }
+    }
+
+    /// Drain the roots of subtrees whose parent changed (`add_child`, `set_parent`,
+    /// `detach_from_parent`). Their ancestor chain changed, so cached world matrices and bounds
+    /// below them are stale; `SystemWorld::process_commands` consumes these every frame.
+    ///
+    /// Ids may have been removed since; callers should skip those.
+    pub fn take_moved_subtrees(&mut self) -> Vec<ComponentId> {
+        self.moved_subtree_set.clear();
+        std::mem::take(&mut self.moved_subtrees)
     }
 
     /// Remove a component from the world.
     ///
     /// This is a *leaf-only* removal: it fails if the component still has children.
     /// Use `remove_component_subtree` when you want to delete a whole branch.
-    pub fn remove_component_leaf(&mut self, c: ComponentId) -> Result<(), &'static str> {
+    pub fn remove_component_leaf(&mut self, c: ComponentId) -> Result<(), EcsError> {
         let Some(node) = self.get_component_record(c) else {
-            return Err("component does not exist");
+            return Err(EcsError::ComponentNotFound(c));
         };
         if !node.children.is_empty() {
-            return Err(
-                "component has children; use remove_component_subtree or detach children first",
-            );
+            return Err(EcsError::HasChildren(c));
         }
 
         self.detach_from_parent(c);
-        self.components.remove(c);
+        if let Some(node) = self.components.remove(c) {
+            self.guids.remove(&node.guid);
+        }
         Ok(())
     }
 
     /// Remove a component and all its descendants.
-    pub fn remove_component_subtree(&mut self, root: ComponentId) -> Result<(), &'static str> {
+    pub fn remove_component_subtree(&mut self, root: ComponentId) -> Result<(), EcsError> {
         if self.get_component_record(root).is_none() {
-            return Err("component does not exist");
+            return Err(EcsError::ComponentNotFound(root));
         }
 
         // Detach root first so parent doesn't retain dead child.
@@ -279,10 +694,7 @@ impl World {
         let mut order: Vec<ComponentId> = Vec::new();
         while let Some(c) = stack.pop() {
             order.push(c);
-            let children: Vec<ComponentId> = self.children_of(c).to_vec();
-            for ch in children {
-                stack.push(ch);
-            }
+            stack.extend_from_slice(self.children_of(c));
         }
 
         // Delete in reverse (children first).
@@ -292,7 +704,9 @@ impl World {
                 node.parent = None;
                 node.children.clear();
             }
-            self.components.remove(c);
+            if let Some(node) = self.components.remove(c) {
+                self.guids.remove(&node.guid);
+            }
         }
 
         Ok(())
@@ -318,4 +732,23 @@ impl World {
             self.init_component_tree(child, queue);
         }
     }
+
+    /// Run `Component::cleanup` on a component tree, children first.
+    ///
+    /// Call this before `remove_component_subtree` so systems can release anything they
+    /// registered for the removed components (e.g. VisualWorld instances).
+    pub fn cleanup_component_tree(
+        &mut self,
+        root: ComponentId,
+        queue: &mut crate::engine::ecs::CommandQueue,
+    ) {
+        let children: Vec<ComponentId> = self.children_of(root).to_vec();
+        for child in children {
+            self.cleanup_component_tree(child, queue);
+        }
+
+        if let Some(node) = self.get_component_record_mut(root) {
+            node.component.cleanup(queue, root);
+        }
+    }
 }
@@ -0,0 +1,254 @@
+//! Prefabs: a component tree saved as its own one-root scene file and instanced into levels.
+//!
+//! A scene node can reference a prefab instead of spelling out its subtree:
+//!
+//! ```json
+//! { "type": "transform", "prefab": "assets/prefabs/lamp.json",
+//!   "overrides": {
+//!     "": { "transform": { "translation": [4, 0, 0] } },
+//!     "1": { "intensity": 2.5 } } }
+//! ```
+//!
+//! Override keys are node paths inside the prefab (`""` = its root, `"1/0"` = first child of
+//! the second child); an overridden field replaces that whole field of the node's data.
+//! Decoding reads the prefab file, so edits to it reach every instance on the next load.
+//! An instance saves the guid of its root only. The components below it get guids derived
+//! from that guid and their node path (UUIDv5), so every load gives them the same guids and
+//! references into an instance from elsewhere in the scene survive a save; references inside
+//! the instance to the prefab's own guids follow the renaming. An instance written without a
+//! guid gets a new one when it is resolved.
+//! `ComponentCodec::encode` writes instances back as prefab + overrides, or in full if their
+//! structure no longer matches the prefab (children added or removed).
+
+use std::collections::{BTreeMap, HashMap};
+
+use uuid::Uuid;
+
+use crate::engine::ecs::codec::{CodecError, ComponentDataNode, Map, Scene, Value};
+
+/// Nesting limit for prefabs referencing prefabs (also catches cycles).
+pub const MAX_PREFAB_DEPTH: usize = 16;
+
+/// Node path inside a prefab -> overridden fields of that node.
+pub type PrefabOverrides = BTreeMap<String, Map<String, Value>>;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PrefabInstance {
+    /// Scene file holding the prefab (JSON, or binary for `*.lcscene`).
+    pub source: String,
+    pub overrides: PrefabOverrides,
+}
+
+/// Loaded prefabs by source path, with nested prefab references already expanded.
+///
+/// Files are read once; call `clear` to pick up edits made while running.
+#[derive(Debug, Default)]
+pub struct PrefabLibrary {
+    prefabs: HashMap<String, ComponentDataNode>,
+}
+
+impl PrefabLibrary {
+    pub fn get(&self, source: &str) -> Option<&ComponentDataNode> {
+        self.prefabs.get(source)
+    }
+
+    /// Register an in-memory prefab under `source` (tests, generated content).
+    pub fn insert(
+        &mut self,
+        source: impl Into<String>,
+        root: &ComponentDataNode,
+    ) -> Result<(), CodecError> {
+        let root = self.resolve_at(root, 1)?;
+        self.prefabs.insert(source.into(), root);
+        Ok(())
+    }
+
+    pub fn clear(&mut self) {
+        self.prefabs.clear();
+    }
+
+    /// `node` with every prefab reference in it replaced by the prefab's (overridden) subtree.
+    ///
+    /// Instance roots keep their `prefab` so the decoder can remember where they came from.
+    pub fn resolve(&mut self, node: &ComponentDataNode) -> Result<ComponentDataNode, CodecError> {
+        self.resolve_at(node, 0)
+    }
+
+    fn resolve_at(
+        &mut self,
+        node: &ComponentDataNode,
+        depth: usize,
+    ) -> Result<ComponentDataNode, CodecError> {
+        let Some(instance) = &node.prefab else {
+            return Ok(ComponentDataNode {
+                type_name: node.type_name.clone(),
+                data: node.data.clone(),
+                children: node
+                    .children
+                    .iter()
+                    .map(|c| self.resolve_at(c, depth))
+                    .collect::<Result<_, _>>()?,
+                prefab: None,
+                guid: node.guid,
+            });
+        };
+        if depth >= MAX_PREFAB_DEPTH {
+            return Err(CodecError::Malformed(format!(
+                "prefab {}: nested more than {MAX_PREFAB_DEPTH} deep",
+                instance.source
+            )));
+        }
+
+        let resolved = self.fetch(&instance.source, depth + 1)?;
+        if !node.type_name.is_empty() && node.type_name != resolved.type_name {
+            return Err(CodecError::Malformed(format!(
+                "prefab {}: instance is '{}' but the prefab root is '{}'",
+                instance.source, node.type_name, resolved.type_name
+            )));
+        }
+        let mut resolved = with_instance_guids(resolved, node.guid.unwrap_or_else(Uuid::new_v4));
+        apply_overrides(&mut resolved, &instance.overrides)
+            .map_err(|e| CodecError::Malformed(format!("prefab {}: {e}", instance.source)))?;
+        resolved.prefab = Some(instance.clone());
+        Ok(resolved)
+    }
+
+    /// Resolved prefab root, loading the file on first use.
+    fn fetch(&mut self, source: &str, depth: usize) -> Result<ComponentDataNode, CodecError> {
+        if let Some(root) = self.prefabs.get(source) {
+            return Ok(root.clone());
+        }
+        let scene = Scene::load(source).map_err(|e| CodecError::Prefab {
+            path: source.to_string(),
+            error: Box::new(e),
+        })?;
+        let [root] = scene.roots.as_slice() else {
+            return Err(CodecError::Malformed(format!(
+                "prefab {source}: expected exactly one root, found {}",
+                scene.roots.len()
+            )));
+        };
+        let root = self.resolve_at(root, depth)?;
+        self.prefabs.insert(source.to_string(), root.clone());
+        Ok(root)
+    }
+}
+
+/// `prefab` as instanced under `guid`: the root takes `guid`, every node below it the guid
+/// derived from `guid` and its node path, and references to the prefab's guids are rewritten
+/// to match.
+pub fn with_instance_guids(mut prefab: ComponentDataNode, guid: Uuid) -> ComponentDataNode {
+    let mut renamed = HashMap::new();
+    if let Some(saved) = prefab.guid {
+        renamed.insert(saved.to_string(), guid.to_string());
+    }
+    prefab.guid = Some(guid);
+    derive_child_guids(&mut prefab, guid, "", &mut renamed);
+    rename_refs(&mut prefab, &renamed);
+    prefab
+}
+
+fn derive_child_guids(
+    node: &mut ComponentDataNode,
+    instance: Uuid,
+    path: &str,
+    renamed: &mut HashMap<String, String>,
+) {
+    for (i, child) in node.children.iter_mut().enumerate() {
+        let child_path = if path.is_empty() {
+            i.to_string()
+        } else {
+            format!("{path}/{i}")
+        };
+        let derived = Uuid::new_v5(&instance, child_path.as_bytes());
+        if let Some(saved) = child.guid {
+            renamed.insert(saved.to_string(), derived.to_string());
+        }
+        child.guid = Some(derived);
+        derive_child_guids(child, instance, &child_path, renamed);
+    }
+}
+
+/// Rewrite `ComponentRef` fields (saved as guid strings) throughout the subtree.
+fn rename_refs(node: &mut ComponentDataNode, renamed: &HashMap<String, String>) {
+    fn rename(value: &mut Value, renamed: &HashMap<String, String>) {
+        match value {
+            Value::String(s) => {
+                if let Some(fresh) = renamed.get(s.as_str()) {
+                    *s = fresh.clone();
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|v| rename(v, renamed)),
+            Value::Object(fields) => fields.values_mut().for_each(|v| rename(v, renamed)),
+            _ => {}
+        }
+    }
+    rename(&mut node.data, renamed);
+    for child in &mut node.children {
+        rename_refs(child, renamed);
+    }
+}
+
+fn apply_overrides(
+    root: &mut ComponentDataNode,
+    overrides: &PrefabOverrides,
+) -> Result<(), String> {
+    for (path, fields) in overrides {
+        let mut node = &mut *root;
+        for step in path.split('/').filter(|s| !s.is_empty()) {
+            node = step
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| node.children.get_mut(i))
+                .ok_or_else(|| format!("no node at override path '{path}'"))?;
+        }
+        if node.data.is_null() {
+            node.data = Value::Object(Map::new());
+        }
+        let Value::Object(data) = &mut node.data else {
+            return Err(format!("node '{path}' has no fields to override"));
+        };
+        for (field, value) in fields {
+            data.insert(field.clone(), value.clone());
+        }
+    }
+    Ok(())
+}
+
+/// Fields of `instance` that differ from `prefab`, or `None` if the two trees no longer have
+/// the same shape (types or child counts differ).
+pub fn diff(prefab: &ComponentDataNode, instance: &ComponentDataNode) -> Option<PrefabOverrides> {
+    let mut overrides = PrefabOverrides::new();
+    diff_at(prefab, instance, String::new(), &mut overrides)?;
+    Some(overrides)
+}
+
+fn diff_at(
+    prefab: &ComponentDataNode,
+    instance: &ComponentDataNode,
+    path: String,
+    out: &mut PrefabOverrides,
+) -> Option<()> {
+    if prefab.type_name != instance.type_name || prefab.children.len() != instance.children.len() {
+        return None;
+    }
+    if let Value::Object(fields) = &instance.data {
+        let changed: Map<String, Value> = fields
+            .iter()
+            .filter(|(k, v)| prefab.data.get(k.as_str()) != Some(v))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        if !changed.is_empty() {
+            out.insert(path.clone(), changed);
+        }
+    }
+    for (i, (p, c)) in prefab.children.iter().zip(&instance.children).enumerate() {
+        let child_path = if path.is_empty() {
+            i.to_string()
+        } else {
+            format!("{path}/{i}")
+        };
+        diff_at(p, c, child_path, out)?;
+    }
+    Some(())
+}
@@ -0,0 +1,170 @@
+//! Component-graph queries, as used by the REPL `find` command.
+//!
+//! A query is a list of space-separated terms, all of which must match:
+//! - `type=<type>`: component type, e.g. `renderable` or `RenderableComponent`.
+//! - `name=<glob>`: stored node name, `*` and `?` wildcards.
+//! - `has=<type>`: has a direct child of that type.
+//! - `<property><op><number>` with `=`, `!=`, `<`, `<=`, `>`, `>=`, on numeric properties
+//!   exposed through `Component::property` (e.g. `x>10`, `intensity<=0.5`).
+//! - `under <id>`: only descendants of that component (ids as printed, e.g. `3v1`).
+
+use crate::engine::ecs::component::schema::normalize_type_name as normalize_type;
+use crate::engine::ecs::{ComponentId, World};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl CompareOp {
+    fn eval(self, a: f32, b: f32) -> bool {
+        match self {
+            CompareOp::Eq => a == b,
+            CompareOp::Ne => a != b,
+            CompareOp::Lt => a < b,
+            CompareOp::Le => a <= b,
+            CompareOp::Gt => a > b,
+            CompareOp::Ge => a >= b,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Predicate {
+    Type(String),
+    Name(String),
+    HasChild(String),
+    Property {
+        name: String,
+        op: CompareOp,
+        value: f32,
+    },
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Query {
+    pub predicates: Vec<Predicate>,
+    pub under: Option<ComponentId>,
+}
+
+impl Query {
+    pub fn parse(terms: &[&str]) -> Result<Self, String> {
+        let mut query = Query::default();
+        let mut terms = terms.iter();
+        while let Some(&term) = terms.next() {
+            if term == "under" {
+                let id = terms.next().ok_or("under: missing component id")?;
+                query.under = Some(id.parse().map_err(|e| format!("under: {e}"))?);
+                continue;
+            }
+            query.predicates.push(Self::parse_predicate(term)?);
+        }
+        Ok(query)
+    }
+
+    fn parse_predicate(term: &str) -> Result<Predicate, String> {
+        if let Some(ty) = term.strip_prefix("type=") {
+            return Ok(Predicate::Type(normalize_type(ty)));
+        }
+        if let Some(glob) = term.strip_prefix("name=") {
+            return Ok(Predicate::Name(glob.to_string()));
+        }
+        if let Some(ty) = term.strip_prefix("has=") {
+            return Ok(Predicate::HasChild(normalize_type(ty)));
+        }
+
+        // Two-character operators first so `<=` isn't read as `<`.
+        const OPS: [(&str, CompareOp); 6] = [
+            ("!=", CompareOp::Ne),
+            ("<=", CompareOp::Le),
+            (">=", CompareOp::Ge),
+            ("=", CompareOp::Eq),
+            ("<", CompareOp::Lt),
+            (">", CompareOp::Gt),
+        ];
+        for (token, op) in OPS {
+            if let Some((name, value)) = term.split_once(token) {
+                let value = value
+                    .parse()
+                    .map_err(|_| format!("'{term}': '{value}' is not a number"))?;
+                return Ok(Predicate::Property {
+                    name: name.to_string(),
+                    op,
+                    value,
+                });
+            }
+        }
+        Err(format!(
+            "'{term}': expected type=, name=, has=, under or a comparison"
+        ))
+    }
+
+    /// Matching components, oldest first.
+    pub fn run(&self, world: &World) -> Vec<ComponentId> {
+        let candidates: Vec<ComponentId> = match self.under {
+            Some(root) => {
+                let mut out = Vec::new();
+                let mut stack = world.children_of(root).to_vec();
+                while let Some(c) = stack.pop() {
+                    out.push(c);
+                    stack.extend_from_slice(world.children_of(c));
+                }
+                world.sort_by_insertion(&mut out);
+                out
+            }
+            None => world.components_in_order(),
+        };
+        candidates
+            .into_iter()
+            .filter(|&cid| self.matches(world, cid))
+            .collect()
+    }
+
+    pub fn matches(&self, world: &World, cid: ComponentId) -> bool {
+        let Some(node) = world.get_component_record(cid) else {
+            return false;
+        };
+        self.predicates.iter().all(|p| match p {
+            Predicate::Type(ty) => normalize_type(node.component.name()) == *ty,
+            Predicate::Name(glob) => glob_match(glob, node.name),
+            Predicate::HasChild(ty) => world.children_of(cid).iter().any(|&c| {
+                world
+                    .get_component_record(c)
+                    .is_some_and(|n| normalize_type(n.component.name()) == *ty)
+            }),
+            Predicate::Property { name, op, value } => node
+                .component
+                .property(name)
+                .is_some_and(|v| op.eval(v, *value)),
+        })
+    }
+}
+
+/// Glob with `*` (any run) and `?` (any one char).
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let t: Vec<char> = text.chars().collect();
+    let (mut pi, mut ti) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while ti < t.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == t[ti]) {
+            pi += 1;
+            ti += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some((pi, ti));
+            pi += 1;
+        } else if let Some((sp, st)) = star {
+            pi = sp + 1;
+            ti = st + 1;
+            star = Some((sp, st + 1));
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|&c| c == '*')
+}
@@ -0,0 +1,105 @@
+//! Component types constructible by name (scene decoding, REPL `spawn`, scripts).
+//!
+//! Every `World` owns a registry pre-filled with the engine's component types. Downstream
+//! crates add their own at startup:
+//!
+//! ```ignore
+//! universe.world.registry_mut().register::<HealthComponent>();
+//! ```
+
+use std::collections::BTreeMap;
+
+use crate::engine::ecs::codec::{CodecError, Map, Value};
+use crate::engine::ecs::component::schema::{ComponentSchema, normalize_type_name};
+use crate::engine::ecs::component::{Component, ComponentType, for_each_builtin_component};
+
+type Constructor = fn(&Value) -> Result<Box<dyn Component>, CodecError>;
+
+#[derive(Debug, Clone, Copy)]
+pub struct RegisteredType {
+    pub type_name: &'static str,
+    pub schema: Option<&'static ComponentSchema>,
+    constructor: Constructor,
+}
+
+#[derive(Debug, Clone)]
+pub struct ComponentRegistry {
+    types: BTreeMap<&'static str, RegisteredType>,
+}
+
+impl Default for ComponentRegistry {
+    fn default() -> Self {
+        Self::with_builtin()
+    }
+}
+
+impl ComponentRegistry {
+    /// No types at all.
+    pub fn empty() -> Self {
+        Self {
+            types: BTreeMap::new(),
+        }
+    }
+
+    /// The engine's component types.
+    pub fn with_builtin() -> Self {
+        let mut r = Self::empty();
+        macro_rules! register {
+            ($($ty:path),* $(,)?) => {
+                $(r.register::<$ty>();)*
+            };
+        }
+        for_each_builtin_component!(register);
+        r
+    }
+
+    /// Add (or replace) `T` under `T::TYPE_NAME`.
+    pub fn register<T: ComponentType + Component>(&mut self) {
+        fn construct<T: ComponentType + Component>(
+            data: &Value,
+        ) -> Result<Box<dyn Component>, CodecError> {
+            Ok(Box::new(T::decode(data)?))
+        }
+        // Schemas are per type; a default-decoded instance is enough to ask for it.
+        let schema = T::decode(&Value::Object(Map::new()))
+            .ok()
+            .and_then(|c| c.schema());
+        self.types.insert(
+            T::TYPE_NAME,
+            RegisteredType {
+                type_name: T::TYPE_NAME,
+                schema,
+                constructor: construct::<T>,
+            },
+        );
+    }
+
+    pub fn get(&self, type_name: &str) -> Option<&RegisteredType> {
+        self.types.get(type_name)
+    }
+
+    /// Like `get`, but also accepts `RenderableComponent`, `ScreenAnchor`, ... spellings.
+    pub fn find(&self, type_name: &str) -> Option<&RegisteredType> {
+        self.get(type_name).or_else(|| {
+            let wanted = normalize_type_name(type_name);
+            self.types
+                .values()
+                .find(|t| normalize_type_name(t.type_name) == wanted)
+        })
+    }
+
+    /// Registered types, sorted by name.
+    pub fn types(&self) -> impl Iterator<Item = &RegisteredType> {
+        self.types.values()
+    }
+
+    /// Construct a component from its type name and encoded fields. `null` data gives the
+    /// type's defaults.
+    pub fn create(&self, type_name: &str, data: &Value) -> Result<Box<dyn Component>, CodecError> {
+        let ty = self
+            .get(type_name)
+            .ok_or_else(|| CodecError::UnknownType(type_name.to_string()))?;
+        let empty = Value::Object(Map::new());
+        (ty.constructor)(if data.is_null() { &empty } else { data })
+    }
+}
@@ -0,0 +1,35 @@
+#[cfg(test)]
+mod tests {
+    use crate::engine::ecs::World;
+
+    #[derive(Debug, PartialEq)]
+    struct Score(u32);
+
+    #[derive(Debug, Default, PartialEq)]
+    struct Selection(Vec<u32>);
+
+    #[test]
+    fn resources_are_stored_once_per_type() {
+        let mut w = World::default();
+        assert_eq!(w.resource::<Score>(), None);
+        assert_eq!(w.insert_resource(Score(1)), None);
+        w.resource_mut::<Score>().unwrap().0 += 10;
+        assert_eq!(w.insert_resource(Score(5)), Some(Score(11)));
+        assert_eq!(w.resource::<Score>(), Some(&Score(5)));
+
+        w.resources_mut()
+            .get_or_insert_with(Selection::default)
+            .0
+            .push(3);
+        w.resources_mut()
+            .get_or_insert_with(Selection::default)
+            .0
+            .push(4);
+        assert_eq!(w.resource::<Selection>(), Some(&Selection(vec![3, 4])));
+        assert_eq!(w.resources().len(), 2);
+
+        assert_eq!(w.remove_resource::<Score>(), Some(Score(5)));
+        assert!(!w.resources().contains::<Score>());
+        assert!(w.resources().contains::<Selection>());
+    }
+}
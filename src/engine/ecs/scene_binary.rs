@@ -0,0 +1,352 @@
+//! Compact binary encoding of `Scene`s.
+//!
+//! Component data is schemaless (`codec::Value`), which bincode/postcard can't read back
+//! without a self-describing format, so this is a small tagged encoding instead:
+//!
+//! ```text
+//! "LCSB" version:u8
+//! strings: n, n * (len, utf8)      type names and object keys, each stored once
+//! roots:   n, n * node
+//! node:    type:string-index, guid, 0, value, children: n, n * node
+//!        | type:string-index, guid, 1, prefab:string-index, overrides: n, n * override
+//! guid:    0 | 1, 16 bytes
+//! override: path:(len, utf8), fields: n, n * (key:string-index, value)
+//! ```
+//!
+//! Version 1 files (no prefab byte) and version 2 files (no guids) are still read.
+//!
+//! Counts, lengths and integers are LEB128 varints. Floats that survive an `f32` round trip
+//! (all component fields) take 4 bytes.
+
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use crate::engine::ecs::codec::{CodecError, ComponentDataNode, Map, Scene, Value};
+use crate::engine::ecs::prefab::{PrefabInstance, PrefabOverrides};
+
+const MAGIC: &[u8; 4] = b"LCSB";
+const VERSION: u8 = 3;
+
+/// Deeper values are rejected instead of overflowing the stack on hostile input.
+const MAX_DEPTH: usize = 128;
+
+const TAG_NULL: u8 = 0;
+const TAG_FALSE: u8 = 1;
+const TAG_TRUE: u8 = 2;
+const TAG_UINT: u8 = 3;
+const TAG_INT: u8 = 4;
+const TAG_F32: u8 = 5;
+const TAG_F64: u8 = 6;
+const TAG_STRING: u8 = 7;
+const TAG_ARRAY: u8 = 8;
+const TAG_OBJECT: u8 = 9;
+
+impl Scene {
+    pub fn to_binary(&self) -> Vec<u8> {
+        let mut strings = StringTable::default();
+        let mut body = Vec::new();
+        write_varint(&mut body, self.roots.len() as u64);
+        for root in &self.roots {
+            write_node(&mut body, &mut strings, root);
+        }
+
+        let mut out = Vec::with_capacity(body.len() + 64);
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        write_varint(&mut out, strings.list.len() as u64);
+        for s in &strings.list {
+            write_str(&mut out, s);
+        }
+        out.extend_from_slice(&body);
+        out
+    }
+
+    pub fn from_binary(bytes: &[u8]) -> Result<Self, CodecError> {
+        let mut r = Reader {
+            bytes,
+            pos: 0,
+            version: VERSION,
+        };
+        if r.take(MAGIC.len())? != MAGIC {
+            return Err(malformed("not a binary scene"));
+        }
+        let version = r.byte()?;
+        if version == 0 || version > VERSION {
+            return Err(CodecError::Malformed(format!(
+                "unsupported binary scene version {version}"
+            )));
+        }
+        r.version = version;
+        let count = r.count()?;
+        let mut strings = Vec::with_capacity(count);
+        for _ in 0..count {
+            strings.push(r.string()?);
+        }
+        let count = r.count()?;
+        let mut roots = Vec::with_capacity(count);
+        for _ in 0..count {
+            roots.push(r.node(&strings, 0)?);
+        }
+        if r.pos != bytes.len() {
+            return Err(malformed("trailing bytes"));
+        }
+        Ok(Self { roots })
+    }
+}
+
+#[derive(Default)]
+struct StringTable<'a> {
+    list: Vec<&'a str>,
+    index: HashMap<&'a str, u64>,
+}
+
+impl<'a> StringTable<'a> {
+    fn intern(&mut self, s: &'a str) -> u64 {
+        *self.index.entry(s).or_insert_with(|| {
+            self.list.push(s);
+            self.list.len() as u64 - 1
+        })
+    }
+}
+
+fn write_node<'a>(out: &mut Vec<u8>, strings: &mut StringTable<'a>, node: &'a ComponentDataNode) {
+    write_varint(out, strings.intern(&node.type_name));
+    match node.guid {
+        Some(guid) => {
+            out.push(1);
+            out.extend_from_slice(guid.as_bytes());
+        }
+        None => out.push(0),
+    }
+    if let Some(instance) = &node.prefab {
+        out.push(1);
+        write_varint(out, strings.intern(&instance.source));
+        write_varint(out, instance.overrides.len() as u64);
+        for (path, fields) in &instance.overrides {
+            write_str(out, path);
+            write_varint(out, fields.len() as u64);
+            for (key, value) in fields {
+                write_varint(out, strings.intern(key));
+                write_value(out, strings, value);
+            }
+        }
+        return;
+    }
+    out.push(0);
+    write_value(out, strings, &node.data);
+    write_varint(out, node.children.len() as u64);
+    for child in &node.children {
+        write_node(out, strings, child);
+    }
+}
+
+fn write_value<'a>(out: &mut Vec<u8>, strings: &mut StringTable<'a>, value: &'a Value) {
+    match value {
+        Value::Null => out.push(TAG_NULL),
+        Value::Bool(false) => out.push(TAG_FALSE),
+        Value::Bool(true) => out.push(TAG_TRUE),
+        Value::Number(n) => {
+            if let Some(u) = n.as_u64() {
+                out.push(TAG_UINT);
+                write_varint(out, u);
+            } else if let Some(i) = n.as_i64() {
+                out.push(TAG_INT);
+                write_varint(out, ((i << 1) ^ (i >> 63)) as u64);
+            } else {
+                let f = n.as_f64().unwrap_or(0.0);
+                if f as f32 as f64 == f {
+                    out.push(TAG_F32);
+                    out.extend_from_slice(&(f as f32).to_le_bytes());
+                } else {
+                    out.push(TAG_F64);
+                    out.extend_from_slice(&f.to_le_bytes());
+                }
+            }
+        }
+        Value::String(s) => {
+            out.push(TAG_STRING);
+            write_str(out, s);
+        }
+        Value::Array(items) => {
+            out.push(TAG_ARRAY);
+            write_varint(out, items.len() as u64);
+            for item in items {
+                write_value(out, strings, item);
+            }
+        }
+        Value::Object(map) => {
+            out.push(TAG_OBJECT);
+            write_varint(out, map.len() as u64);
+            for (key, item) in map {
+                write_varint(out, strings.intern(key));
+                write_value(out, strings, item);
+            }
+        }
+    }
+}
+
+fn write_varint(out: &mut Vec<u8>, mut v: u64) {
+    while v >= 0x80 {
+        out.push(v as u8 | 0x80);
+        v >>= 7;
+    }
+    out.push(v as u8);
+}
+
+fn write_str(out: &mut Vec<u8>, s: &str) {
+    write_varint(out, s.len() as u64);
+    out.extend_from_slice(s.as_bytes());
+}
+
+fn malformed(msg: &str) -> CodecError {
+    CodecError::Malformed(format!("binary scene: {msg}"))
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    version: u8,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], CodecError> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| malformed("unexpected end of data"))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn byte(&mut self) -> Result<u8, CodecError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn varint(&mut self) -> Result<u64, CodecError> {
+        let mut v = 0u64;
+        for shift in (0..64).step_by(7) {
+            let b = self.byte()?;
+            v |= u64::from(b & 0x7f) << shift;
+            if b & 0x80 == 0 {
+                return Ok(v);
+            }
+        }
+        Err(malformed("varint too long"))
+    }
+
+    /// An element count; bounded by the remaining bytes so a corrupt count can't allocate
+    /// gigabytes up front.
+    fn count(&mut self) -> Result<usize, CodecError> {
+        let n = self.varint()?;
+        if n > (self.bytes.len() - self.pos) as u64 {
+            return Err(malformed("count exceeds data"));
+        }
+        Ok(n as usize)
+    }
+
+    fn string(&mut self) -> Result<String, CodecError> {
+        let len = self.count()?;
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| malformed("invalid utf-8"))
+    }
+
+    fn interned<'s>(&mut self, strings: &'s [String]) -> Result<&'s str, CodecError> {
+        let i = self.varint()?;
+        strings
+            .get(i as usize)
+            .map(String::as_str)
+            .ok_or_else(|| malformed("string index out of range"))
+    }
+
+    fn node(&mut self, strings: &[String], depth: usize) -> Result<ComponentDataNode, CodecError> {
+        if depth > MAX_DEPTH {
+            return Err(malformed("nesting too deep"));
+        }
+        let type_name = self.interned(strings)?.to_string();
+        let guid = if self.version >= 3 && self.byte()? == 1 {
+            Some(Uuid::from_slice(self.take(16)?).map_err(|_| malformed("bad guid"))?)
+        } else {
+            None
+        };
+        if self.version >= 2 && self.byte()? == 1 {
+            let source = self.interned(strings)?.to_string();
+            let mut overrides = PrefabOverrides::new();
+            for _ in 0..self.count()? {
+                let path = self.string()?;
+                let mut fields = Map::new();
+                for _ in 0..self.count()? {
+                    let key = self.interned(strings)?.to_string();
+                    fields.insert(key, self.value(strings, depth + 1)?);
+                }
+                overrides.insert(path, fields);
+            }
+            return Ok(ComponentDataNode {
+                guid,
+                ..ComponentDataNode::prefab_instance(
+                    type_name,
+                    PrefabInstance { source, overrides },
+                )
+            });
+        }
+        let data = self.value(strings, depth)?;
+        let count = self.count()?;
+        let mut children = Vec::with_capacity(count);
+        for _ in 0..count {
+            children.push(self.node(strings, depth + 1)?);
+        }
+        Ok(ComponentDataNode {
+            type_name,
+            data,
+            children,
+            prefab: None,
+            guid,
+        })
+    }
+
+    fn value(&mut self, strings: &[String], depth: usize) -> Result<Value, CodecError> {
+        if depth > MAX_DEPTH {
+            return Err(malformed("nesting too deep"));
+        }
+        Ok(match self.byte()? {
+            TAG_NULL => Value::Null,
+            TAG_FALSE => Value::Bool(false),
+            TAG_TRUE => Value::Bool(true),
+            TAG_UINT => Value::from(self.varint()?),
+            TAG_INT => {
+                let z = self.varint()?;
+                Value::from((z >> 1) as i64 ^ -((z & 1) as i64))
+            }
+            TAG_F32 => {
+                let b = self.take(4)?;
+                Value::from(f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64)
+            }
+            TAG_F64 => {
+                let mut b = [0u8; 8];
+                b.copy_from_slice(self.take(8)?);
+                Value::from(f64::from_le_bytes(b))
+            }
+            TAG_STRING => Value::String(self.string()?),
+            TAG_ARRAY => {
+                let count = self.count()?;
+                let mut items = Vec::with_capacity(count);
+                for _ in 0..count {
+                    items.push(self.value(strings, depth + 1)?);
+                }
+                Value::Array(items)
+            }
+            TAG_OBJECT => {
+                let count = self.count()?;
+                let mut map = Map::new();
+                for _ in 0..count {
+                    let key = self.interned(strings)?.to_string();
+                    map.insert(key, self.value(strings, depth + 1)?);
+                }
+                Value::Object(map)
+            }
+            tag => return Err(malformed(&format!("unknown value tag {tag}"))),
+        })
+    }
+}
@@ -0,0 +1,83 @@
+//! Generated worlds of many instances, for benchmarks (`benches/ecs.rs`) and profiling.
+//!
+//! `SyntheticWorld::new(n).spawn(&mut ctx)` lays `n` cubes out on a square grid in the XZ
+//! plane, each at the end of a chain of `depth` transforms, cycling through `materials`.
+//! Spawning only queues registration like any other spawn: run `process_commands` and then
+//! `Universe::prepare_render_with` (a `NullUploader` will do) to get the instances into
+//! `VisualWorld`.
+
+use crate::engine::ecs::component::{RenderableComponent, TransformComponent};
+use crate::engine::ecs::{ComponentId, WorldContext};
+use crate::engine::graphics::MeshFactory;
+use crate::engine::graphics::primitives::{MaterialHandle, Renderable};
+
+#[derive(Debug, Clone)]
+pub struct SyntheticWorld {
+    /// Renderable instances to spawn.
+    pub count: usize,
+    /// Transforms from each root down to its renderable (at least 1).
+    pub depth: usize,
+    /// Grid cell size in world units.
+    pub spacing: f32,
+    /// Assigned round-robin, so batching sees `materials.len()` batches.
+    pub materials: Vec<MaterialHandle>,
+}
+
+impl SyntheticWorld {
+    pub fn new(count: usize) -> Self {
+        Self {
+            count,
+            depth: 1,
+            spacing: 2.0,
+            materials: vec![MaterialHandle::TOON_MESH, MaterialHandle::UNLIT_MESH],
+        }
+    }
+
+    pub fn with_depth(mut self, depth: usize) -> Self {
+        self.depth = depth.max(1);
+        self
+    }
+
+    pub fn with_spacing(mut self, spacing: f32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Ignored if empty.
+    pub fn with_materials(mut self, materials: &[MaterialHandle]) -> Self {
+        if !materials.is_empty() {
+            self.materials = materials.to_vec();
+        }
+        self
+    }
+
+    /// Spawn the instances; returns the root transform of each, in grid order.
+    pub fn spawn(&self, ctx: &mut WorldContext) -> Vec<ComponentId> {
+        let mesh = ctx.render_assets.register_mesh(MeshFactory::cube());
+        let side = (self.count as f32).sqrt().ceil().max(1.0) as usize;
+        let mut roots = Vec::with_capacity(self.count);
+        for i in 0..self.count {
+            let x = (i % side) as f32 * self.spacing;
+            let z = (i / side) as f32 * self.spacing;
+            let root = ctx
+                .world
+                .add_component(TransformComponent::new().with_position(x, 0.0, z));
+            let mut leaf = root;
+            for _ in 1..self.depth {
+                let child = ctx
+                    .world
+                    .add_component(TransformComponent::new().with_position(0.0, 0.1, 0.0));
+                let _ = ctx.world.add_child(leaf, child);
+                leaf = child;
+            }
+            let material = self.materials[i % self.materials.len()];
+            let renderable = ctx
+                .world
+                .add_component(RenderableComponent::new(Renderable::new(mesh, material)));
+            let _ = ctx.world.add_child(leaf, renderable);
+            ctx.world.init_component_tree(root, ctx.queue);
+            roots.push(root);
+        }
+        roots
+    }
+}
@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod tests {
+    use crate::engine::ecs::component::TransformComponent;
+    use crate::engine::ecs::synthetic::SyntheticWorld;
+    use crate::engine::graphics::NullUploader;
+    use crate::engine::{RendererBackend, UniverseBuilder};
+
+    #[test]
+    fn synthetic_world_reaches_the_draw_cache_without_a_gpu() {
+        let mut u = UniverseBuilder::new()
+            .renderer(RendererBackend::None)
+            .repl(false)
+            .build();
+        let roots = SyntheticWorld::new(10)
+            .with_depth(3)
+            .spawn(&mut u.context());
+        assert_eq!(roots.len(), 10);
+        // 3 transforms and a renderable each.
+        assert_eq!(u.world.component_count(), 40);
+
+        u.systems
+            .process_commands(&mut u.world, &mut u.visuals, &mut u.command_queue);
+        u.prepare_render_with(&mut NullUploader::default());
+        let stats = u.visuals.prepare_draw_cache();
+        assert_eq!((stats.instances, stats.batches), (10, 2));
+
+        // Moving a root moves the cube at the end of its chain.
+        let root = roots[0];
+        let mut renderable = root;
+        while let Some(&child) = u.world.children_of(renderable).first() {
+            renderable = child;
+        }
+        let handle = u.visuals.instance_for_component(renderable).unwrap();
+        u.world
+            .get_component_by_id_as_mut::<TransformComponent>(root)
+            .unwrap()
+            .set_position(&mut u.command_queue, 0.0, 5.0, 0.0);
+        u.systems
+            .process_commands(&mut u.world, &mut u.visuals, &mut u.command_queue);
+        let y = u.visuals.instance(handle).unwrap().transform.model[3][1];
+        assert!((y - 5.2).abs() < 1e-5, "y = {y}");
+    }
+}
@@ -0,0 +1,126 @@
+use crate::engine::assets::{AssetServer, DecodedImage};
+use crate::engine::ecs::component::{BackgroundComponent, BackgroundMode};
+use crate::engine::ecs::{ComponentId, World};
+use crate::engine::graphics::{TextureHandle, TextureUploader, VisualBackground, VisualWorld};
+use std::collections::HashMap;
+
+/// Mirrors the active `BackgroundComponent` into `VisualWorld`.
+///
+/// Solid and gradient backgrounds apply on registration; skyboxes wait for `flush_pending`,
+/// which has the uploader. Cubemaps stay uploaded per URI, so switching back and forth
+/// between skyboxes doesn't reload them.
+#[derive(Debug, Default)]
+pub struct BackgroundSystem {
+    /// Last registered background component.
+    active: Option<ComponentId>,
+    /// `active` is a skybox whose cubemap isn't in `VisualWorld` yet.
+    pending_skybox: bool,
+    cubemaps: HashMap<String, TextureHandle>,
+}
+
+impl BackgroundSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn active(&self) -> Option<ComponentId> {
+        self.active
+    }
+
+    pub fn register_background(
+        &mut self,
+        world: &mut World,
+        visuals: &mut VisualWorld,
+        component: ComponentId,
+    ) {
+        let Some(background) = world.get_component_by_id_as::<BackgroundComponent>(component)
+        else {
+            return;
+        };
+        self.active = Some(component);
+        self.pending_skybox = false;
+        match background.mode {
+            BackgroundMode::Solid => {
+                visuals.set_background(VisualBackground::Solid(background.color.to_array()))
+            }
+            BackgroundMode::GradientXY => visuals.set_background(VisualBackground::GradientXY {
+                start: background.color.to_array(),
+                end: background.color_end.to_array(),
+                direction: background.direction,
+            }),
+            BackgroundMode::Skybox => match self.cubemaps.get(&background.skybox) {
+                Some(&cubemap) => visuals.set_background(VisualBackground::Skybox { cubemap }),
+                None => {
+                    // Solid color until the cubemap is uploaded.
+                    visuals.set_background(VisualBackground::Solid(background.color.to_array()));
+                    self.pending_skybox = true;
+                }
+            },
+        }
+    }
+
+    pub fn remove_background(&mut self, visuals: &mut VisualWorld, component: ComponentId) {
+        if self.active == Some(component) {
+            self.active = None;
+            self.pending_skybox = false;
+            visuals.set_background(VisualBackground::default());
+        }
+    }
+
+    /// Load and upload the active skybox, if it is waiting for one.
+    pub fn flush_pending(
+        &mut self,
+        world: &World,
+        visuals: &mut VisualWorld,
+        assets: &mut AssetServer,
+        uploader: &mut dyn TextureUploader,
+    ) {
+        if !std::mem::take(&mut self.pending_skybox) {
+            return;
+        }
+        let Some(background) = self
+            .active
+            .and_then(|cid| world.get_component_by_id_as::<BackgroundComponent>(cid))
+        else {
+            return;
+        };
+        let uri = background.skybox.as_str();
+        let uploaded = assets
+            .load_image(uri)
+            .and_then(|image| cubemap_faces(&image))
+            .and_then(|(faces, size)| {
+                uploader
+                    .upload_cubemap_rgba8(&faces, size)
+                    .map_err(|e| e.to_string())
+            });
+        match uploaded {
+            Ok(cubemap) => {
+                self.cubemaps.insert(uri.to_string(), cubemap);
+                visuals.set_background(VisualBackground::Skybox { cubemap });
+                assets.release_image(uri);
+            }
+            Err(e) => crate::log_warn!("skybox {uri}: {e}"),
+        }
+    }
+}
+
+/// Split a horizontal strip of six square faces into face-after-face pixel order.
+pub fn cubemap_faces(strip: &DecodedImage) -> Result<(Vec<u8>, u32), String> {
+    let size = strip.height;
+    if size == 0 || strip.width != size * 6 {
+        return Err(format!(
+            "expected a 6:1 strip of square faces, got {}x{}",
+            strip.width, strip.height
+        ));
+    }
+    let face_row = size as usize * 4;
+    let strip_row = face_row * 6;
+    let mut faces = Vec::with_capacity(strip.rgba.len());
+    for face in 0..6 {
+        for row in 0..size as usize {
+            let start = row * strip_row + face * face_row;
+            faces.extend_from_slice(&strip.rgba[start..start + face_row]);
+        }
+    }
+    Ok((faces, size))
+}
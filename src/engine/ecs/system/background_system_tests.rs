@@ -0,0 +1,36 @@
+#[cfg(test)]
+mod tests {
+    use crate::engine::assets::DecodedImage;
+    use crate::engine::ecs::system::background_system::cubemap_faces;
+
+    #[test]
+    fn splits_strip_into_faces() {
+        // 2x2 faces; every pixel's red channel is its face index.
+        let (size, width) = (2u32, 12u32);
+        let mut rgba = Vec::new();
+        for _row in 0..size {
+            for x in 0..width {
+                rgba.extend_from_slice(&[(x / size) as u8, 0, 0, 255]);
+            }
+        }
+        let strip = DecodedImage {
+            rgba,
+            width,
+            height: size,
+        };
+
+        let (faces, face_size) = cubemap_faces(&strip).unwrap();
+        assert_eq!(face_size, 2);
+        let face_bytes = (size * size * 4) as usize;
+        for (i, face) in faces.chunks(face_bytes).enumerate() {
+            assert!(face.chunks(4).all(|px| px[0] == i as u8), "face {i}");
+        }
+
+        let square = DecodedImage {
+            rgba: vec![0; 16],
+            width: 2,
+            height: 2,
+        };
+        assert!(cubemap_faces(&square).is_err());
+    }
+}
@@ -1,7 +1,11 @@
 use crate::engine::ecs::ComponentId;
 use crate::engine::ecs::World;
+use crate::engine::ecs::component::CameraProjection;
 use crate::engine::ecs::system::System;
+use crate::engine::ecs::system::TransformSystem;
 use crate::engine::graphics::VisualWorld;
+use crate::utils::curve::Curve;
+use glam::{EulerRot, Mat4, Vec3, Vec4};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct CameraHandle(pub u32);
@@ -47,30 +51,128 @@ impl Camera3D {
         z_near: f32,
         z_far: f32,
     ) -> [[f32; 4]; 4] {
-        // Based on the standard RH, zero-to-one depth projection.
         // Maps camera forward -Z.
-        let f = 1.0 / (0.5 * fov_y_radians).tan();
-        let nf = 1.0 / (z_near - z_far);
-
-        // Column-major:
-        // [ f/aspect, 0,  0,                      0 ]
-        // [ 0,        f,  0,                      0 ]
-        // [ 0,        0,  z_far*nf,               -1 ]
-        // [ 0,        0,  z_near*z_far*nf,         0 ]
-        [
-            [f / aspect, 0.0, 0.0, 0.0],
-            [0.0, f, 0.0, 0.0],
-            [0.0, 0.0, z_far * nf, -1.0],
-            [0.0, 0.0, (z_near * z_far) * nf, 0.0],
-        ]
+        Mat4::perspective_rh(fov_y_radians, aspect, z_near, z_far).to_cols_array_2d()
+    }
+
+    /// Right-handed orthographic projection `2 * size` tall and `2 * size * aspect` wide,
+    /// with the same conventions as `perspective_rh_zo`.
+    pub fn orthographic_rh_zo(size: f32, aspect: f32, z_near: f32, z_far: f32) -> [[f32; 4]; 4] {
+        let (w, h) = (size * aspect, size);
+        Mat4::orthographic_rh(-w, w, -h, h, z_near, z_far).to_cols_array_2d()
+    }
+}
+
+/// Per-camera effect settings, stored on `Camera2DComponent` / `Camera3DComponent`.
+///
+/// All effects are advanced by `CameraSystem::tick` while the camera is active:
+/// - `smoothing`: angular frequency (rad/s) of a critically damped spring that pulls the camera
+///   toward its Transform position. `0` disables smoothing (camera snaps to the Transform).
+///   `smoothing_curve` optionally scales it by the camera's distance to that position, e.g.
+///   rising from 0.5 at 0 to 3 at 5 world units lets the camera drift on small moves and catch
+///   up quickly on large ones.
+/// - `trauma`: 0..1 shake amount; shake strength scales with `trauma^2` and trauma decays
+///   linearly by `trauma_decay` per second.
+/// - `fov_kick`: extra vertical FOV (radians) added to 3D cameras, decaying exponentially at
+///   `fov_kick_decay` per second. Ignored by 2D cameras.
+#[derive(Debug, Clone)]
+pub struct CameraEffects {
+    pub smoothing: f32,
+    /// Multiplier of `smoothing` by distance to the target (world units).
+    pub smoothing_curve: Option<Curve>,
+
+    pub trauma: f32,
+    pub trauma_decay: f32,
+    /// Max positional offset (world units) at full trauma.
+    pub shake_offset: f32,
+    /// Max roll (radians) at full trauma.
+    pub shake_roll: f32,
+    /// How fast the shake pattern changes (Hz).
+    pub shake_frequency: f32,
+
+    pub fov_kick: f32,
+    pub fov_kick_decay: f32,
+}
+
+impl Default for CameraEffects {
+    fn default() -> Self {
+        Self {
+            smoothing: 0.0,
+            smoothing_curve: None,
+            trauma: 0.0,
+            trauma_decay: 1.0,
+            shake_offset: 0.1,
+            shake_roll: 0.05,
+            shake_frequency: 15.0,
+            fov_kick: 0.0,
+            fov_kick_decay: 8.0,
+        }
+    }
+}
+
+impl CameraEffects {
+    pub fn with_smoothing(mut self, omega: f32) -> Self {
+        self.smoothing = omega.max(0.0);
+        self
+    }
+
+    pub fn with_smoothing_curve(mut self, curve: Curve) -> Self {
+        self.smoothing_curve = Some(curve);
+        self
+    }
+
+    /// Add shake trauma (clamped to 1).
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+
+    /// Widen the FOV by `radians`; it eases back to the base FOV over time.
+    pub fn kick_fov(&mut self, radians: f32) {
+        self.fov_kick += radians;
     }
 }
 
+/// Runtime state for `CameraEffects`, owned by `CameraSystem`.
+#[derive(Debug, Clone, Copy, Default)]
+struct CameraEffectState {
+    initialized: bool,
+    smoothing: bool,
+    pos: [f32; 3],
+    vel: [f32; 3],
+    time: f32,
+    shake: [f32; 3],
+    shake_roll: f32,
+    fov_kick: f32,
+}
+
+/// One step of a critically damped spring (implicit integration, stable for any dt).
+fn spring_step(x: &mut f32, v: &mut f32, target: f32, omega: f32, dt: f32) {
+    let f = 1.0 + 2.0 * dt * omega;
+    let oo = omega * omega;
+    let hoo = dt * oo;
+    let hhoo = dt * hoo;
+    let det_inv = 1.0 / (f + hhoo);
+    let x_new = (f * *x + dt * *v + hhoo * target) * det_inv;
+    let v_new = (*v + hoo * (target - *x)) * det_inv;
+    *x = x_new;
+    *v = v_new;
+}
+
+/// Cheap smooth pseudo-noise in roughly [-1, 1]; `seed` decorrelates axes.
+fn shake_noise(t: f32, frequency: f32, seed: f32) -> f32 {
+    let w = std::f32::consts::TAU * frequency;
+    0.6 * (t * w + seed * 1.7).sin() + 0.4 * (t * w * 2.3 + seed * 3.1).sin()
+}
+
 #[derive(Debug, Default)]
 pub struct CameraSystem {
     next_handle: u32,
     cameras: Vec<(CameraHandle, AnyCamera)>,
     camera2d_components: std::collections::HashMap<CameraHandle, ComponentId>,
+    camera3d_components: std::collections::HashMap<CameraHandle, ComponentId>,
+    effect_state: std::collections::HashMap<CameraHandle, CameraEffectState>,
+    /// Per-camera layer masks, mirrored into VisualWorld when the camera becomes active.
+    visibility_layers: std::collections::HashMap<CameraHandle, u32>,
     pub active_camera: Option<CameraHandle>,
 }
 
@@ -84,23 +186,39 @@ impl CameraSystem {
     /// The newest registered camera becomes active.
     pub fn register_camera(
         &mut self,
-        _world: &mut World,
+        world: &mut World,
         visuals: &mut VisualWorld,
-        _component: ComponentId,
+        component: ComponentId,
     ) -> CameraHandle {
-        // NOTE: Debug step: force BOTH view and projection to identity to fully isolate
-        // whether the camera path (push constants, shader bindings, etc.) is the cause.
-        // (So we also intentionally ignore any camera transform for now.)
         let cam = Camera3D::identity();
 
         let h = CameraHandle(self.next_handle);
         self.next_handle = self.next_handle.wrapping_add(1);
 
         self.cameras.push((h, AnyCamera::Camera3D(cam)));
+        self.camera3d_components.insert(h, component);
+
+        let layers = world
+            .get_component_by_id_as::<crate::engine::ecs::component::Camera3DComponent>(component)
+            .map(|c| c.visibility_layers)
+            .unwrap_or(crate::engine::graphics::ALL_VISIBILITY_LAYERS);
+        self.visibility_layers.insert(h, layers);
 
         // Newest becomes active.
         self.active_camera = Some(h);
         visuals.set_camera(cam.view, cam.proj);
+        visuals.set_camera_visibility_layers(layers);
+
+        // A perspective or orthographic camera under a Transform gets a real view/projection;
+        // otherwise it keeps the identity set above.
+        if let Some(camera_comp) = world
+            .get_component_by_id_as_mut::<crate::engine::ecs::component::Camera3DComponent>(
+                component,
+            )
+        {
+            camera_comp.handle = Some(h);
+        }
+        self.update_camera_3d(world, visuals, component);
 
         h
     }
@@ -112,6 +230,12 @@ impl CameraSystem {
 
         if let Some((_, cam)) = self.cameras.iter().find(|(ch, _)| *ch == h) {
             self.active_camera = Some(h);
+            visuals.set_camera_visibility_layers(
+                self.visibility_layers
+                    .get(&h)
+                    .copied()
+                    .unwrap_or(crate::engine::graphics::ALL_VISIBILITY_LAYERS),
+            );
             match *cam {
                 AnyCamera::Camera3D(cam3d) => {
                     visuals.set_camera(cam3d.view, cam3d.proj);
@@ -150,16 +274,27 @@ impl CameraSystem {
                 };
 
                 // Build a 2D view matrix (world -> camera) from the camera's TRS.
-                // We treat the camera component's parent Transform as the camera pose.
-                let tx = transform_comp.transform.translation[0];
-                let ty = transform_comp.transform.translation[1];
-                let sx = transform_comp.transform.scale[0];
-                let sy = transform_comp.transform.scale[1];
-
-                // Extract Z-rotation (roll) from quaternion (xyzw), assuming it's a 2D camera.
-                let qz = transform_comp.transform.rotation[2];
-                let qw = transform_comp.transform.rotation[3];
-                let theta = 2.0 * qz.atan2(qw);
+                // We treat the camera component's parent Transform as the camera pose, placed
+                // at its world position (what smoothing eases toward in `tick`).
+                let t = &transform_comp.transform;
+                let [mut tx, mut ty, _] =
+                    TransformSystem::world_position(world, camera2d_component_id)
+                        .unwrap_or(t.translation.to_array());
+                let (sx, sy) = (t.scale.x, t.scale.y);
+
+                // Z-rotation (roll) only; a 2D camera ignores pitch/yaw.
+                let mut theta = t.rotation.to_euler(EulerRot::ZYX).0;
+
+                // Apply smoothing/shake computed in `tick`.
+                if let Some(fx) = self.effect_state.get(&handle) {
+                    if fx.smoothing {
+                        tx = fx.pos[0];
+                        ty = fx.pos[1];
+                    }
+                    tx += fx.shake[0];
+                    ty += fx.shake[1];
+                    theta += fx.shake_roll;
+                }
                 let (s, c) = theta.sin_cos();
 
                 let inv_sx = if sx.abs() > 1e-8 { 1.0 / sx } else { 1.0 };
@@ -184,11 +319,324 @@ impl CameraSystem {
         }
     }
 
+    /// Rebuild the active 3D camera's view/projection from its ancestor transforms.
+    ///
+    /// The view is the inverse of the camera's world model (with smoothing/shake applied), and the
+    /// projection uses the component's FOV (plus any FOV kick) or orthographic size and the
+    /// VisualWorld viewport aspect. FOV kick has no effect on orthographic cameras, and
+    /// `CameraProjection::Identity` cameras keep identity matrices.
+    /// No-op for inactive cameras or cameras without a Transform ancestor.
+    pub fn update_camera_3d(
+        &mut self,
+        world: &World,
+        visuals: &mut VisualWorld,
+        camera3d_component_id: ComponentId,
+    ) {
+        let Some(camera_comp) = world
+            .get_component_by_id_as::<crate::engine::ecs::component::Camera3DComponent>(
+                camera3d_component_id,
+            )
+        else {
+            return;
+        };
+        let Some(handle) = camera_comp.handle else {
+            return;
+        };
+        if self.active_camera != Some(handle) {
+            return;
+        }
+        let fov_kick = self.effect_state.get(&handle).map_or(0.0, |fx| fx.fov_kick);
+        let (aspect, near, far) = (
+            visuals.viewport_aspect(),
+            camera_comp.z_near,
+            camera_comp.z_far,
+        );
+        let proj = match camera_comp.projection {
+            CameraProjection::Identity => {
+                let cam = Camera3D::identity();
+                self.set_camera_3d_matrices(visuals, handle, cam.view, cam.proj);
+                return;
+            }
+            CameraProjection::Perspective => {
+                let fov = (camera_comp.fov_y_radians + fov_kick).clamp(0.01, 3.1);
+                Camera3D::perspective_rh_zo(fov, aspect, near, far)
+            }
+            CameraProjection::Orthographic => {
+                Camera3D::orthographic_rh_zo(camera_comp.ortho_size.max(1e-4), aspect, near, far)
+            }
+        };
+
+        let Some(mut model) = TransformSystem::world_model(world, camera3d_component_id) else {
+            return;
+        };
+        if let Some(fx) = self.effect_state.get(&handle) {
+            for (i, m) in model[3].iter_mut().take(3).enumerate() {
+                if fx.smoothing {
+                    *m = fx.pos[i];
+                }
+                *m += fx.shake[i];
+            }
+            if fx.shake_roll != 0.0 {
+                let roll = Mat4::from_rotation_z(fx.shake_roll).to_cols_array_2d();
+                model = TransformSystem::mat4_mul(model, roll);
+            }
+        }
+        let Some(view) = mat4_inverse(&model) else {
+            return;
+        };
+
+        self.set_camera_3d_matrices(visuals, handle, view, proj);
+    }
+
+    fn set_camera_3d_matrices(
+        &mut self,
+        visuals: &mut VisualWorld,
+        handle: CameraHandle,
+        view: [[f32; 4]; 4],
+        proj: [[f32; 4]; 4],
+    ) {
+        if let Some((_, AnyCamera::Camera3D(cam))) =
+            self.cameras.iter_mut().find(|(ch, _)| *ch == handle)
+        {
+            cam.view = view;
+            cam.proj = proj;
+        }
+        visuals.set_camera(view, proj);
+    }
+
+    /// Mouse navigation for the active Camera2D (opt-in flags on `Camera2DComponent`).
+    ///
+    /// - Middle-mouse drag pans so the world point under the cursor follows the cursor.
+    /// - Wheel zooms toward the cursor, keeping the world point under it fixed.
+    ///
+    /// Both edit the camera's parent Transform and queue a transform update, like `InputSystem`.
+    pub fn process_input(
+        &mut self,
+        world: &mut World,
+        visuals: &VisualWorld,
+        input: &crate::engine::user_input::InputState,
+        queue: &mut crate::engine::ecs::CommandQueue,
+    ) {
+        /// Caps a single frame's zoom so touchpad pixel deltas don't jump wildly.
+        const MAX_NOTCHES_PER_FRAME: f32 = 3.0;
+
+        let Some(active) = self.active_camera else {
+            return;
+        };
+        let Some(camera2d_cid) = self.camera2d_components.get(&active).copied() else {
+            return;
+        };
+        let Some(camera) = world
+            .get_component_by_id_as::<crate::engine::ecs::component::Camera2DComponent>(
+                camera2d_cid,
+            )
+            .cloned()
+        else {
+            return;
+        };
+        let Some(cursor) = input.cursor_pos else {
+            return;
+        };
+
+        let panning = camera.pan_with_middle_mouse
+            && input
+                .mouse_down
+                .contains(&winit::event::MouseButton::Middle);
+        let notches = if camera.zoom_with_wheel {
+            input
+                .wheel_delta
+                .1
+                .clamp(-MAX_NOTCHES_PER_FRAME, MAX_NOTCHES_PER_FRAME)
+        } else {
+            0.0
+        };
+        if !panning && notches == 0.0 {
+            return;
+        }
+
+        let Some(parent) = world.parent_of(camera2d_cid) else {
+            return;
+        };
+        let Some(mut transform) = world
+            .get_component_by_id_as::<crate::engine::ecs::component::TransformComponent>(parent)
+            .map(|t| t.transform)
+        else {
+            return;
+        };
+
+        let to_world = |xy: (f32, f32)| self.screen_to_world(visuals, [xy.0, xy.1], 0.0);
+
+        if panning {
+            let (dx, dy) = input.mouse_movement();
+            let prev = (cursor.0 - dx, cursor.1 - dy);
+            if let (Some(a), Some(b)) = (to_world(prev), to_world(cursor)) {
+                transform.translation.x -= b[0] - a[0];
+                transform.translation.y -= b[1] - a[1];
+            }
+        }
+
+        if notches != 0.0 {
+            let (min_s, max_s) = camera.zoom_range;
+            let sx = transform.scale.x;
+            let target = (sx * (-notches * camera.zoom_speed).exp()).clamp(min_s, max_s);
+            let k = if sx.abs() > 1e-8 { target / sx } else { 1.0 };
+            if let Some(p) = to_world(cursor) {
+                // Keep `p` under the cursor: t' = p - k * (p - t).
+                transform.translation.x = p[0] - k * (p[0] - transform.translation.x);
+                transform.translation.y = p[1] - k * (p[1] - transform.translation.y);
+            }
+            transform.scale.x *= k;
+            transform.scale.y *= k;
+        }
+
+        transform.recompute_model();
+        if let Some(t) = world
+            .get_component_by_id_as_mut::<crate::engine::ecs::component::TransformComponent>(parent)
+        {
+            t.transform = transform;
+        }
+        queue.queue_update_transform(parent, transform);
+    }
+
+    /// Project a world-space point to screen pixels for the active camera.
+    ///
+    /// Mirrors the mesh vertex shader: `camera_2d` + aspect correction, then `proj * view`.
+    /// Screen space is physical pixels with the origin at the top-left (same as
+    /// `InputState::cursor_pos`). The returned `z` is NDC depth, suitable for feeding back into
+    /// `screen_to_world`. Returns `None` for points behind a perspective camera.
+    pub fn world_to_screen(&self, visuals: &VisualWorld, world_pos: [f32; 3]) -> Option<[f32; 3]> {
+        let c2 = visuals.camera_2d();
+        let x = c2[0][0] * world_pos[0] + c2[1][0] * world_pos[1] + c2[2][0];
+        let y = c2[0][1] * world_pos[0] + c2[1][1] * world_pos[1] + c2[2][1];
+
+        let cw = [x * Self::inv_aspect(visuals), y, world_pos[2], 1.0];
+        let pv = TransformSystem::mat4_mul(visuals.camera_proj(), visuals.camera_view());
+        let clip = mat4_transform(&pv, cw);
+        if clip[3] <= 1e-8 {
+            return None;
+        }
+
+        let [w, h] = visuals.viewport();
+        let ndc = [clip[0] / clip[3], clip[1] / clip[3], clip[2] / clip[3]];
+        Some([(ndc[0] + 1.0) * 0.5 * w, (ndc[1] + 1.0) * 0.5 * h, ndc[2]])
+    }
+
+    /// Unproject screen pixels at an NDC `depth` back to world space for the active camera.
+    ///
+    /// Inverse of `world_to_screen`. For the 2D camera path (identity view/proj), `depth` is the
+    /// world-space Z of the plane to hit (0 for the default 2D plane).
+    pub fn screen_to_world(
+        &self,
+        visuals: &VisualWorld,
+        screen_xy: [f32; 2],
+        depth: f32,
+    ) -> Option<[f32; 3]> {
+        let [w, h] = visuals.viewport();
+        if w <= 0.0 || h <= 0.0 {
+            return None;
+        }
+        let ndc = [
+            screen_xy[0] / w * 2.0 - 1.0,
+            screen_xy[1] / h * 2.0 - 1.0,
+            depth,
+            1.0,
+        ];
+
+        let pv = TransformSystem::mat4_mul(visuals.camera_proj(), visuals.camera_view());
+        let inv_pv = mat4_inverse(&pv)?;
+        let cw = mat4_transform(&inv_pv, ndc);
+        if cw[3].abs() <= 1e-8 {
+            return None;
+        }
+        let cw = [cw[0] / cw[3], cw[1] / cw[3], cw[2] / cw[3]];
+
+        // Undo aspect correction, then the 2D camera affine.
+        let x = cw[0] / Self::inv_aspect(visuals);
+        let y = cw[1];
+        let c2 = visuals.camera_2d();
+        let (a, b, c, d) = (c2[0][0], c2[1][0], c2[0][1], c2[1][1]);
+        let det = a * d - b * c;
+        if det.abs() <= 1e-12 {
+            return None;
+        }
+        let (px, py) = (x - c2[2][0], y - c2[2][1]);
+        Some([(d * px - b * py) / det, (a * py - c * px) / det, cw[2]])
+    }
+
+    /// Matches `inv_aspect` in the mesh vertex shader.
+    fn inv_aspect(visuals: &VisualWorld) -> f32 {
+        let [w, h] = visuals.viewport();
+        if w > 0.0 { h / w } else { 1.0 }
+    }
+
+    /// Advance smoothing/shake/FOV kick for the active camera toward `target`.
+    fn advance_effects(
+        &mut self,
+        world: &mut World,
+        handle: CameraHandle,
+        camera_component_id: ComponentId,
+        target: [f32; 3],
+        dt_sec: f32,
+    ) {
+        let effects = if let Some(c) = world
+            .get_component_by_id_as_mut::<crate::engine::ecs::component::Camera2DComponent>(
+                camera_component_id,
+            ) {
+            &mut c.effects
+        } else if let Some(c) = world
+            .get_component_by_id_as_mut::<crate::engine::ecs::component::Camera3DComponent>(
+                camera_component_id,
+            )
+        {
+            &mut c.effects
+        } else {
+            return;
+        };
+
+        let state = self.effect_state.entry(handle).or_default();
+        state.time += dt_sec;
+
+        if !state.initialized || effects.smoothing <= 0.0 {
+            state.pos = target;
+            state.vel = [0.0; 3];
+            state.initialized = true;
+        } else {
+            let omega = match &effects.smoothing_curve {
+                Some(curve) => {
+                    let distance = Vec3::from(state.pos).distance(Vec3::from(target));
+                    effects.smoothing * curve.sample(distance).max(0.0)
+                }
+                None => effects.smoothing,
+            };
+            for (i, &t) in target.iter().enumerate() {
+                spring_step(&mut state.pos[i], &mut state.vel[i], t, omega, dt_sec);
+            }
+        }
+        state.smoothing = effects.smoothing > 0.0;
+
+        let shake = effects.trauma * effects.trauma;
+        let t = state.time;
+        let f = effects.shake_frequency;
+        state.shake = [
+            shake * effects.shake_offset * shake_noise(t, f, 0.0),
+            shake * effects.shake_offset * shake_noise(t, f, 1.0),
+            shake * effects.shake_offset * shake_noise(t, f, 2.0),
+        ];
+        state.shake_roll = shake * effects.shake_roll * shake_noise(t, f, 3.0);
+        state.fov_kick = effects.fov_kick;
+
+        effects.trauma = (effects.trauma - effects.trauma_decay * dt_sec).max(0.0);
+        effects.fov_kick *= (-effects.fov_kick_decay * dt_sec).exp();
+        if effects.fov_kick.abs() < 1e-5 {
+            effects.fov_kick = 0.0;
+        }
+    }
+
     /// Register a Camera2D component.
     pub fn register_camera2d(
         &mut self,
-        _world: &mut World,
-        _visuals: &mut VisualWorld,
+        world: &mut World,
+        visuals: &mut VisualWorld,
         component: ComponentId,
     ) -> CameraHandle {
         let h = CameraHandle(self.next_handle);
@@ -197,12 +645,28 @@ impl CameraSystem {
         self.cameras.push((h, AnyCamera::Camera2D));
         self.camera2d_components.insert(h, component);
 
+        let layers = world
+            .get_component_by_id_as::<crate::engine::ecs::component::Camera2DComponent>(component)
+            .map(|c| c.visibility_layers)
+            .unwrap_or(crate::engine::graphics::ALL_VISIBILITY_LAYERS);
+        self.visibility_layers.insert(h, layers);
+
         // Newest becomes active.
         self.active_camera = Some(h);
+        visuals.set_camera_visibility_layers(layers);
 
         h
     }
 
+    /// Component id of the active camera (Camera2D or Camera3D).
+    pub fn active_camera_component(&self) -> Option<ComponentId> {
+        let h = self.active_camera?;
+        self.camera2d_components
+            .get(&h)
+            .or_else(|| self.camera3d_components.get(&h))
+            .copied()
+    }
+
     pub fn active_camera_matrices(&self) -> Option<([[f32; 4]; 4], [[f32; 4]; 4])> {
         let h = self.active_camera?;
         let (_, cam) = self.cameras.iter().find(|(ch, _)| *ch == h)?;
@@ -213,38 +677,40 @@ impl CameraSystem {
     }
 }
 
-/// Invert a TRS matrix assuming it's only translation + scale (no rotation/shear).
+/// Full world -> clip matrix of the vertex shader path: `proj * view * aspect * camera_2d`.
 ///
-/// This matches how the demo currently uses `TransformComponent` (position + scale only).
-/// If/when we add rotations, we'll want a full mat4 inverse or a quat-based view build.
-fn invert_rigid_transform(m: &[[f32; 4]; 4]) -> [[f32; 4]; 4] {
-    // Column-major, with translation in column 3 (index 3).
-    // Our Transform builder also stores translation in m[3][0..2] (4th column).
-    let sx = m[0][0];
-    let sy = m[1][1];
-    let sz = m[2][2];
-
-    // Protect against divide-by-zero.
-    let inv_sx = if sx.abs() > 1e-8 { 1.0 / sx } else { 1.0 };
-    let inv_sy = if sy.abs() > 1e-8 { 1.0 / sy } else { 1.0 };
-    let inv_sz = if sz.abs() > 1e-8 { 1.0 / sz } else { 1.0 };
-
-    let tx = m[3][0];
-    let ty = m[3][1];
-    let tz = m[3][2];
-
-    // Inverse of S then T: inv(M) = inv(S) * inv(T)
-    // For column-major with translation in last column: inv translation becomes -(invS * t).
-    let itx = -(tx * inv_sx);
-    let ity = -(ty * inv_sy);
-    let itz = -(tz * inv_sz);
-
-    [
-        [inv_sx, 0.0, 0.0, 0.0],
-        [0.0, inv_sy, 0.0, 0.0],
-        [0.0, 0.0, inv_sz, 0.0],
-        [itx, ity, itz, 1.0],
-    ]
+/// `camera_2d` only touches XY, so it is lifted to a 4x4 that passes Z through.
+pub(crate) fn world_to_clip(visuals: &VisualWorld) -> [[f32; 4]; 4] {
+    let c2 = visuals.camera_2d();
+    let camera_2d = [
+        [c2[0][0], c2[0][1], 0.0, 0.0],
+        [c2[1][0], c2[1][1], 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [c2[2][0], c2[2][1], 0.0, 1.0],
+    ];
+    let inv_aspect = CameraSystem::inv_aspect(visuals);
+    let aspect = [
+        [inv_aspect, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ];
+    let pv = TransformSystem::mat4_mul(visuals.camera_proj(), visuals.camera_view());
+    TransformSystem::mat4_mul(pv, TransformSystem::mat4_mul(aspect, camera_2d))
+}
+
+/// Column-major mat4 * vec4.
+fn mat4_transform(m: &[[f32; 4]; 4], v: [f32; 4]) -> [f32; 4] {
+    (Mat4::from_cols_array_2d(m) * Vec4::from(v)).to_array()
+}
+
+/// General 4x4 inverse (column-major). Returns `None` for singular matrices.
+pub(crate) fn mat4_inverse(m: &[[f32; 4]; 4]) -> Option<[[f32; 4]; 4]> {
+    let m = Mat4::from_cols_array_2d(m);
+    if m.determinant().abs() < 1e-12 {
+        return None;
+    }
+    Some(m.inverse().to_cols_array_2d())
 }
 
 impl System for CameraSystem {
@@ -253,28 +719,47 @@ impl System for CameraSystem {
         world: &mut World,
         visuals: &mut VisualWorld,
         _input: &crate::engine::user_input::InputState,
-        _dt_sec: f32,
+        dt_sec: f32,
     ) {
         // If there's an active Camera2DComponent, read its parent TransformComponent.
         if let Some(active_handle) = self.active_camera {
             // If the handle is in camera2d_components, it's a Camera2D
-            if let Some(camera2d_component_id) = self.camera2d_components.get(&active_handle) {
-                let Some(parent) = world.parent_of(*camera2d_component_id) else {
+            if let Some(camera2d_component_id) =
+                self.camera2d_components.get(&active_handle).copied()
+            {
+                let Some(parent) = world.parent_of(camera2d_component_id) else {
                     return;
                 };
                 if world
                     .get_component_by_id_as::<crate::engine::ecs::component::TransformComponent>(
                         parent,
                     )
-                    .is_some()
+                    .is_none()
                 {
-                    self.update_camera_2d_from_parent_transform(
-                        world,
-                        visuals,
-                        *camera2d_component_id,
-                        parent,
-                    );
+                    return;
                 }
+                // Smooth toward the world position, like 3D cameras, so a camera under a moving
+                // parent follows where it actually is.
+                let Some(target) = TransformSystem::world_position(world, camera2d_component_id)
+                else {
+                    return;
+                };
+                self.advance_effects(world, active_handle, camera2d_component_id, target, dt_sec);
+                self.update_camera_2d_from_parent_transform(
+                    world,
+                    visuals,
+                    camera2d_component_id,
+                    parent,
+                );
+            } else if let Some(camera3d_component_id) =
+                self.camera3d_components.get(&active_handle).copied()
+            {
+                let Some(target) = TransformSystem::world_position(world, camera3d_component_id)
+                else {
+                    return;
+                };
+                self.advance_effects(world, active_handle, camera3d_component_id, target, dt_sec);
+                self.update_camera_3d(world, visuals, camera3d_component_id);
             }
         }
     }
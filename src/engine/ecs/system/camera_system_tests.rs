@@ -0,0 +1,181 @@
+#[cfg(test)]
+mod tests {
+    use crate::engine::ecs::World;
+    use crate::engine::ecs::codec::FieldCodec;
+    use crate::engine::ecs::component::{
+        Camera2DComponent, Camera3DComponent, CameraProjection, TransformComponent,
+    };
+    use crate::engine::ecs::system::camera_system::mat4_inverse;
+    use crate::engine::ecs::system::{Camera3D, CameraEffects, CameraSystem, System};
+    use crate::engine::graphics::VisualWorld;
+    use crate::engine::user_input::InputState;
+
+    fn assert_close(a: [f32; 3], b: [f32; 3]) {
+        for i in 0..3 {
+            assert!((a[i] - b[i]).abs() < 1e-3, "{a:?} != {b:?}");
+        }
+    }
+
+    #[test]
+    fn mat4_inverse_undoes_translation_and_scale() {
+        let m = [
+            [2.0, 0.0, 0.0, 0.0],
+            [0.0, 4.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [3.0, -1.0, 5.0, 1.0],
+        ];
+        let inv = mat4_inverse(&m).unwrap();
+        assert!((inv[0][0] - 0.5).abs() < 1e-6);
+        assert!((inv[1][1] - 0.25).abs() < 1e-6);
+        assert!((inv[3][0] + 1.5).abs() < 1e-6);
+        assert!((inv[3][1] - 0.25).abs() < 1e-6);
+        assert!((inv[3][2] + 5.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn camera_position_from_rotated_scaled_view() {
+        let camera = glam::Mat4::from_scale_rotation_translation(
+            glam::Vec3::splat(2.0),
+            glam::Quat::from_rotation_y(0.7),
+            glam::Vec3::new(1.0, -2.0, 5.0),
+        );
+        let mut visuals = VisualWorld::new();
+        visuals.set_camera(
+            mat4_inverse(&camera.to_cols_array_2d()).unwrap(),
+            glam::Mat4::IDENTITY.to_cols_array_2d(),
+        );
+        assert_close(visuals.camera_position(), [1.0, -2.0, 5.0]);
+    }
+
+    #[test]
+    fn screen_round_trip_with_2d_camera() {
+        let cameras = CameraSystem::new();
+        let mut visuals = VisualWorld::new();
+        visuals.set_viewport(800.0, 600.0);
+        // Camera at (1, 2) zoomed 2x.
+        visuals.set_camera_2d([
+            [0.5, 0.0, 0.0, 0.0],
+            [0.0, 0.5, 0.0, 0.0],
+            [-0.5, -1.0, 1.0, 0.0],
+        ]);
+
+        // The camera position maps to the screen center.
+        let center = cameras.world_to_screen(&visuals, [1.0, 2.0, 0.0]).unwrap();
+        assert_close(center, [400.0, 300.0, 0.0]);
+
+        let p = [1.7, 1.4, 0.0];
+        let s = cameras.world_to_screen(&visuals, p).unwrap();
+        let back = cameras
+            .screen_to_world(&visuals, [s[0], s[1]], s[2])
+            .unwrap();
+        assert_close(back, p);
+    }
+
+    #[test]
+    fn screen_round_trip_with_3d_camera() {
+        let cameras = CameraSystem::new();
+        let mut visuals = VisualWorld::new();
+        visuals.set_viewport(1024.0, 768.0);
+        let view = mat4_inverse(&[
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 5.0, 1.0],
+        ])
+        .unwrap();
+        let proj = Camera3D::perspective_rh_zo(1.0, 1024.0 / 768.0, 0.1, 100.0);
+        visuals.set_camera(view, proj);
+
+        let p = [0.3, -0.2, 1.0];
+        let s = cameras.world_to_screen(&visuals, p).unwrap();
+        let back = cameras
+            .screen_to_world(&visuals, [s[0], s[1]], s[2])
+            .unwrap();
+        assert_close(back, p);
+
+        // Behind the camera.
+        assert!(
+            cameras
+                .world_to_screen(&visuals, [0.0, 0.0, 10.0])
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn orthographic_camera_ignores_depth() {
+        let mut world = World::default();
+        let mut visuals = VisualWorld::new();
+        visuals.set_viewport(800.0, 600.0);
+        let t = world.add_component(TransformComponent::new().with_position(0.0, 0.0, 10.0));
+        let camera =
+            world.add_component(Camera3DComponent::new().with_orthographic(2.0, 0.1, 100.0));
+        world.add_child(t, camera).unwrap();
+
+        let mut cameras = CameraSystem::new();
+        cameras.register_camera(&mut world, &mut visuals, camera);
+        assert_eq!(
+            visuals.camera_proj(),
+            Camera3D::orthographic_rh_zo(2.0, 800.0 / 600.0, 0.1, 100.0)
+        );
+        let near = cameras.world_to_screen(&visuals, [1.0, 1.0, 0.0]).unwrap();
+        let far = cameras.world_to_screen(&visuals, [1.0, 1.0, -5.0]).unwrap();
+        assert_close([near[0], near[1], 0.0], [far[0], far[1], 0.0]);
+
+        let encoded = CameraProjection::Orthographic.encode();
+        assert_eq!(encoded.as_str(), Some("orthographic"));
+        assert_eq!(
+            CameraProjection::decode(&encoded),
+            Some(CameraProjection::Orthographic)
+        );
+    }
+
+    #[test]
+    fn default_3d_camera_keeps_identity_matrices() {
+        let mut world = World::default();
+        let mut visuals = VisualWorld::new();
+        visuals.set_viewport(800.0, 600.0);
+        let t = world.add_component(TransformComponent::new().with_position(0.0, 0.0, 10.0));
+        let camera = world.add_component(Camera3DComponent::new());
+        world.add_child(t, camera).unwrap();
+
+        let mut cameras = CameraSystem::new();
+        cameras.register_camera(&mut world, &mut visuals, camera);
+        cameras.tick(&mut world, &mut visuals, &InputState::default(), 0.1);
+        let identity = Camera3D::identity();
+        assert_eq!(visuals.camera_view(), identity.view);
+        assert_eq!(visuals.camera_proj(), identity.proj);
+    }
+
+    #[test]
+    fn camera_2d_follows_its_world_position_with_or_without_smoothing() {
+        for effects in [
+            CameraEffects::default(),
+            CameraEffects::default().with_smoothing(1000.0),
+        ] {
+            let mut world = World::default();
+            let mut visuals = VisualWorld::new();
+            let root = world.add_component(TransformComponent::new().with_position(10.0, 0.0, 0.0));
+            let t = world.add_component(TransformComponent::new());
+            let camera =
+                world.add_component(Camera2DComponent::new().with_effects(effects.clone()));
+            world.add_child(root, t).unwrap();
+            world.add_child(t, camera).unwrap();
+
+            let mut cameras = CameraSystem::new();
+            let handle = cameras.register_camera2d(&mut world, &mut visuals, camera);
+            world
+                .get_component_by_id_as_mut::<Camera2DComponent>(camera)
+                .unwrap()
+                .handle = Some(handle);
+            for _ in 0..10 {
+                cameras.tick(&mut world, &mut visuals, &InputState::default(), 0.1);
+            }
+            cameras.update_camera_2d_from_parent_transform(&world, &mut visuals, camera, t);
+            // The view translation is minus the camera position: the parent's offset, not the
+            // camera Transform's own (zero) translation.
+            let view = visuals.camera_2d();
+            assert!((view[2][0] + 10.0).abs() < 1e-2, "{effects:?}: {view:?}");
+            assert!(view[2][1].abs() < 1e-2, "{effects:?}: {view:?}");
+        }
+    }
+}
@@ -0,0 +1,320 @@
+use glam::{Mat4, Vec2, Vec3};
+
+use crate::engine::action_map::ActionMap;
+use crate::engine::ecs::component::{
+    CharacterControllerComponent, ColliderComponent, MovePlane, TransformComponent,
+};
+use crate::engine::ecs::system::TransformSystem;
+use crate::engine::ecs::{CommandQueue, ComponentId, World};
+use crate::engine::spatial::Aabb;
+use crate::engine::user_input::InputState;
+
+/// Longest frame time simulated in one tick; anything beyond is dropped so a stall doesn't
+/// drop characters through the floor.
+const MAX_FRAME_DT: f32 = 0.25;
+
+/// Push-out passes per substep, deepest contact first; corners need more than one.
+const RESOLVE_ITERATIONS: usize = 8;
+
+/// Contacts whose normal is within ~45° of up are ground (and of down, ceiling).
+const GROUND_NORMAL: f32 = 0.7;
+
+/// Moves `CharacterControllerComponent`s from the action map and keeps them out of
+/// `ColliderComponent`s.
+///
+/// Colliders are tested one by one every tick, which is fine for level geometry made of a
+/// few hundred boxes.
+#[derive(Debug, Default)]
+pub struct CharacterSystem {
+    characters: Vec<ComponentId>,
+    colliders: Vec<ComponentId>,
+}
+
+impl CharacterSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register_character(&mut self, world: &World, component: ComponentId) {
+        if world
+            .get_component_by_id_as::<CharacterControllerComponent>(component)
+            .is_some()
+            && !self.characters.contains(&component)
+        {
+            self.characters.push(component);
+        }
+    }
+
+    pub fn register_collider(&mut self, world: &World, component: ComponentId) {
+        if world
+            .get_component_by_id_as::<ColliderComponent>(component)
+            .is_some()
+            && !self.colliders.contains(&component)
+        {
+            self.colliders.push(component);
+        }
+    }
+
+    /// Move every character by one frame of input and gravity, and queue UPDATE_TRANSFORM for
+    /// the ones that moved.
+    pub fn tick(
+        &mut self,
+        world: &mut World,
+        actions: &ActionMap,
+        input: &InputState,
+        queue: &mut CommandQueue,
+        dt_sec: f32,
+    ) {
+        let dt = dt_sec.clamp(0.0, MAX_FRAME_DT);
+        if dt == 0.0 {
+            return;
+        }
+        let colliders = self.collider_bounds(world);
+
+        let characters = self.characters.clone();
+        for cid in characters {
+            let Some(mut controller) = world
+                .get_component_by_id_as::<CharacterControllerComponent>(cid)
+                .cloned()
+            else {
+                self.characters.retain(|c| *c != cid);
+                continue;
+            };
+            let Some(parent) = world.parent_of(cid) else {
+                continue;
+            };
+            let Some(local) = world
+                .get_component_by_id_as::<TransformComponent>(parent)
+                .map(|t| t.transform.translation)
+            else {
+                continue;
+            };
+            // Space the Transform's translation is in.
+            let outer = TransformSystem::world_model(world, parent)
+                .map_or(Mat4::IDENTITY, |m| Mat4::from_cols_array_2d(&m));
+
+            // The character's own colliders (for other characters to bump into) don't count.
+            let boxes: Vec<Aabb> = colliders
+                .iter()
+                .filter(|(owner, _)| *owner != parent)
+                .map(|(_, bounds)| *bounds)
+                .collect();
+            let start = outer.transform_point3(local);
+            let end = Self::step(&mut controller, &boxes, actions, input, start, dt);
+
+            if let Some(c) = world.get_component_by_id_as_mut::<CharacterControllerComponent>(cid) {
+                c.vertical_speed = controller.vertical_speed;
+                c.grounded = controller.grounded;
+            }
+            if end == start {
+                continue;
+            }
+            let Some(t) = world.get_component_by_id_as_mut::<TransformComponent>(parent) else {
+                continue;
+            };
+            t.transform.translation = outer.inverse().transform_point3(end);
+            t.transform.recompute_model();
+            queue.queue_update_transform(parent, t.transform);
+        }
+    }
+
+    /// World-space box of every collider, with the Transform it hangs off.
+    fn collider_bounds(&mut self, world: &World) -> Vec<(ComponentId, Aabb)> {
+        self.colliders.retain(|&c| {
+            world
+                .get_component_by_id_as::<ColliderComponent>(c)
+                .is_some()
+        });
+        self.colliders
+            .iter()
+            .filter_map(|&c| {
+                let h = world
+                    .get_component_by_id_as::<ColliderComponent>(c)?
+                    .half_extents;
+                let owner = world.parent_of(c)?;
+                let model = TransformSystem::world_model(world, c)?;
+                Some((
+                    owner,
+                    Aabb::new([-h[0], -h[1], -h[2]], h).transformed(&model),
+                ))
+            })
+            .collect()
+    }
+
+    /// Apply input and gravity to `controller` and return where the capsule at `start` ends up.
+    fn step(
+        controller: &mut CharacterControllerComponent,
+        boxes: &[Aabb],
+        actions: &ActionMap,
+        input: &InputState,
+        start: Vec3,
+        dt: f32,
+    ) -> Vec3 {
+        let falls = controller.gravity > 0.0;
+        let mut axes = Vec2::new(
+            actions.axis(input, &controller.move_axes.0),
+            actions.axis(input, &controller.move_axes.1),
+        );
+        let up = match controller.plane {
+            MovePlane::XY => {
+                // Side-on: the y axis is the one gravity owns.
+                if falls {
+                    axes.y = 0.0;
+                }
+                Vec3::NEG_Y
+            }
+            MovePlane::XZ => Vec3::Y,
+        };
+        if axes.length_squared() > 1.0 {
+            axes = axes.normalize();
+        }
+        let walk = match controller.plane {
+            MovePlane::XY => Vec3::new(axes.x, axes.y, 0.0),
+            MovePlane::XZ => Vec3::new(axes.x, 0.0, axes.y),
+        } * controller.speed;
+
+        if falls {
+            let jump = controller
+                .jump_action
+                .as_deref()
+                .is_some_and(|action| actions.action_pressed(input, action));
+            if jump && controller.grounded {
+                controller.vertical_speed = controller.jump_speed;
+            }
+            controller.vertical_speed -= controller.gravity * dt;
+        } else {
+            controller.vertical_speed = 0.0;
+        }
+
+        let capsule = Capsule {
+            radius: controller.radius.max(1e-3),
+            half_segment: (0.5 * controller.height - controller.radius).max(0.0),
+            plane: controller.plane,
+        };
+        // Stepping up needs something to stand on first.
+        let step_height = if falls && controller.grounded {
+            controller.step_height
+        } else {
+            0.0
+        };
+        let delta = (walk + up * controller.vertical_speed) * dt;
+        let (end, contacts) = capsule.move_and_slide(start, delta, up, step_height, boxes);
+
+        controller.grounded = contacts.grounded;
+        if (contacts.grounded && controller.vertical_speed < 0.0)
+            || (contacts.ceiling && controller.vertical_speed > 0.0)
+        {
+            controller.vertical_speed = 0.0;
+        }
+        end
+    }
+}
+
+/// Upright capsule: a segment along Y swept by a sphere.
+struct Capsule {
+    radius: f32,
+    half_segment: f32,
+    plane: MovePlane,
+}
+
+#[derive(Debug, Default)]
+struct Contacts {
+    grounded: bool,
+    ceiling: bool,
+}
+
+impl Capsule {
+    /// Move from `start` by `delta` in substeps no longer than half the radius, pushing out
+    /// of `boxes` after each.
+    fn move_and_slide(
+        &self,
+        start: Vec3,
+        delta: Vec3,
+        up: Vec3,
+        step_height: f32,
+        boxes: &[Aabb],
+    ) -> (Vec3, Contacts) {
+        let steps = (delta.length() / (0.5 * self.radius))
+            .ceil()
+            .clamp(1.0, 16.0);
+        let mut pos = start;
+        let mut contacts = Contacts::default();
+        for _ in 0..steps as usize {
+            pos += delta / steps;
+            for _ in 0..RESOLVE_ITERATIONS {
+                let Some((normal, depth, bounds)) = boxes
+                    .iter()
+                    .filter_map(|b| self.penetration(pos, b).map(|(n, d)| (n, d, b)))
+                    .max_by(|a, b| a.1.total_cmp(&b.1))
+                else {
+                    break;
+                };
+                let along_up = normal.dot(up);
+                if along_up.abs() < GROUND_NORMAL && step_height > 0.0 {
+                    let rise = self.rise_onto(pos, up, bounds);
+                    if rise > 0.0 && rise <= step_height {
+                        pos += up * rise;
+                        contacts.grounded = true;
+                        continue;
+                    }
+                }
+                pos += normal * depth;
+                if along_up >= GROUND_NORMAL {
+                    contacts.grounded = true;
+                } else if along_up <= -GROUND_NORMAL {
+                    contacts.ceiling = true;
+                }
+            }
+        }
+        (pos, contacts)
+    }
+
+    /// Direction and distance that push the capsule centered at `pos` out of `b`, or `None`
+    /// if they don't overlap. In `MovePlane::XY` depth along Z is ignored: 2D colliders block
+    /// whatever layer the character is drawn on.
+    fn penetration(&self, pos: Vec3, b: &Aabb) -> Option<(Vec3, f32)> {
+        let (min, max) = (Vec3::from(b.min), Vec3::from(b.max));
+        let (lo, hi) = (pos.y - self.half_segment, pos.y + self.half_segment);
+        // Point of the segment closest to the box.
+        let y = if hi < min.y {
+            hi
+        } else if lo > max.y {
+            lo
+        } else {
+            pos.y.clamp(lo.max(min.y), hi.min(max.y))
+        };
+        let mut p = Vec3::new(pos.x, y, pos.z);
+        if self.plane == MovePlane::XY {
+            p.z = p.z.clamp(min.z, max.z);
+        }
+        let d = p - p.clamp(min, max);
+        let dist = d.length();
+        if dist >= self.radius {
+            return None;
+        }
+        if dist > 1e-5 {
+            return Some((d / dist, self.radius - dist));
+        }
+
+        // Segment inside the box: leave through the nearest face.
+        let r = self.radius;
+        let mut exits = vec![
+            (Vec3::X, max.x - p.x + r),
+            (Vec3::NEG_X, p.x - min.x + r),
+            (Vec3::Y, max.y - lo + r),
+            (Vec3::NEG_Y, hi - min.y + r),
+        ];
+        if self.plane == MovePlane::XZ {
+            exits.push((Vec3::Z, max.z - p.z + r));
+            exits.push((Vec3::NEG_Z, p.z - min.z + r));
+        }
+        exits.into_iter().min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// How far to lift the capsule at `pos` along `up` so its bottom rests on top of `b`.
+    fn rise_onto(&self, pos: Vec3, up: Vec3, b: &Aabb) -> f32 {
+        let top = (b.min[1] * up.y).max(b.max[1] * up.y);
+        let foot = pos.y * up.y - (self.half_segment + self.radius);
+        top - foot
+    }
+}
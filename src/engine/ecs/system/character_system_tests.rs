@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod tests {
+    use winit::keyboard::Key;
+
+    use crate::engine::action_map::ActionMap;
+    use crate::engine::ecs::component::{
+        CharacterControllerComponent, ColliderComponent, TransformComponent,
+    };
+    use crate::engine::ecs::system::CharacterSystem;
+    use crate::engine::ecs::{CommandQueue, ComponentId, World};
+    use crate::engine::user_input::InputState;
+
+    fn collider(world: &mut World, center: [f32; 3], half: [f32; 3]) -> ComponentId {
+        let t = world.add_component(
+            TransformComponent::new().with_position(center[0], center[1], center[2]),
+        );
+        let c = world.add_component(ColliderComponent::new(half[0], half[1], half[2]));
+        world.add_child(t, c).unwrap();
+        c
+    }
+
+    #[test]
+    fn walker_lands_climbs_steps_and_stops_at_walls() {
+        let mut world = World::default();
+        let floor = collider(&mut world, [0.0, -0.5, 0.0], [20.0, 0.5, 20.0]);
+        // A 0.2-high step from x = 2 to 4, then a wall at x = 6.
+        let step = collider(&mut world, [3.0, 0.1, 0.0], [1.0, 0.1, 2.0]);
+        let wall = collider(&mut world, [6.5, 2.0, 0.0], [0.5, 2.0, 2.0]);
+
+        let player = world.add_component(TransformComponent::new().with_position(0.0, 1.5, 0.0));
+        let controller = world.add_component(CharacterControllerComponent::walker_3d());
+        world.add_child(player, controller).unwrap();
+
+        let mut characters = CharacterSystem::new();
+        for c in [floor, step, wall] {
+            characters.register_collider(&world, c);
+        }
+        characters.register_character(&world, controller);
+
+        let actions = ActionMap::with_default_bindings();
+        let mut input = InputState::default();
+        let mut queue = CommandQueue::new();
+        let mut run = |world: &mut World, input: &InputState, ticks: usize| {
+            for _ in 0..ticks {
+                characters.tick(world, &actions, input, &mut queue, 1.0 / 60.0);
+            }
+            let c = world
+                .get_component_by_id_as::<CharacterControllerComponent>(controller)
+                .unwrap()
+                .grounded;
+            let t = world
+                .get_component_by_id_as::<TransformComponent>(player)
+                .unwrap()
+                .transform
+                .translation;
+            (t, c)
+        };
+
+        // Falls onto the floor: half the 1.8 capsule above y = 0.
+        let (pos, grounded) = run(&mut world, &input, 60);
+        assert!(grounded);
+        assert!((pos.y - 0.9).abs() < 0.02, "y = {}", pos.y);
+
+        // Walking right climbs onto the step without jumping.
+        input.keys_down.insert(Key::Character("d".into()));
+        let (pos, grounded) = run(&mut world, &input, 45);
+        assert!((2.0..4.0).contains(&pos.x), "x = {}", pos.x);
+        assert!(grounded);
+        assert!((pos.y - 1.1).abs() < 0.02, "y = {}", pos.y);
+
+        // The wall stops it, one radius short.
+        let (pos, _) = run(&mut world, &input, 120);
+        assert!((pos.x - 5.6).abs() < 0.02, "x = {}", pos.x);
+    }
+}
@@ -0,0 +1,139 @@
+use crate::engine::ecs::component::ClipRectComponent;
+use crate::engine::ecs::system::TransformSystem;
+use crate::engine::ecs::system::camera_system::world_to_clip;
+use crate::engine::ecs::{ComponentId, World};
+use crate::engine::graphics::{ClipRect, VisualWorld};
+use glam::{Mat4, Vec4};
+use std::collections::HashMap;
+
+/// Layout pass for `ClipRectComponent`s.
+///
+/// Each frame the clip's parent-local rectangle is projected through its world model and the
+/// active camera, reduced to a screen-space AABB and intersected with the enclosing clips.
+/// Instances learn their clip when they are flushed (`clip_of`); a clip registered after its
+/// content re-tags the instances already below it.
+#[derive(Debug, Default)]
+pub struct ClipRectSystem {
+    /// Clip cid -> nearest enclosing clip cid.
+    clips: HashMap<ComponentId, Option<ComponentId>>,
+}
+
+impl ClipRectSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register_clip_rect(
+        &mut self,
+        world: &mut World,
+        visuals: &mut VisualWorld,
+        component: ComponentId,
+    ) {
+        if world
+            .get_component_by_id_as::<ClipRectComponent>(component)
+            .is_none()
+        {
+            return;
+        }
+        self.clips
+            .insert(component, Self::clip_of(world, component));
+
+        let mut stack = world.children_of(component).to_vec();
+        while let Some(cid) = stack.pop() {
+            if let Some(handle) = visuals.instance_for_component(cid) {
+                visuals.update_clip(handle, Self::clip_of(world, cid));
+            }
+            if let Some(enclosing) = self.clips.get_mut(&cid) {
+                *enclosing = Self::clip_of(world, cid);
+            }
+            stack.extend_from_slice(world.children_of(cid));
+        }
+    }
+
+    /// Forget a clip; its instances and nested clips fall back to the enclosing clip.
+    pub fn remove_clip_rect(&mut self, visuals: &mut VisualWorld, component: ComponentId) {
+        let Some(enclosing) = self.clips.remove(&component) else {
+            return;
+        };
+        visuals.remove_clip_rect(component, enclosing);
+        for parent in self.clips.values_mut() {
+            if *parent == Some(component) {
+                *parent = enclosing;
+            }
+        }
+    }
+
+    /// Nearest `ClipRectComponent` above `cid`, if any.
+    pub fn clip_of(world: &World, cid: ComponentId) -> Option<ComponentId> {
+        let mut cur = cid;
+        while let Some(parent) = world.parent_of(cur) {
+            if world
+                .get_component_by_id_as::<ClipRectComponent>(parent)
+                .is_some()
+            {
+                return Some(parent);
+            }
+            cur = parent;
+        }
+        None
+    }
+
+    /// Project every clip onto the screen for this frame's camera, viewport and transforms.
+    pub fn layout(&mut self, world: &World, visuals: &mut VisualWorld) {
+        if self.clips.is_empty() {
+            return;
+        }
+        let [w, h] = visuals.viewport();
+        let screen = ClipRect {
+            min: [0.0, 0.0],
+            max: [w, h],
+        };
+        let camera = Mat4::from_cols_array_2d(&world_to_clip(visuals));
+
+        let mut own = HashMap::with_capacity(self.clips.len());
+        for &cid in self.clips.keys() {
+            let Some(clip) = world.get_component_by_id_as::<ClipRectComponent>(cid) else {
+                continue;
+            };
+            let model = TransformSystem::world_model(world, cid)
+                .map(|m| Mat4::from_cols_array_2d(&m))
+                .unwrap_or(Mat4::IDENTITY);
+            let to_clip = camera * model;
+
+            let mut rect = ClipRect {
+                min: [f32::INFINITY; 2],
+                max: [f32::NEG_INFINITY; 2],
+            };
+            for [x, y] in [
+                clip.min,
+                [clip.max[0], clip.min[1]],
+                [clip.min[0], clip.max[1]],
+                clip.max,
+            ] {
+                let p = to_clip * Vec4::new(x, y, 0.0, 1.0);
+                if p.w <= 1e-8 {
+                    // Corner behind a perspective camera: don't guess, leave it unclipped.
+                    rect = screen;
+                    break;
+                }
+                let px = [(p.x / p.w + 1.0) * 0.5 * w, (p.y / p.w + 1.0) * 0.5 * h];
+                rect.min = [rect.min[0].min(px[0]), rect.min[1].min(px[1])];
+                rect.max = [rect.max[0].max(px[0]), rect.max[1].max(px[1])];
+            }
+            own.insert(cid, rect);
+        }
+
+        self.clips.retain(|cid, _| own.contains_key(cid));
+        for (&cid, &enclosing) in &self.clips {
+            let mut rect = own[&cid];
+            let mut outer = enclosing;
+            while let Some(o) = outer {
+                if let Some(&r) = own.get(&o) {
+                    rect = rect.intersect(r);
+                }
+                outer = self.clips.get(&o).copied().flatten();
+            }
+            visuals.set_clip_rect(cid, rect);
+        }
+    }
+}
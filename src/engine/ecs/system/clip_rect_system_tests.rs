@@ -0,0 +1,52 @@
+#[cfg(test)]
+mod tests {
+    use crate::engine::ecs::World;
+    use crate::engine::ecs::component::{ClipRectComponent, TransformComponent};
+    use crate::engine::ecs::system::ClipRectSystem;
+    use crate::engine::graphics::{ClipRect, VisualWorld};
+
+    #[test]
+    fn nested_clips_intersect_on_screen() {
+        let mut world = World::default();
+        let mut visuals = VisualWorld::new();
+        // Default 2D camera: screen px = (50x + 100, 50y + 50).
+        visuals.set_viewport(200.0, 100.0);
+
+        let t = world.add_component(TransformComponent::new().with_position(-1.0, 0.0, 0.0));
+        let outer = world.add_component(ClipRectComponent::sized(1.0, 1.0));
+        let inner = world.add_component(ClipRectComponent::new([-0.5, 0.5], [2.0, 2.0]));
+        world.add_child(t, outer).unwrap();
+        world.add_child(outer, inner).unwrap();
+        assert_eq!(ClipRectSystem::clip_of(&world, inner), Some(outer));
+
+        let mut clips = ClipRectSystem::new();
+        clips.register_clip_rect(&mut world, &mut visuals, outer);
+        clips.register_clip_rect(&mut world, &mut visuals, inner);
+        clips.layout(&world, &mut visuals);
+
+        let outer_rect = ClipRect {
+            min: [50.0, 50.0],
+            max: [100.0, 100.0],
+        };
+        assert_eq!(visuals.clip_rect(outer), Some(outer_rect));
+        assert_eq!(
+            visuals.clip_rect(inner),
+            Some(ClipRect {
+                min: [50.0, 75.0],
+                max: [100.0, 100.0],
+            })
+        );
+
+        // Without its parent clip the inner one only keeps its own rect.
+        clips.remove_clip_rect(&mut visuals, outer);
+        clips.layout(&world, &mut visuals);
+        assert_eq!(visuals.clip_rect(outer), None);
+        assert_eq!(
+            visuals.clip_rect(inner),
+            Some(ClipRect {
+                min: [25.0, 75.0],
+                max: [150.0, 150.0],
+            })
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use crate::engine::assets::AssetServer;
+use crate::engine::ecs::component::ColorGradingComponent;
+use crate::engine::ecs::{ComponentId, World};
+use crate::engine::graphics::color_grading::lut_strip_size;
+use crate::engine::graphics::{ColorGrading, TextureHandle, TextureUploader, VisualWorld};
+use std::collections::HashMap;
+
+/// Mirrors the active `ColorGradingComponent` into `VisualWorld`.
+///
+/// LUTs load in `flush_pending`, which has the uploader, and stay uploaded per URI.
+#[derive(Debug, Default)]
+pub struct ColorGradingSystem {
+    /// Last registered color grading component.
+    active: Option<ComponentId>,
+    /// `active` needs its LUT loaded before it can apply.
+    pending: bool,
+    /// LUT URI -> strip texture and edge length.
+    luts: HashMap<String, (TextureHandle, u32)>,
+}
+
+impl ColorGradingSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn active(&self) -> Option<ComponentId> {
+        self.active
+    }
+
+    pub fn register_color_grading(
+        &mut self,
+        world: &mut World,
+        visuals: &mut VisualWorld,
+        component: ComponentId,
+    ) {
+        let Some(grading) = world.get_component_by_id_as::<ColorGradingComponent>(component) else {
+            return;
+        };
+        self.active = Some(component);
+        match self.luts.get(&grading.lut) {
+            Some(&(lut, size)) => {
+                visuals.set_color_grading(Some(ColorGrading {
+                    lut,
+                    size,
+                    strength: grading.strength,
+                }));
+                self.pending = false;
+            }
+            None => {
+                // Ungraded until the LUT is uploaded.
+                visuals.set_color_grading(None);
+                self.pending = !grading.lut.is_empty();
+            }
+        }
+    }
+
+    pub fn remove_color_grading(&mut self, visuals: &mut VisualWorld, component: ComponentId) {
+        if self.active == Some(component) {
+            self.active = None;
+            self.pending = false;
+            visuals.set_color_grading(None);
+        }
+    }
+
+    /// Load and upload the active LUT, if it is waiting for one.
+    pub fn flush_pending(
+        &mut self,
+        world: &World,
+        visuals: &mut VisualWorld,
+        assets: &mut AssetServer,
+        uploader: &mut dyn TextureUploader,
+    ) {
+        if !std::mem::take(&mut self.pending) {
+            return;
+        }
+        let Some(grading) = self
+            .active
+            .and_then(|cid| world.get_component_by_id_as::<ColorGradingComponent>(cid))
+        else {
+            return;
+        };
+        let uri = grading.lut.as_str();
+        let uploaded = assets.load_image(uri).and_then(|image| {
+            let size = lut_strip_size(image.width, image.height).ok_or_else(|| {
+                format!(
+                    "expected an N*N x N LUT strip, got {}x{}",
+                    image.width, image.height
+                )
+            })?;
+            uploader
+                .upload_texture_rgba8(&image.rgba, image.width, image.height)
+                .map(|lut| (lut, size))
+                .map_err(|e| e.to_string())
+        });
+        match uploaded {
+            Ok((lut, size)) => {
+                self.luts.insert(uri.to_string(), (lut, size));
+                visuals.set_color_grading(Some(ColorGrading {
+                    lut,
+                    size,
+                    strength: grading.strength,
+                }));
+                assets.release_image(uri);
+            }
+            Err(e) => crate::log_warn!("color grading LUT {uri}: {e}"),
+        }
+    }
+}
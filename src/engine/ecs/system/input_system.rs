@@ -1,23 +1,37 @@
+use crate::engine::action_map::ActionMap;
 use crate::engine::ecs::ComponentId;
 use crate::engine::ecs::World;
-use crate::engine::ecs::component::{InputComponent, TransformComponent};
+use crate::engine::ecs::component::{InputComponent, InputTarget, TransformComponent};
 use crate::engine::ecs::system::System;
 use crate::engine::graphics::VisualWorld;
 use crate::engine::user_input::InputState;
-use winit::keyboard::Key;
+use glam::{Quat, Vec3};
 
-/// System that processes input components and updates transforms based on WASD input.
+/// System that moves Transforms from `InputComponent` axes.
 ///
-/// Intended topology (simple one-way data flow):
-/// InputComponent -> TransformComponent -> (Camera2DComponent, RenderableComponent, ...)
-#[derive(Debug, Default)]
+/// Keys are never read directly: each InputComponent names action-map axes, and `actions`
+/// decides which keys/buttons feed them. The driven Transform is picked by
+/// `InputComponent::target` (see `InputTarget`).
+#[derive(Debug)]
 pub struct InputSystem {
     inputs: Vec<ComponentId>,
+
+    /// Bindings used by every InputComponent.
+    pub actions: ActionMap,
+}
+
+impl Default for InputSystem {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl InputSystem {
     pub fn new() -> Self {
-        Self { inputs: Vec::new() }
+        Self {
+            inputs: Vec::new(),
+            actions: ActionMap::with_default_bindings(),
+        }
     }
 
     /// Register an InputComponent.
@@ -28,141 +42,114 @@ impl InputSystem {
     }
 
     fn compute_transform(
-        &self,
         speed_units_per_sec: f32,
-        input: &InputState,
+        axes: [f32; 2],
+        roll: f32,
         dt_sec: f32,
         transform: &mut crate::engine::graphics::primitives::Transform,
     ) {
-        // Read movement keys.
-        let w = input.key_down(&Key::Character("w".into()))
-            || input.key_down(&Key::Character("W".into()));
-        let a = input.key_down(&Key::Character("a".into()))
-            || input.key_down(&Key::Character("A".into()));
-        let s = input.key_down(&Key::Character("s".into()))
-            || input.key_down(&Key::Character("S".into()));
-        let d = input.key_down(&Key::Character("d".into()))
-            || input.key_down(&Key::Character("D".into()));
-
-        // Roll keys.
-        let q = input.key_down(&Key::Character("q".into()))
-            || input.key_down(&Key::Character("Q".into()));
-        let e = input.key_down(&Key::Character("e".into()))
-            || input.key_down(&Key::Character("E".into()));
-
         // Roll around Z first so translation happens "after" rotation.
-        if q || e {
+        if roll != 0.0 {
             const ROT_SPEED_RAD_PER_SEC: f32 = 1.5;
-            let dir = (e as i32) as f32 - (q as i32) as f32;
-            let dtheta = dir * ROT_SPEED_RAD_PER_SEC * dt_sec;
-            let (sz, cz) = (0.5 * dtheta).sin_cos();
-            let qz = [0.0f32, 0.0f32, sz, cz];
-
-            fn quat_mul(a: [f32; 4], b: [f32; 4]) -> [f32; 4] {
-                let (ax, ay, az, aw) = (a[0], a[1], a[2], a[3]);
-                let (bx, by, bz, bw) = (b[0], b[1], b[2], b[3]);
-                [
-                    aw * bx + ax * bw + ay * bz - az * by,
-                    aw * by - ax * bz + ay * bw + az * bx,
-                    aw * bz + ax * by - ay * bx + az * bw,
-                    aw * bw - ax * bx - ay * by - az * bz,
-                ]
-            }
-
+            let dtheta = roll * ROT_SPEED_RAD_PER_SEC * dt_sec;
             // Apply local Z-roll increment.
-            transform.rotation = quat_mul(transform.rotation, qz);
-        }
-
-        // Translation delta.
-        let mut dx = 0.0f32;
-        let mut dy = 0.0f32;
-        if w {
-            dy -= 1.0;
-        }
-        if s {
-            dy += 1.0;
-        }
-        if a {
-            dx -= 1.0;
-        }
-        if d {
-            dx += 1.0;
+            transform.rotation *= Quat::from_rotation_z(dtheta);
         }
 
         // Normalize diagonal movement.
+        let [mut dx, mut dy] = axes;
         let len = (dx * dx + dy * dy).sqrt();
-        if len > 0.0 {
+        if len > 1.0 {
             dx /= len;
             dy /= len;
         }
 
         // Translate after rotation, in the transform's local (rotated) axes.
         let speed = speed_units_per_sec * dt_sec;
-        let (qz, qw) = (transform.rotation[2], transform.rotation[3]);
-        let theta = 2.0 * qz.atan2(qw);
-        let (sin_theta, cos_theta) = theta.sin_cos();
-        let local_dx = cos_theta * dx - sin_theta * dy;
-        let local_dy = sin_theta * dx + cos_theta * dy;
-
-        transform.translation[0] += local_dx * speed;
-        transform.translation[1] += local_dy * speed;
+        let local = transform.rotation * Vec3::new(dx, dy, 0.0);
+        transform.translation += local * speed;
 
         transform.recompute_model();
     }
 
+    /// Resolve the Transform an InputComponent drives, or `None` if the topology doesn't have
+    /// one for its target mode.
+    fn target_transform(
+        world: &World,
+        input_cid: ComponentId,
+        target: InputTarget,
+        active_camera: Option<ComponentId>,
+    ) -> Option<ComponentId> {
+        let nearest_ancestor = |cid: ComponentId| {
+            let mut cur = cid;
+            while let Some(parent) = world.parent_of(cur) {
+                if world
+                    .get_component_by_id_as::<TransformComponent>(parent)
+                    .is_some()
+                {
+                    return Some(parent);
+                }
+                cur = parent;
+            }
+            None
+        };
+
+        match target {
+            InputTarget::OwnTransform => {
+                world.children_of(input_cid).iter().copied().find(|&cid| {
+                    world
+                        .get_component_by_id_as::<TransformComponent>(cid)
+                        .is_some()
+                })
+            }
+            InputTarget::ParentTransform => nearest_ancestor(input_cid),
+            InputTarget::Camera => nearest_ancestor(active_camera?),
+        }
+    }
+
     /// Process input and queue at most one transform update per InputComponent.
     ///
-    /// This only supports the intended topology:
-    /// InputComponent -> TransformComponent (child)
+    /// `active_camera` is the active camera's component id, used by `InputTarget::Camera`.
     pub fn process_input(
         &mut self,
         world: &mut World,
         input: &InputState,
         queue: &mut crate::engine::ecs::CommandQueue,
         dt_sec: f32,
+        active_camera: Option<ComponentId>,
     ) {
-        // We gate early to avoid scanning inputs if nothing relevant is pressed.
-        let any_move = input.key_down(&Key::Character("w".into()))
-            || input.key_down(&Key::Character("W".into()))
-            || input.key_down(&Key::Character("a".into()))
-            || input.key_down(&Key::Character("A".into()))
-            || input.key_down(&Key::Character("s".into()))
-            || input.key_down(&Key::Character("S".into()))
-            || input.key_down(&Key::Character("d".into()))
-            || input.key_down(&Key::Character("D".into()))
-            || input.key_down(&Key::Character("q".into()))
-            || input.key_down(&Key::Character("Q".into()))
-            || input.key_down(&Key::Character("e".into()))
-            || input.key_down(&Key::Character("E".into()));
-
-        if !any_move {
-            return;
-        }
-
         for &input_cid in &self.inputs {
-            let speed_units_per_sec =
-                match world.get_component_by_id_as::<InputComponent>(input_cid) {
-                    Some(input_comp) => input_comp.speed,
-                    None => continue,
-                };
-
-            // Find TransformComponent child. If absent, we don't compute.
-            let transform_child = world.children_of(input_cid).iter().copied().find(|&cid| {
-                world
-                    .get_component_by_id_as::<TransformComponent>(cid)
-                    .is_some()
-            });
+            let Some(input_comp) = world.get_component_by_id_as::<InputComponent>(input_cid) else {
+                continue;
+            };
+
+            let axes = [
+                self.actions.axis(input, &input_comp.move_axes.0),
+                self.actions.axis(input, &input_comp.move_axes.1),
+            ];
+            let roll = input_comp
+                .roll_axis
+                .as_deref()
+                .map(|axis| self.actions.axis(input, axis))
+                .unwrap_or(0.0);
+            if axes == [0.0, 0.0] && roll == 0.0 {
+                continue;
+            }
+            let speed_units_per_sec = input_comp.speed;
 
-            let Some(transform_cid) = transform_child else {
+            let Some(transform_cid) =
+                Self::target_transform(world, input_cid, input_comp.target, active_camera)
+            else {
                 continue;
             };
 
             if let Some(transform_comp_mut) =
                 world.get_component_by_id_as_mut::<TransformComponent>(transform_cid)
             {
-                self.compute_transform(
+                Self::compute_transform(
                     speed_units_per_sec,
-                    input,
+                    axes,
+                    roll,
                     dt_sec,
                     &mut transform_comp_mut.transform,
                 );
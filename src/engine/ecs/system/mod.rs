@@ -1,20 +1,85 @@
+pub mod background_system;
 pub mod camera_system;
+pub mod character_system;
+pub mod clip_rect_system;
+pub mod color_grading_system;
 pub mod input_system;
 pub mod light_system;
 pub mod lit_voxel_system;
+pub mod movement_system;
+pub mod nav_agent_system;
+pub mod nine_slice_system;
 pub mod renderable_system;
+pub mod schedule;
+pub mod screen_anchor_system;
+pub mod state_machine_system;
+pub mod static_batch_system;
 pub mod system_world;
+pub mod texture_streaming;
 pub mod texture_system;
+pub mod tilemap_system;
+pub mod timer_system;
 pub mod transform_system;
+pub mod trigger_system;
+pub mod xr_pose_system;
 
-pub use camera_system::{Camera3D, CameraHandle, CameraSystem};
+#[cfg(test)]
+mod background_system_tests;
+#[cfg(test)]
+mod camera_system_tests;
+#[cfg(test)]
+mod character_system_tests;
+#[cfg(test)]
+mod clip_rect_system_tests;
+#[cfg(test)]
+mod movement_system_tests;
+#[cfg(test)]
+mod nav_agent_system_tests;
+#[cfg(test)]
+mod renderable_system_tests;
+#[cfg(test)]
+mod schedule_tests;
+#[cfg(test)]
+mod screen_anchor_system_tests;
+#[cfg(test)]
+mod state_machine_system_tests;
+#[cfg(test)]
+mod static_batch_system_tests;
+#[cfg(test)]
+mod texture_streaming_tests;
+#[cfg(test)]
+mod tilemap_system_tests;
+#[cfg(test)]
+mod timer_system_tests;
+#[cfg(test)]
+mod trigger_system_tests;
+#[cfg(test)]
+mod xr_pose_system_tests;
+
+pub use background_system::BackgroundSystem;
+pub use camera_system::{Camera3D, CameraEffects, CameraHandle, CameraSystem};
+pub use character_system::CharacterSystem;
+pub use clip_rect_system::ClipRectSystem;
+pub use color_grading_system::ColorGradingSystem;
 pub use input_system::InputSystem;
 pub use light_system::LightSystem;
 pub use lit_voxel_system::LitVoxelSystem;
+pub use movement_system::MovementSystem;
+pub use nav_agent_system::NavAgentSystem;
+pub use nine_slice_system::NineSliceSystem;
 pub use renderable_system::RenderableSystem;
-pub use system_world::SystemWorld;
+pub use schedule::{Paused, RunCriteria, Schedule, SystemId};
+pub use screen_anchor_system::ScreenAnchorSystem;
+pub use state_machine_system::{PendingCommands, StateMachineSystem};
+pub use static_batch_system::StaticBatchSystem;
+pub use system_world::{EnabledSystems, SystemWorld};
+pub use texture_streaming::{TextureStreamingPolicy, TextureStreamingStats};
 pub use texture_system::TextureSystem;
+pub use tilemap_system::TilemapSystem;
+pub use timer_system::{Scheduler, Timer, TimerAction, TimerCallback, TimerId, TimerSystem};
 pub use transform_system::TransformSystem;
+pub use trigger_system::{TriggerEvent, TriggerEvents, TriggerPhase, TriggerSystem};
+pub use xr_pose_system::XrPoseSystem;
 
 use super::World;
 use crate::engine::graphics::VisualWorld;
@@ -0,0 +1,108 @@
+use crate::engine::ecs::component::{TransformComponent, VelocityComponent};
+use crate::engine::ecs::{CommandQueue, ComponentId, World};
+use glam::{Quat, Vec3};
+
+/// Fixed integration step, independent of the frame rate.
+pub const MOVEMENT_FIXED_DT: f32 = 1.0 / 60.0;
+
+/// Longest frame time integrated in one tick; anything beyond is dropped so a stall doesn't
+/// teleport everything.
+const MAX_FRAME_DT: f32 = 0.25;
+
+/// Integrates `VelocityComponent`s into their parent Transforms on a fixed timestep.
+#[derive(Debug, Default)]
+pub struct MovementSystem {
+    velocities: Vec<ComponentId>,
+
+    /// Frame time not yet consumed by a fixed step.
+    accumulator: f32,
+}
+
+impl MovementSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register_velocity(&mut self, world: &World, component: ComponentId) {
+        if world
+            .get_component_by_id_as::<VelocityComponent>(component)
+            .is_none()
+        {
+            return;
+        }
+        if !self.velocities.contains(&component) {
+            self.velocities.push(component);
+        }
+    }
+
+    /// Run as many fixed steps as `dt_sec` covers and queue UPDATE_TRANSFORM for every moved
+    /// Transform.
+    pub fn tick(&mut self, world: &mut World, queue: &mut CommandQueue, dt_sec: f32) {
+        self.accumulator += dt_sec.clamp(0.0, MAX_FRAME_DT);
+        let mut steps = 0;
+        while self.accumulator >= MOVEMENT_FIXED_DT {
+            self.accumulator -= MOVEMENT_FIXED_DT;
+            steps += 1;
+        }
+        if steps == 0 {
+            return;
+        }
+
+        let velocities = self.velocities.clone();
+        for velocity_cid in velocities {
+            let Some(mut velocity) = world
+                .get_component_by_id_as::<VelocityComponent>(velocity_cid)
+                .copied()
+            else {
+                self.velocities.retain(|c| *c != velocity_cid);
+                continue;
+            };
+            let Some(parent) = world.parent_of(velocity_cid) else {
+                continue;
+            };
+            let Some(t) = world.get_component_by_id_as_mut::<TransformComponent>(parent) else {
+                continue;
+            };
+
+            let mut moved = false;
+            for _ in 0..steps {
+                moved |= Self::step(&mut velocity, &mut t.transform, MOVEMENT_FIXED_DT);
+            }
+            if !moved {
+                continue;
+            }
+            t.transform.recompute_model();
+            queue.queue_update_transform(parent, t.transform);
+
+            if let Some(v) = world.get_component_by_id_as_mut::<VelocityComponent>(velocity_cid) {
+                *v = velocity;
+            }
+        }
+    }
+
+    /// One explicit Euler step. Returns `false` if nothing moved.
+    fn step(
+        velocity: &mut VelocityComponent,
+        transform: &mut crate::engine::graphics::primitives::Transform,
+        dt: f32,
+    ) -> bool {
+        let linear = Vec3::from(velocity.linear);
+        let angular = Vec3::from(velocity.angular);
+        if linear == Vec3::ZERO && angular == Vec3::ZERO {
+            return false;
+        }
+
+        transform.translation += linear * dt;
+        if angular != Vec3::ZERO {
+            transform.rotation =
+                (Quat::from_scaled_axis(angular * dt) * transform.rotation).normalize();
+        }
+
+        if velocity.damping > 0.0 {
+            let decay = (-velocity.damping * dt).exp();
+            velocity.linear = (linear * decay).to_array();
+            velocity.angular = (angular * decay).to_array();
+        }
+        true
+    }
+}
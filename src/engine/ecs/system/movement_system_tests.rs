@@ -0,0 +1,35 @@
+#[cfg(test)]
+mod tests {
+    use crate::engine::ecs::component::{TransformComponent, VelocityComponent};
+    use crate::engine::ecs::system::MovementSystem;
+    use crate::engine::ecs::system::movement_system::MOVEMENT_FIXED_DT;
+    use crate::engine::ecs::{CommandQueue, World};
+
+    #[test]
+    fn integrates_parent_transform_in_fixed_steps() {
+        let mut world = World::default();
+        let mut queue = CommandQueue::new();
+        let t = world.add_component(TransformComponent::new());
+        let v = world.add_component(VelocityComponent::new(6.0, 0.0, 0.0));
+        world.add_child(t, v).unwrap();
+
+        let mut movement = MovementSystem::new();
+        movement.register_velocity(&world, v);
+
+        // Less than one step: nothing moves yet.
+        let x = |world: &World| {
+            world
+                .get_component_by_id_as::<TransformComponent>(t)
+                .unwrap()
+                .transform
+                .translation
+                .x
+        };
+        movement.tick(&mut world, &mut queue, MOVEMENT_FIXED_DT * 0.5);
+        assert_eq!(x(&world), 0.0);
+
+        movement.tick(&mut world, &mut queue, MOVEMENT_FIXED_DT * 10.0);
+        let x = x(&world);
+        assert!((x - 6.0 * MOVEMENT_FIXED_DT * 10.0).abs() < 1e-4, "x = {x}");
+    }
+}
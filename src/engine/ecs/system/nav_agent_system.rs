@@ -0,0 +1,132 @@
+use glam::{Mat4, Vec2};
+
+use crate::engine::ai::NavGrid;
+use crate::engine::ecs::component::{NavAgentComponent, TransformComponent};
+use crate::engine::ecs::system::TransformSystem;
+use crate::engine::ecs::{CommandQueue, ComponentId, World};
+
+/// Moves `NavAgentComponent`s along paths on the world's `NavGrid` resource.
+#[derive(Debug, Default)]
+pub struct NavAgentSystem {
+    agents: Vec<ComponentId>,
+}
+
+impl NavAgentSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register_agent(&mut self, world: &World, component: ComponentId) {
+        if world
+            .get_component_by_id_as::<NavAgentComponent>(component)
+            .is_some()
+            && !self.agents.contains(&component)
+        {
+            self.agents.push(component);
+        }
+    }
+
+    /// Replan where needed, step every agent along its path and queue UPDATE_TRANSFORM for the
+    /// ones that moved.
+    pub fn tick(&mut self, world: &mut World, queue: &mut CommandQueue, dt_sec: f32) {
+        let agents = self.agents.clone();
+        for cid in agents {
+            let Some(mut agent) = world
+                .get_component_by_id_as::<NavAgentComponent>(cid)
+                .cloned()
+            else {
+                self.agents.retain(|c| *c != cid);
+                continue;
+            };
+            let Some(parent) = world.parent_of(cid) else {
+                continue;
+            };
+            let Some(local) = world
+                .get_component_by_id_as::<TransformComponent>(parent)
+                .map(|t| t.transform.translation)
+            else {
+                continue;
+            };
+            // Space the Transform's translation is in.
+            let outer = TransformSystem::world_model(world, parent)
+                .map_or(Mat4::IDENTITY, |m| Mat4::from_cols_array_2d(&m));
+            let start = outer.transform_point3(local);
+
+            let goal = TransformSystem::world_model_including(world, agent.target.id)
+                .map(|m| Mat4::from_cols_array_2d(&m).w_axis.truncate().truncate());
+            let mut pos = start.truncate();
+            match goal {
+                Some(goal) if pos.distance(goal) > agent.stop_distance => {
+                    Self::plan(&mut agent, world.resource::<NavGrid>(), pos, goal);
+                    let budget = agent.speed * dt_sec.max(0.0);
+                    pos = Self::advance(&mut agent.path, pos, budget, agent.stop_distance);
+                }
+                _ => {
+                    agent.path.clear();
+                    agent.planned_for = None;
+                }
+            }
+
+            if let Some(a) = world.get_component_by_id_as_mut::<NavAgentComponent>(cid) {
+                a.path = agent.path;
+                a.planned_for = agent.planned_for;
+            }
+            if pos == start.truncate() {
+                continue;
+            }
+            let Some(t) = world.get_component_by_id_as_mut::<TransformComponent>(parent) else {
+                continue;
+            };
+            t.transform.translation = outer.inverse().transform_point3(pos.extend(start.z));
+            t.transform.recompute_model();
+            queue.queue_update_transform(parent, t.transform);
+        }
+    }
+
+    /// Keep `agent.path` leading to `goal`: replan when the goal changed cells, otherwise just
+    /// move the last waypoint onto it.
+    fn plan(agent: &mut NavAgentComponent, grid: Option<&NavGrid>, pos: Vec2, goal: Vec2) {
+        let Some(grid) = grid else {
+            agent.path = vec![goal.to_array()];
+            return;
+        };
+        let goal_cell = grid.cell_at(goal.to_array());
+        if goal_cell != agent.planned_for {
+            agent.planned_for = goal_cell;
+            agent.path = grid
+                .find_path(pos.to_array(), goal.to_array())
+                .unwrap_or_default();
+        } else if let Some(last) = agent.path.last_mut() {
+            *last = goal.to_array();
+        } else if grid.cell_at(pos.to_array()) == goal_cell {
+            agent.path = vec![goal.to_array()];
+        }
+    }
+
+    /// Walk up to `budget` units along `path` from `pos`, dropping reached waypoints. The last
+    /// one counts as reached `stop_distance` short of it.
+    fn advance(
+        path: &mut Vec<[f32; 2]>,
+        mut pos: Vec2,
+        mut budget: f32,
+        stop_distance: f32,
+    ) -> Vec2 {
+        while budget > 0.0
+            && let Some(&next) = path.first()
+        {
+            let next = Vec2::from(next);
+            let stop = if path.len() == 1 { stop_distance } else { 0.0 };
+            let reach = (pos.distance(next) - stop).max(0.0);
+            let dir = (next - pos).normalize_or_zero();
+            if reach <= budget {
+                pos += dir * reach;
+                budget -= reach;
+                path.remove(0);
+            } else {
+                pos += dir * budget;
+                budget = 0.0;
+            }
+        }
+        pos
+    }
+}
@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod tests {
+    use crate::engine::ai::NavGrid;
+    use crate::engine::ecs::component::{NavAgentComponent, TransformComponent};
+    use crate::engine::ecs::system::NavAgentSystem;
+    use crate::engine::ecs::{CommandQueue, World};
+    use crate::engine::spatial::Aabb;
+
+    #[test]
+    fn agent_walks_around_blocked_cells_to_its_target() {
+        let mut world = World::default();
+        let mut nav = NavGrid::new(Aabb::new([0.0; 3], [5.0, 5.0, 0.0]), 1.0);
+        for y in 0..4 {
+            nav.set_blocked((2, y), true);
+        }
+        world.insert_resource(nav);
+
+        let goal = world.add_component(TransformComponent::new().with_position(4.5, 0.5, 0.0));
+        let npc = world.add_component(TransformComponent::new().with_position(0.5, 0.5, 0.0));
+        let agent = world.add_component(NavAgentComponent::new(world.component_ref(goal), 4.0));
+        world.add_child(npc, agent).unwrap();
+
+        let mut agents = NavAgentSystem::new();
+        agents.register_agent(&world, agent);
+        let mut queue = CommandQueue::new();
+        let grid = world.resource::<NavGrid>().unwrap().clone();
+        for _ in 0..240 {
+            agents.tick(&mut world, &mut queue, 1.0 / 60.0);
+            let p = world
+                .get_component_by_id_as::<TransformComponent>(npc)
+                .unwrap()
+                .transform
+                .translation;
+            let cell = grid.cell_at([p.x, p.y]).unwrap();
+            assert!(!grid.is_blocked(cell), "walked into {cell:?}");
+        }
+
+        let p = world
+            .get_component_by_id_as::<TransformComponent>(npc)
+            .unwrap()
+            .transform
+            .translation;
+        assert!(
+            (p.truncate() - glam::Vec2::new(4.5, 0.5)).length() <= 0.11,
+            "{p}"
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use crate::engine::ecs::component::{NineSliceComponent, RenderableComponent, TextureComponent};
+use crate::engine::ecs::system::{RenderableSystem, TextureSystem};
+use crate::engine::ecs::{ComponentId, World};
+use crate::engine::graphics::mesh::MeshFactory;
+use crate::engine::graphics::primitives::{MaterialHandle, Renderable};
+use crate::engine::graphics::{RenderAssets, VisualWorld};
+use std::collections::HashMap;
+
+/// Builds 9-patch meshes for `NineSliceComponent`s.
+///
+/// Each panel owns one child `RenderableComponent` (and `TextureComponent`), created on first
+/// flush; later size/border changes replace the renderable's mesh (and release the old one).
+#[derive(Debug, Default)]
+pub struct NineSliceSystem {
+    /// NineSliceComponent cid -> child RenderableComponent cid
+    panels: HashMap<ComponentId, Option<ComponentId>>,
+}
+
+impl NineSliceSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register_nine_slice(
+        &mut self,
+        world: &mut World,
+        _visuals: &mut VisualWorld,
+        component: ComponentId,
+    ) {
+        if world
+            .get_component_by_id_as::<NineSliceComponent>(component)
+            .is_none()
+        {
+            return;
+        }
+        self.panels.entry(component).or_insert(None);
+    }
+
+    /// Rebuild meshes for panels whose size/border changed.
+    ///
+    /// Must run before `RenderableSystem::flush_pending`.
+    pub fn flush_pending(
+        &mut self,
+        world: &mut World,
+        visuals: &mut VisualWorld,
+        render_assets: &mut RenderAssets,
+        renderables: &mut RenderableSystem,
+        textures: &mut TextureSystem,
+    ) {
+        let panels: Vec<(ComponentId, Option<ComponentId>)> =
+            self.panels.iter().map(|(&p, &r)| (p, r)).collect();
+        for (panel_cid, renderable_cid) in panels {
+            let Some(panel) = world.get_component_by_id_as_mut::<NineSliceComponent>(panel_cid)
+            else {
+                self.panels.remove(&panel_cid);
+                continue;
+            };
+            if !panel.take_dirty() {
+                continue;
+            }
+
+            let mesh = MeshFactory::nine_slice(panel.size, panel.border_px, panel.uv_border());
+            let texture = panel.texture.clone();
+            let cpu_mesh = render_assets.register_mesh(mesh);
+
+            match renderable_cid {
+                Some(renderable_cid) => {
+                    renderables.replace_mesh(world, renderable_cid, cpu_mesh);
+                }
+                None => {
+                    let renderable_cid = world.add_component(RenderableComponent::new(
+                        Renderable::new(cpu_mesh, MaterialHandle::TOON_MESH),
+                    ));
+                    let _ = world.add_child(panel_cid, renderable_cid);
+                    renderables.register_renderable(world, visuals, renderable_cid);
+
+                    let tex = world.add_component(TextureComponent::new(texture));
+                    let _ = world.add_child(renderable_cid, tex);
+                    textures.register_texture(world, visuals, tex);
+
+                    self.panels.insert(panel_cid, Some(renderable_cid));
+                }
+            }
+        }
+    }
+}
@@ -1,14 +1,18 @@
 use crate::engine::ecs::ComponentId;
-use crate::engine::ecs::component::{ColorComponent, RenderableComponent, UVComponent};
+use crate::engine::ecs::component::{
+    ColorComponent, LodComponent, RenderableComponent, UVComponent,
+};
 
 use crate::engine::ecs::World;
+use crate::engine::ecs::system::ClipRectSystem;
 use crate::engine::ecs::system::System;
 use crate::engine::ecs::system::TransformSystem;
 use crate::engine::graphics::primitives::{CpuMeshHandle, MaterialHandle, Transform};
 use crate::engine::graphics::{GpuRenderable, VisualWorld};
 use crate::engine::graphics::{MeshUploader, RenderAssets};
+use crate::engine::spatial::{Aabb, SpatialGrid};
 use crate::engine::user_input::InputState;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// System that registers/updates renderables in the `VisualWorld`.
 ///
@@ -35,6 +39,47 @@ pub struct RenderableSystem {
     ///
     /// Keyed by the RenderableComponent's ComponentId.
     pending_color: HashMap<ComponentId, [f32; 4]>,
+
+    /// LOD selection state for renderables that have a `LodComponent` descendant.
+    ///
+    /// Keyed by the RenderableComponent's ComponentId.
+    lods: HashMap<ComponentId, LodState>,
+
+    /// Mesh swaps for registered renderables (LOD selection, tilemap chunk rebuilds), applied in
+    /// `flush_pending` (needs RenderAssets).
+    ///
+    /// Keyed by the RenderableComponent's ComponentId.
+    pending_mesh_swap: HashMap<ComponentId, MeshSwap>,
+
+    /// Generated meshes no renderable draws anymore, released in `flush_pending` (needs the
+    /// uploader).
+    pending_release: Vec<CpuMeshHandle>,
+
+    /// Meshes that failed to upload for a swap. LOD selection skips them (keeping the current
+    /// level) instead of retrying and logging every frame.
+    failed_meshes: HashSet<CpuMeshHandle>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct MeshSwap {
+    mesh: CpuMeshHandle,
+    /// The renderable owns the mesh it replaces (see `replace_mesh`): release it afterwards.
+    release_replaced: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct LodState {
+    lod_cid: ComponentId,
+    /// Currently selected index into `LodComponent::meshes` (None until first evaluation).
+    level: Option<usize>,
+    /// Camera/object positions at the last evaluation, for hysteresis.
+    camera: [f32; 3],
+    object: [f32; 3],
+}
+
+fn distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+    let d = [a[0] - b[0], a[1] - b[1], a[2] - b[2]];
+    (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt()
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -122,9 +167,10 @@ impl RenderableSystem {
             let mesh = match render_assets.gpu_mesh_handle(uploader, new_mesh) {
                 Ok(h) => h,
                 Err(err) => {
-                    println!(
-                        "[RenderableSystem]  -> gpu_mesh_handle failed for cpu_mesh={:?}: {:?}",
-                        new_mesh, err
+                    crate::log_error!(
+                        "gpu_mesh_handle failed for cpu_mesh={:?}: {}",
+                        new_mesh,
+                        err
                     );
                     continue;
                 }
@@ -177,7 +223,8 @@ impl RenderableSystem {
             return;
         };
 
-        self.pending_color.insert(renderable_cid, color_comp.rgba);
+        self.pending_color
+            .insert(renderable_cid, color_comp.rgba.to_array());
     }
 
     pub fn register_uv(
@@ -211,6 +258,266 @@ impl RenderableSystem {
         self.pending_uv.insert(renderable_cid, uv_comp.uvs.clone());
     }
 
+    pub fn register_lod(
+        &mut self,
+        world: &mut World,
+        _visuals: &mut VisualWorld,
+        component: ComponentId,
+    ) {
+        if world
+            .get_component_by_id_as::<LodComponent>(component)
+            .is_none()
+        {
+            return;
+        }
+        // Find the ancestor RenderableComponent that this LodComponent should apply to.
+        let mut cur = component;
+        let mut renderable_cid: Option<ComponentId> = None;
+        while let Some(parent) = world.parent_of(cur) {
+            if world
+                .get_component_by_id_as::<RenderableComponent>(parent)
+                .is_some()
+            {
+                renderable_cid = Some(parent);
+                break;
+            }
+            cur = parent;
+        }
+        let Some(renderable_cid) = renderable_cid else {
+            return;
+        };
+
+        self.lods.insert(
+            renderable_cid,
+            LodState {
+                lod_cid: component,
+                level: None,
+                camera: [0.0; 3],
+                object: [0.0; 3],
+            },
+        );
+    }
+
+    /// Pick LOD levels for registered renderables.
+    ///
+    /// A renderable is only re-evaluated when the camera or the object moved further than the
+    /// LodComponent's hysteresis since its last evaluation.
+    fn select_lods(&mut self, world: &World, visuals: &VisualWorld) {
+        let camera = visuals.camera_position();
+
+        let mut dead: Vec<ComponentId> = Vec::new();
+        for (&renderable_cid, state) in self.lods.iter_mut() {
+            let Some(lod) = world.get_component_by_id_as::<LodComponent>(state.lod_cid) else {
+                dead.push(renderable_cid);
+                continue;
+            };
+            let Some(renderable_comp) =
+                world.get_component_by_id_as::<RenderableComponent>(renderable_cid)
+            else {
+                dead.push(renderable_cid);
+                continue;
+            };
+            if renderable_comp.get_handle().is_none() || lod.meshes.is_empty() {
+                continue;
+            }
+            let Some(object) = TransformSystem::world_position(world, renderable_cid) else {
+                continue;
+            };
+
+            if state.level.is_some()
+                && distance(camera, state.camera) <= lod.hysteresis
+                && distance(object, state.object) <= lod.hysteresis
+            {
+                continue;
+            }
+
+            state.camera = camera;
+            state.object = object;
+
+            let level = lod.level_for_distance(distance(camera, object));
+            let mesh = lod.meshes[level].0;
+            if self.failed_meshes.contains(&mesh) {
+                continue;
+            }
+            state.level = Some(level);
+            if renderable_comp.renderable.mesh != mesh {
+                self.pending_mesh_swap.insert(
+                    renderable_cid,
+                    MeshSwap {
+                        mesh,
+                        release_replaced: false,
+                    },
+                );
+            }
+        }
+
+        for cid in dead {
+            self.lods.remove(&cid);
+            self.pending_mesh_swap.remove(&cid);
+        }
+    }
+
+    fn apply_pending_mesh_swaps(
+        &mut self,
+        world: &mut World,
+        visuals: &mut VisualWorld,
+        render_assets: &mut RenderAssets,
+        uploader: &mut dyn MeshUploader,
+        spatial: &mut SpatialGrid,
+    ) {
+        let keys: Vec<ComponentId> = self.pending_mesh_swap.keys().copied().collect();
+        for renderable_cid in keys {
+            let Some(swap) = self.pending_mesh_swap.get(&renderable_cid).copied() else {
+                continue;
+            };
+            let cpu_mesh = swap.mesh;
+            let Some(renderable_comp) =
+                world.get_component_by_id_as::<RenderableComponent>(renderable_cid)
+            else {
+                let _ = self.pending_mesh_swap.remove(&renderable_cid);
+                continue;
+            };
+            let Some(handle) = renderable_comp.get_handle() else {
+                continue;
+            };
+            let material = renderable_comp.renderable.material;
+
+            let mesh = match render_assets.gpu_mesh_handle(uploader, cpu_mesh) {
+                Ok(h) => h,
+                Err(err) => {
+                    crate::log_error!(
+                        "gpu_mesh_handle failed for swapped cpu_mesh={:?}: {}",
+                        cpu_mesh,
+                        err
+                    );
+                    // Keep drawing the current mesh rather than retrying every frame.
+                    self.failed_meshes.insert(cpu_mesh);
+                    let _ = self.pending_mesh_swap.remove(&renderable_cid);
+                    if swap.release_replaced {
+                        self.pending_release.push(cpu_mesh);
+                    }
+                    continue;
+                }
+            };
+
+            let Some(model) = TransformSystem::world_model(world, renderable_cid) else {
+                continue;
+            };
+            let transform = Transform {
+                model,
+                ..Default::default()
+            };
+            let _ = visuals.update(handle, GpuRenderable { mesh, material }, transform);
+
+            if let Some(local) = render_assets
+                .cpu_mesh(cpu_mesh)
+                .and_then(|m| Aabb::from_points(m.vertices.iter().map(|v| v.pos)))
+            {
+                spatial.insert(renderable_cid, local, &model);
+            }
+
+            if let Some(renderable_comp) =
+                world.get_component_by_id_as_mut::<RenderableComponent>(renderable_cid)
+            {
+                let replaced = std::mem::replace(&mut renderable_comp.renderable.mesh, cpu_mesh);
+                if swap.release_replaced && replaced != cpu_mesh {
+                    self.pending_release.push(replaced);
+                }
+            }
+
+            let _ = self.pending_mesh_swap.remove(&renderable_cid);
+        }
+    }
+
+    fn release_pending_meshes(
+        &mut self,
+        render_assets: &mut RenderAssets,
+        uploader: &mut dyn MeshUploader,
+    ) {
+        for cpu_mesh in self.pending_release.drain(..) {
+            if let Err(err) = render_assets.release_mesh(uploader, cpu_mesh) {
+                crate::log_warn!("release_mesh failed for cpu_mesh={:?}: {}", cpu_mesh, err);
+            }
+        }
+    }
+
+    /// Replace the mesh of a renderable.
+    ///
+    /// The component is updated right away; the `VisualWorld` instance picks up the new mesh in
+    /// the next `flush_pending` (after upload).
+    pub fn swap_mesh(
+        &mut self,
+        world: &mut World,
+        renderable_cid: ComponentId,
+        cpu_mesh: CpuMeshHandle,
+    ) {
+        let Some(renderable_comp) =
+            world.get_component_by_id_as_mut::<RenderableComponent>(renderable_cid)
+        else {
+            return;
+        };
+        if renderable_comp.get_handle().is_none() {
+            // Not in VisualWorld yet: the pending registration uploads whatever mesh is current.
+            renderable_comp.renderable.mesh = cpu_mesh;
+            if let Some(p) = self.pending.get_mut(&renderable_cid) {
+                p.cpu_mesh = cpu_mesh;
+            }
+            return;
+        }
+        self.pending_mesh_swap.insert(
+            renderable_cid,
+            MeshSwap {
+                mesh: cpu_mesh,
+                release_replaced: false,
+            },
+        );
+    }
+
+    /// `swap_mesh` for a renderable that owns its mesh (a generated one, like a tilemap chunk):
+    /// the mesh it replaces is released once nothing draws it.
+    pub fn replace_mesh(
+        &mut self,
+        world: &mut World,
+        renderable_cid: ComponentId,
+        cpu_mesh: CpuMeshHandle,
+    ) {
+        let Some(renderable_comp) =
+            world.get_component_by_id_as_mut::<RenderableComponent>(renderable_cid)
+        else {
+            return;
+        };
+        if renderable_comp.get_handle().is_none() {
+            let replaced = std::mem::replace(&mut renderable_comp.renderable.mesh, cpu_mesh);
+            if let Some(p) = self.pending.get_mut(&renderable_cid) {
+                p.cpu_mesh = cpu_mesh;
+            }
+            if replaced != cpu_mesh {
+                self.pending_release.push(replaced);
+            }
+            return;
+        }
+        let superseded = self.pending_mesh_swap.insert(
+            renderable_cid,
+            MeshSwap {
+                mesh: cpu_mesh,
+                release_replaced: true,
+            },
+        );
+        // A swap that never got applied: nothing drew its mesh.
+        if let Some(old) = superseded
+            && old.release_replaced
+            && old.mesh != cpu_mesh
+        {
+            self.pending_release.push(old.mesh);
+        }
+    }
+
+    /// Release `cpu_mesh` at the next `flush_pending`, after its renderable is gone (a removed
+    /// tilemap chunk, say).
+    pub fn release_mesh(&mut self, cpu_mesh: CpuMeshHandle) {
+        self.pending_release.push(cpu_mesh);
+    }
+
     /// Register a renderable component with this system.
     ///
     /// This is also where we ensure a `VisualWorld` instance exists for it.
@@ -227,6 +534,37 @@ impl RenderableSystem {
         self.register_renderable_from_world(world, visuals, component);
     }
 
+    /// Forget a renderable and drop its `VisualWorld` instance.
+    ///
+    /// Instances are keyed by the RenderableComponent's `ComponentId`, so this also works after
+    /// the component itself has been removed from `World`.
+    pub fn remove_renderable(
+        &mut self,
+        world: &mut World,
+        visuals: &mut VisualWorld,
+        component: ComponentId,
+    ) {
+        self.renderables.retain(|c| *c != component);
+        self.pending.remove(&component);
+        self.pending_uv.remove(&component);
+        self.pending_color.remove(&component);
+        self.lods.remove(&component);
+        if let Some(swap) = self.pending_mesh_swap.remove(&component)
+            && swap.release_replaced
+        {
+            // Generated for this renderable and never drawn.
+            self.pending_release.push(swap.mesh);
+        }
+
+        visuals.remove_component(component);
+
+        if let Some(renderable_comp) =
+            world.get_component_by_id_as_mut::<RenderableComponent>(component)
+        {
+            renderable_comp.handle = None;
+        }
+    }
+
     /// Register a renderable by walking the component graph in `World`.
     pub fn register_renderable_from_world(
         &mut self,
@@ -239,7 +577,7 @@ impl RenderableSystem {
             let Some(renderable_comp) =
                 world.get_component_by_id_as::<RenderableComponent>(component)
             else {
-                println!("[RenderableSystem]  -> component is not RenderableComponent somehow");
+                crate::log_warn!("component {component:?} is not a RenderableComponent");
                 return;
             };
             if renderable_comp.get_handle().is_some() {
@@ -250,7 +588,7 @@ impl RenderableSystem {
         // Defer insertion into VisualWorld until the GPU mesh exists.
         let Some(renderable_comp) = world.get_component_by_id_as::<RenderableComponent>(component)
         else {
-            println!("[RenderableSystem]  -> component is not RenderableComponent somehow");
+            crate::log_warn!("component {component:?} is not a RenderableComponent");
             return;
         };
 
@@ -262,8 +600,8 @@ impl RenderableSystem {
                 renderable_cid: component,
             },
         );
-        println!(
-            "[RenderableSystem]  -> pending += 1 (pending_len={}) cpu_mesh={:?} material={:?}",
+        crate::log_trace!(
+            "pending += 1 (pending_len={}) cpu_mesh={:?} material={:?}",
             self.pending.len(),
             renderable_comp.renderable.mesh,
             renderable_comp.renderable.material
@@ -281,6 +619,7 @@ impl RenderableSystem {
         visuals: &mut VisualWorld,
         render_assets: &mut RenderAssets,
         uploader: &mut dyn MeshUploader,
+        spatial: &mut SpatialGrid,
     ) {
         // println!(
         //     "[RenderableSystem] flush_pending: pending_len={} visuals.instances={} ",
@@ -314,9 +653,10 @@ impl RenderableSystem {
             let mesh = match render_assets.gpu_mesh_handle(uploader, cpu_mesh) {
                 Ok(h) => h,
                 Err(err) => {
-                    println!(
-                        "[RenderableSystem]  -> gpu_mesh_handle failed for cpu_mesh={:?}: {:?}",
-                        cpu_mesh, err
+                    crate::log_error!(
+                        "gpu_mesh_handle failed for cpu_mesh={:?}: {}",
+                        cpu_mesh,
+                        err
                     );
                     continue;
                 }
@@ -347,6 +687,20 @@ impl RenderableSystem {
                 .unwrap_or([1.0, 1.0, 1.0, 1.0]);
 
             let handle = visuals.register(p.renderable_cid, gpu_r, transform, color, None);
+            if let Some(renderable_comp) =
+                world.get_component_by_id_as::<RenderableComponent>(p.renderable_cid)
+            {
+                visuals.update_visibility_layers(handle, renderable_comp.visibility_layers);
+                visuals.update_z_order(handle, renderable_comp.z_order);
+            }
+            visuals.update_clip(handle, ClipRectSystem::clip_of(world, p.renderable_cid));
+
+            if let Some(local) = render_assets
+                .cpu_mesh(cpu_mesh)
+                .and_then(|m| Aabb::from_points(m.vertices.iter().map(|v| v.pos)))
+            {
+                spatial.insert(p.renderable_cid, local, &model);
+            }
             if let Some(renderable_comp) =
                 world.get_component_by_id_as_mut::<RenderableComponent>(p.renderable_cid)
             {
@@ -370,23 +724,24 @@ impl RenderableSystem {
             uploader,
         );
         self.apply_pending_color_updates_to_registered_renderables(world, visuals);
+        self.apply_pending_mesh_swaps(world, visuals, render_assets, uploader, spatial);
+        self.release_pending_meshes(render_assets, uploader);
     }
 }
 
 impl System for RenderableSystem {
     fn tick(
         &mut self,
-        _world: &mut World,
-        _visuals: &mut VisualWorld,
+        world: &mut World,
+        visuals: &mut VisualWorld,
         _input: &InputState,
         _dt_sec: f32,
     ) {
-        // Intentionally a no-op for now.
-        //
         // Per your architecture: VisualWorld registration happens at component registration time
         // (RenderableComponent::init -> SystemWorld::register_renderable -> RenderableSystem::register_renderable).
         //
-        // Later, tick() can be used for per-frame sync (transform updates, material changes, etc.)
-        // once we decide how to represent those components and what events/dirty flags we have.
+        // Per-frame work here is limited to LOD selection; the chosen meshes are swapped in
+        // during `flush_pending` where RenderAssets/uploader are available.
+        self.select_lods(world, visuals);
     }
 }
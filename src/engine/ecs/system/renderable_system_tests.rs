@@ -0,0 +1,132 @@
+#[cfg(test)]
+mod tests {
+    use crate::engine::ecs::ComponentId;
+    use crate::engine::ecs::component::{LodComponent, RenderableComponent, TransformComponent};
+    use crate::engine::ecs::system::System;
+    use crate::engine::graphics::mesh::{CpuMesh, MeshFactory};
+    use crate::engine::graphics::primitives::{CpuMeshHandle, MaterialHandle, Renderable};
+    use crate::engine::graphics::{
+        MeshHandle, MeshUploader, NullUploader, RendererError, TextureHandle, TextureUploader,
+    };
+    use crate::engine::user_input::InputState;
+    use crate::engine::{RendererBackend, Universe, UniverseBuilder};
+
+    /// Fails every upload of a mesh with `fail_vertices` vertices, counting the attempts.
+    #[derive(Default)]
+    struct FlakyUploader {
+        inner: NullUploader,
+        fail_vertices: u32,
+        failed: u32,
+    }
+
+    impl MeshUploader for FlakyUploader {
+        fn upload_mesh(&mut self, mesh: &CpuMesh) -> Result<MeshHandle, RendererError> {
+            if mesh.vertex_count() == self.fail_vertices {
+                self.failed += 1;
+                return Err(RendererError::Backend("out of memory".into()));
+            }
+            self.inner.upload_mesh(mesh)
+        }
+    }
+
+    impl TextureUploader for FlakyUploader {
+        fn upload_texture_rgba8(
+            &mut self,
+            rgba: &[u8],
+            width: u32,
+            height: u32,
+        ) -> Result<TextureHandle, RendererError> {
+            self.inner.upload_texture_rgba8(rgba, width, height)
+        }
+
+        fn upload_cubemap_rgba8(
+            &mut self,
+            faces: &[u8],
+            face_size: u32,
+        ) -> Result<TextureHandle, RendererError> {
+            self.inner.upload_cubemap_rgba8(faces, face_size)
+        }
+    }
+
+    /// A renderable at the origin with `near` from 0 and `far` from 10 units away.
+    fn lod_scene(hysteresis: f32) -> (Universe, ComponentId, CpuMeshHandle, CpuMeshHandle) {
+        let mut u = UniverseBuilder::new()
+            .renderer(RendererBackend::None)
+            .repl(false)
+            .build();
+        let near = u.render_assets.register_mesh(MeshFactory::quad_2d());
+        let far = u.render_assets.register_mesh(MeshFactory::triangle_2d());
+        let root = u.world.add_component(TransformComponent::new());
+        let renderable = u
+            .world
+            .add_component(RenderableComponent::new(Renderable::new(
+                near,
+                MaterialHandle::TOON_MESH,
+            )));
+        u.world.add_child(root, renderable).unwrap();
+        let lod = u.world.add_component(
+            LodComponent::new(vec![(near, 0.0), (far, 10.0)]).with_hysteresis(hysteresis),
+        );
+        u.world.add_child(renderable, lod).unwrap();
+        u.world.init_component_tree(root, &mut u.command_queue);
+        (u, renderable, near, far)
+    }
+
+    /// Put the camera `z` units in front of the origin and run LOD selection and the flush.
+    fn frame_at(u: &mut Universe, uploader: &mut FlakyUploader, z: f32) {
+        let view = glam::Mat4::from_translation(glam::Vec3::new(0.0, 0.0, -z));
+        u.visuals.set_camera(
+            view.to_cols_array_2d(),
+            glam::Mat4::IDENTITY.to_cols_array_2d(),
+        );
+        u.systems
+            .process_commands(&mut u.world, &mut u.visuals, &mut u.command_queue);
+        u.systems
+            .renderable
+            .tick(&mut u.world, &mut u.visuals, &InputState::default(), 0.0);
+        u.prepare_render_with(uploader);
+    }
+
+    fn mesh_of(u: &Universe, renderable: ComponentId) -> CpuMeshHandle {
+        u.world
+            .get_component_by_id_as::<RenderableComponent>(renderable)
+            .unwrap()
+            .renderable
+            .mesh
+    }
+
+    #[test]
+    fn lod_follows_camera_distance_with_hysteresis() {
+        let (mut u, renderable, near, far) = lod_scene(1.0);
+        let mut uploader = FlakyUploader::default();
+        frame_at(&mut u, &mut uploader, 5.0);
+        frame_at(&mut u, &mut uploader, 5.0);
+        assert_eq!(mesh_of(&u, renderable), near);
+
+        frame_at(&mut u, &mut uploader, 10.5);
+        assert_eq!(mesh_of(&u, renderable), far);
+
+        // Back under 10, but within the hysteresis of the last evaluation: no switch.
+        frame_at(&mut u, &mut uploader, 9.8);
+        assert_eq!(mesh_of(&u, renderable), far);
+
+        frame_at(&mut u, &mut uploader, 8.0);
+        assert_eq!(mesh_of(&u, renderable), near);
+    }
+
+    #[test]
+    fn failed_lod_upload_keeps_the_current_mesh_without_retrying() {
+        let (mut u, renderable, near, _) = lod_scene(0.0);
+        let mut uploader = FlakyUploader {
+            fail_vertices: MeshFactory::triangle_2d().vertex_count(),
+            ..FlakyUploader::default()
+        };
+        frame_at(&mut u, &mut uploader, 5.0);
+        frame_at(&mut u, &mut uploader, 5.0);
+        for z in [20.0, 21.0, 22.0] {
+            frame_at(&mut u, &mut uploader, z);
+        }
+        assert_eq!(mesh_of(&u, renderable), near);
+        assert_eq!(uploader.failed, 1);
+    }
+}
@@ -0,0 +1,171 @@
+//! When each stage of `SystemWorld::tick` runs.
+//!
+//! Every stage can be switched off (`EnabledSystems`) and has a `RunCriteria`: always, only
+//! while the world's `Paused` resource is unset, or every n-th tick. A stage that skips ticks
+//! for its criteria gets the skipped time added to its next `dt`, so movement covers the same
+//! distance at any rate. REPL `system list`, `system disable renderable` and
+//! `system run movement every 2` change them live.
+
+use std::fmt;
+
+/// The stages of `SystemWorld::tick`, in the order they run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SystemId {
+    Input,
+    CameraInput,
+    Movement,
+    Character,
+    Navigation,
+    Timers,
+    StateMachine,
+    XrPose,
+    Transform,
+    Renderable,
+    Camera,
+    Lighting,
+    Trigger,
+}
+
+impl SystemId {
+    pub const ALL: [SystemId; 13] = [
+        SystemId::Input,
+        SystemId::CameraInput,
+        SystemId::Movement,
+        SystemId::Character,
+        SystemId::Navigation,
+        SystemId::Timers,
+        SystemId::StateMachine,
+        SystemId::XrPose,
+        SystemId::Transform,
+        SystemId::Renderable,
+        SystemId::Camera,
+        SystemId::Lighting,
+        SystemId::Trigger,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SystemId::Input => "input",
+            SystemId::CameraInput => "camera_input",
+            SystemId::Movement => "movement",
+            SystemId::Character => "character",
+            SystemId::Navigation => "navigation",
+            SystemId::Timers => "timers",
+            SystemId::StateMachine => "state_machine",
+            SystemId::XrPose => "xr_pose",
+            SystemId::Transform => "transform",
+            SystemId::Renderable => "renderable",
+            SystemId::Camera => "camera",
+            SystemId::Lighting => "lighting",
+            SystemId::Trigger => "trigger",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|id| id.name() == name)
+    }
+
+    /// Gameplay stops while paused; the camera, poses and rendering keep going.
+    fn default_criteria(self) -> RunCriteria {
+        match self {
+            SystemId::Input
+            | SystemId::Movement
+            | SystemId::Character
+            | SystemId::Navigation
+            | SystemId::Timers
+            | SystemId::StateMachine
+            | SystemId::Trigger => RunCriteria::UnlessPaused,
+            _ => RunCriteria::Always,
+        }
+    }
+}
+
+impl fmt::Display for SystemId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunCriteria {
+    Always,
+    /// Skips while the world's `Paused` resource is `Paused(true)`; paused time is not caught up.
+    UnlessPaused,
+    /// Every n-th tick (n >= 1), with the time of the skipped ones.
+    EveryNFrames(u32),
+}
+
+impl fmt::Display for RunCriteria {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunCriteria::Always => f.write_str("always"),
+            RunCriteria::UnlessPaused => f.write_str("unpaused"),
+            RunCriteria::EveryNFrames(n) => write!(f, "every {n}"),
+        }
+    }
+}
+
+/// World resource pausing the `RunCriteria::UnlessPaused` stages (REPL `system pause`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Paused(pub bool);
+
+/// Run criteria of every stage, and the time each one has skipped.
+#[derive(Debug, Clone)]
+pub struct Schedule {
+    criteria: [RunCriteria; SystemId::ALL.len()],
+    skipped_dt: [f32; SystemId::ALL.len()],
+    ticks: u64,
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        Self {
+            criteria: SystemId::ALL.map(SystemId::default_criteria),
+            skipped_dt: [0.0; SystemId::ALL.len()],
+            ticks: 0,
+        }
+    }
+}
+
+impl Schedule {
+    pub fn criteria(&self, id: SystemId) -> RunCriteria {
+        self.criteria[id as usize]
+    }
+
+    pub fn set_criteria(&mut self, id: SystemId, criteria: RunCriteria) {
+        self.criteria[id as usize] = match criteria {
+            RunCriteria::EveryNFrames(n) => RunCriteria::EveryNFrames(n.max(1)),
+            criteria => criteria,
+        };
+        self.skipped_dt[id as usize] = 0.0;
+    }
+
+    pub(crate) fn begin_tick(&mut self) {
+        self.ticks += 1;
+    }
+
+    /// The `dt` to run `id` with this tick, or `None` to skip it.
+    pub(crate) fn due(
+        &mut self,
+        id: SystemId,
+        enabled: bool,
+        paused: bool,
+        dt_sec: f32,
+    ) -> Option<f32> {
+        let skipped = &mut self.skipped_dt[id as usize];
+        match self.criteria[id as usize] {
+            _ if !enabled => {
+                *skipped = 0.0;
+                None
+            }
+            RunCriteria::Always => Some(dt_sec),
+            RunCriteria::UnlessPaused => (!paused).then_some(dt_sec),
+            RunCriteria::EveryNFrames(n) => {
+                *skipped += dt_sec;
+                self.ticks
+                    .is_multiple_of(u64::from(n))
+                    .then(|| std::mem::take(skipped))
+            }
+        }
+    }
+}
@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod tests {
+    use crate::engine::ecs::system::{RunCriteria, Schedule, SystemId};
+
+    fn run(schedule: &mut Schedule, id: SystemId, enabled: bool, paused: bool) -> Option<f32> {
+        schedule.begin_tick();
+        schedule.due(id, enabled, paused, 0.5)
+    }
+
+    #[test]
+    fn criteria_decide_which_ticks_run() {
+        let mut schedule = Schedule::default();
+        assert_eq!(
+            schedule.criteria(SystemId::Movement),
+            RunCriteria::UnlessPaused
+        );
+        assert_eq!(
+            run(&mut schedule, SystemId::Movement, true, false),
+            Some(0.5)
+        );
+        assert_eq!(run(&mut schedule, SystemId::Movement, true, true), None);
+        assert_eq!(
+            run(&mut schedule, SystemId::Renderable, true, true),
+            Some(0.5)
+        );
+        assert_eq!(run(&mut schedule, SystemId::Renderable, false, false), None);
+
+        // Every third tick, with the time of the two it skipped.
+        let mut schedule = Schedule::default();
+        schedule.set_criteria(SystemId::Lighting, RunCriteria::EveryNFrames(3));
+        let ran: Vec<_> = (0..6)
+            .map(|_| run(&mut schedule, SystemId::Lighting, true, false))
+            .collect();
+        assert_eq!(ran.iter().flatten().count(), 2);
+        assert!(ran.iter().flatten().all(|&dt| dt == 1.5));
+
+        schedule.set_criteria(SystemId::Lighting, RunCriteria::EveryNFrames(0));
+        assert_eq!(
+            schedule.criteria(SystemId::Lighting),
+            RunCriteria::EveryNFrames(1)
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use crate::engine::ecs::component::{ScreenAnchor, ScreenAnchorComponent, TransformComponent};
+use crate::engine::ecs::system::TransformSystem;
+use crate::engine::ecs::system::camera_system::{mat4_inverse, world_to_clip};
+use crate::engine::ecs::{ComponentId, World};
+use crate::engine::graphics::VisualWorld;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct AppliedAnchor {
+    anchor: ScreenAnchor,
+    offset_px: [f32; 2],
+    model: [[f32; 4]; 4],
+}
+
+/// Layout pass for `ScreenAnchorComponent`s.
+///
+/// The vertex shader always applies the active camera, so instead of a separate screen-space
+/// path the anchored Transform gets `inverse(world_to_clip) * pixels_to_clip` baked into its
+/// model matrix. Everything under it then lands on fixed screen pixels.
+#[derive(Debug, Default)]
+pub struct ScreenAnchorSystem {
+    anchors: Vec<ComponentId>,
+
+    /// Anchor cid -> settings and model last written to its parent Transform.
+    applied: HashMap<ComponentId, AppliedAnchor>,
+
+    /// `world_to_clip` + viewport used for the last layout.
+    last_camera: Option<([[f32; 4]; 4], [f32; 2])>,
+}
+
+impl ScreenAnchorSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register_screen_anchor(
+        &mut self,
+        world: &mut World,
+        _visuals: &mut VisualWorld,
+        component: ComponentId,
+    ) {
+        if world
+            .get_component_by_id_as::<ScreenAnchorComponent>(component)
+            .is_none()
+        {
+            return;
+        }
+        if !self.anchors.contains(&component) {
+            self.anchors.push(component);
+        }
+    }
+
+    /// Model matrix mapping anchor-local pixels (+Y down) to world space for the current camera.
+    ///
+    /// Returns `None` if the viewport is empty or the camera is singular.
+    pub fn anchor_model(
+        visuals: &VisualWorld,
+        anchor: ScreenAnchor,
+        offset_px: [f32; 2],
+    ) -> Option<[[f32; 4]; 4]> {
+        let [w, h] = visuals.viewport();
+        if w <= 0.0 || h <= 0.0 {
+            return None;
+        }
+        let [fx, fy] = anchor.factor();
+        let px = fx * w + offset_px[0];
+        let py = fy * h + offset_px[1];
+
+        let pixels_to_clip = [
+            [2.0 / w, 0.0, 0.0, 0.0],
+            [0.0, 2.0 / h, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [px * 2.0 / w - 1.0, py * 2.0 / h - 1.0, 0.0, 1.0],
+        ];
+        let clip_to_world = mat4_inverse(&world_to_clip(visuals))?;
+        Some(TransformSystem::mat4_mul(clip_to_world, pixels_to_clip))
+    }
+
+    /// Re-anchor Transforms whose camera, viewport, anchor settings or local TRS changed.
+    ///
+    /// Returns the Transform cids that were rewritten; the caller must run
+    /// `transform_changed` for them so descendants pick up the new model.
+    pub fn layout(&mut self, world: &mut World, visuals: &VisualWorld) -> Vec<ComponentId> {
+        let camera = (world_to_clip(visuals), visuals.viewport());
+        let camera_changed = self.last_camera != Some(camera);
+        self.last_camera = Some(camera);
+
+        let mut changed = Vec::new();
+        let anchors = self.anchors.clone();
+        for anchor_cid in anchors {
+            let Some(anchor) = world
+                .get_component_by_id_as::<ScreenAnchorComponent>(anchor_cid)
+                .copied()
+            else {
+                self.anchors.retain(|c| *c != anchor_cid);
+                self.applied.remove(&anchor_cid);
+                continue;
+            };
+            let Some(parent) = world.parent_of(anchor_cid) else {
+                continue;
+            };
+            let Some(t) = world.get_component_by_id_as_mut::<TransformComponent>(parent) else {
+                continue;
+            };
+
+            // Anything else writing the Transform (e.g. `update_transform`) replaces the model
+            // with plain TRS, which shows up as a mismatch here.
+            let up_to_date = self.applied.get(&anchor_cid)
+                == Some(&AppliedAnchor {
+                    anchor: anchor.anchor,
+                    offset_px: anchor.offset_px,
+                    model: t.transform.model,
+                });
+            if up_to_date && !camera_changed {
+                continue;
+            }
+
+            let Some(anchor_model) = Self::anchor_model(visuals, anchor.anchor, anchor.offset_px)
+            else {
+                continue;
+            };
+            t.transform.recompute_model();
+            t.transform.model = TransformSystem::mat4_mul(anchor_model, t.transform.model);
+            self.applied.insert(
+                anchor_cid,
+                AppliedAnchor {
+                    anchor: anchor.anchor,
+                    offset_px: anchor.offset_px,
+                    model: t.transform.model,
+                },
+            );
+            changed.push(parent);
+        }
+        changed
+    }
+}
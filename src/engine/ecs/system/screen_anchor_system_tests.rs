@@ -0,0 +1,59 @@
+#[cfg(test)]
+mod tests {
+    use crate::engine::ecs::component::ScreenAnchor;
+    use crate::engine::ecs::system::{CameraSystem, ScreenAnchorSystem};
+    use crate::engine::graphics::VisualWorld;
+
+    fn origin(model: [[f32; 4]; 4]) -> [f32; 3] {
+        [model[3][0], model[3][1], model[3][2]]
+    }
+
+    #[test]
+    fn anchored_origin_lands_on_anchor_pixel() {
+        let cameras = CameraSystem::new();
+        let mut visuals = VisualWorld::new();
+        visuals.set_viewport(800.0, 600.0);
+        // Panned and zoomed 2D camera; the anchor must cancel it out.
+        visuals.set_camera_2d([
+            [0.5, 0.0, 0.0, 0.0],
+            [0.0, 0.5, 0.0, 0.0],
+            [-0.5, -1.0, 1.0, 0.0],
+        ]);
+
+        let cases = [
+            (ScreenAnchor::TopLeft, [10.0, 20.0], [10.0, 20.0]),
+            (ScreenAnchor::Center, [0.0, 0.0], [400.0, 300.0]),
+            (ScreenAnchor::BottomRight, [-16.0, -8.0], [784.0, 592.0]),
+        ];
+        for (anchor, offset, expected) in cases {
+            let model = ScreenAnchorSystem::anchor_model(&visuals, anchor, offset).unwrap();
+            let s = cameras.world_to_screen(&visuals, origin(model)).unwrap();
+            assert!(
+                (s[0] - expected[0]).abs() < 1e-2 && (s[1] - expected[1]).abs() < 1e-2,
+                "{anchor:?}: {s:?} != {expected:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn one_local_unit_is_one_pixel_after_resize() {
+        let cameras = CameraSystem::new();
+        let mut visuals = VisualWorld::new();
+        visuals.set_viewport(1280.0, 720.0);
+
+        let model =
+            ScreenAnchorSystem::anchor_model(&visuals, ScreenAnchor::TopRight, [0.0; 2]).unwrap();
+        // Local (-100, 50) = 100px left of / 50px below the top-right corner.
+        let local = [-100.0f32, 50.0];
+        let world = [
+            model[0][0] * local[0] + model[1][0] * local[1] + model[3][0],
+            model[0][1] * local[0] + model[1][1] * local[1] + model[3][1],
+            0.0,
+        ];
+        let s = cameras.world_to_screen(&visuals, world).unwrap();
+        assert!(
+            (s[0] - 1180.0).abs() < 1e-2 && (s[1] - 50.0).abs() < 1e-2,
+            "{s:?}"
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use crate::engine::ecs::component::{StateMachineComponent, Transition};
+use crate::engine::ecs::{ComponentId, World};
+
+/// World resource of REPL command lines queued by systems (state machine hooks).
+/// `Universe::update` runs and clears them after each tick.
+#[derive(Debug, Default)]
+pub struct PendingCommands {
+    lines: Vec<String>,
+}
+
+impl PendingCommands {
+    pub fn push(&mut self, line: impl Into<String>) {
+        self.lines.push(line.into());
+    }
+
+    pub fn take(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.lines)
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+}
+
+/// Starts `StateMachineComponent`s, fires their transitions and queues their hooks.
+#[derive(Debug, Default)]
+pub struct StateMachineSystem {
+    machines: Vec<ComponentId>,
+}
+
+impl StateMachineSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register_state_machine(&mut self, world: &World, component: ComponentId) {
+        let Some(machine) = world.get_component_by_id_as::<StateMachineComponent>(component) else {
+            return;
+        };
+        for problem in machine.problems() {
+            crate::log_warn!("state machine {component}: {problem}");
+        }
+        if !self.machines.contains(&component) {
+            self.machines.push(component);
+        }
+    }
+
+    /// Enter the initial state of new machines and fire at most one transition of the others.
+    /// Events sent before the tick are used up by it.
+    pub fn tick(&mut self, world: &mut World, dt_sec: f32) {
+        let machines = self.machines.clone();
+        for cid in machines {
+            let Some(machine) = world.get_component_by_id_as::<StateMachineComponent>(cid) else {
+                self.machines.retain(|c| *c != cid);
+                continue;
+            };
+            let owner = world.parent_of(cid);
+            let started = machine.active().is_some();
+            let fired = started
+                .then(|| Self::fired(world, machine, owner, dt_sec))
+                .flatten();
+
+            let Some(machine) = world.get_component_by_id_as_mut::<StateMachineComponent>(cid)
+            else {
+                continue;
+            };
+            let hooks = match fired {
+                _ if !started => machine.start(),
+                Some(to) => {
+                    machine.take_events();
+                    machine.go_to(&to)
+                }
+                None => {
+                    machine.take_events();
+                    machine.advance_time(dt_sec);
+                    Vec::new()
+                }
+            };
+            if hooks.is_empty() {
+                continue;
+            }
+            let pending = world
+                .resources_mut()
+                .get_or_insert_with(PendingCommands::default);
+            for line in hooks {
+                pending.push(Self::expand_hook(&line, cid, owner));
+            }
+        }
+    }
+
+    /// Target of the first ready transition, from the innermost active state outward.
+    fn fired(
+        world: &World,
+        machine: &StateMachineComponent,
+        owner: Option<ComponentId>,
+        dt_sec: f32,
+    ) -> Option<String> {
+        let time = machine.time_in_state() + dt_sec;
+        machine.active_path().into_iter().find_map(|state| {
+            machine
+                .transitions
+                .iter()
+                .find(|t| {
+                    t.from == state
+                        && machine.state(&t.to).is_some()
+                        && Self::ready(t, world, machine.events(), owner, time)
+                })
+                .map(|t| t.to.clone())
+        })
+    }
+
+    fn ready(
+        t: &Transition,
+        world: &World,
+        events: &[String],
+        owner: Option<ComponentId>,
+        time: f32,
+    ) -> bool {
+        let event = t.event.as_ref().is_none_or(|e| events.contains(e));
+        let after = t.after.is_none_or(|after| time >= after);
+        let condition = match t.query() {
+            Ok(None) => true,
+            Ok(Some(query)) => owner.is_some_and(|o| query.matches(world, o)),
+            Err(_) => false,
+        };
+        event && after && condition
+    }
+
+    /// Replace `$self` and `$owner` with component ids.
+    pub fn expand_hook(line: &str, machine: ComponentId, owner: Option<ComponentId>) -> String {
+        let line = line.replace("$self", &machine.to_string());
+        match owner {
+            Some(owner) => line.replace("$owner", &owner.to_string()),
+            None => line,
+        }
+    }
+}
@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod tests {
+    use crate::engine::ecs::World;
+    use crate::engine::ecs::component::{
+        MachineState, StateMachineComponent, TransformComponent, Transition,
+    };
+    use crate::engine::ecs::system::{PendingCommands, StateMachineSystem};
+
+    #[test]
+    fn fires_inner_transitions_first_and_queues_hooks_in_order() {
+        let mut world = World::default();
+        let npc = world.add_component(TransformComponent::new());
+        let machine = StateMachineComponent::new()
+            .with_state(MachineState::new("alive").on_enter("enter alive"))
+            .with_state(
+                MachineState::new("idle")
+                    .with_parent("alive")
+                    .on_enter("enter idle $owner")
+                    .on_exit("exit idle"),
+            )
+            .with_state(MachineState::new("chase").with_parent("alive"))
+            .with_state(MachineState::new("dead").on_enter("despawn $self"))
+            .with_transition(Transition::new("idle", "chase").on_event("seen"))
+            .with_transition(Transition::new("chase", "idle").after(1.0))
+            .with_transition(Transition::new("alive", "dead").when("y<-10"));
+        assert!(machine.problems().is_empty());
+        let fsm = world.add_component(machine);
+        world.add_child(npc, fsm).unwrap();
+
+        let mut system = StateMachineSystem::new();
+        system.register_state_machine(&world, fsm);
+        let mut tick = |world: &mut World, dt: f32| {
+            system.tick(world, dt);
+            let m = world
+                .get_component_by_id_as::<StateMachineComponent>(fsm)
+                .unwrap();
+            m.active_path().join("<")
+        };
+        let hooks = |world: &mut World| {
+            world
+                .resource_mut::<PendingCommands>()
+                .map(PendingCommands::take)
+                .unwrap_or_default()
+        };
+
+        // Starts in the first child of the first state, outermost hook first.
+        assert_eq!(tick(&mut world, 0.1), "idle<alive");
+        assert_eq!(
+            hooks(&mut world),
+            ["enter alive", &format!("enter idle {npc}")]
+        );
+
+        // Events fire once; the parent is not re-entered.
+        assert_eq!(tick(&mut world, 0.1), "idle<alive");
+        world
+            .get_component_by_id_as_mut::<StateMachineComponent>(fsm)
+            .unwrap()
+            .send("seen");
+        assert_eq!(tick(&mut world, 0.1), "chase<alive");
+        assert_eq!(hooks(&mut world), ["exit idle"]);
+        assert_eq!(tick(&mut world, 0.5), "chase<alive");
+        assert_eq!(tick(&mut world, 0.6), "idle<alive");
+
+        // The parent's transition applies from any child.
+        world
+            .get_component_by_id_as_mut::<TransformComponent>(npc)
+            .unwrap()
+            .transform
+            .translation
+            .y = -20.0;
+        hooks(&mut world);
+        assert_eq!(tick(&mut world, 0.1), "dead");
+        assert_eq!(hooks(&mut world), ["exit idle", &format!("despawn {fsm}")]);
+    }
+}
@@ -0,0 +1,302 @@
+use crate::engine::ecs::component::{RenderableComponent, StaticComponent};
+use crate::engine::ecs::system::TransformSystem;
+use crate::engine::ecs::{ComponentId, World};
+use crate::engine::graphics::mesh::{CpuMesh, CpuVertex};
+use crate::engine::graphics::primitives::{CpuMeshHandle, MaterialHandle, Transform};
+use crate::engine::graphics::{
+    GpuRenderable, MeshUploader, RenderAssets, TextureHandle, VisualWorld,
+};
+use std::collections::HashMap;
+
+/// Merges immobile renderables into combined, pre-transformed meshes.
+///
+/// Flow:
+/// - `StaticComponent::init` registers its ancestor renderable here.
+/// - Once that renderable has a `VisualWorld` instance (and its texture has been attached),
+///   we capture mesh/material/color/texture/model and remove the individual instance.
+/// - Captured members sharing (material, texture, color, layers, clip, z-order) are merged into
+///   a single mesh and registered as one instance with an identity model matrix.
+///
+/// Batches are rebuilt only when the captured set changes, which in practice means once at
+/// scene load (members that become ready later trigger another rebuild; the old batch meshes
+/// are released). Dynamic renderables keep the normal one-instance-per-renderable path.
+#[derive(Debug, Default)]
+pub struct StaticBatchSystem {
+    /// RenderableComponent cid -> static member state.
+    members: HashMap<ComponentId, StaticMember>,
+
+    /// Key (the StaticComponent cid of the first member) and merged mesh of each live batch
+    /// instance.
+    batches: Vec<(ComponentId, CpuMeshHandle)>,
+
+    dirty: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct StaticMember {
+    static_cid: ComponentId,
+    baked: Option<BakedInstance>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct BakedInstance {
+    cpu_mesh: CpuMeshHandle,
+    material: MaterialHandle,
+    color: [f32; 4],
+    texture: Option<TextureHandle>,
+    visibility_layers: u32,
+    clip: Option<ComponentId>,
+    z_order: i32,
+    model: [[f32; 4]; 4],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct BatchKey {
+    material: u32,
+    texture: Option<u32>,
+    color: [u32; 4],
+    visibility_layers: u32,
+    clip: Option<ComponentId>,
+    z_order: i32,
+}
+
+fn transform_point(m: &[[f32; 4]; 4], p: [f32; 3]) -> [f32; 3] {
+    [
+        m[0][0] * p[0] + m[1][0] * p[1] + m[2][0] * p[2] + m[3][0],
+        m[0][1] * p[0] + m[1][1] * p[1] + m[2][1] * p[2] + m[3][1],
+        m[0][2] * p[0] + m[1][2] * p[1] + m[2][2] * p[2] + m[3][2],
+    ]
+}
+
+impl StaticBatchSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of merged batch instances currently in `VisualWorld`.
+    pub fn batch_count(&self) -> usize {
+        self.batches.len()
+    }
+
+    pub fn is_static(&self, renderable: ComponentId) -> bool {
+        self.members.contains_key(&renderable)
+    }
+
+    pub fn register_static(
+        &mut self,
+        world: &mut World,
+        _visuals: &mut VisualWorld,
+        component: ComponentId,
+    ) {
+        if world
+            .get_component_by_id_as::<StaticComponent>(component)
+            .is_none()
+        {
+            return;
+        }
+        // Find the ancestor RenderableComponent that this StaticComponent marks.
+        let mut cur = component;
+        let mut renderable_cid: Option<ComponentId> = None;
+        while let Some(parent) = world.parent_of(cur) {
+            if world
+                .get_component_by_id_as::<RenderableComponent>(parent)
+                .is_some()
+            {
+                renderable_cid = Some(parent);
+                break;
+            }
+            cur = parent;
+        }
+        let Some(renderable_cid) = renderable_cid else {
+            return;
+        };
+
+        self.members.insert(
+            renderable_cid,
+            StaticMember {
+                static_cid: component,
+                baked: None,
+            },
+        );
+    }
+
+    /// Drop a renderable from the static set; its batch is rebuilt on the next flush.
+    pub fn remove_renderable(&mut self, component: ComponentId) {
+        if self.members.remove(&component).is_some() {
+            self.dirty = true;
+        }
+    }
+
+    /// Capture newly-ready static renderables and rebuild batches if the captured set changed.
+    ///
+    /// Must run after renderables and textures are flushed into `VisualWorld`.
+    /// `texture_pending` reports renderables still waiting on a texture attach; those are not
+    /// captured yet so the batch picks up the final texture. Members that are not ready keep
+    /// their own instance until they are, so capturing one never hides it.
+    pub fn flush_pending(
+        &mut self,
+        world: &mut World,
+        visuals: &mut VisualWorld,
+        render_assets: &mut RenderAssets,
+        uploader: &mut dyn MeshUploader,
+        texture_pending: impl Fn(ComponentId) -> bool,
+    ) {
+        if !self.dirty && self.members.values().all(|m| m.baked.is_some()) {
+            return;
+        }
+
+        let keys: Vec<ComponentId> = self.members.keys().copied().collect();
+        for renderable_cid in keys {
+            if self.members[&renderable_cid].baked.is_some() {
+                continue;
+            }
+            let Some(renderable_comp) =
+                world.get_component_by_id_as::<RenderableComponent>(renderable_cid)
+            else {
+                self.members.remove(&renderable_cid);
+                continue;
+            };
+            let Some(handle) = renderable_comp.get_handle() else {
+                // RenderableSystem never registers renderables without a transform ancestor.
+                if TransformSystem::world_model(world, renderable_cid).is_none() {
+                    self.members.remove(&renderable_cid);
+                }
+                continue;
+            };
+            if texture_pending(renderable_cid) {
+                continue;
+            }
+            let Some(inst) = visuals.instance(handle).copied() else {
+                continue;
+            };
+
+            if let Some(member) = self.members.get_mut(&renderable_cid) {
+                member.baked = Some(BakedInstance {
+                    cpu_mesh: renderable_comp.renderable.mesh,
+                    material: renderable_comp.renderable.material,
+                    color: inst.color,
+                    texture: inst.texture,
+                    visibility_layers: inst.visibility_layers,
+                    clip: inst.clip,
+                    z_order: inst.z_order,
+                    model: inst.transform.model,
+                });
+            }
+
+            // The batch owns this geometry from now on.
+            self.dirty = true;
+            visuals.remove(handle);
+            if let Some(renderable_comp) =
+                world.get_component_by_id_as_mut::<RenderableComponent>(renderable_cid)
+            {
+                renderable_comp.handle = None;
+            }
+        }
+
+        if !self.dirty {
+            return;
+        }
+
+        self.rebuild(visuals, render_assets, uploader);
+        self.dirty = false;
+    }
+
+    fn rebuild(
+        &mut self,
+        visuals: &mut VisualWorld,
+        render_assets: &mut RenderAssets,
+        uploader: &mut dyn MeshUploader,
+    ) {
+        for (key, cpu_mesh) in self.batches.drain(..) {
+            visuals.remove_component(key);
+            if let Err(err) = render_assets.release_mesh(uploader, cpu_mesh) {
+                crate::log_warn!("release_mesh failed for cpu_mesh={:?}: {}", cpu_mesh, err);
+            }
+        }
+
+        // Group members; sort by cid so batch contents are deterministic.
+        let mut members: Vec<(ComponentId, StaticMember)> =
+            self.members.iter().map(|(&c, &m)| (c, m)).collect();
+        members.sort_by_key(|(c, _)| *c);
+
+        let mut groups: Vec<(BatchKey, Vec<StaticMember>)> = Vec::new();
+        for (_, member) in members {
+            let Some(baked) = member.baked else {
+                continue;
+            };
+            let key = BatchKey {
+                material: baked.material.0,
+                texture: baked.texture.map(|t| t.0),
+                color: baked.color.map(f32::to_bits),
+                visibility_layers: baked.visibility_layers,
+                clip: baked.clip,
+                z_order: baked.z_order,
+            };
+            match groups.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, list)) => list.push(member),
+                None => groups.push((key, vec![member])),
+            }
+        }
+
+        for (_, list) in groups {
+            let mut vertices: Vec<CpuVertex> = Vec::new();
+            let mut indices: Vec<u32> = Vec::new();
+            for member in &list {
+                let Some(baked) = member.baked else {
+                    continue;
+                };
+                let Some(mesh) = render_assets.cpu_mesh(baked.cpu_mesh) else {
+                    continue;
+                };
+                let base = vertices.len() as u32;
+                vertices.extend(mesh.vertices.iter().map(|v| CpuVertex {
+                    pos: transform_point(&baked.model, v.pos),
+                    uv: v.uv,
+                }));
+                indices.extend(mesh.indices_u32.iter().map(|i| base + i));
+            }
+            let Some(first) = list
+                .first()
+                .and_then(|m| m.baked.map(|b| (m.static_cid, b)))
+            else {
+                continue;
+            };
+            let (key_cid, baked) = first;
+
+            let cpu_mesh = render_assets.register_mesh(CpuMesh::new(vertices, indices));
+            let mesh = match render_assets.gpu_mesh_handle(uploader, cpu_mesh) {
+                Ok(h) => h,
+                Err(err) => {
+                    crate::log_error!(
+                        "gpu_mesh_handle failed for cpu_mesh={:?}: {}",
+                        cpu_mesh,
+                        err
+                    );
+                    let _ = render_assets.release_mesh(uploader, cpu_mesh);
+                    continue;
+                }
+            };
+
+            let handle = visuals.register(
+                key_cid,
+                GpuRenderable {
+                    mesh,
+                    material: baked.material,
+                },
+                Transform::default(),
+                baked.color,
+                baked.texture,
+            );
+            visuals.update_visibility_layers(handle, baked.visibility_layers);
+            visuals.update_clip(handle, baked.clip);
+            visuals.update_z_order(handle, baked.z_order);
+            self.batches.push((key_cid, cpu_mesh));
+        }
+
+        crate::log_info!(
+            "merged {} static renderables into {} batches",
+            self.members.values().filter(|m| m.baked.is_some()).count(),
+            self.batches.len()
+        );
+    }
+}
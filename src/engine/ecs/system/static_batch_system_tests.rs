@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod tests {
+    use crate::engine::ecs::ComponentId;
+    use crate::engine::ecs::component::{RenderableComponent, StaticComponent, TransformComponent};
+    use crate::engine::graphics::NullUploader;
+    use crate::engine::graphics::mesh::MeshFactory;
+    use crate::engine::graphics::primitives::{CpuMeshHandle, MaterialHandle, Renderable};
+    use crate::engine::{RendererBackend, Universe, UniverseBuilder};
+
+    fn add_prop(u: &mut Universe, mesh: CpuMeshHandle) -> ComponentId {
+        let root = u.world.add_component(TransformComponent::new());
+        let renderable = u
+            .world
+            .add_component(RenderableComponent::new(Renderable::new(
+                mesh,
+                MaterialHandle::TOON_MESH,
+            )));
+        u.world.add_child(root, renderable).unwrap();
+        let marker = u.world.add_component(StaticComponent::new());
+        u.world.add_child(renderable, marker).unwrap();
+        u.world.init_component_tree(root, &mut u.command_queue);
+        root
+    }
+
+    #[test]
+    fn ready_members_are_batched_while_others_are_not() {
+        let mut u = UniverseBuilder::new()
+            .renderer(RendererBackend::None)
+            .repl(false)
+            .build();
+        let mut uploader = NullUploader::default();
+        let mut frame = |u: &mut Universe| {
+            u.systems
+                .process_commands(&mut u.world, &mut u.visuals, &mut u.command_queue);
+            u.prepare_render_with(&mut uploader);
+        };
+        let quad = u.render_assets.register_mesh(MeshFactory::quad_2d());
+        add_prop(&mut u, quad);
+        // Never uploads: this member stays uncaptured.
+        add_prop(&mut u, CpuMeshHandle(u32::MAX));
+        frame(&mut u);
+        frame(&mut u);
+        assert_eq!(u.systems.static_batch.batch_count(), 1);
+        assert_eq!(
+            u.visuals.instances().len(),
+            1,
+            "the captured prop is still drawn"
+        );
+
+        let meshes = u.render_assets.mesh_count();
+        add_prop(&mut u, quad);
+        frame(&mut u);
+        assert_eq!(u.systems.static_batch.batch_count(), 1);
+        assert_eq!(
+            u.render_assets.mesh_count(),
+            meshes,
+            "the rebuilt batch released the old merged mesh"
+        );
+    }
+}
@@ -1,19 +1,158 @@
 use super::World;
+use crate::engine::assets::AssetServer;
 use crate::engine::ecs::ComponentId;
+use crate::engine::ecs::system::BackgroundSystem;
 use crate::engine::ecs::system::CameraSystem;
+use crate::engine::ecs::system::CharacterSystem;
+use crate::engine::ecs::system::ClipRectSystem;
+use crate::engine::ecs::system::ColorGradingSystem;
 use crate::engine::ecs::system::InputSystem;
 use crate::engine::ecs::system::LightSystem;
 use crate::engine::ecs::system::LitVoxelSystem;
+use crate::engine::ecs::system::MovementSystem;
+use crate::engine::ecs::system::NavAgentSystem;
+use crate::engine::ecs::system::NineSliceSystem;
 use crate::engine::ecs::system::RenderableSystem;
+use crate::engine::ecs::system::ScreenAnchorSystem;
+use crate::engine::ecs::system::StateMachineSystem;
+use crate::engine::ecs::system::StaticBatchSystem;
 use crate::engine::ecs::system::System;
 use crate::engine::ecs::system::TextureSystem;
+use crate::engine::ecs::system::TilemapSystem;
+use crate::engine::ecs::system::TimerSystem;
 use crate::engine::ecs::system::TransformSystem;
+use crate::engine::ecs::system::TriggerSystem;
+use crate::engine::ecs::system::XrPoseSystem;
+use crate::engine::ecs::system::schedule::{Paused, Schedule, SystemId};
 use crate::engine::graphics::{RenderAssets, RenderUploader, VisualWorld};
+use crate::engine::spatial::SpatialGrid;
 use crate::engine::user_input::InputState;
+use crate::utils::trace;
+
+/// Stages `SystemWorld::tick` may skip (see `schedule::SystemId`). Registration and
+/// rendering always run; turning off `transform`, `renderable` or `camera` freezes what is on
+/// screen, which is meant for isolating issues (REPL `system disable renderable`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnabledSystems {
+    /// `InputSystem`: action-driven movement of InputComponent targets.
+    pub input: bool,
+    /// Camera mouse/keyboard controls.
+    pub camera_input: bool,
+    /// `LightSystem` and `LitVoxelSystem` ticks.
+    pub lighting: bool,
+    /// `MovementSystem`: VelocityComponent integration.
+    pub movement: bool,
+    /// `CharacterSystem`: character controllers walking among colliders.
+    pub character: bool,
+    /// `NavAgentSystem`: nav agents following their paths.
+    pub navigation: bool,
+    /// `TimerSystem`: the `Scheduler` resource's timers.
+    pub timers: bool,
+    /// `StateMachineSystem`: state machine transitions and hooks.
+    pub state_machine: bool,
+    /// `XrPoseSystem`: tracked poses onto their Transforms.
+    pub xr_pose: bool,
+    /// `TransformSystem` tick (attachments).
+    pub transform: bool,
+    /// `RenderableSystem` tick (LOD selection, instance updates).
+    pub renderable: bool,
+    /// `CameraSystem` tick.
+    pub camera: bool,
+    /// `TriggerSystem`: trigger volume enter/exit events.
+    pub trigger: bool,
+}
+
+impl EnabledSystems {
+    pub const ALL: Self = Self {
+        input: true,
+        camera_input: true,
+        lighting: true,
+        movement: true,
+        character: true,
+        navigation: true,
+        timers: true,
+        state_machine: true,
+        xr_pose: true,
+        transform: true,
+        renderable: true,
+        camera: true,
+        trigger: true,
+    };
+
+    pub const NONE: Self = Self {
+        input: false,
+        camera_input: false,
+        lighting: false,
+        movement: false,
+        character: false,
+        navigation: false,
+        timers: false,
+        state_machine: false,
+        xr_pose: false,
+        transform: false,
+        renderable: false,
+        camera: false,
+        trigger: false,
+    };
+
+    pub fn get(&self, id: SystemId) -> bool {
+        *self.flag(id)
+    }
+
+    pub fn set(&mut self, id: SystemId, enabled: bool) {
+        *self.flag_mut(id) = enabled;
+    }
+
+    fn flag(&self, id: SystemId) -> &bool {
+        match id {
+            SystemId::Input => &self.input,
+            SystemId::CameraInput => &self.camera_input,
+            SystemId::Movement => &self.movement,
+            SystemId::Character => &self.character,
+            SystemId::Navigation => &self.navigation,
+            SystemId::Timers => &self.timers,
+            SystemId::StateMachine => &self.state_machine,
+            SystemId::XrPose => &self.xr_pose,
+            SystemId::Transform => &self.transform,
+            SystemId::Renderable => &self.renderable,
+            SystemId::Camera => &self.camera,
+            SystemId::Lighting => &self.lighting,
+            SystemId::Trigger => &self.trigger,
+        }
+    }
+
+    fn flag_mut(&mut self, id: SystemId) -> &mut bool {
+        match id {
+            SystemId::Input => &mut self.input,
+            SystemId::CameraInput => &mut self.camera_input,
+            SystemId::Movement => &mut self.movement,
+            SystemId::Character => &mut self.character,
+            SystemId::Navigation => &mut self.navigation,
+            SystemId::Timers => &mut self.timers,
+            SystemId::StateMachine => &mut self.state_machine,
+            SystemId::XrPose => &mut self.xr_pose,
+            SystemId::Transform => &mut self.transform,
+            SystemId::Renderable => &mut self.renderable,
+            SystemId::Camera => &mut self.camera,
+            SystemId::Lighting => &mut self.lighting,
+            SystemId::Trigger => &mut self.trigger,
+        }
+    }
+}
+
+impl Default for EnabledSystems {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
 
 /// System world that holds and runs all registered systems.
 #[derive(Debug, Default)]
 pub struct SystemWorld {
+    pub enabled: EnabledSystems,
+    /// Run criteria of the enabled stages.
+    pub schedule: Schedule,
+
     pub camera: CameraSystem,
     pub renderable: RenderableSystem,
     pub transform: TransformSystem,
@@ -21,6 +160,23 @@ pub struct SystemWorld {
     pub light: LightSystem,
     pub lit_voxel: LitVoxelSystem,
     pub texture: TextureSystem,
+    pub static_batch: StaticBatchSystem,
+    pub tilemap: TilemapSystem,
+    pub nine_slice: NineSliceSystem,
+    pub screen_anchor: ScreenAnchorSystem,
+    pub movement: MovementSystem,
+    pub character: CharacterSystem,
+    pub nav_agent: NavAgentSystem,
+    pub timers: TimerSystem,
+    pub state_machine: StateMachineSystem,
+    pub xr_pose: XrPoseSystem,
+    pub trigger: TriggerSystem,
+    pub background: BackgroundSystem,
+    pub clip_rect: ClipRectSystem,
+    pub color_grading: ColorGradingSystem,
+
+    /// Broad-phase index of renderable instance bounds (see `engine::spatial`).
+    pub spatial: SpatialGrid,
 }
 
 impl SystemWorld {
@@ -39,6 +195,18 @@ impl SystemWorld {
             .register_renderable(world, visuals, component);
     }
 
+    /// Remove a RenderableComponent's instance from VisualWorld.
+    pub fn remove_renderable(
+        &mut self,
+        world: &mut World,
+        visuals: &mut VisualWorld,
+        component: ComponentId,
+    ) {
+        self.renderable.remove_renderable(world, visuals, component);
+        self.static_batch.remove_renderable(component);
+        self.spatial.remove(component);
+    }
+
     /// Register a UVComponent and apply it to its ancestor RenderableComponent.
     pub fn register_uv(
         &mut self,
@@ -59,6 +227,98 @@ impl SystemWorld {
         self.renderable.register_color(world, visuals, component);
     }
 
+    /// Register a LodComponent and attach it to its ancestor RenderableComponent.
+    pub fn register_lod(
+        &mut self,
+        world: &mut World,
+        visuals: &mut VisualWorld,
+        component: ComponentId,
+    ) {
+        self.renderable.register_lod(world, visuals, component);
+    }
+
+    /// Register a StaticComponent and mark its ancestor RenderableComponent for batching.
+    pub fn register_static(
+        &mut self,
+        world: &mut World,
+        visuals: &mut VisualWorld,
+        component: ComponentId,
+    ) {
+        self.static_batch.register_static(world, visuals, component);
+    }
+
+    /// Register a TilemapComponent with the TilemapSystem.
+    pub fn register_tilemap(
+        &mut self,
+        world: &mut World,
+        visuals: &mut VisualWorld,
+        component: ComponentId,
+    ) {
+        self.tilemap.register_tilemap(world, visuals, component);
+    }
+
+    /// Register a NineSliceComponent with the NineSliceSystem.
+    pub fn register_nine_slice(
+        &mut self,
+        world: &mut World,
+        visuals: &mut VisualWorld,
+        component: ComponentId,
+    ) {
+        self.nine_slice
+            .register_nine_slice(world, visuals, component);
+    }
+
+    /// Register a ScreenAnchorComponent with the ScreenAnchorSystem.
+    pub fn register_screen_anchor(
+        &mut self,
+        world: &mut World,
+        visuals: &mut VisualWorld,
+        component: ComponentId,
+    ) {
+        self.screen_anchor
+            .register_screen_anchor(world, visuals, component);
+    }
+
+    /// Register an AttachmentComponent with the TransformSystem.
+    pub fn register_attachment(&mut self, world: &mut World, component: ComponentId) {
+        self.transform.register_attachment(world, component);
+    }
+
+    /// Register a VelocityComponent with the MovementSystem.
+    pub fn register_velocity(&mut self, world: &mut World, component: ComponentId) {
+        self.movement.register_velocity(world, component);
+    }
+
+    /// Register a CharacterControllerComponent with the CharacterSystem.
+    pub fn register_character(&mut self, world: &mut World, component: ComponentId) {
+        self.character.register_character(world, component);
+    }
+
+    /// Register a ColliderComponent with the CharacterSystem.
+    pub fn register_collider(&mut self, world: &mut World, component: ComponentId) {
+        self.character.register_collider(world, component);
+    }
+
+    /// Register a NavAgentComponent with the NavAgentSystem.
+    pub fn register_nav_agent(&mut self, world: &mut World, component: ComponentId) {
+        self.nav_agent.register_agent(world, component);
+    }
+
+    /// Register a StateMachineComponent with the StateMachineSystem.
+    pub fn register_state_machine(&mut self, world: &mut World, component: ComponentId) {
+        self.state_machine.register_state_machine(world, component);
+    }
+
+    /// Register a TriggerVolumeComponent with the TriggerSystem.
+    pub fn register_trigger(&mut self, world: &mut World, component: ComponentId) {
+        self.trigger.register_trigger(world, component);
+    }
+
+    /// Register an XrPoseComponent with the XrPoseSystem.
+    pub fn register_xr_pose(&mut self, world: &mut World, component: ComponentId) {
+        self.xr_pose.register_xr_pose(world, component);
+    }
+
     /// Register a TextureComponent and apply it to its ancestor RenderableComponent.
     pub fn register_texture(
         &mut self,
@@ -79,22 +339,113 @@ impl SystemWorld {
         self.light.register_light(world, visuals, component);
     }
 
+    /// Make a BackgroundComponent the active background.
+    pub fn register_background(
+        &mut self,
+        world: &mut World,
+        visuals: &mut VisualWorld,
+        component: ComponentId,
+    ) {
+        self.background
+            .register_background(world, visuals, component);
+    }
+
+    /// Reset the background if `component` is the active one.
+    pub fn remove_background(&mut self, visuals: &mut VisualWorld, component: ComponentId) {
+        self.background.remove_background(visuals, component);
+    }
+
+    /// Register a ClipRectComponent and clip the instances already below it.
+    pub fn register_clip_rect(
+        &mut self,
+        world: &mut World,
+        visuals: &mut VisualWorld,
+        component: ComponentId,
+    ) {
+        self.clip_rect.register_clip_rect(world, visuals, component);
+    }
+
+    /// Remove a ClipRectComponent; its instances fall back to the enclosing clip.
+    pub fn remove_clip_rect(&mut self, visuals: &mut VisualWorld, component: ComponentId) {
+        self.clip_rect.remove_clip_rect(visuals, component);
+    }
+
+    /// Make a ColorGradingComponent the active color grading.
+    pub fn register_color_grading(
+        &mut self,
+        world: &mut World,
+        visuals: &mut VisualWorld,
+        component: ComponentId,
+    ) {
+        self.color_grading
+            .register_color_grading(world, visuals, component);
+    }
+
+    /// Turn color grading off if `component` is the active one.
+    pub fn remove_color_grading(&mut self, visuals: &mut VisualWorld, component: ComponentId) {
+        self.color_grading.remove_color_grading(visuals, component);
+    }
+
     /// Prepare render state before issuing a frame.
     ///
     /// This flushes any pending renderables by uploading meshes and inserting GPU-ready
-    /// instances into `VisualWorld`.
+    /// instances into `VisualWorld`, then merges static renderables into batches.
     pub fn prepare_render(
         &mut self,
         world: &mut World,
         visuals: &mut VisualWorld,
         render_assets: &mut RenderAssets,
+        assets: &mut AssetServer,
         uploader: &mut dyn RenderUploader,
     ) {
+        // Followers first: their targets have received this frame's transform commands by now.
+        for transform in self.transform.resolve_attachments(world) {
+            self.transform_changed(world, visuals, transform);
+        }
+
+        // Re-anchor HUD transforms against this frame's camera/viewport before anything reads
+        // world models.
+        for transform in self.screen_anchor.layout(world, visuals) {
+            self.transform_changed(world, visuals, transform);
+        }
+        self.clip_rect.layout(world, visuals);
+
+        // Generated renderables (tilemap chunks, 9-slice panels) must exist before the
+        // renderable flush uploads them.
+        self.tilemap.flush_pending(
+            world,
+            visuals,
+            render_assets,
+            &mut self.renderable,
+            &mut self.texture,
+            &mut self.spatial,
+        );
+        self.nine_slice.flush_pending(
+            world,
+            visuals,
+            render_assets,
+            &mut self.renderable,
+            &mut self.texture,
+        );
+
         self.renderable
-            .flush_pending(world, visuals, render_assets, uploader);
+            .flush_pending(world, visuals, render_assets, uploader, &mut self.spatial);
 
         // Must run after renderables are flushed so instance handles exist.
-        self.texture.flush_pending(world, visuals, uploader);
+        self.texture.flush_pending(world, visuals, assets, uploader);
+        self.texture
+            .update_streaming(world, visuals, assets, uploader);
+        self.background
+            .flush_pending(world, visuals, assets, uploader);
+        self.color_grading
+            .flush_pending(world, visuals, assets, uploader);
+
+        // Must run after textures are attached so batches inherit them.
+        let texture = &self.texture;
+        self.static_batch
+            .flush_pending(world, visuals, render_assets, uploader, |cid| {
+                texture.is_pending(cid)
+            });
     }
 
     /// Called when a TransformComponent changes.
@@ -110,6 +461,7 @@ impl SystemWorld {
             component,
             &mut self.camera,
             &mut self.light,
+            &mut self.spatial,
         );
     }
 
@@ -239,15 +591,72 @@ impl SystemWorld {
         queue: &mut crate::engine::ecs::CommandQueue,
         dt_sec: f32,
     ) {
+        self.schedule.begin_tick();
+        let paused = world.resource::<Paused>().is_some_and(|p| p.0);
+        let due = |this: &mut Self, id| this.schedule.due(id, this.enabled.get(id), paused, dt_sec);
+
         // Process input first - it may queue commands
-        self.input.process_input(world, input, queue, dt_sec);
+        if let Some(dt_sec) = due(self, SystemId::Input) {
+            let _span = trace::span("systems.input");
+            let active_camera = self.camera.active_camera_component();
+            self.input
+                .process_input(world, input, queue, dt_sec, active_camera);
+        }
+        if due(self, SystemId::CameraInput).is_some() {
+            let _span = trace::span("systems.camera_input");
+            self.camera.process_input(world, visuals, input, queue);
+        }
+        if let Some(dt_sec) = due(self, SystemId::Movement) {
+            let _span = trace::span("systems.movement");
+            self.movement.tick(world, queue, dt_sec);
+        }
+        if let Some(dt_sec) = due(self, SystemId::Character) {
+            let _span = trace::span("systems.character");
+            self.character
+                .tick(world, &self.input.actions, input, queue, dt_sec);
+        }
+        if let Some(dt_sec) = due(self, SystemId::Navigation) {
+            let _span = trace::span("systems.navigation");
+            self.nav_agent.tick(world, queue, dt_sec);
+        }
+        if let Some(dt_sec) = due(self, SystemId::Timers) {
+            let _span = trace::span("systems.timers");
+            self.timers.tick(world, queue, dt_sec);
+        }
+        if let Some(dt_sec) = due(self, SystemId::StateMachine) {
+            let _span = trace::span("systems.state_machine");
+            self.state_machine.tick(world, dt_sec);
+        }
+        // Keep tracked poses on unless isolating an issue: without them hands detach from
+        // the player.
+        if due(self, SystemId::XrPose).is_some() {
+            let _span = trace::span("systems.xr_pose");
+            self.xr_pose.tick(world, input, queue);
+        }
 
-        self.transform.tick(world, visuals, input, dt_sec);
-        self.renderable.tick(world, visuals, input, dt_sec);
-        self.camera.tick(world, visuals, input, dt_sec);
+        if let Some(dt_sec) = due(self, SystemId::Transform) {
+            let _span = trace::span("systems.transform");
+            self.transform.tick(world, visuals, input, dt_sec);
+        }
+        if let Some(dt_sec) = due(self, SystemId::Renderable) {
+            let _span = trace::span("systems.renderable");
+            self.renderable.tick(world, visuals, input, dt_sec);
+        }
+        if let Some(dt_sec) = due(self, SystemId::Camera) {
+            let _span = trace::span("systems.camera");
+            self.camera.tick(world, visuals, input, dt_sec);
+        }
 
-        self.light.tick(world, visuals, input, dt_sec);
-        self.lit_voxel.tick(world, visuals, input, dt_sec);
+        if let Some(dt_sec) = due(self, SystemId::Lighting) {
+            let _span = trace::span("systems.lighting");
+            self.light.tick(world, visuals, input, dt_sec);
+            self.lit_voxel.tick(world, visuals, input, dt_sec);
+        }
+
+        if due(self, SystemId::Trigger).is_some() {
+            let _span = trace::span("systems.trigger");
+            self.trigger.tick(world, &self.spatial);
+        }
     }
 
     /// Process commands from the command queue.
@@ -257,6 +666,53 @@ impl SystemWorld {
         visuals: &mut VisualWorld,
         commands: &mut crate::engine::ecs::CommandQueue,
     ) {
+        let _span = trace::span("commands.flush");
         commands.flush(world, self, visuals);
+
+        for root in world.take_moved_subtrees() {
+            self.subtree_moved(world, visuals, root);
+        }
+    }
+
+    /// Recompute world-space state under a reparented component.
+    ///
+    /// `transform_changed` on the root refreshes every renderable and light below it; nested
+    /// Transforms are revisited only for their camera children.
+    fn subtree_moved(&mut self, world: &mut World, visuals: &mut VisualWorld, root: ComponentId) {
+        if world.get_component_record(root).is_none() {
+            return;
+        }
+        self.transform_changed(world, visuals, root);
+
+        let mut stack: Vec<ComponentId> = world.children_of(root).to_vec();
+        while let Some(node) = stack.pop() {
+            stack.extend_from_slice(world.children_of(node));
+            let has_camera = world.children_of(node).iter().any(|&c| {
+                world
+                    .get_component_by_id_as::<crate::engine::ecs::component::Camera2DComponent>(c)
+                    .is_some()
+                    || world
+                        .get_component_by_id_as::<crate::engine::ecs::component::Camera3DComponent>(
+                            c,
+                        )
+                        .is_some()
+            });
+            if has_camera
+                && world
+                    .get_component_by_id_as::<crate::engine::ecs::component::TransformComponent>(
+                        node,
+                    )
+                    .is_some()
+            {
+                self.transform.transform_changed(
+                    world,
+                    visuals,
+                    node,
+                    &mut self.camera,
+                    &mut self.light,
+                    &mut self.spatial,
+                );
+            }
+        }
     }
 }
@@ -0,0 +1,371 @@
+use crate::engine::assets::{AssetServer, DecodedImage};
+use crate::engine::ecs::component::RenderableComponent;
+use crate::engine::ecs::system::camera_system::world_to_clip;
+use crate::engine::ecs::{ComponentId, World};
+use crate::engine::graphics::{TextureHandle, TextureUploader, VisualWorld};
+use crate::utils::profiling;
+use glam::{Mat4, Vec3, Vec4};
+use std::collections::HashMap;
+
+/// Settings for `TextureSystem::set_streaming`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextureStreamingPolicy {
+    /// Device memory for streamed textures, in bytes (4 bytes per texel).
+    pub budget_bytes: u64,
+    /// Longest side of the low-resolution copy uploaded first.
+    pub low_res_max_dim: u32,
+    /// Full-resolution uploads started per frame.
+    pub max_upgrades_per_frame: usize,
+}
+
+impl Default for TextureStreamingPolicy {
+    fn default() -> Self {
+        Self {
+            budget_bytes: 256 * 1024 * 1024,
+            low_res_max_dim: 64,
+            max_upgrades_per_frame: 2,
+        }
+    }
+}
+
+/// Snapshot after the last `TextureStreamer::update`; also published as `textures.*` gauges.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TextureStreamingStats {
+    pub budget_bytes: u64,
+    pub resident_bytes: u64,
+    pub textures: usize,
+    /// Textures currently at full resolution (small ones count from the start).
+    pub full_res: usize,
+    /// Textures on screen this frame.
+    pub visible: usize,
+    /// Full-resolution copies dropped to stay within budget, since startup.
+    pub evictions: u64,
+}
+
+#[derive(Debug)]
+struct StreamedTexture {
+    low: TextureHandle,
+    low_bytes: u64,
+    /// Full-resolution copy, when resident.
+    full: Option<TextureHandle>,
+    full_bytes: u64,
+    /// Renderables showing this texture.
+    users: Vec<ComponentId>,
+    last_visible: u64,
+    /// Distance from the camera to the nearest on-screen user.
+    distance: f32,
+    /// Decoding the full resolution failed; stay at the low-resolution copy.
+    upgrade_failed: bool,
+}
+
+impl StreamedTexture {
+    fn is_small(&self) -> bool {
+        self.full_bytes == self.low_bytes
+    }
+
+    fn current(&self) -> TextureHandle {
+        self.full.unwrap_or(self.low)
+    }
+
+    fn resident_bytes(&self) -> u64 {
+        self.low_bytes
+            + if self.full.is_some() {
+                self.full_bytes
+            } else {
+                0
+            }
+    }
+}
+
+/// Budgeted texture residency for `TextureSystem`.
+///
+/// Each texture is uploaded first as a copy no larger than `low_res_max_dim`. Every frame
+/// the on-screen textures (nearest first) get their full-resolution version, decoded in the
+/// background by `AssetServer`, as long as the budget allows; to make room, full copies of
+/// textures that are not on screen are dropped, least recently visible first.
+///
+/// "On screen" means the center of some renderable using the texture projects inside the
+/// viewport (with a margin); large meshes mostly off screen may count as hidden.
+#[derive(Debug)]
+pub struct TextureStreamer {
+    pub policy: TextureStreamingPolicy,
+    textures: HashMap<String, StreamedTexture>,
+    frame: u64,
+    evictions: u64,
+    stats: TextureStreamingStats,
+}
+
+/// Clip-space slack for the on-screen test, so textures upgrade slightly before they appear.
+const VISIBLE_MARGIN: f32 = 1.25;
+
+impl TextureStreamer {
+    pub fn new(policy: TextureStreamingPolicy) -> Self {
+        Self {
+            policy,
+            textures: HashMap::new(),
+            frame: 0,
+            evictions: 0,
+            stats: TextureStreamingStats::default(),
+        }
+    }
+
+    pub fn stats(&self) -> TextureStreamingStats {
+        self.stats
+    }
+
+    /// Handle to show on `renderable` for `uri`, uploading the low-resolution copy on first
+    /// use. `None` if the image can't be loaded or uploaded.
+    pub fn attach(
+        &mut self,
+        uri: &str,
+        renderable: ComponentId,
+        assets: &mut AssetServer,
+        uploader: &mut dyn TextureUploader,
+    ) -> Option<TextureHandle> {
+        if let Some(tex) = self.textures.get_mut(uri) {
+            if !tex.users.contains(&renderable) {
+                tex.users.push(renderable);
+            }
+            return Some(tex.current());
+        }
+
+        let image = match assets.load_image(uri) {
+            Ok(image) => image,
+            Err(e) => {
+                crate::log_warn!("texture load failed: {e}");
+                return None;
+            }
+        };
+        let full_bytes = image_bytes(image.width, image.height);
+        let low = downscale(&image, self.policy.low_res_max_dim);
+        let low_image = low.as_ref().unwrap_or(&image);
+        let handle =
+            match uploader.upload_texture_rgba8(&low_image.rgba, low_image.width, low_image.height)
+            {
+                Ok(h) => h,
+                Err(e) => {
+                    crate::log_error!("upload failed for '{uri}': {e}");
+                    return None;
+                }
+            };
+        // Full resolution is decoded again when (if) it is needed.
+        assets.release_image(uri);
+
+        self.textures.insert(
+            uri.to_string(),
+            StreamedTexture {
+                low: handle,
+                low_bytes: image_bytes(low_image.width, low_image.height),
+                full: None,
+                full_bytes,
+                users: vec![renderable],
+                last_visible: self.frame,
+                distance: f32::MAX,
+                upgrade_failed: false,
+            },
+        );
+        Some(handle)
+    }
+
+    /// Update visibility, upgrade visible textures and evict hidden ones to stay in budget.
+    pub fn update(
+        &mut self,
+        world: &World,
+        visuals: &mut VisualWorld,
+        assets: &mut AssetServer,
+        uploader: &mut dyn TextureUploader,
+    ) {
+        self.frame += 1;
+        self.update_visibility(world, visuals);
+
+        let mut candidates: Vec<(&String, f32)> = self
+            .textures
+            .iter()
+            .filter(|(_, t)| {
+                t.last_visible == self.frame
+                    && t.full.is_none()
+                    && !t.is_small()
+                    && !t.upgrade_failed
+            })
+            .map(|(uri, t)| (uri, t.distance))
+            .collect();
+        candidates.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let candidates: Vec<String> = candidates
+            .into_iter()
+            .take(self.policy.max_upgrades_per_frame)
+            .map(|(uri, _)| uri.clone())
+            .collect();
+
+        for uri in candidates {
+            assets.request_image(&uri);
+            let image = match assets.try_image(&uri) {
+                None => continue,
+                Some(Ok(image)) => image,
+                Some(Err(e)) => {
+                    crate::log_warn!("texture upgrade failed: {e}");
+                    if let Some(tex) = self.textures.get_mut(&uri) {
+                        tex.upgrade_failed = true;
+                    }
+                    continue;
+                }
+            };
+            let needed = image_bytes(image.width, image.height);
+            if !self.make_room(world, needed, visuals, uploader) {
+                break;
+            }
+            match uploader.upload_texture_rgba8(&image.rgba, image.width, image.height) {
+                Ok(full) => {
+                    assets.release_image(&uri);
+                    let Some(tex) = self.textures.get_mut(&uri) else {
+                        continue;
+                    };
+                    tex.full = Some(full);
+                    tex.full_bytes = needed;
+                    point_users_at(world, visuals, &tex.users, full);
+                }
+                Err(e) => crate::log_error!("upload failed for '{uri}': {e}"),
+            }
+        }
+
+        // The budget may have shrunk, or visible textures grown; shed what isn't shown.
+        self.make_room(world, 0, visuals, uploader);
+        self.publish_stats();
+    }
+
+    fn update_visibility(&mut self, world: &World, visuals: &VisualWorld) {
+        let clip = Mat4::from_cols_array_2d(&world_to_clip(visuals));
+        let camera = Vec3::from(visuals.camera_position());
+        for tex in self.textures.values_mut() {
+            tex.users.retain(|&cid| {
+                world
+                    .get_component_by_id_as::<RenderableComponent>(cid)
+                    .is_some()
+            });
+            let mut nearest: Option<f32> = None;
+            for &cid in &tex.users {
+                let Some(instance) = world
+                    .get_component_by_id_as::<RenderableComponent>(cid)
+                    .and_then(|r| r.get_handle())
+                    .and_then(|h| visuals.instance(h))
+                else {
+                    continue;
+                };
+                let position = Vec4::from(instance.transform.model[3]);
+                let p = clip * position;
+                let limit = p.w * VISIBLE_MARGIN;
+                if p.w <= 0.0 || p.x.abs() > limit || p.y.abs() > limit {
+                    continue;
+                }
+                let d = position.truncate().distance(camera);
+                nearest = Some(nearest.map_or(d, |n| n.min(d)));
+            }
+            if let Some(d) = nearest {
+                tex.last_visible = self.frame;
+                tex.distance = d;
+            }
+        }
+    }
+
+    /// Drop full copies of textures not visible this frame, least recently visible first,
+    /// until `extra` more bytes fit in the budget. Returns whether they fit.
+    fn make_room(
+        &mut self,
+        world: &World,
+        extra: u64,
+        visuals: &mut VisualWorld,
+        uploader: &mut dyn TextureUploader,
+    ) -> bool {
+        loop {
+            if self.resident_bytes() + extra <= self.policy.budget_bytes {
+                return true;
+            }
+            let victim = self
+                .textures
+                .iter()
+                .filter(|(_, t)| t.full.is_some() && t.last_visible < self.frame)
+                .min_by_key(|(_, t)| t.last_visible)
+                .map(|(uri, _)| uri.clone());
+            let Some(tex) = victim.and_then(|uri| self.textures.get_mut(&uri)) else {
+                return false;
+            };
+            if let Some(full) = tex.full.take()
+                && let Err(e) = uploader.release_texture(full)
+            {
+                crate::log_warn!("texture release failed: {e}");
+            }
+            point_users_at(world, visuals, &tex.users, tex.low);
+            self.evictions += 1;
+            profiling::count("textures.evictions", 1);
+        }
+    }
+
+    fn resident_bytes(&self) -> u64 {
+        self.textures
+            .values()
+            .map(StreamedTexture::resident_bytes)
+            .sum()
+    }
+
+    fn publish_stats(&mut self) {
+        self.stats = TextureStreamingStats {
+            budget_bytes: self.policy.budget_bytes,
+            resident_bytes: self.resident_bytes(),
+            textures: self.textures.len(),
+            full_res: self
+                .textures
+                .values()
+                .filter(|t| t.full.is_some() || t.is_small())
+                .count(),
+            visible: self
+                .textures
+                .values()
+                .filter(|t| t.last_visible == self.frame)
+                .count(),
+            evictions: self.evictions,
+        };
+        let s = &self.stats;
+        profiling::set_gauge("textures.budget_bytes", s.budget_bytes as f64);
+        profiling::set_gauge("textures.resident_bytes", s.resident_bytes as f64);
+        profiling::set_gauge("textures.streamed", s.textures as f64);
+        profiling::set_gauge("textures.full_res", s.full_res as f64);
+        profiling::set_gauge("textures.visible", s.visible as f64);
+    }
+}
+
+fn point_users_at(
+    world: &World,
+    visuals: &mut VisualWorld,
+    users: &[ComponentId],
+    handle: TextureHandle,
+) {
+    for &cid in users {
+        if let Some(instance) = world
+            .get_component_by_id_as::<RenderableComponent>(cid)
+            .and_then(|r| r.get_handle())
+        {
+            let _ = visuals.update_texture(instance, Some(handle));
+        }
+    }
+}
+
+fn image_bytes(width: u32, height: u32) -> u64 {
+    u64::from(width) * u64::from(height) * 4
+}
+
+/// `image` shrunk so its longest side is at most `max_dim`; `None` if it already fits.
+fn downscale(image: &DecodedImage, max_dim: u32) -> Option<DecodedImage> {
+    let longest = image.width.max(image.height);
+    if longest <= max_dim.max(1) {
+        return None;
+    }
+    let scale = max_dim.max(1) as f32 / longest as f32;
+    let width = ((image.width as f32 * scale).round() as u32).max(1);
+    let height = ((image.height as f32 * scale).round() as u32).max(1);
+    let src = image::RgbaImage::from_raw(image.width, image.height, image.rgba.clone())?;
+    let small = image::imageops::resize(&src, width, height, image::imageops::FilterType::Triangle);
+    Some(DecodedImage {
+        rgba: small.into_raw(),
+        width,
+        height,
+    })
+}
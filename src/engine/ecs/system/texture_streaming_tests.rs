@@ -0,0 +1,137 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    use crate::engine::assets::AssetServer;
+    use crate::engine::ecs::component::{RenderableComponent, TextureComponent};
+    use crate::engine::ecs::system::texture_streaming::{TextureStreamer, TextureStreamingPolicy};
+    use crate::engine::ecs::{ComponentId, World};
+    use crate::engine::graphics::primitives::{
+        CpuMeshHandle, GpuRenderable, MaterialHandle, MeshHandle, Renderable, Transform,
+    };
+    use crate::engine::graphics::{RendererError, TextureHandle, TextureUploader, VisualWorld};
+
+    /// Records texture sizes instead of talking to a GPU.
+    #[derive(Default)]
+    struct FakeUploader {
+        next: u32,
+        resident: HashMap<TextureHandle, (u32, u32)>,
+    }
+
+    impl TextureUploader for FakeUploader {
+        fn upload_texture_rgba8(
+            &mut self,
+            _rgba: &[u8],
+            width: u32,
+            height: u32,
+        ) -> Result<TextureHandle, RendererError> {
+            self.next += 1;
+            let handle = TextureHandle(self.next);
+            self.resident.insert(handle, (width, height));
+            Ok(handle)
+        }
+
+        fn release_texture(&mut self, handle: TextureHandle) -> Result<(), RendererError> {
+            self.resident.remove(&handle);
+            Ok(())
+        }
+    }
+
+    fn textured_renderable(
+        world: &mut World,
+        visuals: &mut VisualWorld,
+        uri: &str,
+        x: f32,
+    ) -> ComponentId {
+        let renderable = Renderable::new(CpuMeshHandle(0), MaterialHandle::TOON_MESH);
+        let cid = world.add_component(RenderableComponent::new(renderable));
+        let tex = world.add_component(TextureComponent::new(uri));
+        world.add_child(cid, tex).unwrap();
+
+        let mut transform = Transform::default();
+        transform.translation.x = x;
+        transform.recompute_model();
+        let gpu = GpuRenderable::new(MeshHandle(0), MaterialHandle::TOON_MESH);
+        let handle = visuals.register(cid, gpu, transform, [1.0; 4], None);
+        world
+            .get_component_by_id_as_mut::<RenderableComponent>(cid)
+            .unwrap()
+            .handle = Some(handle);
+        cid
+    }
+
+    fn move_to(world: &World, visuals: &mut VisualWorld, cid: ComponentId, x: f32) {
+        let handle = world
+            .get_component_by_id_as::<RenderableComponent>(cid)
+            .unwrap()
+            .get_handle()
+            .unwrap();
+        let mut model = visuals.instance(handle).unwrap().transform.model;
+        model[3][0] = x;
+        visuals.update_model(handle, model);
+    }
+
+    #[test]
+    fn upgrades_visible_textures_and_evicts_hidden_ones_within_budget() {
+        let dir = std::env::temp_dir().join(format!("little-cat-stream-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["a.png", "b.png"] {
+            image::RgbaImage::from_pixel(64, 64, image::Rgba([0, 255, 0, 255]))
+                .save(dir.join(name))
+                .unwrap();
+        }
+        let full = 64 * 64 * 4;
+        let low = 8 * 8 * 4;
+
+        let mut world = World::default();
+        let mut visuals = VisualWorld::new();
+        let mut assets = AssetServer::new(Some(dir.clone()));
+        let mut uploader = FakeUploader::default();
+        // Room for both low copies and a single full one.
+        let mut streamer = TextureStreamer::new(TextureStreamingPolicy {
+            budget_bytes: 2 * low + full,
+            low_res_max_dim: 8,
+            max_upgrades_per_frame: 2,
+        });
+
+        let a = textured_renderable(&mut world, &mut visuals, "a.png", 0.0);
+        let b = textured_renderable(&mut world, &mut visuals, "b.png", 1000.0);
+        for (cid, uri) in [(a, "a.png"), (b, "b.png")] {
+            let handle = streamer
+                .attach(uri, cid, &mut assets, &mut uploader)
+                .unwrap();
+            assert_eq!(uploader.resident[&handle], (8, 8));
+        }
+
+        let mut frame = |world: &World, visuals: &mut VisualWorld| {
+            for _ in 0..3 {
+                streamer.update(world, visuals, &mut assets, &mut uploader);
+                assets.wait_idle(Duration::from_secs(10));
+            }
+            streamer.stats()
+        };
+
+        // Only `a` is on screen.
+        let stats = frame(&world, &mut visuals);
+        assert_eq!((stats.full_res, stats.visible), (1, 1));
+        assert_eq!(stats.resident_bytes, 2 * low + full);
+
+        // Swap: `b` needs the full slot, so `a` drops back to its low copy.
+        move_to(&world, &mut visuals, a, 1000.0);
+        move_to(&world, &mut visuals, b, 0.0);
+        let stats = frame(&world, &mut visuals);
+        assert_eq!(stats.evictions, 1);
+        assert_eq!(stats.resident_bytes, 2 * low + full);
+        assert_eq!(
+            uploader
+                .resident
+                .values()
+                .filter(|s| **s == (64, 64))
+                .count(),
+            1
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -1,8 +1,11 @@
+use crate::engine::assets::AssetServer;
 use crate::engine::ecs::component::{RenderableComponent, TextureComponent};
+use crate::engine::ecs::system::texture_streaming::{
+    TextureStreamer, TextureStreamingPolicy, TextureStreamingStats,
+};
 use crate::engine::ecs::{ComponentId, World};
 use crate::engine::graphics::{TextureHandle, TextureUploader, VisualWorld};
 use std::collections::HashMap;
-use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone)]
 struct TextureRecord {
@@ -16,6 +19,8 @@ pub struct TextureSystem {
     uri_cache: HashMap<String, TextureHandle>,
     /// RenderableComponent cid -> TextureComponent cid
     pending_attach: HashMap<ComponentId, ComponentId>,
+    /// Budgeted residency; `None` uploads every texture at full resolution once.
+    streaming: Option<TextureStreamer>,
 }
 
 impl TextureSystem {
@@ -23,6 +28,35 @@ impl TextureSystem {
         Self::default()
     }
 
+    /// Stream textures under `policy` (see `TextureStreamer`), or stop with `None`.
+    ///
+    /// Affects textures attached from now on; set it before loading the scene.
+    pub fn set_streaming(&mut self, policy: Option<TextureStreamingPolicy>) {
+        match (&mut self.streaming, policy) {
+            (Some(streamer), Some(policy)) => streamer.policy = policy,
+            (streaming, policy) => *streaming = policy.map(TextureStreamer::new),
+        }
+    }
+
+    pub fn streaming_stats(&self) -> Option<TextureStreamingStats> {
+        self.streaming.as_ref().map(TextureStreamer::stats)
+    }
+
+    /// Per-frame streaming pass; no-op without a streaming policy.
+    ///
+    /// Must run after `flush_pending`.
+    pub fn update_streaming(
+        &mut self,
+        world: &World,
+        visuals: &mut VisualWorld,
+        assets: &mut AssetServer,
+        uploader: &mut dyn TextureUploader,
+    ) {
+        if let Some(streamer) = self.streaming.as_mut() {
+            streamer.update(world, visuals, assets, uploader);
+        }
+    }
+
     pub fn register_texture(
         &mut self,
         world: &mut World,
@@ -54,13 +88,21 @@ impl TextureSystem {
         }
     }
 
-    /// Decode+upload any textures that are now attachable to renderables.
+    /// Whether a renderable is still waiting for its texture to be attached.
+    pub fn is_pending(&self, renderable: ComponentId) -> bool {
+        self.pending_attach.contains_key(&renderable)
+    }
+
+    /// Upload any textures that are now attachable to renderables.
     ///
-    /// Must run after renderables are flushed into `VisualWorld` so we can update instance handles.
+    /// Images come from `assets` (already decoded if they were preloaded, decoded now
+    /// otherwise). Must run after renderables are flushed into `VisualWorld` so we can update
+    /// instance handles.
     pub fn flush_pending(
         &mut self,
         world: &mut World,
         visuals: &mut VisualWorld,
+        assets: &mut AssetServer,
         uploader: &mut dyn TextureUploader,
     ) {
         let pairs: Vec<(ComponentId, ComponentId)> =
@@ -84,6 +126,15 @@ impl TextureSystem {
                 continue;
             };
 
+            if let Some(streamer) = self.streaming.as_mut() {
+                if let Some(handle) = streamer.attach(&record.uri, renderable_cid, assets, uploader)
+                {
+                    let _ = visuals.update_texture(instance_handle, Some(handle));
+                }
+                let _ = self.pending_attach.remove(&renderable_cid);
+                continue;
+            }
+
             if let Some(cached) = self.uri_cache.get(&record.uri).copied() {
                 record.gpu = Some(cached);
             }
@@ -92,86 +143,27 @@ impl TextureSystem {
                 Some(h) => h,
                 None => {
                     let uri = record.uri.as_str();
-                    let raw_path_str = uri.strip_prefix("file://").unwrap_or(uri);
-                    let raw_path = Path::new(raw_path_str);
-
-                    let mut tried: Vec<PathBuf> = Vec::new();
-                    let resolved_path: Option<PathBuf> = if raw_path.is_absolute() {
-                        tried.push(raw_path.to_path_buf());
-                        if raw_path.exists() {
-                            Some(raw_path.to_path_buf())
-                        } else {
-                            None
-                        }
-                    } else {
-                        // 1) Current working directory
-                        if let Ok(cwd) = std::env::current_dir() {
-                            let p = cwd.join(raw_path);
-                            tried.push(p.clone());
-                            if p.exists() {
-                                Some(p)
-                            } else {
-                                // 2) Crate root (works even if CWD is target/...)
-                                let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-                                let p2 = manifest_dir.join(raw_path);
-                                tried.push(p2.clone());
-                                if p2.exists() { Some(p2) } else { None }
-                            }
-                        } else {
-                            let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-                            let p2 = manifest_dir.join(raw_path);
-                            tried.push(p2.clone());
-                            if p2.exists() { Some(p2) } else { None }
-                        }
-                    };
-
-                    let Some(path) = resolved_path else {
-                        let cwd = std::env::current_dir()
-                            .map(|p| p.display().to_string())
-                            .unwrap_or_else(|_| "<unknown>".to_string());
-                        println!("[TextureSystem] read failed for '{uri}'");
-                        println!("[TextureSystem]   cwd = {cwd}");
-                        for p in tried {
-                            println!("[TextureSystem]   tried: {}", p.display());
-                        }
-                        let _ = self.pending_attach.remove(&renderable_cid);
-                        continue;
-                    };
-
-                    let bytes = match std::fs::read(&path) {
-                        Ok(b) => b,
+                    let image = match assets.load_image(uri) {
+                        Ok(image) => image,
                         Err(e) => {
-                            let cwd = std::env::current_dir()
-                                .map(|p| p.display().to_string())
-                                .unwrap_or_else(|_| "<unknown>".to_string());
-                            println!("[TextureSystem] read failed for '{uri}': {e}");
-                            println!("[TextureSystem]   cwd = {cwd}");
-                            println!("[TextureSystem]   resolved: {}", path.display());
+                            crate::log_warn!("texture load failed: {e}");
                             let _ = self.pending_attach.remove(&renderable_cid);
                             continue;
                         }
                     };
 
-                    let dyn_img = match image::load_from_memory(&bytes) {
-                        Ok(i) => i,
-                        Err(e) => {
-                            println!("[TextureSystem] decode failed for '{uri}': {:?}", e);
-                            let _ = self.pending_attach.remove(&renderable_cid);
-                            continue;
-                        }
-                    };
-
-                    let rgba = dyn_img.to_rgba8();
-                    let (w, h) = rgba.dimensions();
-
-                    let handle = match uploader.upload_texture_rgba8(rgba.as_raw(), w, h) {
-                        Ok(h) => h,
-                        Err(e) => {
-                            println!("[TextureSystem] upload failed for '{uri}': {:?}", e);
-                            let _ = self.pending_attach.remove(&renderable_cid);
-                            continue;
-                        }
-                    };
+                    let handle =
+                        match uploader.upload_texture_rgba8(&image.rgba, image.width, image.height)
+                        {
+                            Ok(h) => h,
+                            Err(e) => {
+                                crate::log_error!("upload failed for '{uri}': {e}");
+                                let _ = self.pending_attach.remove(&renderable_cid);
+                                continue;
+                            }
+                        };
+                    // On the GPU now; later users hit `uri_cache`.
+                    assets.release_image(uri);
 
                     record.gpu = Some(handle);
                     self.uri_cache.insert(record.uri.clone(), handle);
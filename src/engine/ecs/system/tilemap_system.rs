@@ -0,0 +1,117 @@
+use crate::engine::ecs::component::{RenderableComponent, TextureComponent, TilemapComponent};
+use crate::engine::ecs::system::{RenderableSystem, TextureSystem};
+use crate::engine::ecs::{ComponentId, World};
+use crate::engine::graphics::primitives::{MaterialHandle, Renderable};
+use crate::engine::graphics::{RenderAssets, VisualWorld};
+use crate::engine::spatial::SpatialGrid;
+use std::collections::HashMap;
+
+/// Builds chunk meshes for `TilemapComponent`s.
+///
+/// Each non-empty chunk is a child `RenderableComponent` of the tilemap, so chunks follow the
+/// tilemap's Transform and go through the normal RenderableSystem/TextureSystem paths.
+/// Only chunks marked dirty by `TilemapComponent::set_tile` are rebuilt.
+#[derive(Debug, Default)]
+pub struct TilemapSystem {
+    tilemaps: Vec<ComponentId>,
+
+    /// (tilemap cid, chunk coords) -> chunk RenderableComponent cid
+    chunks: HashMap<(ComponentId, (u32, u32)), ComponentId>,
+}
+
+impl TilemapSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register_tilemap(
+        &mut self,
+        world: &mut World,
+        _visuals: &mut VisualWorld,
+        component: ComponentId,
+    ) {
+        if world
+            .get_component_by_id_as::<TilemapComponent>(component)
+            .is_none()
+        {
+            return;
+        }
+        if !self.tilemaps.contains(&component) {
+            self.tilemaps.push(component);
+        }
+    }
+
+    /// Rebuild dirty chunks.
+    ///
+    /// Must run before `RenderableSystem::flush_pending` so new/changed chunk meshes are
+    /// uploaded in the same frame.
+    pub fn flush_pending(
+        &mut self,
+        world: &mut World,
+        visuals: &mut VisualWorld,
+        render_assets: &mut RenderAssets,
+        renderables: &mut RenderableSystem,
+        textures: &mut TextureSystem,
+        spatial: &mut SpatialGrid,
+    ) {
+        let tilemaps = self.tilemaps.clone();
+        for tilemap_cid in tilemaps {
+            let Some(tilemap) = world.get_component_by_id_as_mut::<TilemapComponent>(tilemap_cid)
+            else {
+                // Tilemap was removed; its chunk renderables went with its subtree.
+                self.tilemaps.retain(|c| *c != tilemap_cid);
+                self.chunks.retain(|(t, _), _| *t != tilemap_cid);
+                continue;
+            };
+            if !tilemap.has_dirty_chunks() {
+                continue;
+            }
+            let dirty = tilemap.take_dirty_chunks();
+
+            for chunk in dirty {
+                let Some(tilemap) = world.get_component_by_id_as::<TilemapComponent>(tilemap_cid)
+                else {
+                    break;
+                };
+                let mesh = tilemap.build_chunk_mesh(chunk);
+                let atlas = tilemap.atlas.clone();
+                let existing = self.chunks.get(&(tilemap_cid, chunk)).copied();
+
+                match (mesh, existing) {
+                    (Some(mesh), Some(chunk_cid)) => {
+                        let cpu_mesh = render_assets.register_mesh(mesh);
+                        renderables.replace_mesh(world, chunk_cid, cpu_mesh);
+                    }
+                    (Some(mesh), None) => {
+                        let cpu_mesh = render_assets.register_mesh(mesh);
+                        let chunk_cid = world.add_component(RenderableComponent::new(
+                            Renderable::new(cpu_mesh, MaterialHandle::TOON_MESH),
+                        ));
+                        let _ = world.add_child(tilemap_cid, chunk_cid);
+                        renderables.register_renderable(world, visuals, chunk_cid);
+
+                        if let Some(uri) = atlas {
+                            let tex = world.add_component(TextureComponent::new(uri));
+                            let _ = world.add_child(chunk_cid, tex);
+                            textures.register_texture(world, visuals, tex);
+                        }
+
+                        self.chunks.insert((tilemap_cid, chunk), chunk_cid);
+                    }
+                    (None, Some(chunk_cid)) => {
+                        if let Some(chunk) =
+                            world.get_component_by_id_as::<RenderableComponent>(chunk_cid)
+                        {
+                            renderables.release_mesh(chunk.renderable.mesh);
+                        }
+                        renderables.remove_renderable(world, visuals, chunk_cid);
+                        spatial.remove(chunk_cid);
+                        let _ = world.remove_component_subtree(chunk_cid);
+                        self.chunks.remove(&(tilemap_cid, chunk));
+                    }
+                    (None, None) => {}
+                }
+            }
+        }
+    }
+}
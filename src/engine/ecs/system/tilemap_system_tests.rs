@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod tests {
+    use crate::engine::ecs::component::{TilemapComponent, TransformComponent};
+    use crate::engine::graphics::NullUploader;
+    use crate::engine::{RendererBackend, UniverseBuilder};
+
+    #[test]
+    fn rebuilt_and_emptied_chunks_release_their_meshes() {
+        let mut u = UniverseBuilder::new()
+            .renderer(RendererBackend::None)
+            .repl(false)
+            .build();
+        let mut uploader = NullUploader::default();
+        let root = u.world.add_component(TransformComponent::new());
+        let mut tiles = TilemapComponent::new(4, 4, [1.0, 1.0]);
+        tiles.set_tile(0, 0, Some(0));
+        let tilemap = u.world.add_component(tiles);
+        u.world.add_child(root, tilemap).unwrap();
+        u.world.init_component_tree(root, &mut u.command_queue);
+        let mut frame = |u: &mut crate::engine::Universe| {
+            u.systems
+                .process_commands(&mut u.world, &mut u.visuals, &mut u.command_queue);
+            u.prepare_render_with(&mut uploader);
+        };
+        frame(&mut u);
+        let meshes = u.render_assets.mesh_count();
+
+        for x in 1..4 {
+            u.world
+                .get_component_by_id_as_mut::<TilemapComponent>(tilemap)
+                .unwrap()
+                .set_tile(x, 0, Some(1));
+            frame(&mut u);
+        }
+        assert_eq!(u.render_assets.mesh_count(), meshes, "one mesh per chunk");
+
+        let map = u
+            .world
+            .get_component_by_id_as_mut::<TilemapComponent>(tilemap);
+        let map = map.unwrap();
+        for x in 0..4 {
+            map.set_tile(x, 0, None);
+        }
+        frame(&mut u);
+        frame(&mut u);
+        assert_eq!(u.render_assets.mesh_count(), meshes - 1);
+    }
+}
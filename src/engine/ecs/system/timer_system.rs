@@ -0,0 +1,263 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::engine::ecs::system::PendingCommands;
+use crate::engine::ecs::system::movement_system::MOVEMENT_FIXED_DT;
+use crate::engine::ecs::{CommandQueue, World};
+
+/// Longest frame time a tick catches up on; anything beyond is dropped (as `MovementSystem`).
+const MAX_FRAME_DT: f32 = 0.25;
+
+/// Handle for cancelling a timer; prints as `t<n>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TimerId(u64);
+
+impl fmt::Display for TimerId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "t{}", self.0)
+    }
+}
+
+impl FromStr for TimerId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.strip_prefix('t')
+            .unwrap_or(s)
+            .parse()
+            .map(TimerId)
+            .map_err(|_| format!("'{s}' is not a timer id (t<n>)"))
+    }
+}
+
+/// Code a timer runs, with the world and the frame's command queue.
+pub type TimerCallback = Box<dyn FnMut(&mut World, &mut CommandQueue)>;
+
+pub enum TimerAction {
+    /// REPL command line, run after the tick like a state machine hook.
+    Command(String),
+    Callback(TimerCallback),
+}
+
+impl fmt::Debug for TimerAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimerAction::Command(line) => f.debug_tuple("Command").field(line).finish(),
+            TimerAction::Callback(_) => f.write_str("Callback"),
+        }
+    }
+}
+
+impl fmt::Display for TimerAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimerAction::Command(line) => f.write_str(line),
+            TimerAction::Callback(_) => f.write_str("<callback>"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Timer {
+    pub id: TimerId,
+    pub action: TimerAction,
+    /// Fixed step it fires on.
+    due: u64,
+    /// Steps between repeats; `None` fires once.
+    period: Option<u64>,
+}
+
+impl Timer {
+    /// Seconds between repeats; `None` for a one-shot timer.
+    pub fn period(&self) -> Option<f32> {
+        self.period.map(|p| p as f32 * MOVEMENT_FIXED_DT)
+    }
+}
+
+/// World resource of delayed and repeating actions.
+///
+/// Time counts whole `MOVEMENT_FIXED_DT` steps, so a timer fires on the same step at any frame
+/// rate and tests can step it exactly. Delays round up to a step, and every timer waits at
+/// least one. Timers due on the same step fire in the order they were added.
+///
+/// ```ignore
+/// let timers = world.resources_mut().get_or_insert_with(Scheduler::default);
+/// timers.after(2.0, "despawn 4v1");
+/// timers.every_fn(0.5, |world, queue| { /* ... */ });
+/// ```
+#[derive(Debug, Default)]
+pub struct Scheduler {
+    timers: Vec<Timer>,
+    next_id: u64,
+    step: u64,
+    /// Frame time not yet consumed by a step.
+    accumulator: f32,
+    /// Timers out of `timers` while they fire, and which of them were cancelled meanwhile.
+    firing: Vec<TimerId>,
+    cancelled: Vec<TimerId>,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run a REPL command line once, `seconds` from now.
+    pub fn after(&mut self, seconds: f32, command: impl Into<String>) -> TimerId {
+        self.add(seconds, false, TimerAction::Command(command.into()))
+    }
+
+    /// Run a REPL command line every `seconds`, starting `seconds` from now.
+    pub fn every(&mut self, seconds: f32, command: impl Into<String>) -> TimerId {
+        self.add(seconds, true, TimerAction::Command(command.into()))
+    }
+
+    pub fn after_fn(
+        &mut self,
+        seconds: f32,
+        callback: impl FnMut(&mut World, &mut CommandQueue) + 'static,
+    ) -> TimerId {
+        self.add(seconds, false, TimerAction::Callback(Box::new(callback)))
+    }
+
+    pub fn every_fn(
+        &mut self,
+        seconds: f32,
+        callback: impl FnMut(&mut World, &mut CommandQueue) + 'static,
+    ) -> TimerId {
+        self.add(seconds, true, TimerAction::Callback(Box::new(callback)))
+    }
+
+    /// Stop a timer, including a repeating one from inside its own callback. `false` if it
+    /// already fired or never existed.
+    pub fn cancel(&mut self, id: TimerId) -> bool {
+        if let Some(i) = self.timers.iter().position(|t| t.id == id) {
+            self.timers.remove(i);
+            return true;
+        }
+        if self.firing.contains(&id) && !self.cancelled.contains(&id) {
+            self.cancelled.push(id);
+            return true;
+        }
+        false
+    }
+
+    /// Seconds until `id` fires next.
+    pub fn remaining(&self, id: TimerId) -> Option<f32> {
+        let timer = self.timers.iter().find(|t| t.id == id)?;
+        Some((timer.due - self.step) as f32 * MOVEMENT_FIXED_DT)
+    }
+
+    /// Pending timers, soonest first.
+    pub fn timers(&self) -> impl Iterator<Item = &Timer> {
+        let mut timers: Vec<&Timer> = self.timers.iter().collect();
+        timers.sort_by_key(|t| (t.due, t.id));
+        timers.into_iter()
+    }
+
+    /// Seconds of scheduler time so far.
+    pub fn elapsed(&self) -> f64 {
+        self.step as f64 * f64::from(MOVEMENT_FIXED_DT)
+    }
+
+    pub fn len(&self) -> usize {
+        self.timers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timers.is_empty()
+    }
+
+    fn add(&mut self, seconds: f32, repeat: bool, action: TimerAction) -> TimerId {
+        let id = TimerId(self.next_id);
+        self.next_id += 1;
+        // The small bias keeps 0.5 s at 30 steps despite float error.
+        let steps = (seconds.max(0.0) / MOVEMENT_FIXED_DT - 1e-3)
+            .ceil()
+            .max(1.0) as u64;
+        self.timers.push(Timer {
+            id,
+            action,
+            due: self.step + steps,
+            period: repeat.then_some(steps),
+        });
+        id
+    }
+
+    /// Whole steps `dt_sec` completes.
+    fn advance(&mut self, dt_sec: f32) -> u32 {
+        self.accumulator += dt_sec.clamp(0.0, MAX_FRAME_DT);
+        let mut steps = 0;
+        while self.accumulator >= MOVEMENT_FIXED_DT {
+            self.accumulator -= MOVEMENT_FIXED_DT;
+            steps += 1;
+        }
+        steps
+    }
+
+    /// Move one step on and take out the timers due, in firing order.
+    fn take_due(&mut self) -> Vec<Timer> {
+        self.step += 1;
+        let step = self.step;
+        let (mut due, rest): (Vec<Timer>, Vec<Timer>) = std::mem::take(&mut self.timers)
+            .into_iter()
+            .partition(|t| t.due <= step);
+        self.timers = rest;
+        due.sort_by_key(|t| (t.due, t.id));
+        self.firing = due.iter().map(|t| t.id).collect();
+        due
+    }
+
+    /// Put a fired timer back if it repeats and wasn't cancelled.
+    fn finish(&mut self, mut timer: Timer) {
+        if let Some(period) = timer.period
+            && !self.cancelled.contains(&timer.id)
+        {
+            timer.due += period;
+            self.timers.push(timer);
+        }
+    }
+
+    fn end_step(&mut self) {
+        self.firing.clear();
+        self.cancelled.clear();
+    }
+}
+
+/// Advances the world's `Scheduler` and fires its timers.
+#[derive(Debug, Default)]
+pub struct TimerSystem;
+
+impl TimerSystem {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Run every fixed step `dt_sec` covers. Callbacks run right away; command lines go to
+    /// `PendingCommands`.
+    pub fn tick(&mut self, world: &mut World, queue: &mut CommandQueue, dt_sec: f32) {
+        let Some(steps) = world.resource_mut::<Scheduler>().map(|s| s.advance(dt_sec)) else {
+            return;
+        };
+        for _ in 0..steps {
+            let Some(due) = world.resource_mut::<Scheduler>().map(Scheduler::take_due) else {
+                return;
+            };
+            for mut timer in due {
+                match &mut timer.action {
+                    TimerAction::Command(line) => world
+                        .resources_mut()
+                        .get_or_insert_with(PendingCommands::default)
+                        .push(line.clone()),
+                    TimerAction::Callback(callback) => callback(world, queue),
+                }
+                if let Some(scheduler) = world.resource_mut::<Scheduler>() {
+                    scheduler.finish(timer);
+                }
+            }
+            if let Some(scheduler) = world.resource_mut::<Scheduler>() {
+                scheduler.end_step();
+            }
+        }
+    }
+}
@@ -0,0 +1,76 @@
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use crate::engine::ecs::component::TransformComponent;
+    use crate::engine::ecs::system::movement_system::MOVEMENT_FIXED_DT;
+    use crate::engine::ecs::system::{PendingCommands, Scheduler, TimerId, TimerSystem};
+    use crate::engine::ecs::{CommandQueue, World};
+
+    #[derive(Debug, Default)]
+    struct Fired(u32);
+
+    fn pending(world: &mut World) -> Vec<String> {
+        world
+            .resource_mut::<PendingCommands>()
+            .map(PendingCommands::take)
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn one_shot_fires_on_the_same_step_at_any_frame_rate() {
+        for frame in [1.0 / 30.0, 1.0 / 144.0, 0.013] {
+            let mut world = World::default();
+            world
+                .resources_mut()
+                .get_or_insert_with(Scheduler::default)
+                .after(0.5, "despawn 1v1");
+            let (mut system, mut queue) = (TimerSystem::new(), CommandQueue::new());
+
+            let mut t = 0.0;
+            let fired_at = loop {
+                system.tick(&mut world, &mut queue, frame);
+                t += frame;
+                if !pending(&mut world).is_empty() {
+                    break world.resource::<Scheduler>().unwrap().elapsed();
+                }
+                assert!(t < 1.0, "never fired at frame {frame}");
+            };
+            assert_eq!(fired_at, 30.0 * f64::from(MOVEMENT_FIXED_DT));
+            assert!(world.resource::<Scheduler>().unwrap().is_empty());
+        }
+    }
+
+    #[test]
+    fn repeating_callbacks_queue_commands_and_can_cancel_themselves() {
+        let mut world = World::default();
+        let transform = world.add_component(TransformComponent::new());
+        let own_id = Rc::new(Cell::new(None::<TimerId>));
+        let id = {
+            let own_id = own_id.clone();
+            let scheduler = world.resources_mut().get_or_insert_with(Scheduler::default);
+            scheduler.every_fn(0.1, move |world, queue| {
+                queue.queue_register_transform(transform);
+                let fired = world.resources_mut().get_or_insert_with(Fired::default);
+                fired.0 += 1;
+                if fired.0 == 3 {
+                    let scheduler = world.resource_mut::<Scheduler>().unwrap();
+                    assert!(scheduler.cancel(own_id.get().unwrap()));
+                }
+            })
+        };
+        own_id.set(Some(id));
+        let remaining = world.resource::<Scheduler>().unwrap().remaining(id);
+        assert_eq!(remaining, Some(6.0 * MOVEMENT_FIXED_DT));
+
+        let (mut system, mut queue) = (TimerSystem::new(), CommandQueue::new());
+        // One long frame runs every step it covers: repeats at 0.1 and 0.2.
+        system.tick(&mut world, &mut queue, 0.21);
+        assert_eq!(world.resource::<Fired>().unwrap().0, 2);
+
+        system.tick(&mut world, &mut queue, 0.2);
+        assert_eq!(world.resource::<Fired>().unwrap().0, 3);
+        assert!(world.resource::<Scheduler>().unwrap().is_empty());
+    }
+}
@@ -1,9 +1,14 @@
 use crate::engine::ecs::ComponentId;
 use crate::engine::ecs::World;
-use crate::engine::ecs::component::{Camera2DComponent, RenderableComponent, TransformComponent};
+use crate::engine::ecs::component::{
+    AttachmentComponent, Camera2DComponent, Camera3DComponent, RenderableComponent,
+    TransformComponent,
+};
 use crate::engine::ecs::system::System;
+use crate::engine::ecs::system::camera_system::mat4_inverse;
 use crate::engine::graphics::VisualWorld;
 use crate::engine::user_input::InputState;
+use glam::{Mat4, Vec3};
 
 /// System responsible for syncing `TransformComponent` changes into `VisualWorld`.
 ///
@@ -11,23 +16,98 @@ use crate::engine::user_input::InputState;
 /// - An entity can have multiple TransformComponents.
 /// - A `TransformComponent` can parent other transforms to form groups.
 /// - Instances in `VisualWorld` are created per `RenderableComponent` under transforms.
+/// - World-space bounds in `SpatialGrid` follow the same model matrices.
+/// - `AttachmentComponent`s are resolved once per frame in `resolve_attachments`.
 #[derive(Debug, Default)]
-pub struct TransformSystem;
+pub struct TransformSystem {
+    attachments: Vec<ComponentId>,
+}
 
 impl TransformSystem {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    pub fn register_attachment(&mut self, world: &World, component: ComponentId) {
+        if world
+            .get_component_by_id_as::<AttachmentComponent>(component)
+            .is_none()
+        {
+            return;
+        }
+        if !self.attachments.contains(&component) {
+            self.attachments.push(component);
+        }
     }
 
-    fn mat4_mul(a: [[f32; 4]; 4], b: [[f32; 4]; 4]) -> [[f32; 4]; 4] {
-        let mut out = [[0.0f32; 4]; 4];
-        for c in 0..4 {
-            for r in 0..4 {
-                out[c][r] =
-                    a[0][r] * b[c][0] + a[1][r] * b[c][1] + a[2][r] * b[c][2] + a[3][r] * b[c][3];
+    /// Move every attached Transform onto its target's world position (+ offset).
+    ///
+    /// Returns the Transform cids whose translation changed; the caller must run
+    /// `transform_changed` for them. Attachments resolve in registration order, so a follower
+    /// of a follower sees its target's updated position in the same frame.
+    pub fn resolve_attachments(&mut self, world: &mut World) -> Vec<ComponentId> {
+        let mut changed = Vec::new();
+        let attachments = self.attachments.clone();
+        for attachment_cid in attachments {
+            let Some(attachment) = world
+                .get_component_by_id_as::<AttachmentComponent>(attachment_cid)
+                .copied()
+            else {
+                self.attachments.retain(|c| *c != attachment_cid);
+                continue;
+            };
+            let Some(follower) = world.parent_of(attachment_cid) else {
+                continue;
+            };
+            if world
+                .get_component_by_id_as::<TransformComponent>(follower)
+                .is_none()
+            {
+                continue;
+            }
+            let Some(target) = Self::world_model_including(world, attachment.target.id) else {
+                continue;
+            };
+            let desired = Mat4::from_cols_array_2d(&target).w_axis.truncate()
+                + Vec3::from(attachment.world_offset);
+
+            // Express the desired world position in the follower's parent space.
+            let local = match Self::world_model(world, follower) {
+                Some(parent_model) => {
+                    let Some(inv) = mat4_inverse(&parent_model) else {
+                        continue;
+                    };
+                    Mat4::from_cols_array_2d(&inv).transform_point3(desired)
+                }
+                None => desired,
+            };
+
+            let Some(t) = world.get_component_by_id_as_mut::<TransformComponent>(follower) else {
+                continue;
+            };
+            if t.transform.translation == local {
+                continue;
             }
+            t.transform.translation = local;
+            t.transform.recompute_model();
+            changed.push(follower);
+        }
+        changed
+    }
+
+    /// Like `world_model`, but also applies `cid`'s own model if it is a Transform.
+    pub(crate) fn world_model_including(world: &World, cid: ComponentId) -> Option<[[f32; 4]; 4]> {
+        let own = world
+            .get_component_by_id_as::<TransformComponent>(cid)
+            .map(|t| t.transform.model);
+        match (Self::world_model(world, cid), own) {
+            (Some(parent), Some(own)) => Some(Self::mat4_mul(parent, own)),
+            (parent, own) => parent.or(own),
         }
-        out
+    }
+
+    pub(crate) fn mat4_mul(a: [[f32; 4]; 4], b: [[f32; 4]; 4]) -> [[f32; 4]; 4] {
+        (Mat4::from_cols_array_2d(&a) * Mat4::from_cols_array_2d(&b)).to_cols_array_2d()
     }
 
     /// Compute the world-space model matrix for a component by walking up the component tree
@@ -59,9 +139,12 @@ impl TransformSystem {
     /// Compute the world-space position (translation) for a component.
     pub fn world_position(world: &World, cid: ComponentId) -> Option<[f32; 3]> {
         let model = Self::world_model(world, cid)?;
-        // Column-major translation lives in the last column.
-        let p = model[3];
-        Some([p[0], p[1], p[2]])
+        Some(
+            Mat4::from_cols_array_2d(&model)
+                .w_axis
+                .truncate()
+                .to_array(),
+        )
     }
 
     /// Called by TransformComponent when its values change.
@@ -75,6 +158,7 @@ impl TransformSystem {
         component: ComponentId,
         camera_system: &mut crate::engine::ecs::system::CameraSystem,
         light_system: &mut crate::engine::ecs::system::LightSystem,
+        spatial: &mut crate::engine::spatial::SpatialGrid,
     ) {
         // If this transform has a Camera2D child, update camera translation.
         if let Some(camera2d_cid) = world.children_of(component).iter().copied().find(|&cid| {
@@ -90,6 +174,15 @@ impl TransformSystem {
             );
         }
 
+        // Same for a Camera3D child (view depends on the full ancestor chain).
+        if let Some(camera3d_cid) = world.children_of(component).iter().copied().find(|&cid| {
+            world
+                .get_component_by_id_as::<Camera3DComponent>(cid)
+                .is_some()
+        }) {
+            camera_system.update_camera_3d(world, visuals, camera3d_cid);
+        }
+
         // If any point lights live under this transform, update their world-space position.
         light_system.transform_changed(world, visuals, component);
 
@@ -112,6 +205,7 @@ impl TransformSystem {
 
                     if let Some(model) = Self::world_model(world, child) {
                         visuals.update_model(handle, model);
+                        spatial.update_model(child, &model);
                     }
                 }
             }
@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+
+use crate::engine::ecs::component::TriggerVolumeComponent;
+use crate::engine::ecs::system::TransformSystem;
+use crate::engine::ecs::{ComponentId, World};
+use crate::engine::spatial::{Aabb, SpatialGrid};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerPhase {
+    Enter,
+    Exit,
+}
+
+/// A renderable entering or leaving a trigger volume.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TriggerEvent {
+    /// The `TriggerVolumeComponent`.
+    pub trigger: ComponentId,
+    /// The `RenderableComponent` that entered or left.
+    pub other: ComponentId,
+    pub phase: TriggerPhase,
+}
+
+/// World resource holding the events of the last `TriggerSystem` tick.
+///
+/// Systems running after it in the same tick, and game code after `Universe::update`, see
+/// each event exactly once; the next tick replaces them.
+#[derive(Debug, Default)]
+pub struct TriggerEvents {
+    events: Vec<TriggerEvent>,
+}
+
+impl TriggerEvents {
+    pub fn iter(&self) -> impl Iterator<Item = &TriggerEvent> {
+        self.events.iter()
+    }
+
+    pub fn for_trigger(&self, trigger: ComponentId) -> impl Iterator<Item = &TriggerEvent> {
+        self.events.iter().filter(move |e| e.trigger == trigger)
+    }
+
+    /// What entered `trigger` this tick.
+    pub fn entered(&self, trigger: ComponentId) -> impl Iterator<Item = ComponentId> {
+        self.phase(trigger, TriggerPhase::Enter)
+    }
+
+    /// What left `trigger` this tick (or was removed while inside).
+    pub fn exited(&self, trigger: ComponentId) -> impl Iterator<Item = ComponentId> {
+        self.phase(trigger, TriggerPhase::Exit)
+    }
+
+    fn phase(
+        &self,
+        trigger: ComponentId,
+        phase: TriggerPhase,
+    ) -> impl Iterator<Item = ComponentId> {
+        self.for_trigger(trigger)
+            .filter(move |e| e.phase == phase)
+            .map(|e| e.other)
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+/// Tests `TriggerVolumeComponent`s against the spatial index and publishes `TriggerEvents`.
+#[derive(Debug, Default)]
+pub struct TriggerSystem {
+    triggers: Vec<ComponentId>,
+    /// Renderables inside each trigger as of the last tick, sorted.
+    inside: HashMap<ComponentId, Vec<ComponentId>>,
+}
+
+impl TriggerSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register_trigger(&mut self, world: &World, component: ComponentId) {
+        if world
+            .get_component_by_id_as::<TriggerVolumeComponent>(component)
+            .is_none()
+        {
+            return;
+        }
+        if !self.triggers.contains(&component) {
+            self.triggers.push(component);
+        }
+    }
+
+    /// Compare what overlaps each trigger now with the last tick and replace the world's
+    /// `TriggerEvents` with the difference. Bounds are those of the last command flush.
+    pub fn tick(&mut self, world: &mut World, spatial: &SpatialGrid) {
+        let mut events = Vec::new();
+        let triggers = self.triggers.clone();
+        for trigger in triggers {
+            let Some(volume) = world.get_component_by_id_as::<TriggerVolumeComponent>(trigger)
+            else {
+                self.triggers.retain(|c| *c != trigger);
+                self.inside.remove(&trigger);
+                continue;
+            };
+            let (Some(owner), Some(model)) = (
+                world.parent_of(trigger),
+                TransformSystem::world_model(world, trigger),
+            ) else {
+                continue;
+            };
+            let h = volume.half_extents;
+            let bounds = Aabb::new([-h[0], -h[1], -h[2]], h).transformed(&model);
+            let mut now: Vec<ComponentId> = spatial
+                .query_aabb(&bounds)
+                .into_iter()
+                .filter(|&c| !Self::is_under(world, c, owner))
+                .collect();
+            now.sort_unstable();
+
+            let before = self.inside.entry(trigger).or_default();
+            let event = |other, phase| TriggerEvent {
+                trigger,
+                other,
+                phase,
+            };
+            events.extend(
+                now.iter()
+                    .filter(|c| before.binary_search(c).is_err())
+                    .map(|&c| event(c, TriggerPhase::Enter)),
+            );
+            events.extend(
+                before
+                    .iter()
+                    .filter(|c| now.binary_search(c).is_err())
+                    .map(|&c| event(c, TriggerPhase::Exit)),
+            );
+            *before = now;
+        }
+        world
+            .resources_mut()
+            .get_or_insert_with(TriggerEvents::default)
+            .events = events;
+    }
+
+    fn is_under(world: &World, mut cid: ComponentId, ancestor: ComponentId) -> bool {
+        while let Some(parent) = world.parent_of(cid) {
+            if parent == ancestor {
+                return true;
+            }
+            cid = parent;
+        }
+        false
+    }
+}
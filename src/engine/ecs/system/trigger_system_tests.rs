@@ -0,0 +1,55 @@
+#[cfg(test)]
+mod tests {
+    use crate::engine::ecs::World;
+    use crate::engine::ecs::component::{TransformComponent, TriggerVolumeComponent};
+    use crate::engine::ecs::system::{TriggerEvents, TriggerPhase, TriggerSystem};
+    use crate::engine::spatial::{Aabb, SpatialGrid};
+
+    fn at(x: f32) -> [[f32; 4]; 4] {
+        glam::Mat4::from_translation(glam::Vec3::new(x, 0.0, 0.0)).to_cols_array_2d()
+    }
+
+    #[test]
+    fn reports_enter_and_exit_once() {
+        let mut world = World::default();
+        let zone = world.add_component(TransformComponent::new().with_position(5.0, 0.0, 0.0));
+        let trigger = world.add_component(TriggerVolumeComponent::new(1.0, 1.0, 1.0));
+        world.add_child(zone, trigger).unwrap();
+        // The zone's own mesh never triggers it.
+        let own_mesh = world.add_component(TransformComponent::new());
+        world.add_child(zone, own_mesh).unwrap();
+        let player = world.add_component(TransformComponent::new());
+
+        let unit = Aabb::new([-0.5; 3], [0.5; 3]);
+        let mut spatial = SpatialGrid::default();
+        spatial.insert(own_mesh, unit, &at(5.0));
+        spatial.insert(player, unit, &at(0.0));
+
+        let mut triggers = TriggerSystem::new();
+        triggers.register_trigger(&world, trigger);
+        let mut tick = |world: &mut World, spatial: &SpatialGrid| {
+            triggers.tick(world, spatial);
+            world
+                .resource::<TriggerEvents>()
+                .unwrap()
+                .for_trigger(trigger)
+                .map(|e| (e.other, e.phase))
+                .collect::<Vec<_>>()
+        };
+
+        assert!(tick(&mut world, &spatial).is_empty());
+        spatial.update_model(player, &at(4.0));
+        assert_eq!(
+            tick(&mut world, &spatial),
+            vec![(player, TriggerPhase::Enter)]
+        );
+        // Still inside: no new events.
+        spatial.update_model(player, &at(5.5));
+        assert!(tick(&mut world, &spatial).is_empty());
+        spatial.remove(player);
+        assert_eq!(
+            tick(&mut world, &spatial),
+            vec![(player, TriggerPhase::Exit)]
+        );
+    }
+}
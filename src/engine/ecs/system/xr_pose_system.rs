@@ -0,0 +1,58 @@
+use crate::engine::ecs::component::{TransformComponent, XrPoseComponent};
+use crate::engine::ecs::{CommandQueue, ComponentId, World};
+use crate::engine::user_input::InputState;
+
+/// Copies tracked XR controller poses into the parent Transforms of `XrPoseComponent`s.
+#[derive(Debug, Default)]
+pub struct XrPoseSystem {
+    poses: Vec<ComponentId>,
+}
+
+impl XrPoseSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register_xr_pose(&mut self, world: &World, component: ComponentId) {
+        let Some(pose) = world.get_component_by_id_as::<XrPoseComponent>(component) else {
+            return;
+        };
+        if pose.hand().is_none() {
+            crate::log_warn!("xr_pose {component}: unknown path '{}'", pose.path);
+        }
+        if !self.poses.contains(&component) {
+            self.poses.push(component);
+        }
+    }
+
+    /// Move every followed Transform to its device's pose and queue UPDATE_TRANSFORM for it.
+    pub fn tick(&mut self, world: &mut World, input: &InputState, queue: &mut CommandQueue) {
+        let poses = self.poses.clone();
+        for pose_cid in poses {
+            let Some(component) = world.get_component_by_id_as::<XrPoseComponent>(pose_cid) else {
+                self.poses.retain(|c| *c != pose_cid);
+                continue;
+            };
+            let Some(pose) = component
+                .hand()
+                .and_then(|hand| input.xr_controller(hand).pose)
+            else {
+                continue;
+            };
+            let Some(parent) = world.parent_of(pose_cid) else {
+                continue;
+            };
+            let Some(t) = world.get_component_by_id_as_mut::<TransformComponent>(parent) else {
+                continue;
+            };
+            if t.transform.translation == pose.position && t.transform.rotation == pose.orientation
+            {
+                continue;
+            }
+            t.transform.translation = pose.position;
+            t.transform.rotation = pose.orientation;
+            t.transform.recompute_model();
+            queue.queue_update_transform(parent, t.transform);
+        }
+    }
+}
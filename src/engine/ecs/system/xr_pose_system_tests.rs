@@ -0,0 +1,53 @@
+#[cfg(test)]
+mod tests {
+    use glam::{Quat, Vec3};
+
+    use crate::engine::ecs::component::{TransformComponent, XrPoseComponent};
+    use crate::engine::ecs::system::XrPoseSystem;
+    use crate::engine::ecs::{CommandQueue, World};
+    use crate::engine::user_input::{InputState, XrController, XrHand};
+    use crate::engine::xr::XrPose;
+
+    #[test]
+    fn tracked_hands_move_their_parent_transform() {
+        let mut world = World::default();
+        let mut queue = CommandQueue::new();
+        let hand = world.add_component(TransformComponent::new());
+        let pose = world.add_component(XrPoseComponent::new("right_hand"));
+        world.add_child(hand, pose).unwrap();
+        let mut system = XrPoseSystem::new();
+        system.register_xr_pose(&world, pose);
+
+        // Untracked: the Transform stays put.
+        let mut input = InputState::default();
+        system.tick(&mut world, &input, &mut queue);
+        let t = world
+            .get_component_by_id_as::<TransformComponent>(hand)
+            .unwrap()
+            .transform;
+        assert_eq!((t.translation, t.rotation), (Vec3::ZERO, Quat::IDENTITY));
+
+        let tracked = XrPose {
+            position: Vec3::new(0.2, 1.1, -0.3),
+            orientation: Quat::from_rotation_y(0.5),
+        };
+        input.set_xr_controller(
+            XrHand::Right,
+            XrController {
+                connected: true,
+                pose: Some(tracked),
+                ..Default::default()
+            },
+        );
+        system.tick(&mut world, &input, &mut queue);
+        let t = world
+            .get_component_by_id_as::<TransformComponent>(hand)
+            .unwrap()
+            .transform;
+        assert_eq!(
+            (t.translation, t.rotation),
+            (tracked.position, tracked.orientation)
+        );
+        assert_eq!(XrPoseComponent::new("head").hand(), None);
+    }
+}
@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use crate::engine::ecs::World;
+    use crate::engine::ecs::{EcsError, World};
 
     #[test]
     fn add_child_sets_parent_and_child_list() {
@@ -39,7 +39,13 @@ mod tests {
         w.add_child(a, b).unwrap();
 
         // Can't make ancestor a child of its descendant.
-        assert!(w.add_child(b, a).is_err());
+        assert_eq!(
+            w.add_child(b, a),
+            Err(EcsError::Cycle {
+                parent: b,
+                child: a
+            })
+        );
     }
 
     #[test]
@@ -52,7 +58,7 @@ mod tests {
         w.add_child(p, c).unwrap();
 
         // Parent isn't a leaf.
-        assert!(w.remove_component_leaf(p).is_err());
+        assert_eq!(w.remove_component_leaf(p), Err(EcsError::HasChildren(p)));
 
         // Child is a leaf.
         w.remove_component_leaf(c).unwrap();
@@ -79,4 +85,207 @@ mod tests {
         assert!(w.get_component_record(child).is_none());
         assert!(w.get_component_record(grandchild).is_none());
     }
+
+    #[test]
+    fn world_context_spawn_child_and_set_position() {
+        use crate::engine::ecs::component::{ColorComponent, TransformComponent};
+        use crate::engine::ecs::{CommandQueue, SystemWorld, WorldContext};
+        use crate::engine::graphics::{RenderAssets, VisualWorld};
+
+        let mut w = World::default();
+        let mut queue = CommandQueue::new();
+        let mut systems = SystemWorld::new();
+        let mut visuals = VisualWorld::new();
+        let mut assets = RenderAssets::new();
+        let mut ctx =
+            WorldContext::new(&mut w, &mut queue, &mut systems, &mut visuals, &mut assets);
+
+        let root = ctx.spawn(TransformComponent::new());
+        let color = ctx
+            .spawn_child(root, ColorComponent::rgba(1.0, 0.0, 0.0, 1.0))
+            .unwrap();
+        assert_eq!(ctx.world.parent_of(color), Some(root));
+
+        ctx.set_position(root, [1.0, 2.0, 3.0]).unwrap();
+        let t = ctx
+            .world
+            .get_component_by_id_as::<TransformComponent>(root)
+            .unwrap();
+        assert_eq!(t.transform.translation.to_array(), [1.0, 2.0, 3.0]);
+
+        assert!(matches!(
+            ctx.set_position(color, [0.0; 3]),
+            Err(EcsError::WrongComponentType { .. })
+        ));
+
+        ctx.despawn(root).unwrap();
+        assert!(ctx.world.get_component_record(color).is_none());
+    }
+
+    #[test]
+    fn world_context_world_space_setters() {
+        use crate::engine::ecs::component::TransformComponent;
+        use crate::engine::ecs::system::TransformSystem;
+        use crate::engine::ecs::{CommandQueue, SystemWorld, WorldContext};
+        use crate::engine::graphics::{RenderAssets, VisualWorld};
+        use glam::{Quat, Vec3};
+
+        let mut w = World::default();
+        let mut queue = CommandQueue::new();
+        let mut systems = SystemWorld::new();
+        let mut visuals = VisualWorld::new();
+        let mut assets = RenderAssets::new();
+        let mut ctx =
+            WorldContext::new(&mut w, &mut queue, &mut systems, &mut visuals, &mut assets);
+
+        let parent = ctx.spawn(
+            TransformComponent::new()
+                .with_position(10.0, 0.0, 0.0)
+                .with_scale(2.0, 2.0, 2.0)
+                .with_rotation_euler(0.0, 0.0, std::f32::consts::FRAC_PI_2),
+        );
+        let child = ctx.spawn_child(parent, TransformComponent::new()).unwrap();
+
+        ctx.set_world_position(child, [1.0, 2.0, 3.0]).unwrap();
+        let world_model = TransformSystem::world_model(ctx.world, child).unwrap();
+        let own = ctx
+            .world
+            .get_component_by_id_as::<TransformComponent>(child)
+            .unwrap()
+            .transform
+            .matrix();
+        let pos = (glam::Mat4::from_cols_array_2d(&world_model) * own)
+            .w_axis
+            .truncate();
+        assert!(pos.abs_diff_eq(Vec3::new(1.0, 2.0, 3.0), 1e-4));
+
+        ctx.set_world_rotation(child, Quat::IDENTITY).unwrap();
+        let local = ctx
+            .world
+            .get_component_by_id_as::<TransformComponent>(child)
+            .unwrap()
+            .transform
+            .rotation;
+        assert!(local.abs_diff_eq(Quat::from_rotation_z(-std::f32::consts::FRAC_PI_2), 1e-5));
+    }
+
+    #[test]
+    fn transform_look_at_and_local_axes() {
+        use crate::engine::ecs::CommandQueue;
+        use crate::engine::ecs::component::TransformComponent;
+        use glam::Vec3;
+
+        let mut queue = CommandQueue::new();
+        let mut t = TransformComponent::new().with_position(0.0, 0.0, 5.0);
+        t.look_at(&mut queue, Vec3::new(10.0, 0.0, 5.0), Vec3::Y);
+        assert!(t.forward().abs_diff_eq(Vec3::X, 1e-5));
+        assert!(t.up().abs_diff_eq(Vec3::Y, 1e-5));
+        assert!(t.right().abs_diff_eq(Vec3::Z, 1e-5));
+
+        t.translate_local(&mut queue, Vec3::new(0.0, 0.0, -2.0));
+        assert!(
+            t.transform
+                .translation
+                .abs_diff_eq(Vec3::new(2.0, 0.0, 5.0), 1e-5)
+        );
+
+        t.rotate_around(&mut queue, Vec3::Y, std::f32::consts::FRAC_PI_2);
+        assert!(t.forward().abs_diff_eq(Vec3::NEG_Z, 1e-5));
+    }
+
+    #[test]
+    fn reparenting_records_moved_subtrees() {
+        let mut w = World::default();
+        let a = w.add_component(crate::engine::ecs::component::TransformComponent::new());
+        let b = w.add_component(crate::engine::ecs::component::TransformComponent::new());
+        let c = w.add_component(crate::engine::ecs::component::TransformComponent::new());
+
+        w.add_child(a, c).unwrap();
+        assert_eq!(w.take_moved_subtrees(), vec![c]);
+        assert!(w.take_moved_subtrees().is_empty());
+
+        w.set_parent(c, Some(b)).unwrap();
+        w.add_child(a, b).unwrap();
+        assert_eq!(w.take_moved_subtrees(), vec![c, b]);
+
+        // Detaching a root changes nothing.
+        w.detach_from_parent(a);
+        assert!(w.take_moved_subtrees().is_empty());
+    }
+
+    #[test]
+    fn dot_export_lists_nodes_and_edges() {
+        use crate::engine::ecs::component::{ColorComponent, TransformComponent};
+
+        let mut w = World::default();
+        let t = w.add_component(TransformComponent::new());
+        let c = w.add_component(ColorComponent::rgba(1.0, 1.0, 1.0, 1.0));
+        w.add_child(t, c).unwrap();
+
+        let dot = w.to_dot();
+        assert!(dot.starts_with("digraph world {"));
+        assert!(dot.contains(&format!("\"{t}\" -> \"{c}\";")));
+        assert_eq!(dot.matches("[label=").count(), 2);
+    }
+
+    #[test]
+    fn state_hash_follows_data_and_child_order_but_not_root_order() {
+        use crate::engine::ecs::component::{ColorComponent, TransformComponent};
+
+        let build = |roots_swapped: bool, x: f32| {
+            let mut w = World::default();
+            let add_root = |w: &mut World, x: f32| {
+                let t = w.add_component(TransformComponent::new().with_position(x, 0.0, 0.0));
+                let a = w.add_component(ColorComponent::rgba(1.0, 0.0, 0.0, 1.0));
+                let b = w.add_component(TransformComponent::new());
+                w.add_child(t, a).unwrap();
+                w.add_child(t, b).unwrap();
+                t
+            };
+            if roots_swapped {
+                add_root(&mut w, 5.0);
+                add_root(&mut w, x);
+            } else {
+                add_root(&mut w, x);
+                add_root(&mut w, 5.0);
+            }
+            w
+        };
+
+        let base = build(false, 1.0).state_hash();
+        assert_eq!(build(false, 1.0).state_hash(), base);
+        assert_eq!(build(true, 1.0).state_hash(), base);
+        assert_ne!(build(false, 1.5).state_hash(), base);
+
+        let mut w = build(false, 1.0);
+        let root = w.root_components()[0];
+        let first = w.children_of(root)[0];
+        w.detach_from_parent(first);
+        w.add_child(root, first).unwrap();
+        assert_ne!(w.state_hash(), base);
+    }
+
+    #[test]
+    fn state_hash_counts_references_by_target_not_guid() {
+        use crate::engine::ecs::component::{AttachmentComponent, TransformComponent};
+
+        // A follower attached to the first or second of two targets, in another tree.
+        let build = |target_index: usize| {
+            let mut w = World::default();
+            let targets = [
+                w.add_component(TransformComponent::new().with_position(1.0, 0.0, 0.0)),
+                w.add_component(TransformComponent::new().with_position(2.0, 0.0, 0.0)),
+            ];
+            let follower = w.add_component(TransformComponent::new());
+            let attachment = w.add_component(AttachmentComponent::new(
+                w.component_ref(targets[target_index]),
+            ));
+            w.add_child(follower, attachment).unwrap();
+            w
+        };
+
+        let base = build(0).state_hash();
+        assert_eq!(build(0).state_hash(), base, "guids differ, hashes must not");
+        assert_ne!(build(1).state_hash(), base);
+    }
 }
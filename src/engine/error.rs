@@ -0,0 +1,86 @@
+use std::path::PathBuf;
+
+use crate::engine::ecs::EcsError;
+use crate::engine::graphics::RendererError;
+use crate::engine::networking::NetworkError;
+use crate::engine::save_game::SaveError;
+
+/// Engine-level error: every module error converts into it, so `?` works across layers while
+/// callers can still match on the cause.
+#[derive(Debug, thiserror::Error)]
+pub enum EngineError {
+    #[error("not implemented")]
+    NotImplemented,
+
+    #[error(transparent)]
+    Renderer(#[from] RendererError),
+
+    #[error(transparent)]
+    Asset(#[from] AssetError),
+
+    #[error(transparent)]
+    Ecs(#[from] EcsError),
+
+    #[error(transparent)]
+    Network(#[from] NetworkError),
+
+    #[error(transparent)]
+    Save(#[from] SaveError),
+
+    #[error("openxr: {0}")]
+    Xr(#[from] openxr::sys::Result),
+
+    #[error("event loop: {0}")]
+    EventLoop(#[from] winit::error::EventLoopError),
+}
+
+pub type EngineResult<T> = Result<T, EngineError>;
+
+/// Failures loading or saving files under the asset root (bindings, textures, scenes).
+#[derive(Debug, thiserror::Error)]
+pub enum AssetError {
+    #[error("{}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("'{uri}' not found (tried {tried:?})")]
+    NotFound { uri: String, tried: Vec<PathBuf> },
+
+    #[error("{uri}: {source}")]
+    Image {
+        uri: String,
+        #[source]
+        source: image::ImageError,
+    },
+
+    #[error("{uri}: invalid LUT: {message}")]
+    Lut { uri: String, message: String },
+
+    #[error("{}:{line}: {message}", path.display())]
+    InputRecording {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+
+    #[error("{}: {message}", path.display())]
+    StringTable { path: PathBuf, message: String },
+
+    #[error("invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Codec(#[from] crate::engine::ecs::codec::CodecError),
+}
+
+impl AssetError {
+    pub fn io(path: impl Into<PathBuf>, source: std::io::Error) -> Self {
+        AssetError::Io {
+            path: path.into(),
+            source,
+        }
+    }
+}
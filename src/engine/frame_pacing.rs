@@ -0,0 +1,83 @@
+//! Frame pacing: when the windowed loop should draw the next frame.
+//!
+//! The event loop asks `FramePacing::next_frame` after handling events and either requests a
+//! redraw right away or sleeps (`ControlFlow::WaitUntil`) until the frame is due. Frames are
+//! capped at `fps_cap` while focused (uncapped when `None`) and at `unfocused_fps` while
+//! another window has focus, so a game in the background doesn't spin a core.
+
+use std::time::{Duration, Instant};
+
+/// Frame rate while the window is not focused.
+pub const DEFAULT_UNFOCUSED_FPS: u32 = 15;
+
+/// What the event loop should do before the next frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PacingDecision {
+    /// Draw now.
+    Redraw,
+    /// Sleep until then, then draw.
+    WaitUntil(Instant),
+}
+
+#[derive(Debug, Clone)]
+pub struct FramePacing {
+    /// Frame rate cap while focused; `None` draws as fast as the renderer allows.
+    pub fps_cap: Option<u32>,
+    pub unfocused_fps: u32,
+    focused: bool,
+    /// When the last frame started (set by `frame_started`).
+    last_frame: Option<Instant>,
+}
+
+impl FramePacing {
+    pub fn new(fps_cap: Option<u32>) -> Self {
+        Self {
+            fps_cap: fps_cap.filter(|&fps| fps > 0),
+            unfocused_fps: DEFAULT_UNFOCUSED_FPS,
+            focused: true,
+            last_frame: None,
+        }
+    }
+
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    pub fn focused(&self) -> bool {
+        self.focused
+    }
+
+    /// Time between frame starts under the current policy; `None` if uncapped.
+    pub fn frame_interval(&self) -> Option<Duration> {
+        let fps = if self.focused {
+            self.fps_cap?
+        } else {
+            self.unfocused_fps.max(1)
+        };
+        Some(Duration::from_secs_f64(1.0 / f64::from(fps)))
+    }
+
+    /// Record that a frame started at `now`.
+    pub fn frame_started(&mut self, now: Instant) {
+        self.last_frame = Some(now);
+    }
+
+    pub fn next_frame(&self, now: Instant) -> PacingDecision {
+        let (Some(interval), Some(last)) = (self.frame_interval(), self.last_frame) else {
+            return PacingDecision::Redraw;
+        };
+        // Late frames start right away; there's no catching up on missed ones.
+        let due = last + interval;
+        if due <= now {
+            PacingDecision::Redraw
+        } else {
+            PacingDecision::WaitUntil(due)
+        }
+    }
+}
+
+impl Default for FramePacing {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
@@ -0,0 +1,36 @@
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crate::engine::frame_pacing::{FramePacing, PacingDecision};
+
+    #[test]
+    fn caps_focused_and_throttles_unfocused_frames() {
+        let start = Instant::now();
+        let mut pacing = FramePacing::new(Some(50));
+        assert_eq!(pacing.next_frame(start), PacingDecision::Redraw);
+
+        pacing.frame_started(start);
+        assert_eq!(
+            pacing.next_frame(start + Duration::from_millis(5)),
+            PacingDecision::WaitUntil(start + Duration::from_millis(20))
+        );
+        assert_eq!(
+            pacing.next_frame(start + Duration::from_millis(25)),
+            PacingDecision::Redraw
+        );
+
+        pacing.set_focused(false);
+        pacing.unfocused_fps = 10;
+        assert_eq!(
+            pacing.next_frame(start + Duration::from_millis(25)),
+            PacingDecision::WaitUntil(start + Duration::from_millis(100))
+        );
+
+        // Uncapped while focused.
+        let mut pacing = FramePacing::new(None);
+        pacing.frame_started(start);
+        assert_eq!(pacing.next_frame(start), PacingDecision::Redraw);
+        assert_eq!(FramePacing::new(Some(0)).fps_cap, None);
+    }
+}
@@ -0,0 +1,110 @@
+//! Color grading LUTs for the tonemap pass.
+//!
+//! A LUT lives on the GPU as an ordinary RGBA8 texture: a strip of N slices of N x N placed
+//! side by side (N*N wide, N tall), red along each slice, green downwards and blue from slice
+//! to slice. Strip images load as they are; `.cube` files are converted to the same layout by
+//! `AssetServer`, so everything downstream sees one format.
+
+use crate::engine::assets::DecodedImage;
+use crate::engine::graphics::TextureHandle;
+
+/// Largest LUT edge accepted: a 64-point strip is 4096 px wide, the widest texture every
+/// Vulkan device supports. 65-point `.cube` files need resampling first.
+pub const MAX_LUT_SIZE: u32 = 64;
+
+/// LUT the tonemap pass applies to the final image (mirrored from `ColorGradingComponent`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorGrading {
+    /// Strip texture (see the module docs).
+    pub lut: TextureHandle,
+    /// Edge length N of the LUT cube.
+    pub size: u32,
+    /// 0 = ungraded, 1 = fully graded.
+    pub strength: f32,
+}
+
+/// Edge length of a `width` x `height` LUT strip, if it is one.
+pub fn lut_strip_size(width: u32, height: u32) -> Option<u32> {
+    ((2..=MAX_LUT_SIZE).contains(&height) && width == height * height).then_some(height)
+}
+
+/// Convert the text of a `.cube` file (Adobe / Resolve 3D LUT) into a LUT strip.
+pub fn parse_cube_lut(text: &str) -> Result<DecodedImage, String> {
+    let mut size: Option<u32> = None;
+    let mut values: Vec<[f32; 3]> = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let err = |message: String| format!("line {}: {message}", number + 1);
+        let mut words = line.split_whitespace();
+        let keyword = words.next().unwrap_or_default();
+        match keyword {
+            "TITLE" => {}
+            "LUT_1D_SIZE" => return Err(err("1D LUTs are not supported".to_string())),
+            "LUT_3D_SIZE" => {
+                let n = words
+                    .next()
+                    .and_then(|w| w.parse::<u32>().ok())
+                    .filter(|n| (2..=MAX_LUT_SIZE).contains(n))
+                    .ok_or_else(|| err(format!("LUT_3D_SIZE must be 2..={MAX_LUT_SIZE}")))?;
+                size = Some(n);
+            }
+            "DOMAIN_MIN" | "DOMAIN_MAX" | "LUT_3D_INPUT_RANGE" => {
+                let expected = if keyword == "DOMAIN_MIN" { 0.0 } else { 1.0 };
+                let bounds: Vec<f32> = words.filter_map(|w| w.parse().ok()).collect();
+                let ok = match keyword {
+                    "LUT_3D_INPUT_RANGE" => bounds == [0.0, 1.0],
+                    _ => bounds == [expected; 3],
+                };
+                if !ok {
+                    return Err(err(format!(
+                        "only a 0..1 input domain is supported: '{line}'"
+                    )));
+                }
+            }
+            _ => {
+                let rgb: Vec<f32> = line
+                    .split_whitespace()
+                    .map(str::parse)
+                    .collect::<Result<_, _>>()
+                    .map_err(|_| err(format!("unexpected '{line}'")))?;
+                let [r, g, b] = rgb[..] else {
+                    return Err(err(format!("expected 3 values, got {}", rgb.len())));
+                };
+                if size.is_none() {
+                    return Err(err("table before LUT_3D_SIZE".to_string()));
+                }
+                values.push([r, g, b]);
+            }
+        }
+    }
+
+    let n = size.ok_or("missing LUT_3D_SIZE")?;
+    let expected = (n * n * n) as usize;
+    if values.len() != expected {
+        return Err(format!(
+            "expected {expected} table entries for size {n}, got {}",
+            values.len()
+        ));
+    }
+
+    // Table order: red fastest, then green, then blue.
+    let width = n * n;
+    let mut rgba = vec![0u8; (width * n * 4) as usize];
+    for (i, rgb) in values.iter().enumerate() {
+        let i = i as u32;
+        let (r, g, b) = (i % n, (i / n) % n, i / (n * n));
+        let offset = ((g * width + b * n + r) * 4) as usize;
+        for (c, v) in rgb.iter().enumerate() {
+            rgba[offset + c] = (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+        }
+        rgba[offset + 3] = 255;
+    }
+    Ok(DecodedImage {
+        rgba,
+        width,
+        height: n,
+    })
+}
@@ -0,0 +1,35 @@
+#[cfg(test)]
+mod tests {
+    use crate::engine::graphics::color_grading::{lut_strip_size, parse_cube_lut};
+
+    #[test]
+    fn cube_file_becomes_a_strip() {
+        // Identity 2-point LUT: red fastest, then green, then blue.
+        let mut text = String::from("# identity\nTITLE \"id\"\nLUT_3D_SIZE 2\nDOMAIN_MIN 0 0 0\n");
+        for b in 0..2 {
+            for g in 0..2 {
+                for r in 0..2 {
+                    text += &format!("{r}.0 {g}.0 {b}.0\n");
+                }
+            }
+        }
+        let strip = parse_cube_lut(&text).unwrap();
+        assert_eq!((strip.width, strip.height), (4, 2));
+        assert_eq!(lut_strip_size(strip.width, strip.height), Some(2));
+
+        let pixel = |x: u32, y: u32| {
+            let i = ((y * strip.width + x) * 4) as usize;
+            &strip.rgba[i..i + 4]
+        };
+        // Slice 1 (blue = 1), red = 1, green = 0.
+        assert_eq!(pixel(3, 0), [255, 0, 255, 255]);
+        // Slice 0, red = 0, green = 1.
+        assert_eq!(pixel(0, 1), [0, 255, 0, 255]);
+
+        assert!(parse_cube_lut("LUT_3D_SIZE 2\n0 0 0\n").is_err());
+        assert!(parse_cube_lut("0 0 0\n").is_err());
+        assert!(parse_cube_lut("LUT_3D_SIZE 2\nDOMAIN_MAX 2 2 2\n").is_err());
+        assert_eq!(lut_strip_size(256, 16), Some(16));
+        assert_eq!(lut_strip_size(16, 16), None);
+    }
+}
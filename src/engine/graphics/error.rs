@@ -0,0 +1,54 @@
+use crate::engine::graphics::primitives::CpuMeshHandle;
+
+/// Failures reported by renderers, uploaders and `RenderAssets`.
+#[derive(Debug, thiserror::Error)]
+pub enum RendererError {
+    #[error("renderer not initialized (call init_for_window first)")]
+    NotInitialized,
+
+    #[error("invalid CpuMeshHandle {0:?}")]
+    InvalidMeshHandle(CpuMeshHandle),
+
+    #[error("mesh has no {0}")]
+    EmptyMesh(&'static str),
+
+    #[error("texture has zero size ({width}x{height})")]
+    EmptyTexture { width: u32, height: u32 },
+
+    #[error("texture rgba length mismatch: got={got}, expected={expected}")]
+    TextureSizeMismatch { got: usize, expected: usize },
+
+    /// A handle used after its resource was released; its slot may hold another one now.
+    #[error(
+        "stale {kind} handle: slot {index} is at generation {current}, handle has {generation}"
+    )]
+    StaleHandle {
+        kind: &'static str,
+        index: u32,
+        generation: u8,
+        current: u8,
+    },
+
+    #[error("unknown {kind} handle (slot {index} was never allocated)")]
+    UnknownHandle { kind: &'static str, index: u32 },
+
+    #[error("out of {0} handles")]
+    HandlesExhausted(&'static str),
+
+    #[error("{0} not supported by this renderer")]
+    Unsupported(&'static str),
+
+    /// Anything raised by the graphics API itself (device loss, allocation, swapchain, ...).
+    #[error("graphics backend: {0}")]
+    Backend(String),
+}
+
+/// Backend code propagates with `?` into `Box<dyn Error>`; recover our own variants from it.
+impl From<Box<dyn std::error::Error>> for RendererError {
+    fn from(e: Box<dyn std::error::Error>) -> Self {
+        match e.downcast::<RendererError>() {
+            Ok(e) => *e,
+            Err(e) => RendererError::Backend(e.to_string()),
+        }
+    }
+}
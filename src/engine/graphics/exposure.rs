@@ -0,0 +1,95 @@
+//! Exposure of the HDR scene before it is tonemapped for display.
+//!
+//! The scene is lit in linear HDR; the tonemap pass multiplies it by `Exposure::value` and
+//! compresses it into the display range. The value is either set by hand or adapted each
+//! frame from the scene's average luminance, which the renderer measures on the GPU and feeds
+//! back through `update` a frame or two late.
+
+/// Exposure scale a fresh `VisualWorld` renders with.
+pub const DEFAULT_EXPOSURE: f32 = 1.0;
+
+/// Middle grey: auto exposure scales the average luminance to this.
+pub const DEFAULT_EXPOSURE_KEY: f32 = 0.18;
+
+/// Fraction of the way to the target exposure covered per frame.
+const ADAPTATION_RATE: f32 = 0.05;
+/// Luminance floor, so a black frame doesn't ask for infinite exposure.
+const MIN_LUMINANCE: f32 = 1.0e-4;
+
+/// Targets of the automatic mode.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AutoExposure {
+    /// Luminance the scene average is exposed to.
+    pub key: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+impl Default for AutoExposure {
+    fn default() -> Self {
+        Self {
+            key: DEFAULT_EXPOSURE_KEY,
+            min: 0.25,
+            max: 4.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exposure {
+    value: f32,
+    auto: Option<AutoExposure>,
+}
+
+impl Exposure {
+    /// Always multiply the scene by `value` (negative and non-finite values become 0 / 1).
+    pub fn manual(value: f32) -> Self {
+        Self {
+            value: if value.is_finite() {
+                value.max(0.0)
+            } else {
+                DEFAULT_EXPOSURE
+            },
+            auto: None,
+        }
+    }
+
+    /// Adapt to the scene's average luminance within `settings.min..=settings.max`.
+    pub fn automatic(settings: AutoExposure) -> Self {
+        Self {
+            value: DEFAULT_EXPOSURE.clamp(settings.min, settings.max),
+            auto: Some(settings),
+        }
+    }
+
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    /// Switch to a manual exposure (turns the automatic mode off).
+    pub fn set(&mut self, value: f32) {
+        *self = Self::manual(value);
+    }
+
+    pub fn auto(&self) -> Option<AutoExposure> {
+        self.auto
+    }
+
+    /// Feed the average linear luminance of a rendered frame. Does nothing when manual.
+    pub fn update(&mut self, average_luminance: f32) {
+        let Some(auto) = self.auto else {
+            return;
+        };
+        if !average_luminance.is_finite() {
+            return;
+        }
+        let target = (auto.key / average_luminance.max(MIN_LUMINANCE)).clamp(auto.min, auto.max);
+        self.value += (target - self.value) * ADAPTATION_RATE;
+    }
+}
+
+impl Default for Exposure {
+    fn default() -> Self {
+        Self::manual(DEFAULT_EXPOSURE)
+    }
+}
@@ -0,0 +1,28 @@
+#[cfg(test)]
+mod tests {
+    use crate::engine::graphics::exposure::{AutoExposure, Exposure};
+
+    #[test]
+    fn auto_exposure_adapts_to_scene_luminance() {
+        let mut exposure = Exposure::automatic(AutoExposure::default());
+        for _ in 0..500 {
+            exposure.update(0.09);
+        }
+        assert!((exposure.value() - 2.0).abs() < 1.0e-3);
+
+        // Clamped on very dark and very bright frames.
+        for _ in 0..500 {
+            exposure.update(0.0);
+        }
+        assert!((exposure.value() - 4.0).abs() < 1.0e-3);
+        for _ in 0..500 {
+            exposure.update(100.0);
+        }
+        assert!((exposure.value() - 0.25).abs() < 1.0e-3);
+
+        exposure.set(1.5);
+        exposure.update(0.09);
+        assert_eq!(exposure.value(), 1.5);
+        assert!(exposure.auto().is_none());
+    }
+}
@@ -0,0 +1,136 @@
+//! Device memory accounting for renderer backends.
+//!
+//! Backends report every allocation that outlives an upload (mesh buffers, texture images)
+//! and, once per frame, the size of the buffers rebuilt for that frame (instances, uniforms).
+//! Staging buffers are transient and not counted. Sizes are what was requested from the
+//! allocator, so driver padding and alignment make the real footprint somewhat larger.
+
+use crate::utils::profiling;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GpuMemoryCategory {
+    /// Shared vertex/index buffers; the count is the number of meshes stored in them.
+    Meshes,
+    Textures,
+    /// Instance, camera and light buffers of every frame in flight, plus the material
+    /// buffers rebuilt each frame.
+    FrameBuffers,
+}
+
+impl GpuMemoryCategory {
+    pub const ALL: [Self; 3] = [Self::Meshes, Self::Textures, Self::FrameBuffers];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Meshes => "meshes",
+            Self::Textures => "textures",
+            Self::FrameBuffers => "frame_buffers",
+        }
+    }
+
+    fn gauge(self) -> &'static str {
+        match self {
+            Self::Meshes => "vram.meshes_bytes",
+            Self::Textures => "vram.textures_bytes",
+            Self::FrameBuffers => "vram.frame_buffers_bytes",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GpuAllocations {
+    pub bytes: u64,
+    pub count: usize,
+}
+
+/// Live device allocations by category.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GpuMemoryUsage {
+    pub meshes: GpuAllocations,
+    pub textures: GpuAllocations,
+    pub frame_buffers: GpuAllocations,
+}
+
+impl GpuMemoryUsage {
+    pub fn get(&self, category: GpuMemoryCategory) -> GpuAllocations {
+        match category {
+            GpuMemoryCategory::Meshes => self.meshes,
+            GpuMemoryCategory::Textures => self.textures,
+            GpuMemoryCategory::FrameBuffers => self.frame_buffers,
+        }
+    }
+
+    fn get_mut(&mut self, category: GpuMemoryCategory) -> &mut GpuAllocations {
+        match category {
+            GpuMemoryCategory::Meshes => &mut self.meshes,
+            GpuMemoryCategory::Textures => &mut self.textures,
+            GpuMemoryCategory::FrameBuffers => &mut self.frame_buffers,
+        }
+    }
+
+    pub fn allocate(&mut self, category: GpuMemoryCategory, bytes: u64) {
+        let a = self.get_mut(category);
+        a.bytes += bytes;
+        a.count += 1;
+    }
+
+    pub fn free(&mut self, category: GpuMemoryCategory, bytes: u64) {
+        let a = self.get_mut(category);
+        a.bytes = a.bytes.saturating_sub(bytes);
+        a.count = a.count.saturating_sub(1);
+    }
+
+    /// Replace a category wholesale (per-frame buffers are rebuilt, not freed one by one).
+    pub fn set(&mut self, category: GpuMemoryCategory, allocations: GpuAllocations) {
+        *self.get_mut(category) = allocations;
+    }
+
+    pub fn total_bytes(&self) -> u64 {
+        GpuMemoryCategory::ALL
+            .iter()
+            .map(|&c| self.get(c).bytes)
+            .sum()
+    }
+
+    /// Publish as `vram.*` profiling gauges (see `utils::profiling`).
+    pub fn publish(&self) {
+        for category in GpuMemoryCategory::ALL {
+            profiling::set_gauge(category.gauge(), self.get(category).bytes as f64);
+        }
+        profiling::set_gauge("vram.total_bytes", self.total_bytes() as f64);
+    }
+
+    /// One line per category plus the total, for the REPL `vram` command.
+    pub fn report(&self) -> Vec<String> {
+        let mut out: Vec<String> = GpuMemoryCategory::ALL
+            .iter()
+            .map(|&c| {
+                let a = self.get(c);
+                format!(
+                    "{:<14}{:>12} ({} allocations)",
+                    c.name(),
+                    format_bytes(a.bytes),
+                    a.count
+                )
+            })
+            .collect();
+        out.push(format!(
+            "{:<14}{:>12}",
+            "total",
+            format_bytes(self.total_bytes())
+        ));
+        out
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const KIB: f64 = 1024.0;
+    let b = bytes as f64;
+    if b >= KIB * KIB {
+        format!("{:.1} MiB", b / (KIB * KIB))
+    } else if b >= KIB {
+        format!("{:.1} KiB", b / KIB)
+    } else {
+        format!("{bytes} B")
+    }
+}
@@ -0,0 +1,102 @@
+//! Slot allocation for renderer resources addressed by `GenerationalHandle`s.
+
+use std::marker::PhantomData;
+
+use crate::engine::graphics::RendererError;
+use crate::engine::graphics::primitives::{GenerationalHandle, MAX_HANDLE_INDEX};
+
+#[derive(Debug, Clone, Copy)]
+struct Slot {
+    generation: u8,
+    live: bool,
+}
+
+/// Hands out handles, reusing released slots under a new generation.
+///
+/// A slot released at the last generation is retired rather than reused, so a stale handle
+/// can never validate again.
+#[derive(Debug)]
+pub struct HandleTable<H> {
+    slots: Vec<Slot>,
+    free: Vec<u32>,
+    _handle: PhantomData<H>,
+}
+
+impl<H> Default for HandleTable<H> {
+    fn default() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+            _handle: PhantomData,
+        }
+    }
+}
+
+impl<H: GenerationalHandle> HandleTable<H> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A handle to a free slot, preferring released ones.
+    pub fn alloc(&mut self) -> Result<H, RendererError> {
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                let index = self.slots.len() as u32;
+                if index > MAX_HANDLE_INDEX {
+                    return Err(RendererError::HandlesExhausted(H::KIND));
+                }
+                self.slots.push(Slot {
+                    generation: 0,
+                    live: false,
+                });
+                index
+            }
+        };
+        let slot = &mut self.slots[index as usize];
+        slot.live = true;
+        Ok(H::from_parts(index, slot.generation))
+    }
+
+    /// Ok if `handle` is live: allocated and not released since.
+    pub fn validate(&self, handle: H) -> Result<(), RendererError> {
+        let index = handle.index();
+        let Some(slot) = self.slots.get(index as usize) else {
+            return Err(RendererError::UnknownHandle {
+                kind: H::KIND,
+                index,
+            });
+        };
+        if !slot.live || slot.generation != handle.generation() {
+            return Err(RendererError::StaleHandle {
+                kind: H::KIND,
+                index,
+                generation: handle.generation(),
+                current: slot.generation,
+            });
+        }
+        Ok(())
+    }
+
+    /// Free `handle`'s slot; later lookups with it fail as stale.
+    pub fn release(&mut self, handle: H) -> Result<(), RendererError> {
+        self.validate(handle)?;
+        let index = handle.index();
+        let slot = &mut self.slots[index as usize];
+        slot.live = false;
+        if let Some(next) = slot.generation.checked_add(1) {
+            slot.generation = next;
+            self.free.push(index);
+        }
+        Ok(())
+    }
+
+    /// Handles currently live.
+    pub fn len(&self) -> usize {
+        self.slots.iter().filter(|s| s.live).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -0,0 +1,52 @@
+#[cfg(test)]
+mod tests {
+    use crate::engine::graphics::{GenerationalHandle, HandleTable, RendererError, TextureHandle};
+
+    #[test]
+    fn released_slots_are_reused_and_old_handles_go_stale() {
+        let mut table = HandleTable::<TextureHandle>::new();
+        let a = table.alloc().unwrap();
+        let b = table.alloc().unwrap();
+        assert_eq!((a, b), (TextureHandle(0), TextureHandle(1)));
+
+        table.release(a).unwrap();
+        let c = table.alloc().unwrap();
+        assert_eq!((c.index(), c.generation()), (0, 1));
+        assert_ne!(a, c);
+        assert!(matches!(
+            table.validate(a),
+            Err(RendererError::StaleHandle {
+                kind: "texture",
+                index: 0,
+                generation: 0,
+                current: 1
+            })
+        ));
+        assert!(matches!(
+            table.release(a),
+            Err(RendererError::StaleHandle { .. })
+        ));
+        table.validate(c).unwrap();
+        table.validate(b).unwrap();
+        assert!(matches!(
+            table.validate(TextureHandle(9)),
+            Err(RendererError::UnknownHandle { index: 9, .. })
+        ));
+        assert_eq!(table.len(), 2);
+    }
+
+    #[test]
+    fn a_slot_is_retired_after_its_last_generation() {
+        let mut table = HandleTable::<TextureHandle>::new();
+        let mut handle = table.alloc().unwrap();
+        for _ in 0..u8::MAX {
+            table.release(handle).unwrap();
+            handle = table.alloc().unwrap();
+            assert_eq!(handle.index(), 0);
+        }
+        assert_eq!(handle.generation(), u8::MAX);
+        table.release(handle).unwrap();
+        assert!(table.validate(handle).is_err());
+        assert_eq!(table.alloc().unwrap().index(), 1);
+    }
+}
@@ -4,7 +4,9 @@
 //! The renderer later uploads them into GPU buffers (vertex/index buffers)
 //! and returns a `MeshHandle` that can be referenced by ECS renderables.
 
+#[cfg(feature = "windowed")]
 use vulkano::buffer::BufferContents;
+#[cfg(feature = "windowed")]
 use vulkano::pipeline::graphics::vertex_input::Vertex;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -22,12 +24,13 @@ pub enum IndexFormat {
 ///
 /// - `pos`: object-space / model-space position
 /// - `uv`: optional 0..1 UV (useful for screen-space gradients)
-#[derive(BufferContents, Vertex, Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "windowed", derive(BufferContents, Vertex))]
 #[repr(C)]
 pub struct CpuVertex {
-    #[format(R32G32B32_SFLOAT)]
+    #[cfg_attr(feature = "windowed", format(R32G32B32_SFLOAT))]
     pub pos: [f32; 3],
-    #[format(R32G32_SFLOAT)]
+    #[cfg_attr(feature = "windowed", format(R32G32_SFLOAT))]
     pub uv: [f32; 2],
 }
 
@@ -130,6 +133,46 @@ impl MeshFactory {
         CpuMesh::new(vertices, vec![0, 1, 2, 0, 2, 3])
     }
 
+    /// 9-patch quad centered at origin, `size` units wide/tall (negative or NaN sizes count
+    /// as 0).
+    ///
+    /// - `border`: corner size in mesh units (clamped to half the size on each axis).
+    /// - `uv_border`: corner size in UV space (0..0.5) on each axis.
+    ///
+    /// Corners keep their size when `size` changes; edges stretch along one axis and the
+    /// center stretches along both. Same orientation/winding as `quad_2d` (UV v follows +Y).
+    pub fn nine_slice(size: [f32; 2], border: f32, uv_border: [f32; 2]) -> CpuMesh {
+        let (hx, hy) = (0.5 * size[0].max(0.0), 0.5 * size[1].max(0.0));
+        let bx = border.clamp(0.0, hx);
+        let by = border.clamp(0.0, hy);
+
+        let xs = [-hx, -hx + bx, hx - bx, hx];
+        let ys = [-hy, -hy + by, hy - by, hy];
+        let us = [0.0, uv_border[0], 1.0 - uv_border[0], 1.0];
+        let vs = [0.0, uv_border[1], 1.0 - uv_border[1], 1.0];
+
+        let mut vertices = Vec::with_capacity(16);
+        for (y, v) in ys.into_iter().zip(vs) {
+            for (x, u) in xs.into_iter().zip(us) {
+                vertices.push(CpuVertex {
+                    pos: [x, y, 0.0],
+                    uv: [u, v],
+                });
+            }
+        }
+
+        let mut indices = Vec::with_capacity(54);
+        for j in 0..3u32 {
+            for i in 0..3u32 {
+                let a = j * 4 + i;
+                let (b, c, d) = (a + 1, a + 5, a + 4);
+                indices.extend([a, b, c, a, c, d]);
+            }
+        }
+
+        CpuMesh::new(vertices, indices)
+    }
+
     /// Unit-ish cube centered at origin.
     ///
     /// This is an *indexed position-only* cube (8 vertices, 12 triangles).
@@ -1,26 +1,83 @@
+pub mod color_grading;
+#[cfg(test)]
+mod color_grading_tests;
+pub mod error;
+pub mod exposure;
+#[cfg(test)]
+mod exposure_tests;
+pub mod gpu_memory;
+pub mod handle_table;
+#[cfg(test)]
+mod handle_table_tests;
 pub mod mesh;
-pub mod pipeline_descriptor_set_layouts;
+#[cfg(feature = "windowed")]
+pub(crate) mod pipeline_descriptor_set_layouts;
+pub mod post_process;
 pub mod primitives;
+pub mod range_allocator;
+#[cfg(test)]
+mod range_allocator_tests;
 pub mod render_assets;
+pub mod render_graph;
+#[cfg(test)]
+mod render_graph_tests;
 pub mod render_info;
+pub mod render_scale;
+#[cfg(test)]
+mod render_scale_tests;
+pub mod renderer_config;
+#[cfg(test)]
+mod renderer_config_tests;
+pub mod rendering_inspector;
+pub mod text;
+#[cfg(test)]
+mod text_tests;
 pub mod visual_world;
+#[cfg(test)]
+mod visual_world_tests;
+#[cfg(feature = "windowed")]
 pub mod vulkano_renderer;
 
+pub use color_grading::ColorGrading;
+pub use error::RendererError;
+pub use exposure::Exposure;
+pub use gpu_memory::{GpuMemoryCategory, GpuMemoryUsage};
+pub use handle_table::HandleTable;
 pub use mesh::{CpuMesh, CpuVertex, MeshFactory};
+pub use post_process::{BloomSettings, PostProcessSettings};
 pub use primitives::{
-    GpuRenderable, Material, MaterialHandle, MeshHandle, Renderable, TextureHandle, Transform,
+    BlendMode, CullMode, Culling, FrontFace, GenerationalHandle, GpuRenderable, Material,
+    MaterialHandle, MeshHandle, Outline, Reflection, Renderable, TextureHandle, TextureKind,
+    Transform,
 };
 
 pub use render_assets::RenderAssets;
-pub use visual_world::VisualWorld;
+pub use visual_world::{
+    ALL_VISIBILITY_LAYERS, CameraMatrices, ClipRect, DEFAULT_VISIBILITY_LAYERS, DEPTH_BIAS_RANGE,
+    DrawCacheStats, MAX_DEPTH_Z_ORDER, VisualBackground, VisualWorld, z_order_depth_bias,
+};
+#[cfg(feature = "windowed")]
 pub use vulkano_renderer::VulkanoRenderer;
 
 pub use render_info::RenderInfo;
+pub use render_scale::RenderScale;
+pub use renderer_config::{Letterbox, RendererConfig};
+pub use rendering_inspector::RenderingInspector;
+/// Frames a GPU renderer records ahead of the one being displayed; see
+/// `UniverseBuilder::frames_in_flight`.
+pub const DEFAULT_FRAMES_IN_FLIGHT: usize = 2;
+
 /// Trait for uploading CPU meshes to GPU.
 /// This abstraction allows different renderer implementations
 /// to provide mesh uploading functionality without exposing renderer-specific details.
 pub trait MeshUploader {
-    fn upload_mesh(&mut self, mesh: &CpuMesh) -> Result<MeshHandle, Box<dyn std::error::Error>>;
+    fn upload_mesh(&mut self, mesh: &CpuMesh) -> Result<MeshHandle, RendererError>;
+
+    /// Free a mesh uploaded earlier; instances must no longer reference it. Fails with
+    /// `RendererError::StaleHandle` if it was released already.
+    fn release_mesh(&mut self, _handle: MeshHandle) -> Result<(), RendererError> {
+        Ok(())
+    }
 }
 
 /// Trait for uploading decoded textures to the GPU.
@@ -32,7 +89,23 @@ pub trait TextureUploader {
         rgba: &[u8],
         width: u32,
         height: u32,
-    ) -> Result<TextureHandle, Box<dyn std::error::Error>>;
+    ) -> Result<TextureHandle, RendererError>;
+
+    /// Upload a cubemap (`TextureKind::Cube`) of six square `face_size` faces, stored one
+    /// after another in the order +X, -X, +Y, -Y, +Z, -Z. Renderers build its mip chain.
+    fn upload_cubemap_rgba8(
+        &mut self,
+        _faces: &[u8],
+        _face_size: u32,
+    ) -> Result<TextureHandle, RendererError> {
+        Err(RendererError::Unsupported("cubemaps"))
+    }
+
+    /// Free a texture uploaded earlier; instances must no longer reference it. Fails with
+    /// `RendererError::StaleHandle` if it was released already.
+    fn release_texture(&mut self, _handle: TextureHandle) -> Result<(), RendererError> {
+        Ok(())
+    }
 }
 
 /// Convenience super-trait for types that can upload both meshes and textures.
@@ -40,11 +113,40 @@ pub trait RenderUploader: MeshUploader + TextureUploader {}
 
 impl<T> RenderUploader for T where T: MeshUploader + TextureUploader {}
 
-/// Graphics/Vulkan placeholder.
-pub struct Graphics;
+/// Uploader without a GPU: hands out fresh handles and keeps nothing.
+///
+/// Lets `Universe::prepare_render_with` run with `RendererBackend::None`, for benchmarks and
+/// tools that need `VisualWorld` populated but never draw.
+#[derive(Debug, Default)]
+pub struct NullUploader {
+    meshes: u32,
+    textures: u32,
+}
 
-impl Graphics {
-    pub fn new() -> Self {
-        Self
+impl MeshUploader for NullUploader {
+    fn upload_mesh(&mut self, _mesh: &CpuMesh) -> Result<MeshHandle, RendererError> {
+        self.meshes += 1;
+        Ok(MeshHandle(self.meshes))
+    }
+}
+
+impl TextureUploader for NullUploader {
+    fn upload_texture_rgba8(
+        &mut self,
+        _rgba: &[u8],
+        _width: u32,
+        _height: u32,
+    ) -> Result<TextureHandle, RendererError> {
+        self.textures += 1;
+        Ok(TextureHandle(self.textures))
+    }
+
+    fn upload_cubemap_rgba8(
+        &mut self,
+        _faces: &[u8],
+        _face_size: u32,
+    ) -> Result<TextureHandle, RendererError> {
+        self.textures += 1;
+        Ok(TextureHandle(self.textures))
     }
 }
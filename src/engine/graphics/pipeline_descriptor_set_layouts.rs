@@ -15,7 +15,13 @@ pub struct PipelineDescriptorSetLayouts {
     pub material: Arc<DescriptorSetLayout>,
 
     /// Set 2: per-instance/object data (bones, per-instance lighting, etc).
+    /// Not bound by any pipeline yet.
+    #[allow(dead_code)]
     pub rig: Arc<DescriptorSetLayout>,
+
+    /// Sets of the fullscreen passes: one sampled image (the HDR scene and the color grading
+    /// LUT for tonemapping, the skybox cubemap for the background).
+    pub post: Arc<DescriptorSetLayout>,
 }
 
 impl PipelineDescriptorSetLayouts {
@@ -52,6 +58,7 @@ impl PipelineDescriptorSetLayouts {
         // Set 1 (material):
         // - binding 0: uniform buffer (MaterialUBO)
         // - binding 1: combined image sampler (base color texture)
+        // - binding 2: combined image sampler (reflection cubemap)
         let mut material_bindings = BTreeMap::new();
         let mut material_params =
             DescriptorSetLayoutBinding::descriptor_type(DescriptorType::UniformBuffer);
@@ -65,6 +72,12 @@ impl PipelineDescriptorSetLayouts {
         base_color_tex.stages = ShaderStages::FRAGMENT;
         material_bindings.insert(1, base_color_tex);
 
+        let mut reflection_tex =
+            DescriptorSetLayoutBinding::descriptor_type(DescriptorType::CombinedImageSampler);
+        reflection_tex.descriptor_count = 1;
+        reflection_tex.stages = ShaderStages::FRAGMENT;
+        material_bindings.insert(2, reflection_tex);
+
         let material = DescriptorSetLayout::new(
             device.clone(),
             DescriptorSetLayoutCreateInfo {
@@ -90,17 +103,35 @@ impl PipelineDescriptorSetLayouts {
         rig_bindings.insert(1, bones);
 
         let rig = DescriptorSetLayout::new(
-            device,
+            device.clone(),
             DescriptorSetLayoutCreateInfo {
                 bindings: rig_bindings,
                 ..Default::default()
             },
         )?;
 
+        // Post (tonemap) set:
+        // - binding 0: combined image sampler (HDR scene)
+        let mut post_bindings = BTreeMap::new();
+        let mut hdr_scene =
+            DescriptorSetLayoutBinding::descriptor_type(DescriptorType::CombinedImageSampler);
+        hdr_scene.descriptor_count = 1;
+        hdr_scene.stages = ShaderStages::FRAGMENT;
+        post_bindings.insert(0, hdr_scene);
+
+        let post = DescriptorSetLayout::new(
+            device,
+            DescriptorSetLayoutCreateInfo {
+                bindings: post_bindings,
+                ..Default::default()
+            },
+        )?;
+
         Ok(Self {
             global,
             material,
             rig,
+            post,
         })
     }
 }
@@ -0,0 +1,47 @@
+//! Settings of the passes between the lit HDR scene and the tonemapped image.
+//!
+//! Bloom: the scene pass writes what emissive materials draw into a second target; the
+//! renderer keeps the part brighter than `threshold`, blurs it at half resolution and adds it
+//! back, times `intensity`, before exposure and tonemapping.
+//!
+//! Temporal AA: the scene pass also writes per-pixel motion vectors (screen movement since the
+//! previous frame, from `VisualInstance::prev_model` and `VisualWorld::previous_camera`). With
+//! `taa` on, the projection is jittered by a sub-pixel offset every frame and a resolve pass
+//! blends the scene with the reprojected history of earlier frames.
+
+/// Glow around emissive materials.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BloomSettings {
+    /// Scale of the blurred glow added to the scene; 0 turns bloom (and its passes) off.
+    pub intensity: f32,
+    /// Emissive luminance below which nothing glows.
+    pub threshold: f32,
+}
+
+impl BloomSettings {
+    pub const OFF: Self = Self {
+        intensity: 0.0,
+        threshold: 0.8,
+    };
+
+    pub fn is_enabled(&self) -> bool {
+        self.intensity > 0.0
+    }
+}
+
+impl Default for BloomSettings {
+    fn default() -> Self {
+        Self {
+            intensity: 1.0,
+            threshold: 0.8,
+        }
+    }
+}
+
+/// Post-processing of the frame (`VisualWorld::post_process`).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PostProcessSettings {
+    pub bloom: BloomSettings,
+    /// Temporal anti-aliasing; off by default.
+    pub taa: bool,
+}
@@ -1,11 +1,18 @@
-/// Mesh helpers / basic primitives placeholder.
+//! Mesh helpers / basic primitives placeholder.
 
-/// Minimal transform (placeholder).
+use glam::{Mat4, Quat, Vec3};
+
+use crate::utils::color::Color;
+
+/// Translation/rotation/scale plus the cached model matrix.
+///
+/// TRS uses glam types; `model` stays a plain column-major array because it is what gets
+/// copied into GPU instance data.
 #[derive(Debug, Clone, Copy)]
 pub struct Transform {
-    pub translation: [f32; 3],
-    pub rotation: [f32; 4], // quat xyzw
-    pub scale: [f32; 3],
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
 
     /// Cached model matrix (column-major). Keep this in sync with TRS.
     pub model: [[f32; 4]; 4],
@@ -13,19 +20,11 @@ pub struct Transform {
 
 impl Default for Transform {
     fn default() -> Self {
-        let translation = [0.0; 3];
-        let rotation = [0.0, 0.0, 0.0, 1.0];
-        let scale = [1.0; 3];
         Self {
-            translation,
-            rotation,
-            scale,
-            model: [
-                [scale[0], 0.0, 0.0, 0.0],
-                [0.0, scale[1], 0.0, 0.0],
-                [0.0, 0.0, scale[2], 0.0],
-                [translation[0], translation[1], translation[2], 1.0],
-            ],
+            translation: Vec3::ZERO,
+            rotation: Quat::IDENTITY,
+            scale: Vec3::ONE,
+            model: Mat4::IDENTITY.to_cols_array_2d(),
         }
     }
 }
@@ -33,45 +32,20 @@ impl Default for Transform {
 impl Transform {
     /// Recompute `self.model` from translation/rotation/scale.
     pub fn recompute_model(&mut self) {
-        let [tx, ty, tz] = self.translation;
-        let [sx, sy, sz] = self.scale;
-        let [x, y, z, w] = self.rotation;
-
         // Normalize quat defensively.
-        let len2 = x * x + y * y + z * z + w * w;
-        let inv_len = if len2 > 0.0 { len2.sqrt().recip() } else { 1.0 };
-        let (x, y, z, w) = (x * inv_len, y * inv_len, z * inv_len, w * inv_len);
-
-        // Quaternion to rotation matrix (column-major).
-        let xx = x * x;
-        let yy = y * y;
-        let zz = z * z;
-        let xy = x * y;
-        let xz = x * z;
-        let yz = y * z;
-        let wx = w * x;
-        let wy = w * y;
-        let wz = w * z;
-
-        let r00 = 1.0 - 2.0 * (yy + zz);
-        let r01 = 2.0 * (xy + wz);
-        let r02 = 2.0 * (xz - wy);
-
-        let r10 = 2.0 * (xy - wz);
-        let r11 = 1.0 - 2.0 * (xx + zz);
-        let r12 = 2.0 * (yz + wx);
-
-        let r20 = 2.0 * (xz + wy);
-        let r21 = 2.0 * (yz - wx);
-        let r22 = 1.0 - 2.0 * (xx + yy);
-
-        // Apply scale by scaling the rotation columns.
-        let c0 = [r00 * sx, r01 * sx, r02 * sx, 0.0];
-        let c1 = [r10 * sy, r11 * sy, r12 * sy, 0.0];
-        let c2 = [r20 * sz, r21 * sz, r22 * sz, 0.0];
-        let c3 = [tx, ty, tz, 1.0];
+        let len = self.rotation.length();
+        let rotation = if len > 1e-8 {
+            self.rotation / len
+        } else {
+            Quat::IDENTITY
+        };
+        self.model = Mat4::from_scale_rotation_translation(self.scale, rotation, self.translation)
+            .to_cols_array_2d();
+    }
 
-        self.model = [c0, c1, c2, c3];
+    /// `model` as a glam matrix.
+    pub fn matrix(&self) -> Mat4 {
+        Mat4::from_cols_array_2d(&self.model)
     }
 }
 
@@ -152,19 +126,99 @@ pub struct GpuMesh {
 }
 
 /// Renderer-owned resource handles (lightweight ids into renderer/asset tables).
+///
+/// Mesh, material and texture handles are generational (`GenerationalHandle`).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct MeshHandle(pub u32);
 
 /// CPU-side mesh identity (owned by `RenderAssets`).
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct CpuMeshHandle(pub u32);
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct MaterialHandle(pub u32);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct TextureHandle(pub u32);
 
+/// Low bits of a `GenerationalHandle` naming its slot; the high 8 bits are the generation.
+pub const HANDLE_INDEX_BITS: u32 = 24;
+
+/// Largest slot index a `GenerationalHandle` can name.
+pub const MAX_HANDLE_INDEX: u32 = (1 << HANDLE_INDEX_BITS) - 1;
+
+/// Handle into a renderer table whose slots are reused (see `HandleTable`).
+///
+/// The generation changes whenever the slot is released, so a handle kept past its release
+/// fails validation with `RendererError::StaleHandle` instead of naming whatever was
+/// uploaded into the slot next. Handles built from a bare number are generation 0.
+pub trait GenerationalHandle: Copy {
+    /// Resource name used in errors.
+    const KIND: &'static str;
+
+    fn from_raw(raw: u32) -> Self;
+
+    fn raw(self) -> u32;
+
+    fn from_parts(index: u32, generation: u8) -> Self {
+        Self::from_raw((generation as u32) << HANDLE_INDEX_BITS | (index & MAX_HANDLE_INDEX))
+    }
+
+    fn index(self) -> u32 {
+        self.raw() & MAX_HANDLE_INDEX
+    }
+
+    fn generation(self) -> u8 {
+        (self.raw() >> HANDLE_INDEX_BITS) as u8
+    }
+}
+
+impl GenerationalHandle for MeshHandle {
+    const KIND: &'static str = "mesh";
+
+    fn from_raw(raw: u32) -> Self {
+        Self(raw)
+    }
+
+    fn raw(self) -> u32 {
+        self.0
+    }
+}
+
+impl GenerationalHandle for MaterialHandle {
+    const KIND: &'static str = "material";
+
+    fn from_raw(raw: u32) -> Self {
+        Self(raw)
+    }
+
+    fn raw(self) -> u32 {
+        self.0
+    }
+}
+
+impl GenerationalHandle for TextureHandle {
+    const KIND: &'static str = "texture";
+
+    fn from_raw(raw: u32) -> Self {
+        Self(raw)
+    }
+
+    fn raw(self) -> u32 {
+        self.0
+    }
+}
+
+/// Shape of an uploaded texture; decides which sampler slots it can be bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TextureKind {
+    /// `TextureUploader::upload_texture_rgba8`: instance base color.
+    #[default]
+    D2,
+    /// `TextureUploader::upload_cubemap_rgba8`: skyboxes and reflections.
+    Cube,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct InstanceHandle(pub u32);
 
@@ -174,6 +228,12 @@ pub struct InstanceHandle(pub u32);
 pub struct Material {
     pub vertex_shader: &'static str,
     pub fragment_shader: &'static str,
+    /// Outline drawn around meshes using this material unless `VisualWorld` overrides it.
+    pub outline: Option<Outline>,
+    /// How fragments combine with what is already drawn, unless `VisualWorld` overrides it.
+    pub blend: BlendMode,
+    /// Which triangle sides are drawn, unless `VisualWorld` overrides it.
+    pub culling: Culling,
     // Later:
     // pub pipeline_config: PipelineConfig,
     // pub uniforms: MaterialUniforms,
@@ -185,19 +245,151 @@ impl Material {
     pub const UNLIT_MESH: Material = Material {
         vertex_shader: "engine/graphics/shaders/unlit-mesh.vert",
         fragment_shader: "engine/graphics/shaders/unlit-mesh.frag",
+        outline: None,
+        blend: BlendMode::AlphaBlend,
+        culling: Culling::DOUBLE_SIDED,
     };
 
     /// Toon material used by the Vulkano renderer bring-up pipeline.
     pub const TOON_MESH: Material = Material {
         vertex_shader: "engine/graphics/shaders/toon-mesh.vert",
         fragment_shader: "engine/graphics/shaders/toon-mesh.frag",
+        // Off by default: tilemaps and nine-slice UI draw with this material too. Turn it on
+        // per scene with `VisualWorld::set_material_outline`.
+        outline: None,
+        blend: BlendMode::AlphaBlend,
+        // Sprites are often mirrored with a negative scale, which flips their winding.
+        culling: Culling::DOUBLE_SIDED,
+    };
+}
+
+/// Color blending of a material; every material shader gets one pipeline per mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BlendMode {
+    /// Overwrites the target; alpha is ignored.
+    Opaque,
+    /// Straight alpha: `src * a + dst * (1 - a)`.
+    #[default]
+    AlphaBlend,
+    /// `dst + src * a`, for glows and particles.
+    Additive,
+    /// `dst * src`, for shadows and tints.
+    Multiply,
+}
+
+impl BlendMode {
+    pub const ALL: [BlendMode; 4] = [
+        BlendMode::Opaque,
+        BlendMode::AlphaBlend,
+        BlendMode::Additive,
+        BlendMode::Multiply,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BlendMode::Opaque => "opaque",
+            BlendMode::AlphaBlend => "alpha",
+            BlendMode::Additive => "additive",
+            BlendMode::Multiply => "multiply",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|m| m.name() == name)
+    }
+
+    /// Draw order rank: opaque batches first, then blended ones, additive last.
+    pub fn draw_rank(self) -> u8 {
+        match self {
+            BlendMode::Opaque => 0,
+            BlendMode::AlphaBlend | BlendMode::Multiply => 1,
+            BlendMode::Additive => 2,
+        }
+    }
+}
+
+/// Triangle sides a material skips.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CullMode {
+    /// Double-sided.
+    #[default]
+    None,
+    Back,
+    Front,
+}
+
+/// Winding of front faces as authored, in world space with +Y up (`MeshFactory` meshes are
+/// counter-clockwise). Renderers translate this to their own clip-space convention.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum FrontFace {
+    #[default]
+    CounterClockwise,
+    Clockwise,
+}
+
+/// Face culling of a material.
+///
+/// A negative scale on an odd number of axes mirrors a mesh and swaps its front and back
+/// faces; keep such meshes on a double-sided material.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Culling {
+    pub mode: CullMode,
+    pub front_face: FrontFace,
+}
+
+impl Culling {
+    pub const DOUBLE_SIDED: Culling = Culling {
+        mode: CullMode::None,
+        front_face: FrontFace::CounterClockwise,
+    };
+
+    pub const BACK: Culling = Culling {
+        mode: CullMode::Back,
+        front_face: FrontFace::CounterClockwise,
+    };
+}
+
+/// Cel-shading outline: an inverted hull drawn behind the mesh, pushed outward in screen space.
+///
+/// The hull grows away from the mesh origin (2D meshes have no vertex normals), so it follows
+/// shapes that are convex around their origin, like the `MeshFactory` primitives.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Outline {
+    pub color: Color,
+    /// Width in physical pixels of the render target.
+    pub width: f32,
+}
+
+impl Outline {
+    pub const DEFAULT: Outline = Outline {
+        color: Color::BLACK,
+        width: 2.0,
     };
 }
 
+/// Environment reflection mixed over a material: the cubemap is sampled along the view ray
+/// mirrored about the surface normal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reflection {
+    /// Cube texture (`TextureKind::Cube`).
+    pub cubemap: TextureHandle,
+    /// 0 keeps the material's own color, 1 is a perfect mirror.
+    pub strength: f32,
+}
+
 impl MaterialHandle {
     /// Unlit mesh material (see `Material::UNLIT_MESH`).
     pub const UNLIT_MESH: MaterialHandle = MaterialHandle(0);
 
     /// Toon mesh material (see `Material::TOON_MESH`).
     pub const TOON_MESH: MaterialHandle = MaterialHandle(1);
+
+    /// Built-in material definition of this handle.
+    pub fn material(self) -> Option<&'static Material> {
+        match self {
+            Self::UNLIT_MESH => Some(&Material::UNLIT_MESH),
+            Self::TOON_MESH => Some(&Material::TOON_MESH),
+            _ => None,
+        }
+    }
 }
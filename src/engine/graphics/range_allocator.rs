@@ -0,0 +1,81 @@
+//! First-fit suballocation of a fixed-capacity buffer, for the renderer's mesh arena.
+
+use std::ops::Range;
+
+/// Hands out ranges of a buffer of `capacity` elements and takes freed ones back.
+///
+/// Freed ranges merge with their neighbours; one that ends at the high-water mark lowers it
+/// instead, so the end of the buffer stays a single free block.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RangeAllocator {
+    capacity: u64,
+    /// End of the highest range handed out.
+    used: u64,
+    /// Freed ranges below `used`, sorted, none touching another.
+    free: Vec<Range<u64>>,
+}
+
+impl RangeAllocator {
+    pub fn new(capacity: u64) -> Self {
+        Self {
+            capacity,
+            ..Self::default()
+        }
+    }
+
+    pub fn capacity(&self) -> u64 {
+        self.capacity
+    }
+
+    /// Everything in use lies below this (what growing the buffer must copy).
+    pub fn used(&self) -> u64 {
+        self.used
+    }
+
+    /// Elements currently handed out.
+    pub fn allocated(&self) -> u64 {
+        self.used - self.free.iter().map(|r| r.end - r.start).sum::<u64>()
+    }
+
+    /// Start of a free range of `len` elements, or `None` if none is left.
+    pub fn alloc(&mut self, len: u64) -> Option<u64> {
+        if let Some(i) = self.free.iter().position(|r| r.end - r.start >= len) {
+            let start = self.free[i].start;
+            self.free[i].start += len;
+            if self.free[i].is_empty() {
+                self.free.remove(i);
+            }
+            return Some(start);
+        }
+        if self.capacity - self.used < len {
+            return None;
+        }
+        let start = self.used;
+        self.used += len;
+        Some(start)
+    }
+
+    /// Give back a range from `alloc`.
+    pub fn free(&mut self, range: Range<u64>) {
+        if range.is_empty() {
+            return;
+        }
+        let mut i = self.free.partition_point(|r| r.start < range.start);
+        self.free.insert(i, range);
+        if i + 1 < self.free.len() && self.free[i].end == self.free[i + 1].start {
+            self.free[i].end = self.free.remove(i + 1).end;
+        }
+        if i > 0 && self.free[i - 1].end == self.free[i].start {
+            self.free[i - 1].end = self.free.remove(i).end;
+            i -= 1;
+        }
+        if self.free[i].end == self.used {
+            self.used = self.free.remove(i).start;
+        }
+    }
+
+    /// The buffer was replaced by one of `capacity` elements holding the same ranges.
+    pub fn grow(&mut self, capacity: u64) {
+        self.capacity = self.capacity.max(capacity);
+    }
+}
@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod tests {
+    use crate::engine::graphics::range_allocator::RangeAllocator;
+
+    #[test]
+    fn freed_ranges_are_reused_first_fit_and_merge() {
+        let mut ranges = RangeAllocator::new(100);
+        let a = ranges.alloc(10).unwrap();
+        let b = ranges.alloc(20).unwrap();
+        let c = ranges.alloc(30).unwrap();
+        assert_eq!((a, b, c), (0, 10, 30));
+        assert_eq!(ranges.used(), 60);
+
+        ranges.free(a..a + 10);
+        ranges.free(b..b + 20);
+        assert_eq!(ranges.allocated(), 30);
+        // The two freed neighbours merged into 0..30, which fits a 25.
+        assert_eq!(ranges.alloc(25), Some(0));
+        assert_eq!(ranges.alloc(5), Some(25));
+        assert_eq!(ranges.used(), 60);
+        assert_eq!(ranges.alloc(41), None);
+        assert_eq!(ranges.alloc(40), Some(60));
+    }
+
+    #[test]
+    fn freeing_the_top_range_lowers_the_high_water_mark() {
+        let mut ranges = RangeAllocator::new(100);
+        ranges.alloc(10).unwrap();
+        let b = ranges.alloc(10).unwrap();
+        let c = ranges.alloc(10).unwrap();
+        ranges.free(b..b + 10);
+        assert_eq!(ranges.used(), 30);
+        ranges.free(c..c + 10);
+        assert_eq!(ranges.used(), 10);
+        assert_eq!(ranges.allocated(), 10);
+        assert_eq!(ranges.alloc(90), Some(10));
+    }
+
+    #[test]
+    fn growing_keeps_ranges_and_adds_room_at_the_end() {
+        let mut ranges = RangeAllocator::new(16);
+        assert_eq!(ranges.alloc(16), Some(0));
+        assert_eq!(ranges.alloc(1), None);
+        ranges.grow(32);
+        assert_eq!(ranges.capacity(), 32);
+        assert_eq!(ranges.alloc(16), Some(16));
+    }
+}
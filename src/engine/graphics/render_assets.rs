@@ -1,8 +1,8 @@
 use std::collections::HashMap;
 
-use crate::engine::graphics::MeshUploader;
 use crate::engine::graphics::mesh::CpuMesh;
 use crate::engine::graphics::primitives::{CpuMeshHandle, MeshHandle};
+use crate::engine::graphics::{MeshUploader, RendererError};
 
 /// Renderer-side asset registry used by ECS systems.
 ///
@@ -10,9 +10,12 @@ use crate::engine::graphics::primitives::{CpuMeshHandle, MeshHandle};
 /// - ECS and gameplay code refer to geometry by `CpuMeshHandle` (CPU asset identity).
 /// - The renderer owns GPU resources and returns `MeshHandle`.
 /// - `RenderAssets` bridges the two and caches uploads.
+/// - Generated meshes (tilemap chunks, 9-slice panels, static batches) are released when they
+///   are replaced. Released handles are not reused: `CpuMeshHandle` has no generation, so a
+///   stale one must find nothing rather than another mesh.
 #[derive(Debug, Default)]
 pub struct RenderAssets {
-    cpu_meshes: Vec<CpuMesh>,
+    cpu_meshes: Vec<Option<CpuMesh>>,
     gpu_meshes: HashMap<CpuMeshHandle, MeshHandle>,
 }
 
@@ -26,12 +29,36 @@ impl RenderAssets {
     /// If callers want reuse, they should keep and share this handle.
     pub fn register_mesh(&mut self, mesh: CpuMesh) -> CpuMeshHandle {
         let h = CpuMeshHandle(self.cpu_meshes.len() as u32);
-        self.cpu_meshes.push(mesh);
+        self.cpu_meshes.push(Some(mesh));
         h
     }
 
+    /// `None` for unknown and released handles.
     pub fn cpu_mesh(&self, h: CpuMeshHandle) -> Option<&CpuMesh> {
-        self.cpu_meshes.get(h.0 as usize)
+        self.cpu_meshes.get(h.0 as usize)?.as_ref()
+    }
+
+    /// Drop a mesh's CPU data and free its upload, if any. No instance may still draw it.
+    ///
+    /// Fails with `RendererError::InvalidMeshHandle` if it is unknown or released already.
+    pub fn release_mesh(
+        &mut self,
+        uploader: &mut dyn MeshUploader,
+        cpu_mesh: CpuMeshHandle,
+    ) -> Result<(), RendererError> {
+        self.cpu_meshes
+            .get_mut(cpu_mesh.0 as usize)
+            .and_then(Option::take)
+            .ok_or(RendererError::InvalidMeshHandle(cpu_mesh))?;
+        match self.gpu_meshes.remove(&cpu_mesh) {
+            Some(gpu) => uploader.release_mesh(gpu),
+            None => Ok(()),
+        }
+    }
+
+    /// Meshes registered and not released.
+    pub fn mesh_count(&self) -> usize {
+        self.cpu_meshes.iter().filter(|m| m.is_some()).count()
     }
 
     /// Get (or upload) a mesh into the renderer and return a renderer-owned `MeshHandle`.
@@ -39,14 +66,14 @@ impl RenderAssets {
         &mut self,
         uploader: &mut dyn MeshUploader,
         cpu_mesh: CpuMeshHandle,
-    ) -> Result<MeshHandle, Box<dyn std::error::Error>> {
+    ) -> Result<MeshHandle, RendererError> {
         if let Some(h) = self.gpu_meshes.get(&cpu_mesh).copied() {
             return Ok(h);
         }
 
         let mesh = self
             .cpu_mesh(cpu_mesh)
-            .ok_or("RenderAssets: invalid CpuMeshHandle")?;
+            .ok_or(RendererError::InvalidMeshHandle(cpu_mesh))?;
         let h = uploader.upload_mesh(mesh)?;
         self.gpu_meshes.insert(cpu_mesh, h);
         Ok(h)
@@ -0,0 +1,160 @@
+//! Declarative ordering of the renderer's passes.
+//!
+//! Every frame the renderer declares its passes with the resources (images, buffers) they read
+//! and write, by name. `compile` orders them so each pass runs after the writers of what it
+//! reads, keeps declaration order where nothing constrains it, and drops passes that don't
+//! contribute to an output. Adding a pass means declaring it and recording it; nothing else
+//! has to be moved around.
+//!
+//! The graph only decides the order: vulkano's command buffer builder already inserts the
+//! barriers and image layout transitions each command needs from the resources it touches.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+/// Name of a resource passes hand to each other (`"scene"`, `"bloom"`, ...).
+pub type ResourceName = &'static str;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum RenderGraphError {
+    #[error("resource '{resource}' is written by both {first} and {second}")]
+    MultipleWriters {
+        resource: ResourceName,
+        first: String,
+        second: String,
+    },
+
+    #[error("output '{0}' is not written by any pass")]
+    UnwrittenOutput(ResourceName),
+
+    #[error("passes depend on each other in a cycle: {0:?}")]
+    Cycle(Vec<String>),
+}
+
+#[derive(Debug, Clone)]
+struct PassNode<P> {
+    pass: P,
+    reads: Vec<ResourceName>,
+    writes: Vec<ResourceName>,
+}
+
+/// Passes of one frame; `P` identifies a pass to the code that records it.
+#[derive(Debug, Clone)]
+pub struct RenderGraph<P> {
+    passes: Vec<PassNode<P>>,
+    outputs: Vec<ResourceName>,
+}
+
+impl<P> Default for RenderGraph<P> {
+    fn default() -> Self {
+        Self {
+            passes: Vec::new(),
+            outputs: Vec::new(),
+        }
+    }
+}
+
+impl<P: Copy + Debug> RenderGraph<P> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declare a pass. Reads nothing in the graph writes (textures, last frame's history) are
+    /// inputs from outside it. Each resource has a single writer.
+    pub fn add_pass(
+        &mut self,
+        pass: P,
+        reads: &[ResourceName],
+        writes: &[ResourceName],
+    ) -> &mut Self {
+        self.passes.push(PassNode {
+            pass,
+            reads: reads.to_vec(),
+            writes: writes.to_vec(),
+        });
+        self
+    }
+
+    /// Mark a resource as used outside the graph (presented, read back by the CPU).
+    pub fn add_output(&mut self, resource: ResourceName) -> &mut Self {
+        self.outputs.push(resource);
+        self
+    }
+
+    /// Passes in execution order.
+    pub fn compile(&self) -> Result<Vec<P>, RenderGraphError> {
+        let mut writers: HashMap<ResourceName, usize> = HashMap::new();
+        for (i, node) in self.passes.iter().enumerate() {
+            for &resource in &node.writes {
+                if let Some(&first) = writers.get(resource) {
+                    return Err(RenderGraphError::MultipleWriters {
+                        resource,
+                        first: format!("{:?}", self.passes[first].pass),
+                        second: format!("{:?}", node.pass),
+                    });
+                }
+                writers.insert(resource, i);
+            }
+        }
+        let dependencies: Vec<Vec<usize>> = self
+            .passes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| {
+                let mut deps: Vec<usize> = node
+                    .reads
+                    .iter()
+                    .filter_map(|r| writers.get(r).copied())
+                    .filter(|&w| w != i)
+                    .collect();
+                deps.sort_unstable();
+                deps.dedup();
+                deps
+            })
+            .collect();
+
+        // Live passes: the writers of the outputs and everything they depend on.
+        let mut live = vec![false; self.passes.len()];
+        let mut stack = Vec::with_capacity(self.outputs.len());
+        for &output in &self.outputs {
+            stack.push(
+                *writers
+                    .get(output)
+                    .ok_or(RenderGraphError::UnwrittenOutput(output))?,
+            );
+        }
+        while let Some(i) = stack.pop() {
+            if !std::mem::replace(&mut live[i], true) {
+                stack.extend_from_slice(&dependencies[i]);
+            }
+        }
+
+        // Kahn's algorithm, always taking the earliest declared pass that is ready.
+        let mut remaining: Vec<usize> = dependencies
+            .iter()
+            .map(|deps| deps.iter().filter(|&&d| live[d]).count())
+            .collect();
+        let mut done = vec![false; self.passes.len()];
+        let mut order = Vec::new();
+        while let Some(next) =
+            (0..self.passes.len()).find(|&i| live[i] && !done[i] && remaining[i] == 0)
+        {
+            done[next] = true;
+            order.push(self.passes[next].pass);
+            for (i, deps) in dependencies.iter().enumerate() {
+                if deps.contains(&next) {
+                    remaining[i] -= 1;
+                }
+            }
+        }
+
+        let stuck: Vec<String> = (0..self.passes.len())
+            .filter(|&i| live[i] && !done[i])
+            .map(|i| format!("{:?}", self.passes[i].pass))
+            .collect();
+        if !stuck.is_empty() {
+            return Err(RenderGraphError::Cycle(stuck));
+        }
+        Ok(order)
+    }
+}
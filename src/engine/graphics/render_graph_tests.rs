@@ -0,0 +1,45 @@
+#[cfg(test)]
+mod tests {
+    use crate::engine::graphics::render_graph::{RenderGraph, RenderGraphError};
+
+    #[test]
+    fn passes_run_after_what_they_read_and_unused_ones_are_culled() {
+        let mut graph = RenderGraph::new();
+        graph
+            .add_pass("tonemap", &["scene", "bloom", "lut"], &["swapchain"])
+            .add_pass("bloom", &["emissive"], &["bloom"])
+            .add_pass("debug_view", &["scene"], &["debug"])
+            .add_pass("scene", &[], &["scene", "emissive"])
+            .add_output("swapchain");
+        // `bloom` is declared first but waits for `scene`, which writes the emissive target.
+        assert_eq!(graph.compile(), Ok(vec!["scene", "bloom", "tonemap"]));
+
+        graph.add_output("nothing");
+        assert_eq!(
+            graph.compile(),
+            Err(RenderGraphError::UnwrittenOutput("nothing"))
+        );
+    }
+
+    #[test]
+    fn cycles_and_shared_writes_are_rejected() {
+        let mut graph = RenderGraph::new();
+        graph
+            .add_pass("a", &["y"], &["x"])
+            .add_pass("b", &["x"], &["y"])
+            .add_output("y");
+        assert_eq!(
+            graph.compile(),
+            Err(RenderGraphError::Cycle(vec![
+                "\"a\"".to_string(),
+                "\"b\"".to_string()
+            ]))
+        );
+
+        graph.add_pass("c", &[], &["x"]);
+        assert!(matches!(
+            graph.compile(),
+            Err(RenderGraphError::MultipleWriters { resource: "x", .. })
+        ));
+    }
+}
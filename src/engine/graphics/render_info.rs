@@ -1,6 +1,6 @@
 // Render-related helper functions and types that don't neatly fit anywhere else.
 //
-// Currently just owns the LC_PRINT_PIPELINE_LAYOUTS env-var gating and printing.
+// Currently just owns the LC_PRINT_PIPELINE_LAYOUTS env-var gating and logging.
 
 // Intentionally empty for now; this file remains as a home for renderer debug helpers.
 
@@ -25,8 +25,8 @@ impl RenderInfo {
             return;
         }
 
-        println!(
-            "[Renderer] pipeline/layout debug: material={:?} mesh={:?} pipeline=0x{:x} layout={}",
+        crate::log_info!(
+            "pipeline/layout debug: material={:?} mesh={:?} pipeline=0x{:x} layout={}",
             material,
             mesh,
             pipeline_u64,
@@ -34,13 +34,16 @@ impl RenderInfo {
                 .map(|l| format!("0x{:x}", l))
                 .unwrap_or_else(|| "<missing>".to_string()),
         );
-        println!(
-            "[Renderer] expected push-constant range: stage=VERTEX offset=0 size={} bytes",
+        crate::log_info!(
+            "expected push-constant range: stage=VERTEX offset=0 size={} bytes",
             push_constant_size_bytes
         );
-        println!(
-            "[Renderer] batch idx {}/{} start={} count={}",
-            batch_index, batch_len, start, count
+        crate::log_info!(
+            "batch idx {}/{} start={} count={}",
+            batch_index,
+            batch_len,
+            start,
+            count
         );
     }
 }
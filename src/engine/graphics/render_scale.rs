@@ -0,0 +1,111 @@
+//! Dynamic resolution: the scene is drawn at a fraction of the window size and upscaled when
+//! presented.
+//!
+//! `RenderScale` lives on `VisualWorld` so renderers read it with the rest of the frame. It is
+//! either fixed (`set`) or automatic: `Universe::render` feeds it each frame's GPU time, and
+//! the scale drops while frames miss the target rate and creeps back up once there is headroom.
+
+use std::time::Duration;
+
+/// Smallest scale accepted; below this the upscaled image is mostly blur.
+pub const MIN_RENDER_SCALE: f32 = 0.25;
+
+/// Weight of the newest frame in the smoothed frame time.
+const FRAME_TIME_SMOOTHING: f32 = 0.1;
+/// Scale factor applied per frame while over budget.
+const SHRINK_FACTOR: f32 = 0.95;
+/// Scale added per frame while comfortably under budget.
+const GROW_STEP: f32 = 0.02;
+
+/// Targets of the automatic mode.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AutoRenderScale {
+    pub target_fps: u32,
+    pub min: f32,
+    pub max: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderScale {
+    scale: f32,
+    auto: Option<AutoRenderScale>,
+    /// Smoothed frame time in seconds; `None` until the first frame in automatic mode.
+    frame_time: Option<f32>,
+}
+
+impl RenderScale {
+    /// Always render at `scale` (clamped to `MIN_RENDER_SCALE..=1`).
+    pub fn fixed(scale: f32) -> Self {
+        Self {
+            scale: clamp_scale(scale),
+            auto: None,
+            frame_time: None,
+        }
+    }
+
+    /// Adjust the scale between `MIN_RENDER_SCALE` and 1 to hold `target_fps`.
+    pub fn automatic(target_fps: u32) -> Self {
+        Self {
+            scale: 1.0,
+            auto: Some(AutoRenderScale {
+                target_fps: target_fps.max(1),
+                min: MIN_RENDER_SCALE,
+                max: 1.0,
+            }),
+            frame_time: None,
+        }
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Switch to a fixed scale (turns the automatic mode off).
+    pub fn set(&mut self, scale: f32) {
+        *self = Self::fixed(scale);
+    }
+
+    pub fn auto(&self) -> Option<AutoRenderScale> {
+        self.auto
+    }
+
+    /// Feed the GPU time of the last frame. Does nothing in fixed mode.
+    pub fn update(&mut self, frame_time: Duration) {
+        let Some(auto) = self.auto else {
+            return;
+        };
+        let t = frame_time.as_secs_f32();
+        let smoothed = match self.frame_time {
+            Some(prev) => prev + (t - prev) * FRAME_TIME_SMOOTHING,
+            None => t,
+        };
+        self.frame_time = Some(smoothed);
+
+        let budget = 1.0 / auto.target_fps as f32;
+        if smoothed > budget * 1.05 {
+            self.scale *= SHRINK_FACTOR;
+        } else if smoothed < budget * 0.85 {
+            self.scale += GROW_STEP;
+        }
+        self.scale = self.scale.clamp(auto.min, auto.max);
+    }
+
+    /// Size to render at for a target of `extent` pixels (never below 1x1).
+    pub fn scaled_extent(&self, extent: [u32; 2]) -> [u32; 2] {
+        extent.map(|e| ((e as f32 * self.scale).round() as u32).clamp(1, e.max(1)))
+    }
+}
+
+impl Default for RenderScale {
+    fn default() -> Self {
+        Self::fixed(1.0)
+    }
+}
+
+fn clamp_scale(scale: f32) -> f32 {
+    if scale.is_finite() {
+        scale.clamp(MIN_RENDER_SCALE, 1.0)
+    } else {
+        1.0
+    }
+}
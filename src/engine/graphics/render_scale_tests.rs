@@ -0,0 +1,28 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::engine::graphics::render_scale::{MIN_RENDER_SCALE, RenderScale};
+
+    #[test]
+    fn automatic_scale_follows_frame_time() {
+        let mut scale = RenderScale::automatic(60);
+        for _ in 0..200 {
+            scale.update(Duration::from_millis(40));
+        }
+        assert_eq!(scale.scale(), MIN_RENDER_SCALE);
+        assert_eq!(scale.scaled_extent([1920, 1080]), [480, 270]);
+
+        for _ in 0..400 {
+            scale.update(Duration::from_millis(5));
+        }
+        assert_eq!(scale.scale(), 1.0);
+
+        scale.set(0.5);
+        assert!(scale.auto().is_none());
+        scale.update(Duration::from_millis(40));
+        assert_eq!(scale.scale(), 0.5);
+        assert_eq!(RenderScale::fixed(4.0).scale(), 1.0);
+        assert_eq!(RenderScale::fixed(0.5).scaled_extent([1, 0]), [1, 1]);
+    }
+}
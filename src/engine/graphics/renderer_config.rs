@@ -0,0 +1,60 @@
+//! Renderer settings that belong to the game rather than the scene: the clear color and
+//! letterboxing.
+//!
+//! With a virtual resolution set, the scene is laid out at that size (`VisualWorld::viewport`
+//! reports it to cameras, screen anchors and clip rects) and drawn into the largest centered
+//! rectangle of the window with the same aspect ratio. The bars around it show the clear
+//! color.
+
+use crate::utils::color::Color;
+
+/// Settings the renderer reads from `VisualWorld::renderer_config`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RendererConfig {
+    /// Under the scene when no `BackgroundComponent` draws one, and of the letterbox bars.
+    pub clear_color: Color,
+    /// Fixed design resolution in pixels; `None` fills the window.
+    pub virtual_resolution: Option<[u32; 2]>,
+}
+
+impl Default for RendererConfig {
+    fn default() -> Self {
+        Self {
+            clear_color: Color::BLACK,
+            virtual_resolution: None,
+        }
+    }
+}
+
+/// Window pixels a frame is drawn into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Letterbox {
+    pub offset: [u32; 2],
+    pub extent: [u32; 2],
+}
+
+impl RendererConfig {
+    /// Where the frame sits in a `window`-sized surface: all of it without a virtual
+    /// resolution, the largest centered rect of the virtual aspect ratio otherwise.
+    pub fn letterbox(&self, window: [u32; 2]) -> Letterbox {
+        let full = Letterbox {
+            offset: [0, 0],
+            extent: window,
+        };
+        let Some([vw, vh]) = self.virtual_resolution else {
+            return full;
+        };
+        if vw == 0 || vh == 0 || window[0] == 0 || window[1] == 0 {
+            return full;
+        }
+        let scale = (window[0] as f32 / vw as f32).min(window[1] as f32 / vh as f32);
+        let extent = [
+            ((vw as f32 * scale).round() as u32).clamp(1, window[0]),
+            ((vh as f32 * scale).round() as u32).clamp(1, window[1]),
+        ];
+        Letterbox {
+            offset: [(window[0] - extent[0]) / 2, (window[1] - extent[1]) / 2],
+            extent,
+        }
+    }
+}
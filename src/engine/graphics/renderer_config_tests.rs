@@ -0,0 +1,37 @@
+#[cfg(test)]
+mod tests {
+    use crate::engine::graphics::{Letterbox, RendererConfig, VisualWorld};
+
+    #[test]
+    fn letterbox_centers_the_virtual_aspect_ratio() {
+        let config = RendererConfig {
+            virtual_resolution: Some([320, 180]),
+            ..RendererConfig::default()
+        };
+        // Pillarboxed in a 4:3 window, letterboxed in an ultrawide one.
+        assert_eq!(
+            config.letterbox([800, 600]),
+            Letterbox {
+                offset: [0, 75],
+                extent: [800, 450],
+            }
+        );
+        assert_eq!(
+            config.letterbox([2560, 1080]),
+            Letterbox {
+                offset: [320, 0],
+                extent: [1920, 1080],
+            }
+        );
+        assert_eq!(
+            RendererConfig::default().letterbox([800, 600]).extent,
+            [800, 600]
+        );
+
+        let mut visuals = VisualWorld::new();
+        visuals.set_viewport(800.0, 600.0);
+        visuals.set_renderer_config(config);
+        assert_eq!(visuals.viewport(), [320.0, 180.0]);
+        assert_eq!(visuals.window_to_viewport([400.0, 75.0]), [160.0, 0.0]);
+    }
+}
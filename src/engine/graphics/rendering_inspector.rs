@@ -0,0 +1,124 @@
+//! Debug inspector that dumps the component graph and `VisualWorld` render state.
+//!
+//! Toggle at runtime with F3, or start enabled with `LC_INSPECTOR=1`.
+//! While enabled, a report is logged (info level) when the inspector is switched on and whenever the
+//! render-relevant shape of the scene changes (instances, batches, lights).
+
+use crate::engine::ecs::component::RenderableComponent;
+use crate::engine::ecs::{ComponentId, World};
+use crate::engine::graphics::VisualWorld;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct SceneShape {
+    components: usize,
+    instances: usize,
+    batches: usize,
+    lights: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct RenderingInspector {
+    enabled: bool,
+    last_shape: Option<SceneShape>,
+}
+
+impl RenderingInspector {
+    pub fn new() -> Self {
+        Self {
+            enabled: std::env::var("LC_INSPECTOR").ok().as_deref() == Some("1"),
+            last_shape: None,
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        // Force a fresh report the next time we inspect.
+        self.last_shape = None;
+    }
+
+    pub fn toggle(&mut self) {
+        self.set_enabled(!self.enabled);
+        crate::log_info!(
+            "rendering inspector {}",
+            if self.enabled { "enabled" } else { "disabled" }
+        );
+    }
+
+    /// Log a report if enabled and the scene shape changed since the last report.
+    ///
+    /// Call after `VisualWorld::prepare_draw_cache` so its draw cache stats are current.
+    pub fn inspect(&mut self, world: &World, visuals: &VisualWorld) {
+        if !self.enabled {
+            return;
+        }
+
+        let stats = visuals.draw_cache_stats();
+        let shape = SceneShape {
+            components: world.component_count(),
+            instances: visuals.instances().len(),
+            batches: stats.batches,
+            lights: visuals.point_lights().len(),
+        };
+        if self.last_shape == Some(shape) {
+            return;
+        }
+        self.last_shape = Some(shape);
+
+        crate::log_info!("{}", Self::report(world, visuals));
+    }
+
+    /// Build a human-readable report of the component graph and render state.
+    pub fn report(world: &World, visuals: &VisualWorld) -> String {
+        let mut out = String::new();
+        let stats = visuals.draw_cache_stats();
+        out.push_str(&format!(
+            "[RenderingInspector] components={} instances={} drawn={} culled={} batches={} state_changes={} lights={}\n",
+            world.component_count(),
+            visuals.instances().len(),
+            stats.instances,
+            stats.culled,
+            stats.batches,
+            stats.state_changes,
+            visuals.point_lights().len(),
+        ));
+
+        out.push_str("  component graph:\n");
+        for root in world.root_components() {
+            Self::write_subtree(world, root, 2, &mut out);
+        }
+
+        out.push_str("  draw batches:\n");
+        for (i, b) in visuals.draw_batches().iter().enumerate() {
+            out.push_str(&format!(
+                "    #{i}: material={:?} mesh={:?} texture={:?} start={} count={}\n",
+                b.material, b.mesh, b.texture, b.start, b.count
+            ));
+        }
+
+        out
+    }
+
+    fn write_subtree(world: &World, cid: ComponentId, depth: usize, out: &mut String) {
+        let Some(node) = world.get_component_record(cid) else {
+            return;
+        };
+
+        let indent = "  ".repeat(depth);
+        let instance = world
+            .get_component_by_id_as::<RenderableComponent>(cid)
+            .map(|r| match r.get_handle() {
+                Some(h) => format!(" instance={:?}", h),
+                None => " instance=<pending>".to_string(),
+            })
+            .unwrap_or_default();
+        out.push_str(&format!("{indent}- {} {:?}{instance}\n", node.name, cid));
+
+        for &child in &node.children {
+            Self::write_subtree(world, child, depth + 1, out);
+        }
+    }
+}
@@ -0,0 +1,38 @@
+#version 450
+
+layout(location = 0) in vec2 v_uv;
+
+layout(location = 0) out vec4 f_color;
+// The background never glows.
+layout(location = 1) out vec4 f_emissive;
+// No motion: the reprojection of a skybox under a turning camera is left to the TAA clamp.
+layout(location = 2) out vec4 f_motion;
+
+// Set 0: skybox cubemap (a white placeholder for gradients).
+layout(set = 0, binding = 0) uniform samplerCube skybox;
+
+layout(push_constant) uniform Background {
+    // Clip space -> world-space view direction (camera rotation only).
+    mat4 inv_view_proj;
+    vec4 color_start;
+    vec4 color_end;
+    // Gradient direction in screen space, +Y down.
+    vec2 direction;
+    // 0: gradient, 1: skybox.
+    uint mode;
+} pc;
+
+void main() {
+    f_emissive = vec4(0.0);
+    f_motion = vec4(0.0);
+    if (pc.mode == 1u) {
+        vec4 far = pc.inv_view_proj * vec4(v_uv * 2.0 - 1.0, 1.0, 1.0);
+        f_color = vec4(texture(skybox, far.xyz / far.w).rgb, 1.0);
+        return;
+    }
+
+    // Project onto the direction, scaled so the screen corners span 0..1.
+    vec2 dir = length(pc.direction) > 1e-4 ? normalize(pc.direction) : vec2(0.0, 1.0);
+    float t = dot(v_uv - 0.5, dir) / (abs(dir.x) + abs(dir.y)) + 0.5;
+    f_color = mix(pc.color_start, pc.color_end, clamp(t, 0.0, 1.0));
+}
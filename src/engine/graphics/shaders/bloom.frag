@@ -0,0 +1,42 @@
+#version 450
+
+// Bloom passes at half resolution: bright-pass the emissive target, then a separable
+// Gaussian blur, run once horizontally and once vertically.
+
+layout(location = 0) in vec2 v_uv;
+
+layout(location = 0) out vec4 f_color;
+
+// Set 0: the emissive target (bright pass) or the previous blur pass.
+layout(set = 0, binding = 0) uniform sampler2D src;
+
+layout(push_constant) uniform Bloom {
+    // Blur step in UV (one texel along the blur axis); unused by the bright pass.
+    vec2 texel;
+    float threshold;
+    // 0: bright pass, 1: blur.
+    uint mode;
+} pc;
+
+// 9-tap Gaussian folded into 5 bilinear fetches.
+const float OFFSETS[3] = float[](0.0, 1.3846153846, 3.2307692308);
+const float WEIGHTS[3] = float[](0.2270270270, 0.3162162162, 0.0702702703);
+
+void main() {
+    if (pc.mode == 0u) {
+        vec3 c = textureLod(src, v_uv, 0.0).rgb;
+        float luminance = dot(c, vec3(0.2126, 0.7152, 0.0722));
+        // Keep only the part above the threshold, preserving hue.
+        float keep = max(luminance - pc.threshold, 0.0) / max(luminance, 1e-4);
+        f_color = vec4(c * keep, 1.0);
+        return;
+    }
+
+    vec3 sum = textureLod(src, v_uv, 0.0).rgb * WEIGHTS[0];
+    for (int i = 1; i < 3; i++) {
+        vec2 offset = pc.texel * OFFSETS[i];
+        sum += textureLod(src, v_uv + offset, 0.0).rgb * WEIGHTS[i];
+        sum += textureLod(src, v_uv - offset, 0.0).rgb * WEIGHTS[i];
+    }
+    f_color = vec4(sum, 1.0);
+}
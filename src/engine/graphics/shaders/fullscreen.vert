@@ -0,0 +1,10 @@
+#version 450
+
+// Fullscreen triangle; draw 3 vertices with no vertex buffer.
+layout(location = 0) out vec2 v_uv;
+
+void main() {
+    vec2 uv = vec2((gl_VertexIndex << 1) & 2, gl_VertexIndex & 2);
+    v_uv = uv;
+    gl_Position = vec4(uv * 2.0 - 1.0, 0.0, 1.0);
+}
@@ -0,0 +1,22 @@
+#version 450
+
+layout(location = 0) out vec4 f_color;
+// Outlines hide the glow of emissive meshes behind them.
+layout(location = 1) out vec4 f_emissive;
+// Screen movement since the previous frame, in UV units.
+layout(location = 2) out vec4 f_motion;
+
+layout(location = 0) in vec4 v_clip;
+layout(location = 1) in vec4 v_prev_clip;
+
+layout(push_constant) uniform OutlineParams {
+    vec4 color;
+    float width;
+} pc;
+
+void main() {
+    f_color = pc.color;
+    f_emissive = vec4(0.0, 0.0, 0.0, pc.color.a);
+    vec2 motion = (v_clip.xy / v_clip.w - v_prev_clip.xy / v_prev_clip.w) * 0.5;
+    f_motion = vec4(motion, 0.0, pc.color.a);
+}
@@ -0,0 +1,83 @@
+#version 450
+
+// Inverted-hull outline: the toon-mesh vertex transform, then every vertex is pushed away
+// from the mesh origin by `width` pixels in screen space. Drawn (untextured) right before the
+// mesh itself, so only the rim past the mesh edge stays visible.
+
+layout(location = 0) in vec3 in_pos;
+
+// Per-instance model matrix (same layout as toon-mesh.vert).
+layout(location = 1) in vec4 i_model_c0;
+layout(location = 2) in vec4 i_model_c1;
+layout(location = 3) in vec4 i_model_c2;
+layout(location = 4) in vec4 i_model_c3;
+layout(location = 7) in float i_depth_bias;
+layout(location = 8) in vec4 i_prev_model_c0;
+layout(location = 9) in vec4 i_prev_model_c1;
+layout(location = 10) in vec4 i_prev_model_c2;
+layout(location = 11) in vec4 i_prev_model_c3;
+
+// Must match `DEPTH_BIAS_RANGE` in visual_world.rs.
+const float DEPTH_BIAS_RANGE = 1.0 / 64.0;
+
+layout(set = 0, binding = 0) uniform CameraUBO {
+    mat4 view;
+    mat4 proj;
+    mat3 camera2d;
+    vec2 viewport;
+    vec2 _pad0;
+    // Camera of the previous frame, for motion vectors.
+    mat4 prev_view;
+    mat4 prev_proj;
+    mat3 prev_camera2d;
+    // TAA sub-pixel offset in NDC; zero while TAA is off.
+    vec2 jitter;
+    vec2 _pad1;
+} ubo;
+
+layout(push_constant) uniform OutlineParams {
+    vec4 color;
+    float width;
+} pc;
+
+// Unjittered clip positions of this and the previous frame (motion vectors).
+layout(location = 0) out vec4 v_clip;
+layout(location = 1) out vec4 v_prev_clip;
+
+// Must match the clip transform of toon-mesh.vert.
+vec4 to_clip(mat4 view, mat4 proj, mat3 camera2d, vec4 world) {
+    vec3 cam2d = camera2d * vec3(world.xy, 1.0);
+    float inv_aspect = (ubo.viewport.x > 0.0) ? (ubo.viewport.y / ubo.viewport.x) : 1.0;
+    vec4 clip_world = world;
+    clip_world.xy = vec2(cam2d.x * inv_aspect, cam2d.y);
+    return proj * view * clip_world;
+}
+
+// `in_pos` under `model` and the given camera, pushed outwards.
+vec4 outline_clip(mat4 model, mat4 view, mat4 proj, mat3 camera2d) {
+    vec4 clip = to_clip(view, proj, camera2d, model * vec4(in_pos, 1.0));
+    vec4 origin = to_clip(view, proj, camera2d, model * vec4(0.0, 0.0, 0.0, 1.0));
+
+    // Outward direction in pixels; the origin vertex of a fan has none and stays put.
+    vec2 half_viewport = 0.5 * ubo.viewport;
+    vec2 outward = (clip.xy / clip.w - origin.xy / origin.w) * half_viewport;
+    if (dot(outward, outward) > 1e-8) {
+        vec2 offset_ndc = normalize(outward) * pc.width / max(half_viewport, vec2(1.0));
+        clip.xy += offset_ndc * clip.w;
+    }
+    return clip;
+}
+
+void main() {
+    mat4 model = mat4(i_model_c0, i_model_c1, i_model_c2, i_model_c3);
+    mat4 prev_model = mat4(i_prev_model_c0, i_prev_model_c1, i_prev_model_c2, i_prev_model_c3);
+
+    vec4 clip = outline_clip(model, ubo.view, ubo.proj, ubo.camera2d);
+    v_clip = clip;
+    v_prev_clip = outline_clip(prev_model, ubo.prev_view, ubo.prev_proj, ubo.prev_camera2d);
+    clip.xy += ubo.jitter * clip.w;
+
+    // Same depth as the fill drawn over it (see toon-mesh.vert).
+    clip.z = clip.z * (1.0 - 2.0 * DEPTH_BIAS_RANGE) + (DEPTH_BIAS_RANGE - i_depth_bias) * clip.w;
+    gl_Position = clip;
+}
@@ -0,0 +1,45 @@
+#version 450
+
+// Temporal AA resolve: blend the (jittered) scene with the history of earlier frames,
+// reprojected by the motion vectors and clamped to the scene's 3x3 neighborhood so stale
+// history can't ghost.
+
+layout(location = 0) in vec2 v_uv;
+
+layout(location = 0) out vec4 f_color;
+
+// Set 0: this frame's HDR scene (mip 0).
+layout(set = 0, binding = 0) uniform sampler2D scene;
+// Set 1: the previous resolve.
+layout(set = 1, binding = 0) uniform sampler2D history;
+// Set 2: motion vectors, UV movement since the previous frame.
+layout(set = 2, binding = 0) uniform sampler2D motion;
+
+layout(push_constant) uniform Taa {
+    // One scene texel in UV.
+    vec2 texel;
+    // Share of the history in the result; 0 when there is none (first frame, resize).
+    float history_weight;
+} pc;
+
+void main() {
+    vec3 current = textureLod(scene, v_uv, 0.0).rgb;
+    vec3 lo = current;
+    vec3 hi = current;
+    for (int y = -1; y <= 1; y++) {
+        for (int x = -1; x <= 1; x++) {
+            vec3 c = textureLod(scene, v_uv + vec2(x, y) * pc.texel, 0.0).rgb;
+            lo = min(lo, c);
+            hi = max(hi, c);
+        }
+    }
+
+    vec2 prev_uv = v_uv - textureLod(motion, v_uv, 0.0).rg;
+    float weight = pc.history_weight;
+    if (any(lessThan(prev_uv, vec2(0.0))) || any(greaterThan(prev_uv, vec2(1.0)))) {
+        // Came in from off-screen: nothing to reuse.
+        weight = 0.0;
+    }
+    vec3 previous = clamp(textureLod(history, prev_uv, 0.0).rgb, lo, hi);
+    f_color = vec4(mix(current, previous, weight), 1.0);
+}
@@ -0,0 +1,71 @@
+#version 450
+
+layout(location = 0) in vec2 v_uv;
+
+layout(location = 0) out vec4 f_color;
+
+// Set 0: the linear HDR scene (mip 0, or the TAA resolve of it), possibly smaller than the
+// swapchain (render scale).
+layout(set = 0, binding = 0) uniform sampler2D hdr_scene;
+
+// Set 1: color grading LUT strip (see color_grading.rs), sRGB texture. Unused while
+// `lut_strength` is 0.
+layout(set = 1, binding = 0) uniform sampler2D lut;
+
+// Set 2: blurred bloom at half resolution (see bloom.frag). Unused while `bloom_intensity`
+// is 0.
+layout(set = 2, binding = 0) uniform sampler2D bloom;
+
+layout(push_constant) uniform Tonemap {
+    float exposure;
+    // Non-zero when the swapchain is UNORM and the sRGB encode must happen here.
+    uint encode_srgb;
+    // Edge length N of the LUT cube.
+    float lut_size;
+    float lut_strength;
+    float bloom_intensity;
+} pc;
+
+// Values up to the knee pass through unchanged (toon colors stay as authored); above it they
+// roll off smoothly towards 1.0 instead of clipping.
+const float KNEE = 0.8;
+
+vec3 tonemap(vec3 c) {
+    vec3 over = max(c - KNEE, 0.0);
+    vec3 shoulder = KNEE + (1.0 - KNEE) * (1.0 - exp(-over / (1.0 - KNEE)));
+    return mix(c, shoulder, step(KNEE, c));
+}
+
+vec3 linear_to_srgb(vec3 c) {
+    vec3 lo = c * 12.92;
+    vec3 hi = 1.055 * pow(c, vec3(1.0 / 2.4)) - 0.055;
+    return mix(lo, hi, step(0.0031308, c));
+}
+
+// Look the display color of `ldr` up in the LUT, blending the two nearest blue slices.
+vec3 grade(vec3 ldr) {
+    float n = pc.lut_size;
+    vec3 cell = clamp(linear_to_srgb(ldr), 0.0, 1.0) * (n - 1.0);
+    float slice = floor(cell.b);
+    float next = min(slice + 1.0, n - 1.0);
+    // Texel centers inside a slice, so filtering never bleeds into the neighboring slice.
+    vec2 uv = vec2(cell.r + 0.5, cell.g + 0.5) / vec2(n * n, n);
+    vec3 a = textureLod(lut, uv + vec2(slice / n, 0.0), 0.0).rgb;
+    vec3 b = textureLod(lut, uv + vec2(next / n, 0.0), 0.0).rgb;
+    return mix(a, b, cell.b - slice);
+}
+
+void main() {
+    vec3 hdr = textureLod(hdr_scene, v_uv, 0.0).rgb;
+    if (pc.bloom_intensity > 0.0) {
+        hdr += textureLod(bloom, v_uv, 0.0).rgb * pc.bloom_intensity;
+    }
+    vec3 ldr = tonemap(max(hdr * pc.exposure, 0.0));
+    if (pc.lut_strength > 0.0) {
+        ldr = mix(ldr, grade(ldr), pc.lut_strength);
+    }
+    if (pc.encode_srgb != 0u) {
+        ldr = linear_to_srgb(ldr);
+    }
+    f_color = vec4(ldr, 1.0);
+}
@@ -4,8 +4,14 @@ layout(location = 0) in vec3 v_world_pos;
 layout(location = 1) in vec3 v_normal;
 layout(location = 2) in vec2 v_uv;
 layout(location = 3) in vec4 v_color;
+layout(location = 4) in vec4 v_clip;
+layout(location = 5) in vec4 v_prev_clip;
 
 layout(location = 0) out vec4 f_color;
+// Bloom source: what emissive materials draw; everything else covers it with black.
+layout(location = 1) out vec4 f_emissive;
+// Screen movement since the previous frame, in UV units (TAA reprojection).
+layout(location = 2) out vec4 f_motion;
 
 // Compile-time debug selector:
 // 0 = normal lighting
@@ -20,6 +26,14 @@ struct PointLight {
     vec4 color_distance; // rgb color, w distance
 };
 
+layout(set = 0, binding = 0) uniform CameraUBO {
+    mat4 view;
+    mat4 proj;
+    mat3 camera2d;
+    vec2 viewport;
+    vec2 _pad0;
+} ubo;
+
 layout(set = 0, binding = 1, std430) readonly buffer LightsSSBO {
     uint count;
     // IMPORTANT: keep this header exactly 16 bytes to match the Rust side.
@@ -35,38 +49,66 @@ layout(set = 1, binding = 0) uniform MaterialUBO {
     vec4 base_color;
     float quant_steps;
     uint emissive;
-    uvec2 _pad0;
+    float reflectivity;
+    uint _pad0;
 } mat;
 
 layout(set = 1, binding = 1) uniform sampler2D base_tex;
+// White unless the material reflects an environment (reflectivity > 0).
+layout(set = 1, binding = 2) uniform samplerCube reflection_tex;
 
 float quantize(float x, float steps) {
     float s = max(1.0, steps);
     return floor(clamp(x, 0.0, 1.0) * s) / s;
 }
 
+// Mix the environment seen in the mirror direction over `rgb`.
+vec3 reflect_env(vec3 rgb) {
+    if (mat.reflectivity <= 0.0) {
+        return rgb;
+    }
+    // Camera position: the inverse of the view matrix's rigid transform.
+    vec3 eye = -transpose(mat3(ubo.view)) * ubo.view[3].xyz;
+    vec3 r = reflect(normalize(v_world_pos - eye), normalize(v_normal));
+    return mix(rgb, texture(reflection_tex, r).rgb, mat.reflectivity);
+}
+
 void main() {
     vec4 tex_rgba = texture(base_tex, v_uv);
     vec4 base_rgba = tex_rgba * v_color;
     vec3 base = base_rgba.rgb;
+    vec2 motion = (v_clip.xy / v_clip.w - v_prev_clip.xy / v_prev_clip.w) * 0.5;
+    f_motion = vec4(motion, 0.0, base_rgba.a);
 
     if (mat.emissive != 0u) {
-        f_color = vec4(base, base_rgba.a);
+        f_color = vec4(reflect_env(base), base_rgba.a);
+        f_emissive = f_color;
         return;
     }
+    f_emissive = vec4(0.0, 0.0, 0.0, base_rgba.a);
 
     uint light_count = min(g_lights.count, 64u);
     if (light_count == 0u) {
         // No lights: show black so it's obvious.
         f_color = vec4(0.0, 0.0, 0.0, 1.0);
+        f_emissive = vec4(0.0, 0.0, 0.0, 1.0);
         return;
     }
 
-    vec3 light_pos = g_lights.lights[0].pos_intensity.xyz;
-    vec3 light_color = g_lights.lights[0].color_distance.rgb;
-
+    // Lighting accumulates in linear HDR: each light adds banded (quantized) falloff on top of
+    // the unlit base, so overlapping or intense lights go above 1.0 and are compressed by the
+    // tonemap pass instead of clipping here.
+    vec3 light = vec3(1.0);
+    for (uint i = 0u; i < light_count; i++) {
+        vec3 light_pos = g_lights.lights[i].pos_intensity.xyz;
+        float intensity = g_lights.lights[i].pos_intensity.w;
+        vec3 light_color = g_lights.lights[i].color_distance.rgb;
+        float range = max(g_lights.lights[i].color_distance.w, 1e-4);
 
+        float falloff = clamp(1.0 - distance(light_pos, v_world_pos) / range, 0.0, 1.0);
+        light += light_color * intensity * quantize(falloff * falloff, mat.quant_steps);
+    }
 
-    vec3 out_rgb = base;
+    vec3 out_rgb = reflect_env(base * light);
     f_color = vec4(out_rgb, base_rgba.a);
 }
@@ -9,6 +9,16 @@ layout(location = 2) in vec4 i_model_c1;
 layout(location = 3) in vec4 i_model_c2;
 layout(location = 4) in vec4 i_model_c3;
 layout(location = 6) in vec4 i_color;
+// NDC depth to pull this instance toward the camera (`z_order_depth_bias`).
+layout(location = 7) in float i_depth_bias;
+// Model matrix of the previous frame.
+layout(location = 8) in vec4 i_prev_model_c0;
+layout(location = 9) in vec4 i_prev_model_c1;
+layout(location = 10) in vec4 i_prev_model_c2;
+layout(location = 11) in vec4 i_prev_model_c3;
+
+// Must match `DEPTH_BIAS_RANGE` in visual_world.rs.
+const float DEPTH_BIAS_RANGE = 1.0 / 64.0;
 
 // Set 0: global camera.
 // NOTE: This vertex shader currently applies `camera2d` + aspect correction before `proj*view`.
@@ -19,12 +29,31 @@ layout(set = 0, binding = 0) uniform CameraUBO {
     mat3 camera2d;
     vec2 viewport;
     vec2 _pad0;
+    // Camera of the previous frame, for motion vectors.
+    mat4 prev_view;
+    mat4 prev_proj;
+    mat3 prev_camera2d;
+    // TAA sub-pixel offset in NDC; zero while TAA is off.
+    vec2 jitter;
+    vec2 _pad1;
 } ubo;
 
 layout(location = 0) out vec3 v_world_pos;
 layout(location = 1) out vec3 v_normal;
 layout(location = 2) out vec2 v_uv;
 layout(location = 3) out vec4 v_color;
+// Unjittered clip positions of this and the previous frame (motion vectors).
+layout(location = 4) out vec4 v_clip;
+layout(location = 5) out vec4 v_prev_clip;
+
+// The clip transform below, with the previous frame's camera.
+vec4 prev_clip_of(vec4 world) {
+    vec3 cam2d = ubo.prev_camera2d * vec3(world.xy, 1.0);
+    float inv_aspect = (ubo.viewport.x > 0.0) ? (ubo.viewport.y / ubo.viewport.x) : 1.0;
+    vec4 clip_world = world;
+    clip_world.xy = vec2(cam2d.x * inv_aspect, cam2d.y);
+    return ubo.prev_proj * ubo.prev_view * clip_world;
+}
 
 void main() {
     mat4 model = mat4(i_model_c0, i_model_c1, i_model_c2, i_model_c3);
@@ -50,4 +79,13 @@ void main() {
     v_color = i_color;
 
     gl_Position = ubo.proj * ubo.view * clip_world;
+
+    mat4 prev_model = mat4(i_prev_model_c0, i_prev_model_c1, i_prev_model_c2, i_prev_model_c3);
+    v_clip = gl_Position;
+    v_prev_clip = prev_clip_of(prev_model * vec4(in_pos, 1.0));
+
+    gl_Position.xy += ubo.jitter * gl_Position.w;
+    // Squeeze scene depth into [RANGE, 1 - RANGE] so the bias never leaves [0, 1].
+    gl_Position.z = gl_Position.z * (1.0 - 2.0 * DEPTH_BIAS_RANGE)
+        + (DEPTH_BIAS_RANGE - i_depth_bias) * gl_Position.w;
 }
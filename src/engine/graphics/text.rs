@@ -0,0 +1,281 @@
+//! Bitmap text: a built-in 5x7 pixel font and meshes that lay strings out with it.
+//!
+//! `font_atlas` packs the printable ASCII glyphs (plus a panel cell for backgrounds) into one
+//! small RGBA texture; `TextMeshBuilder` emits a quad per glyph in pixel coordinates
+//! (+Y down, the space `ScreenAnchorSystem::anchor_model` maps to the screen). Draw the mesh
+//! with `MaterialHandle::UNLIT_MESH` and the atlas texture.
+//!
+//! Characters outside printable ASCII draw as `?`.
+
+use crate::engine::graphics::{CpuMesh, CpuVertex};
+
+/// Glyph size in font pixels.
+pub const GLYPH_WIDTH: u32 = 5;
+pub const GLYPH_HEIGHT: u32 = 7;
+
+/// Pen advance per character and per line, in font pixels.
+pub const GLYPH_ADVANCE: u32 = 6;
+pub const LINE_HEIGHT: u32 = 9;
+
+/// First character in the font (`' '`); glyphs run through `'~'`.
+const FIRST_CHAR: u32 = 0x20;
+const GLYPH_COUNT: u32 = 95;
+
+/// Atlas cell right after `'~'`, filled with `PANEL_RGBA` (see `TextMeshBuilder::panel`).
+const PANEL_CELL: u32 = GLYPH_COUNT;
+
+/// Color of panel quads: translucent black under the (white) glyphs.
+const PANEL_RGBA: [u8; 4] = [0, 0, 0, 176];
+
+/// Atlas layout: cells of one glyph plus a pixel of padding on the right and bottom.
+const ATLAS_COLUMNS: u32 = 16;
+const ATLAS_ROWS: u32 = 6;
+const CELL_WIDTH: u32 = GLYPH_WIDTH + 1;
+const CELL_HEIGHT: u32 = GLYPH_HEIGHT + 1;
+
+/// Rows of each glyph from top to bottom; bit 4 is the leftmost pixel.
+#[rustfmt::skip]
+const GLYPHS: [[u8; 7]; GLYPH_COUNT as usize] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // ' '
+    [0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x04], // '!'
+    [0x0A, 0x0A, 0x0A, 0x00, 0x00, 0x00, 0x00], // '"'
+    [0x0A, 0x0A, 0x1F, 0x0A, 0x1F, 0x0A, 0x0A], // '#'
+    [0x04, 0x0F, 0x14, 0x0E, 0x05, 0x1E, 0x04], // '$'
+    [0x18, 0x19, 0x02, 0x04, 0x08, 0x13, 0x03], // '%'
+    [0x0C, 0x12, 0x14, 0x08, 0x15, 0x12, 0x0D], // '&'
+    [0x0C, 0x04, 0x08, 0x00, 0x00, 0x00, 0x00], // '\''
+    [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02], // '('
+    [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08], // ')'
+    [0x00, 0x04, 0x15, 0x0E, 0x15, 0x04, 0x00], // '*'
+    [0x00, 0x04, 0x04, 0x1F, 0x04, 0x04, 0x00], // '+'
+    [0x00, 0x00, 0x00, 0x00, 0x0C, 0x04, 0x08], // ','
+    [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00], // '-'
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C], // '.'
+    [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00], // '/'
+    [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E], // '0'
+    [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E], // '1'
+    [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F], // '2'
+    [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E], // '3'
+    [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02], // '4'
+    [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E], // '5'
+    [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E], // '6'
+    [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08], // '7'
+    [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E], // '8'
+    [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C], // '9'
+    [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00], // ':'
+    [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x04, 0x08], // ';'
+    [0x02, 0x04, 0x08, 0x10, 0x08, 0x04, 0x02], // '<'
+    [0x00, 0x00, 0x1F, 0x00, 0x1F, 0x00, 0x00], // '='
+    [0x08, 0x04, 0x02, 0x01, 0x02, 0x04, 0x08], // '>'
+    [0x0E, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04], // '?'
+    [0x0E, 0x11, 0x01, 0x0D, 0x15, 0x15, 0x0E], // '@'
+    [0x0E, 0x11, 0x11, 0x11, 0x1F, 0x11, 0x11], // 'A'
+    [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E], // 'B'
+    [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E], // 'C'
+    [0x1C, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1C], // 'D'
+    [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F], // 'E'
+    [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10], // 'F'
+    [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F], // 'G'
+    [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11], // 'H'
+    [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E], // 'I'
+    [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C], // 'J'
+    [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11], // 'K'
+    [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F], // 'L'
+    [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11], // 'M'
+    [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11], // 'N'
+    [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E], // 'O'
+    [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10], // 'P'
+    [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D], // 'Q'
+    [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11], // 'R'
+    [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E], // 'S'
+    [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04], // 'T'
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E], // 'U'
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04], // 'V'
+    [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A], // 'W'
+    [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11], // 'X'
+    [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04], // 'Y'
+    [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F], // 'Z'
+    [0x0E, 0x08, 0x08, 0x08, 0x08, 0x08, 0x0E], // '['
+    [0x00, 0x10, 0x08, 0x04, 0x02, 0x01, 0x00], // '\\'
+    [0x0E, 0x02, 0x02, 0x02, 0x02, 0x02, 0x0E], // ']'
+    [0x04, 0x0A, 0x11, 0x00, 0x00, 0x00, 0x00], // '^'
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1F], // '_'
+    [0x08, 0x04, 0x02, 0x00, 0x00, 0x00, 0x00], // '`'
+    [0x00, 0x00, 0x0E, 0x01, 0x0F, 0x11, 0x0F], // 'a'
+    [0x10, 0x10, 0x16, 0x19, 0x11, 0x11, 0x1E], // 'b'
+    [0x00, 0x00, 0x0E, 0x10, 0x10, 0x11, 0x0E], // 'c'
+    [0x01, 0x01, 0x0D, 0x13, 0x11, 0x11, 0x0F], // 'd'
+    [0x00, 0x00, 0x0E, 0x11, 0x1F, 0x10, 0x0E], // 'e'
+    [0x06, 0x09, 0x08, 0x1C, 0x08, 0x08, 0x08], // 'f'
+    [0x00, 0x0F, 0x11, 0x11, 0x0F, 0x01, 0x0E], // 'g'
+    [0x10, 0x10, 0x16, 0x19, 0x11, 0x11, 0x11], // 'h'
+    [0x04, 0x00, 0x0C, 0x04, 0x04, 0x04, 0x0E], // 'i'
+    [0x02, 0x00, 0x06, 0x02, 0x02, 0x12, 0x0C], // 'j'
+    [0x10, 0x10, 0x12, 0x14, 0x18, 0x14, 0x12], // 'k'
+    [0x0C, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E], // 'l'
+    [0x00, 0x00, 0x1A, 0x15, 0x15, 0x11, 0x11], // 'm'
+    [0x00, 0x00, 0x16, 0x19, 0x11, 0x11, 0x11], // 'n'
+    [0x00, 0x00, 0x0E, 0x11, 0x11, 0x11, 0x0E], // 'o'
+    [0x00, 0x00, 0x1E, 0x11, 0x1E, 0x10, 0x10], // 'p'
+    [0x00, 0x00, 0x0D, 0x13, 0x0F, 0x01, 0x01], // 'q'
+    [0x00, 0x00, 0x16, 0x19, 0x10, 0x10, 0x10], // 'r'
+    [0x00, 0x00, 0x0E, 0x10, 0x0E, 0x01, 0x1E], // 's'
+    [0x08, 0x08, 0x1C, 0x08, 0x08, 0x09, 0x06], // 't'
+    [0x00, 0x00, 0x11, 0x11, 0x11, 0x13, 0x0D], // 'u'
+    [0x00, 0x00, 0x11, 0x11, 0x11, 0x0A, 0x04], // 'v'
+    [0x00, 0x00, 0x11, 0x11, 0x15, 0x15, 0x0A], // 'w'
+    [0x00, 0x00, 0x11, 0x0A, 0x04, 0x0A, 0x11], // 'x'
+    [0x00, 0x00, 0x11, 0x11, 0x0F, 0x01, 0x0E], // 'y'
+    [0x00, 0x00, 0x1F, 0x02, 0x04, 0x08, 0x1F], // 'z'
+    [0x02, 0x04, 0x04, 0x08, 0x04, 0x04, 0x02], // '{'
+    [0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04], // '|'
+    [0x08, 0x04, 0x04, 0x02, 0x04, 0x04, 0x08], // '}'
+    [0x00, 0x00, 0x08, 0x15, 0x02, 0x00, 0x00], // '~'
+];
+
+/// RGBA8 texture holding the built-in font.
+#[derive(Debug, Clone)]
+pub struct FontAtlas {
+    pub rgba: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Render the built-in font: white glyphs on transparent cells, plus the panel cell.
+pub fn font_atlas() -> FontAtlas {
+    let width = ATLAS_COLUMNS * CELL_WIDTH;
+    let height = ATLAS_ROWS * CELL_HEIGHT;
+    let mut rgba = vec![0u8; (width * height * 4) as usize];
+    let mut put = |x: u32, y: u32, px: [u8; 4]| {
+        let i = ((y * width + x) * 4) as usize;
+        rgba[i..i + 4].copy_from_slice(&px);
+    };
+
+    for (cell, rows) in GLYPHS.iter().enumerate() {
+        let (cx, cy) = cell_origin(cell as u32);
+        for (y, row) in rows.iter().enumerate() {
+            for x in 0..GLYPH_WIDTH {
+                if row & (0x10 >> x) != 0 {
+                    put(cx + x, cy + y as u32, [255; 4]);
+                }
+            }
+        }
+    }
+    // The whole panel cell, padding included, so filtering never pulls in transparent texels.
+    let (cx, cy) = cell_origin(PANEL_CELL);
+    for y in 0..CELL_HEIGHT {
+        for x in 0..CELL_WIDTH {
+            put(cx + x, cy + y, PANEL_RGBA);
+        }
+    }
+
+    FontAtlas {
+        rgba,
+        width,
+        height,
+    }
+}
+
+fn cell_origin(cell: u32) -> (u32, u32) {
+    (
+        (cell % ATLAS_COLUMNS) * CELL_WIDTH,
+        (cell / ATLAS_COLUMNS) * CELL_HEIGHT,
+    )
+}
+
+/// Atlas cell drawn for `c`.
+fn glyph_cell(c: char) -> u32 {
+    let code = c as u32;
+    if (FIRST_CHAR..FIRST_CHAR + GLYPH_COUNT).contains(&code) {
+        code - FIRST_CHAR
+    } else {
+        '?' as u32 - FIRST_CHAR
+    }
+}
+
+/// Collects glyph and panel quads into one mesh.
+///
+/// Positions are in screen pixels (+Y down) and every font pixel covers `scale` of them;
+/// later quads draw over earlier ones, so add panels before the text on them.
+#[derive(Debug, Clone)]
+pub struct TextMeshBuilder {
+    scale: f32,
+    vertices: Vec<CpuVertex>,
+    indices: Vec<u32>,
+}
+
+impl TextMeshBuilder {
+    pub fn new(scale: f32) -> Self {
+        Self {
+            scale,
+            vertices: Vec::new(),
+            indices: Vec::new(),
+        }
+    }
+
+    /// Add a background panel covering `min..max` (screen pixels).
+    pub fn panel(&mut self, min: [f32; 2], max: [f32; 2]) {
+        let (cx, cy) = cell_origin(PANEL_CELL);
+        // Sample the middle of the cell; the whole cell has the panel color.
+        let u = (cx as f32 + 0.5 * CELL_WIDTH as f32) / (ATLAS_COLUMNS * CELL_WIDTH) as f32;
+        let v = (cy as f32 + 0.5 * CELL_HEIGHT as f32) / (ATLAS_ROWS * CELL_HEIGHT) as f32;
+        self.quad(min, max, [u, v], [u, v]);
+    }
+
+    /// Add `line` with the top-left of its first glyph at `origin` (screen pixels).
+    pub fn line(&mut self, origin: [f32; 2], line: &str) {
+        let (atlas_w, atlas_h) = (
+            (ATLAS_COLUMNS * CELL_WIDTH) as f32,
+            (ATLAS_ROWS * CELL_HEIGHT) as f32,
+        );
+        let (w, h) = (
+            GLYPH_WIDTH as f32 * self.scale,
+            GLYPH_HEIGHT as f32 * self.scale,
+        );
+        for (i, c) in line.chars().enumerate() {
+            if c == ' ' {
+                continue;
+            }
+            let (cx, cy) = cell_origin(glyph_cell(c));
+            let x = origin[0] + (i as u32 * GLYPH_ADVANCE) as f32 * self.scale;
+            let y = origin[1];
+            self.quad(
+                [x, y],
+                [x + w, y + h],
+                [cx as f32 / atlas_w, cy as f32 / atlas_h],
+                [
+                    (cx + GLYPH_WIDTH) as f32 / atlas_w,
+                    (cy + GLYPH_HEIGHT) as f32 / atlas_h,
+                ],
+            );
+        }
+    }
+
+    /// Add `lines` one below the other, starting at `origin`.
+    pub fn lines<'a>(&mut self, origin: [f32; 2], lines: impl IntoIterator<Item = &'a str>) {
+        let step = LINE_HEIGHT as f32 * self.scale;
+        for (i, line) in lines.into_iter().enumerate() {
+            self.line([origin[0], origin[1] + i as f32 * step], line);
+        }
+    }
+
+    pub fn build(self) -> CpuMesh {
+        CpuMesh::new(self.vertices, self.indices)
+    }
+
+    fn quad(&mut self, min: [f32; 2], max: [f32; 2], uv_min: [f32; 2], uv_max: [f32; 2]) {
+        let base = self.vertices.len() as u32;
+        let corners = [
+            ([min[0], min[1]], [uv_min[0], uv_min[1]]),
+            ([max[0], min[1]], [uv_max[0], uv_min[1]]),
+            ([max[0], max[1]], [uv_max[0], uv_max[1]]),
+            ([min[0], max[1]], [uv_min[0], uv_max[1]]),
+        ];
+        self.vertices.extend(corners.map(|([x, y], uv)| CpuVertex {
+            pos: [x, y, 0.0],
+            uv,
+        }));
+        self.indices
+            .extend([base, base + 1, base + 2, base, base + 2, base + 3]);
+    }
+}
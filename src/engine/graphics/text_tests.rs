@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod tests {
+    use crate::engine::graphics::text::{
+        GLYPH_ADVANCE, GLYPH_HEIGHT, GLYPH_WIDTH, LINE_HEIGHT, TextMeshBuilder, font_atlas,
+    };
+
+    #[test]
+    fn atlas_draws_glyph_pixels_in_their_cells() {
+        let atlas = font_atlas();
+        assert_eq!(atlas.rgba.len(), (atlas.width * atlas.height * 4) as usize);
+        let alpha = |x: u32, y: u32| atlas.rgba[((y * atlas.width + x) * 4 + 3) as usize];
+
+        // '!' is the second cell: a center column with a gap above the dot.
+        let x0 = GLYPH_WIDTH + 1;
+        assert_eq!(alpha(x0 + 2, 0), 255);
+        assert_eq!(alpha(x0 + 2, 5), 0);
+        assert_eq!(alpha(x0 + 2, 6), 255);
+        assert_eq!(alpha(x0, 0), 0);
+        // Padding between cells stays transparent.
+        assert_eq!(alpha(x0 + GLYPH_WIDTH, 0), 0);
+        assert_eq!(alpha(x0, GLYPH_HEIGHT), 0);
+    }
+
+    #[test]
+    fn lines_lay_out_one_quad_per_visible_glyph() {
+        let mut text = TextMeshBuilder::new(2.0);
+        text.lines([10.0, 20.0], ["a b", "\u{e9}"]);
+        let mesh = text.build();
+
+        // Spaces add nothing; the unknown character still draws (as '?').
+        assert_eq!(mesh.vertex_count(), 3 * 4);
+        assert_eq!(mesh.index_count(), 3 * 6);
+
+        let b = &mesh.vertices[4..8];
+        let x = 10.0 + (2 * GLYPH_ADVANCE) as f32 * 2.0;
+        assert_eq!(b[0].pos, [x, 20.0, 0.0]);
+        assert_eq!(
+            b[2].pos,
+            [
+                x + GLYPH_WIDTH as f32 * 2.0,
+                20.0 + GLYPH_HEIGHT as f32 * 2.0,
+                0.0
+            ]
+        );
+        let second_line = &mesh.vertices[8];
+        assert_eq!(second_line.pos[1], 20.0 + LINE_HEIGHT as f32 * 2.0);
+
+        let mut question = TextMeshBuilder::new(2.0);
+        question.line([0.0, 0.0], "?");
+        assert_eq!(question.build().vertices[0].uv, mesh.vertices[8].uv);
+    }
+
+    #[test]
+    fn panels_sample_an_opaque_texel() {
+        let atlas = font_atlas();
+        let mut text = TextMeshBuilder::new(1.0);
+        text.panel([0.0, 0.0], [100.0, 50.0]);
+        let mesh = text.build();
+        assert_eq!(mesh.vertices[2].pos, [100.0, 50.0, 0.0]);
+
+        let [u, v] = mesh.vertices[0].uv;
+        let (x, y) = (
+            (u * atlas.width as f32) as u32,
+            (v * atlas.height as f32) as u32,
+        );
+        assert!(atlas.rgba[((y * atlas.width + x) * 4 + 3) as usize] > 0);
+    }
+}
@@ -2,17 +2,80 @@ use crate::engine::ecs::ComponentId;
 use crate::engine::ecs::Transform;
 use crate::engine::graphics::GpuRenderable;
 use crate::engine::graphics::primitives::InstanceHandle;
+use crate::engine::graphics::{
+    BlendMode, ColorGrading, Culling, Exposure, Outline, PostProcessSettings, Reflection,
+    RenderScale, RendererConfig,
+};
+use crate::utils::frame_arena::FrameArena;
+use crate::utils::profiling;
+
+/// Layer mask new instances are placed on unless their renderable says otherwise.
+pub const DEFAULT_VISIBILITY_LAYERS: u32 = 1;
+
+/// Camera mask that sees every layer.
+pub const ALL_VISIBILITY_LAYERS: u32 = u32::MAX;
+
+/// Share of NDC depth reserved at each end for z-order bias; the mesh shaders squeeze scene
+/// depth into the rest, so biased 2D quads at the near plane are not clipped.
+pub const DEPTH_BIAS_RANGE: f32 = 1.0 / 64.0;
+
+/// `z_order` values beyond this are clamped (it still orders draws, but not depth).
+pub const MAX_DEPTH_Z_ORDER: i32 = 1024;
+
+const IDENTITY: [[f32; 4]; 4] = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+];
+
+/// NDC depth an instance is pulled toward the camera for its `z_order`.
+pub fn z_order_depth_bias(z_order: i32) -> f32 {
+    let steps = z_order.clamp(-MAX_DEPTH_Z_ORDER, MAX_DEPTH_Z_ORDER) as f32;
+    steps * (DEPTH_BIAS_RANGE / MAX_DEPTH_Z_ORDER as f32)
+}
+
+/// Order of an instance's batch: (z-order, blend rank, clip, material, mesh, texture).
+type DrawKey = (i32, u8, Option<ComponentId>, u32, u32, u32);
+
+/// Where an instance sits in `VisualWorld::draw_buckets`.
+#[derive(Debug, Clone, Copy, Default)]
+struct DrawSlot {
+    key: DrawKey,
+    pos: u32,
+}
 
 #[derive(Debug, Clone, Copy)]
 pub struct DrawBatch {
     pub material: crate::engine::graphics::MaterialHandle,
     pub mesh: crate::engine::graphics::primitives::MeshHandle,
     pub texture: Option<crate::engine::graphics::TextureHandle>,
+    /// `VisualWorld::material_blend` of `material`.
+    pub blend: BlendMode,
+    /// Clip of every instance in the batch (`VisualWorld::clip_rect`).
+    pub clip: Option<ComponentId>,
+    /// `VisualInstance::z_order` of every instance in the batch.
+    pub z_order: i32,
     /// Range into `draw_order`
     pub start: usize,
     pub count: usize,
 }
 
+/// Shape of the draw cache built by `VisualWorld::prepare_draw_cache`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DrawCacheStats {
+    /// Instances in `draw_order`.
+    pub instances: usize,
+    pub batches: usize,
+    /// Instances left out by the camera's visibility layers.
+    pub culled: usize,
+    /// Binds the renderer makes walking the batches: pipeline and material sets whenever
+    /// material, texture or blend mode change, scissor whenever the clip does.
+    pub state_changes: usize,
+    /// The cache was rebuilt by this call rather than reused.
+    pub rebuilt: bool,
+}
+
 pub struct VisualWorld {
     instances: Vec<VisualInstance>,
 
@@ -27,18 +90,80 @@ pub struct VisualWorld {
     // Stored as mat3 column vectors padded to vec4 columns (std140 friendly).
     camera_2d: [[f32; 4]; 3],
     dirty_camera: bool,
+    /// Camera of the last rendered frame, for motion vectors.
+    previous_camera: CameraMatrices,
+    /// Window size in physical pixels (mirrored from the window by `Universe`).
+    window_size: [f32; 2],
+    renderer_config: RendererConfig,
+    /// Layer mask of the active camera; instances outside it are left out of `draw_order`.
+    camera_visibility_layers: u32,
+    /// Fraction of the viewport the scene is rendered at before upscaling.
+    render_scale: RenderScale,
+    /// Scale applied to the HDR scene before tonemapping.
+    exposure: Exposure,
+    /// Bloom and other passes between the scene and tonemapping.
+    post_process: PostProcessSettings,
+    /// Drawn behind every instance (see `BackgroundComponent`).
+    background: VisualBackground,
+    /// LUT applied after tonemapping (see `ColorGradingComponent`).
+    color_grading: Option<ColorGrading>,
+    /// Per-material outline overrides; `None` turns a material's default outline off.
+    outlines: std::collections::HashMap<crate::engine::graphics::MaterialHandle, Option<Outline>>,
+    /// Environment reflections per material; none by default.
+    reflections: std::collections::HashMap<crate::engine::graphics::MaterialHandle, Reflection>,
+    /// Per-material blend mode overrides.
+    blends: std::collections::HashMap<crate::engine::graphics::MaterialHandle, BlendMode>,
+    /// Screen rects of `ClipRectComponent`s, keyed by component.
+    clip_rects: std::collections::HashMap<ComponentId, ClipRect>,
+    /// Per-material face culling overrides.
+    cullings: std::collections::HashMap<crate::engine::graphics::MaterialHandle, Culling>,
 
     next_handle: u32,
     handle_to_index: std::collections::HashMap<InstanceHandle, usize>,
+    /// Handle and owning component of each entry of `instances`, so `remove` can fix up the
+    /// instance it swaps into the hole without searching.
+    index_to_handle: Vec<(InstanceHandle, ComponentId)>,
     component_to_handle: std::collections::HashMap<ComponentId, InstanceHandle>,
 
     // Cached draw data (rebuilt when dirty)
     dirty_draw_cache: bool,
+    /// Every draw key may have changed (a material's blend mode): the next
+    /// `prepare_draw_cache` sorts all instances into `draw_buckets` again.
+    dirty_draw_keys: bool,
+    /// Instances by draw key, one bucket per batch. Kept up to date as instances come, go
+    /// and change, so `prepare_draw_cache` only concatenates the buckets.
+    draw_buckets: std::collections::BTreeMap<DrawKey, Vec<u32>>,
+    /// Bucket of each entry of `instances`.
+    draw_slots: Vec<DrawSlot>,
     /// True when per-instance data (e.g. model matrices) changed and any cached GPU instance
     /// buffer should be rebuilt/uploaded.
     dirty_instance_data: bool,
     draw_order: Vec<u32>, // indices into `instances`
     draw_batches: Vec<DrawBatch>,
+    draw_cache_stats: DrawCacheStats,
+
+    /// Scratch for draw cache rebuilds and the renderer; reset by `end_frame`.
+    frame_arena: FrameArena,
+}
+
+/// Renderer-side background (mirrored from `BackgroundComponent` by `BackgroundSystem`).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum VisualBackground {
+    /// Nothing drawn: `RendererConfig::clear_color` shows through.
+    #[default]
+    Clear,
+    /// Linear RGBA clear color.
+    Solid([f32; 4]),
+    GradientXY {
+        start: [f32; 4],
+        end: [f32; 4],
+        /// Screen space, +Y down.
+        direction: [f32; 2],
+    },
+    Skybox {
+        /// Cubemap from `TextureUploader::upload_cubemap_rgba8`.
+        cubemap: crate::engine::graphics::TextureHandle,
+    },
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -47,6 +172,43 @@ pub struct VisualInstance {
     pub transform: Transform,
     pub color: [f32; 4],
     pub texture: Option<crate::engine::graphics::TextureHandle>,
+    /// Bitmask of layers this instance lives on (see `Camera*Component::visibility_layers`).
+    pub visibility_layers: u32,
+    /// `ClipRectComponent` the instance is clipped to (see `VisualWorld::clip_rect`).
+    pub clip: Option<ComponentId>,
+    /// 2D stacking order: higher draws later and, with depth testing, on top of coplanar
+    /// instances with a lower value (`z_order_depth_bias`).
+    pub z_order: i32,
+    /// Model matrix of the previous frame (see `VisualWorld::end_frame`), for motion vectors.
+    pub prev_model: [[f32; 4]; 4],
+}
+
+/// Camera matrices a frame is drawn with (see `VisualWorld::previous_camera`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraMatrices {
+    pub view: [[f32; 4]; 4],
+    pub proj: [[f32; 4]; 4],
+    pub camera_2d: [[f32; 4]; 3],
+}
+
+/// Screen rectangle in physical pixels, origin at the top-left.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClipRect {
+    pub min: [f32; 2],
+    pub max: [f32; 2],
+}
+
+impl ClipRect {
+    pub fn intersect(self, other: ClipRect) -> ClipRect {
+        ClipRect {
+            min: [self.min[0].max(other.min[0]), self.min[1].max(other.min[1])],
+            max: [self.max[0].min(other.max[0]), self.max[1].min(other.max[1])],
+        }
+    }
+
+    pub fn is_empty(self) -> bool {
+        !(self.max[0] > self.min[0] && self.max[1] > self.min[1])
+    }
 }
 
 impl Default for VisualWorld {
@@ -58,33 +220,51 @@ impl Default for VisualWorld {
             point_light_index_by_component: std::collections::HashMap::new(),
             dirty_lights: true,
 
-            camera_view: [
-                [1.0, 0.0, 0.0, 0.0],
-                [0.0, 1.0, 0.0, 0.0],
-                [0.0, 0.0, 1.0, 0.0],
-                [0.0, 0.0, 0.0, 1.0],
-            ],
-            camera_proj: [
-                [1.0, 0.0, 0.0, 0.0],
-                [0.0, 1.0, 0.0, 0.0],
-                [0.0, 0.0, 1.0, 0.0],
-                [0.0, 0.0, 0.0, 1.0],
-            ],
+            camera_view: IDENTITY,
+            camera_proj: IDENTITY,
             camera_2d: [
                 [1.0, 0.0, 0.0, 0.0],
                 [0.0, 1.0, 0.0, 0.0],
                 [0.0, 0.0, 1.0, 0.0],
             ],
             dirty_camera: true,
+            previous_camera: CameraMatrices {
+                view: IDENTITY,
+                proj: IDENTITY,
+                camera_2d: [
+                    [1.0, 0.0, 0.0, 0.0],
+                    [0.0, 1.0, 0.0, 0.0],
+                    [0.0, 0.0, 1.0, 0.0],
+                ],
+            },
+            window_size: [1.0, 1.0],
+            renderer_config: RendererConfig::default(),
+            camera_visibility_layers: ALL_VISIBILITY_LAYERS,
+            render_scale: RenderScale::default(),
+            exposure: Exposure::default(),
+            post_process: PostProcessSettings::default(),
+            outlines: std::collections::HashMap::new(),
+            reflections: std::collections::HashMap::new(),
+            blends: std::collections::HashMap::new(),
+            cullings: std::collections::HashMap::new(),
+            clip_rects: std::collections::HashMap::new(),
+            background: VisualBackground::default(),
+            color_grading: None,
 
             next_handle: 0,
             handle_to_index: std::collections::HashMap::new(),
+            index_to_handle: Vec::new(),
             component_to_handle: std::collections::HashMap::new(),
 
             dirty_draw_cache: true,
+            dirty_draw_keys: false,
+            draw_buckets: std::collections::BTreeMap::new(),
+            draw_slots: Vec::new(),
             dirty_instance_data: true,
             draw_order: Vec::new(),
             draw_batches: Vec::new(),
+            draw_cache_stats: DrawCacheStats::default(),
+            frame_arena: FrameArena::new(),
         }
     }
 }
@@ -105,14 +285,19 @@ impl VisualWorld {
     pub fn clear(&mut self) {
         self.instances.clear();
         self.handle_to_index.clear();
+        self.index_to_handle.clear();
         self.component_to_handle.clear();
         self.next_handle = 0;
 
         self.point_lights.clear();
         self.point_light_index_by_component.clear();
         self.dirty_lights = true;
+        self.clip_rects.clear();
 
         self.dirty_draw_cache = true;
+        self.dirty_draw_keys = false;
+        self.draw_buckets.clear();
+        self.draw_slots.clear();
         self.dirty_instance_data = true;
         self.dirty_camera = true;
         self.draw_order.clear();
@@ -166,6 +351,34 @@ impl VisualWorld {
         self.camera_2d
     }
 
+    /// World-space position of the active 3D camera, recovered from the view matrix.
+    pub fn camera_position(&self) -> [f32; 3] {
+        let view = glam::Mat4::from_cols_array_2d(&self.camera_view);
+        if view.determinant().abs() < 1e-12 {
+            return [0.0; 3];
+        }
+        view.inverse().w_axis.truncate().to_array()
+    }
+
+    /// Camera the last rendered frame was drawn with.
+    pub fn previous_camera(&self) -> CameraMatrices {
+        self.previous_camera
+    }
+
+    /// Make this frame's camera and instance models the previous frame of motion vectors.
+    /// Called once after every rendered frame.
+    pub fn end_frame(&mut self) {
+        self.frame_arena.reset();
+        for instance in &mut self.instances {
+            instance.prev_model = instance.transform.model;
+        }
+        self.previous_camera = CameraMatrices {
+            view: self.camera_view,
+            proj: self.camera_proj,
+            camera_2d: self.camera_2d,
+        };
+    }
+
     pub fn set_camera(&mut self, view: [[f32; 4]; 4], proj: [[f32; 4]; 4]) {
         self.camera_view = view;
         self.camera_proj = proj;
@@ -186,6 +399,185 @@ impl VisualWorld {
         self.dirty_camera = true;
     }
 
+    /// Size the scene is laid out at, in pixels: the window size, or the virtual resolution
+    /// while letterboxing (`RendererConfig::virtual_resolution`).
+    pub fn viewport(&self) -> [f32; 2] {
+        match self.renderer_config.virtual_resolution {
+            Some([w, h]) if w > 0 && h > 0 => [w as f32, h as f32],
+            _ => self.window_size,
+        }
+    }
+
+    /// Width / height of the viewport (1.0 if the height is zero, e.g. minimized).
+    pub fn viewport_aspect(&self) -> f32 {
+        let [w, h] = self.viewport();
+        if h > 0.0 { w / h } else { 1.0 }
+    }
+
+    pub fn window_size(&self) -> [f32; 2] {
+        self.window_size
+    }
+
+    /// Map window pixels (e.g. the cursor) to viewport pixels, undoing the letterbox.
+    pub fn window_to_viewport(&self, window_px: [f32; 2]) -> [f32; 2] {
+        let window = self.window_size.map(|v| v.max(0.0) as u32);
+        let letterbox = self.renderer_config.letterbox(window);
+        let viewport = self.viewport();
+        [0, 1].map(|i| {
+            let extent = letterbox.extent[i].max(1) as f32;
+            (window_px[i] - letterbox.offset[i] as f32) * viewport[i] / extent
+        })
+    }
+
+    pub fn renderer_config(&self) -> &RendererConfig {
+        &self.renderer_config
+    }
+
+    pub fn set_renderer_config(&mut self, config: RendererConfig) {
+        if config.virtual_resolution != self.renderer_config.virtual_resolution {
+            self.dirty_camera = true;
+        }
+        self.renderer_config = config;
+    }
+
+    pub fn render_scale(&self) -> &RenderScale {
+        &self.render_scale
+    }
+
+    pub fn render_scale_mut(&mut self) -> &mut RenderScale {
+        &mut self.render_scale
+    }
+
+    pub fn exposure(&self) -> &Exposure {
+        &self.exposure
+    }
+
+    pub fn exposure_mut(&mut self) -> &mut Exposure {
+        &mut self.exposure
+    }
+
+    pub fn post_process(&self) -> &PostProcessSettings {
+        &self.post_process
+    }
+
+    pub fn post_process_mut(&mut self) -> &mut PostProcessSettings {
+        &mut self.post_process
+    }
+
+    pub fn background(&self) -> VisualBackground {
+        self.background
+    }
+
+    pub fn set_background(&mut self, background: VisualBackground) {
+        self.background = background;
+    }
+
+    pub fn color_grading(&self) -> Option<ColorGrading> {
+        self.color_grading
+    }
+
+    pub fn set_color_grading(&mut self, grading: Option<ColorGrading>) {
+        self.color_grading = grading;
+    }
+
+    /// Outline drawn around meshes with `material`: the override if set, else the built-in
+    /// material's default.
+    pub fn material_outline(
+        &self,
+        material: crate::engine::graphics::MaterialHandle,
+    ) -> Option<Outline> {
+        match self.outlines.get(&material) {
+            Some(outline) => *outline,
+            None => material.material().and_then(|m| m.outline),
+        }
+    }
+
+    /// Override the outline of `material` (`None` disables it).
+    pub fn set_material_outline(
+        &mut self,
+        material: crate::engine::graphics::MaterialHandle,
+        outline: Option<Outline>,
+    ) {
+        self.outlines.insert(material, outline);
+    }
+
+    /// Blend mode of `material`: the override if set, else the built-in material's.
+    pub fn material_blend(&self, material: crate::engine::graphics::MaterialHandle) -> BlendMode {
+        match self.blends.get(&material) {
+            Some(blend) => *blend,
+            None => material.material().map(|m| m.blend).unwrap_or_default(),
+        }
+    }
+
+    /// Override the blend mode of `material`; its batches move to the matching draw group.
+    pub fn set_material_blend(
+        &mut self,
+        material: crate::engine::graphics::MaterialHandle,
+        blend: BlendMode,
+    ) {
+        if self.blends.insert(material, blend) != Some(blend) {
+            self.dirty_draw_cache = true;
+            self.dirty_draw_keys = true;
+        }
+    }
+
+    /// Face culling of `material`: the override if set, else the built-in material's.
+    pub fn material_culling(&self, material: crate::engine::graphics::MaterialHandle) -> Culling {
+        match self.cullings.get(&material) {
+            Some(culling) => *culling,
+            None => material.material().map(|m| m.culling).unwrap_or_default(),
+        }
+    }
+
+    pub fn set_material_culling(
+        &mut self,
+        material: crate::engine::graphics::MaterialHandle,
+        culling: Culling,
+    ) {
+        self.cullings.insert(material, culling);
+    }
+
+    pub fn material_reflection(
+        &self,
+        material: crate::engine::graphics::MaterialHandle,
+    ) -> Option<Reflection> {
+        self.reflections.get(&material).copied()
+    }
+
+    /// Reflect `reflection.cubemap` on meshes with `material` (`None` turns it off).
+    pub fn set_material_reflection(
+        &mut self,
+        material: crate::engine::graphics::MaterialHandle,
+        reflection: Option<Reflection>,
+    ) {
+        match reflection {
+            Some(reflection) => self.reflections.insert(material, reflection),
+            None => self.reflections.remove(&material),
+        };
+    }
+
+    /// Set the window size; the viewport follows it unless letterboxing.
+    pub fn set_viewport(&mut self, width: f32, height: f32) {
+        if self.window_size == [width, height] {
+            return;
+        }
+        self.window_size = [width, height];
+        self.dirty_camera = true;
+    }
+
+    pub fn camera_visibility_layers(&self) -> u32 {
+        self.camera_visibility_layers
+    }
+
+    /// Set the active camera's layer mask. Only instances sharing a bit are drawn.
+    pub fn set_camera_visibility_layers(&mut self, mask: u32) {
+        if self.camera_visibility_layers == mask {
+            return;
+        }
+        self.camera_visibility_layers = mask;
+        self.dirty_draw_cache = true;
+    }
+
     /// Returns whether any per-instance data has changed since the last time it was consumed.
     pub fn instance_data_dirty(&self) -> bool {
         self.dirty_instance_data
@@ -202,6 +594,17 @@ impl VisualWorld {
         &self.instances
     }
 
+    pub fn instance(&self, handle: InstanceHandle) -> Option<&VisualInstance> {
+        self.handle_to_index
+            .get(&handle)
+            .map(|&idx| &self.instances[idx])
+    }
+
+    /// Per-frame scratch allocations (see `utils::frame_arena`).
+    pub fn frame_arena(&self) -> &FrameArena {
+        &self.frame_arena
+    }
+
     /// Indices into `instances()` in the order they should be drawn (opaque batching).
     pub fn draw_order(&self) -> &[u32] {
         &self.draw_order
@@ -211,60 +614,176 @@ impl VisualWorld {
         &self.draw_batches
     }
 
-    /// Call once per frame before rendering. Cheap if nothing changed.
+    /// Returns whether the draw order/batches will be rebuilt by the next `prepare_draw_cache`.
+    pub fn draw_cache_dirty(&self) -> bool {
+        self.dirty_draw_cache
+    }
+
+    /// Make the next `prepare_draw_cache` re-sort every instance even if nothing changed
+    /// (benchmarks).
+    pub fn invalidate_draw_cache(&mut self) {
+        self.dirty_draw_cache = true;
+        self.dirty_draw_keys = true;
+    }
+
+    /// Stats of the current draw cache (as of the last `prepare_draw_cache`).
+    pub fn draw_cache_stats(&self) -> DrawCacheStats {
+        self.draw_cache_stats
+    }
+
+    /// Call once per frame before rendering. Cheap if nothing changed; otherwise linear in
+    /// the instance count, since instances are kept sorted as they change.
     ///
-    /// Returns `true` if the cached draw order/batches were rebuilt this call.
-    pub fn prepare_draw_cache(&mut self) -> bool {
+    /// Rebuilds publish the stats as `draw.*` profiling gauges and count `draw.cache_rebuilds`.
+    pub fn prepare_draw_cache(&mut self) -> DrawCacheStats {
         if !self.dirty_draw_cache {
-            return false;
+            return DrawCacheStats {
+                rebuilt: false,
+                ..self.draw_cache_stats
+            };
+        }
+        if self.dirty_draw_keys {
+            self.rebuild_draw_buckets();
         }
 
+        // Buckets come in key order, so higher z-orders draw last and, within one, blended
+        // batches draw over everything opaque. Each bucket with a visible instance is a batch.
+        let mask = self.camera_visibility_layers;
         self.draw_order.clear();
-        self.draw_order.extend(0..self.instances.len() as u32);
-
-        // Sort by (material, mesh). Stable sort keeps relative order for identical keys.
-        self.draw_order.sort_by_key(|&i| {
-            let inst = self.instances[i as usize];
-            let r = inst.renderable;
-            let tex = inst.texture.map(|t| t.0).unwrap_or(u32::MAX);
-            (r.material.0, r.mesh.0, tex)
-        });
-
         self.draw_batches.clear();
-        let mut cursor = 0usize;
-        while cursor < self.draw_order.len() {
-            let idx0 = self.draw_order[cursor] as usize;
-            let inst0 = self.instances[idx0];
-            let r0 = inst0.renderable;
-            let material = r0.material;
-            let mesh = r0.mesh;
-            let texture = inst0.texture;
-
-            let start = cursor;
-            cursor += 1;
-
-            while cursor < self.draw_order.len() {
-                let idx = self.draw_order[cursor] as usize;
-                let inst = self.instances[idx];
-                let r = inst.renderable;
-                if r.material == material && r.mesh == mesh && inst.texture == texture {
-                    cursor += 1;
-                } else {
-                    break;
-                }
-            }
-
+        for bucket in self.draw_buckets.values() {
+            let start = self.draw_order.len();
+            self.draw_order.extend(
+                bucket
+                    .iter()
+                    .copied()
+                    .filter(|&i| self.instances[i as usize].visibility_layers & mask != 0),
+            );
+            let Some(&first) = self.draw_order.get(start) else {
+                continue;
+            };
+            let inst = self.instances[first as usize];
+            let material = inst.renderable.material;
+            let blend = self.material_blend(material);
             self.draw_batches.push(DrawBatch {
                 material,
-                mesh,
-                texture,
+                mesh: inst.renderable.mesh,
+                texture: inst.texture,
+                blend,
+                clip: inst.clip,
+                z_order: inst.z_order,
                 start,
-                count: cursor - start,
+                count: self.draw_order.len() - start,
             });
         }
 
         self.dirty_draw_cache = false;
-        true
+        self.publish_draw_cache_stats();
+        self.draw_cache_stats
+    }
+
+    fn draw_key(&self, inst: &VisualInstance) -> DrawKey {
+        let r = inst.renderable;
+        let tex = inst.texture.map(|t| t.0).unwrap_or(u32::MAX);
+        let rank = self.material_blend(r.material).draw_rank();
+        (inst.z_order, rank, inst.clip, r.material.0, r.mesh.0, tex)
+    }
+
+    /// Sort every instance into `draw_buckets` from scratch. The keys are sorted in the frame
+    /// arena, with the instance index last so each bucket lists its instances in order.
+    fn rebuild_draw_buckets(&mut self) {
+        let mut keyed = self.frame_arena.vec_with_capacity(self.instances.len());
+        keyed.extend(
+            self.instances
+                .iter()
+                .enumerate()
+                .map(|(i, inst)| (self.draw_key(inst), i as u32)),
+        );
+        keyed.sort_unstable();
+        self.draw_buckets.clear();
+        self.draw_slots.clear();
+        self.draw_slots
+            .resize(self.instances.len(), DrawSlot::default());
+        for run in keyed.chunk_by(|a, b| a.0 == b.0) {
+            let key = run[0].0;
+            for (pos, &(_, i)) in run.iter().enumerate() {
+                self.draw_slots[i as usize] = DrawSlot {
+                    key,
+                    pos: pos as u32,
+                };
+            }
+            self.draw_buckets
+                .insert(key, run.iter().map(|&(_, i)| i).collect());
+        }
+        self.dirty_draw_keys = false;
+    }
+
+    /// Add instance `idx` to the bucket of its key (a new last instance gets a new slot).
+    fn bucket_insert(&mut self, idx: usize) {
+        let key = self.draw_key(&self.instances[idx]);
+        let bucket = self.draw_buckets.entry(key).or_default();
+        let slot = DrawSlot {
+            key,
+            pos: bucket.len() as u32,
+        };
+        bucket.push(idx as u32);
+        if idx == self.draw_slots.len() {
+            self.draw_slots.push(slot);
+        } else {
+            self.draw_slots[idx] = slot;
+        }
+    }
+
+    /// Take instance `idx` out of its bucket; its slot is left for the caller to reuse.
+    fn bucket_remove(&mut self, idx: usize) {
+        let DrawSlot { key, pos } = self.draw_slots[idx];
+        let Some(bucket) = self.draw_buckets.get_mut(&key) else {
+            return;
+        };
+        bucket.swap_remove(pos as usize);
+        if let Some(&moved) = bucket.get(pos as usize) {
+            self.draw_slots[moved as usize].pos = pos;
+        } else if bucket.is_empty() {
+            self.draw_buckets.remove(&key);
+        }
+    }
+
+    /// Move instance `idx` to the bucket of its current key, if that changed.
+    fn rekey(&mut self, idx: usize) {
+        if self.draw_key(&self.instances[idx]) != self.draw_slots[idx].key {
+            self.bucket_remove(idx);
+            self.bucket_insert(idx);
+            self.dirty_draw_cache = true;
+        }
+    }
+
+    fn publish_draw_cache_stats(&mut self) {
+        let mut state_changes = 0;
+        let mut previous: Option<&DrawBatch> = None;
+        for batch in &self.draw_batches {
+            let key = |b: &DrawBatch| (b.material, b.texture, b.blend);
+            if previous.is_none_or(|p| key(p) != key(batch)) {
+                state_changes += 1;
+            }
+            if previous.map_or(batch.clip.is_some(), |p| p.clip != batch.clip) {
+                state_changes += 1;
+            }
+            previous = Some(batch);
+        }
+        self.draw_cache_stats = DrawCacheStats {
+            instances: self.draw_order.len(),
+            batches: self.draw_batches.len(),
+            culled: self.instances.len() - self.draw_order.len(),
+            state_changes,
+            rebuilt: true,
+        };
+
+        let s = &self.draw_cache_stats;
+        profiling::set_gauge("draw.instances", s.instances as f64);
+        profiling::set_gauge("draw.batches", s.batches as f64);
+        profiling::set_gauge("draw.culled", s.culled as f64);
+        profiling::set_gauge("draw.state_changes", s.state_changes as f64);
+        profiling::count("draw.cache_rebuilds", 1);
     }
 
     pub fn register(
@@ -281,34 +800,57 @@ impl VisualWorld {
         let idx = self.instances.len();
         self.instances.push(VisualInstance {
             renderable,
+            prev_model: transform.model,
             transform,
             color,
             texture,
+            visibility_layers: DEFAULT_VISIBILITY_LAYERS,
+            clip: None,
+            z_order: 0,
         });
         self.handle_to_index.insert(handle, idx);
+        self.index_to_handle.push((handle, cid));
         self.component_to_handle.insert(cid, handle);
+        self.bucket_insert(idx);
 
         self.dirty_draw_cache = true;
         self.dirty_instance_data = true;
         handle
     }
 
+    /// Instance handle registered for a component (the `RenderableComponent` that owns it).
+    pub fn instance_for_component(&self, cid: ComponentId) -> Option<InstanceHandle> {
+        self.component_to_handle.get(&cid).copied()
+    }
+
+    /// Remove the instance registered for `cid`, if any.
+    pub fn remove_component(&mut self, cid: ComponentId) -> bool {
+        match self.component_to_handle.get(&cid).copied() {
+            Some(handle) => self.remove(handle),
+            None => false,
+        }
+    }
+
+    /// Remove an instance in O(1); the last instance moves into its slot.
     pub fn remove(&mut self, handle: InstanceHandle) -> bool {
         if let Some(idx) = self.handle_to_index.remove(&handle) {
+            self.bucket_remove(idx);
             self.instances.swap_remove(idx);
-
-            if idx < self.instances.len() {
-                // NOTE: This is O(n). Consider storing index->handle too if it becomes hot.
-                if let Some((moved_handle, _)) = self
-                    .handle_to_index
-                    .iter()
-                    .find(|(_, i)| **i == self.instances.len())
-                {
-                    self.handle_to_index.insert(*moved_handle, idx);
+            self.draw_slots.swap_remove(idx);
+            let (_, cid) = self.index_to_handle.swap_remove(idx);
+            if let Some(&(moved_handle, _)) = self.index_to_handle.get(idx) {
+                self.handle_to_index.insert(moved_handle, idx);
+                // Its bucket still lists the moved instance under its old index.
+                let DrawSlot { key, pos } = self.draw_slots[idx];
+                if let Some(bucket) = self.draw_buckets.get_mut(&key) {
+                    bucket[pos as usize] = idx as u32;
                 }
             }
 
-            self.component_to_handle.retain(|_, &mut h| h != handle);
+            // The component may have been re-registered under a newer handle since.
+            if self.component_to_handle.get(&cid) == Some(&handle) {
+                self.component_to_handle.remove(&cid);
+            }
 
             self.dirty_draw_cache = true;
             self.dirty_instance_data = true;
@@ -358,13 +900,75 @@ impl VisualWorld {
         if let Some(&idx) = self.handle_to_index.get(&handle) {
             self.instances[idx].texture = texture;
             // Texture affects batching (descriptor binding), but not instance vertex data.
-            self.dirty_draw_cache = true;
+            self.rekey(idx);
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn update_visibility_layers(&mut self, handle: InstanceHandle, mask: u32) -> bool {
+        if let Some(&idx) = self.handle_to_index.get(&handle) {
+            if self.instances[idx].visibility_layers != mask {
+                self.instances[idx].visibility_layers = mask;
+                // Layers decide membership in draw_order.
+                self.dirty_draw_cache = true;
+            }
             true
         } else {
             false
         }
     }
 
+    /// Set the 2D stacking order of `handle` (see `VisualInstance::z_order`).
+    pub fn update_z_order(&mut self, handle: InstanceHandle, z_order: i32) -> bool {
+        if let Some(&idx) = self.handle_to_index.get(&handle) {
+            if self.instances[idx].z_order != z_order {
+                self.instances[idx].z_order = z_order;
+                // Reorders draws and changes the per-instance depth bias.
+                self.rekey(idx);
+                self.dirty_instance_data = true;
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Clip `handle` to the rect of the `ClipRectComponent` `clip` (`None`: unclipped).
+    pub fn update_clip(&mut self, handle: InstanceHandle, clip: Option<ComponentId>) -> bool {
+        if let Some(&idx) = self.handle_to_index.get(&handle) {
+            if self.instances[idx].clip != clip {
+                self.instances[idx].clip = clip;
+                // Clips split batches.
+                self.rekey(idx);
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Screen rect of a `ClipRectComponent`, as last laid out by `ClipRectSystem`.
+    pub fn clip_rect(&self, clip: ComponentId) -> Option<ClipRect> {
+        self.clip_rects.get(&clip).copied()
+    }
+
+    pub fn set_clip_rect(&mut self, clip: ComponentId, rect: ClipRect) {
+        self.clip_rects.insert(clip, rect);
+    }
+
+    /// Forget `clip` and move its instances to `fallback` (the enclosing clip, if any).
+    pub fn remove_clip_rect(&mut self, clip: ComponentId, fallback: Option<ComponentId>) {
+        self.clip_rects.remove(&clip);
+        for idx in 0..self.instances.len() {
+            if self.instances[idx].clip == Some(clip) {
+                self.instances[idx].clip = fallback;
+                self.rekey(idx);
+            }
+        }
+    }
+
     pub fn update(
         &mut self,
         handle: InstanceHandle,
@@ -375,13 +979,21 @@ impl VisualWorld {
             // Preserve per-instance color when updating renderable/transform.
             let color = self.instances[idx].color;
             let texture = self.instances[idx].texture;
+            let visibility_layers = self.instances[idx].visibility_layers;
+            let clip = self.instances[idx].clip;
+            let z_order = self.instances[idx].z_order;
+            let prev_model = self.instances[idx].prev_model;
             self.instances[idx] = VisualInstance {
                 renderable,
                 transform,
+                prev_model,
                 color,
                 texture,
+                visibility_layers,
+                clip,
+                z_order,
             };
-            self.dirty_draw_cache = true; // renderable changes likely affect sort/batch
+            self.rekey(idx); // renderable changes likely affect sort/batch
             self.dirty_instance_data = true;
             true
         } else {
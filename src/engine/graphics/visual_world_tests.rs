@@ -0,0 +1,273 @@
+#[cfg(test)]
+mod tests {
+    use crate::engine::ecs::World;
+    use crate::engine::ecs::component::TransformComponent;
+    use crate::engine::graphics::primitives::MeshHandle;
+    use crate::engine::graphics::{
+        BlendMode, DEPTH_BIAS_RANGE, GpuRenderable, MAX_DEPTH_Z_ORDER, MaterialHandle,
+        TextureHandle, Transform, VisualWorld, z_order_depth_bias,
+    };
+
+    #[test]
+    fn blended_batches_draw_after_opaque_ones() {
+        let mut world = World::default();
+        let mut visuals = VisualWorld::new();
+        for material in [MaterialHandle::UNLIT_MESH, MaterialHandle::TOON_MESH] {
+            let cid = world.add_component(TransformComponent::new());
+            visuals.register(
+                cid,
+                GpuRenderable::new(MeshHandle::SQUARE, material),
+                Transform::default(),
+                [1.0; 4],
+                None,
+            );
+        }
+        let order = |visuals: &mut VisualWorld| {
+            visuals.prepare_draw_cache();
+            visuals
+                .draw_batches()
+                .iter()
+                .map(|b| (b.material, b.blend))
+                .collect::<Vec<_>>()
+        };
+
+        // Same group: material order.
+        assert_eq!(
+            order(&mut visuals),
+            vec![
+                (MaterialHandle::UNLIT_MESH, BlendMode::AlphaBlend),
+                (MaterialHandle::TOON_MESH, BlendMode::AlphaBlend),
+            ]
+        );
+
+        visuals.set_material_blend(MaterialHandle::UNLIT_MESH, BlendMode::Additive);
+        visuals.set_material_blend(MaterialHandle::TOON_MESH, BlendMode::Opaque);
+        assert_eq!(
+            order(&mut visuals),
+            vec![
+                (MaterialHandle::TOON_MESH, BlendMode::Opaque),
+                (MaterialHandle::UNLIT_MESH, BlendMode::Additive),
+            ]
+        );
+
+        let stats = visuals.draw_cache_stats();
+        assert_eq!(
+            (
+                stats.instances,
+                stats.batches,
+                stats.culled,
+                stats.state_changes
+            ),
+            (2, 2, 0, 2)
+        );
+        assert!(stats.rebuilt);
+        // Nothing changed: the cache is reused.
+        assert!(!visuals.prepare_draw_cache().rebuilt);
+    }
+
+    #[test]
+    fn z_order_overrides_material_order() {
+        let mut world = World::default();
+        let mut visuals = VisualWorld::new();
+        visuals.set_material_blend(MaterialHandle::TOON_MESH, BlendMode::Opaque);
+        visuals.set_material_blend(MaterialHandle::UNLIT_MESH, BlendMode::Additive);
+        let mut handles = Vec::new();
+        // An additive sprite registered first, then an opaque one.
+        for material in [MaterialHandle::UNLIT_MESH, MaterialHandle::TOON_MESH] {
+            let cid = world.add_component(TransformComponent::new());
+            handles.push(visuals.register(
+                cid,
+                GpuRenderable::new(MeshHandle::SQUARE, material),
+                Transform::default(),
+                [1.0; 4],
+                None,
+            ));
+        }
+        let order = |visuals: &mut VisualWorld| {
+            visuals.prepare_draw_cache();
+            visuals
+                .draw_batches()
+                .iter()
+                .map(|b| (b.material, b.z_order))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            order(&mut visuals),
+            vec![
+                (MaterialHandle::TOON_MESH, 0),
+                (MaterialHandle::UNLIT_MESH, 0)
+            ]
+        );
+
+        // Raising the opaque sprite puts it over the additive one.
+        assert!(visuals.update_z_order(handles[1], 3));
+        assert_eq!(
+            order(&mut visuals),
+            vec![
+                (MaterialHandle::UNLIT_MESH, 0),
+                (MaterialHandle::TOON_MESH, 3)
+            ]
+        );
+
+        assert!(z_order_depth_bias(3) > z_order_depth_bias(0));
+        assert_eq!(z_order_depth_bias(0), 0.0);
+        assert_eq!(z_order_depth_bias(MAX_DEPTH_Z_ORDER * 4), DEPTH_BIAS_RANGE);
+        assert_eq!(z_order_depth_bias(i32::MIN), -DEPTH_BIAS_RANGE);
+    }
+
+    #[test]
+    fn end_frame_keeps_models_for_motion_vectors() {
+        let mut world = World::default();
+        let mut visuals = VisualWorld::new();
+        let cid = world.add_component(TransformComponent::new());
+        let handle = visuals.register(
+            cid,
+            GpuRenderable::new(MeshHandle::SQUARE, MaterialHandle::TOON_MESH),
+            Transform::default(),
+            [1.0; 4],
+            None,
+        );
+        let start = Transform::default().model;
+        let mut moved = start;
+        moved[3][0] = 2.0;
+
+        // Until the frame ends, the previous model is the one the instance was drawn with.
+        visuals.update_model(handle, moved);
+        assert_eq!(visuals.instance(handle).unwrap().prev_model, start);
+
+        visuals.end_frame();
+        assert_eq!(visuals.instance(handle).unwrap().prev_model, moved);
+    }
+
+    #[test]
+    fn removing_thousands_of_instances_per_frame_keeps_the_maps_consistent() {
+        let mut world = World::default();
+        let mut visuals = VisualWorld::new();
+        // (handle, component, id stored in the color) of every live instance.
+        let mut live = Vec::new();
+        let mut next_id = 0u32;
+        let mut register = |world: &mut World, visuals: &mut VisualWorld, live: &mut Vec<_>| {
+            let cid = world.add_component(TransformComponent::new());
+            let handle = visuals.register(
+                cid,
+                GpuRenderable::new(MeshHandle::SQUARE, MaterialHandle::TOON_MESH),
+                Transform::default(),
+                [next_id as f32, 0.0, 0.0, 1.0],
+                None,
+            );
+            live.push((handle, cid, next_id));
+            next_id += 1;
+        };
+        for _ in 0..20_000 {
+            register(&mut world, &mut visuals, &mut live);
+        }
+
+        let mut seed = 0x2545_f491u32;
+        for _ in 0..10 {
+            let mut removed = Vec::new();
+            for _ in 0..2_500 {
+                seed ^= seed << 13;
+                seed ^= seed >> 17;
+                seed ^= seed << 5;
+                let (handle, cid, _) = live.swap_remove(seed as usize % live.len());
+                assert!(visuals.remove(handle));
+                removed.push((handle, cid));
+            }
+            for _ in 0..1_000 {
+                register(&mut world, &mut visuals, &mut live);
+            }
+            visuals.end_frame();
+
+            assert_eq!(visuals.instances().len(), live.len());
+            for &(handle, cid, id) in &live {
+                assert_eq!(visuals.instance(handle).unwrap().color[0], id as f32);
+                assert_eq!(visuals.instance_for_component(cid), Some(handle));
+            }
+            for (handle, cid) in removed {
+                assert!(visuals.instance(handle).is_none());
+                assert_eq!(visuals.instance_for_component(cid), None);
+                assert!(!visuals.remove(handle));
+            }
+        }
+        assert_eq!(live.len(), 5_000);
+    }
+
+    #[test]
+    fn incremental_draw_cache_matches_a_full_rebuild() {
+        // Batches, each with its instances sorted (order within a batch is unspecified).
+        fn snapshot(visuals: &mut VisualWorld) -> Vec<String> {
+            visuals.prepare_draw_cache();
+            visuals
+                .draw_batches()
+                .iter()
+                .map(|b| {
+                    let mut ids = visuals.draw_order()[b.start..b.start + b.count].to_vec();
+                    ids.sort_unstable();
+                    format!(
+                        "{:?} {:?} {:?} {:?} {:?} {} {ids:?}",
+                        b.material, b.mesh, b.texture, b.blend, b.clip, b.z_order
+                    )
+                })
+                .collect()
+        }
+
+        let mut world = World::default();
+        let mut visuals = VisualWorld::new();
+        let clip = world.add_component(TransformComponent::new());
+        let meshes = [MeshHandle::SQUARE, MeshHandle::CUBE];
+        let materials = [MaterialHandle::UNLIT_MESH, MaterialHandle::TOON_MESH];
+        let mut live = Vec::new();
+        let mut seed = 0x9e37_79b9u32;
+        let mut next = || {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            seed as usize
+        };
+        for step in 0..2_000 {
+            match next() % 8 {
+                0..=2 => {
+                    let cid = world.add_component(TransformComponent::new());
+                    let renderable = GpuRenderable::new(meshes[next() % 2], materials[next() % 2]);
+                    live.push(visuals.register(
+                        cid,
+                        renderable,
+                        Transform::default(),
+                        [1.0; 4],
+                        None,
+                    ));
+                }
+                3 if !live.is_empty() => {
+                    let handle = live.swap_remove(next() % live.len());
+                    visuals.remove(handle);
+                }
+                4 if !live.is_empty() => {
+                    let handle = live[next() % live.len()];
+                    visuals.update_z_order(handle, (next() % 3) as i32 - 1);
+                }
+                5 if !live.is_empty() => {
+                    let handle = live[next() % live.len()];
+                    let texture = (next() % 3 > 0).then(|| TextureHandle((next() % 2) as u32));
+                    visuals.update_texture(handle, texture);
+                }
+                6 if !live.is_empty() => {
+                    let handle = live[next() % live.len()];
+                    visuals.update_clip(handle, (next() % 2 == 0).then_some(clip));
+                }
+                7 => {
+                    let blend = BlendMode::ALL[next() % BlendMode::ALL.len()];
+                    visuals.set_material_blend(materials[next() % 2], blend);
+                }
+                _ => {}
+            }
+            if step % 50 == 0 {
+                visuals.remove_clip_rect(clip, None);
+            }
+
+            let incremental = snapshot(&mut visuals);
+            visuals.invalidate_draw_cache();
+            assert_eq!(incremental, snapshot(&mut visuals), "step {step}");
+        }
+        assert!(!live.is_empty());
+    }
+}
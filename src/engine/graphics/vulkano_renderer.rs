@@ -1,41 +1,60 @@
+use crate::engine::graphics::DEFAULT_FRAMES_IN_FLIGHT;
 use crate::engine::graphics::MeshUploader;
+use crate::engine::graphics::RendererError;
 use crate::engine::graphics::TextureUploader;
+use crate::engine::graphics::gpu_memory::{GpuMemoryCategory, GpuMemoryUsage};
 use crate::engine::graphics::mesh::CpuMesh;
 use crate::engine::graphics::primitives::MeshHandle;
-use crate::engine::graphics::primitives::TextureHandle;
+use crate::engine::graphics::primitives::{TextureHandle, TextureKind};
 use crate::engine::graphics::visual_world::VisualWorld;
+use crate::engine::graphics::{GenerationalHandle, HandleTable};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use winit::window::Window;
 
 mod vulkano_backend {
     use std::collections::HashMap;
     use std::mem::size_of;
+    use std::ops::Range;
     use std::sync::Arc;
 
+    use crate::engine::graphics::RendererError;
+    use crate::engine::graphics::gpu_memory::{GpuAllocations, GpuMemoryCategory, GpuMemoryUsage};
     use crate::engine::graphics::mesh::{CpuMesh, CpuVertex};
     use crate::engine::graphics::pipeline_descriptor_set_layouts::PipelineDescriptorSetLayouts;
     use crate::engine::graphics::primitives::MeshHandle;
-    use crate::engine::graphics::primitives::TextureHandle;
-    use crate::engine::graphics::visual_world::VisualWorld;
+    use crate::engine::graphics::primitives::{
+        BlendMode, CullMode, Culling, FrontFace, TextureHandle, TextureKind,
+    };
+    use crate::engine::graphics::range_allocator::RangeAllocator;
+    use crate::engine::graphics::render_graph::RenderGraph;
+    use crate::engine::graphics::visual_world::{VisualBackground, VisualInstance, VisualWorld};
+    use crate::engine::graphics::{BloomSettings, Letterbox, PostProcessSettings};
+    use glam::Mat4;
+    use rayon::prelude::*;
     use vulkano::buffer::{Buffer, BufferContents, BufferCreateInfo, BufferUsage, Subbuffer};
     use vulkano::command_buffer::{
-        AutoCommandBufferBuilder, CommandBufferUsage, CopyBufferInfo, PrimaryCommandBufferAbstract,
-        RenderPassBeginInfo, SubpassBeginInfo, SubpassEndInfo,
-        allocator::StandardCommandBufferAllocator,
+        AutoCommandBufferBuilder, BlitImageInfo, CommandBufferUsage, CopyBufferInfo,
+        PrimaryAutoCommandBuffer, PrimaryCommandBufferAbstract, RenderPassBeginInfo,
+        SubpassBeginInfo, SubpassEndInfo, allocator::StandardCommandBufferAllocator,
     };
     use vulkano::descriptor_set::allocator::StandardDescriptorSetAllocator;
+    use vulkano::descriptor_set::layout::DescriptorSetLayout;
     use vulkano::descriptor_set::{DescriptorSet, WriteDescriptorSet};
     use vulkano::format::ClearValue;
     use vulkano::image::view::ImageView;
-    use vulkano::image::{Image, ImageCreateInfo, ImageType, ImageUsage};
+    use vulkano::image::{Image, ImageCreateFlags, ImageCreateInfo, ImageType, ImageUsage};
     use vulkano::memory::allocator::{AllocationCreateInfo, MemoryTypeFilter};
+    use vulkano::pipeline::graphics::GraphicsPipelineCreateInfo;
     use vulkano::pipeline::graphics::color_blend::{
         AttachmentBlend, BlendFactor, BlendOp, ColorBlendAttachmentState, ColorBlendState,
         ColorComponents,
     };
     use vulkano::pipeline::graphics::input_assembly::InputAssemblyState;
     use vulkano::pipeline::graphics::multisample::MultisampleState;
-    use vulkano::pipeline::graphics::rasterization::RasterizationState;
+    use vulkano::pipeline::graphics::rasterization::{
+        CullMode as VkCullMode, FrontFace as VkFrontFace, RasterizationState,
+    };
     use vulkano::pipeline::graphics::subpass::PipelineSubpassType;
     use vulkano::pipeline::graphics::vertex_input::{
         VertexInputAttributeDescription, VertexInputBindingDescription, VertexInputRate,
@@ -45,14 +64,19 @@ mod vulkano_backend {
     use vulkano::pipeline::layout::{PipelineLayout, PipelineLayoutCreateInfo};
 
     use vulkano::DeviceSize;
-    use vulkano::command_buffer::CopyBufferToImageInfo;
+    use vulkano::command_buffer::{CopyBufferToImageInfo, CopyImageToBufferInfo};
     use vulkano::format::Format;
-    use vulkano::image::sampler::{Sampler, SamplerCreateInfo};
+    use vulkano::format::NumericFormat;
+    use vulkano::image::sampler::{Filter, Sampler, SamplerAddressMode, SamplerCreateInfo};
+    use vulkano::image::view::{ImageViewCreateInfo, ImageViewType};
+    use vulkano::pipeline::layout::PushConstantRange;
     use vulkano::pipeline::{
         DynamicState, GraphicsPipeline, Pipeline, PipelineBindPoint, PipelineShaderStageCreateInfo,
     };
     use vulkano::render_pass::{Framebuffer, FramebufferCreateInfo, RenderPass, Subpass};
+    use vulkano::shader::ShaderStages;
     use vulkano::swapchain::{self, Surface, Swapchain, SwapchainCreateInfo, SwapchainPresentInfo};
+    use vulkano::sync::future::FenceSignalFuture;
     use vulkano::sync::{self, GpuFuture};
     use vulkano::{Validated, VulkanError};
     use vulkano_util::context::{VulkanoConfig, VulkanoContext};
@@ -72,6 +96,65 @@ mod vulkano_backend {
         }
     }
 
+    mod outline_vs {
+        vulkano_shaders::shader! {
+            ty: "vertex",
+            path: "src/engine/graphics/shaders/outline.vert",
+        }
+    }
+
+    mod outline_fs {
+        vulkano_shaders::shader! {
+            ty: "fragment",
+            path: "src/engine/graphics/shaders/outline.frag",
+        }
+    }
+
+    mod fullscreen_vs {
+        vulkano_shaders::shader! {
+            ty: "vertex",
+            path: "src/engine/graphics/shaders/fullscreen.vert",
+        }
+    }
+
+    mod tonemap_fs {
+        vulkano_shaders::shader! {
+            ty: "fragment",
+            path: "src/engine/graphics/shaders/tonemap.frag",
+        }
+    }
+
+    mod background_fs {
+        vulkano_shaders::shader! {
+            ty: "fragment",
+            path: "src/engine/graphics/shaders/background.frag",
+        }
+    }
+
+    mod bloom_fs {
+        vulkano_shaders::shader! {
+            ty: "fragment",
+            path: "src/engine/graphics/shaders/bloom.frag",
+        }
+    }
+
+    mod taa_fs {
+        vulkano_shaders::shader! {
+            ty: "fragment",
+            path: "src/engine/graphics/shaders/taa.frag",
+        }
+    }
+
+    /// Format the scene is lit and blended in before tonemapping.
+    const HDR_FORMAT: Format = Format::R16G16B16A16_SFLOAT;
+
+    /// Format of the motion vectors the scene pass writes.
+    const MOTION_FORMAT: Format = Format::R16G16_SFLOAT;
+
+    /// Color attachments of the scene pass: the lit scene, what emissive materials drew and
+    /// motion vectors.
+    const SCENE_ATTACHMENTS: u32 = 3;
+
     #[derive(BufferContents, Clone, Copy, Debug, Default)]
     #[repr(C, align(16))]
     pub struct CameraUBO {
@@ -82,6 +165,13 @@ mod vulkano_backend {
         // Swapchain size in pixels (width, height). Used for aspect correction in 2D.
         pub viewport: [f32; 2],
         pub _pad0: [f32; 2],
+        // `VisualWorld::previous_camera`, for motion vectors.
+        pub prev_view: [[f32; 4]; 4],
+        pub prev_proj: [[f32; 4]; 4],
+        pub prev_camera2d: [[f32; 4]; 3],
+        // TAA sub-pixel offset in NDC.
+        pub jitter: [f32; 2],
+        pub _pad1: [f32; 2],
     }
 
     #[derive(BufferContents, Clone, Copy, Debug, Default)]
@@ -90,7 +180,73 @@ mod vulkano_backend {
         base_color: [f32; 4],
         quant_steps: f32,
         emissive: u32,
-        _pad0: [u32; 2],
+        /// Mix towards the reflection cubemap (`VisualWorld::material_reflection`).
+        reflectivity: f32,
+        _pad0: u32,
+    }
+
+    #[derive(BufferContents, Clone, Copy, Debug)]
+    #[repr(C)]
+    struct OutlinePushConstants {
+        color: [f32; 4],
+        width: f32,
+    }
+
+    #[derive(BufferContents, Clone, Copy, Debug)]
+    #[repr(C)]
+    struct TonemapPushConstants {
+        exposure: f32,
+        encode_srgb: u32,
+        lut_size: f32,
+        lut_strength: f32,
+        bloom_intensity: f32,
+    }
+
+    const BLOOM_BRIGHT_PASS: u32 = 0;
+    const BLOOM_BLUR: u32 = 1;
+
+    #[derive(BufferContents, Clone, Copy, Debug)]
+    #[repr(C)]
+    struct BloomPushConstants {
+        texel: [f32; 2],
+        threshold: f32,
+        mode: u32,
+    }
+
+    /// Share of the reprojected history in each TAA resolve.
+    const TAA_HISTORY_WEIGHT: f32 = 0.9;
+
+    /// Jitter pattern of TAA: the first points of the Halton (2, 3) sequence, in pixels.
+    const TAA_JITTER: [[f32; 2]; 8] = [
+        [0.0, -1.0 / 6.0],
+        [-0.25, 1.0 / 6.0],
+        [0.25, -7.0 / 18.0],
+        [-0.375, -1.0 / 18.0],
+        [0.125, 5.0 / 18.0],
+        [-0.125, -5.0 / 18.0],
+        [0.375, 1.0 / 18.0],
+        [-0.4375, 7.0 / 18.0],
+    ];
+
+    #[derive(BufferContents, Clone, Copy, Debug)]
+    #[repr(C)]
+    struct TaaPushConstants {
+        texel: [f32; 2],
+        history_weight: f32,
+    }
+
+    const BACKGROUND_GRADIENT: u32 = 0;
+    const BACKGROUND_SKYBOX: u32 = 1;
+
+    #[derive(BufferContents, Clone, Copy, Debug)]
+    #[repr(C)]
+    struct BackgroundPushConstants {
+        inv_view_proj: [[f32; 4]; 4],
+        color_start: [f32; 4],
+        color_end: [f32; 4],
+        direction: [f32; 2],
+        mode: u32,
+        _pad0: u32,
     }
 
     #[derive(
@@ -113,19 +269,782 @@ mod vulkano_backend {
         pub i_model_c3: [f32; 4],
         #[format(R32G32B32A32_SFLOAT)]
         pub i_color: [f32; 4],
+        /// `z_order_depth_bias` of the instance's z-order.
+        #[format(R32_SFLOAT)]
+        pub i_depth_bias: f32,
+        /// `VisualInstance::prev_model`, for motion vectors.
+        #[format(R32G32B32A32_SFLOAT)]
+        pub i_prev_model_c0: [f32; 4],
+        #[format(R32G32B32A32_SFLOAT)]
+        pub i_prev_model_c1: [f32; 4],
+        #[format(R32G32B32A32_SFLOAT)]
+        pub i_prev_model_c2: [f32; 4],
+        #[format(R32G32B32A32_SFLOAT)]
+        pub i_prev_model_c3: [f32; 4],
     }
 
+    impl InstanceData {
+        fn pack(inst: &VisualInstance) -> Self {
+            let m = inst.transform.model;
+            Self {
+                i_model_c0: m[0],
+                i_model_c1: m[1],
+                i_model_c2: m[2],
+                i_model_c3: m[3],
+                i_color: inst.color,
+                i_depth_bias: crate::engine::graphics::z_order_depth_bias(inst.z_order),
+                i_prev_model_c0: inst.prev_model[0],
+                i_prev_model_c1: inst.prev_model[1],
+                i_prev_model_c2: inst.prev_model[2],
+                i_prev_model_c3: inst.prev_model[3],
+            }
+        }
+    }
+
+    /// Where a mesh lives inside the `MeshArena`.
     pub struct VulkanoGpuMesh {
-        #[allow(dead_code)]
+        /// Added to every index (`vertexOffset` of the indexed draw).
+        pub vertex_offset: i32,
+        pub vertex_count: u32,
+        pub first_index: u32,
+        pub index_count: u32,
+    }
+
+    /// One device-local vertex buffer and one index buffer shared by every mesh, so batches
+    /// that only differ by mesh draw without rebinding buffers.
+    ///
+    /// Meshes go into the first free range that fits; when none does, the buffers are
+    /// replaced by ones twice as large and the contents are copied over. Released meshes give
+    /// their ranges back, but only once the frames in flight that may still draw them have
+    /// finished (`retired` until then).
+    pub struct MeshArena {
         pub vertices: Subbuffer<[CpuVertex]>,
-        #[allow(dead_code)]
         pub indices: Subbuffer<[u32]>,
-        #[allow(dead_code)]
-        pub index_count: u32,
+        vertex_ranges: RangeAllocator,
+        index_ranges: RangeAllocator,
+        /// Vertex and index ranges of released meshes, not reusable yet.
+        retired: Vec<(Range<DeviceSize>, Range<DeviceSize>)>,
+    }
+
+    impl MeshArena {
+        /// Starts of free vertex and index ranges of these lengths, if both fit.
+        fn alloc(
+            &mut self,
+            vertices: DeviceSize,
+            indices: DeviceSize,
+        ) -> Option<(DeviceSize, DeviceSize)> {
+            let first_vertex = self.vertex_ranges.alloc(vertices)?;
+            match self.index_ranges.alloc(indices) {
+                Some(first_index) => Some((first_vertex, first_index)),
+                None => {
+                    self.vertex_ranges
+                        .free(first_vertex..first_vertex + vertices);
+                    None
+                }
+            }
+        }
+
+        /// Make retired ranges free; no frame may still be reading them.
+        fn reclaim_retired(&mut self) {
+            for (vertices, indices) in self.retired.drain(..) {
+                self.vertex_ranges.free(vertices);
+                self.index_ranges.free(indices);
+            }
+        }
+    }
+
+    type FrameFence = Arc<FenceSignalFuture<Box<dyn GpuFuture + Send + Sync>>>;
+
+    /// Instances the per-frame instance buffer holds before it first grows.
+    const INITIAL_INSTANCE_CAPACITY: DeviceSize = 1024;
+
+    /// Below this many instances packing stays on the render thread; rayon's fork/join costs
+    /// more than it saves.
+    const PARALLEL_PACK_MIN_INSTANCES: usize = 4096;
+
+    /// Instances one rayon task packs.
+    const PACK_CHUNK: usize = 1024;
+
+    /// Everything a frame writes or records, duplicated per frame in flight so the CPU
+    /// prepares frame N+1 while the GPU still renders frame N, and never writes what the GPU
+    /// is reading. Buffers are written in place; only the instance buffer is reallocated, when
+    /// it must grow.
+    pub struct FrameSlot {
+        instances: Subbuffer<[InstanceData]>,
+        camera: Subbuffer<CameraUBO>,
+        lights: Subbuffer<LightsSSBO>,
+        /// Camera + lights (set 0); the buffers never change, so neither does the set.
+        global_set: Arc<DescriptorSet>,
+        command_buffer_allocator: Arc<StandardCommandBufferAllocator>,
+        /// Signaled when the GPU finished the last frame recorded with this slot.
+        fence: Option<FrameFence>,
+        /// Scene target at the scaled render extent; created by the first frame.
+        hdr: Option<HdrTarget>,
+        /// Bloom targets at half the scene extent, created by the first frame with bloom on.
+        /// The bright pass writes `images[0]`, the horizontal blur `images[1]` and the vertical
+        /// blur `images[0]` again, which the tonemap pass then adds.
+        bloom: Option<PingPongTargets>,
+        /// Average scene color (RGBA16F) copied from the last mip of `hdr`, read back once
+        /// the slot's fence signaled.
+        luminance: Subbuffer<[u16; 4]>,
+        /// `luminance` holds a frame the CPU has not read yet.
+        luminance_pending: bool,
+    }
+
+    /// Linear HDR scene image. Mip 0 is rendered to and tonemapped; the other mips are a
+    /// downsample chain down to 1x1 that averages the frame for auto exposure.
+    struct HdrTarget {
+        image: Arc<Image>,
+        /// What emissive materials drew this frame (second scene attachment), the bloom source.
+        emissive: Arc<Image>,
+        framebuffer: Arc<Framebuffer>,
+        /// Tonemap pass input (set 0).
+        post_set: Arc<DescriptorSet>,
+        /// `emissive`, sampled by the bloom bright pass.
+        emissive_set: Arc<DescriptorSet>,
+        /// Per-pixel UV movement since the previous frame (third scene attachment).
+        motion: Arc<Image>,
+        /// `motion`, sampled by the TAA resolve.
+        motion_set: Arc<DescriptorSet>,
+    }
+
+    /// Two single-level HDR targets of a fullscreen pass that reads one and writes the other.
+    struct PingPongTargets {
+        images: [Arc<Image>; 2],
+        framebuffers: [Arc<Framebuffer>; 2],
+        /// `images[i]` sampled by the next pass.
+        sets: [Arc<DescriptorSet>; 2],
+    }
+
+    impl PingPongTargets {
+        fn new(
+            context: &VulkanoContext,
+            render_pass: &Arc<RenderPass>,
+            descriptor_set_allocator: &Arc<StandardDescriptorSetAllocator>,
+            post_layout: &Arc<DescriptorSetLayout>,
+            sampler: &Arc<Sampler>,
+            extent: [u32; 2],
+        ) -> Result<Self, Box<dyn std::error::Error>> {
+            let images = [
+                sampled_attachment(context, HDR_FORMAT, extent)?,
+                sampled_attachment(context, HDR_FORMAT, extent)?,
+            ];
+            let mut framebuffers = Vec::with_capacity(2);
+            let mut sets = Vec::with_capacity(2);
+            for image in &images {
+                let view = ImageView::new_default(image.clone())?;
+                framebuffers.push(Framebuffer::new(
+                    render_pass.clone(),
+                    FramebufferCreateInfo {
+                        attachments: vec![view.clone()],
+                        ..Default::default()
+                    },
+                )?);
+                sets.push(DescriptorSet::new(
+                    descriptor_set_allocator.clone(),
+                    post_layout.clone(),
+                    [WriteDescriptorSet::image_view_sampler(
+                        0,
+                        view,
+                        sampler.clone(),
+                    )],
+                    [],
+                )?);
+            }
+            let [fb0, fb1] = <[_; 2]>::try_from(framebuffers).expect("two framebuffers");
+            let [set0, set1] = <[_; 2]>::try_from(sets).expect("two sets");
+            Ok(Self {
+                images,
+                framebuffers: [fb0, fb1],
+                sets: [set0, set1],
+            })
+        }
+
+        fn extent(&self) -> [u32; 2] {
+            let [w, h, _] = self.images[0].extent();
+            [w, h]
+        }
+
+        fn bytes(&self) -> u64 {
+            let [w, h] = self.extent();
+            2 * u64::from(w) * u64::from(h) * HDR_FORMAT.block_size()
+        }
+    }
+
+    /// Resolved frames of temporal AA. The resolve pass reads `targets` at `latest` and
+    /// writes the other one, which becomes `latest`.
+    struct TaaHistory {
+        targets: PingPongTargets,
+        latest: usize,
+        /// `latest` holds a resolved frame (false right after creation).
+        valid: bool,
+    }
+
+    impl FrameSlot {
+        fn new(
+            context: &VulkanoContext,
+            descriptor_set_allocator: &Arc<StandardDescriptorSetAllocator>,
+            set_layouts: &PipelineDescriptorSetLayouts,
+        ) -> Result<Self, Box<dyn std::error::Error>> {
+            let host_write = || AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                    | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                ..Default::default()
+            };
+            let allocator = context.memory_allocator().clone();
+            let instances = Buffer::new_slice::<InstanceData>(
+                allocator.clone(),
+                BufferCreateInfo {
+                    usage: BufferUsage::VERTEX_BUFFER,
+                    ..Default::default()
+                },
+                host_write(),
+                INITIAL_INSTANCE_CAPACITY,
+            )?;
+            let camera = Buffer::new_sized::<CameraUBO>(
+                allocator.clone(),
+                BufferCreateInfo {
+                    usage: BufferUsage::UNIFORM_BUFFER,
+                    ..Default::default()
+                },
+                host_write(),
+            )?;
+            let lights = Buffer::new_sized::<LightsSSBO>(
+                allocator.clone(),
+                BufferCreateInfo {
+                    usage: BufferUsage::STORAGE_BUFFER,
+                    ..Default::default()
+                },
+                host_write(),
+            )?;
+            let luminance = Buffer::new_sized::<[u16; 4]>(
+                allocator,
+                BufferCreateInfo {
+                    usage: BufferUsage::TRANSFER_DST,
+                    ..Default::default()
+                },
+                AllocationCreateInfo {
+                    memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                        | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+                    ..Default::default()
+                },
+            )?;
+            let global_set = DescriptorSet::new(
+                descriptor_set_allocator.clone(),
+                set_layouts.global.clone(),
+                [
+                    WriteDescriptorSet::buffer(0, camera.clone()),
+                    WriteDescriptorSet::buffer(1, lights.clone()),
+                ],
+                [],
+            )?;
+            let command_buffer_allocator = Arc::new(StandardCommandBufferAllocator::new(
+                context.device().clone(),
+                Default::default(),
+            ));
+            Ok(Self {
+                instances,
+                camera,
+                lights,
+                global_set,
+                command_buffer_allocator,
+                fence: None,
+                hdr: None,
+                bloom: None,
+                luminance,
+                luminance_pending: false,
+            })
+        }
+
+        /// HDR target of `extent` pixels, (re)created if the render extent changed.
+        fn hdr_target(
+            &mut self,
+            context: &VulkanoContext,
+            render_pass: &Arc<RenderPass>,
+            descriptor_set_allocator: &Arc<StandardDescriptorSetAllocator>,
+            set_layouts: &PipelineDescriptorSetLayouts,
+            sampler: &Arc<Sampler>,
+            extent: [u32; 2],
+        ) -> Result<&HdrTarget, Box<dyn std::error::Error>> {
+            let stale = self
+                .hdr
+                .as_ref()
+                .is_none_or(|hdr| hdr.image.extent() != [extent[0], extent[1], 1]);
+            if stale {
+                let image = Image::new(
+                    context.memory_allocator().clone(),
+                    ImageCreateInfo {
+                        image_type: ImageType::Dim2d,
+                        format: HDR_FORMAT,
+                        extent: [extent[0], extent[1], 1],
+                        mip_levels: mip_levels(extent),
+                        usage: ImageUsage::COLOR_ATTACHMENT
+                            | ImageUsage::SAMPLED
+                            | ImageUsage::TRANSFER_SRC
+                            | ImageUsage::TRANSFER_DST,
+                        ..Default::default()
+                    },
+                    AllocationCreateInfo {
+                        memory_type_filter: MemoryTypeFilter::PREFER_DEVICE,
+                        ..Default::default()
+                    },
+                )?;
+                let mut view_info = ImageViewCreateInfo::from_image(&image);
+                view_info.subresource_range.mip_levels = 0..1;
+                let view = ImageView::new(image.clone(), view_info)?;
+                let emissive = sampled_attachment(context, HDR_FORMAT, extent)?;
+                let emissive_view = ImageView::new_default(emissive.clone())?;
+                let motion = sampled_attachment(context, MOTION_FORMAT, extent)?;
+                let motion_view = ImageView::new_default(motion.clone())?;
+                let framebuffer = Framebuffer::new(
+                    render_pass.clone(),
+                    FramebufferCreateInfo {
+                        attachments: vec![view.clone(), emissive_view.clone(), motion_view.clone()],
+                        ..Default::default()
+                    },
+                )?;
+                let post_set = DescriptorSet::new(
+                    descriptor_set_allocator.clone(),
+                    set_layouts.post.clone(),
+                    [WriteDescriptorSet::image_view_sampler(
+                        0,
+                        view,
+                        sampler.clone(),
+                    )],
+                    [],
+                )?;
+                let emissive_set = DescriptorSet::new(
+                    descriptor_set_allocator.clone(),
+                    set_layouts.post.clone(),
+                    [WriteDescriptorSet::image_view_sampler(
+                        0,
+                        emissive_view,
+                        sampler.clone(),
+                    )],
+                    [],
+                )?;
+                let motion_set = DescriptorSet::new(
+                    descriptor_set_allocator.clone(),
+                    set_layouts.post.clone(),
+                    [WriteDescriptorSet::image_view_sampler(
+                        0,
+                        motion_view,
+                        sampler.clone(),
+                    )],
+                    [],
+                )?;
+                self.hdr = Some(HdrTarget {
+                    image,
+                    emissive,
+                    framebuffer,
+                    post_set,
+                    emissive_set,
+                    motion,
+                    motion_set,
+                });
+            }
+            Ok(self.hdr.as_ref().expect("created above"))
+        }
+
+        /// Bloom targets at half of `extent`, recreated when it changed.
+        fn bloom_targets(
+            &mut self,
+            context: &VulkanoContext,
+            bloom_pass: &Arc<RenderPass>,
+            descriptor_set_allocator: &Arc<StandardDescriptorSetAllocator>,
+            post_layout: &Arc<DescriptorSetLayout>,
+            sampler: &Arc<Sampler>,
+            extent: [u32; 2],
+        ) -> Result<&PingPongTargets, Box<dyn std::error::Error>> {
+            let half = bloom_extent(extent);
+            if self
+                .bloom
+                .as_ref()
+                .is_none_or(|bloom| bloom.extent() != half)
+            {
+                self.bloom = Some(PingPongTargets::new(
+                    context,
+                    bloom_pass,
+                    descriptor_set_allocator,
+                    post_layout,
+                    sampler,
+                    half,
+                )?);
+            }
+            Ok(self.bloom.as_ref().expect("created above"))
+        }
+
+        /// Average linear luminance of the last frame rendered with this slot, if it was not
+        /// read yet. The slot's fence must have signaled.
+        fn take_luminance(&mut self) -> Result<Option<f32>, Box<dyn std::error::Error>> {
+            if !std::mem::take(&mut self.luminance_pending) {
+                return Ok(None);
+            }
+            let [r, g, b, _] = self.luminance.read()?.map(f16_to_f32);
+            Ok(Some(0.2126 * r + 0.7152 * g + 0.0722 * b))
+        }
+
+        /// Write `data` to the instance buffer, reallocating it (doubled) if too small.
+        /// Pack `instances` in `draw_order` into this slot's mapped instance buffer, so each
+        /// `DrawBatch` maps to a contiguous range. Large scenes are packed in parallel.
+        fn write_instances(
+            &mut self,
+            context: &VulkanoContext,
+            draw_order: &[u32],
+            instances: &[VisualInstance],
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            let count = draw_order.len() as DeviceSize;
+            if count > self.instances.len() {
+                let mut capacity = self.instances.len().max(INITIAL_INSTANCE_CAPACITY);
+                while capacity < count {
+                    capacity *= 2;
+                }
+                self.instances = Buffer::new_slice::<InstanceData>(
+                    context.memory_allocator().clone(),
+                    BufferCreateInfo {
+                        usage: BufferUsage::VERTEX_BUFFER,
+                        ..Default::default()
+                    },
+                    AllocationCreateInfo {
+                        memory_type_filter: MemoryTypeFilter::PREFER_HOST
+                            | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                        ..Default::default()
+                    },
+                    capacity,
+                )?;
+            }
+            let _span = crate::utils::trace::span("renderer.pack");
+            let mut dst = self.instances.write()?;
+            let dst = &mut dst[..draw_order.len()];
+            let pack = |dst: &mut [InstanceData], order: &[u32]| {
+                for (slot, &idx) in dst.iter_mut().zip(order) {
+                    *slot = InstanceData::pack(&instances[idx as usize]);
+                }
+            };
+            if draw_order.len() < PARALLEL_PACK_MIN_INSTANCES {
+                pack(dst, draw_order);
+            } else {
+                dst.par_chunks_mut(PACK_CHUNK)
+                    .zip(draw_order.par_chunks(PACK_CHUNK))
+                    .for_each(|(dst, order)| pack(dst, order));
+            }
+            Ok(())
+        }
+
+        fn bytes(&self) -> u64 {
+            let hdr = self.hdr.as_ref().map_or(0, |hdr| {
+                let [w, h, _] = hdr.image.extent();
+                (0..hdr.image.mip_levels())
+                    .map(|level| {
+                        let [w, h, _] = mip_extent([w, h], level);
+                        u64::from(w) * u64::from(h)
+                    })
+                    .sum::<u64>()
+                    * HDR_FORMAT.block_size()
+            });
+            let single_level = |image: &Arc<Image>| {
+                let [w, h, _] = image.extent();
+                u64::from(w) * u64::from(h) * image.format().block_size()
+            };
+            let attachments = self.hdr.as_ref().map_or(0, |hdr| {
+                single_level(&hdr.emissive) + single_level(&hdr.motion)
+            });
+            let bloom = self.bloom.as_ref().map_or(0, PingPongTargets::bytes);
+            self.instances.size()
+                + self.camera.size()
+                + self.lights.size()
+                + self.luminance.size()
+                + hdr
+                + attachments
+                + bloom
+        }
+
+        /// Buffers and images counted by `bytes`.
+        fn allocation_count(&self) -> usize {
+            4 + 3 * usize::from(self.hdr.is_some()) + 2 * usize::from(self.bloom.is_some())
+        }
+    }
+
+    /// Begin a single-attachment pass whose draw covers all of `framebuffer` (`extent`), with a
+    /// matching viewport and scissor.
+    fn begin_fullscreen_pass(
+        cbb: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
+        framebuffer: Arc<Framebuffer>,
+        extent: [u32; 2],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let area = Letterbox {
+            offset: [0, 0],
+            extent,
+        };
+        begin_pass_in(cbb, framebuffer, area, None)
+    }
+
+    /// Begin a single-attachment pass whose draw covers `area` of `framebuffer`. `clear` is
+    /// the clear value for render passes that clear on load.
+    fn begin_pass_in(
+        cbb: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
+        framebuffer: Arc<Framebuffer>,
+        area: Letterbox,
+        clear: Option<ClearValue>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        cbb.begin_render_pass(
+            RenderPassBeginInfo {
+                clear_values: vec![clear],
+                ..RenderPassBeginInfo::framebuffer(framebuffer)
+            },
+            SubpassBeginInfo::default(),
+        )?;
+        cbb.set_viewport(
+            0,
+            vec![Viewport {
+                offset: area.offset.map(|v| v as f32),
+                extent: area.extent.map(|v| v as f32),
+                depth_range: 0.0..=1.0,
+                ..Default::default()
+            }]
+            .into(),
+        )?;
+        cbb.set_scissor(
+            0,
+            vec![Scissor {
+                offset: area.offset,
+                extent: area.extent,
+                ..Default::default()
+            }]
+            .into(),
+        )?;
+        Ok(())
+    }
+
+    /// `linear_to_srgb` of tonemap.frag, for colors written to the swapchain without it.
+    fn linear_to_srgb(c: f32) -> f32 {
+        if c <= 0.003_130_8 {
+            c * 12.92
+        } else {
+            1.055 * c.powf(1.0 / 2.4) - 0.055
+        }
+    }
+
+    /// Passes `render_visual_world` records.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum FramePass {
+        Scene,
+        Luminance,
+        Taa,
+        Bloom,
+        Tonemap,
+    }
+
+    /// This frame's passes and what they hand each other.
+    fn frame_graph(post: &PostProcessSettings) -> RenderGraph<FramePass> {
+        let mut graph = RenderGraph::new();
+        graph
+            .add_pass(FramePass::Scene, &[], &["scene", "emissive", "motion"])
+            .add_pass(FramePass::Luminance, &["scene"], &["luminance"]);
+        let mut tonemap_reads = vec!["scene", "lut"];
+        if post.taa {
+            graph.add_pass(
+                FramePass::Taa,
+                &["scene", "motion", "taa_history"],
+                &["taa_resolve"],
+            );
+            tonemap_reads[0] = "taa_resolve";
+        }
+        if post.bloom.is_enabled() {
+            graph.add_pass(FramePass::Bloom, &["emissive"], &["bloom"]);
+            tonemap_reads.push("bloom");
+        }
+        graph
+            .add_pass(FramePass::Tonemap, &tonemap_reads, &["swapchain"])
+            .add_output("swapchain")
+            .add_output("luminance");
+        graph
+    }
+
+    /// Blit corner of mip `level` of a 2D image of `extent`.
+    fn mip_extent(extent: [u32; 2], level: u32) -> [u32; 3] {
+        [(extent[0] >> level).max(1), (extent[1] >> level).max(1), 1]
+    }
+
+    /// Fixed-function state that differs between pipelines of one material shader.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct MaterialPipelineKey {
+        pub blend: BlendMode,
+        pub culling: Culling,
+    }
+
+    /// `template` with the blend and cull state of `key`.
+    fn material_pipeline(
+        device: &Arc<vulkano::device::Device>,
+        template: &GraphicsPipelineCreateInfo,
+        key: MaterialPipelineKey,
+    ) -> Result<Arc<GraphicsPipeline>, Box<dyn std::error::Error>> {
+        let mut ci = template.clone();
+        ci.color_blend_state = Some(scene_blend_state(key.blend));
+        ci.rasterization_state = Some(RasterizationState {
+            cull_mode: match key.culling.mode {
+                CullMode::None => VkCullMode::None,
+                CullMode::Back => VkCullMode::Back,
+                CullMode::Front => VkCullMode::Front,
+            },
+            // Projections don't flip Y, so world-space counter-clockwise triangles come out
+            // clockwise in Vulkan's Y-down framebuffer.
+            front_face: match key.culling.front_face {
+                FrontFace::CounterClockwise => VkFrontFace::Clockwise,
+                FrontFace::Clockwise => VkFrontFace::CounterClockwise,
+            },
+            ..Default::default()
+        });
+        Ok(GraphicsPipeline::new(device.clone(), None, ci)?)
+    }
+
+    /// Blending of a material pipeline into the scene attachments. Motion vectors are replaced
+    /// by opaque draws and alpha-blended by the others; adding or multiplying them means nothing.
+    fn scene_blend_state(blend: BlendMode) -> ColorBlendState {
+        let motion = match blend {
+            BlendMode::Opaque => BlendMode::Opaque,
+            _ => BlendMode::AlphaBlend,
+        };
+        ColorBlendState {
+            attachments: vec![
+                blend_attachment(blend),
+                blend_attachment(blend),
+                blend_attachment(motion),
+            ],
+            ..Default::default()
+        }
+    }
+
+    /// Color attachment state of a material pipeline drawing with `blend`.
+    fn blend_attachment(blend: BlendMode) -> ColorBlendAttachmentState {
+        let blend = match blend {
+            BlendMode::Opaque => None,
+            // out.rgb = src.rgb * src.a + dst.rgb * (1 - src.a)
+            BlendMode::AlphaBlend => Some(AttachmentBlend {
+                src_color_blend_factor: BlendFactor::SrcAlpha,
+                dst_color_blend_factor: BlendFactor::OneMinusSrcAlpha,
+                color_blend_op: BlendOp::Add,
+                src_alpha_blend_factor: BlendFactor::One,
+                dst_alpha_blend_factor: BlendFactor::OneMinusSrcAlpha,
+                alpha_blend_op: BlendOp::Add,
+            }),
+            // out.rgb = dst.rgb + src.rgb * src.a; target alpha unchanged.
+            BlendMode::Additive => Some(AttachmentBlend {
+                src_color_blend_factor: BlendFactor::SrcAlpha,
+                dst_color_blend_factor: BlendFactor::One,
+                color_blend_op: BlendOp::Add,
+                src_alpha_blend_factor: BlendFactor::Zero,
+                dst_alpha_blend_factor: BlendFactor::One,
+                alpha_blend_op: BlendOp::Add,
+            }),
+            // out.rgb = dst.rgb * src.rgb; target alpha unchanged.
+            BlendMode::Multiply => Some(AttachmentBlend {
+                src_color_blend_factor: BlendFactor::DstColor,
+                dst_color_blend_factor: BlendFactor::Zero,
+                color_blend_op: BlendOp::Add,
+                src_alpha_blend_factor: BlendFactor::Zero,
+                dst_alpha_blend_factor: BlendFactor::One,
+                alpha_blend_op: BlendOp::Add,
+            }),
+        };
+        ColorBlendAttachmentState {
+            blend,
+            color_write_enable: true,
+            color_write_mask: ColorComponents::all(),
+        }
     }
 
+    /// Scissor for instances under `clip`, in the (render-scaled) scene framebuffer.
+    ///
+    /// Unknown or absent clips cover the whole framebuffer; `None` if nothing is left visible.
+    fn clip_scissor(
+        visual_world: &VisualWorld,
+        clip: Option<crate::engine::ecs::ComponentId>,
+        extent: [u32; 2],
+    ) -> Option<Scissor> {
+        let full = Scissor {
+            offset: [0, 0],
+            extent,
+            ..Default::default()
+        };
+        let Some(rect) = clip.and_then(|c| visual_world.clip_rect(c)) else {
+            return Some(full);
+        };
+        // Clip rects are in viewport pixels; the scene target may be render-scaled.
+        let [vw, vh] = visual_world.viewport();
+        let scale = [
+            if vw > 0.0 { extent[0] as f32 / vw } else { 1.0 },
+            if vh > 0.0 { extent[1] as f32 / vh } else { 1.0 },
+        ];
+        let lo = [0, 1].map(|i| {
+            (rect.min[i] * scale[i])
+                .floor()
+                .clamp(0.0, extent[i] as f32)
+        });
+        let hi = [0, 1].map(|i| (rect.max[i] * scale[i]).ceil().clamp(0.0, extent[i] as f32));
+        if hi[0] <= lo[0] || hi[1] <= lo[1] {
+            return None;
+        }
+        Some(Scissor {
+            offset: [lo[0] as u32, lo[1] as u32],
+            extent: [(hi[0] - lo[0]) as u32, (hi[1] - lo[1]) as u32],
+            ..Default::default()
+        })
+    }
+
+    /// Extent of the bloom targets for a scene target of `extent`.
+    fn bloom_extent(extent: [u32; 2]) -> [u32; 2] {
+        [(extent[0] / 2).max(1), (extent[1] / 2).max(1)]
+    }
+
+    /// Single-level image that is rendered to and then sampled.
+    fn sampled_attachment(
+        context: &VulkanoContext,
+        format: Format,
+        extent: [u32; 2],
+    ) -> Result<Arc<Image>, Box<dyn std::error::Error>> {
+        Ok(Image::new(
+            context.memory_allocator().clone(),
+            ImageCreateInfo {
+                image_type: ImageType::Dim2d,
+                format,
+                extent: [extent[0], extent[1], 1],
+                usage: ImageUsage::COLOR_ATTACHMENT | ImageUsage::SAMPLED,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_DEVICE,
+                ..Default::default()
+            },
+        )?)
+    }
+
+    /// Mip levels of a full chain down to 1x1.
+    fn mip_levels(extent: [u32; 2]) -> u32 {
+        32 - extent[0].max(extent[1]).max(1).leading_zeros()
+    }
+
+    /// IEEE 754 half to single precision.
+    fn f16_to_f32(bits: u16) -> f32 {
+        let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+        let exponent = i32::from((bits >> 10) & 0x1f);
+        let mantissa = f32::from(bits & 0x3ff);
+        match exponent {
+            0 => sign * mantissa * 2f32.powi(-24),
+            0x1f if mantissa == 0.0 => sign * f32::INFINITY,
+            0x1f => f32::NAN,
+            _ => sign * (1.0 + mantissa / 1024.0) * 2f32.powi(exponent - 15),
+        }
+    }
+
+    /// Arena capacity, in elements, when the first mesh is uploaded.
+    const INITIAL_ARENA_VERTICES: DeviceSize = 64 * 1024;
+    const INITIAL_ARENA_INDICES: DeviceSize = 192 * 1024;
+
     pub struct VulkanoGpuTexture {
         pub view: Arc<ImageView>,
+        pub kind: TextureKind,
+        pub bytes: u64,
     }
 
     pub struct VulkanoState {
@@ -139,12 +1058,16 @@ mod vulkano_backend {
         pub swapchain: Arc<Swapchain>,
         #[allow(dead_code)]
         pub swapchain_views: Vec<Arc<ImageView>>,
-        #[allow(dead_code)]
+        /// Scene pass into the frame's HDR target.
         pub render_pass: Arc<RenderPass>,
-        #[allow(dead_code)]
+        /// Tonemap pass into the swapchain image.
+        pub present_render_pass: Arc<RenderPass>,
+        /// Fullscreen passes into one HDR target: bloom, TAA resolve.
+        pub post_render_pass: Arc<RenderPass>,
+        /// Swapchain framebuffers of `present_render_pass`.
         pub framebuffers: Vec<Arc<Framebuffer>>,
 
-        #[allow(dead_code)]
+        /// Mesh and texture uploads; frames record with their `FrameSlot`'s allocator.
         pub command_buffer_allocator: Arc<StandardCommandBufferAllocator>,
 
         #[allow(dead_code)]
@@ -153,18 +1076,48 @@ mod vulkano_backend {
         #[allow(dead_code)]
         pub set_layouts: PipelineDescriptorSetLayouts,
 
-        #[allow(dead_code)]
         pub meshes: HashMap<MeshHandle, VulkanoGpuMesh>,
+        /// Created by the first mesh upload.
+        pub mesh_arena: Option<MeshArena>,
 
         pub textures: HashMap<TextureHandle, VulkanoGpuTexture>,
         pub sampler: Arc<Sampler>,
         pub default_white_texture: TextureHandle,
-
-        pub pipeline_toon_mesh: Arc<GraphicsPipeline>,
+        /// 1x1 white cubemap bound by the background pass when it draws a gradient.
+        pub default_cubemap: Arc<ImageView>,
+
+        /// The toon material shader, one pipeline per blend mode and culling in use.
+        pub pipelines_toon_mesh: HashMap<MaterialPipelineKey, Arc<GraphicsPipeline>>,
+        /// Template of `pipelines_toon_mesh` variants.
+        pipeline_toon_mesh_ci: GraphicsPipelineCreateInfo,
+        /// Inverted-hull outlines of materials that have one (`VisualWorld::material_outline`).
+        pub pipeline_outline: Arc<GraphicsPipeline>,
+        pub pipeline_tonemap: Arc<GraphicsPipeline>,
+        /// Bloom bright pass and blur (`bloom.frag`), selected by push constant.
+        pub pipeline_bloom: Arc<GraphicsPipeline>,
+        /// Temporal AA resolve into `taa_history`.
+        pub pipeline_taa: Arc<GraphicsPipeline>,
+        /// Shared by all frame slots: each frame reads the resolve of the one before. Created
+        /// while TAA is on.
+        taa_history: Option<TaaHistory>,
+        /// Frames rendered with TAA on; picks the jitter offset.
+        taa_frame: usize,
+        /// Gradient and skybox backgrounds, drawn first in the scene pass.
+        pub pipeline_background: Arc<GraphicsPipeline>,
+        /// Clamped linear sampler the tonemap pass upscales the HDR target with.
+        pub post_sampler: Arc<Sampler>,
+        /// The swapchain format is UNORM, so the tonemap shader encodes sRGB itself.
+        pub encode_srgb: bool,
 
         pub window_resized: bool,
         pub recreate_swapchain: bool,
-        pub previous_frame_end: Option<Box<dyn GpuFuture>>,
+        pub frames: Vec<FrameSlot>,
+        /// Slot of the next frame.
+        pub frame_index: usize,
+        /// Slot of the last submitted frame; the next one is chained after it.
+        previous_frame: usize,
+
+        pub memory: GpuMemoryUsage,
     }
 
     const MAX_POINT_LIGHTS: usize = 64;
@@ -203,20 +1156,25 @@ mod vulkano_backend {
                     base_color: [1.0, 0.7, 0.2, 1.0],
                     quant_steps: 4.0,
                     emissive: 0,
-                    _pad0: [0, 0],
+                    reflectivity: 0.0,
+                    _pad0: 0,
                 },
                 // While migrating, treat UNLIT as a simple toon material too.
                 crate::engine::graphics::MaterialHandle::UNLIT_MESH => MaterialUBO {
                     base_color: [1.0, 1.0, 1.0, 1.0],
                     quant_steps: 1.0,
                     emissive: 1,
-                    _pad0: [0, 0],
+                    reflectivity: 0.0,
+                    _pad0: 0,
                 },
                 _ => MaterialUBO::default(),
             }
         }
 
-        pub fn new(window: Arc<Window>) -> Result<Self, Box<dyn std::error::Error>> {
+        pub fn new(
+            window: Arc<Window>,
+            frames_in_flight: usize,
+        ) -> Result<Self, Box<dyn std::error::Error>> {
             // Prefer the helper context while we're migrating: it enables surface extensions
             // and sets up graphics/compute queues and allocators.
             let context = VulkanoContext::new(VulkanoConfig::default());
@@ -234,6 +1192,8 @@ mod vulkano_backend {
                 .ok_or("no supported surface formats")?
                 .0;
 
+            let encode_srgb = image_format.numeric_format_color() != Some(NumericFormat::SRGB);
+
             let mut min_image_count = 2u32.max(surface_capabilities.min_image_count);
             if let Some(max_image_count) = surface_capabilities.max_image_count {
                 min_image_count = min_image_count.min(max_image_count);
@@ -246,7 +1206,7 @@ mod vulkano_backend {
                     min_image_count,
                     image_format,
                     image_extent: window.inner_size().into(),
-                    image_usage: vulkano::image::ImageUsage::COLOR_ATTACHMENT,
+                    image_usage: ImageUsage::COLOR_ATTACHMENT,
                     composite_alpha: surface_capabilities
                         .supported_composite_alpha
                         .into_iter()
@@ -263,6 +1223,51 @@ mod vulkano_backend {
                 .collect::<Result<Vec<_>, Box<dyn std::error::Error>>>()?;
 
             let render_pass = vulkano::single_pass_renderpass!(
+                device.clone(),
+                attachments: {
+                    color: {
+                        format: HDR_FORMAT,
+                        samples: 1,
+                        load_op: Clear,
+                        store_op: Store,
+                    },
+                    emissive: {
+                        format: HDR_FORMAT,
+                        samples: 1,
+                        load_op: Clear,
+                        store_op: Store,
+                    },
+                    motion: {
+                        format: MOTION_FORMAT,
+                        samples: 1,
+                        load_op: Clear,
+                        store_op: Store,
+                    },
+                },
+                pass: {
+                    color: [color, emissive, motion],
+                    depth_stencil: {},
+                }
+            )?;
+
+            let post_render_pass = vulkano::single_pass_renderpass!(
+                device.clone(),
+                attachments: {
+                    color: {
+                        format: HDR_FORMAT,
+                        samples: 1,
+                        load_op: DontCare,
+                        store_op: Store,
+                    },
+                },
+                pass: {
+                    color: [color],
+                    depth_stencil: {},
+                }
+            )?;
+
+            // Cleared for the letterbox bars; the tonemap pass covers everything else.
+            let present_render_pass = vulkano::single_pass_renderpass!(
                 device.clone(),
                 attachments: {
                     color: {
@@ -282,7 +1287,7 @@ mod vulkano_backend {
                 .iter()
                 .map(|view| {
                     Framebuffer::new(
-                        render_pass.clone(),
+                        present_render_pass.clone(),
                         FramebufferCreateInfo {
                             attachments: vec![view.clone()],
                             ..Default::default()
@@ -318,7 +1323,7 @@ mod vulkano_backend {
 
             // Important: `CpuVertex` contains more than just position (e.g. UV).
             // We explicitly declare which attributes are consumed by the shader.
-            // Instance data occupies locations 1-4.
+            // Instance data occupies locations 1-4 and 6-11.
             let vertex_input_state = VertexInputState::new()
                 .binding(
                     0,
@@ -398,7 +1403,27 @@ mod vulkano_backend {
                         offset: 64,
                         ..Default::default()
                     },
+                )
+                .attribute(
+                    7,
+                    VertexInputAttributeDescription {
+                        binding: 1,
+                        format: Format::R32_SFLOAT,
+                        offset: 80,
+                        ..Default::default()
+                    },
                 );
+            let vertex_input_state = (0..4).fold(vertex_input_state, |state, column| {
+                state.attribute(
+                    8 + column,
+                    VertexInputAttributeDescription {
+                        binding: 1,
+                        format: Format::R32G32B32A32_SFLOAT,
+                        offset: 84 + 16 * column,
+                        ..Default::default()
+                    },
+                )
+            });
 
             let subpass = Subpass::from(render_pass.clone(), 0).ok_or("missing subpass 0")?;
             let mut pipeline_ci =
@@ -410,29 +1435,193 @@ mod vulkano_backend {
             pipeline_ci.rasterization_state = Some(RasterizationState::default());
             pipeline_ci.multisample_state = Some(MultisampleState::default());
             pipeline_ci.depth_stencil_state = None;
-            // Enable alpha blending so textures with transparency (e.g. PNG alpha) render correctly.
-            // Uses straight alpha: out.rgb = src.rgb * src.a + dst.rgb * (1-src.a)
-            pipeline_ci.color_blend_state = Some(ColorBlendState::with_attachment_states(
-                1,
-                ColorBlendAttachmentState {
-                    blend: Some(AttachmentBlend {
-                        src_color_blend_factor: BlendFactor::SrcAlpha,
-                        dst_color_blend_factor: BlendFactor::OneMinusSrcAlpha,
-                        color_blend_op: BlendOp::Add,
-                        src_alpha_blend_factor: BlendFactor::One,
-                        dst_alpha_blend_factor: BlendFactor::OneMinusSrcAlpha,
-                        alpha_blend_op: BlendOp::Add,
-                    }),
-                    color_write_enable: true,
-                    color_write_mask: ColorComponents::all(),
-                },
-            ));
+            // Alpha blending so textures with transparency (e.g. PNG alpha) render correctly.
+            // The other blend modes get their own copies of this pipeline below.
+            pipeline_ci.color_blend_state = Some(scene_blend_state(BlendMode::AlphaBlend));
             pipeline_ci.dynamic_state = [DynamicState::Viewport, DynamicState::Scissor]
                 .into_iter()
                 .collect();
             pipeline_ci.subpass = Some(PipelineSubpassType::BeginRenderPass(subpass));
 
-            let pipeline_toon_mesh = GraphicsPipeline::new(device.clone(), None, pipeline_ci)?;
+            // Outlines reuse the toon vertex input, blending and pass; only the shaders and the
+            // layout (camera set + push constants) differ.
+            let outline_vs = outline_vs::load(device.clone())?;
+            let outline_fs = outline_fs::load(device.clone())?;
+            let mut outline_ci = pipeline_ci.clone();
+            outline_ci.stages = vec![
+                PipelineShaderStageCreateInfo::new(
+                    outline_vs
+                        .entry_point("main")
+                        .ok_or("missing outline.vert entry point")?,
+                ),
+                PipelineShaderStageCreateInfo::new(
+                    outline_fs
+                        .entry_point("main")
+                        .ok_or("missing outline.frag entry point")?,
+                ),
+            ]
+            .into();
+            outline_ci.layout = PipelineLayout::new(
+                device.clone(),
+                PipelineLayoutCreateInfo {
+                    set_layouts: vec![set_layouts.global.clone()],
+                    push_constant_ranges: vec![PushConstantRange {
+                        stages: ShaderStages::VERTEX | ShaderStages::FRAGMENT,
+                        offset: 0,
+                        size: size_of::<OutlinePushConstants>() as u32,
+                    }],
+                    ..Default::default()
+                },
+            )?;
+            let pipeline_outline = GraphicsPipeline::new(device.clone(), None, outline_ci)?;
+
+            // Variants with the default culling are built up front so a broken pipeline fails
+            // at startup; other cull settings are built the first time a material uses them.
+            let pipelines_toon_mesh = BlendMode::ALL
+                .into_iter()
+                .map(|blend| {
+                    let key = MaterialPipelineKey {
+                        blend,
+                        culling: Culling::default(),
+                    };
+                    Ok((key, material_pipeline(&device, &pipeline_ci, key)?))
+                })
+                .collect::<Result<HashMap<_, _>, Box<dyn std::error::Error>>>()?;
+
+            let fullscreen_vs = fullscreen_vs::load(device.clone())?;
+            let fullscreen_vs = fullscreen_vs
+                .entry_point("main")
+                .ok_or("missing fullscreen.vert entry point")?;
+            let tonemap_fs = tonemap_fs::load(device.clone())?;
+            let tonemap_layout = PipelineLayout::new(
+                device.clone(),
+                PipelineLayoutCreateInfo {
+                    // Set 1 is the color grading LUT, set 2 the blurred bloom.
+                    set_layouts: vec![
+                        set_layouts.post.clone(),
+                        set_layouts.post.clone(),
+                        set_layouts.post.clone(),
+                    ],
+                    push_constant_ranges: vec![PushConstantRange {
+                        stages: ShaderStages::FRAGMENT,
+                        offset: 0,
+                        size: size_of::<TonemapPushConstants>() as u32,
+                    }],
+                    ..Default::default()
+                },
+            )?;
+            let mut tonemap_ci =
+                vulkano::pipeline::graphics::GraphicsPipelineCreateInfo::layout(tonemap_layout);
+            tonemap_ci.stages = [
+                PipelineShaderStageCreateInfo::new(fullscreen_vs.clone()),
+                PipelineShaderStageCreateInfo::new(
+                    tonemap_fs
+                        .entry_point("main")
+                        .ok_or("missing tonemap.frag entry point")?,
+                ),
+            ]
+            .into_iter()
+            .collect();
+            tonemap_ci.vertex_input_state = Some(VertexInputState::new());
+            tonemap_ci.input_assembly_state = Some(InputAssemblyState::default());
+            tonemap_ci.viewport_state = Some(ViewportState::default());
+            tonemap_ci.rasterization_state = Some(RasterizationState::default());
+            tonemap_ci.multisample_state = Some(MultisampleState::default());
+            tonemap_ci.color_blend_state = Some(ColorBlendState::with_attachment_states(
+                1,
+                ColorBlendAttachmentState::default(),
+            ));
+            tonemap_ci.dynamic_state = [DynamicState::Viewport, DynamicState::Scissor]
+                .into_iter()
+                .collect();
+            tonemap_ci.subpass = Some(PipelineSubpassType::BeginRenderPass(
+                Subpass::from(present_render_pass.clone(), 0).ok_or("missing subpass 0")?,
+            ));
+
+            // The background is another fullscreen triangle, drawn into the HDR scene pass.
+            let background_fs = background_fs::load(device.clone())?;
+            let mut background_ci = tonemap_ci.clone();
+            background_ci.stages = [
+                PipelineShaderStageCreateInfo::new(fullscreen_vs),
+                PipelineShaderStageCreateInfo::new(
+                    background_fs
+                        .entry_point("main")
+                        .ok_or("missing background.frag entry point")?,
+                ),
+            ]
+            .into_iter()
+            .collect();
+            background_ci.layout = PipelineLayout::new(
+                device.clone(),
+                PipelineLayoutCreateInfo {
+                    set_layouts: vec![set_layouts.post.clone()],
+                    push_constant_ranges: vec![PushConstantRange {
+                        stages: ShaderStages::FRAGMENT,
+                        offset: 0,
+                        size: size_of::<BackgroundPushConstants>() as u32,
+                    }],
+                    ..Default::default()
+                },
+            )?;
+            background_ci.color_blend_state = Some(ColorBlendState::with_attachment_states(
+                SCENE_ATTACHMENTS,
+                ColorBlendAttachmentState::default(),
+            ));
+            background_ci.subpass = Some(PipelineSubpassType::BeginRenderPass(
+                Subpass::from(render_pass.clone(), 0).ok_or("missing subpass 0")?,
+            ));
+            let pipeline_background = GraphicsPipeline::new(device.clone(), None, background_ci)?;
+
+            let bloom_fs = bloom_fs::load(device.clone())?;
+            let mut bloom_ci = tonemap_ci.clone();
+            bloom_ci.stages[1] = PipelineShaderStageCreateInfo::new(
+                bloom_fs
+                    .entry_point("main")
+                    .ok_or("missing bloom.frag entry point")?,
+            );
+            bloom_ci.layout = PipelineLayout::new(
+                device.clone(),
+                PipelineLayoutCreateInfo {
+                    set_layouts: vec![set_layouts.post.clone()],
+                    push_constant_ranges: vec![PushConstantRange {
+                        stages: ShaderStages::FRAGMENT,
+                        offset: 0,
+                        size: size_of::<BloomPushConstants>() as u32,
+                    }],
+                    ..Default::default()
+                },
+            )?;
+            bloom_ci.subpass = Some(PipelineSubpassType::BeginRenderPass(
+                Subpass::from(post_render_pass.clone(), 0).ok_or("missing subpass 0")?,
+            ));
+            let pipeline_bloom = GraphicsPipeline::new(device.clone(), None, bloom_ci.clone())?;
+
+            let taa_fs = taa_fs::load(device.clone())?;
+            let mut taa_ci = bloom_ci;
+            taa_ci.stages[1] = PipelineShaderStageCreateInfo::new(
+                taa_fs
+                    .entry_point("main")
+                    .ok_or("missing taa.frag entry point")?,
+            );
+            taa_ci.layout = PipelineLayout::new(
+                device.clone(),
+                PipelineLayoutCreateInfo {
+                    // Scene, history, motion vectors.
+                    set_layouts: vec![
+                        set_layouts.post.clone(),
+                        set_layouts.post.clone(),
+                        set_layouts.post.clone(),
+                    ],
+                    push_constant_ranges: vec![PushConstantRange {
+                        stages: ShaderStages::FRAGMENT,
+                        offset: 0,
+                        size: size_of::<TaaPushConstants>() as u32,
+                    }],
+                    ..Default::default()
+                },
+            )?;
+            let pipeline_taa = GraphicsPipeline::new(device.clone(), None, taa_ci)?;
+            let pipeline_tonemap = GraphicsPipeline::new(device.clone(), None, tonemap_ci)?;
 
             let command_buffer_allocator = Arc::new(StandardCommandBufferAllocator::new(
                 device.clone(),
@@ -445,6 +1634,28 @@ mod vulkano_backend {
             ));
 
             let sampler = Sampler::new(device.clone(), SamplerCreateInfo::simple_repeat_linear())?;
+            let post_sampler = Sampler::new(
+                device.clone(),
+                SamplerCreateInfo {
+                    mag_filter: Filter::Linear,
+                    min_filter: Filter::Linear,
+                    address_mode: [SamplerAddressMode::ClampToEdge; 3],
+                    ..Default::default()
+                },
+            )?;
+
+            let default_cubemap = Self::upload_image(
+                &context,
+                &command_buffer_allocator,
+                &[255; 6 * 4],
+                [1, 1],
+                TextureKind::Cube,
+            )?
+            .view;
+
+            let frames = (0..frames_in_flight.max(1))
+                .map(|_| FrameSlot::new(&context, &descriptor_set_allocator, &set_layouts))
+                .collect::<Result<Vec<_>, _>>()?;
 
             let mut state = Self {
                 context,
@@ -453,23 +1664,41 @@ mod vulkano_backend {
                 swapchain,
                 swapchain_views,
                 render_pass,
+                present_render_pass,
+                post_render_pass,
                 framebuffers,
 
                 command_buffer_allocator,
                 descriptor_set_allocator,
                 meshes: HashMap::new(),
+                mesh_arena: None,
 
                 textures: HashMap::new(),
                 sampler,
                 default_white_texture: TextureHandle(0),
+                default_cubemap,
 
                 set_layouts,
 
-                pipeline_toon_mesh,
+                pipelines_toon_mesh,
+                pipeline_toon_mesh_ci: pipeline_ci,
+                pipeline_outline,
+                pipeline_tonemap,
+                pipeline_bloom,
+                pipeline_taa,
+                taa_history: None,
+                taa_frame: 0,
+                pipeline_background,
+                post_sampler,
+                encode_srgb,
 
                 window_resized: false,
                 recreate_swapchain: false,
-                previous_frame_end: Some(sync::now(device).boxed()),
+                frames,
+                frame_index: 0,
+                previous_frame: 0,
+
+                memory: GpuMemoryUsage::default(),
             };
 
             // Default texture: 1x1 white so untextured materials can still bind a sampler.
@@ -497,10 +1726,7 @@ mod vulkano_backend {
                 Ok(r) => r,
                 Err(e) => {
                     self.recreate_swapchain = true;
-                    println!(
-                        "[VulkanoRenderer] failed to recreate swapchain: {}",
-                        Validated::unwrap(e)
-                    );
+                    crate::log_warn!("failed to recreate swapchain: {}", Validated::unwrap(e));
                     return Ok(());
                 }
             };
@@ -516,7 +1742,7 @@ mod vulkano_backend {
                 .iter()
                 .map(|view| {
                     Framebuffer::new(
-                        self.render_pass.clone(),
+                        self.present_render_pass.clone(),
                         FramebufferCreateInfo {
                             attachments: vec![view.clone()],
                             ..Default::default()
@@ -534,13 +1760,25 @@ mod vulkano_backend {
             &mut self,
             visual_world: &mut VisualWorld,
         ) -> Result<(), Box<dyn std::error::Error>> {
+            // Minimized: a zero-extent swapchain is invalid, so there is nothing to draw into.
+            let size = self.window.inner_size();
+            if size.width == 0 || size.height == 0 {
+                return Ok(());
+            }
+
             self.recreate_swapchain_if_needed()?;
 
             let device = self.context.device().clone();
             let queue = self.context.graphics_queue().clone();
 
-            if let Some(previous_frame_end) = self.previous_frame_end.as_mut() {
-                previous_frame_end.cleanup_finished();
+            // Wait until the GPU is done with this slot's buffers before writing them.
+            let acquire = crate::utils::trace::span("renderer.acquire");
+            let slot = self.frame_index;
+            if let Some(fence) = self.frames[slot].fence.take() {
+                fence.wait(None)?;
+            }
+            if let Some(luminance) = self.frames[slot].take_luminance()? {
+                visual_world.exposure_mut().update(luminance);
             }
 
             let (image_i, suboptimal, acquire_future) =
@@ -558,76 +1796,62 @@ mod vulkano_backend {
             if suboptimal {
                 self.recreate_swapchain = true;
             }
+            drop(acquire);
 
+            let record = crate::utils::trace::span("renderer.record");
             // Always rebuild draw cache cheaply.
             visual_world.prepare_draw_cache();
 
-            // Build instance buffer in draw order so each DrawBatch maps to a contiguous range.
-            let instance_count = visual_world.draw_order().len();
-            let instances_ref = visual_world.instances();
-
-            let instance_data_iter = visual_world.draw_order().iter().map(|&idx| {
-                let inst = instances_ref[idx as usize];
-                let m = inst.transform.model;
-                InstanceData {
-                    i_model_c0: m[0],
-                    i_model_c1: m[1],
-                    i_model_c2: m[2],
-                    i_model_c3: m[3],
-                    i_color: inst.color,
-                }
-            });
-
-            let instance_buffer: Subbuffer<[InstanceData]> = Buffer::from_iter(
-                self.context.memory_allocator().clone(),
-                BufferCreateInfo {
-                    usage: BufferUsage::VERTEX_BUFFER,
-                    ..Default::default()
-                },
-                AllocationCreateInfo {
-                    memory_type_filter: MemoryTypeFilter::PREFER_HOST
-                        | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
-                    ..Default::default()
-                },
-                instance_data_iter,
+            self.frames[slot].write_instances(
+                &self.context,
+                visual_world.draw_order(),
+                visual_world.instances(),
             )?;
 
-            let framebuffer = self.framebuffers[image_i as usize].clone();
-            let mut render_pass_begin = RenderPassBeginInfo::framebuffer(framebuffer);
-            render_pass_begin.clear_values = vec![Some(ClearValue::from([0.0f32, 0.0, 0.0, 1.0]))];
-
-            let extent = self.swapchain.image_extent();
-            let viewport = Viewport {
-                offset: [0.0, 0.0],
-                extent: [extent[0] as f32, extent[1] as f32],
-                depth_range: 0.0..=1.0,
-                ..Default::default()
-            };
+            // The scene renders into the HDR target at the scaled extent; the tonemap pass
+            // stretches it over the letterboxed part of the swapchain image (all of it unless
+            // there is a virtual resolution).
+            let swapchain_extent = self.swapchain.image_extent();
+            let letterbox = visual_world.renderer_config().letterbox(swapchain_extent);
+            let extent = visual_world.render_scale().scaled_extent(letterbox.extent);
+            let hdr = self.frames[slot].hdr_target(
+                &self.context,
+                &self.render_pass,
+                &self.descriptor_set_allocator,
+                &self.set_layouts,
+                &self.post_sampler,
+                extent,
+            )?;
+            let post_set = hdr.post_set.clone();
 
             // Camera uniform buffer (set=0, binding=0).
             // `camera2d` currently feeds the 2D path directly; we also pass the current
-            // swapchain extent so shaders can correct for aspect ratio.
+            // render extent so shaders can correct for aspect ratio.
+            let taa = visual_world.post_process().taa;
+            let jitter = if taa {
+                self.taa_frame = self.taa_frame.wrapping_add(1);
+                let [x, y] = TAA_JITTER[self.taa_frame % TAA_JITTER.len()];
+                [2.0 * x / extent[0] as f32, 2.0 * y / extent[1] as f32]
+            } else {
+                // Drop the history too, so turning TAA back on doesn't blend in a stale frame.
+                self.taa_history = None;
+                [0.0, 0.0]
+            };
+            let previous = visual_world.previous_camera();
             let camera_ubo = CameraUBO {
                 view: visual_world.camera_view(),
                 proj: visual_world.camera_proj(),
                 camera2d: visual_world.camera_2d(),
                 viewport: [extent[0] as f32, extent[1] as f32],
                 _pad0: [0.0, 0.0],
+                prev_view: previous.view,
+                prev_proj: previous.proj,
+                prev_camera2d: previous.camera_2d,
+                jitter,
+                _pad1: [0.0, 0.0],
             };
 
-            let camera_buffer: Subbuffer<CameraUBO> = Buffer::from_data(
-                self.context.memory_allocator().clone(),
-                BufferCreateInfo {
-                    usage: BufferUsage::UNIFORM_BUFFER,
-                    ..Default::default()
-                },
-                AllocationCreateInfo {
-                    memory_type_filter: MemoryTypeFilter::PREFER_HOST
-                        | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
-                    ..Default::default()
-                },
-                camera_ubo,
-            )?;
+            *self.frames[slot].camera.write()? = camera_ubo;
 
             // Lights storage buffer (set=0, binding=1). Placeholder for now.
             let mut lights_ssbo = LightsSSBO::default();
@@ -646,39 +1870,135 @@ mod vulkano_backend {
                 };
             }
 
-            let lights_buffer: Subbuffer<LightsSSBO> = Buffer::from_data(
-                self.context.memory_allocator().clone(),
-                BufferCreateInfo {
-                    usage: BufferUsage::STORAGE_BUFFER,
-                    ..Default::default()
-                },
-                AllocationCreateInfo {
-                    memory_type_filter: MemoryTypeFilter::PREFER_HOST
-                        | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
-                    ..Default::default()
-                },
-                lights_ssbo,
+            *self.frames[slot].lights.write()? = lights_ssbo;
+
+            let mut frame_buffers = GpuAllocations {
+                bytes: self.frames.iter().map(FrameSlot::bytes).sum::<u64>()
+                    + self
+                        .taa_history
+                        .as_ref()
+                        .map_or(0, |history| history.targets.bytes()),
+                count: self
+                    .frames
+                    .iter()
+                    .map(FrameSlot::allocation_count)
+                    .sum::<usize>()
+                    + 2 * usize::from(self.taa_history.is_some()),
+            };
+            let mut cbb = AutoCommandBufferBuilder::primary(
+                self.frames[slot].command_buffer_allocator.clone(),
+                queue.queue_family_index(),
+                CommandBufferUsage::OneTimeSubmit,
             )?;
 
-            let global_set = DescriptorSet::new(
-                self.descriptor_set_allocator.clone(),
-                self.set_layouts.global.clone(),
-                [
-                    WriteDescriptorSet::buffer(0, camera_buffer),
-                    WriteDescriptorSet::buffer(1, lights_buffer),
-                ],
-                [],
-            )?;
+            // Passes run in the order the frame graph derives from what they read and write.
+            let post = *visual_world.post_process();
+            let mut scene_set = post_set;
+            let mut bloom_set = None;
+            for pass in frame_graph(&post).compile()? {
+                match pass {
+                    FramePass::Scene => {
+                        self.record_scene(&mut cbb, visual_world, slot, extent, &mut frame_buffers)?
+                    }
+                    FramePass::Luminance => self.record_luminance(&mut cbb, slot, extent)?,
+                    FramePass::Taa => scene_set = self.record_taa(&mut cbb, slot, extent)?,
+                    FramePass::Bloom => {
+                        bloom_set = Some(self.record_bloom(&mut cbb, slot, extent, post.bloom)?)
+                    }
+                    FramePass::Tonemap => self.record_tonemap(
+                        &mut cbb,
+                        visual_world,
+                        slot,
+                        image_i,
+                        scene_set.clone(),
+                        bloom_set.clone(),
+                    )?,
+                }
+            }
+
+            self.memory
+                .set(GpuMemoryCategory::FrameBuffers, frame_buffers);
+            self.memory.publish();
+
+            let cb = cbb.build()?;
+            drop(record);
+
+            let _submit = crate::utils::trace::span("renderer.submit");
+            let start_future: Box<dyn GpuFuture + Send + Sync> =
+                match self.frames[self.previous_frame].fence.clone() {
+                    Some(fence) => fence.boxed_send_sync(),
+                    None => sync::now(device.clone()).boxed_send_sync(),
+                };
+
+            let execution = start_future
+                .join(acquire_future)
+                .then_execute(queue.clone(), cb)?
+                .then_swapchain_present(
+                    queue.clone(),
+                    SwapchainPresentInfo::swapchain_image_index(self.swapchain.clone(), image_i),
+                )
+                .boxed_send_sync()
+                .then_signal_fence_and_flush();
+
+            self.frames[slot].fence = match execution.map_err(Validated::unwrap) {
+                Ok(fence) => {
+                    self.frames[slot].luminance_pending = true;
+                    Some(Arc::new(fence))
+                }
+                Err(VulkanError::OutOfDate) => {
+                    self.recreate_swapchain = true;
+                    None
+                }
+                Err(e) => {
+                    crate::log_error!("failed to flush future: {e}");
+                    None
+                }
+            };
+            self.previous_frame = slot;
+            self.frame_index = (slot + 1) % self.frames.len();
 
-            let mut cbb = AutoCommandBufferBuilder::primary(
-                self.command_buffer_allocator.clone(),
-                queue.queue_family_index(),
-                CommandBufferUsage::OneTimeSubmit,
-            )?;
+            Ok(())
+        }
+
+        /// Background, then every batch (outlines included) into the frame's HDR target.
+        fn record_scene(
+            &mut self,
+            cbb: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
+            visual_world: &VisualWorld,
+            slot: usize,
+            extent: [u32; 2],
+            frame_buffers: &mut GpuAllocations,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            let frame = &self.frames[slot];
+            let hdr = frame
+                .hdr
+                .as_ref()
+                .ok_or("scene pass without an HDR target")?;
+            let global_set = frame.global_set.clone();
+            let instance_buffer = frame.instances.clone();
+            let instance_count = visual_world.draw_order().len();
+            let mut render_pass_begin = RenderPassBeginInfo::framebuffer(hdr.framebuffer.clone());
+            let background = visual_world.background();
+            let clear_color = match background {
+                VisualBackground::Solid(color) => color,
+                _ => visual_world.renderer_config().clear_color.to_array(),
+            };
+            render_pass_begin.clear_values = vec![
+                Some(ClearValue::from(clear_color)),
+                Some(ClearValue::from([0.0; 4])),
+                Some(ClearValue::from([0.0; 4])),
+            ];
+
+            let viewport = Viewport {
+                offset: [0.0, 0.0],
+                extent: [extent[0] as f32, extent[1] as f32],
+                depth_range: 0.0..=1.0,
+                ..Default::default()
+            };
 
             cbb.begin_render_pass(render_pass_begin, SubpassBeginInfo::default())?;
 
-            cbb.set_viewport(0, vec![viewport].into())?;
+            cbb.set_viewport(0, vec![viewport.clone()].into())?;
             cbb.set_scissor(
                 0,
                 vec![Scissor {
@@ -689,25 +2009,59 @@ mod vulkano_backend {
                 .into(),
             )?;
 
+            self.draw_background(cbb, visual_world, background)?;
+
             // Bind pipeline/descriptor sets per (material, texture).
             // For now, TOON_MESH is the primary bring-up pipeline.
             // UNLIT_MESH is treated as an alias to TOON_MESH for compatibility while migrating.
             let mut bound_material: Option<crate::engine::graphics::MaterialHandle> = None;
             let mut bound_texture: Option<TextureHandle> = None;
+            // Sets of the bound material, rebound after drawing an outline.
+            let mut bound_sets: Option<(Arc<DescriptorSet>, Arc<DescriptorSet>)> = None;
+            // Every mesh shares the arena buffers: bind them once per frame.
+            let mut arena_bound = false;
+            // Scissor matches this clip (`None`: the whole framebuffer).
+            let mut bound_clip: Option<crate::engine::ecs::ComponentId> = None;
 
             for batch in visual_world.draw_batches() {
+                if batch.clip != bound_clip {
+                    let Some(scissor) = clip_scissor(visual_world, batch.clip, extent) else {
+                        // Clipped away entirely.
+                        continue;
+                    };
+                    cbb.set_scissor(0, vec![scissor].into())?;
+                    bound_clip = batch.clip;
+                }
                 let texture_handle = batch.texture.unwrap_or(self.default_white_texture);
+                let pipeline = self.toon_pipeline(MaterialPipelineKey {
+                    blend: batch.blend,
+                    culling: visual_world.material_culling(batch.material),
+                })?;
 
                 if bound_material != Some(batch.material) || bound_texture != Some(texture_handle) {
                     match batch.material {
                         crate::engine::graphics::MaterialHandle::TOON_MESH
                         | crate::engine::graphics::MaterialHandle::UNLIT_MESH => {
-                            let Some(tex) = self.textures.get(&texture_handle) else {
+                            let Some(mut tex) = self.textures.get(&texture_handle) else {
                                 // Missing texture: skip this batch.
                                 continue;
                             };
-
-                            let material_ubo = Self::create_material_ubo(batch.material);
+                            if tex.kind != TextureKind::D2 {
+                                tex = &self.textures[&self.default_white_texture];
+                            }
+
+                            let mut material_ubo = Self::create_material_ubo(batch.material);
+                            let reflection = visual_world
+                                .material_reflection(batch.material)
+                                .and_then(|r| Some((r, self.textures.get(&r.cubemap)?)))
+                                .filter(|(_, cube)| cube.kind == TextureKind::Cube);
+                            let reflection_view = match reflection {
+                                Some((r, cube)) => {
+                                    material_ubo.reflectivity = r.strength.clamp(0.0, 1.0);
+                                    cube.view.clone()
+                                }
+                                None => self.default_cubemap.clone(),
+                            };
                             let material_buffer: Subbuffer<MaterialUBO> = Buffer::from_data(
                                 self.context.memory_allocator().clone(),
                                 BufferCreateInfo {
@@ -721,6 +2075,8 @@ mod vulkano_backend {
                                 },
                                 material_ubo,
                             )?;
+                            frame_buffers.bytes += material_buffer.size();
+                            frame_buffers.count += 1;
 
                             let material_set = DescriptorSet::new(
                                 self.descriptor_set_allocator.clone(),
@@ -732,17 +2088,23 @@ mod vulkano_backend {
                                         tex.view.clone(),
                                         self.sampler.clone(),
                                     ),
+                                    WriteDescriptorSet::image_view_sampler(
+                                        2,
+                                        reflection_view,
+                                        self.sampler.clone(),
+                                    ),
                                 ],
                                 [],
                             )?;
 
-                            cbb.bind_pipeline_graphics(self.pipeline_toon_mesh.clone())?;
+                            cbb.bind_pipeline_graphics(pipeline.clone())?;
                             cbb.bind_descriptor_sets(
                                 PipelineBindPoint::Graphics,
-                                self.pipeline_toon_mesh.layout().clone(),
+                                pipeline.layout().clone(),
                                 0,
-                                (global_set.clone(), material_set),
+                                (global_set.clone(), material_set.clone()),
                             )?;
+                            bound_sets = Some((global_set.clone(), material_set));
                         }
                         _ => {
                             // Unknown material: skip this batch.
@@ -754,58 +2116,410 @@ mod vulkano_backend {
                     bound_texture = Some(texture_handle);
                 }
 
-                let Some(mesh) = self.meshes.get(&batch.mesh) else {
+                let (Some(mesh), Some(arena)) = (self.meshes.get(&batch.mesh), &self.mesh_arena)
+                else {
                     continue;
                 };
-                cbb.bind_vertex_buffers(0, (mesh.vertices.clone(), instance_buffer.clone()))?;
-                cbb.bind_index_buffer(mesh.indices.clone())?;
+                if !arena_bound {
+                    cbb.bind_vertex_buffers(0, (arena.vertices.clone(), instance_buffer.clone()))?;
+                    cbb.bind_index_buffer(arena.indices.clone())?;
+                    arena_bound = true;
+                }
 
-                if instance_count > 0 {
+                if instance_count == 0 {
+                    continue;
+                }
+                let outline = visual_world
+                    .material_outline(batch.material)
+                    .filter(|o| o.width > 0.0 && o.color.a > 0.0);
+                let (Some(outline), Some(sets)) = (outline, &bound_sets) else {
                     unsafe {
                         cbb.draw_indexed(
                             mesh.index_count,
                             batch.count as u32,
-                            0,
-                            0,
+                            mesh.first_index,
+                            mesh.vertex_offset,
                             batch.start as u32,
                         )?;
                     }
+                    continue;
+                };
+                // Hull then fill, one instance at a time, so an instance drawn later covers
+                // both the fill and the outline of the ones below it.
+                for instance in batch.start..batch.start + batch.count {
+                    cbb.bind_pipeline_graphics(self.pipeline_outline.clone())?;
+                    cbb.bind_descriptor_sets(
+                        PipelineBindPoint::Graphics,
+                        self.pipeline_outline.layout().clone(),
+                        0,
+                        global_set.clone(),
+                    )?;
+                    cbb.push_constants(
+                        self.pipeline_outline.layout().clone(),
+                        0,
+                        OutlinePushConstants {
+                            color: outline.color.to_array(),
+                            width: outline.width,
+                        },
+                    )?;
+                    unsafe {
+                        cbb.draw_indexed(
+                            mesh.index_count,
+                            1,
+                            mesh.first_index,
+                            mesh.vertex_offset,
+                            instance as u32,
+                        )?;
+                    }
+                    cbb.bind_pipeline_graphics(pipeline.clone())?;
+                    cbb.bind_descriptor_sets(
+                        PipelineBindPoint::Graphics,
+                        pipeline.layout().clone(),
+                        0,
+                        sets.clone(),
+                    )?;
+                    unsafe {
+                        cbb.draw_indexed(
+                            mesh.index_count,
+                            1,
+                            mesh.first_index,
+                            mesh.vertex_offset,
+                            instance as u32,
+                        )?;
+                    }
                 }
             }
 
             cbb.end_render_pass(SubpassEndInfo::default())?;
+            Ok(())
+        }
 
-            let cb = cbb.build()?;
+        /// Halve the scene down to 1x1 and copy that texel out for auto exposure.
+        fn record_luminance(
+            &self,
+            cbb: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
+            slot: usize,
+            extent: [u32; 2],
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            let hdr_image = self.frames[slot]
+                .hdr
+                .as_ref()
+                .ok_or("luminance pass without an HDR target")?
+                .image
+                .clone();
+            let levels = hdr_image.mip_levels();
+            for level in 1..levels {
+                let mut blit = BlitImageInfo::images(hdr_image.clone(), hdr_image.clone());
+                blit.filter = Filter::Linear;
+                let region = &mut blit.regions[0];
+                region.src_subresource.mip_level = level - 1;
+                region.src_offsets[1] = mip_extent(extent, level - 1);
+                region.dst_subresource.mip_level = level;
+                region.dst_offsets[1] = mip_extent(extent, level);
+                cbb.blit_image(blit)?;
+            }
+            let mut copy = CopyImageToBufferInfo::image_buffer(
+                hdr_image.clone(),
+                self.frames[slot].luminance.clone(),
+            );
+            copy.regions[0].image_subresource.mip_level = levels - 1;
+            copy.regions[0].image_extent = [1, 1, 1];
+            cbb.copy_image_to_buffer(copy)?;
 
-            let start_future: Box<dyn GpuFuture> = self
-                .previous_frame_end
-                .take()
-                .unwrap_or_else(|| sync::now(device.clone()).boxed());
+            Ok(())
+        }
 
-            let execution = start_future
-                .join(acquire_future)
-                .then_execute(queue.clone(), cb)?
-                .then_swapchain_present(
-                    queue.clone(),
-                    SwapchainPresentInfo::swapchain_image_index(self.swapchain.clone(), image_i),
-                )
-                .then_signal_fence_and_flush();
+        /// Resolve the scene against the TAA history into the other history target; returns
+        /// the resolve for the tonemap pass to read instead of the scene.
+        fn record_taa(
+            &mut self,
+            cbb: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
+            slot: usize,
+            extent: [u32; 2],
+        ) -> Result<Arc<DescriptorSet>, Box<dyn std::error::Error>> {
+            let hdr = self.frames[slot]
+                .hdr
+                .as_ref()
+                .ok_or("TAA pass without an HDR target")?;
+            let (scene_set, motion_set) = (hdr.post_set.clone(), hdr.motion_set.clone());
+            if self
+                .taa_history
+                .as_ref()
+                .is_none_or(|history| history.targets.extent() != extent)
+            {
+                self.taa_history = Some(TaaHistory {
+                    targets: PingPongTargets::new(
+                        &self.context,
+                        &self.post_render_pass,
+                        &self.descriptor_set_allocator,
+                        &self.set_layouts.post,
+                        &self.post_sampler,
+                        extent,
+                    )?,
+                    latest: 0,
+                    valid: false,
+                });
+            }
+            let history = self.taa_history.as_mut().expect("created above");
+            let (src, dst) = (history.latest, 1 - history.latest);
+            begin_fullscreen_pass(cbb, history.targets.framebuffers[dst].clone(), extent)?;
+            cbb.bind_pipeline_graphics(self.pipeline_taa.clone())?;
+            cbb.bind_descriptor_sets(
+                PipelineBindPoint::Graphics,
+                self.pipeline_taa.layout().clone(),
+                0,
+                (scene_set, history.targets.sets[src].clone(), motion_set),
+            )?;
+            cbb.push_constants(
+                self.pipeline_taa.layout().clone(),
+                0,
+                TaaPushConstants {
+                    texel: [1.0 / extent[0] as f32, 1.0 / extent[1] as f32],
+                    history_weight: if history.valid {
+                        TAA_HISTORY_WEIGHT
+                    } else {
+                        0.0
+                    },
+                },
+            )?;
+            unsafe {
+                cbb.draw(3, 1, 0, 0)?;
+            }
+            cbb.end_render_pass(SubpassEndInfo::default())?;
+            history.latest = dst;
+            history.valid = true;
+            Ok(history.targets.sets[dst].clone())
+        }
 
-            match execution.map_err(Validated::unwrap) {
-                Ok(future) => {
-                    // Keep the future so resources can be cleaned up incrementally.
-                    self.previous_frame_end = Some(future.boxed());
-                }
-                Err(VulkanError::OutOfDate) => {
-                    self.recreate_swapchain = true;
-                    self.previous_frame_end = Some(sync::now(device).boxed());
+        /// Threshold the emissive attachment into a half-resolution target, blur it
+        /// horizontally into the other one and vertically back; returns the blurred glow.
+        fn record_bloom(
+            &mut self,
+            cbb: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
+            slot: usize,
+            extent: [u32; 2],
+            bloom: BloomSettings,
+        ) -> Result<Arc<DescriptorSet>, Box<dyn std::error::Error>> {
+            let emissive_set = self.frames[slot]
+                .hdr
+                .as_ref()
+                .ok_or("bloom pass without an HDR target")?
+                .emissive_set
+                .clone();
+            let targets = self.frames[slot].bloom_targets(
+                &self.context,
+                &self.post_render_pass,
+                &self.descriptor_set_allocator,
+                &self.set_layouts.post,
+                &self.post_sampler,
+                extent,
+            )?;
+            let half = bloom_extent(extent);
+            let texel = [1.0 / half[0] as f32, 1.0 / half[1] as f32];
+            let passes = [
+                (
+                    emissive_set,
+                    0,
+                    [1.0 / extent[0] as f32, 1.0 / extent[1] as f32],
+                    BLOOM_BRIGHT_PASS,
+                ),
+                (targets.sets[0].clone(), 1, [texel[0], 0.0], BLOOM_BLUR),
+                (targets.sets[1].clone(), 0, [0.0, texel[1]], BLOOM_BLUR),
+            ];
+            for (src, dst, texel, mode) in passes {
+                begin_fullscreen_pass(cbb, targets.framebuffers[dst].clone(), half)?;
+                cbb.bind_pipeline_graphics(self.pipeline_bloom.clone())?;
+                cbb.bind_descriptor_sets(
+                    PipelineBindPoint::Graphics,
+                    self.pipeline_bloom.layout().clone(),
+                    0,
+                    src,
+                )?;
+                cbb.push_constants(
+                    self.pipeline_bloom.layout().clone(),
+                    0,
+                    BloomPushConstants {
+                        texel,
+                        threshold: bloom.threshold,
+                        mode,
+                    },
+                )?;
+                unsafe {
+                    cbb.draw(3, 1, 0, 0)?;
                 }
-                Err(e) => {
-                    println!("[VulkanoRenderer] failed to flush future: {e}");
-                    self.previous_frame_end = Some(sync::now(device).boxed());
+                cbb.end_render_pass(SubpassEndInfo::default())?;
+            }
+            Ok(targets.sets[0].clone())
+        }
+
+        /// Expose, tonemap and grade `scene_set` (plus the bloom, if any) into swapchain image
+        /// `image_i`.
+        fn record_tonemap(
+            &self,
+            cbb: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
+            visual_world: &VisualWorld,
+            slot: usize,
+            image_i: u32,
+            scene_set: Arc<DescriptorSet>,
+            bloom_set: Option<Arc<DescriptorSet>>,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            let (bloom_set, bloom_intensity) = match bloom_set {
+                Some(set) => (set, visual_world.post_process().bloom.intensity.max(0.0)),
+                // Unused while the intensity is 0; any sampled HDR image fits the set.
+                None => (
+                    self.frames[slot]
+                        .hdr
+                        .as_ref()
+                        .ok_or("tonemap pass without an HDR target")?
+                        .emissive_set
+                        .clone(),
+                    0.0,
+                ),
+            };
+            let config = visual_world.renderer_config();
+            let mut bars = config.clear_color.to_array();
+            if self.encode_srgb {
+                for c in &mut bars[..3] {
+                    *c = linear_to_srgb(*c);
                 }
             }
+            begin_pass_in(
+                cbb,
+                self.framebuffers[image_i as usize].clone(),
+                config.letterbox(self.swapchain.image_extent()),
+                Some(ClearValue::from(bars)),
+            )?;
+            // Any 2D texture stands in for the LUT while grading is off.
+            let grading = visual_world
+                .color_grading()
+                .and_then(|g| Some((g, self.textures.get(&g.lut)?.view.clone())));
+            let (lut_size, lut_strength, lut_view) = match grading {
+                Some((g, view)) => (g.size as f32, g.strength.clamp(0.0, 1.0), view),
+                None => (
+                    1.0,
+                    0.0,
+                    self.textures
+                        .get(&self.default_white_texture)
+                        .ok_or("missing default texture")?
+                        .view
+                        .clone(),
+                ),
+            };
+            let lut_set = DescriptorSet::new(
+                self.descriptor_set_allocator.clone(),
+                self.set_layouts.post.clone(),
+                [WriteDescriptorSet::image_view_sampler(
+                    0,
+                    lut_view,
+                    self.post_sampler.clone(),
+                )],
+                [],
+            )?;
+            cbb.bind_pipeline_graphics(self.pipeline_tonemap.clone())?;
+            cbb.bind_descriptor_sets(
+                PipelineBindPoint::Graphics,
+                self.pipeline_tonemap.layout().clone(),
+                0,
+                (scene_set, lut_set, bloom_set),
+            )?;
+            cbb.push_constants(
+                self.pipeline_tonemap.layout().clone(),
+                0,
+                TonemapPushConstants {
+                    exposure: visual_world.exposure().value(),
+                    encode_srgb: u32::from(self.encode_srgb),
+                    lut_size,
+                    lut_strength,
+                    bloom_intensity,
+                },
+            )?;
+            unsafe {
+                cbb.draw(3, 1, 0, 0)?;
+            }
+            cbb.end_render_pass(SubpassEndInfo::default())?;
+            Ok(())
+        }
+
+        /// Toon pipeline variant for `key`, built on first use.
+        fn toon_pipeline(
+            &mut self,
+            key: MaterialPipelineKey,
+        ) -> Result<Arc<GraphicsPipeline>, Box<dyn std::error::Error>> {
+            if let Some(pipeline) = self.pipelines_toon_mesh.get(&key) {
+                return Ok(pipeline.clone());
+            }
+            let pipeline =
+                material_pipeline(self.context.device(), &self.pipeline_toon_mesh_ci, key)?;
+            self.pipelines_toon_mesh.insert(key, pipeline.clone());
+            Ok(pipeline)
+        }
+
+        /// Fullscreen gradient or skybox under everything else; solid backgrounds are just the
+        /// clear color.
+        fn draw_background(
+            &self,
+            cbb: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>,
+            visual_world: &VisualWorld,
+            background: VisualBackground,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            let mut push = BackgroundPushConstants {
+                inv_view_proj: Mat4::IDENTITY.to_cols_array_2d(),
+                color_start: [0.0; 4],
+                color_end: [0.0; 4],
+                direction: [0.0, 1.0],
+                mode: BACKGROUND_GRADIENT,
+                _pad0: 0,
+            };
+            let cubemap = match background {
+                VisualBackground::Clear | VisualBackground::Solid(_) => return Ok(()),
+                VisualBackground::GradientXY {
+                    start,
+                    end,
+                    direction,
+                } => {
+                    push.color_start = start;
+                    push.color_end = end;
+                    push.direction = direction;
+                    self.default_cubemap.clone()
+                }
+                VisualBackground::Skybox { cubemap } => {
+                    let Some(texture) = self.textures.get(&cubemap) else {
+                        return Ok(());
+                    };
+                    // Only the camera's rotation: the sky is infinitely far away.
+                    let mut view = Mat4::from_cols_array_2d(&visual_world.camera_view());
+                    view.w_axis = glam::Vec4::W;
+                    let proj = Mat4::from_cols_array_2d(&visual_world.camera_proj());
+                    let inv_view_proj = (proj * view).inverse();
+                    if inv_view_proj.is_finite() {
+                        push.inv_view_proj = inv_view_proj.to_cols_array_2d();
+                    }
+                    push.mode = BACKGROUND_SKYBOX;
+                    texture.view.clone()
+                }
+            };
 
+            let set = DescriptorSet::new(
+                self.descriptor_set_allocator.clone(),
+                self.set_layouts.post.clone(),
+                [WriteDescriptorSet::image_view_sampler(
+                    0,
+                    cubemap,
+                    self.sampler.clone(),
+                )],
+                [],
+            )?;
+            cbb.bind_pipeline_graphics(self.pipeline_background.clone())?;
+            cbb.bind_descriptor_sets(
+                PipelineBindPoint::Graphics,
+                self.pipeline_background.layout().clone(),
+                0,
+                set,
+            )?;
+            cbb.push_constants(self.pipeline_background.layout().clone(), 0, push)?;
+            unsafe {
+                cbb.draw(3, 1, 0, 0)?;
+            }
             Ok(())
         }
 
@@ -819,23 +2533,74 @@ mod vulkano_backend {
             if self.textures.contains_key(&handle) {
                 return Ok(());
             }
+            let texture = Self::upload_image(
+                &self.context,
+                &self.command_buffer_allocator,
+                rgba,
+                [width, height],
+                TextureKind::D2,
+            )?;
+            self.insert_texture(handle, texture);
+            Ok(())
+        }
+
+        /// Upload six square faces stored one after another (+X, -X, +Y, -Y, +Z, -Z).
+        pub fn upload_cubemap_rgba8(
+            &mut self,
+            handle: TextureHandle,
+            faces: &[u8],
+            face_size: u32,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            if self.textures.contains_key(&handle) {
+                return Ok(());
+            }
+            let texture = Self::upload_image(
+                &self.context,
+                &self.command_buffer_allocator,
+                faces,
+                [face_size, face_size],
+                TextureKind::Cube,
+            )?;
+            self.insert_texture(handle, texture);
+            Ok(())
+        }
 
+        fn insert_texture(&mut self, handle: TextureHandle, texture: VulkanoGpuTexture) {
+            self.memory
+                .allocate(GpuMemoryCategory::Textures, texture.bytes);
+            self.memory.publish();
+            self.textures.insert(handle, texture);
+        }
+
+        /// Upload sRGB RGBA8 pixels into a sampled image and wait for the copy. Cubemaps take
+        /// six faces one after another and get a full mip chain, since reflections and
+        /// skyboxes sample them at any scale.
+        fn upload_image(
+            context: &VulkanoContext,
+            command_buffer_allocator: &Arc<StandardCommandBufferAllocator>,
+            rgba: &[u8],
+            [width, height]: [u32; 2],
+            kind: TextureKind,
+        ) -> Result<VulkanoGpuTexture, Box<dyn std::error::Error>> {
+            let (layers, mip_levels) = match kind {
+                TextureKind::D2 => (1, 1),
+                TextureKind::Cube => (6, mip_levels([width, height])),
+            };
             if width == 0 || height == 0 {
-                return Err("texture has zero size".into());
+                return Err(RendererError::EmptyTexture { width, height }.into());
             }
 
-            let expected_len = width as usize * height as usize * 4;
+            let expected_len = width as usize * height as usize * 4 * layers as usize;
             if rgba.len() != expected_len {
-                return Err(format!(
-                    "texture rgba length mismatch: got={}, expected={}",
-                    rgba.len(),
-                    expected_len
-                )
+                return Err(RendererError::TextureSizeMismatch {
+                    got: rgba.len(),
+                    expected: expected_len,
+                }
                 .into());
             }
 
-            let memory_allocator = self.context.memory_allocator().clone();
-            let queue = self.context.graphics_queue().clone();
+            let memory_allocator = context.memory_allocator().clone();
+            let queue = context.graphics_queue().clone();
 
             let staging = Buffer::from_iter(
                 memory_allocator.clone(),
@@ -851,13 +2616,24 @@ mod vulkano_backend {
                 rgba.iter().copied(),
             )?;
 
+            let cube = kind == TextureKind::Cube;
             let image = Image::new(
                 memory_allocator,
                 ImageCreateInfo {
+                    flags: if cube {
+                        ImageCreateFlags::CUBE_COMPATIBLE
+                    } else {
+                        ImageCreateFlags::empty()
+                    },
                     image_type: ImageType::Dim2d,
-                    format: Format::R8G8B8A8_UNORM,
+                    // sRGB-encoded pixels, sampled as linear for HDR lighting.
+                    format: Format::R8G8B8A8_SRGB,
                     extent: [width, height, 1],
-                    usage: ImageUsage::TRANSFER_DST | ImageUsage::SAMPLED,
+                    array_layers: layers,
+                    mip_levels,
+                    usage: ImageUsage::TRANSFER_SRC
+                        | ImageUsage::TRANSFER_DST
+                        | ImageUsage::SAMPLED,
                     ..Default::default()
                 },
                 AllocationCreateInfo {
@@ -867,12 +2643,27 @@ mod vulkano_backend {
             )?;
 
             let mut cbb = AutoCommandBufferBuilder::primary(
-                self.command_buffer_allocator.clone(),
+                command_buffer_allocator.clone(),
                 queue.queue_family_index(),
                 CommandBufferUsage::OneTimeSubmit,
             )?;
 
+            // Layers are packed one after another, which is the copy's default buffer layout.
             cbb.copy_buffer_to_image(CopyBufferToImageInfo::buffer_image(staging, image.clone()))?;
+            // Each blit halves all layers of the previous mip at once.
+            let mut bytes = rgba.len() as u64;
+            for level in 1..mip_levels {
+                let mut blit = BlitImageInfo::images(image.clone(), image.clone());
+                blit.filter = Filter::Linear;
+                let region = &mut blit.regions[0];
+                region.src_subresource.mip_level = level - 1;
+                region.src_offsets[1] = mip_extent([width, height], level - 1);
+                region.dst_subresource.mip_level = level;
+                let dst = mip_extent([width, height], level);
+                region.dst_offsets[1] = dst;
+                cbb.blit_image(blit)?;
+                bytes += u64::from(dst[0] * dst[1] * 4 * layers);
+            }
 
             let cb = cbb.build()?;
 
@@ -880,10 +2671,19 @@ mod vulkano_backend {
                 .then_signal_fence_and_flush()?
                 .wait(None)?;
 
-            let view = ImageView::new_default(image)
-                .map_err(|e| -> Box<dyn std::error::Error> { format!("{e:?}").into() })?;
-            self.textures.insert(handle, VulkanoGpuTexture { view });
-            Ok(())
+            let view = if cube {
+                ImageView::new(
+                    image.clone(),
+                    ImageViewCreateInfo {
+                        view_type: ImageViewType::Cube,
+                        ..ImageViewCreateInfo::from_image(&image)
+                    },
+                )
+            } else {
+                ImageView::new_default(image)
+            }
+            .map_err(|e| -> Box<dyn std::error::Error> { format!("{e:?}").into() })?;
+            Ok(VulkanoGpuTexture { view, kind, bytes })
         }
 
         pub fn upload_mesh(
@@ -896,10 +2696,10 @@ mod vulkano_backend {
             }
 
             if mesh.vertices.is_empty() {
-                return Err("mesh has no vertices".into());
+                return Err(RendererError::EmptyMesh("vertices").into());
             }
             if mesh.indices_u32.is_empty() {
-                return Err("mesh has no indices".into());
+                return Err(RendererError::EmptyMesh("indices").into());
             }
 
             let memory_allocator = self.context.memory_allocator().clone();
@@ -934,42 +2734,29 @@ mod vulkano_backend {
                 mesh.indices_u32.iter().copied(),
             )?;
 
-            // Device-local destination buffers.
-            let vertices_dst = Buffer::new_slice::<CpuVertex>(
-                memory_allocator.clone(),
-                BufferCreateInfo {
-                    usage: BufferUsage::VERTEX_BUFFER | BufferUsage::TRANSFER_DST,
-                    ..Default::default()
-                },
-                AllocationCreateInfo {
-                    memory_type_filter: MemoryTypeFilter::PREFER_DEVICE,
-                    ..Default::default()
-                },
+            let (first_vertex, first_index) = self.alloc_mesh_space(
                 mesh.vertices.len() as DeviceSize,
-            )?;
-
-            let indices_dst = Buffer::new_slice::<u32>(
-                memory_allocator.clone(),
-                BufferCreateInfo {
-                    usage: BufferUsage::INDEX_BUFFER | BufferUsage::TRANSFER_DST,
-                    ..Default::default()
-                },
-                AllocationCreateInfo {
-                    memory_type_filter: MemoryTypeFilter::PREFER_DEVICE,
-                    ..Default::default()
-                },
                 mesh.indices_u32.len() as DeviceSize,
             )?;
-
-            // Copy staging -> device-local.
+            let arena = self.mesh_arena.as_mut().ok_or("mesh arena missing")?;
+            let vertices_dst = arena
+                .vertices
+                .clone()
+                .slice(first_vertex..first_vertex + mesh.vertices.len() as DeviceSize);
+            let indices_dst = arena
+                .indices
+                .clone()
+                .slice(first_index..first_index + mesh.indices_u32.len() as DeviceSize);
+
+            // Copy staging -> arena ranges.
             let mut cbb = AutoCommandBufferBuilder::primary(
                 self.command_buffer_allocator.clone(),
                 queue.queue_family_index(),
                 CommandBufferUsage::OneTimeSubmit,
             )?;
 
-            cbb.copy_buffer(CopyBufferInfo::buffers(vertices_src, vertices_dst.clone()))?;
-            cbb.copy_buffer(CopyBufferInfo::buffers(indices_src, indices_dst.clone()))?;
+            cbb.copy_buffer(CopyBufferInfo::buffers(vertices_src, vertices_dst))?;
+            cbb.copy_buffer(CopyBufferInfo::buffers(indices_src, indices_dst))?;
 
             let cb = cbb.build()?;
 
@@ -980,43 +2767,229 @@ mod vulkano_backend {
             self.meshes.insert(
                 handle,
                 VulkanoGpuMesh {
-                    vertices: vertices_dst,
-                    indices: indices_dst,
+                    vertex_offset: i32::try_from(first_vertex)?,
+                    vertex_count: u32::try_from(mesh.vertices.len())?,
+                    first_index: u32::try_from(first_index)?,
                     index_count: mesh.index_count(),
                 },
             );
+            self.publish_mesh_memory();
+
+            Ok(())
+        }
+
+        /// Forget a released mesh; its arena ranges are reused once the frames in flight are
+        /// done with them.
+        pub fn release_mesh(&mut self, handle: MeshHandle) {
+            let Some(mesh) = self.meshes.remove(&handle) else {
+                return;
+            };
+            if let Some(arena) = self.mesh_arena.as_mut() {
+                let first_vertex = mesh.vertex_offset as DeviceSize;
+                let first_index = DeviceSize::from(mesh.first_index);
+                arena.retired.push((
+                    first_vertex..first_vertex + DeviceSize::from(mesh.vertex_count),
+                    first_index..first_index + DeviceSize::from(mesh.index_count),
+                ));
+            }
+            self.publish_mesh_memory();
+        }
+
+        /// Find room in the arena for `vertices` and `indices` elements: a free range, else a
+        /// retired one (after waiting for the frames in flight), else a grown arena.
+        fn alloc_mesh_space(
+            &mut self,
+            vertices: DeviceSize,
+            indices: DeviceSize,
+        ) -> Result<(DeviceSize, DeviceSize), Box<dyn std::error::Error>> {
+            if let Some(found) = self
+                .mesh_arena
+                .as_mut()
+                .and_then(|a| a.alloc(vertices, indices))
+            {
+                return Ok(found);
+            }
+            if self
+                .mesh_arena
+                .as_ref()
+                .is_some_and(|a| !a.retired.is_empty())
+            {
+                for frame in &self.frames {
+                    if let Some(fence) = &frame.fence {
+                        fence.wait(None)?;
+                    }
+                }
+                let arena = self.mesh_arena.as_mut().ok_or("mesh arena missing")?;
+                arena.reclaim_retired();
+                if let Some(found) = arena.alloc(vertices, indices) {
+                    return Ok(found);
+                }
+            }
+            self.grow_mesh_arena(vertices, indices)?;
+            self.mesh_arena
+                .as_mut()
+                .and_then(|a| a.alloc(vertices, indices))
+                .ok_or_else(|| "mesh arena full after growing".into())
+        }
+
+        /// Replace the arena by one with room for `vertices` and `indices` more elements past
+        /// what it uses (doubling, and copying what it holds).
+        fn grow_mesh_arena(
+            &mut self,
+            vertices: DeviceSize,
+            indices: DeviceSize,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            let (vertex_cap, index_cap, used_vertices, used_indices) = match &self.mesh_arena {
+                Some(a) => (
+                    a.vertices.len(),
+                    a.indices.len(),
+                    a.vertex_ranges.used(),
+                    a.index_ranges.used(),
+                ),
+                None => (0, 0, 0, 0),
+            };
+            let needed_vertices = used_vertices + vertices;
+            let needed_indices = used_indices + indices;
+
+            let grow = |cap: DeviceSize, needed: DeviceSize, initial: DeviceSize| {
+                let mut cap = cap.max(initial);
+                while cap < needed {
+                    cap *= 2;
+                }
+                cap
+            };
+            let memory_allocator = self.context.memory_allocator().clone();
+            let new_vertices = Buffer::new_slice::<CpuVertex>(
+                memory_allocator.clone(),
+                BufferCreateInfo {
+                    usage: BufferUsage::VERTEX_BUFFER
+                        | BufferUsage::TRANSFER_DST
+                        | BufferUsage::TRANSFER_SRC,
+                    ..Default::default()
+                },
+                AllocationCreateInfo {
+                    memory_type_filter: MemoryTypeFilter::PREFER_DEVICE,
+                    ..Default::default()
+                },
+                grow(vertex_cap, needed_vertices, INITIAL_ARENA_VERTICES),
+            )?;
+            let new_indices = Buffer::new_slice::<u32>(
+                memory_allocator,
+                BufferCreateInfo {
+                    usage: BufferUsage::INDEX_BUFFER
+                        | BufferUsage::TRANSFER_DST
+                        | BufferUsage::TRANSFER_SRC,
+                    ..Default::default()
+                },
+                AllocationCreateInfo {
+                    memory_type_filter: MemoryTypeFilter::PREFER_DEVICE,
+                    ..Default::default()
+                },
+                grow(index_cap, needed_indices, INITIAL_ARENA_INDICES),
+            )?;
 
+            // Frames in flight keep the old buffers alive until they finish.
+            let old = self.mesh_arena.take();
+            if let Some(old) = &old
+                && used_vertices > 0
+            {
+                let queue = self.context.graphics_queue().clone();
+                let mut cbb = AutoCommandBufferBuilder::primary(
+                    self.command_buffer_allocator.clone(),
+                    queue.queue_family_index(),
+                    CommandBufferUsage::OneTimeSubmit,
+                )?;
+                cbb.copy_buffer(CopyBufferInfo::buffers(
+                    old.vertices.clone().slice(0..used_vertices),
+                    new_vertices.clone().slice(0..used_vertices),
+                ))?;
+                cbb.copy_buffer(CopyBufferInfo::buffers(
+                    old.indices.clone().slice(0..used_indices),
+                    new_indices.clone().slice(0..used_indices),
+                ))?;
+                cbb.build()?
+                    .execute(queue)?
+                    .then_signal_fence_and_flush()?
+                    .wait(None)?;
+            }
+
+            let (mut vertex_ranges, mut index_ranges, retired) = match old {
+                Some(old) => (old.vertex_ranges, old.index_ranges, old.retired),
+                None => Default::default(),
+            };
+            vertex_ranges.grow(new_vertices.len());
+            index_ranges.grow(new_indices.len());
+            self.mesh_arena = Some(MeshArena {
+                vertices: new_vertices,
+                indices: new_indices,
+                vertex_ranges,
+                index_ranges,
+                retired,
+            });
+            self.publish_mesh_memory();
             Ok(())
         }
+
+        /// Meshes are accounted as the arena's capacity, not the sum of the meshes in it.
+        fn publish_mesh_memory(&mut self) {
+            let bytes = self
+                .mesh_arena
+                .as_ref()
+                .map_or(0, |a| a.vertices.size() + a.indices.size());
+            self.memory.set(
+                GpuMemoryCategory::Meshes,
+                GpuAllocations {
+                    bytes,
+                    count: self.meshes.len(),
+                },
+            );
+            self.memory.publish();
+        }
     }
 }
 
 /// Vulkano-only renderer.
 pub struct VulkanoRenderer {
     vulkano: Option<vulkano_backend::VulkanoState>,
-    next_mesh_handle: u32,
-    next_texture_handle: u32,
+    mesh_handles: HandleTable<MeshHandle>,
+    texture_handles: HandleTable<TextureHandle>,
     did_enable_present_loop_log: bool,
+    frames_in_flight: usize,
+    last_frame_time: Option<Duration>,
 }
 
 impl VulkanoRenderer {
     pub fn new() -> Self {
+        let mut texture_handles = HandleTable::new();
+        // Reserve handle 0 for the default white texture.
+        texture_handles
+            .alloc()
+            .expect("an empty table has free slots");
         Self {
             vulkano: None,
-            next_mesh_handle: 0,
-            // Reserve handle 0 for the default white texture.
-            next_texture_handle: 1,
+            mesh_handles: HandleTable::new(),
+            texture_handles,
             did_enable_present_loop_log: false,
+            frames_in_flight: DEFAULT_FRAMES_IN_FLIGHT,
+            last_frame_time: None,
         }
     }
 
-    pub fn init_for_window(
-        &mut self,
-        window: &Arc<Window>,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    /// Frames recorded ahead of the GPU (at least 1). More hides CPU spikes better at the
+    /// cost of latency and one more copy of the per-frame buffers. Takes effect at
+    /// `init_for_window`.
+    pub fn with_frames_in_flight(mut self, frames: usize) -> Self {
+        self.frames_in_flight = frames.max(1);
+        self
+    }
+
+    pub fn init_for_window(&mut self, window: &Arc<Window>) -> Result<(), RendererError> {
         if self.vulkano.is_none() {
-            self.vulkano = Some(vulkano_backend::VulkanoState::new(window.clone())?);
-            println!("[VulkanoRenderer] Vulkano swapchain/render-pass initialized");
+            self.vulkano = Some(vulkano_backend::VulkanoState::new(
+                window.clone(),
+                self.frames_in_flight,
+            )?);
+            crate::log_info!("Vulkano swapchain/render-pass initialized");
         }
 
         Ok(())
@@ -1029,42 +3002,79 @@ impl VulkanoRenderer {
         }
     }
 
-    pub fn upload_mesh(
-        &mut self,
-        mesh: &CpuMesh,
-    ) -> Result<MeshHandle, Box<dyn std::error::Error>> {
+    pub fn upload_mesh(&mut self, mesh: &CpuMesh) -> Result<MeshHandle, RendererError> {
         let Some(vulkano) = self.vulkano.as_mut() else {
-            return Err("VulkanoRenderer not initialized (call init_for_window first)".into());
+            return Err(RendererError::NotInitialized);
         };
 
-        let handle = MeshHandle(self.next_mesh_handle);
-        self.next_mesh_handle = self.next_mesh_handle.wrapping_add(1);
-
-        vulkano.upload_mesh(handle, mesh)?;
+        let handle = self.mesh_handles.alloc()?;
+        if let Err(e) = vulkano.upload_mesh(handle, mesh) {
+            let _ = self.mesh_handles.release(handle);
+            return Err(e.into());
+        }
         Ok(handle)
     }
 
     pub fn render_visual_world(
         &mut self,
         visual_world: &mut VisualWorld,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    ) -> Result<(), RendererError> {
         let Some(vulkano) = self.vulkano.as_mut() else {
-            return Err("VulkanoRenderer not initialized (call init_for_window first)".into());
+            return Err(RendererError::NotInitialized);
         };
 
         if !self.did_enable_present_loop_log {
             self.did_enable_present_loop_log = true;
-            println!("[VulkanoRenderer] Present loop enabled");
+            crate::log_info!("present loop enabled");
         }
 
-        vulkano.render_visual_world(visual_world)
+        let start = Instant::now();
+        vulkano.render_visual_world(visual_world)?;
+        self.last_frame_time = Some(start.elapsed());
+        Ok(())
+    }
+
+    /// Kind of an uploaded texture; fails for handles that were released or never uploaded.
+    pub fn texture_kind(&self, handle: TextureHandle) -> Result<TextureKind, RendererError> {
+        let Some(vulkano) = self.vulkano.as_ref() else {
+            return Err(RendererError::NotInitialized);
+        };
+        self.texture_handles.validate(handle)?;
+        vulkano
+            .textures
+            .get(&handle)
+            .map(|t| t.kind)
+            .ok_or(RendererError::UnknownHandle {
+                kind: "texture",
+                index: handle.index(),
+            })
+    }
+
+    /// Wall time of the last `render_visual_world`. It includes waiting for the GPU to free a
+    /// frame slot, so it tracks GPU frame time once the GPU is the bottleneck.
+    pub fn last_frame_time(&self) -> Option<Duration> {
+        self.last_frame_time
+    }
+
+    /// Device memory held by this renderer; all zero before `init_for_window`.
+    pub fn memory_usage(&self) -> GpuMemoryUsage {
+        self.vulkano.as_ref().map(|v| v.memory).unwrap_or_default()
     }
 }
 
 impl MeshUploader for VulkanoRenderer {
-    fn upload_mesh(&mut self, mesh: &CpuMesh) -> Result<MeshHandle, Box<dyn std::error::Error>> {
+    fn upload_mesh(&mut self, mesh: &CpuMesh) -> Result<MeshHandle, RendererError> {
         self.upload_mesh(mesh)
     }
+
+    fn release_mesh(&mut self, handle: MeshHandle) -> Result<(), RendererError> {
+        let Some(vulkano) = self.vulkano.as_mut() else {
+            return Err(RendererError::NotInitialized);
+        };
+        self.mesh_handles.release(handle)?;
+        vulkano.release_mesh(handle);
+        Ok(())
+    }
 }
 
 impl TextureUploader for VulkanoRenderer {
@@ -1073,15 +3083,51 @@ impl TextureUploader for VulkanoRenderer {
         rgba: &[u8],
         width: u32,
         height: u32,
-    ) -> Result<TextureHandle, Box<dyn std::error::Error>> {
+    ) -> Result<TextureHandle, RendererError> {
         let Some(vulkano) = self.vulkano.as_mut() else {
-            return Err("VulkanoRenderer not initialized (call init_for_window first)".into());
+            return Err(RendererError::NotInitialized);
         };
 
-        let handle = TextureHandle(self.next_texture_handle);
-        self.next_texture_handle = self.next_texture_handle.wrapping_add(1);
+        let handle = self.texture_handles.alloc()?;
+        if let Err(e) = vulkano.upload_texture_rgba8(handle, rgba, width, height) {
+            let _ = self.texture_handles.release(handle);
+            return Err(e.into());
+        }
+        Ok(handle)
+    }
+
+    fn upload_cubemap_rgba8(
+        &mut self,
+        faces: &[u8],
+        face_size: u32,
+    ) -> Result<TextureHandle, RendererError> {
+        let Some(vulkano) = self.vulkano.as_mut() else {
+            return Err(RendererError::NotInitialized);
+        };
 
-        vulkano.upload_texture_rgba8(handle, rgba, width, height)?;
+        let handle = self.texture_handles.alloc()?;
+        if let Err(e) = vulkano.upload_cubemap_rgba8(handle, faces, face_size) {
+            let _ = self.texture_handles.release(handle);
+            return Err(e.into());
+        }
         Ok(handle)
     }
+
+    fn release_texture(&mut self, handle: TextureHandle) -> Result<(), RendererError> {
+        let Some(vulkano) = self.vulkano.as_mut() else {
+            return Err(RendererError::NotInitialized);
+        };
+        if handle == vulkano.default_white_texture {
+            return Ok(());
+        }
+        self.texture_handles.release(handle)?;
+        // Frames in flight keep their own reference to the image view.
+        if let Some(texture) = vulkano.textures.remove(&handle) {
+            vulkano
+                .memory
+                .free(GpuMemoryCategory::Textures, texture.bytes);
+            vulkano.memory.publish();
+        }
+        Ok(())
+    }
 }
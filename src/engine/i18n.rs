@@ -0,0 +1,264 @@
+//! Localization: string tables per locale, and the `tr!` lookup.
+//!
+//! Tables are JSON files named after their locale under `assets/locales/` (`en.json`,
+//! `de.json`, ...), flat or nested; nested keys join with `.`:
+//!
+//! ```json
+//! {"menu": {"start": "Start game", "greeting": "Hello, {name}!"}}
+//! ```
+//!
+//! `Universe` loads the directory at startup and picks `$LITTLECAT_LOCALE` if it names one of
+//! them. Game code looks strings up through the process-wide current locale:
+//!
+//! ```ignore
+//! let label = tr!("menu.start");
+//! let hello = tr!("menu.greeting", name = player_name);
+//! ```
+//!
+//! A key missing from the current locale falls back to the fallback locale (`en`), then to the
+//! key itself, so untranslated strings show up as their keys. `set_locale` (REPL `locale de`)
+//! switches every lookup at once, including `TextComponent`s showing a key.
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::Path;
+use std::sync::{LazyLock, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::engine::AssetError;
+use crate::engine::ecs::codec::Value;
+
+/// Directory of the string tables, relative to the asset root.
+pub const LOCALES_DIR: &str = "assets/locales";
+
+/// Environment variable choosing the locale at startup.
+pub const LOCALE_ENV_VAR: &str = "LITTLECAT_LOCALE";
+
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Strings of one locale by key.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StringTable {
+    strings: BTreeMap<String, String>,
+}
+
+impl StringTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Flatten a JSON object of strings and nested objects.
+    pub fn from_json(value: &Value) -> Result<Self, String> {
+        let mut table = Self::new();
+        table.add_json("", value)?;
+        Ok(table)
+    }
+
+    fn add_json(&mut self, prefix: &str, value: &Value) -> Result<(), String> {
+        let Value::Object(map) = value else {
+            return Err(format!("'{prefix}' is not an object"));
+        };
+        for (key, value) in map {
+            let key = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{prefix}.{key}")
+            };
+            match value {
+                Value::String(s) => {
+                    self.strings.insert(key, s.clone());
+                }
+                Value::Object(_) => self.add_json(&key, value)?,
+                _ => return Err(format!("'{key}' is neither a string nor an object")),
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.strings.get(key).map(String::as_str)
+    }
+
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.strings.insert(key.into(), value.into());
+    }
+
+    /// Add `other`'s strings, replacing those with the same key.
+    pub fn extend(&mut self, other: StringTable) {
+        self.strings.extend(other.strings);
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.strings.keys().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
+
+/// Every loaded table and the locale lookups use.
+#[derive(Debug, Clone)]
+pub struct Localization {
+    tables: BTreeMap<String, StringTable>,
+    locale: String,
+    fallback: String,
+    /// Bumped whenever lookups may give different results.
+    generation: u64,
+}
+
+impl Default for Localization {
+    fn default() -> Self {
+        Self {
+            tables: BTreeMap::new(),
+            locale: DEFAULT_LOCALE.to_string(),
+            fallback: DEFAULT_LOCALE.to_string(),
+            generation: 0,
+        }
+    }
+}
+
+impl Localization {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Merge `table` into the locale's strings.
+    pub fn add_table(&mut self, locale: impl Into<String>, table: StringTable) {
+        self.tables.entry(locale.into()).or_default().extend(table);
+        self.generation += 1;
+    }
+
+    /// Merge every `<locale>.json` in `dir`. Returns the locales read.
+    pub fn load_dir(&mut self, dir: impl AsRef<Path>) -> Result<Vec<String>, AssetError> {
+        let dir = dir.as_ref();
+        let entries = std::fs::read_dir(dir).map_err(|e| AssetError::io(dir, e))?;
+        let mut paths: Vec<_> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|e| e == "json"))
+            .collect();
+        paths.sort();
+        let mut locales = Vec::with_capacity(paths.len());
+        for path in paths {
+            let Some(locale) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            let bytes = std::fs::read(&path).map_err(|e| AssetError::io(&path, e))?;
+            let table =
+                StringTable::from_json(&serde_json::from_slice(&bytes)?).map_err(|message| {
+                    AssetError::StringTable {
+                        path: path.clone(),
+                        message,
+                    }
+                })?;
+            self.add_table(locale, table);
+            locales.push(locale.to_string());
+        }
+        Ok(locales)
+    }
+
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    /// Switch lookups to `locale`; `false` (and no change) if no table for it is loaded.
+    pub fn set_locale(&mut self, locale: &str) -> bool {
+        if !self.tables.contains_key(locale) {
+            return false;
+        }
+        if self.locale != locale {
+            self.locale = locale.to_string();
+            self.generation += 1;
+        }
+        true
+    }
+
+    pub fn fallback(&self) -> &str {
+        &self.fallback
+    }
+
+    pub fn set_fallback(&mut self, locale: impl Into<String>) {
+        self.fallback = locale.into();
+        self.generation += 1;
+    }
+
+    /// Loaded locales, sorted.
+    pub fn locales(&self) -> impl Iterator<Item = &str> {
+        self.tables.keys().map(String::as_str)
+    }
+
+    pub fn table(&self, locale: &str) -> Option<&StringTable> {
+        self.tables.get(locale)
+    }
+
+    /// Changes whenever a lookup may give a different result (tables added, locale switched):
+    /// something caching translated text redoes it when this moves.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// `key` in the current locale, else in the fallback one.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        [&self.locale, &self.fallback]
+            .into_iter()
+            .find_map(|locale| self.tables.get(locale)?.get(key))
+    }
+
+    /// `key` translated, or the key itself if no table has it.
+    pub fn tr(&self, key: &str) -> String {
+        self.get(key).unwrap_or(key).to_string()
+    }
+
+    /// `tr` with `{name}` placeholders replaced; unknown placeholders stay as they are.
+    pub fn tr_args(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let mut out = self.tr(key);
+        for (name, value) in args {
+            out = out.replace(&format!("{{{name}}}"), &value.to_string());
+        }
+        out
+    }
+}
+
+static CURRENT: LazyLock<RwLock<Localization>> = LazyLock::new(Default::default);
+
+/// The process-wide localization `tr!` reads.
+pub fn current() -> RwLockReadGuard<'static, Localization> {
+    // A panic while holding the lock must not take translations down with it.
+    CURRENT.read().unwrap_or_else(|e| e.into_inner())
+}
+
+pub fn current_mut() -> RwLockWriteGuard<'static, Localization> {
+    CURRENT.write().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Switch the current locale; `false` if it isn't loaded.
+pub fn set_locale(locale: &str) -> bool {
+    current_mut().set_locale(locale)
+}
+
+pub fn tr(key: &str) -> String {
+    current().tr(key)
+}
+
+pub fn tr_args(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    current().tr_args(key, args)
+}
+
+/// Translate a key in the current locale, with optional `{name}` arguments:
+/// `tr!("menu.start")`, `tr!("hud.score", score = points)`.
+#[macro_export]
+macro_rules! tr {
+    ($key:expr $(,)?) => {
+        $crate::engine::i18n::tr($key)
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::engine::i18n::tr_args(
+            $key,
+            &[$((stringify!($name), &$value as &dyn ::std::fmt::Display)),+],
+        )
+    };
+}
@@ -0,0 +1,58 @@
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::engine::ecs::component::TextComponent;
+    use crate::engine::i18n::{self, Localization, StringTable};
+
+    #[test]
+    fn lookups_fall_back_to_the_fallback_locale_then_the_key() {
+        let dir = std::env::temp_dir().join(format!("little-cat-locales-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let en = json!({"menu": {"start": "Start", "greeting": "Hello, {name}!"}, "quit": "Quit"});
+        std::fs::write(dir.join("en.json"), en.to_string()).unwrap();
+        std::fs::write(dir.join("de.json"), r#"{"menu": {"start": "Los"}}"#).unwrap();
+
+        let mut l10n = Localization::new();
+        assert_eq!(l10n.load_dir(&dir).unwrap(), ["de", "en"]);
+        assert_eq!(l10n.table("en").unwrap().len(), 3);
+        assert!(!l10n.set_locale("fr"));
+        assert!(l10n.set_locale("de"));
+        assert_eq!(l10n.tr("menu.start"), "Los");
+        assert_eq!(l10n.tr("quit"), "Quit", "from the fallback locale");
+        assert_eq!(l10n.tr("menu.missing"), "menu.missing");
+        assert_eq!(
+            l10n.tr_args("menu.greeting", &[("name", &"Mia")]),
+            "Hello, Mia!"
+        );
+
+        std::fs::write(dir.join("bad.json"), r#"{"menu": 3}"#).unwrap();
+        let err = Localization::new().load_dir(&dir).unwrap_err();
+        assert!(err.to_string().contains("'menu'"), "{err}");
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn text_components_follow_the_current_locale() {
+        // The only test touching the process-wide locale; keys are unique to it.
+        let mut table = StringTable::new();
+        table.insert("i18n_test.score", "Score: {points}");
+        table.insert("i18n_test.title", "Little Cat");
+        i18n::current_mut().add_table("en", table);
+        let mut table = StringTable::new();
+        table.insert("i18n_test.title", "Kleine Katze");
+        i18n::current_mut().add_table("i18n-test", table);
+
+        let title = TextComponent::localized("i18n_test.title");
+        assert_eq!(title.display(), "Little Cat");
+        assert_eq!(crate::tr!("i18n_test.score", points = 12), "Score: 12");
+
+        let before = i18n::current().generation();
+        assert!(i18n::set_locale("i18n-test"));
+        assert_ne!(i18n::current().generation(), before);
+        assert_eq!(title.display(), "Kleine Katze");
+        assert_eq!(crate::tr!("i18n_test.score", points = 3), "Score: 3");
+        assert_eq!(TextComponent::literal("raw").display(), "raw");
+        assert!(i18n::set_locale(i18n::DEFAULT_LOCALE));
+    }
+}
@@ -0,0 +1,325 @@
+//! Recording and playback of user input, for automated demos and smoke tests
+//! (`UserInput::record` / `UserInput::playback`, `--record` / `--replay` on the player).
+//!
+//! A recording is a JSON Lines file with one object per frame that had input, keyed by the
+//! frame index since recording started:
+//!
+//! ```json
+//! {"frame":3,"pressed":["KeyW"],"down":["KeyW"],"cursor":[412.0,300.5]}
+//! {"frame":40,"released":["KeyW"],"down":[],"wheel":[0.0,-1.0],"text":"w"}
+//! {"frame":90}
+//! ```
+//!
+//! Keys and mouse buttons use binding names (`InputBinding::name`), except character keys,
+//! stored as `"char:<c>"` so their case survives; keys without a name are not recorded. `down`
+//! is the full held set whenever it changed. The last line marks where the recording stopped.
+//! XR controllers are not recorded.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{LineWriter, Write};
+use std::path::{Path, PathBuf};
+
+use winit::event::MouseButton;
+use winit::keyboard::Key;
+
+use crate::engine::AssetError;
+use crate::engine::action_map::InputBinding;
+use crate::engine::ecs::codec::{Map, Value};
+use crate::engine::user_input::InputState;
+
+/// Input of one frame, as far as it differs from the frame before.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InputFrame {
+    pub frame: u64,
+    pub pressed: Vec<InputBinding>,
+    pub released: Vec<InputBinding>,
+    /// Held keys and buttons, if they changed.
+    pub down: Option<Vec<InputBinding>>,
+    /// Cursor position, if it moved.
+    pub cursor: Option<(f32, f32)>,
+    pub wheel: (f32, f32),
+    pub text: String,
+}
+
+impl InputFrame {
+    fn is_empty(&self) -> bool {
+        self.pressed.is_empty()
+            && self.released.is_empty()
+            && self.down.is_none()
+            && self.cursor.is_none()
+            && self.wheel == (0.0, 0.0)
+            && self.text.is_empty()
+    }
+
+    /// Write this frame's input into `state` (before `InputState::begin_frame`).
+    pub fn apply(&self, state: &mut InputState) {
+        for binding in &self.pressed {
+            match binding {
+                InputBinding::Key(key) => state.keys_pressed.insert(key.clone()),
+                InputBinding::Mouse(button) => state.mouse_pressed.insert(*button),
+                InputBinding::Xr(..) => false,
+            };
+        }
+        for binding in &self.released {
+            match binding {
+                InputBinding::Key(key) => state.keys_released.insert(key.clone()),
+                InputBinding::Mouse(button) => state.mouse_released.insert(*button),
+                InputBinding::Xr(..) => false,
+            };
+        }
+        if let Some(down) = &self.down {
+            state.keys_down.clear();
+            state.mouse_down.clear();
+            for binding in down {
+                match binding {
+                    InputBinding::Key(key) => state.keys_down.insert(key.clone()),
+                    InputBinding::Mouse(button) => state.mouse_down.insert(*button),
+                    InputBinding::Xr(..) => false,
+                };
+            }
+        }
+        if self.cursor.is_some() {
+            state.cursor_pos = self.cursor;
+        }
+        state.wheel_delta.0 += self.wheel.0;
+        state.wheel_delta.1 += self.wheel.1;
+        state.push_text(&self.text);
+    }
+
+    pub fn to_json(&self) -> Value {
+        let mut out = Map::new();
+        out.insert("frame".into(), Value::from(self.frame));
+        let names = |bindings: &[InputBinding]| {
+            Value::from(bindings.iter().filter_map(binding_name).collect::<Vec<_>>())
+        };
+        if !self.pressed.is_empty() {
+            out.insert("pressed".into(), names(&self.pressed));
+        }
+        if !self.released.is_empty() {
+            out.insert("released".into(), names(&self.released));
+        }
+        if let Some(down) = &self.down {
+            out.insert("down".into(), names(down));
+        }
+        if let Some((x, y)) = self.cursor {
+            out.insert("cursor".into(), Value::from(vec![x, y]));
+        }
+        if self.wheel != (0.0, 0.0) {
+            out.insert(
+                "wheel".into(),
+                Value::from(vec![self.wheel.0, self.wheel.1]),
+            );
+        }
+        if !self.text.is_empty() {
+            out.insert("text".into(), Value::from(self.text.as_str()));
+        }
+        Value::Object(out)
+    }
+
+    pub fn from_json(value: &Value) -> Result<Self, String> {
+        let frame = value
+            .get("frame")
+            .and_then(Value::as_u64)
+            .ok_or("missing 'frame'")?;
+        let bindings = |key: &str| -> Result<Option<Vec<InputBinding>>, String> {
+            let Some(list) = value.get(key) else {
+                return Ok(None);
+            };
+            list.as_array()
+                .ok_or(format!("'{key}' is not a list"))?
+                .iter()
+                .map(|name| {
+                    name.as_str()
+                        .and_then(parse_binding)
+                        .ok_or(format!("'{key}': unknown key or button {name}"))
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(Some)
+        };
+        let pair = |key: &str| -> Result<Option<(f32, f32)>, String> {
+            let Some(v) = value.get(key) else {
+                return Ok(None);
+            };
+            match v
+                .as_array()
+                .map(|a| a.iter().map(Value::as_f64).collect::<Vec<_>>())
+            {
+                Some(xy) if xy.len() == 2 && xy.iter().all(Option::is_some) => {
+                    Ok(Some((xy[0].unwrap() as f32, xy[1].unwrap() as f32)))
+                }
+                _ => Err(format!("'{key}' is not a pair of numbers")),
+            }
+        };
+        Ok(Self {
+            frame,
+            pressed: bindings("pressed")?.unwrap_or_default(),
+            released: bindings("released")?.unwrap_or_default(),
+            down: bindings("down")?,
+            cursor: pair("cursor")?,
+            wheel: pair("wheel")?.unwrap_or_default(),
+            text: value
+                .get("text")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+        })
+    }
+}
+
+/// Appends one line per frame with input to a recording file.
+#[derive(Debug)]
+pub struct InputRecorder {
+    out: LineWriter<File>,
+    path: PathBuf,
+    frame: u64,
+    down: HashSet<InputBinding>,
+    cursor: Option<(f32, f32)>,
+}
+
+impl InputRecorder {
+    pub fn create(path: impl AsRef<Path>) -> Result<Self, AssetError> {
+        let path = path.as_ref().to_path_buf();
+        let file = File::create(&path).map_err(|e| AssetError::io(&path, e))?;
+        Ok(Self {
+            out: LineWriter::new(file),
+            path,
+            frame: 0,
+            down: HashSet::new(),
+            cursor: None,
+        })
+    }
+
+    /// Record the input `state` holds at the end of a frame (before `InputState::end_frame`).
+    pub fn capture(&mut self, state: &InputState) -> Result<(), AssetError> {
+        let down: HashSet<InputBinding> = bindings(&state.keys_down, &state.mouse_down).collect();
+        let frame = InputFrame {
+            frame: self.frame,
+            pressed: sorted(bindings(&state.keys_pressed, &state.mouse_pressed)),
+            released: sorted(bindings(&state.keys_released, &state.mouse_released)),
+            down: (down != self.down).then(|| sorted(down.iter().cloned())),
+            cursor: state.cursor_pos.filter(|_| state.cursor_pos != self.cursor),
+            wheel: state.wheel_delta,
+            text: state.committed_text().to_string(),
+        };
+        self.frame += 1;
+        self.down = down;
+        self.cursor = state.cursor_pos;
+        if frame.is_empty() {
+            return Ok(());
+        }
+        self.write(&frame)
+    }
+
+    fn write(&mut self, frame: &InputFrame) -> Result<(), AssetError> {
+        writeln!(self.out, "{}", frame.to_json()).map_err(|e| AssetError::io(&self.path, e))
+    }
+}
+
+impl Drop for InputRecorder {
+    fn drop(&mut self) {
+        // Marks the end, so playback lasts as long as the recording.
+        let end = InputFrame {
+            frame: self.frame,
+            ..Default::default()
+        };
+        if let Err(e) = self.write(&end) {
+            crate::log_warn!("input recording: {e}");
+        }
+    }
+}
+
+/// Frames of a recording, handed out in order.
+#[derive(Debug, Clone, Default)]
+pub struct InputPlayback {
+    frames: Vec<InputFrame>,
+    next: usize,
+    frame: u64,
+    /// Frames in the recording (from its end marker, if it has one).
+    len: u64,
+}
+
+impl InputPlayback {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AssetError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|e| AssetError::io(path, e))?;
+        let mut frames = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let frame = serde_json::from_str(line)
+                .map_err(|e| e.to_string())
+                .and_then(|v| InputFrame::from_json(&v))
+                .map_err(|message| AssetError::InputRecording {
+                    path: path.to_path_buf(),
+                    line: i + 1,
+                    message,
+                })?;
+            frames.push(frame);
+        }
+        Ok(Self::new(frames))
+    }
+
+    pub fn new(mut frames: Vec<InputFrame>) -> Self {
+        frames.sort_by_key(|f| f.frame);
+        let len = match frames.last() {
+            Some(end) if end.is_empty() => frames.pop().map_or(0, |end| end.frame),
+            Some(last) => last.frame + 1,
+            None => 0,
+        };
+        Self {
+            frames,
+            next: 0,
+            frame: 0,
+            len,
+        }
+    }
+
+    /// Apply the next frame's recorded input to `state` (before `InputState::begin_frame`).
+    pub fn advance(&mut self, state: &mut InputState) {
+        while let Some(frame) = self.frames.get(self.next).filter(|f| f.frame <= self.frame) {
+            frame.apply(state);
+            self.next += 1;
+        }
+        self.frame += 1;
+    }
+
+    /// Every recorded frame has been played.
+    pub fn finished(&self) -> bool {
+        self.frame >= self.len
+    }
+}
+
+fn bindings<'a>(
+    keys: &'a HashSet<Key>,
+    buttons: &'a HashSet<MouseButton>,
+) -> impl Iterator<Item = InputBinding> + 'a {
+    keys.iter()
+        .cloned()
+        .map(InputBinding::Key)
+        .chain(buttons.iter().copied().map(InputBinding::Mouse))
+}
+
+/// Keys with a name, in name order, so recordings diff cleanly.
+fn sorted(bindings: impl Iterator<Item = InputBinding>) -> Vec<InputBinding> {
+    let mut named: Vec<(String, InputBinding)> = bindings
+        .filter_map(|b| Some((binding_name(&b)?, b)))
+        .collect();
+    named.sort_by(|a, b| a.0.cmp(&b.0));
+    named.into_iter().map(|(_, b)| b).collect()
+}
+
+fn binding_name(binding: &InputBinding) -> Option<String> {
+    match binding {
+        InputBinding::Key(Key::Character(c)) => Some(format!("char:{c}")),
+        _ => binding.name(),
+    }
+}
+
+fn parse_binding(name: &str) -> Option<InputBinding> {
+    match name.strip_prefix("char:") {
+        Some(c) => Some(InputBinding::Key(Key::Character(c.into()))),
+        None => InputBinding::parse(name),
+    }
+}
@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod tests {
+    use winit::event::MouseButton;
+    use winit::keyboard::{Key, NamedKey};
+
+    use crate::engine::user_input::{InputState, UserInput};
+
+    /// Input of one frame, as a game would see it between `begin_frame` and `end_frame`.
+    fn snapshot(state: &InputState) -> String {
+        let mut keys: Vec<_> = state.keys_down.iter().map(|k| format!("{k:?}")).collect();
+        keys.sort();
+        format!(
+            "{keys:?} {:?} {:?} {:?} {:?} {:?}",
+            state.keys_pressed,
+            state.mouse_down,
+            state.cursor_pos,
+            state.mouse_movement(),
+            state.wheel_delta,
+        )
+    }
+
+    #[test]
+    fn playback_reproduces_recorded_frames() {
+        let path =
+            std::env::temp_dir().join(format!("little-cat-input-{}.jsonl", std::process::id()));
+        let shift_w = Key::Character("W".into());
+        let mut recorded = Vec::new();
+        {
+            let mut input = UserInput::new();
+            input.record(&path).unwrap();
+            for frame in 0..6 {
+                let state = input.state_mut();
+                match frame {
+                    1 => {
+                        state.keys_down.insert(shift_w.clone());
+                        state.keys_pressed.insert(shift_w.clone());
+                        state.cursor_pos = Some((10.0, 20.0));
+                    }
+                    2 => {
+                        state.mouse_down.insert(MouseButton::Left);
+                        state.cursor_pos = Some((15.0, 18.0));
+                        state.wheel_delta = (0.0, -1.0);
+                    }
+                    4 => {
+                        state.keys_down.clear();
+                        state.keys_released.insert(shift_w.clone());
+                        state.keys_pressed.insert(Key::Named(NamedKey::Space));
+                    }
+                    _ => {}
+                }
+                input.begin_frame();
+                recorded.push(snapshot(input.state()));
+                input.end_frame();
+            }
+        }
+
+        let mut input = UserInput::new();
+        input.playback(&path).unwrap();
+        let mut played = Vec::new();
+        while !input.playback_finished() {
+            input.begin_frame();
+            played.push(snapshot(input.state()));
+            input.end_frame();
+        }
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(played, recorded);
+    }
+}
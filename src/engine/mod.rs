@@ -1,18 +1,49 @@
+pub mod action_map;
+pub mod ai;
+pub mod assets;
+pub mod console;
 pub mod ecs;
+pub mod error;
+pub mod frame_pacing;
 pub mod graphics;
+pub mod i18n;
+pub mod input_recording;
 pub mod networking;
+pub mod repl;
+pub mod save_game;
+pub mod spatial;
 pub mod universe;
+pub mod universe_builder;
 pub mod user_input;
+#[cfg(feature = "windowed")]
 pub mod windowing;
 pub mod xr;
 
+#[cfg(test)]
+mod action_map_tests;
+#[cfg(test)]
+mod ai_tests;
+#[cfg(test)]
+mod assets_tests;
+#[cfg(test)]
+mod console_tests;
+#[cfg(test)]
+mod frame_pacing_tests;
+#[cfg(test)]
+mod i18n_tests;
+#[cfg(test)]
+mod input_recording_tests;
+#[cfg(test)]
+mod repl_tests;
+#[cfg(test)]
+mod save_game_tests;
+#[cfg(test)]
+mod spatial_tests;
+#[cfg(test)]
+mod user_input_tests;
+
+pub use error::{AssetError, EngineError, EngineResult};
 pub use universe::Universe;
+pub use universe_builder::{InitialScene, RendererBackend, UniverseBuilder};
+#[cfg(feature = "windowed")]
 pub use windowing::Windowing;
-
-/// Engine-level error type placeholder.
-#[derive(Debug)]
-pub enum EngineError {
-    NotImplemented,
-}
-
-pub type EngineResult<T> = Result<T, EngineError>;
@@ -0,0 +1,76 @@
+//! Built-in text chat over the message layer.
+//!
+//! Clients send what they `say` to the server, which stamps the sender's id, appends the line
+//! to its own `ChatLog` and broadcasts it to every client (the sender included), so all logs
+//! end up in the server's order. Offline, `say` only appends to the local log.
+
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::networking::{NetMessage, PeerId};
+
+/// Lines a `ChatLog` keeps; older ones are dropped.
+pub const CHAT_HISTORY: usize = 200;
+
+/// Longest line in characters; longer ones are cut (keeps a line inside one packet).
+pub const MAX_CHAT_LEN: usize = 256;
+
+/// One line of chat, on the wire and in the log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatLine {
+    pub from: PeerId,
+    pub text: String,
+}
+
+impl ChatLine {
+    pub(crate) fn new(from: PeerId, text: &str) -> Self {
+        Self {
+            from,
+            text: text.chars().take(MAX_CHAT_LEN).collect(),
+        }
+    }
+}
+
+impl NetMessage for ChatLine {
+    const CHANNEL: &'static str = "chat";
+}
+
+impl std::fmt::Display for ChatLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<{}> {}", self.from, self.text)
+    }
+}
+
+/// Chat history of this process, oldest first.
+#[derive(Debug, Default)]
+pub struct ChatLog {
+    lines: VecDeque<ChatLine>,
+}
+
+impl ChatLog {
+    pub fn lines(&self) -> impl Iterator<Item = &ChatLine> {
+        self.lines.iter()
+    }
+
+    /// The last `n` lines, oldest first.
+    pub fn recent(&self, n: usize) -> impl Iterator<Item = &ChatLine> {
+        self.lines.iter().skip(self.lines.len().saturating_sub(n))
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    pub(crate) fn push(&mut self, line: ChatLine) {
+        crate::log_info!("chat {line}");
+        if self.lines.len() == CHAT_HISTORY {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line);
+    }
+}
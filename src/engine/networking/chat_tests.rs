@@ -0,0 +1,59 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use crate::engine::networking::chat::MAX_CHAT_LEN;
+    use crate::engine::networking::{
+        ChatLine, LoopbackNetwork, NetworkError, Networking, PeerId, Transport,
+    };
+
+    fn log(net: &Networking) -> Vec<String> {
+        net.chat().lines().map(ChatLine::to_string).collect()
+    }
+
+    #[test]
+    fn the_server_relays_chat_to_every_client_in_its_order() {
+        let now = Instant::now();
+        let network = LoopbackNetwork::new();
+        let server_transport = network.endpoint();
+        let server_addr = server_transport.local_addr();
+        let mut server = Networking::server(server_transport);
+        let mut clients = [
+            Networking::client(network.endpoint(), server_addr),
+            Networking::client(network.endpoint(), server_addr),
+        ];
+        assert!(matches!(
+            clients[0].say("too early"),
+            Err(NetworkError::NotConnected(PeerId::SERVER))
+        ));
+        let round = |server: &mut Networking, clients: &mut [Networking; 2]| {
+            for client in clients.iter_mut() {
+                client.poll_at(now).unwrap();
+            }
+            server.poll_at(now).unwrap();
+            for client in clients.iter_mut() {
+                client.poll_at(now).unwrap();
+            }
+        };
+        round(&mut server, &mut clients);
+
+        clients[1].say("hi all").unwrap();
+        server.say("welcome").unwrap();
+        // A client's own line only shows up once the server relays it.
+        assert!(clients[1].chat().is_empty());
+        round(&mut server, &mut clients);
+        round(&mut server, &mut clients);
+
+        let expected = vec!["<0> welcome".to_string(), "<2> hi all".to_string()];
+        assert_eq!(log(&server), expected);
+        assert_eq!(log(&clients[0]), expected);
+        assert_eq!(log(&clients[1]), expected);
+
+        let mut offline = Networking::new();
+        offline.say(&"x".repeat(MAX_CHAT_LEN + 10)).unwrap();
+        assert_eq!(
+            offline.chat().recent(5).next().unwrap().text.len(),
+            MAX_CHAT_LEN
+        );
+    }
+}
@@ -0,0 +1,687 @@
+//! Connection lifecycle on top of a `Transport`: `Networking` accepts and tracks peers by
+//! `PeerId`, measures their round-trip time and buffers outgoing data per peer.
+//!
+//! Every transport payload starts with a kind byte:
+//!
+//! - `CONNECT` (unreliable, resent until accepted): the client's `u64` session id.
+//! - `ACCEPT` (reliable): the `PeerId` the server assigned to the client.
+//! - `DISCONNECT` (unreliable, best effort; timeouts cover its loss).
+//! - `PING` / `PONG` (unreliable): a `u32` sequence number, echoed back.
+//! - `DATA`: the application payload, on whichever channel it was sent.
+//! - `MESSAGE` (reliable): a typed message (see `messages`).
+//!
+//! A `CONNECT` from a connected address with a new session id means the client restarted:
+//! the old peer is dropped (`DisconnectReason::Replaced`) and the new one gets a fresh id.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::net::SocketAddr;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::networking::chat::{ChatLine, ChatLog};
+use crate::engine::networking::messages::{MessageChannels, NetMessage};
+use crate::engine::networking::{Channel, MAX_PAYLOAD, NetworkError, Transport};
+
+/// How often each side pings the other to measure round-trip time.
+pub const PING_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Silence after which a peer (or a server that never answered `CONNECT`) is dropped.
+pub const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Messages a peer's send queue holds before `send` reports `QueueFull`.
+pub const SEND_QUEUE_LIMIT: usize = 256;
+
+/// Payload bytes sent to one peer per `poll`; the rest waits for the next one.
+pub const SEND_BUDGET: usize = 32 * 1024;
+
+/// Events kept for `poll_event`; older ones are dropped if nobody reads them.
+const EVENT_QUEUE_LIMIT: usize = 256;
+
+const CONNECT: u8 = 1;
+const ACCEPT: u8 = 2;
+const DISCONNECT: u8 = 3;
+const PING: u8 = 4;
+const PONG: u8 = 5;
+const DATA: u8 = 6;
+const MESSAGE: u8 = 7;
+
+/// Identity of a connection: clients are numbered from 1 by the server, and a client sees
+/// its server as `PeerId::SERVER`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct PeerId(pub u32);
+
+impl PeerId {
+    pub const SERVER: PeerId = PeerId(0);
+}
+
+impl std::fmt::Display for PeerId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisconnectReason {
+    /// `Networking::disconnect` on this side.
+    Local,
+    /// The peer said goodbye.
+    Remote,
+    /// Nothing heard for `TIMEOUT`.
+    TimedOut,
+    /// The peer's address connected again with a new session.
+    Replaced,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetEvent {
+    Connected(PeerId),
+    Disconnected {
+        peer: PeerId,
+        reason: DisconnectReason,
+    },
+}
+
+/// Application data received from a peer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Incoming {
+    pub from: PeerId,
+    pub channel: Channel,
+    pub payload: Vec<u8>,
+}
+
+/// What `Networking::peers` reports about one connection (REPL `net status`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeerStatus {
+    pub id: PeerId,
+    pub addr: SocketAddr,
+    /// Smoothed round-trip time; `None` until the first pong.
+    pub rtt: Option<Duration>,
+    /// Messages waiting in the send queue.
+    pub queued: usize,
+}
+
+#[derive(Debug)]
+struct Peer {
+    addr: SocketAddr,
+    session: u64,
+    last_heard: Instant,
+    last_ping: Instant,
+    ping_seq: u32,
+    /// Sequence number and send time of the ping we wait a pong for.
+    ping_out: Option<(u32, Instant)>,
+    rtt: Option<Duration>,
+    /// Framed payloads (kind byte first) waiting for `poll`.
+    queue: VecDeque<(Channel, Vec<u8>)>,
+}
+
+impl Peer {
+    fn new(addr: SocketAddr, session: u64, now: Instant) -> Self {
+        Self {
+            addr,
+            session,
+            last_heard: now,
+            last_ping: now,
+            ping_seq: 0,
+            ping_out: None,
+            rtt: None,
+            queue: VecDeque::new(),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Role {
+    Offline,
+    Server,
+    Client {
+        server: SocketAddr,
+        session: u64,
+        state: ClientState,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ClientState {
+    /// Created; the first `poll` sends `CONNECT`.
+    Start,
+    Connecting {
+        started: Instant,
+        last_sent: Instant,
+    },
+    Connected,
+    /// Refused, timed out or disconnected; the game decides whether to connect again.
+    Closed,
+}
+
+/// Connections of this process: offline, a server accepting clients, or a client of one
+/// server. `Universe::update` polls it once per frame.
+pub struct Networking {
+    transport: Option<Box<dyn Transport>>,
+    role: Role,
+    /// Our id as the server assigned it (clients only).
+    local_id: Option<PeerId>,
+    peers: BTreeMap<PeerId, Peer>,
+    next_id: u32,
+    events: VecDeque<NetEvent>,
+    inbox: VecDeque<Incoming>,
+    messages: MessageChannels,
+    chat: ChatLog,
+}
+
+impl Default for Networking {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Networking {
+    /// No transport: `poll` does nothing and `send` fails.
+    pub fn new() -> Self {
+        let mut messages = MessageChannels::default();
+        messages
+            .register::<ChatLine>()
+            .expect("first channel cannot collide");
+        Self {
+            transport: None,
+            role: Role::Offline,
+            local_id: None,
+            peers: BTreeMap::new(),
+            next_id: 1,
+            events: VecDeque::new(),
+            inbox: VecDeque::new(),
+            messages,
+            chat: ChatLog::default(),
+        }
+    }
+
+    /// Accept any client that connects to `transport`.
+    pub fn server(transport: impl Transport + 'static) -> Self {
+        Self {
+            transport: Some(Box::new(transport)),
+            role: Role::Server,
+            ..Self::new()
+        }
+    }
+
+    /// Connect to the server at `server`; `NetEvent::Connected(PeerId::SERVER)` follows once
+    /// it accepts.
+    pub fn client(mut transport: impl Transport + 'static, server: SocketAddr) -> Self {
+        // Tells a restarted client apart from the old one on the same address.
+        let session = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64)
+            ^ u64::from(transport.local_addr().port());
+        transport.accept(server);
+        Self {
+            transport: Some(Box::new(transport)),
+            role: Role::Client {
+                server,
+                session,
+                state: ClientState::Start,
+            },
+            ..Self::new()
+        }
+    }
+
+    pub fn is_server(&self) -> bool {
+        matches!(self.role, Role::Server)
+    }
+
+    /// Our address, `None` offline.
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.transport.as_ref().map(|t| t.local_addr())
+    }
+
+    /// Our id on the server, once it accepted us (clients only).
+    pub fn local_id(&self) -> Option<PeerId> {
+        self.local_id
+    }
+
+    /// Connected peers in id order.
+    pub fn peers(&self) -> Vec<PeerStatus> {
+        self.peers
+            .iter()
+            .map(|(&id, peer)| PeerStatus {
+                id,
+                addr: peer.addr,
+                rtt: peer.rtt,
+                queued: peer.queue.len(),
+            })
+            .collect()
+    }
+
+    /// Queue `payload` (at most `MAX_PAYLOAD - 1` bytes) for a connected peer; it goes out
+    /// with the next `poll`, within that peer's `SEND_BUDGET`.
+    pub fn send(
+        &mut self,
+        peer: PeerId,
+        channel: Channel,
+        payload: &[u8],
+    ) -> Result<(), NetworkError> {
+        self.enqueue(peer, channel, [&[DATA][..], payload].concat())
+    }
+
+    fn enqueue(
+        &mut self,
+        peer: PeerId,
+        channel: Channel,
+        framed: Vec<u8>,
+    ) -> Result<(), NetworkError> {
+        if framed.len() > MAX_PAYLOAD {
+            return Err(NetworkError::PayloadTooLarge {
+                len: framed.len() - 1,
+                max: MAX_PAYLOAD - 1,
+            });
+        }
+        let peer_state = self
+            .peers
+            .get_mut(&peer)
+            .ok_or(NetworkError::NotConnected(peer))?;
+        if peer_state.queue.len() >= SEND_QUEUE_LIMIT {
+            return Err(NetworkError::QueueFull {
+                peer,
+                queued: peer_state.queue.len(),
+            });
+        }
+        peer_state.queue.push_back((channel, framed));
+        Ok(())
+    }
+
+    /// Accept and send messages of type `T`; do it on both ends before they talk.
+    pub fn register_message<T: NetMessage>(&mut self) -> Result<(), NetworkError> {
+        self.messages.register::<T>()
+    }
+
+    /// Queue a reliable message for `peer`.
+    pub fn send_message<T: NetMessage>(
+        &mut self,
+        peer: PeerId,
+        message: &T,
+    ) -> Result<(), NetworkError> {
+        let body = self.messages.encode(message)?;
+        self.enqueue(peer, Channel::Reliable, [&[MESSAGE][..], &body].concat())
+    }
+
+    /// Client side: `send_message` to the server.
+    pub fn send_to_server<T: NetMessage>(&mut self, message: &T) -> Result<(), NetworkError> {
+        self.send_message(PeerId::SERVER, message)
+    }
+
+    /// Server side: `send_message` to one client.
+    pub fn send_to_client<T: NetMessage>(
+        &mut self,
+        client: PeerId,
+        message: &T,
+    ) -> Result<(), NetworkError> {
+        self.send_message(client, message)
+    }
+
+    /// `send_message` to every connected peer (a client's only peer is the server). Peers
+    /// whose queue is full miss it; the first such error is returned after trying them all.
+    pub fn broadcast<T: NetMessage>(&mut self, message: &T) -> Result<(), NetworkError> {
+        let body = self.messages.encode(message)?;
+        let framed = [&[MESSAGE][..], &body].concat();
+        let peers: Vec<PeerId> = self.peers.keys().copied().collect();
+        let mut result = Ok(());
+        for peer in peers {
+            if let Err(e) = self.enqueue(peer, Channel::Reliable, framed.clone()) {
+                result = result.and(Err(e));
+            }
+        }
+        result
+    }
+
+    /// Messages of type `T` received so far, with their sender, oldest first.
+    pub fn take_messages<T: NetMessage>(&mut self) -> Vec<(PeerId, T)> {
+        self.messages.take::<T>()
+    }
+
+    /// Chat lines seen by this process (see `chat`).
+    pub fn chat(&self) -> &ChatLog {
+        &self.chat
+    }
+
+    /// Say `text` in chat. A client's line reaches its own log once the server echoes it.
+    pub fn say(&mut self, text: &str) -> Result<(), NetworkError> {
+        let line = ChatLine::new(self.local_id.unwrap_or(PeerId::SERVER), text);
+        match self.role {
+            Role::Client { .. } => self.send_to_server(&line),
+            Role::Server => {
+                let sent = self.broadcast(&line);
+                self.chat.push(line);
+                sent
+            }
+            Role::Offline => {
+                self.chat.push(line);
+                Ok(())
+            }
+        }
+    }
+
+    /// Server: stamp and rebroadcast what clients said. Client: log what the server relayed.
+    fn relay_chat(&mut self) {
+        for (from, line) in self.messages.take::<ChatLine>() {
+            match self.role {
+                Role::Server => {
+                    let line = ChatLine::new(from, &line.text);
+                    if let Err(e) = self.broadcast(&line) {
+                        crate::log_warn!("net: relaying chat: {e}");
+                    }
+                    self.chat.push(line);
+                }
+                Role::Client { .. } if from == PeerId::SERVER => self.chat.push(line),
+                _ => {}
+            }
+        }
+    }
+
+    /// Next application payload received by the last `poll`.
+    pub fn receive(&mut self) -> Option<Incoming> {
+        self.inbox.pop_front()
+    }
+
+    /// Next connect/disconnect event, oldest first.
+    pub fn poll_event(&mut self) -> Option<NetEvent> {
+        self.events.pop_front()
+    }
+
+    /// Drop `peer`, telling it so if the packet gets through.
+    pub fn disconnect(&mut self, peer: PeerId) -> bool {
+        let Some(state) = self.peers.get(&peer) else {
+            return false;
+        };
+        let addr = state.addr;
+        if let Some(transport) = self.transport.as_mut() {
+            let _ = transport.send(addr, Channel::Unreliable, &[DISCONNECT]);
+        }
+        self.remove_peer(peer, DisconnectReason::Local);
+        true
+    }
+
+    /// `poll_at` the current time.
+    pub fn poll(&mut self) -> Result<(), NetworkError> {
+        self.poll_at(Instant::now())
+    }
+
+    /// Read what arrived, run the handshake, pings and timeouts as of `now`, then flush the
+    /// send queues.
+    pub fn poll_at(&mut self, now: Instant) -> Result<(), NetworkError> {
+        let Some(transport) = self.transport.as_mut() else {
+            return Ok(());
+        };
+        let unreachable = match transport.poll() {
+            Ok(()) => None,
+            Err(NetworkError::Unacknowledged(addr)) => Some(addr),
+            Err(e) => return Err(e),
+        };
+        while let Some(packet) = self.transport.as_mut().and_then(|t| t.receive()) {
+            self.handle(packet.from, packet.channel, &packet.payload, now);
+        }
+        if let Some(addr) = unreachable
+            && let Some((&id, _)) = self.peers.iter().find(|(_, p)| p.addr == addr)
+        {
+            self.remove_peer(id, DisconnectReason::TimedOut);
+        }
+
+        self.handshake(now);
+
+        let timed_out: Vec<PeerId> = self
+            .peers
+            .iter()
+            .filter(|(_, p)| now.duration_since(p.last_heard) >= TIMEOUT)
+            .map(|(&id, _)| id)
+            .collect();
+        for id in timed_out {
+            self.remove_peer(id, DisconnectReason::TimedOut);
+        }
+
+        self.relay_chat();
+
+        let Some(transport) = self.transport.as_mut() else {
+            return Ok(());
+        };
+        for (id, peer) in &mut self.peers {
+            // A peer that can't be reached is left to time out rather than failing the poll.
+            if let Err(e) = flush_peer(transport.as_mut(), peer, now) {
+                crate::log_warn!("net: sending to peer {id} ({}): {e}", peer.addr);
+            }
+        }
+        Ok(())
+    }
+
+    /// Client side: (re)send `CONNECT` until the server accepts, give up after `TIMEOUT`.
+    fn handshake(&mut self, now: Instant) {
+        let Role::Client {
+            server,
+            session,
+            state,
+        } = &mut self.role
+        else {
+            return;
+        };
+        let send = match *state {
+            ClientState::Start => {
+                *state = ClientState::Connecting {
+                    started: now,
+                    last_sent: now,
+                };
+                true
+            }
+            ClientState::Connecting { started, .. } if now.duration_since(started) >= TIMEOUT => {
+                *state = ClientState::Closed;
+                crate::log_warn!("net: no answer from {server}");
+                push_event(
+                    &mut self.events,
+                    NetEvent::Disconnected {
+                        peer: PeerId::SERVER,
+                        reason: DisconnectReason::TimedOut,
+                    },
+                );
+                false
+            }
+            ClientState::Connecting { started, last_sent }
+                if now.duration_since(last_sent) >= PING_INTERVAL =>
+            {
+                *state = ClientState::Connecting {
+                    started,
+                    last_sent: now,
+                };
+                true
+            }
+            _ => false,
+        };
+        if send {
+            let connect = [&[CONNECT][..], &session.to_le_bytes()].concat();
+            if let Some(transport) = self.transport.as_mut()
+                && let Err(e) = transport.send(*server, Channel::Unreliable, &connect)
+            {
+                crate::log_warn!("net: connecting to {server}: {e}");
+            }
+        }
+    }
+
+    fn handle(&mut self, from: SocketAddr, channel: Channel, payload: &[u8], now: Instant) {
+        let Some((&kind, body)) = payload.split_first() else {
+            return;
+        };
+        if kind == CONNECT {
+            self.handle_connect(from, body, now);
+            return;
+        }
+        if kind == ACCEPT {
+            self.handle_accept(from, body, now);
+            return;
+        }
+        let Some((&id, peer)) = self.peers.iter_mut().find(|(_, p)| p.addr == from) else {
+            return;
+        };
+        peer.last_heard = now;
+        match kind {
+            DISCONNECT => self.remove_peer(id, DisconnectReason::Remote),
+            PING => {
+                let pong = [&[PONG][..], body].concat();
+                if let Some(transport) = self.transport.as_mut() {
+                    let _ = transport.send(from, Channel::Unreliable, &pong);
+                }
+            }
+            PONG => {
+                if let Some((seq, sent)) = peer.ping_out
+                    && read_u32(body) == Some(seq)
+                {
+                    let sample = now.duration_since(sent);
+                    peer.rtt = Some(match peer.rtt {
+                        Some(rtt) => (rtt * 7 + sample) / 8,
+                        None => sample,
+                    });
+                    peer.ping_out = None;
+                }
+            }
+            DATA => self.inbox.push_back(Incoming {
+                from: id,
+                channel,
+                payload: body.to_vec(),
+            }),
+            MESSAGE => self.messages.deliver(id, body),
+            _ => crate::log_warn!("net: unknown packet kind {kind} from {from}"),
+        }
+    }
+
+    fn handle_connect(&mut self, from: SocketAddr, body: &[u8], now: Instant) {
+        if !self.is_server() {
+            return;
+        }
+        let Some(session) = body.try_into().ok().map(u64::from_le_bytes) else {
+            return;
+        };
+        let existing = self
+            .peers
+            .iter()
+            .find(|(_, p)| p.addr == from)
+            .map(|(&id, p)| (id, p.session));
+        let id = match existing {
+            // Our accept was lost; say it again.
+            Some((id, s)) if s == session => id,
+            replaced => {
+                if let Some((old, _)) = replaced {
+                    self.remove_peer(old, DisconnectReason::Replaced);
+                }
+                let id = PeerId(self.next_id);
+                self.next_id += 1;
+                self.peers.insert(id, Peer::new(from, session, now));
+                if let Some(transport) = self.transport.as_mut() {
+                    transport.accept(from);
+                }
+                crate::log_info!("net: peer {id} connected from {from}");
+                push_event(&mut self.events, NetEvent::Connected(id));
+                id
+            }
+        };
+        let accept = [&[ACCEPT][..], &id.0.to_le_bytes()].concat();
+        if let Some(transport) = self.transport.as_mut()
+            && let Err(e) = transport.send(from, Channel::Reliable, &accept)
+        {
+            crate::log_warn!("net: accepting {from}: {e}");
+        }
+    }
+
+    fn handle_accept(&mut self, from: SocketAddr, body: &[u8], now: Instant) {
+        let Role::Client {
+            server,
+            session,
+            state,
+        } = &mut self.role
+        else {
+            return;
+        };
+        if from != *server || !matches!(state, ClientState::Start | ClientState::Connecting { .. })
+        {
+            return;
+        }
+        let Some(id) = read_u32(body) else {
+            return;
+        };
+        *state = ClientState::Connected;
+        self.local_id = Some(PeerId(id));
+        self.peers
+            .insert(PeerId::SERVER, Peer::new(from, *session, now));
+        crate::log_info!("net: connected to {from} as peer {id}");
+        push_event(&mut self.events, NetEvent::Connected(PeerId::SERVER));
+    }
+
+    fn remove_peer(&mut self, id: PeerId, reason: DisconnectReason) {
+        let Some(peer) = self.peers.remove(&id) else {
+            return;
+        };
+        if let Some(transport) = self.transport.as_mut() {
+            transport.forget(peer.addr);
+        }
+        if let Role::Client { state, .. } = &mut self.role {
+            *state = ClientState::Closed;
+            self.local_id = None;
+        }
+        crate::log_info!("net: peer {id} ({}) disconnected: {reason:?}", peer.addr);
+        push_event(
+            &mut self.events,
+            NetEvent::Disconnected { peer: id, reason },
+        );
+    }
+
+    /// `net status` lines for the REPL.
+    pub fn status_lines(&self) -> Vec<String> {
+        let head = match (&self.role, self.local_addr()) {
+            (Role::Offline, _) | (_, None) => return vec!["networking offline".to_string()],
+            (Role::Server, Some(addr)) => format!("server on {addr}"),
+            (Role::Client { server, state, .. }, Some(addr)) => match (state, self.local_id) {
+                (_, Some(id)) => format!("client {id} of {server} (on {addr})"),
+                (ClientState::Closed, None) => format!("client of {server} (on {addr}), closed"),
+                (_, None) => format!("client of {server} (on {addr}), connecting"),
+            },
+        };
+        std::iter::once(format!("{head}, {} peers", self.peers.len()))
+            .chain(self.peers().into_iter().map(|p| {
+                let rtt = p.rtt.map_or("-".to_string(), |rtt| {
+                    format!("{:.1} ms", rtt.as_secs_f64() * 1000.0)
+                });
+                format!("  {} {} rtt {rtt}, {} queued", p.id, p.addr, p.queued)
+            }))
+            .collect()
+    }
+}
+
+/// Ping if due, then send queued data within `SEND_BUDGET`.
+fn flush_peer(
+    transport: &mut dyn Transport,
+    peer: &mut Peer,
+    now: Instant,
+) -> Result<(), NetworkError> {
+    if now.duration_since(peer.last_ping) >= PING_INTERVAL {
+        peer.last_ping = now;
+        peer.ping_seq = peer.ping_seq.wrapping_add(1);
+        peer.ping_out = Some((peer.ping_seq, now));
+        let ping = [&[PING][..], &peer.ping_seq.to_le_bytes()].concat();
+        transport.send(peer.addr, Channel::Unreliable, &ping)?;
+    }
+
+    let mut budget = SEND_BUDGET;
+    while let Some((channel, framed)) = peer.queue.front() {
+        if framed.len() > budget {
+            break;
+        }
+        budget -= framed.len();
+        transport.send(peer.addr, *channel, framed)?;
+        peer.queue.pop_front();
+    }
+    Ok(())
+}
+
+fn push_event(events: &mut VecDeque<NetEvent>, event: NetEvent) {
+    if events.len() == EVENT_QUEUE_LIMIT {
+        events.pop_front();
+    }
+    events.push_back(event);
+}
+
+fn read_u32(bytes: &[u8]) -> Option<u32> {
+    bytes.try_into().ok().map(u32::from_le_bytes)
+}
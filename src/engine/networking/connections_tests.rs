@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crate::engine::networking::connections::{PING_INTERVAL, SEND_QUEUE_LIMIT, TIMEOUT};
+    use crate::engine::networking::{
+        Channel, DisconnectReason, LoopbackNetwork, NetEvent, NetworkError, Networking, PeerId,
+        Transport,
+    };
+
+    fn events(net: &mut Networking) -> Vec<NetEvent> {
+        std::iter::from_fn(|| net.poll_event()).collect()
+    }
+
+    /// A server and a client on one loopback network, handshake done at `start`.
+    fn connected(start: Instant) -> (Networking, Networking) {
+        let network = LoopbackNetwork::new();
+        let server_transport = network.endpoint();
+        let server_addr = server_transport.local_addr();
+        let mut server = Networking::server(server_transport);
+        let mut client = Networking::client(network.endpoint(), server_addr);
+        client.poll_at(start).unwrap();
+        server.poll_at(start).unwrap();
+        client.poll_at(start).unwrap();
+        (server, client)
+    }
+
+    #[test]
+    fn clients_get_ids_rtt_and_data_until_they_leave() {
+        let start = Instant::now();
+        let (mut server, mut client) = connected(start);
+        assert_eq!(events(&mut server), vec![NetEvent::Connected(PeerId(1))]);
+        assert_eq!(
+            events(&mut client),
+            vec![NetEvent::Connected(PeerId::SERVER)]
+        );
+        assert_eq!(client.local_id(), Some(PeerId(1)));
+
+        // A ping round trip that takes 20 ms.
+        let ping = start + PING_INTERVAL;
+        server.poll_at(ping).unwrap();
+        client.poll_at(ping).unwrap();
+        server.poll_at(ping + Duration::from_millis(20)).unwrap();
+        let peers = server.peers();
+        assert_eq!(peers.len(), 1);
+        assert_eq!(peers[0].rtt, Some(Duration::from_millis(20)));
+        assert_eq!(
+            server.status_lines()[1].split_whitespace().nth(3),
+            Some("20.0")
+        );
+
+        client
+            .send(PeerId::SERVER, Channel::Reliable, b"hello")
+            .unwrap();
+        client.poll_at(ping).unwrap();
+        server.poll_at(ping).unwrap();
+        let incoming = server.receive().unwrap();
+        assert_eq!(
+            (incoming.from, incoming.payload),
+            (PeerId(1), b"hello".to_vec())
+        );
+
+        assert!(client.disconnect(PeerId::SERVER));
+        server.poll_at(ping).unwrap();
+        assert_eq!(
+            events(&mut server),
+            vec![NetEvent::Disconnected {
+                peer: PeerId(1),
+                reason: DisconnectReason::Remote
+            }]
+        );
+        assert!(matches!(
+            client.send(PeerId::SERVER, Channel::Reliable, b"?"),
+            Err(NetworkError::NotConnected(PeerId::SERVER))
+        ));
+    }
+
+    #[test]
+    fn silent_peers_time_out_and_full_queues_push_back() {
+        let start = Instant::now();
+        let (mut server, client) = connected(start);
+        events(&mut server);
+
+        for i in 0..SEND_QUEUE_LIMIT {
+            server
+                .send(PeerId(1), Channel::Unreliable, &[i as u8])
+                .unwrap();
+        }
+        assert!(matches!(
+            server.send(PeerId(1), Channel::Unreliable, b"one more"),
+            Err(NetworkError::QueueFull {
+                peer: PeerId(1),
+                ..
+            })
+        ));
+        server.poll_at(start).unwrap();
+        assert_eq!(server.peers()[0].queued, 0);
+
+        // The client goes away without a word.
+        drop(client);
+        server.poll_at(start + TIMEOUT).unwrap();
+        assert_eq!(
+            events(&mut server),
+            vec![NetEvent::Disconnected {
+                peer: PeerId(1),
+                reason: DisconnectReason::TimedOut
+            }]
+        );
+        assert!(server.peers().is_empty());
+    }
+}
@@ -0,0 +1,40 @@
+use std::net::SocketAddr;
+
+use crate::engine::networking::PeerId;
+
+/// Failures of transports and the connection layer above them.
+#[derive(Debug, thiserror::Error)]
+pub enum NetworkError {
+    #[error("socket: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("no endpoint at {0}")]
+    UnknownPeer(SocketAddr),
+
+    /// A reliable packet to this address went unacknowledged through every resend; the
+    /// transport dropped its state for the address.
+    #[error("{0} stopped acknowledging reliable packets")]
+    Unacknowledged(SocketAddr),
+
+    #[error("payload of {len} bytes exceeds the {max} byte limit")]
+    PayloadTooLarge { len: usize, max: usize },
+
+    #[error("peer {0} is not connected")]
+    NotConnected(PeerId),
+
+    /// The peer's send queue is full; back off until `PeerStatus::queued` drops.
+    #[error("send queue of peer {peer} is full ({queued} messages)")]
+    QueueFull { peer: PeerId, queued: usize },
+
+    #[error("message channel '{0}' is not registered")]
+    UnregisteredMessage(&'static str),
+
+    #[error("message channels '{first}' and '{second}' hash to the same id; rename one")]
+    ChannelCollision {
+        first: &'static str,
+        second: &'static str,
+    },
+
+    #[error("message encoding: {0}")]
+    Message(#[from] serde_json::Error),
+}
@@ -0,0 +1,119 @@
+//! In-process transport for tests and local play.
+//!
+//! Endpoints made by the same `LoopbackNetwork` share its queues: a packet sent by one shows
+//! up in the receiver's next `poll`, in send order. Nothing touches the OS, so runs are
+//! deterministic; `drop_unreliable_every` adds predictable loss on the unreliable channel.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::rc::Rc;
+
+use crate::engine::networking::transport::check_payload;
+use crate::engine::networking::{Channel, NetworkError, Packet, Transport};
+
+/// Port of the first endpoint; later ones count up.
+const FIRST_PORT: u16 = 40000;
+
+#[derive(Debug, Default)]
+struct Hub {
+    endpoints: u16,
+    /// Packets sent to each endpoint, not yet picked up by its `poll`.
+    in_transit: HashMap<SocketAddr, VecDeque<Packet>>,
+    /// Drop every nth unreliable packet (0 = none).
+    drop_every: u32,
+    unreliable_sent: u32,
+}
+
+/// Shared medium of a set of loopback endpoints. Cloning it shares the same network.
+#[derive(Debug, Clone, Default)]
+pub struct LoopbackNetwork {
+    hub: Rc<RefCell<Hub>>,
+}
+
+impl LoopbackNetwork {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A new endpoint with its own address (`127.0.0.1:40000`, `:40001`, ...).
+    pub fn endpoint(&self) -> LoopbackTransport {
+        let mut hub = self.hub.borrow_mut();
+        let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, FIRST_PORT + hub.endpoints));
+        hub.endpoints += 1;
+        hub.in_transit.insert(addr, VecDeque::new());
+        LoopbackTransport {
+            hub: self.hub.clone(),
+            addr,
+            inbox: VecDeque::new(),
+        }
+    }
+
+    /// Lose every `n`th unreliable packet sent on this network from now on (0 turns it off).
+    pub fn drop_unreliable_every(&self, n: u32) {
+        let mut hub = self.hub.borrow_mut();
+        hub.drop_every = n;
+        hub.unreliable_sent = 0;
+    }
+}
+
+/// One endpoint of a `LoopbackNetwork`.
+#[derive(Debug)]
+pub struct LoopbackTransport {
+    hub: Rc<RefCell<Hub>>,
+    addr: SocketAddr,
+    inbox: VecDeque<Packet>,
+}
+
+impl Transport for LoopbackTransport {
+    fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Fails with `UnknownPeer` if no endpoint of this network has address `to`.
+    fn send(
+        &mut self,
+        to: SocketAddr,
+        channel: Channel,
+        payload: &[u8],
+    ) -> Result<(), NetworkError> {
+        check_payload(payload)?;
+        let mut hub = self.hub.borrow_mut();
+        if !hub.in_transit.contains_key(&to) {
+            return Err(NetworkError::UnknownPeer(to));
+        }
+        if channel == Channel::Unreliable && hub.drop_every > 0 {
+            hub.unreliable_sent += 1;
+            if hub.unreliable_sent.is_multiple_of(hub.drop_every) {
+                return Ok(());
+            }
+        }
+        let packet = Packet {
+            from: self.addr,
+            channel,
+            payload: payload.to_vec(),
+        };
+        if let Some(queue) = hub.in_transit.get_mut(&to) {
+            queue.push_back(packet);
+        }
+        Ok(())
+    }
+
+    fn poll(&mut self) -> Result<(), NetworkError> {
+        if let Some(queue) = self.hub.borrow_mut().in_transit.get_mut(&self.addr) {
+            self.inbox.extend(queue.drain(..));
+        }
+        Ok(())
+    }
+
+    fn receive(&mut self) -> Option<Packet> {
+        self.inbox.pop_front()
+    }
+}
+
+impl Drop for LoopbackTransport {
+    fn drop(&mut self) {
+        // Later sends to this address fail like sends to an address that never existed.
+        self.hub.borrow_mut().in_transit.remove(&self.addr);
+    }
+}
@@ -0,0 +1,127 @@
+//! Typed gameplay messages (chat lines, ability use, ...) next to replicated state.
+//!
+//! A message type is any serde type with a channel name:
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! enum Ability { Dash, Cast { spell: u32 } }
+//! impl NetMessage for Ability { const CHANNEL: &'static str = "ability"; }
+//!
+//! networking.register_message::<Ability>()?;          // on both ends
+//! networking.send_to_server(&Ability::Dash)?;
+//! for (peer, ability) in networking.take_messages::<Ability>() { ... }
+//! ```
+//!
+//! Messages go out on the reliable channel as the channel's id (a hash of its name) followed
+//! by the JSON of the value, and wait per channel until the game takes them.
+
+use std::any::Any;
+use std::collections::{HashMap, VecDeque};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::engine::networking::{NetworkError, PeerId};
+
+/// Received messages kept per channel; older ones are dropped if nobody takes them.
+const RECEIVED_LIMIT: usize = 1024;
+
+/// A message type; both ends must `Networking::register_message` it.
+pub trait NetMessage: Serialize + DeserializeOwned + 'static {
+    /// Name of the channel carrying this type, unique among registered types.
+    const CHANNEL: &'static str;
+}
+
+/// Wire id of a channel: FNV-1a of its name, so both ends agree without a handshake.
+pub fn channel_id(name: &str) -> u32 {
+    name.bytes().fold(0x811c_9dc5, |hash, b| {
+        (hash ^ u32::from(b)).wrapping_mul(0x0100_0193)
+    })
+}
+
+type Decoder = fn(&[u8]) -> Result<Box<dyn Any>, serde_json::Error>;
+
+fn decode<T: NetMessage>(json: &[u8]) -> Result<Box<dyn Any>, serde_json::Error> {
+    Ok(Box::new(serde_json::from_slice::<T>(json)?))
+}
+
+struct Registered {
+    name: &'static str,
+    decode: Decoder,
+    received: VecDeque<(PeerId, Box<dyn Any>)>,
+}
+
+/// Registered message types and what arrived for each.
+#[derive(Default)]
+pub(crate) struct MessageChannels {
+    channels: HashMap<u32, Registered>,
+}
+
+impl MessageChannels {
+    /// Registering a type twice is a no-op; two names with the same id are an error.
+    pub(crate) fn register<T: NetMessage>(&mut self) -> Result<(), NetworkError> {
+        let id = channel_id(T::CHANNEL);
+        match self.channels.get(&id) {
+            Some(existing) if existing.name == T::CHANNEL => Ok(()),
+            Some(existing) => Err(NetworkError::ChannelCollision {
+                first: existing.name,
+                second: T::CHANNEL,
+            }),
+            None => {
+                self.channels.insert(
+                    id,
+                    Registered {
+                        name: T::CHANNEL,
+                        decode: decode::<T>,
+                        received: VecDeque::new(),
+                    },
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Channel id and JSON of `message`.
+    pub(crate) fn encode<T: NetMessage>(&self, message: &T) -> Result<Vec<u8>, NetworkError> {
+        let id = channel_id(T::CHANNEL);
+        if !self.channels.contains_key(&id) {
+            return Err(NetworkError::UnregisteredMessage(T::CHANNEL));
+        }
+        let mut body = id.to_le_bytes().to_vec();
+        serde_json::to_writer(&mut body, message)?;
+        Ok(body)
+    }
+
+    /// Decode a received body and queue it on its channel.
+    pub(crate) fn deliver(&mut self, from: PeerId, body: &[u8]) {
+        let Some((id, json)) = body.split_first_chunk::<4>() else {
+            return;
+        };
+        let id = u32::from_le_bytes(*id);
+        let Some(channel) = self.channels.get_mut(&id) else {
+            crate::log_warn!("net: message on unregistered channel {id:#x} from peer {from}");
+            return;
+        };
+        match (channel.decode)(json) {
+            Ok(message) => {
+                if channel.received.len() == RECEIVED_LIMIT {
+                    channel.received.pop_front();
+                }
+                channel.received.push_back((from, message));
+            }
+            Err(e) => crate::log_warn!("net: bad '{}' message from peer {from}: {e}", channel.name),
+        }
+    }
+
+    /// Messages of type `T` received so far, oldest first.
+    pub(crate) fn take<T: NetMessage>(&mut self) -> Vec<(PeerId, T)> {
+        let Some(channel) = self.channels.get_mut(&channel_id(T::CHANNEL)) else {
+            return Vec::new();
+        };
+        channel
+            .received
+            .drain(..)
+            .filter_map(|(from, message)| Some((from, *message.downcast::<T>().ok()?)))
+            .collect()
+    }
+}
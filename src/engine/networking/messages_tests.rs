@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use serde::{Deserialize, Serialize};
+
+    use crate::engine::networking::{
+        LoopbackNetwork, NetMessage, NetworkError, Networking, PeerId, Transport,
+    };
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Ability {
+        Dash,
+        Cast { spell: u32 },
+    }
+
+    impl NetMessage for Ability {
+        const CHANNEL: &'static str = "ability";
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Emote(String);
+
+    impl NetMessage for Emote {
+        const CHANNEL: &'static str = "emote";
+    }
+
+    #[test]
+    fn messages_reach_the_server_and_broadcasts_reach_every_client() {
+        let now = Instant::now();
+        let network = LoopbackNetwork::new();
+        let server_transport = network.endpoint();
+        let server_addr = server_transport.local_addr();
+        let mut server = Networking::server(server_transport);
+        let mut clients = [
+            Networking::client(network.endpoint(), server_addr),
+            Networking::client(network.endpoint(), server_addr),
+        ];
+        server.register_message::<Ability>().unwrap();
+        for client in &mut clients {
+            client.register_message::<Ability>().unwrap();
+        }
+        // Registering twice is harmless.
+        server.register_message::<Ability>().unwrap();
+        // Only the first client knows emotes.
+        clients[0].register_message::<Emote>().unwrap();
+        for client in &mut clients {
+            client.poll_at(now).unwrap();
+        }
+        server.poll_at(now).unwrap();
+        for client in &mut clients {
+            client.poll_at(now).unwrap();
+        }
+
+        clients[1]
+            .send_to_server(&Ability::Cast { spell: 7 })
+            .unwrap();
+        clients[0].send_to_server(&Emote("wave".into())).unwrap();
+        clients[0].send_to_server(&Ability::Dash).unwrap();
+        assert!(matches!(
+            clients[1].send_to_server(&Emote("wave".into())),
+            Err(NetworkError::UnregisteredMessage("emote"))
+        ));
+        for client in &mut clients {
+            client.poll_at(now).unwrap();
+        }
+        server.poll_at(now).unwrap();
+        let mut received = server.take_messages::<Ability>();
+        received.sort_by_key(|(peer, _)| *peer);
+        assert_eq!(
+            received,
+            vec![
+                (PeerId(1), Ability::Dash),
+                (PeerId(2), Ability::Cast { spell: 7 })
+            ]
+        );
+        // The server never registered emotes, so that one was dropped.
+        assert!(server.take_messages::<Emote>().is_empty());
+        assert!(server.take_messages::<Ability>().is_empty());
+
+        server.broadcast(&Ability::Dash).unwrap();
+        server
+            .send_to_client(PeerId(2), &Ability::Cast { spell: 1 })
+            .unwrap();
+        server.poll_at(now).unwrap();
+        for client in &mut clients {
+            client.poll_at(now).unwrap();
+        }
+        assert_eq!(
+            clients[0].take_messages::<Ability>(),
+            vec![(PeerId::SERVER, Ability::Dash)]
+        );
+        assert_eq!(
+            clients[1].take_messages::<Ability>(),
+            vec![
+                (PeerId::SERVER, Ability::Dash),
+                (PeerId::SERVER, Ability::Cast { spell: 1 })
+            ]
+        );
+    }
+}
@@ -0,0 +1,31 @@
+//! Networking: transports that move packets between peers, and the connections on top.
+//!
+//! Everything above the socket talks to a `Transport`: `UdpTransport` for real networks,
+//! `LoopbackTransport` for endpoints in the same process, which delivers deterministically and
+//! lets networking code be tested without sockets. `Networking` (see `connections`) turns a
+//! transport into a server or client with numbered peers, typed messages (`messages`) and
+//! text chat (`chat`).
+
+pub mod chat;
+#[cfg(test)]
+mod chat_tests;
+pub mod connections;
+#[cfg(test)]
+mod connections_tests;
+pub mod error;
+pub mod loopback;
+pub mod messages;
+#[cfg(test)]
+mod messages_tests;
+pub mod transport;
+#[cfg(test)]
+mod transport_tests;
+pub mod udp;
+
+pub use chat::{ChatLine, ChatLog};
+pub use connections::{DisconnectReason, Incoming, NetEvent, Networking, PeerId, PeerStatus};
+pub use error::NetworkError;
+pub use loopback::{LoopbackNetwork, LoopbackTransport};
+pub use messages::NetMessage;
+pub use transport::{Channel, MAX_PAYLOAD, Packet, Transport};
+pub use udp::UdpTransport;
@@ -0,0 +1,66 @@
+//! The packet interface every transport implements.
+
+use std::net::SocketAddr;
+
+use crate::engine::networking::NetworkError;
+
+/// Largest payload one `send` accepts; keeps UDP datagrams under common path MTUs.
+pub const MAX_PAYLOAD: usize = 1200;
+
+/// Delivery guarantee of a packet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Channel {
+    /// May be lost, duplicated or reordered. For state that is resent every tick anyway.
+    Unreliable,
+    /// Delivered exactly once, in the order sent to that peer.
+    Reliable,
+}
+
+/// A payload received from a peer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packet {
+    pub from: SocketAddr,
+    pub channel: Channel,
+    pub payload: Vec<u8>,
+}
+
+/// Moves packets between this endpoint and its peers. Connectionless: peers are addressed
+/// by `SocketAddr`, and anything that sends to this endpoint is a peer.
+pub trait Transport {
+    /// Address peers send to to reach this endpoint.
+    fn local_addr(&self) -> SocketAddr;
+
+    /// Queue `payload` (at most `MAX_PAYLOAD` bytes) for `to`.
+    fn send(
+        &mut self,
+        to: SocketAddr,
+        channel: Channel,
+        payload: &[u8],
+    ) -> Result<(), NetworkError>;
+
+    /// Do pending I/O: read what arrived, acknowledge and resend. Call once per tick, before
+    /// draining `receive`.
+    fn poll(&mut self) -> Result<(), NetworkError>;
+
+    /// Next packet that arrived by the last `poll`, if any.
+    fn receive(&mut self) -> Option<Packet>;
+
+    /// Expect traffic from `peer` (a client the connection layer accepted, or the server a
+    /// client connects to). Transports with per-peer state ignore reliable packets from
+    /// addresses they neither sent to nor accepted.
+    fn accept(&mut self, _peer: SocketAddr) {}
+
+    /// Drop per-peer state (reliable sequence numbers, unacked packets) for `peer`, so a new
+    /// connection from the same address starts fresh.
+    fn forget(&mut self, _peer: SocketAddr) {}
+}
+
+pub(crate) fn check_payload(payload: &[u8]) -> Result<(), NetworkError> {
+    if payload.len() > MAX_PAYLOAD {
+        return Err(NetworkError::PayloadTooLarge {
+            len: payload.len(),
+            max: MAX_PAYLOAD,
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,140 @@
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crate::engine::networking::udp::{MAX_RESENDS, RECEIVE_WINDOW, RESEND_AFTER, ReliablePeer};
+    use crate::engine::networking::{
+        Channel, LoopbackNetwork, NetworkError, Transport, UdpTransport,
+    };
+
+    fn drain(t: &mut impl Transport) -> Vec<(Channel, Vec<u8>)> {
+        t.poll().unwrap();
+        std::iter::from_fn(|| t.receive())
+            .map(|p| (p.channel, p.payload))
+            .collect()
+    }
+
+    #[test]
+    fn loopback_delivers_in_order_on_the_next_poll() {
+        let net = LoopbackNetwork::new();
+        let mut a = net.endpoint();
+        let mut b = net.endpoint();
+        a.send(b.local_addr(), Channel::Reliable, b"one").unwrap();
+        a.send(b.local_addr(), Channel::Unreliable, b"two").unwrap();
+        assert_eq!(b.receive(), None);
+        assert_eq!(
+            drain(&mut b),
+            vec![
+                (Channel::Reliable, b"one".to_vec()),
+                (Channel::Unreliable, b"two".to_vec())
+            ]
+        );
+
+        net.drop_unreliable_every(2);
+        for i in 0..4u8 {
+            b.send(a.local_addr(), Channel::Unreliable, &[i]).unwrap();
+        }
+        b.send(a.local_addr(), Channel::Reliable, b"kept").unwrap();
+        let payloads: Vec<Vec<u8>> = drain(&mut a).into_iter().map(|(_, p)| p).collect();
+        assert_eq!(payloads, vec![vec![0], vec![2], b"kept".to_vec()]);
+
+        let gone = b.local_addr();
+        drop(b);
+        assert!(matches!(
+            a.send(gone, Channel::Reliable, b"?"),
+            Err(NetworkError::UnknownPeer(addr)) if addr == gone
+        ));
+        assert!(matches!(
+            a.send(a.local_addr(), Channel::Reliable, &[0; 2000]),
+            Err(NetworkError::PayloadTooLarge { len: 2000, .. })
+        ));
+    }
+
+    #[test]
+    fn reliable_peer_reorders_dedups_and_resends_until_acked() {
+        let start = Instant::now();
+        let mut sender = ReliablePeer::default();
+        let mut receiver = ReliablePeer::default();
+        for payload in [b"a", b"b", b"c"] {
+            sender.wrap(payload, start);
+        }
+
+        // "b" and "c" arrive before "a", and "b" twice.
+        assert!(receiver.receive(1, b"b").is_empty());
+        assert!(receiver.receive(2, b"c").is_empty());
+        assert!(receiver.receive(1, b"b").is_empty());
+        assert_eq!(
+            receiver.receive(0, b"a"),
+            vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec()]
+        );
+        assert!(receiver.receive(0, b"a").is_empty());
+
+        sender.acked(0);
+        sender.acked(2);
+        assert!(
+            sender
+                .due_resends(start + RESEND_AFTER / 2)
+                .unwrap()
+                .is_empty()
+        );
+        let resent = sender.due_resends(start + RESEND_AFTER).unwrap();
+        assert_eq!(resent.len(), 1);
+        assert_eq!(&resent[0][5..], b"b");
+        sender.acked(1);
+        assert_eq!(sender.in_flight(), 0);
+    }
+
+    #[test]
+    fn reliable_peer_bounds_early_messages_and_resends() {
+        let mut receiver = ReliablePeer::default();
+        assert!(receiver.in_window(RECEIVE_WINDOW - 1));
+        assert!(!receiver.in_window(RECEIVE_WINDOW));
+        assert!(receiver.receive(RECEIVE_WINDOW, b"far").is_empty());
+        assert!(receiver.receive(u32::MAX, b"far").is_empty());
+        assert_eq!(receiver.receive(0, b"a"), vec![b"a".to_vec()]);
+        // Only now is `RECEIVE_WINDOW` in the window; the early copy was not kept.
+        assert!(receiver.in_window(RECEIVE_WINDOW));
+        assert!(receiver.receive(RECEIVE_WINDOW, b"far").is_empty());
+
+        let mut sender = ReliablePeer::default();
+        let mut now = Instant::now();
+        sender.wrap(b"lost", now);
+        for _ in 0..MAX_RESENDS {
+            now += RESEND_AFTER;
+            assert_eq!(sender.due_resends(now).unwrap().len(), 1);
+        }
+        now += RESEND_AFTER;
+        assert!(sender.due_resends(now).is_none());
+    }
+
+    #[test]
+    fn udp_round_trip_on_localhost() {
+        let mut server = UdpTransport::bind("127.0.0.1:0").unwrap();
+        let mut client = UdpTransport::bind("127.0.0.1:0").unwrap();
+        let mut stranger = UdpTransport::bind("127.0.0.1:0").unwrap();
+        stranger
+            .send(server.local_addr(), Channel::Reliable, b"?")
+            .unwrap();
+        server.accept(client.local_addr());
+        for i in 0..5u8 {
+            client
+                .send(server.local_addr(), Channel::Reliable, &[i])
+                .unwrap();
+        }
+
+        let mut received = Vec::new();
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while (received.len() < 5 || client.in_flight(server.local_addr()) > 0)
+            && Instant::now() < deadline
+        {
+            received.extend(drain(&mut server).into_iter().map(|(_, p)| p[0]));
+            client.poll().unwrap();
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        // The stranger was never accepted: not delivered, not acked.
+        assert_eq!(received, vec![0, 1, 2, 3, 4]);
+        assert_eq!(client.in_flight(server.local_addr()), 0);
+        stranger.poll().unwrap();
+        assert_eq!(stranger.in_flight(server.local_addr()), 1);
+    }
+}
@@ -0,0 +1,272 @@
+//! UDP transport with a small reliability layer for `Channel::Reliable`.
+//!
+//! Every datagram starts with a kind byte:
+//!
+//! - `0` unreliable: the payload follows.
+//! - `1` reliable: a `u32` sequence number (little endian), then the payload.
+//! - `2` ack: the `u32` sequence number of a reliable datagram that arrived.
+//!
+//! Reliable datagrams are numbered per peer and resent every `RESEND_AFTER` until acked, at
+//! most `MAX_RESENDS` times; then `poll` reports the peer as `NetworkError::Unacknowledged`.
+//! The receiver acks each one, holds back those that arrive early (up to `RECEIVE_WINDOW`
+//! ahead; later ones are dropped unacked, so they come again) and delivers them in sequence
+//! order, dropping duplicates.
+//!
+//! Reliable state exists only for peers this endpoint sent to or `accept`ed; reliable
+//! datagrams from anyone else are dropped without an ack.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::io::ErrorKind;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::{Duration, Instant};
+
+use crate::engine::networking::transport::{MAX_PAYLOAD, check_payload};
+use crate::engine::networking::{Channel, NetworkError, Packet, Transport};
+
+/// How long a reliable datagram waits for its ack before it is sent again.
+pub const RESEND_AFTER: Duration = Duration::from_millis(100);
+
+/// Resends of one reliable datagram before its peer counts as unreachable (5 s at
+/// `RESEND_AFTER`, the connection layer's `TIMEOUT`).
+pub const MAX_RESENDS: u32 = 50;
+
+/// How far past the next expected sequence number reliable datagrams are held back.
+pub const RECEIVE_WINDOW: u32 = 256;
+
+const UNRELIABLE: u8 = 0;
+const RELIABLE: u8 = 1;
+const ACK: u8 = 2;
+/// Kind byte plus sequence number.
+const HEADER: usize = 5;
+
+/// Transport over a non-blocking UDP socket.
+#[derive(Debug)]
+pub struct UdpTransport {
+    socket: UdpSocket,
+    addr: SocketAddr,
+    peers: HashMap<SocketAddr, ReliablePeer>,
+    inbox: VecDeque<Packet>,
+}
+
+impl UdpTransport {
+    /// Bind to `addr` (`"0.0.0.0:7777"` for a server, `"0.0.0.0:0"` for a client).
+    pub fn bind(addr: impl ToSocketAddrs) -> Result<Self, NetworkError> {
+        let socket = UdpSocket::bind(addr)?;
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            addr: socket.local_addr()?,
+            socket,
+            peers: HashMap::new(),
+            inbox: VecDeque::new(),
+        })
+    }
+
+    /// Reliable messages sent to `peer` that it has not acked yet.
+    pub fn in_flight(&self, peer: SocketAddr) -> usize {
+        self.peers.get(&peer).map_or(0, ReliablePeer::in_flight)
+    }
+
+    /// Send a datagram; a full socket buffer counts as loss, which the reliable channel
+    /// recovers from by resending.
+    fn send_datagram(&self, to: SocketAddr, datagram: &[u8]) -> Result<(), NetworkError> {
+        match self.socket.send_to(datagram, to) {
+            Ok(_) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::WouldBlock => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn handle_datagram(&mut self, from: SocketAddr, datagram: &[u8]) -> Result<(), NetworkError> {
+        match datagram.split_first() {
+            Some((&UNRELIABLE, payload)) => self.inbox.push_back(Packet {
+                from,
+                channel: Channel::Unreliable,
+                payload: payload.to_vec(),
+            }),
+            Some((&RELIABLE, rest)) if rest.len() >= 4 => {
+                let seq = u32::from_le_bytes([rest[0], rest[1], rest[2], rest[3]]);
+                let Some(peer) = self.peers.get_mut(&from) else {
+                    crate::log_debug!("udp: dropping reliable datagram from unknown {from}");
+                    return Ok(());
+                };
+                if !peer.in_window(seq) {
+                    return Ok(());
+                }
+                let delivered = peer.receive(seq, &rest[4..]);
+                let mut ack = [ACK, 0, 0, 0, 0];
+                ack[1..].copy_from_slice(&seq.to_le_bytes());
+                self.send_datagram(from, &ack)?;
+                self.inbox
+                    .extend(delivered.into_iter().map(|payload| Packet {
+                        from,
+                        channel: Channel::Reliable,
+                        payload,
+                    }));
+            }
+            Some((&ACK, rest)) if rest.len() >= 4 => {
+                let seq = u32::from_le_bytes([rest[0], rest[1], rest[2], rest[3]]);
+                if let Some(peer) = self.peers.get_mut(&from) {
+                    peer.acked(seq);
+                }
+            }
+            _ => crate::log_warn!("udp: dropping malformed datagram from {from}"),
+        }
+        Ok(())
+    }
+}
+
+impl Transport for UdpTransport {
+    fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    fn send(
+        &mut self,
+        to: SocketAddr,
+        channel: Channel,
+        payload: &[u8],
+    ) -> Result<(), NetworkError> {
+        check_payload(payload)?;
+        let datagram = match channel {
+            Channel::Unreliable => [&[UNRELIABLE], payload].concat(),
+            Channel::Reliable => self
+                .peers
+                .entry(to)
+                .or_default()
+                .wrap(payload, Instant::now()),
+        };
+        self.send_datagram(to, &datagram)
+    }
+
+    fn poll(&mut self) -> Result<(), NetworkError> {
+        let mut buf = [0u8; HEADER + MAX_PAYLOAD];
+        loop {
+            match self.socket.recv_from(&mut buf) {
+                Ok((len, from)) => self.handle_datagram(from, &buf[..len])?,
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                // ICMP port unreachable from an earlier send (Windows); not this read's fault.
+                Err(e) if e.kind() == ErrorKind::ConnectionReset => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        let now = Instant::now();
+        let mut resends: Vec<(SocketAddr, Vec<u8>)> = Vec::new();
+        let mut unreachable = None;
+        for (&addr, peer) in &mut self.peers {
+            match peer.due_resends(now) {
+                Some(due) => resends.extend(due.into_iter().map(|d| (addr, d))),
+                None => {
+                    unreachable.get_or_insert(addr);
+                }
+            }
+        }
+        for (to, datagram) in resends {
+            self.send_datagram(to, &datagram)?;
+        }
+        // Others that gave up this tick are reported by the next poll.
+        if let Some(addr) = unreachable {
+            self.peers.remove(&addr);
+            return Err(NetworkError::Unacknowledged(addr));
+        }
+        Ok(())
+    }
+
+    fn receive(&mut self) -> Option<Packet> {
+        self.inbox.pop_front()
+    }
+
+    fn accept(&mut self, peer: SocketAddr) {
+        self.peers.entry(peer).or_default();
+    }
+
+    fn forget(&mut self, peer: SocketAddr) {
+        self.peers.remove(&peer);
+    }
+}
+
+#[derive(Debug)]
+struct Unacked {
+    datagram: Vec<u8>,
+    sent_at: Instant,
+    resends: u32,
+}
+
+/// Reliable-channel state for one peer, both directions. Sequence numbers are `u32` and do
+/// not wrap: four billion reliable messages to one peer outlast any session.
+#[derive(Debug, Default)]
+pub(crate) struct ReliablePeer {
+    next_seq: u32,
+    unacked: BTreeMap<u32, Unacked>,
+    expected: u32,
+    early: BTreeMap<u32, Vec<u8>>,
+}
+
+impl ReliablePeer {
+    /// Datagram carrying `payload` as the next reliable message; kept until acked.
+    pub(crate) fn wrap(&mut self, payload: &[u8], now: Instant) -> Vec<u8> {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        let mut datagram = Vec::with_capacity(HEADER + payload.len());
+        datagram.push(RELIABLE);
+        datagram.extend_from_slice(&seq.to_le_bytes());
+        datagram.extend_from_slice(payload);
+        self.unacked.insert(
+            seq,
+            Unacked {
+                datagram: datagram.clone(),
+                sent_at: now,
+                resends: 0,
+            },
+        );
+        datagram
+    }
+
+    /// Whether reliable message `seq` may be acked: already delivered (the ack was lost) or
+    /// at most `RECEIVE_WINDOW` ahead.
+    pub(crate) fn in_window(&self, seq: u32) -> bool {
+        seq < self.expected.saturating_add(RECEIVE_WINDOW)
+    }
+
+    /// Reliable message `seq` arrived; returns the payloads now deliverable, in order.
+    /// Messages outside the window are dropped.
+    pub(crate) fn receive(&mut self, seq: u32, payload: &[u8]) -> Vec<Vec<u8>> {
+        if seq < self.expected || !self.in_window(seq) {
+            return Vec::new();
+        }
+        self.early.entry(seq).or_insert_with(|| payload.to_vec());
+        let mut delivered = Vec::new();
+        while let Some(payload) = self.early.remove(&self.expected) {
+            delivered.push(payload);
+            self.expected += 1;
+        }
+        delivered
+    }
+
+    pub(crate) fn acked(&mut self, seq: u32) {
+        self.unacked.remove(&seq);
+    }
+
+    /// Datagrams that have waited `RESEND_AFTER` for an ack, restarting their wait; `None`
+    /// once one of them was resent `MAX_RESENDS` times.
+    pub(crate) fn due_resends(&mut self, now: Instant) -> Option<Vec<Vec<u8>>> {
+        let mut due = Vec::new();
+        for u in self.unacked.values_mut() {
+            if now.duration_since(u.sent_at) < RESEND_AFTER {
+                continue;
+            }
+            if u.resends >= MAX_RESENDS {
+                return None;
+            }
+            u.resends += 1;
+            u.sent_at = now;
+            due.push(u.datagram.clone());
+        }
+        Some(due)
+    }
+
+    /// Reliable messages sent but not acked yet.
+    pub(crate) fn in_flight(&self) -> usize {
+        self.unacked.len()
+    }
+}
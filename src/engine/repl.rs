@@ -0,0 +1,1282 @@
+//! Text command interface to a running `Universe`.
+//!
+//! Commands arrive from two front-ends and go through the same `ReplBackend`:
+//! - stdin (a reader thread started by `spawn_stdin_reader`), for terminal sessions;
+//! - the in-game `Console` (toggled with `` ` ``), for when stdin/stdout aren't visible.
+//!
+//! Every output line is echoed to stdout and kept in the console scrollback.
+//!
+//! Lines may hold several commands separated by `;`, and the first word of each command is
+//! expanded through `alias` definitions. At startup `Universe` runs the rc script
+//! (`~/.littlecatrc`, or the file named by `LITTLECAT_RC`) after the world is built.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+
+use crate::engine::Universe;
+use crate::engine::action_map::INPUT_BINDINGS_PATH;
+use crate::engine::ecs::ComponentId;
+use crate::engine::ecs::codec::{ComponentCodec, ComponentDataNode, SceneFormat, Value};
+use crate::engine::ecs::component::StateMachineComponent;
+use crate::engine::ecs::prefab::PrefabInstance;
+use crate::engine::ecs::query::Query;
+use crate::engine::ecs::system::{
+    Paused, PendingCommands, RunCriteria, Scheduler, StateMachineSystem, SystemId, TimerId,
+};
+use crate::engine::graphics::exposure::AutoExposure;
+use crate::engine::graphics::render_scale::MIN_RENDER_SCALE;
+use crate::engine::graphics::{
+    BlendMode, CullMode, Culling, Exposure, FrontFace, MaterialHandle, Outline, Reflection,
+    RenderScale, VisualBackground,
+};
+use crate::engine::i18n;
+use crate::engine::networking::ChatLine;
+use crate::engine::save_game::SaveGame;
+use crate::utils::color::Color;
+use crate::utils::logger::{self, Level};
+use crate::utils::{clipboard, profiling, trace};
+
+/// Startup script looked up in the home directory.
+pub const RC_FILE_NAME: &str = ".littlecatrc";
+
+/// Environment variable overriding the startup script path.
+pub const RC_ENV_VAR: &str = "LITTLECAT_RC";
+
+/// Output of `graphviz` without a path argument.
+const GRAPHVIZ_DEFAULT_PATH: &str = "world.dot";
+
+/// Output of `trace stop` without a path argument.
+const TRACE_DEFAULT_PATH: &str = "trace.json";
+
+/// Maximum `source` nesting (guards scripts that source themselves).
+const MAX_SOURCE_DEPTH: usize = 8;
+
+/// Executes one command (already alias-expanded, no `;`) and returns the output lines.
+pub trait ReplBackend {
+    fn execute(&mut self, line: &str) -> Vec<String>;
+}
+
+/// REPL front-end state owned by `Universe`: aliases and the stdin reader.
+#[derive(Debug)]
+pub struct Repl {
+    aliases: BTreeMap<String, String>,
+    stdin: Option<Receiver<String>>,
+    source_depth: usize,
+}
+
+impl Repl {
+    pub fn new() -> Self {
+        Self {
+            stdin: Some(spawn_stdin_reader()),
+            ..Self::without_stdin()
+        }
+    }
+
+    /// A REPL that never reads the terminal (commands only come from code, scripts and the
+    /// console).
+    pub fn without_stdin() -> Self {
+        Self {
+            aliases: BTreeMap::new(),
+            stdin: None,
+            source_depth: 0,
+        }
+    }
+
+    /// Lines typed into the terminal since the last call.
+    pub fn poll_stdin(&mut self) -> Vec<String> {
+        match &self.stdin {
+            Some(stdin) => stdin.try_iter().collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Split a line on `;` and expand the leading alias of each command.
+    ///
+    /// Alias bodies may contain `;` themselves; expansion is applied once (no recursion). A
+    /// line starting with `alias` is one command, so the body it defines keeps its `;`.
+    pub fn expand(&self, line: &str) -> Vec<String> {
+        let line = line.trim();
+        if line.split_whitespace().next() == Some("alias") {
+            return vec![line.to_string()];
+        }
+        let mut out = Vec::new();
+        for command in line.split(';').map(str::trim).filter(|c| !c.is_empty()) {
+            let (head, rest) = command
+                .split_once(char::is_whitespace)
+                .unwrap_or((command, ""));
+            match self.aliases.get(head) {
+                Some(body) => {
+                    let mut parts: Vec<String> = body
+                        .split(';')
+                        .map(str::trim)
+                        .filter(|p| !p.is_empty())
+                        .map(str::to_string)
+                        .collect();
+                    // Extra arguments go to the last command of the alias body.
+                    if let Some(last) = parts.last_mut().filter(|_| !rest.trim().is_empty()) {
+                        last.push(' ');
+                        last.push_str(rest.trim());
+                    }
+                    out.extend(parts);
+                }
+                None => out.push(command.to_string()),
+            }
+        }
+        out
+    }
+
+    pub fn set_alias(&mut self, name: impl Into<String>, body: impl Into<String>) {
+        self.aliases.insert(name.into(), body.into());
+    }
+
+    pub fn remove_alias(&mut self, name: &str) -> bool {
+        self.aliases.remove(name).is_some()
+    }
+
+    pub fn aliases(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.aliases.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+}
+
+impl Default for Repl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Startup script path: `$LITTLECAT_RC`, else `$HOME/.littlecatrc` (`%USERPROFILE%` on
+/// Windows).
+pub fn rc_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os(RC_ENV_VAR) {
+        return Some(PathBuf::from(path));
+    }
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?;
+    Some(PathBuf::from(home).join(RC_FILE_NAME))
+}
+
+/// Command lines of a script: blank lines and `#` comments are skipped.
+pub fn read_script(path: &Path) -> std::io::Result<Vec<String>> {
+    Ok(std::fs::read_to_string(path)?
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Read stdin lines on a background thread. The thread exits when stdin closes.
+pub(crate) fn spawn_stdin_reader() -> Receiver<String> {
+    let (tx, rx) = mpsc::channel();
+    let _ = std::thread::Builder::new()
+        .name("repl-stdin".into())
+        .spawn(move || {
+            for line in std::io::stdin().lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+    rx
+}
+
+const HELP: &[&str] = &[
+    "help                      list commands",
+    "bind <action> <binding>   rebind an action (e.g. bind move_up KeyW)",
+    "unbind <action>           remove an action's bindings",
+    "bindings                  list action bindings",
+    "save_bindings [path]      write bindings (default assets/input-bindings.json)",
+    "save [--binary] <path>    write every component tree as a scene (binary for *.lcscene)",
+    "load <path>               add the component trees of a scene (binary for *.lcscene)",
+    "savegame <slot>           save the persistent components and resources to a slot",
+    "loadgame <slot>           put a slot's saved state back into the world",
+    "saves                     list save slots",
+    "inspector                 toggle the rendering inspector",
+    "fps [n|off]               show or set the frame rate cap while focused",
+    "render_scale [s|auto fps] show or set the scene resolution scale (0.25..1, or automatic)",
+    "exposure [value|auto]     show or set the HDR exposure (manual scale, or adapt to the scene)",
+    "bloom [off|intensity [threshold]] show or set the glow of emissive materials",
+    "taa [on|off]              show or toggle temporal anti-aliasing",
+    "render [clear <color>|letterbox <w h|off>] show or set the clear color and virtual resolution",
+    "outline <material> [off|width [color]] show or set a material's outline (toon, unlit or id)",
+    "reflect <material> [off|strength] show or set a material's reflection of the skybox (0..1)",
+    "blend <material> [opaque|alpha|additive|multiply] show or set a material's blend mode",
+    "cull <material> [none|back|front [ccw|cw]] show or set a material's face culling",
+    "net [status]              connection role, peers and their round-trip times",
+    "say <text...>             send a chat line (offline: only logged here)",
+    "chat [n]                  show the last n chat lines (default 20)",
+    "system [list]             list tick systems: on/off and when they run",
+    "system enable|disable <name> switch a system's tick on or off (e.g. system disable renderable)",
+    "system run <name> always|unpaused|every <n> set when a system ticks",
+    "system pause|resume       pause or resume the systems that run 'unpaused'",
+    "fsm <id>                  show a state machine: active states, transitions, problems",
+    "fsm <id> send <event>     send an event to a state machine",
+    "fsm <id> goto <state>     switch a state machine's state (hooks run next update)",
+    "after <seconds> <command...> run a command once, later (an alias runs several)",
+    "every <seconds> <command...> run a command repeatedly",
+    "timers                    list pending timers",
+    "cancel <timer>            stop a timer (e.g. cancel t3)",
+    "locale [name]             show or switch the locale of translated text",
+    "tr <key>                  show a string table entry in the current locale",
+    "vram                      device memory used by meshes, textures and per-frame buffers",
+    "stats [prefix]            show profiling gauges and last-frame counters (e.g. stats textures)",
+    "trace [start|stop [path]] capture frame spans as chrome://tracing JSON (default trace.json)",
+    "ls [id]                   list root components (or the children of id), oldest first",
+    "find <terms...>           list matching component ids (type= name= has= x>1 under <id>)",
+    "describe <type|id>        list a component type's editable fields",
+    "set <id> <field> <value>  change a field at runtime (camera3d projection, fov_y_radians, z_near, z_far, ortho_size)",
+    "cat <id>                  print a component subtree as scene JSON",
+    "prefab <path> [parent-id] instantiate a prefab scene file",
+    "spawn <type> [parent-id] [json] add a component (e.g. spawn velocity 3v1 {\"linear\":[1,0,0]})",
+    "graphviz [path]           write the component graph as DOT (default world.dot)",
+    "log [module] [level|default] show or set log filters (e.g. log engine::graphics debug)",
+    "alias [name [command...]] list, show or define aliases (';' separates commands)",
+    "unalias <name>            remove an alias",
+    "source <path>             run a script of commands",
+    "<command> | copy          put a command's output on the clipboard (e.g. cat 3v1 | copy)",
+];
+
+impl ReplBackend for Universe {
+    fn execute(&mut self, line: &str) -> Vec<String> {
+        if let Some((command, sink)) = line.rsplit_once('|')
+            && sink.trim() == "copy"
+        {
+            let out = self.execute(command.trim());
+            let text = out.join("\n");
+            return match clipboard::copy(&text) {
+                Ok(()) => vec![format!("copied {} lines", out.len())],
+                Err(e) => vec![format!("copy: {e} (Ctrl+V in the console still pastes it)")],
+            };
+        }
+
+        let args: Vec<&str> = line.split_whitespace().collect();
+        let Some((&command, args)) = args.split_first() else {
+            return Vec::new();
+        };
+        let actions = &mut self.systems.input.actions;
+
+        match (command, args) {
+            ("help", _) => HELP.iter().map(|l| l.to_string()).collect(),
+            ("bind", [action, binding]) => match actions.rebind(action, binding) {
+                Ok(()) => vec![format!("{action} -> {binding}")],
+                Err(e) => vec![e],
+            },
+            ("unbind", [action]) => {
+                actions.unbind(action);
+                vec![format!("{action} unbound")]
+            }
+            ("bindings", []) => {
+                let mut names: Vec<&str> = actions.actions().collect();
+                names.sort_unstable();
+                names
+                    .into_iter()
+                    .map(|action| {
+                        let bound: Vec<String> = actions
+                            .bindings(action)
+                            .iter()
+                            .filter_map(|b| b.name())
+                            .collect();
+                        format!("{action}: {}", bound.join(", "))
+                    })
+                    .collect()
+            }
+            ("save_bindings", [] | [_]) => {
+                let path = match args.first() {
+                    Some(path) => PathBuf::from(path),
+                    None => self.asset_path(INPUT_BINDINGS_PATH),
+                };
+                match self.systems.input.actions.save(&path) {
+                    Ok(()) => vec![format!("saved {}", path.display())],
+                    Err(e) => vec![format!("save failed: {e}")],
+                }
+            }
+            ("alias", []) => self
+                .repl
+                .aliases()
+                .map(|(name, body)| format!("alias {name} {body}"))
+                .collect(),
+            ("alias", [name]) => match self.repl.aliases().find(|(n, _)| n == name) {
+                Some((name, body)) => vec![format!("alias {name} {body}")],
+                None => vec![format!("no alias '{name}'")],
+            },
+            ("alias", [name, ..]) => {
+                // Keep the body verbatim (including `;`), minus the `alias <name>` prefix.
+                let body = line
+                    .trim_start()
+                    .strip_prefix("alias")
+                    .map(str::trim_start)
+                    .and_then(|l| l.strip_prefix(name))
+                    .map(str::trim)
+                    .unwrap_or_default();
+                self.repl.set_alias(*name, body);
+                Vec::new()
+            }
+            ("unalias", [name]) => {
+                if self.repl.remove_alias(name) {
+                    Vec::new()
+                } else {
+                    vec![format!("no alias '{name}'")]
+                }
+            }
+            ("source", [path]) => self.source(Path::new(path)),
+            ("log", []) => std::iter::once(format!("default: {}", logger::default_level()))
+                .chain(
+                    logger::module_levels()
+                        .into_iter()
+                        .map(|(module, level)| format!("{module}: {level}")),
+                )
+                .collect(),
+            ("log", [level]) => match level.parse::<Level>() {
+                Ok(level) => {
+                    logger::set_default_level(level);
+                    vec![format!("default: {level}")]
+                }
+                Err(e) => vec![e],
+            },
+            ("log", [module, "default"]) => {
+                if logger::clear_module_level(module) {
+                    vec![format!("{module}: default")]
+                } else {
+                    vec![format!("no filter for '{module}'")]
+                }
+            }
+            ("log", [module, level]) => match level.parse::<Level>() {
+                Ok(level) => {
+                    logger::set_module_level(module, level);
+                    vec![format!("{module}: {level}")]
+                }
+                Err(e) => vec![e],
+            },
+            ("save", [path] | ["--binary", path]) => {
+                let scene = ComponentCodec::encode_world(&self.world);
+                let format = match args {
+                    ["--binary", _] => SceneFormat::Binary,
+                    _ => SceneFormat::from_path(Path::new(path)),
+                };
+                match scene.save_as(path, format) {
+                    Ok(()) => vec![format!("saved {path} ({} roots)", scene.roots.len())],
+                    Err(e) => vec![format!("save failed: {e}")],
+                }
+            }
+            ("load", [path]) => match self.load_scene(path) {
+                Ok(roots) => vec![format!("loaded {path} ({} roots)", roots.len())],
+                Err(e) => vec![format!("load failed: {e}")],
+            },
+            ("savegame", [slot]) => {
+                let saves = self
+                    .world
+                    .resource::<SaveGame>()
+                    .cloned()
+                    .unwrap_or_default();
+                match saves.save(&self.world, slot) {
+                    Ok(path) => vec![format!("saved {}", path.display())],
+                    Err(e) => vec![format!("savegame: {e}")],
+                }
+            }
+            ("loadgame", [slot]) => {
+                let saves = self
+                    .world
+                    .resource::<SaveGame>()
+                    .cloned()
+                    .unwrap_or_default();
+                match saves.load(&mut self.world, &mut self.command_queue, slot) {
+                    Ok(report) => {
+                        let mut out = vec![format!(
+                            "loaded {slot}: {} components, {} resources",
+                            report.components, report.resources
+                        )];
+                        out.extend(report.skipped.into_iter().map(|l| format!("  skipped {l}")));
+                        out
+                    }
+                    Err(e) => vec![format!("loadgame: {e}")],
+                }
+            }
+            ("saves", []) => {
+                let saves = self
+                    .world
+                    .resource::<SaveGame>()
+                    .cloned()
+                    .unwrap_or_default();
+                match saves.slots() {
+                    Ok(slots) if slots.is_empty() => {
+                        vec![format!("no saves in {}", saves.dir().display())]
+                    }
+                    Ok(slots) => {
+                        let now = std::time::SystemTime::now()
+                            .duration_since(std::time::UNIX_EPOCH)
+                            .map_or(0, |d| d.as_secs());
+                        slots
+                            .into_iter()
+                            .map(|s| {
+                                let age = now.saturating_sub(s.saved_at);
+                                format!("{:<16} version {}, {} ago", s.name, s.version, ago(age))
+                            })
+                            .collect()
+                    }
+                    Err(e) => vec![format!("saves: {e}")],
+                }
+            }
+            ("find", terms) => match Query::parse(terms) {
+                Ok(query) => query
+                    .run(&self.world)
+                    .into_iter()
+                    .map(|cid| cid.to_string())
+                    .collect(),
+                Err(e) => vec![format!("find: {e}")],
+            },
+            ("ls", [] | [_]) => {
+                let ids = match args.first() {
+                    None => self.world.root_components(),
+                    Some(id) => match id
+                        .parse::<ComponentId>()
+                        .ok()
+                        .filter(|&cid| self.world.get_component_record(cid).is_some())
+                    {
+                        Some(cid) => self.world.children_of(cid).to_vec(),
+                        None => return vec![format!("ls: no component '{id}'")],
+                    },
+                };
+                ids.into_iter()
+                    .filter_map(|cid| {
+                        let node = self.world.get_component_record(cid)?;
+                        Some(format!(
+                            "{cid} {} ({} children)",
+                            node.name,
+                            node.children.len()
+                        ))
+                    })
+                    .collect()
+            }
+            ("describe", [target]) => {
+                let schema = match target.parse::<ComponentId>() {
+                    Ok(cid) => self
+                        .world
+                        .get_component_record(cid)
+                        .and_then(|n| n.component.schema()),
+                    Err(_) => self.world.registry().find(target).and_then(|t| t.schema),
+                };
+                match schema {
+                    Some(schema) => std::iter::once(format!("{}:", schema.type_name))
+                        .chain(schema.fields.iter().map(|f| format!("  {f}")))
+                        .collect(),
+                    None => vec![format!("describe: no schema for '{target}'")],
+                }
+            }
+            ("cat", [id]) => {
+                let node = id
+                    .parse::<ComponentId>()
+                    .ok()
+                    .and_then(|cid| ComponentCodec::encode(&self.world, cid));
+                match node.map(|n| serde_json::to_string_pretty(&n.to_json())) {
+                    Some(Ok(json)) => json.lines().map(str::to_string).collect(),
+                    Some(Err(e)) => vec![format!("cat: {e}")],
+                    None => vec![format!("cat: no component '{id}'")],
+                }
+            }
+            ("set", [id, field, value @ ..]) if !value.is_empty() => {
+                self.set_field(id, field, &value.join(" "))
+            }
+            ("spawn", [type_name, rest @ ..]) => self.spawn(type_name, rest),
+            ("prefab", [path] | [path, _]) => self.spawn_prefab(path, args.get(1).copied()),
+            ("graphviz", [] | [_]) => {
+                let path = args.first().copied().unwrap_or(GRAPHVIZ_DEFAULT_PATH);
+                match self.world.export_dot(path) {
+                    Ok(()) => vec![format!(
+                        "wrote {path} ({} components)",
+                        self.world.component_count()
+                    )],
+                    Err(e) => vec![format!("graphviz failed: {e}")],
+                }
+            }
+            ("trace", []) => vec![if trace::is_capturing() {
+                "trace: capturing".to_string()
+            } else {
+                "trace: off".to_string()
+            }],
+            ("trace", ["start"]) => {
+                trace::start();
+                vec!["trace: capturing (trace stop [path] to write it)".to_string()]
+            }
+            ("trace", ["stop"] | ["stop", _]) => {
+                let path = args.get(1).copied().unwrap_or(TRACE_DEFAULT_PATH);
+                let Some(capture) = trace::stop() else {
+                    return vec!["trace: not capturing".to_string()];
+                };
+                match capture.write(path) {
+                    Ok(()) if capture.dropped > 0 => vec![format!(
+                        "wrote {path} ({} spans, {} dropped)",
+                        capture.events.len(),
+                        capture.dropped
+                    )],
+                    Ok(()) => vec![format!("wrote {path} ({} spans)", capture.events.len())],
+                    Err(e) => vec![format!("trace: {path}: {e}")],
+                }
+            }
+            ("stats", [] | [_]) => {
+                let prefix = args.first().copied().unwrap_or("");
+                let mut out: Vec<String> = profiling::gauges()
+                    .into_iter()
+                    .filter(|(name, _)| name.starts_with(prefix))
+                    .map(|(name, value)| format!("{name} = {value}"))
+                    .collect();
+                if let Some(frame) = profiling::last_frame() {
+                    out.extend(
+                        frame
+                            .counters
+                            .iter()
+                            .filter(|(name, _)| name.starts_with(prefix))
+                            .map(|(name, value)| {
+                                format!("{name}: {value} (frame {})", frame.frame)
+                            }),
+                    );
+                }
+                if out.is_empty() {
+                    out.push(format!("no stats matching '{prefix}'"));
+                }
+                out
+            }
+            ("render_scale", []) => {
+                let scale = self.visuals.render_scale();
+                let mode = match scale.auto() {
+                    Some(auto) => format!(" (auto, target {} fps)", auto.target_fps),
+                    None => String::new(),
+                };
+                vec![format!("render scale {:.2}{mode}", scale.scale())]
+            }
+            ("render_scale", ["auto", fps]) => match fps.parse::<u32>() {
+                Ok(fps) if fps > 0 => {
+                    *self.visuals.render_scale_mut() = RenderScale::automatic(fps);
+                    vec![format!("render scale auto, target {fps} fps")]
+                }
+                _ => vec![format!("render_scale: expected a target fps, got '{fps}'")],
+            },
+            ("render_scale", [scale]) => match scale.parse::<f32>() {
+                Ok(s) if (MIN_RENDER_SCALE..=1.0).contains(&s) => {
+                    self.visuals.render_scale_mut().set(s);
+                    vec![format!("render scale {s:.2}")]
+                }
+                _ => vec![format!(
+                    "render_scale: expected {MIN_RENDER_SCALE}..1 or 'auto <fps>', got '{scale}'"
+                )],
+            },
+            ("exposure", []) => {
+                let exposure = self.visuals.exposure();
+                let mode = if exposure.auto().is_some() {
+                    " (auto)"
+                } else {
+                    ""
+                };
+                vec![format!("exposure {:.2}{mode}", exposure.value())]
+            }
+            ("exposure", ["auto"]) => {
+                *self.visuals.exposure_mut() = Exposure::automatic(AutoExposure::default());
+                vec!["exposure auto".to_string()]
+            }
+            ("exposure", [value]) => match value.parse::<f32>() {
+                Ok(v) if v.is_finite() && v >= 0.0 => {
+                    self.visuals.exposure_mut().set(v);
+                    vec![format!("exposure {v:.2}")]
+                }
+                _ => vec![format!(
+                    "exposure: expected a non-negative number or 'auto', got '{value}'"
+                )],
+            },
+            ("bloom", args) => {
+                let bloom = &mut self.visuals.post_process_mut().bloom;
+                match args {
+                    [] => {}
+                    ["off"] => bloom.intensity = 0.0,
+                    [intensity, threshold @ ..] if threshold.len() <= 1 => {
+                        let parse = |s: &str| s.parse::<f32>().ok().filter(|v| *v >= 0.0);
+                        let Some(intensity) = parse(intensity) else {
+                            return vec![format!(
+                                "bloom: expected a non-negative intensity, got '{intensity}'"
+                            )];
+                        };
+                        bloom.intensity = intensity;
+                        if let Some(threshold) = threshold.first() {
+                            let Some(threshold) = parse(threshold) else {
+                                return vec![format!(
+                                    "bloom: expected a non-negative threshold, got '{threshold}'"
+                                )];
+                            };
+                            bloom.threshold = threshold;
+                        }
+                    }
+                    _ => return vec!["bloom: expected off or intensity [threshold]".to_string()],
+                }
+                vec![if bloom.is_enabled() {
+                    format!(
+                        "bloom {:.2}, threshold {:.2}",
+                        bloom.intensity, bloom.threshold
+                    )
+                } else {
+                    "bloom off".to_string()
+                }]
+            }
+            ("taa", args) => {
+                let taa = &mut self.visuals.post_process_mut().taa;
+                match args {
+                    [] => {}
+                    ["on"] => *taa = true,
+                    ["off"] => *taa = false,
+                    _ => return vec![format!("taa: expected on or off, got '{}'", args.join(" "))],
+                }
+                vec![format!("taa {}", if *taa { "on" } else { "off" })]
+            }
+            ("render", args) => {
+                let mut config = *self.visuals.renderer_config();
+                match args {
+                    [] => {}
+                    ["clear", rgba @ ..] => {
+                        config.clear_color = match parse_color(rgba) {
+                            Some(color) => color,
+                            None => {
+                                return vec![format!(
+                                    "render clear: expected r g b [a], #hex or a color name, got '{}'",
+                                    rgba.join(" ")
+                                )];
+                            }
+                        };
+                    }
+                    ["letterbox", "off"] => config.virtual_resolution = None,
+                    ["letterbox", w, h] => match (w.parse::<u32>(), h.parse::<u32>()) {
+                        (Ok(w), Ok(h)) if w > 0 && h > 0 => {
+                            config.virtual_resolution = Some([w, h]);
+                        }
+                        _ => {
+                            return vec![format!(
+                                "render letterbox: expected a width and height in pixels, got '{w} {h}'"
+                            )];
+                        }
+                    },
+                    _ => {
+                        return vec![
+                            "render: expected clear <color>, letterbox <w> <h> or letterbox off"
+                                .to_string(),
+                        ];
+                    }
+                }
+                self.visuals.set_renderer_config(config);
+                self.refresh_camera_projection();
+                let [r, g, b, a] = config.clear_color.to_array();
+                let letterbox = match config.virtual_resolution {
+                    Some([w, h]) => format!("{w}x{h}"),
+                    None => "off".to_string(),
+                };
+                vec![format!(
+                    "render clear {r:.2} {g:.2} {b:.2} {a:.2}, letterbox {letterbox}"
+                )]
+            }
+            ("outline", [material, rest @ ..]) => {
+                let Some(material) = parse_material(material) else {
+                    return vec![format!(
+                        "outline: unknown material '{material}' (toon, unlit or an id)"
+                    )];
+                };
+                match rest {
+                    [] => {}
+                    ["off"] => self.visuals.set_material_outline(material, None),
+                    [width, color @ ..] => {
+                        let Some(outline) = parse_outline(width, color) else {
+                            return vec![format!(
+                                "outline: expected a width and optional color, got '{}'",
+                                rest.join(" ")
+                            )];
+                        };
+                        self.visuals.set_material_outline(material, Some(outline));
+                    }
+                }
+                vec![match self.visuals.material_outline(material) {
+                    Some(o) => format!(
+                        "outline {} px, color [{}, {}, {}, {}]",
+                        o.width, o.color.r, o.color.g, o.color.b, o.color.a
+                    ),
+                    None => "outline off".to_string(),
+                }]
+            }
+            ("reflect", [material, rest @ ..]) => {
+                let Some(material) = parse_material(material) else {
+                    return vec![format!(
+                        "reflect: unknown material '{material}' (toon, unlit or an id)"
+                    )];
+                };
+                match rest {
+                    [] => {}
+                    ["off"] => self.visuals.set_material_reflection(material, None),
+                    [strength] => {
+                        let Some(strength) = strength
+                            .parse::<f32>()
+                            .ok()
+                            .filter(|s| (0.0..=1.0).contains(s))
+                        else {
+                            return vec![format!(
+                                "reflect: expected a strength in 0..1, got '{strength}'"
+                            )];
+                        };
+                        let VisualBackground::Skybox { cubemap } = self.visuals.background() else {
+                            return vec![
+                                "reflect: needs a skybox background to reflect".to_string(),
+                            ];
+                        };
+                        self.visuals.set_material_reflection(
+                            material,
+                            Some(Reflection { cubemap, strength }),
+                        );
+                    }
+                    _ => return vec!["usage: reflect <material> [off|strength]".to_string()],
+                }
+                vec![match self.visuals.material_reflection(material) {
+                    Some(r) => format!("reflect {} (cubemap {})", r.strength, r.cubemap.0),
+                    None => "reflect off".to_string(),
+                }]
+            }
+            ("blend", [material, rest @ ..]) => {
+                let Some(material) = parse_material(material) else {
+                    return vec![format!(
+                        "blend: unknown material '{material}' (toon, unlit or an id)"
+                    )];
+                };
+                match rest {
+                    [] => {}
+                    [mode] => {
+                        let Some(blend) = BlendMode::from_name(mode) else {
+                            return vec![format!(
+                                "blend: expected opaque, alpha, additive or multiply, got '{mode}'"
+                            )];
+                        };
+                        self.visuals.set_material_blend(material, blend);
+                    }
+                    _ => return vec!["usage: blend <material> [mode]".to_string()],
+                }
+                vec![format!(
+                    "blend {}",
+                    self.visuals.material_blend(material).name()
+                )]
+            }
+            ("cull", [material, rest @ ..]) => {
+                let Some(material) = parse_material(material) else {
+                    return vec![format!(
+                        "cull: unknown material '{material}' (toon, unlit or an id)"
+                    )];
+                };
+                if !rest.is_empty() {
+                    let Some(culling) = parse_culling(rest) else {
+                        return vec![
+                            "usage: cull <material> [none|back|front [ccw|cw]]".to_string(),
+                        ];
+                    };
+                    self.visuals.set_material_culling(material, culling);
+                }
+                let culling = self.visuals.material_culling(material);
+                let mode = match culling.mode {
+                    CullMode::None => "none",
+                    CullMode::Back => "back",
+                    CullMode::Front => "front",
+                };
+                let front = match culling.front_face {
+                    FrontFace::CounterClockwise => "ccw",
+                    FrontFace::Clockwise => "cw",
+                };
+                vec![format!("cull {mode}, front faces {front}")]
+            }
+            ("net", [] | ["status"]) => self.networking.status_lines(),
+            ("say", [_, ..]) => {
+                let text = line
+                    .trim_start()
+                    .strip_prefix("say")
+                    .map(str::trim)
+                    .unwrap_or_default();
+                match self.networking.say(text) {
+                    Ok(()) => Vec::new(),
+                    Err(e) => vec![format!("say: {e}")],
+                }
+            }
+            ("chat", [] | [_]) => {
+                let n = match args.first().map(|n| n.parse::<usize>()) {
+                    None => 20,
+                    Some(Ok(n)) => n,
+                    Some(Err(_)) => {
+                        return vec![format!("chat: expected a line count, got '{}'", args[0])];
+                    }
+                };
+                self.networking
+                    .chat()
+                    .recent(n)
+                    .map(ChatLine::to_string)
+                    .collect()
+            }
+            ("system", [] | ["list"]) => {
+                let paused = self.world.resource::<Paused>().is_some_and(|p| p.0);
+                let mut out = vec![format!("paused: {paused}")];
+                out.extend(SystemId::ALL.into_iter().map(|id| {
+                    let on = if self.systems.enabled.get(id) {
+                        "on "
+                    } else {
+                        "off"
+                    };
+                    format!(
+                        "{:<13}{on} {}",
+                        id.name(),
+                        self.systems.schedule.criteria(id)
+                    )
+                }));
+                out
+            }
+            ("system", [switch @ ("enable" | "disable"), name]) => {
+                let Some(id) = SystemId::from_name(name) else {
+                    return vec![unknown_system(name)];
+                };
+                self.systems.enabled.set(id, *switch == "enable");
+                vec![format!("{id} {switch}d")]
+            }
+            ("system", ["run", name, criteria @ ..]) => {
+                let Some(id) = SystemId::from_name(name) else {
+                    return vec![unknown_system(name)];
+                };
+                let criteria = match criteria {
+                    ["always"] => RunCriteria::Always,
+                    ["unpaused"] => RunCriteria::UnlessPaused,
+                    ["every", n] => match n.parse::<u32>() {
+                        Ok(n) if n > 0 => RunCriteria::EveryNFrames(n),
+                        _ => return vec![format!("system: expected a frame count, got '{n}'")],
+                    },
+                    _ => return vec!["system run <name> always|unpaused|every <n>".to_string()],
+                };
+                self.systems.schedule.set_criteria(id, criteria);
+                vec![format!("{id} runs {criteria}")]
+            }
+            ("system", [pause @ ("pause" | "resume")]) => {
+                self.world.insert_resource(Paused(*pause == "pause"));
+                vec![format!("{pause}d")]
+            }
+            ("fsm", [id, rest @ ..]) => {
+                let Some(cid) = id.parse::<ComponentId>().ok().filter(|&cid| {
+                    self.world
+                        .get_component_by_id_as::<StateMachineComponent>(cid)
+                        .is_some()
+                }) else {
+                    return vec![format!("fsm: no state machine '{id}'")];
+                };
+                let owner = self.world.parent_of(cid);
+                let Some(machine) = self
+                    .world
+                    .get_component_by_id_as_mut::<StateMachineComponent>(cid)
+                else {
+                    return Vec::new();
+                };
+                match rest {
+                    [] => describe_state_machine(machine),
+                    ["send", event] => {
+                        machine.send(*event);
+                        vec![format!("sent {event}")]
+                    }
+                    ["goto", state] => {
+                        if machine.state(state).is_none() {
+                            return vec![format!("fsm: no state '{state}'")];
+                        }
+                        let hooks = machine.go_to(state);
+                        let path = machine.active_path().into_iter().rev().collect::<Vec<_>>();
+                        let out = vec![format!("now in {}", path.join("/"))];
+                        let pending = self
+                            .world
+                            .resources_mut()
+                            .get_or_insert_with(PendingCommands::default);
+                        for line in hooks {
+                            pending.push(StateMachineSystem::expand_hook(&line, cid, owner));
+                        }
+                        out
+                    }
+                    _ => vec!["fsm <id> [send <event>|goto <state>]".to_string()],
+                }
+            }
+            (repeat @ ("after" | "every"), [seconds, _, ..]) => {
+                let Some(seconds) = seconds.parse::<f32>().ok().filter(|s| *s >= 0.0) else {
+                    return vec![format!("{repeat}: expected seconds, got '{seconds}'")];
+                };
+                // Keep the command verbatim, minus the `<after|every> <seconds>` prefix.
+                let command = line
+                    .trim_start()
+                    .strip_prefix(repeat)
+                    .map(str::trim_start)
+                    .and_then(|l| l.strip_prefix(args[0]))
+                    .map(str::trim)
+                    .unwrap_or_default();
+                let scheduler = self
+                    .world
+                    .resources_mut()
+                    .get_or_insert_with(Scheduler::default);
+                let id = if repeat == "every" {
+                    scheduler.every(seconds, command)
+                } else {
+                    scheduler.after(seconds, command)
+                };
+                vec![id.to_string()]
+            }
+            ("timers", []) => {
+                let Some(scheduler) = self.world.resource::<Scheduler>() else {
+                    return Vec::new();
+                };
+                scheduler
+                    .timers()
+                    .map(|t| {
+                        let due = scheduler.remaining(t.id).unwrap_or_default();
+                        let every = t
+                            .period()
+                            .map(|p| format!(" every {p:.2}s"))
+                            .unwrap_or_default();
+                        format!("{} in {due:.2}s{every}: {}", t.id, t.action)
+                    })
+                    .collect()
+            }
+            ("cancel", [id]) => {
+                let id = match id.parse::<TimerId>() {
+                    Ok(id) => id,
+                    Err(e) => return vec![format!("cancel: {e}")],
+                };
+                let cancelled = self
+                    .world
+                    .resource_mut::<Scheduler>()
+                    .is_some_and(|s| s.cancel(id));
+                if cancelled {
+                    vec![format!("cancelled {id}")]
+                } else {
+                    vec![format!("cancel: no timer {id}")]
+                }
+            }
+            ("locale", []) => {
+                let l10n = i18n::current();
+                let locales: Vec<&str> = l10n.locales().collect();
+                vec![format!(
+                    "locale {} (fallback {}; loaded: {})",
+                    l10n.locale(),
+                    l10n.fallback(),
+                    if locales.is_empty() {
+                        "none".to_string()
+                    } else {
+                        locales.join(", ")
+                    }
+                )]
+            }
+            ("locale", [locale]) => {
+                if i18n::set_locale(locale) {
+                    vec![format!("locale {locale}")]
+                } else {
+                    vec![format!("locale: no string table for '{locale}'")]
+                }
+            }
+            ("tr", [key]) => match i18n::current().get(key) {
+                Some(text) => vec![text.to_string()],
+                None => vec![format!("tr: no string '{key}'")],
+            },
+            ("vram", []) => self.gpu_memory().report(),
+            ("fps", []) => {
+                let cap = match self.pacing.fps_cap {
+                    Some(fps) => fps.to_string(),
+                    None => "uncapped".to_string(),
+                };
+                let last = profiling::last_frame()
+                    .filter(|f| f.dt_sec > 0.0)
+                    .map(|f| format!(", last frame {:.1} ms", f.dt_sec * 1000.0))
+                    .unwrap_or_default();
+                vec![format!(
+                    "fps cap {cap} (unfocused {}){last}",
+                    self.pacing.unfocused_fps
+                )]
+            }
+            ("fps", [cap]) => match *cap {
+                "off" | "0" => {
+                    self.pacing.fps_cap = None;
+                    vec!["fps cap off".to_string()]
+                }
+                n => match n.parse::<u32>() {
+                    Ok(fps) => {
+                        self.pacing.fps_cap = Some(fps);
+                        vec![format!("fps cap {fps}")]
+                    }
+                    Err(_) => vec![format!("fps: expected a number or 'off', got '{n}'")],
+                },
+            },
+            ("inspector", []) => {
+                self.inspector.toggle();
+                vec![format!("inspector {}", on_off(self.inspector.enabled()))]
+            }
+            _ => vec![format!(
+                "unknown command or arguments: '{line}' (try 'help')"
+            )],
+        }
+    }
+}
+
+impl Universe {
+    /// Run every command of a script, returning the combined output.
+    pub fn source(&mut self, path: &Path) -> Vec<String> {
+        if self.repl.source_depth >= MAX_SOURCE_DEPTH {
+            return vec![format!("source: nesting too deep at {}", path.display())];
+        }
+        let lines = match read_script(path) {
+            Ok(lines) => lines,
+            Err(e) => return vec![format!("source {}: {e}", path.display())],
+        };
+
+        self.repl.source_depth += 1;
+        let mut out = Vec::new();
+        for line in lines {
+            for command in self.repl.expand(&line) {
+                out.extend(self.execute(&command));
+            }
+        }
+        self.repl.source_depth -= 1;
+        out
+    }
+}
+
+impl Universe {
+    /// `set <id> <field> <value>`: change one field of a live component. The value is JSON;
+    /// anything else is taken as a string (`set 3v1 projection orthographic`).
+    fn set_field(&mut self, id: &str, field: &str, value: &str) -> Vec<String> {
+        let value: Value = serde_json::from_str(value).unwrap_or_else(|_| Value::from(value));
+        let Some((cid, node)) = id
+            .parse::<ComponentId>()
+            .ok()
+            .and_then(|cid| Some((cid, self.world.get_component_record_mut(cid)?)))
+        else {
+            return vec![format!("set: no component '{id}'")];
+        };
+        let component = &mut node.component;
+        let Some(schema) = component.schema().and_then(|s| s.field(field)) else {
+            return vec![format!(
+                "set: {} has no field '{field}'",
+                component.type_name()
+            )];
+        };
+        if let (Some((min, max)), Some(v)) = (schema.range, value.as_f64())
+            && !(min as f64..=max as f64).contains(&v)
+        {
+            return vec![format!("set: {field} must be in {min}..{max}")];
+        }
+        if !component.set_field(field, &value) {
+            return vec![format!(
+                "set: can't set {}.{field} to {value}",
+                component.type_name()
+            )];
+        }
+        let now = component.encode()[field].clone();
+        // Colors reach the renderer when registered; registering again pushes the new one.
+        if matches!(component.type_name(), "color" | "background") {
+            component.init(&mut self.command_queue, cid);
+        }
+        self.refresh_camera_projection();
+        vec![format!("{id}.{field} = {now}")]
+    }
+
+    /// `spawn <type> [parent-id] [json]`: construct a registered type and initialize it.
+    fn spawn(&mut self, type_name: &str, args: &[&str]) -> Vec<String> {
+        let Some(ty) = self.world.registry().find(type_name) else {
+            return vec![format!("spawn: unknown component type '{type_name}'")];
+        };
+        let (parent, json) = match args.split_first() {
+            Some((first, rest)) => match first.parse::<ComponentId>() {
+                Ok(parent) => (Some(parent), rest),
+                Err(_) => (None, args),
+            },
+            None => (None, args),
+        };
+        let data = match json {
+            [] => Value::Null,
+            _ => match serde_json::from_str(&json.join(" ")) {
+                Ok(data) => data,
+                Err(e) => return vec![format!("spawn: bad json: {e}")],
+            },
+        };
+
+        let node = ComponentDataNode {
+            type_name: ty.type_name.to_string(),
+            data,
+            children: Vec::new(),
+            prefab: None,
+            guid: None,
+        };
+        match self.add_node(&node, parent) {
+            Ok(cid) => vec![cid.to_string()],
+            Err(e) => vec![format!("spawn: {e}")],
+        }
+    }
+
+    /// `prefab <path> [parent-id]`: instantiate a prefab file.
+    fn spawn_prefab(&mut self, path: &str, parent: Option<&str>) -> Vec<String> {
+        let parent = match parent.map(str::parse::<ComponentId>) {
+            None => None,
+            Some(Ok(parent)) => Some(parent),
+            Some(Err(e)) => return vec![format!("prefab: {e}")],
+        };
+        let node = ComponentDataNode::prefab_instance(
+            "",
+            PrefabInstance {
+                source: path.to_string(),
+                overrides: Default::default(),
+            },
+        );
+        match self.add_node(&node, parent) {
+            Ok(cid) => vec![cid.to_string()],
+            Err(e) => vec![format!("prefab: {e}")],
+        }
+    }
+
+    /// Decode `node` under `parent` (or as a root) and initialize it.
+    fn add_node(
+        &mut self,
+        node: &ComponentDataNode,
+        parent: Option<ComponentId>,
+    ) -> Result<ComponentId, String> {
+        if let Some(parent) = parent
+            && self.world.get_component_record(parent).is_none()
+        {
+            return Err(format!("no component {parent}"));
+        }
+        let cid = ComponentCodec::decode(&mut self.world, node).map_err(|e| e.to_string())?;
+        if let Some(parent) = parent
+            && let Err(e) = self.world.add_child(parent, cid)
+        {
+            let _ = self.world.remove_component_subtree(cid);
+            return Err(e.to_string());
+        }
+        ComponentCodec::resolve_refs(&mut self.world, &[cid]);
+        self.world.init_component_tree(cid, &mut self.command_queue);
+        Ok(cid)
+    }
+}
+
+/// Active path, the state tree (`*` marks active states), transitions and problems.
+fn describe_state_machine(machine: &StateMachineComponent) -> Vec<String> {
+    let mut out = Vec::new();
+    match machine.active() {
+        Some(_) => {
+            let path: Vec<_> = machine.active_path().into_iter().rev().collect();
+            out.push(format!(
+                "in {} for {:.2}s",
+                path.join("/"),
+                machine.time_in_state()
+            ));
+        }
+        None => out.push("not started".to_string()),
+    }
+
+    fn tree(
+        machine: &StateMachineComponent,
+        parent: Option<&str>,
+        depth: usize,
+        out: &mut Vec<String>,
+    ) {
+        for state in machine
+            .states
+            .iter()
+            .filter(|s| s.parent.as_deref() == parent)
+        {
+            let mark = if machine.is_in(&state.name) { '*' } else { ' ' };
+            out.push(format!("{}{mark} {}", "  ".repeat(depth + 1), state.name));
+            if depth < machine.states.len() {
+                tree(machine, Some(&state.name), depth + 1, out);
+            }
+        }
+    }
+    out.push("states:".to_string());
+    tree(machine, None, 0, &mut out);
+
+    out.push("transitions:".to_string());
+    for t in &machine.transitions {
+        let mut line = format!("  {} -> {}", t.from, t.to);
+        if let Some(event) = &t.event {
+            line.push_str(&format!(" on {event}"));
+        }
+        if let Some(condition) = &t.condition {
+            line.push_str(&format!(" when {condition}"));
+        }
+        if let Some(after) = t.after {
+            line.push_str(&format!(" after {after}s"));
+        }
+        out.push(line);
+    }
+    out.extend(
+        machine
+            .problems()
+            .into_iter()
+            .map(|p| format!("problem: {p}")),
+    );
+    out
+}
+
+fn unknown_system(name: &str) -> String {
+    let names: Vec<_> = SystemId::ALL.iter().map(|id| id.name()).collect();
+    format!("system: no system '{name}' ({})", names.join(", "))
+}
+
+fn parse_material(name: &str) -> Option<MaterialHandle> {
+    match name {
+        "toon" => Some(MaterialHandle::TOON_MESH),
+        "unlit" => Some(MaterialHandle::UNLIT_MESH),
+        id => id.parse().ok().map(MaterialHandle),
+    }
+}
+
+/// `<width> [color]`; the color defaults to opaque black.
+fn parse_outline(width: &str, color: &[&str]) -> Option<Outline> {
+    let width = width
+        .parse::<f32>()
+        .ok()
+        .filter(|w| w.is_finite() && *w >= 0.0)?;
+    let color = match color {
+        [] => Outline::DEFAULT.color,
+        _ => parse_color(color)?,
+    };
+    Some(Outline { color, width })
+}
+
+/// Linear `r g b [a]`, `#hex` (sRGB) or a color name, as separate REPL arguments.
+fn parse_color(args: &[&str]) -> Option<Color> {
+    match args {
+        [] => None,
+        [one] => one.parse().ok(),
+        _ if args.len() <= 4 => args.join(" ").parse().ok(),
+        _ => None,
+    }
+}
+
+/// `none|back|front [ccw|cw]`; the winding defaults to counter-clockwise.
+fn parse_culling(args: &[&str]) -> Option<Culling> {
+    let (mode, front) = match args {
+        [mode] => (*mode, "ccw"),
+        [mode, front] => (*mode, *front),
+        _ => return None,
+    };
+    Some(Culling {
+        mode: match mode {
+            "none" => CullMode::None,
+            "back" => CullMode::Back,
+            "front" => CullMode::Front,
+            _ => return None,
+        },
+        front_face: match front {
+            "ccw" => FrontFace::CounterClockwise,
+            "cw" => FrontFace::Clockwise,
+            _ => return None,
+        },
+    })
+}
+
+/// `secs` as the largest whole unit: `40s`, `12m`, `3h`, `2d`.
+fn ago(secs: u64) -> String {
+    match secs {
+        0..60 => format!("{secs}s"),
+        60..3600 => format!("{}m", secs / 60),
+        3600..86400 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    }
+}
+
+fn on_off(enabled: bool) -> &'static str {
+    if enabled { "on" } else { "off" }
+}
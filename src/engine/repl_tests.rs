@@ -0,0 +1,399 @@
+#[cfg(test)]
+mod tests {
+    use crate::engine::repl::Repl;
+
+    #[test]
+    fn expand_splits_commands_and_aliases() {
+        let mut repl = Repl::without_stdin();
+        assert_eq!(repl.expand(" help ;; bindings "), vec!["help", "bindings"]);
+
+        repl.set_alias("dbg", "inspector; bind");
+        assert_eq!(
+            repl.expand("dbg move_up KeyI; help"),
+            vec!["inspector", "bind move_up KeyI", "help"]
+        );
+
+        // Expansion is not recursive.
+        repl.set_alias("loop", "loop");
+        assert_eq!(repl.expand("loop"), vec!["loop"]);
+
+        // An alias definition is not split.
+        assert_eq!(repl.expand(" alias x a; b "), vec!["alias x a; b"]);
+    }
+
+    #[test]
+    fn multi_command_aliases_can_be_defined_from_lines_and_scripts() {
+        use crate::engine::Universe;
+        use crate::engine::ecs::World;
+        use crate::engine::repl::ReplBackend;
+
+        let mut universe = Universe::new(World::default());
+        universe.run_command("alias dbg inspector; bind");
+        assert_eq!(
+            universe.execute("alias dbg"),
+            vec!["alias dbg inspector; bind"]
+        );
+
+        let path = std::env::temp_dir().join(format!("little-cat-rc-{}.txt", std::process::id()));
+        std::fs::write(&path, "alias pals help; bindings\n").unwrap();
+        universe.source(&path);
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(
+            universe.execute("alias pals"),
+            vec!["alias pals help; bindings"]
+        );
+    }
+
+    #[test]
+    fn say_logs_offline_chat() {
+        use crate::engine::Universe;
+        use crate::engine::ecs::World;
+        use crate::engine::repl::ReplBackend;
+
+        let mut universe = Universe::new(World::default());
+        assert!(universe.execute("say  hello   there ").is_empty());
+        universe.execute("say again");
+        assert_eq!(universe.execute("chat 1"), vec!["<0> again"]);
+        assert_eq!(
+            universe.execute("chat"),
+            vec!["<0> hello   there", "<0> again"]
+        );
+        assert!(universe.execute("chat many")[0].starts_with("chat:"));
+    }
+
+    #[test]
+    fn find_filters_by_type_property_and_subtree() {
+        use crate::engine::Universe;
+        use crate::engine::ecs::World;
+        use crate::engine::ecs::component::{ColorComponent, TransformComponent};
+        use crate::engine::repl::ReplBackend;
+
+        let mut world = World::default();
+        let root = world.add_component(TransformComponent::new());
+        let near = world.add_component(TransformComponent::new().with_position(1.0, 0.0, 0.0));
+        let far = world.add_component(TransformComponent::new().with_position(20.0, 0.0, 0.0));
+        let color = world.add_component(ColorComponent::new());
+        world.add_child(root, near).unwrap();
+        world.add_child(near, color).unwrap();
+        let _ = far;
+
+        let mut universe = Universe::new(world);
+        assert_eq!(
+            universe.execute(&format!("find type=TransformComponent x<10 under {root}")),
+            vec![near.to_string()]
+        );
+        assert_eq!(universe.execute("find has=color"), vec![near.to_string()]);
+        assert!(universe.execute("find x<<1")[0].starts_with("find:"));
+    }
+
+    #[test]
+    fn system_commands_switch_and_pause_systems() {
+        use crate::engine::Universe;
+        use crate::engine::ecs::World;
+        use crate::engine::ecs::system::{Paused, RunCriteria, SystemId};
+        use crate::engine::repl::ReplBackend;
+
+        let mut universe = Universe::new(World::default());
+        universe.execute("system disable renderable");
+        assert!(!universe.systems.enabled.renderable);
+        universe.execute("system run lighting every 4");
+        assert_eq!(
+            universe.systems.schedule.criteria(SystemId::Lighting),
+            RunCriteria::EveryNFrames(4)
+        );
+        universe.execute("system pause");
+        assert_eq!(universe.world.resource::<Paused>(), Some(&Paused(true)));
+
+        let list = universe.execute("system list");
+        assert_eq!(list[0], "paused: true");
+        assert!(
+            list.iter()
+                .any(|l| l.starts_with("renderable") && l.contains("off"))
+        );
+        assert!(
+            list.iter()
+                .any(|l| l.starts_with("lighting") && l.ends_with("every 4"))
+        );
+        assert!(universe.execute("system disable physics")[0].contains("no system"));
+    }
+
+    #[test]
+    fn fsm_inspects_machines_and_hooks_run_after_update() {
+        use crate::engine::Universe;
+        use crate::engine::ecs::World;
+        use crate::engine::ecs::component::{TransformComponent, VelocityComponent};
+        use crate::engine::repl::ReplBackend;
+        use crate::engine::user_input::InputState;
+
+        let mut world = World::default();
+        let npc = world.add_component(TransformComponent::new());
+        let mut universe = Universe::new(world);
+        let json = r#"{"states":[{"name":"idle"},{"name":"flee","on_enter":["spawn velocity $owner"]}],"transitions":[{"from":"idle","to":"flee","event":"scared"}]}"#;
+        let fsm = universe.execute(&format!("spawn state_machine {npc} {json}"))[0]
+            .split_whitespace()
+            .find_map(|w| w.parse::<crate::engine::ecs::ComponentId>().ok())
+            .unwrap();
+
+        let mut input = InputState::default();
+        universe.update(0.0, &mut input);
+        universe.update(0.0, &mut input);
+        let out = universe.execute(&format!("fsm {fsm}"));
+        assert!(out[0].starts_with("in idle"), "{out:?}");
+        assert!(
+            out.contains(&"  idle -> flee on scared".to_string()),
+            "{out:?}"
+        );
+
+        universe.execute(&format!("fsm {fsm} send scared"));
+        universe.update(0.0, &mut input);
+        assert!(universe.execute(&format!("fsm {fsm}"))[0].starts_with("in flee"));
+        // The enter hook spawned a velocity under the owner.
+        assert!(universe.world.children_of(npc).iter().any(|&c| {
+            universe
+                .world
+                .get_component_by_id_as::<VelocityComponent>(c)
+                .is_some()
+        }));
+        assert!(universe.execute(&format!("fsm {fsm} goto nowhere"))[0].contains("no state"));
+        assert!(universe.execute(&format!("fsm {npc}"))[0].contains("no state machine"));
+    }
+
+    #[test]
+    fn ls_lists_components_oldest_first() {
+        use crate::engine::Universe;
+        use crate::engine::ecs::World;
+        use crate::engine::ecs::component::{ColorComponent, TransformComponent};
+        use crate::engine::repl::ReplBackend;
+
+        let mut world = World::default();
+        let gone = world.add_component(TransformComponent::new());
+        let first = world.add_component(TransformComponent::new());
+        world.remove_component_leaf(gone).unwrap();
+        // Reuses the freed slot, so arena order would list it before `first`.
+        let second = world.add_component(TransformComponent::new());
+        let child = world.add_component(ColorComponent::new());
+        world.add_child(second, child).unwrap();
+        assert_eq!(world.root_components(), vec![first, second]);
+
+        let mut universe = Universe::new(world);
+        let roots = universe.execute("ls");
+        assert_eq!(roots.len(), 2);
+        assert!(roots[0].starts_with(&format!("{first} ")));
+        assert!(roots[1].starts_with(&format!("{second} ")));
+        assert!(roots[1].ends_with("(1 children)"));
+        assert_eq!(universe.execute(&format!("ls {second}")).len(), 1);
+        assert!(universe.execute(&format!("ls {gone}"))[0].starts_with("ls:"));
+    }
+
+    #[test]
+    fn describe_lists_schema_fields() {
+        use crate::engine::Universe;
+        use crate::engine::ecs::World;
+        use crate::engine::ecs::component::schema;
+        use crate::engine::repl::ReplBackend;
+
+        let mut universe = Universe::new(World::default());
+        let out = universe.execute("describe PointLightComponent");
+        assert_eq!(out[0], "point_light:");
+        assert!(
+            out.iter()
+                .any(|l| l.trim_start().starts_with("intensity: f32"))
+        );
+        assert!(universe.execute("describe nope")[0].starts_with("describe:"));
+
+        // Every built-in type is reachable by its `Component::name`.
+        for s in schema::builtin() {
+            assert_eq!(schema::find(s.type_name), Some(s));
+        }
+    }
+
+    #[test]
+    fn vram_reports_usage_by_category() {
+        use crate::engine::Universe;
+        use crate::engine::ecs::World;
+        use crate::engine::graphics::{GpuMemoryCategory, GpuMemoryUsage};
+        use crate::engine::repl::ReplBackend;
+
+        // No window yet: nothing allocated.
+        let mut universe = Universe::new(World::default());
+        let out = universe.execute("vram");
+        assert_eq!(out.len(), GpuMemoryCategory::ALL.len() + 1);
+        assert!(out.last().unwrap().contains("0 B"));
+
+        let mut usage = GpuMemoryUsage::default();
+        usage.allocate(GpuMemoryCategory::Textures, 4 * 1024 * 1024);
+        usage.allocate(GpuMemoryCategory::Meshes, 512);
+        usage.allocate(GpuMemoryCategory::Meshes, 512);
+        usage.free(GpuMemoryCategory::Meshes, 512);
+        assert_eq!(usage.meshes.count, 1);
+        assert_eq!(usage.total_bytes(), 4 * 1024 * 1024 + 512);
+        let report = usage.report();
+        assert!(report[1].contains("4.0 MiB"), "{report:?}");
+    }
+
+    #[test]
+    fn outline_overrides_material_defaults() {
+        use crate::engine::Universe;
+        use crate::engine::ecs::World;
+        use crate::engine::graphics::{MaterialHandle, Outline};
+        use crate::engine::repl::ReplBackend;
+        use crate::utils::color::Color;
+
+        let mut universe = Universe::new(World::default());
+        assert_eq!(universe.execute("outline toon"), vec!["outline off"]);
+
+        universe.execute("outline toon 3 1 0 0");
+        assert_eq!(
+            universe.visuals.material_outline(MaterialHandle::TOON_MESH),
+            Some(Outline {
+                color: Color::RED,
+                width: 3.0
+            })
+        );
+        assert!(universe.execute("outline toon 3 1 0")[0].starts_with("outline: expected"));
+
+        universe.execute("outline toon off");
+        assert_eq!(
+            universe.visuals.material_outline(MaterialHandle::TOON_MESH),
+            None
+        );
+    }
+
+    #[test]
+    fn bloom_sets_post_process_settings() {
+        use crate::engine::Universe;
+        use crate::engine::ecs::World;
+        use crate::engine::graphics::BloomSettings;
+        use crate::engine::repl::ReplBackend;
+
+        let mut universe = Universe::new(World::default());
+        assert_eq!(
+            universe.execute("bloom"),
+            vec!["bloom 1.00, threshold 0.80"]
+        );
+        assert_eq!(
+            universe.execute("bloom 2 0.5"),
+            vec!["bloom 2.00, threshold 0.50"]
+        );
+        assert!(universe.execute("bloom -1")[0].starts_with("bloom: expected"));
+
+        assert_eq!(universe.execute("bloom off"), vec!["bloom off"]);
+        assert_eq!(
+            universe.visuals.post_process().bloom,
+            BloomSettings {
+                intensity: 0.0,
+                threshold: 0.5
+            }
+        );
+    }
+
+    #[test]
+    fn render_sets_clear_color_and_letterbox() {
+        use crate::engine::Universe;
+        use crate::engine::ecs::World;
+        use crate::engine::repl::ReplBackend;
+        use crate::utils::color::Color;
+
+        let mut universe = Universe::new(World::default());
+        assert_eq!(
+            universe.execute("render clear 0.1 0.1 0.12"),
+            vec!["render clear 0.10 0.10 0.12 1.00, letterbox off"]
+        );
+        assert_eq!(
+            universe.visuals.renderer_config().clear_color,
+            Color::rgb(0.1, 0.1, 0.12)
+        );
+        assert!(universe.execute("render clear 1 0")[0].starts_with("render clear: expected"));
+
+        universe.visuals.set_viewport(1920.0, 1080.0);
+        assert_eq!(
+            universe.execute("render letterbox 320 240"),
+            vec!["render clear 0.10 0.10 0.12 1.00, letterbox 320x240"]
+        );
+        assert_eq!(universe.visuals.viewport(), [320.0, 240.0]);
+        assert!(universe.execute("render letterbox 0 240")[0].starts_with("render letterbox:"));
+
+        universe.execute("render letterbox off");
+        assert_eq!(universe.visuals.viewport(), [1920.0, 1080.0]);
+
+        universe.execute("render clear #ff0000");
+        assert_eq!(universe.visuals.renderer_config().clear_color, Color::RED);
+    }
+
+    #[test]
+    fn set_rebuilds_the_camera_projection() {
+        use crate::engine::Universe;
+        use crate::engine::ecs::World;
+        use crate::engine::ecs::system::Camera3D;
+        use crate::engine::repl::ReplBackend;
+        use crate::engine::user_input::InputState;
+
+        let mut universe = Universe::new(World::default());
+        universe.visuals.set_viewport(800.0, 400.0);
+        let t = universe.execute("spawn transform")[0].clone();
+        let camera = universe.execute(&format!("spawn camera3d {t}"))[0].clone();
+        universe.update(0.0, &mut InputState::default());
+
+        assert_eq!(
+            universe.execute(&format!("set {camera} projection orthographic")),
+            vec![format!("{camera}.projection = \"orthographic\"")]
+        );
+        universe.execute(&format!("set {camera} ortho_size 3"));
+        assert_eq!(
+            universe.visuals.camera_proj(),
+            Camera3D::orthographic_rh_zo(3.0, 2.0, 0.1, 100.0)
+        );
+
+        assert!(universe.execute(&format!("set {camera} z_near 200"))[0].starts_with("set: can't"));
+        assert!(universe.execute(&format!("set {camera} fov_y_radians 9"))[0].contains("0..3.14"));
+        assert!(universe.execute(&format!("set {t} fov_y_radians 1"))[0].contains("no field"));
+    }
+
+    #[test]
+    fn reflect_uses_the_skybox_cubemap() {
+        use crate::engine::Universe;
+        use crate::engine::ecs::World;
+        use crate::engine::graphics::{MaterialHandle, TextureHandle, VisualBackground};
+        use crate::engine::repl::ReplBackend;
+
+        let mut universe = Universe::new(World::default());
+        assert!(universe.execute("reflect toon 0.5")[0].contains("needs a skybox"));
+
+        universe.visuals.set_background(VisualBackground::Skybox {
+            cubemap: TextureHandle(7),
+        });
+        assert_eq!(
+            universe.execute("reflect toon 0.5"),
+            vec!["reflect 0.5 (cubemap 7)"]
+        );
+        assert!(universe.execute("reflect toon 2")[0].starts_with("reflect: expected"));
+
+        universe.execute("reflect toon off");
+        assert_eq!(
+            universe
+                .visuals
+                .material_reflection(MaterialHandle::TOON_MESH),
+            None
+        );
+    }
+
+    #[test]
+    fn cat_prints_subtree_json() {
+        use crate::engine::Universe;
+        use crate::engine::ecs::World;
+        use crate::engine::ecs::component::{ColorComponent, TransformComponent};
+        use crate::engine::repl::ReplBackend;
+
+        let mut world = World::default();
+        let root = world.add_component(TransformComponent::new());
+        let color = world.add_component(ColorComponent::new());
+        world.add_child(root, color).unwrap();
+
+        let mut universe = Universe::new(world);
+        let out = universe.execute(&format!("cat {root}"));
+        let json: serde_json::Value = serde_json::from_str(&out.join("\n")).unwrap();
+        assert_eq!(json["type"], "transform");
+        assert_eq!(json["children"][0]["type"], "color");
+        assert!(universe.execute("cat 99v1")[0].starts_with("cat:"));
+    }
+}
@@ -0,0 +1,462 @@
+//! Save games: player progress, kept apart from the scene files that describe levels.
+//!
+//! A save holds only what changes while someone plays: the components marked with a
+//! `PersistentComponent` and the resources registered with `SaveGame::with_resource`. Each
+//! slot is a directory under the save root holding one JSON document:
+//!
+//! ```text
+//! saves/slot1/save.json  {"format":1,"version":2,"saved_at":1760000000,"components":[...],"resources":{...}}
+//! ```
+//!
+//! Loading patches the live world instead of rebuilding it: every saved component replaces the
+//! fields of the component with the same guid (which is then re-initialized, so runtime state
+//! starts over), `subtree` saves replace that component's children, and registered resources
+//! are overwritten. Everything else stays as it is.
+//!
+//! `version` is the game's own save version. Saves from older versions go through the
+//! migrations registered for each step; newer ones are refused:
+//!
+//! ```ignore
+//! let saves = SaveGame::new("saves")
+//!     .with_version(2)
+//!     .with_resource::<Score>("score")
+//!     .with_migration(1, |save| {
+//!         save["resources"]["score"] = save["resources"]["points"].take();
+//!         Ok(())
+//!     });
+//! universe.world.insert_resource(saves);
+//! ```
+//!
+//! REPL `savegame <slot>`, `loadgame <slot>` and `saves` use the world's `SaveGame`, or the
+//! defaults (`saves/`, version 0) without one.
+
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::engine::AssetError;
+use crate::engine::ecs::codec::{CodecError, ComponentCodec, ComponentDataNode, FieldCodec, Value};
+use crate::engine::ecs::component::{Component, PersistentComponent};
+use crate::engine::ecs::{CommandQueue, ComponentId, World};
+
+/// Layout of `save.json` this build writes; saves with a higher one are refused.
+pub const SAVE_FORMAT: u32 = 1;
+
+pub const DEFAULT_SAVE_DIR: &str = "saves";
+
+const SAVE_FILE: &str = "save.json";
+
+/// Rewrites a save document of one version into the next.
+pub type Migration = fn(&mut Value) -> Result<(), String>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SaveError {
+    #[error("'{0}' is not a slot name (letters, digits, '-' and '_')")]
+    SlotName(String),
+
+    #[error("no save in slot '{0}'")]
+    NoSave(String),
+
+    #[error("save format {0} is newer than this build reads ({SAVE_FORMAT})")]
+    Format(u32),
+
+    #[error("save version {found} is newer than the game's ({current})")]
+    NewerVersion { found: u32, current: u32 },
+
+    #[error("no migration from save version {0}")]
+    NoMigration(u32),
+
+    #[error("migrating from save version {from}: {message}")]
+    Migration { from: u32, message: String },
+
+    #[error("malformed save: {0}")]
+    Malformed(String),
+
+    #[error(transparent)]
+    Asset(#[from] AssetError),
+
+    #[error(transparent)]
+    Codec(#[from] CodecError),
+}
+
+/// A slot on disk, as `SaveGame::slots` lists it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotInfo {
+    pub name: String,
+    pub version: u32,
+    /// Unix time in seconds.
+    pub saved_at: u64,
+}
+
+/// What `SaveGame::load` put back.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LoadReport {
+    pub components: usize,
+    pub resources: usize,
+    /// Saved entries that didn't fit the world (component gone, different type, bad value),
+    /// one line each. Also logged as warnings.
+    pub skipped: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PersistedResource {
+    name: &'static str,
+    encode: fn(&World) -> Option<Value>,
+    apply: fn(&mut World, &Value) -> bool,
+}
+
+/// Where saves go and what they hold besides persistent components. Usually a world resource.
+#[derive(Debug, Clone)]
+pub struct SaveGame {
+    dir: PathBuf,
+    version: u32,
+    migrations: BTreeMap<u32, Migration>,
+    resources: Vec<PersistedResource>,
+}
+
+impl Default for SaveGame {
+    fn default() -> Self {
+        Self::new(DEFAULT_SAVE_DIR)
+    }
+}
+
+impl SaveGame {
+    /// Slots are directories under `dir`.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            version: 0,
+            migrations: BTreeMap::new(),
+            resources: Vec::new(),
+        }
+    }
+
+    pub fn with_version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Upgrade saves of version `from` to `from + 1`.
+    pub fn with_migration(mut self, from: u32, migration: Migration) -> Self {
+        self.migrations.insert(from, migration);
+        self
+    }
+
+    /// Save the world's `T` resource under `name`, and replace it on load.
+    pub fn with_resource<T: FieldCodec + Any>(mut self, name: &'static str) -> Self {
+        self.resources.retain(|r| r.name != name);
+        self.resources.push(PersistedResource {
+            name,
+            encode: encode_resource::<T>,
+            apply: apply_resource::<T>,
+        });
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// The directory of `slot`.
+    pub fn slot_dir(&self, slot: &str) -> Result<PathBuf, SaveError> {
+        let valid = !slot.is_empty()
+            && slot
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(SaveError::SlotName(slot.to_string()));
+        }
+        Ok(self.dir.join(slot))
+    }
+
+    /// Write the world's persistent state to `slot`, replacing what was there. Returns the
+    /// file written.
+    pub fn save(&self, world: &World, slot: &str) -> Result<PathBuf, SaveError> {
+        let dir = self.slot_dir(slot)?;
+        std::fs::create_dir_all(&dir).map_err(|e| AssetError::io(&dir, e))?;
+        let path = dir.join(SAVE_FILE);
+        let json = serde_json::to_string_pretty(&self.capture(world)).map_err(AssetError::from)?;
+        // Write aside and rename, so a crash mid-save leaves the previous save intact.
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json).map_err(|e| AssetError::io(&tmp, e))?;
+        std::fs::rename(&tmp, &path).map_err(|e| AssetError::io(&path, e))?;
+        Ok(path)
+    }
+
+    /// Read `slot` and apply it to the world (see `apply`).
+    pub fn load(
+        &self,
+        world: &mut World,
+        queue: &mut CommandQueue,
+        slot: &str,
+    ) -> Result<LoadReport, SaveError> {
+        let path = self.slot_dir(slot)?.join(SAVE_FILE);
+        let bytes = match std::fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(SaveError::NoSave(slot.to_string()));
+            }
+            Err(e) => return Err(AssetError::io(&path, e).into()),
+        };
+        let save = serde_json::from_slice(&bytes).map_err(AssetError::from)?;
+        self.apply(world, queue, save)
+    }
+
+    /// The save document for the world as it is now.
+    pub fn capture(&self, world: &World) -> Value {
+        let components: Vec<Value> = persistent(world)
+            .into_iter()
+            .filter_map(|(cid, subtree)| {
+                let record = world.get_component_record(cid)?;
+                let children = if subtree {
+                    world
+                        .children_of(cid)
+                        .iter()
+                        .filter_map(|&c| ComponentCodec::encode(world, c))
+                        .collect()
+                } else {
+                    Vec::new()
+                };
+                let node = ComponentDataNode {
+                    type_name: record.component.type_name().to_string(),
+                    data: record.component.encode(),
+                    children,
+                    prefab: None,
+                    guid: Some(record.guid),
+                };
+                Some(serde_json::json!({ "subtree": subtree, "node": node.to_json() }))
+            })
+            .collect();
+        let resources: serde_json::Map<String, Value> = self
+            .resources
+            .iter()
+            .filter_map(|r| Some((r.name.to_string(), (r.encode)(world)?)))
+            .collect();
+        let saved_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        serde_json::json!({
+            "format": SAVE_FORMAT,
+            "version": self.version,
+            "saved_at": saved_at,
+            "components": components,
+            "resources": resources,
+        })
+    }
+
+    /// Migrate a save document to the current version and patch the world with it. A save
+    /// that can't be read changes nothing; entries that don't fit the world are skipped.
+    pub fn apply(
+        &self,
+        world: &mut World,
+        queue: &mut CommandQueue,
+        mut save: Value,
+    ) -> Result<LoadReport, SaveError> {
+        let number = |save: &Value, key: &str| {
+            save.get(key)
+                .and_then(Value::as_u64)
+                .and_then(|n| u32::try_from(n).ok())
+                .ok_or_else(|| SaveError::Malformed(format!("no '{key}' number")))
+        };
+        let format = number(&save, "format")?;
+        if format > SAVE_FORMAT {
+            return Err(SaveError::Format(format));
+        }
+        let version = number(&save, "version")?;
+        if version > self.version {
+            return Err(SaveError::NewerVersion {
+                found: version,
+                current: self.version,
+            });
+        }
+        for from in version..self.version {
+            let migration = self
+                .migrations
+                .get(&from)
+                .ok_or(SaveError::NoMigration(from))?;
+            migration(&mut save).map_err(|message| SaveError::Migration { from, message })?;
+        }
+
+        let mut report = LoadReport::default();
+        let patches = Self::prepare(world, &save, &mut report.skipped)?;
+
+        let mut touched = Vec::with_capacity(patches.len());
+        let mut added = Vec::new();
+        for patch in patches {
+            let cid = patch.cid;
+            if let Some(children) = &patch.children {
+                for child in world.children_of(cid).to_vec() {
+                    world.cleanup_component_tree(child, queue);
+                    let _ = world.remove_component_subtree(child);
+                }
+                for node in children {
+                    // Checked in `prepare`, and the old children's guids are free again.
+                    let child = ComponentCodec::decode(world, node)?;
+                    let _ = world.add_child(cid, child);
+                    added.push(child);
+                }
+            }
+            if let Some(record) = world.get_component_record_mut(cid) {
+                let mut old = std::mem::replace(&mut record.component, patch.component);
+                old.cleanup(queue, cid);
+                record.component.set_id(cid);
+            }
+            touched.push(cid);
+        }
+        ComponentCodec::resolve_refs(world, &touched);
+        for &cid in &touched {
+            if let Some(record) = world.get_component_record_mut(cid) {
+                record.component.init(queue, cid);
+            }
+        }
+        for child in added {
+            world.init_component_tree(child, queue);
+        }
+        report.components = touched.len();
+
+        let saved = save.get("resources").and_then(Value::as_object);
+        for r in &self.resources {
+            match saved.and_then(|s| s.get(r.name)) {
+                Some(value) if (r.apply)(world, value) => report.resources += 1,
+                Some(_) => report
+                    .skipped
+                    .push(format!("resource '{}': invalid value", r.name)),
+                None => {}
+            }
+        }
+        for line in &report.skipped {
+            crate::log_warn!("loading save: {line}");
+        }
+        Ok(report)
+    }
+
+    /// Decode every saved component before anything in the world changes.
+    fn prepare(
+        world: &mut World,
+        save: &Value,
+        skipped: &mut Vec<String>,
+    ) -> Result<Vec<Patch>, SaveError> {
+        let entries = save
+            .get("components")
+            .and_then(Value::as_array)
+            .ok_or_else(|| SaveError::Malformed("no 'components' array".into()))?;
+        let mut patches = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let subtree = entry
+                .get("subtree")
+                .and_then(Value::as_bool)
+                .unwrap_or(false);
+            let node = ComponentDataNode::from_json(entry.get("node").unwrap_or(&Value::Null))?;
+            let guid = node.guid.ok_or_else(|| {
+                SaveError::Malformed(format!("{} saved without a guid", node.type_name))
+            })?;
+            let Some(cid) = world.component_id_by_guid(guid) else {
+                skipped.push(format!("{} {guid}: not in the world", node.type_name));
+                continue;
+            };
+            let live = world
+                .get_component_record(cid)
+                .map(|r| r.component.type_name())
+                .unwrap_or_default();
+            if live != node.type_name {
+                skipped.push(format!("{cid}: saved as {}, is {live}", node.type_name));
+                continue;
+            }
+            let component = world.registry().create(&node.type_name, &node.data)?;
+            let children = if subtree {
+                for child in &node.children {
+                    ComponentCodec::check(world, child)?;
+                }
+                Some(node.children)
+            } else {
+                None
+            };
+            patches.push(Patch {
+                cid,
+                component,
+                children,
+            });
+        }
+        Ok(patches)
+    }
+
+    /// Slots with a readable save, by name.
+    pub fn slots(&self) -> Result<Vec<SlotInfo>, SaveError> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(AssetError::io(&self.dir, e).into()),
+        };
+        let mut slots: Vec<SlotInfo> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let bytes = std::fs::read(entry.path().join(SAVE_FILE)).ok()?;
+                let save: Value = serde_json::from_slice(&bytes).ok()?;
+                Some(SlotInfo {
+                    name: entry.file_name().to_string_lossy().into_owned(),
+                    version: save.get("version")?.as_u64()? as u32,
+                    saved_at: save.get("saved_at").and_then(Value::as_u64).unwrap_or(0),
+                })
+            })
+            .collect();
+        slots.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(slots)
+    }
+}
+
+/// A saved component ready to go into the world.
+struct Patch {
+    cid: ComponentId,
+    component: Box<dyn Component>,
+    /// Replacement children, for `subtree` saves.
+    children: Option<Vec<ComponentDataNode>>,
+}
+
+/// Components marked by a `PersistentComponent`, oldest first, with whether their subtree is
+/// kept. Ones inside a kept subtree are left to it.
+fn persistent(world: &World) -> Vec<(ComponentId, bool)> {
+    let mut marked: Vec<(ComponentId, bool)> = Vec::new();
+    for cid in world.components_in_order() {
+        let Some(marker) = world.get_component_by_id_as::<PersistentComponent>(cid) else {
+            continue;
+        };
+        let Some(parent) = world.parent_of(cid) else {
+            continue;
+        };
+        match marked.iter_mut().find(|(c, _)| *c == parent) {
+            Some((_, subtree)) => *subtree |= marker.subtree,
+            None => marked.push((parent, marker.subtree)),
+        }
+    }
+    let subtrees: Vec<ComponentId> = marked.iter().filter(|m| m.1).map(|m| m.0).collect();
+    marked.retain(|&(cid, _)| {
+        let mut cur = cid;
+        while let Some(parent) = world.parent_of(cur) {
+            if subtrees.contains(&parent) {
+                return false;
+            }
+            cur = parent;
+        }
+        true
+    });
+    marked
+}
+
+fn encode_resource<T: FieldCodec + Any>(world: &World) -> Option<Value> {
+    world.resource::<T>().map(FieldCodec::encode)
+}
+
+fn apply_resource<T: FieldCodec + Any>(world: &mut World, value: &Value) -> bool {
+    match T::decode(value) {
+        Some(resource) => {
+            world.insert_resource(resource);
+            true
+        }
+        None => false,
+    }
+}
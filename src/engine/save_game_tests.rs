@@ -0,0 +1,133 @@
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use glam::Vec3;
+
+    use crate::engine::ecs::codec::{FieldCodec, Value};
+    use crate::engine::ecs::component::{
+        ColorComponent, PersistentComponent, TransformComponent, VelocityComponent,
+    };
+    use crate::engine::ecs::{CommandQueue, World};
+    use crate::engine::save_game::{SaveError, SaveGame};
+
+    #[derive(Debug, PartialEq)]
+    struct Score(u32);
+
+    impl FieldCodec for Score {
+        fn encode(&self) -> Value {
+            self.0.encode()
+        }
+
+        fn decode(value: &Value) -> Option<Self> {
+            u32::decode(value).map(Score)
+        }
+    }
+
+    fn save_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("little-cat-saves-{name}-{}", std::process::id()))
+    }
+
+    #[test]
+    fn load_patches_persistent_components_and_resources_in_place() {
+        let dir = save_dir("patch");
+        let saves = SaveGame::new(&dir).with_resource::<Score>("score");
+        let mut world = World::default();
+        let player = world.add_component(TransformComponent::new().with_position(1.0, 2.0, 3.0));
+        let marker = world.add_component(PersistentComponent::new());
+        world.add_child(player, marker).unwrap();
+        let bag = world.add_component(TransformComponent::new());
+        let marker = world.add_component(PersistentComponent::subtree());
+        world.add_child(bag, marker).unwrap();
+        let item = world.add_component(VelocityComponent::default());
+        world.add_child(bag, item).unwrap();
+        let item_guid = world.get_component_record(item).unwrap().guid;
+        let scenery = world.add_component(TransformComponent::new());
+        world.insert_resource(Score(7));
+
+        saves.save(&world, "slot1").unwrap();
+        assert_eq!(saves.slots().unwrap()[0].name, "slot1");
+
+        let mut queue = CommandQueue::new();
+        let moved = |world: &mut World, cid, x| {
+            let t = world.get_component_by_id_as_mut::<TransformComponent>(cid);
+            t.unwrap().transform.translation = Vec3::new(x, 0.0, 0.0);
+        };
+        moved(&mut world, player, 50.0);
+        moved(&mut world, scenery, 9.0);
+        world.remove_component_subtree(item).unwrap();
+        let picked_up = world.add_component(ColorComponent::default());
+        world.add_child(bag, picked_up).unwrap();
+        world.insert_resource(Score(100));
+
+        let report = saves.load(&mut world, &mut queue, "slot1").unwrap();
+        assert_eq!((report.components, report.resources), (2, 1));
+        assert!(report.skipped.is_empty(), "{:?}", report.skipped);
+
+        let position = |world: &World, cid| {
+            let t = world.get_component_by_id_as::<TransformComponent>(cid);
+            t.unwrap().transform.translation
+        };
+        assert_eq!(position(&world, player), Vec3::new(1.0, 2.0, 3.0));
+        assert_eq!(position(&world, scenery).x, 9.0, "not persistent");
+        assert_eq!(world.resource::<Score>(), Some(&Score(7)));
+        // The bag's children are the saved ones again, under their saved guids.
+        let item = world.component_id_by_guid(item_guid).unwrap();
+        assert_eq!(world.parent_of(item), Some(bag));
+        assert!(
+            world
+                .get_component_by_id_as::<ColorComponent>(picked_up)
+                .is_none()
+        );
+        assert_eq!(world.children_of(bag).len(), 2);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn older_saves_migrate_and_newer_ones_are_refused() {
+        let dir = save_dir("versions");
+        let mut world = World::default();
+        world.insert_resource(Score(3));
+        let v1 = SaveGame::new(&dir)
+            .with_version(1)
+            .with_resource::<Score>("points");
+        v1.save(&world, "old").unwrap();
+
+        let mut queue = CommandQueue::new();
+        let v2 = SaveGame::new(&dir)
+            .with_version(2)
+            .with_resource::<Score>("score")
+            .with_migration(1, |save| {
+                save["resources"]["score"] = save["resources"]["points"].take();
+                Ok(())
+            });
+        world.insert_resource(Score(0));
+        assert_eq!(v2.load(&mut world, &mut queue, "old").unwrap().resources, 1);
+        assert_eq!(world.resource::<Score>(), Some(&Score(3)));
+
+        let v3 = SaveGame::new(&dir).with_version(3);
+        assert!(matches!(
+            v3.load(&mut world, &mut queue, "old"),
+            Err(SaveError::NoMigration(1))
+        ));
+        let v0 = SaveGame::new(&dir);
+        assert!(matches!(
+            v0.load(&mut world, &mut queue, "old"),
+            Err(SaveError::NewerVersion {
+                found: 1,
+                current: 0
+            })
+        ));
+        assert!(matches!(
+            v0.load(&mut world, &mut queue, "../old"),
+            Err(SaveError::SlotName(_))
+        ));
+        assert!(matches!(
+            v0.load(&mut world, &mut queue, "missing"),
+            Err(SaveError::NoSave(_))
+        ));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
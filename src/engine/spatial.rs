@@ -0,0 +1,357 @@
+//! Spatial partitioning for instance bounds.
+//!
+//! `SpatialGrid` is a uniform grid over world-space AABBs, keyed by the `ComponentId` of the
+//! `RenderableComponent` that owns the instance.
+//!
+//! Data flow:
+//! - `RenderableSystem` inserts local (mesh-space) bounds when a renderable reaches VisualWorld.
+//! - `TransformSystem` pushes new model matrices when transforms change, which re-buckets the
+//!   world-space bounds.
+//! - Gameplay/picking code calls `query_aabb`, `query_ray`, `query_circle`.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::engine::ecs::ComponentId;
+
+/// Default grid cell edge length in world units.
+///
+/// The demo scenes live roughly in [-1, 1], so a unit cell keeps buckets small without
+/// spreading instances across many cells.
+pub const DEFAULT_CELL_SIZE: f32 = 1.0;
+
+/// Most grid cells one entry is bucketed into. Entries spanning more (e.g. a huge ground
+/// plane), or with non-finite bounds, go on the grid's `large` list instead.
+pub const MAX_ENTRY_CELLS: i64 = 512;
+
+/// Axis-aligned bounding box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: [f32; 3],
+    pub max: [f32; 3],
+}
+
+impl Aabb {
+    pub fn new(min: [f32; 3], max: [f32; 3]) -> Self {
+        Self { min, max }
+    }
+
+    /// Smallest box containing all points. Returns `None` for an empty iterator.
+    pub fn from_points(points: impl IntoIterator<Item = [f32; 3]>) -> Option<Self> {
+        let mut it = points.into_iter();
+        let first = it.next()?;
+        let mut aabb = Self::new(first, first);
+        for p in it {
+            for (i, v) in p.into_iter().enumerate() {
+                aabb.min[i] = aabb.min[i].min(v);
+                aabb.max[i] = aabb.max[i].max(v);
+            }
+        }
+        Some(aabb)
+    }
+
+    pub fn center(&self) -> [f32; 3] {
+        [
+            0.5 * (self.min[0] + self.max[0]),
+            0.5 * (self.min[1] + self.max[1]),
+            0.5 * (self.min[2] + self.max[2]),
+        ]
+    }
+
+    /// Transform this box by a column-major model matrix and return the world-space AABB
+    /// enclosing all 8 transformed corners.
+    pub fn transformed(&self, m: &[[f32; 4]; 4]) -> Self {
+        let corners = (0..8).map(|i| {
+            let x = if i & 1 == 0 { self.min[0] } else { self.max[0] };
+            let y = if i & 2 == 0 { self.min[1] } else { self.max[1] };
+            let z = if i & 4 == 0 { self.min[2] } else { self.max[2] };
+            [
+                m[0][0] * x + m[1][0] * y + m[2][0] * z + m[3][0],
+                m[0][1] * x + m[1][1] * y + m[2][1] * z + m[3][1],
+                m[0][2] * x + m[1][2] * y + m[2][2] * z + m[3][2],
+            ]
+        });
+        // 8 corners always exist.
+        Self::from_points(corners).unwrap_or(*self)
+    }
+
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        (0..3).all(|i| self.min[i] <= other.max[i] && self.max[i] >= other.min[i])
+    }
+
+    /// Slab test. Returns the entry distance along `dir` (0 if the origin is inside).
+    pub fn ray_hit(&self, origin: [f32; 3], dir: [f32; 3]) -> Option<f32> {
+        let mut t_min = 0.0f32;
+        let mut t_max = f32::INFINITY;
+        for i in 0..3 {
+            if dir[i].abs() < 1e-12 {
+                if origin[i] < self.min[i] || origin[i] > self.max[i] {
+                    return None;
+                }
+                continue;
+            }
+            let inv = 1.0 / dir[i];
+            let mut t0 = (self.min[i] - origin[i]) * inv;
+            let mut t1 = (self.max[i] - origin[i]) * inv;
+            if t0 > t1 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            t_min = t_min.max(t0);
+            t_max = t_max.min(t1);
+            if t_min > t_max {
+                return None;
+            }
+        }
+        Some(t_min)
+    }
+
+    /// Circle overlap in the XY plane (Z is ignored).
+    pub fn intersects_circle_xy(&self, center: [f32; 2], radius: f32) -> bool {
+        let cx = center[0].clamp(self.min[0], self.max[0]);
+        let cy = center[1].clamp(self.min[1], self.max[1]);
+        let dx = center[0] - cx;
+        let dy = center[1] - cy;
+        dx * dx + dy * dy <= radius * radius
+    }
+}
+
+type CellKey = (i32, i32, i32);
+
+#[derive(Debug, Clone, Copy)]
+struct SpatialEntry {
+    local: Aabb,
+    world: Aabb,
+}
+
+/// Uniform grid of world-space instance bounds.
+#[derive(Debug)]
+pub struct SpatialGrid {
+    cell_size: f32,
+    entries: HashMap<ComponentId, SpatialEntry>,
+    cells: HashMap<CellKey, Vec<ComponentId>>,
+    /// Entries too big (or broken) to bucket; every query checks them.
+    large: HashSet<ComponentId>,
+}
+
+impl Default for SpatialGrid {
+    fn default() -> Self {
+        Self::new(DEFAULT_CELL_SIZE)
+    }
+}
+
+impl SpatialGrid {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size: cell_size.max(1e-4),
+            entries: HashMap::new(),
+            cells: HashMap::new(),
+            large: HashSet::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains(&self, cid: ComponentId) -> bool {
+        self.entries.contains_key(&cid)
+    }
+
+    /// Current world-space bounds for an entry.
+    pub fn world_bounds(&self, cid: ComponentId) -> Option<Aabb> {
+        self.entries.get(&cid).map(|e| e.world)
+    }
+
+    /// Insert (or replace) an entry with mesh-space bounds and its current model matrix.
+    pub fn insert(&mut self, cid: ComponentId, local: Aabb, model: &[[f32; 4]; 4]) {
+        self.remove(cid);
+        let world = local.transformed(model);
+        self.add_to_cells(cid, &world);
+        self.entries.insert(cid, SpatialEntry { local, world });
+    }
+
+    /// Re-bucket an entry after its model matrix changed. No-op for unknown ids.
+    pub fn update_model(&mut self, cid: ComponentId, model: &[[f32; 4]; 4]) {
+        let Some(entry) = self.entries.get(&cid).copied() else {
+            return;
+        };
+        let world = entry.local.transformed(model);
+        if self.entry_cells(&world) != self.entry_cells(&entry.world) {
+            self.remove_from_cells(cid, &entry.world);
+            self.add_to_cells(cid, &world);
+        }
+        self.entries.insert(
+            cid,
+            SpatialEntry {
+                local: entry.local,
+                world,
+            },
+        );
+    }
+
+    pub fn remove(&mut self, cid: ComponentId) -> bool {
+        let Some(entry) = self.entries.remove(&cid) else {
+            return false;
+        };
+        self.remove_from_cells(cid, &entry.world);
+        true
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.cells.clear();
+        self.large.clear();
+    }
+
+    /// All entries whose world bounds overlap `aabb`.
+    pub fn query_aabb(&self, aabb: &Aabb) -> Vec<ComponentId> {
+        let mut seen: HashSet<ComponentId> = HashSet::new();
+        let mut out = Vec::new();
+        let mut visit = |ids: &mut dyn Iterator<Item = &ComponentId>| {
+            for &cid in ids {
+                if seen.insert(cid) && self.entries[&cid].world.intersects(aabb) {
+                    out.push(cid);
+                }
+            }
+        };
+        visit(&mut self.large.iter());
+
+        let (lo, hi) = self.cell_range(aabb);
+        let volume = (hi.0 - lo.0 + 1) as i64 * (hi.1 - lo.1 + 1) as i64 * (hi.2 - lo.2 + 1) as i64;
+
+        if volume > self.cells.len() as i64 {
+            // Large query box: walking occupied cells is cheaper than walking the range.
+            for (key, ids) in &self.cells {
+                let in_range = (lo.0..=hi.0).contains(&key.0)
+                    && (lo.1..=hi.1).contains(&key.1)
+                    && (lo.2..=hi.2).contains(&key.2);
+                if in_range {
+                    visit(&mut ids.iter());
+                }
+            }
+        } else {
+            for x in lo.0..=hi.0 {
+                for y in lo.1..=hi.1 {
+                    for z in lo.2..=hi.2 {
+                        if let Some(ids) = self.cells.get(&(x, y, z)) {
+                            visit(&mut ids.iter());
+                        }
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// Entries hit by a ray within `max_dist`, sorted nearest first with their hit distance.
+    ///
+    /// `dir` does not need to be normalized; distances are in units of `dir`.
+    pub fn query_ray(
+        &self,
+        origin: [f32; 3],
+        dir: [f32; 3],
+        max_dist: f32,
+    ) -> Vec<(ComponentId, f32)> {
+        let end = [
+            origin[0] + dir[0] * max_dist,
+            origin[1] + dir[1] * max_dist,
+            origin[2] + dir[2] * max_dist,
+        ];
+        let Some(segment) = Aabb::from_points([origin, end]) else {
+            return Vec::new();
+        };
+
+        let mut hits: Vec<(ComponentId, f32)> = self
+            .query_aabb(&segment)
+            .into_iter()
+            .filter_map(|cid| {
+                let t = self.entries[&cid].world.ray_hit(origin, dir)?;
+                (t <= max_dist).then_some((cid, t))
+            })
+            .collect();
+        hits.sort_by(|a, b| a.1.total_cmp(&b.1));
+        hits
+    }
+
+    /// Entries overlapping a circle in the XY plane (the 2D broad-phase query).
+    pub fn query_circle(&self, center: [f32; 2], radius: f32) -> Vec<ComponentId> {
+        let bounds = Aabb::new(
+            [center[0] - radius, center[1] - radius, f32::MIN],
+            [center[0] + radius, center[1] + radius, f32::MAX],
+        );
+        self.query_aabb(&bounds)
+            .into_iter()
+            .filter(|cid| self.entries[cid].world.intersects_circle_xy(center, radius))
+            .collect()
+    }
+
+    fn cell_coord(&self, v: f32) -> i32 {
+        // Clamp so unbounded query boxes (e.g. the Z extent of `query_circle`) stay finite.
+        (v / self.cell_size).floor().clamp(-1.0e6, 1.0e6) as i32
+    }
+
+    fn cell_range(&self, aabb: &Aabb) -> (CellKey, CellKey) {
+        (
+            (
+                self.cell_coord(aabb.min[0]),
+                self.cell_coord(aabb.min[1]),
+                self.cell_coord(aabb.min[2]),
+            ),
+            (
+                self.cell_coord(aabb.max[0]),
+                self.cell_coord(aabb.max[1]),
+                self.cell_coord(aabb.max[2]),
+            ),
+        )
+    }
+
+    /// Cells an entry with these bounds is bucketed into, or `None` if it belongs on the
+    /// `large` list.
+    fn entry_cells(&self, aabb: &Aabb) -> Option<(CellKey, CellKey)> {
+        if !aabb.min.iter().chain(&aabb.max).all(|v| v.is_finite()) {
+            return None;
+        }
+        let (lo, hi) = self.cell_range(aabb);
+        let span = |lo: i32, hi: i32| (i64::from(hi) - i64::from(lo) + 1).max(0);
+        let cells = span(lo.0, hi.0)
+            .saturating_mul(span(lo.1, hi.1))
+            .saturating_mul(span(lo.2, hi.2));
+        (cells <= MAX_ENTRY_CELLS).then_some((lo, hi))
+    }
+
+    fn add_to_cells(&mut self, cid: ComponentId, world: &Aabb) {
+        let Some((lo, hi)) = self.entry_cells(world) else {
+            self.large.insert(cid);
+            return;
+        };
+        for x in lo.0..=hi.0 {
+            for y in lo.1..=hi.1 {
+                for z in lo.2..=hi.2 {
+                    self.cells.entry((x, y, z)).or_default().push(cid);
+                }
+            }
+        }
+    }
+
+    fn remove_from_cells(&mut self, cid: ComponentId, world: &Aabb) {
+        let Some((lo, hi)) = self.entry_cells(world) else {
+            self.large.remove(&cid);
+            return;
+        };
+        for x in lo.0..=hi.0 {
+            for y in lo.1..=hi.1 {
+                for z in lo.2..=hi.2 {
+                    if let Some(ids) = self.cells.get_mut(&(x, y, z)) {
+                        ids.retain(|&c| c != cid);
+                        if ids.is_empty() {
+                            self.cells.remove(&(x, y, z));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,117 @@
+#[cfg(test)]
+mod tests {
+    use crate::engine::ecs::World;
+    use crate::engine::ecs::component::TransformComponent;
+    use crate::engine::spatial::{Aabb, SpatialGrid};
+
+    fn translation(x: f32, y: f32, z: f32) -> [[f32; 4]; 4] {
+        [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [x, y, z, 1.0],
+        ]
+    }
+
+    fn unit_box() -> Aabb {
+        Aabb::new([-0.5, -0.5, 0.0], [0.5, 0.5, 0.0])
+    }
+
+    #[test]
+    fn query_aabb_finds_overlapping_entries_only() {
+        let mut w = World::default();
+        let a = w.add_component(TransformComponent::new());
+        let b = w.add_component(TransformComponent::new());
+
+        let mut grid = SpatialGrid::new(1.0);
+        grid.insert(a, unit_box(), &translation(0.0, 0.0, 0.0));
+        grid.insert(b, unit_box(), &translation(5.0, 0.0, 0.0));
+
+        let hits = grid.query_aabb(&Aabb::new([-1.0, -1.0, -1.0], [1.0, 1.0, 1.0]));
+        assert_eq!(hits, vec![a]);
+    }
+
+    #[test]
+    fn update_model_moves_entry_between_cells() {
+        let mut w = World::default();
+        let a = w.add_component(TransformComponent::new());
+
+        let mut grid = SpatialGrid::new(1.0);
+        grid.insert(a, unit_box(), &translation(0.0, 0.0, 0.0));
+        grid.update_model(a, &translation(10.0, 10.0, 0.0));
+
+        assert!(grid.query_circle([0.0, 0.0], 1.0).is_empty());
+        assert_eq!(grid.query_circle([10.0, 10.0], 0.1), vec![a]);
+    }
+
+    #[test]
+    fn query_ray_sorts_hits_by_distance() {
+        let mut w = World::default();
+        let near = w.add_component(TransformComponent::new());
+        let far = w.add_component(TransformComponent::new());
+        let behind = w.add_component(TransformComponent::new());
+
+        let mut grid = SpatialGrid::new(1.0);
+        grid.insert(far, unit_box(), &translation(4.0, 0.0, 0.0));
+        grid.insert(near, unit_box(), &translation(2.0, 0.0, 0.0));
+        grid.insert(behind, unit_box(), &translation(-3.0, 0.0, 0.0));
+
+        let hits = grid.query_ray([0.0, 0.0, 0.0], [1.0, 0.0, 0.0], 10.0);
+        let ids: Vec<_> = hits.iter().map(|(cid, _)| *cid).collect();
+        assert_eq!(ids, vec![near, far]);
+        assert!((hits[0].1 - 1.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn remove_drops_entry_from_queries() {
+        let mut w = World::default();
+        let a = w.add_component(TransformComponent::new());
+
+        let mut grid = SpatialGrid::default();
+        grid.insert(a, unit_box(), &translation(0.0, 0.0, 0.0));
+        assert!(grid.remove(a));
+        assert!(grid.is_empty());
+        assert!(grid.query_circle([0.0, 0.0], 2.0).is_empty());
+    }
+
+    #[test]
+    fn huge_and_non_finite_bounds_skip_the_grid_but_stay_queryable() {
+        let mut w = World::default();
+        let ground = w.add_component(TransformComponent::new());
+        let broken = w.add_component(TransformComponent::new());
+        let small = w.add_component(TransformComponent::new());
+
+        // Bucketing these cell by cell would walk ~1e12 cells.
+        let mut grid = SpatialGrid::new(1.0);
+        let huge = Aabb::new([-1.0e6, -1.0e6, 0.0], [1.0e6, 1.0e6, 0.0]);
+        grid.insert(ground, huge, &translation(0.0, 0.0, 0.0));
+        let nan = Aabb::new([f32::NAN, 0.0, 0.0], [0.0, f32::INFINITY, 0.0]);
+        grid.insert(broken, nan, &translation(-50.0, 0.0, 0.0));
+        grid.insert(small, unit_box(), &translation(5.0, 0.0, 0.0));
+
+        assert_eq!(grid.query_circle([100.0, 100.0], 1.0), vec![ground]);
+        assert_eq!(grid.query_circle([-50.0, 1000.0], 1.0).len(), 2);
+        let mut hits = grid.query_circle([5.0, 0.0], 1.0);
+        hits.sort();
+        let mut expected = vec![ground, small];
+        expected.sort();
+        assert_eq!(hits, expected);
+
+        // Shrinking the ground moves it into the grid; it is no longer everywhere.
+        grid.update_model(
+            ground,
+            &[
+                [1.0e-6, 0.0, 0.0, 0.0],
+                [0.0, 1.0e-6, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ],
+        );
+        assert!(grid.query_circle([100.0, 100.0], 1.0).is_empty());
+        assert_eq!(grid.query_circle([0.0, 0.0], 0.5), vec![ground]);
+
+        assert!(grid.remove(ground));
+        assert!(grid.remove(broken));
+        assert_eq!(grid.len(), 1);
+    }
+}
@@ -1,12 +1,25 @@
-use crate::engine::ecs::component::{
-    ColorComponent, InputComponent, PointLightComponent, RenderableComponent, TextureComponent,
-    TransformComponent,
-};
-use crate::engine::graphics::mesh::MeshFactory;
-use crate::engine::graphics::primitives::MaterialHandle;
+#[cfg(feature = "headless-server")]
+use crate::engine::EngineResult;
+use crate::engine::assets::{AssetDependencies, AssetServer};
+use crate::engine::console::Console;
+use crate::engine::ecs::codec::{ComponentCodec, Scene};
+use crate::engine::error::AssetError;
+use crate::engine::frame_pacing::FramePacing;
+#[cfg(feature = "windowed")]
+use crate::engine::graphics::RendererError;
+use crate::engine::networking::Networking;
+use crate::engine::repl::{self, Repl, ReplBackend};
+use crate::engine::universe_builder::{InitialScene, RendererBackend, UniverseBuilder};
 use crate::engine::user_input::InputState;
-use crate::engine::{ecs, graphics};
+use crate::engine::{ecs, graphics, i18n};
+use crate::utils::trace;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "windowed")]
 use std::sync::Arc;
+#[cfg(feature = "headless-server")]
+use std::time::{Duration, Instant};
+use winit::keyboard::{Key, NamedKey};
+#[cfg(feature = "windowed")]
 use winit::window::Window;
 
 pub struct Universe {
@@ -16,12 +29,62 @@ pub struct Universe {
 
     pub visuals: graphics::VisualWorld,
     pub render_assets: graphics::RenderAssets,
+    pub assets: AssetServer,
+    pub inspector: graphics::RenderingInspector,
+    /// When the windowed loop draws; see `UniverseBuilder::fps_cap`.
+    pub pacing: FramePacing,
+    pub console: Console,
+    pub repl: Repl,
+    /// Connections to other processes; offline unless `UniverseBuilder::networking` set one
+    /// up. Polled at the start of every `update`.
+    pub networking: Networking,
 
-    renderer: graphics::VulkanoRenderer,
+    /// `None` with `RendererBackend::None`.
+    #[cfg(feature = "windowed")]
+    renderer: Option<graphics::VulkanoRenderer>,
+    /// See `UniverseBuilder::asset_root`.
+    asset_root: Option<PathBuf>,
+    /// See `UniverseBuilder::repl`.
+    repl_enabled: bool,
+    /// Frames updated so far (stamped on log records).
+    frame: u64,
+    /// See `on_suspend`.
+    suspended: bool,
+    /// See `UniverseBuilder::update_while_suspended`.
+    update_while_suspended: bool,
 }
 
 impl Universe {
+    /// Empty scene with every default; see `UniverseBuilder` for the options.
     pub fn new(world: ecs::World) -> Self {
+        UniverseBuilder::new().world(world).build()
+    }
+
+    pub(crate) fn from_builder(builder: UniverseBuilder) -> Self {
+        let UniverseBuilder {
+            world,
+            scene,
+            systems,
+            renderer,
+            asset_root,
+            repl,
+            frames_in_flight,
+            update_while_suspended,
+            fps_cap,
+            renderer_config,
+            networking,
+        } = builder;
+
+        #[cfg(feature = "windowed")]
+        let renderer = match renderer {
+            RendererBackend::Vulkano => {
+                Some(graphics::VulkanoRenderer::new().with_frames_in_flight(frames_in_flight))
+            }
+            RendererBackend::None => None,
+        };
+        #[cfg(not(feature = "windowed"))]
+        let (RendererBackend::None, _) = (renderer, frames_in_flight);
+
         let mut u = Self {
             world,
             command_queue: ecs::CommandQueue::new(),
@@ -29,217 +92,283 @@ impl Universe {
 
             visuals: graphics::VisualWorld::new(),
             render_assets: graphics::RenderAssets::new(),
-            renderer: graphics::VulkanoRenderer::new(),
+            assets: AssetServer::new(asset_root.clone()),
+            inspector: graphics::RenderingInspector::new(),
+            pacing: FramePacing::new(fps_cap),
+            console: Console::new(),
+            repl: if repl {
+                Repl::new()
+            } else {
+                Repl::without_stdin()
+            },
+            networking,
+            #[cfg(feature = "windowed")]
+            renderer,
+            asset_root,
+            repl_enabled: repl,
+            frame: 0,
+            suspended: false,
+            update_while_suspended,
         };
+        u.systems.enabled = systems;
+        u.visuals.set_renderer_config(renderer_config);
 
-        // Temporary: rebuild a demo scene directly in Universe creation.
-        // This keeps runtime visuals alive while we finalize a proper scene/level layer.
-        u.build_demo_scene_7_shapes();
+        let bindings = u.asset_path(crate::engine::action_map::INPUT_BINDINGS_PATH);
+        if let Err(e) = u.systems.input.actions.load(&bindings) {
+            crate::log_info!("using default input bindings ({e})");
+        }
+        u.load_string_tables();
+
+        match scene {
+            InitialScene::Empty => {}
+            InitialScene::File(path) => {
+                let out = u.source(&u.asset_path(path));
+                u.print_output(out);
+            }
+            InitialScene::Custom(build) => build(&mut u),
+        }
+
+        if u.repl_enabled {
+            u.run_rc_script();
+        }
 
         u
     }
 
+    /// Mutable view for game code: spawn components and mutate them through queued commands.
+    pub fn context(&mut self) -> ecs::WorldContext<'_> {
+        ecs::WorldContext::new(
+            &mut self.world,
+            &mut self.command_queue,
+            &mut self.systems,
+            &mut self.visuals,
+            &mut self.render_assets,
+        )
+    }
+
+    /// Merge the string tables under `i18n::LOCALES_DIR`, then switch to `$LITTLECAT_LOCALE`.
+    fn load_string_tables(&self) {
+        let dir = self.asset_path(i18n::LOCALES_DIR);
+        let mut l10n = i18n::current_mut();
+        if dir.is_dir() {
+            match l10n.load_dir(&dir) {
+                Ok(locales) => crate::log_debug!("string tables: {}", locales.join(", ")),
+                Err(e) => crate::log_warn!("string tables: {e}"),
+            }
+        }
+        if let Ok(locale) = std::env::var(i18n::LOCALE_ENV_VAR)
+            && !l10n.set_locale(&locale)
+        {
+            crate::log_warn!("{}: no string table for '{locale}'", i18n::LOCALE_ENV_VAR);
+        }
+    }
+
+    /// `path` resolved against the configured asset root (unchanged without one).
+    pub fn asset_path(&self, path: impl AsRef<Path>) -> PathBuf {
+        match &self.asset_root {
+            Some(root) => root.join(path),
+            None => path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Start loading everything `scene` needs, so instantiating it doesn't stall the first
+    /// frame that shows it. Returns the scene's dependencies.
+    pub fn preload(&mut self, scene: &Scene) -> Result<AssetDependencies, AssetError> {
+        let deps = AssetDependencies::of_scene(scene, &mut self.world)?;
+        self.assets.preload(&deps);
+        Ok(deps)
+    }
+
+    /// Read a scene file, record it in the dependency graph, preload its assets and add its
+    /// component trees to the world.
+    pub fn load_scene(
+        &mut self,
+        path: impl AsRef<Path>,
+    ) -> Result<Vec<ecs::ComponentId>, AssetError> {
+        let path = path.as_ref();
+        let scene = Scene::load(path)?;
+        let deps = self.preload(&scene)?;
+        self.assets.record_scene(path, deps);
+        Ok(ComponentCodec::instantiate(
+            &mut self.world,
+            &mut self.command_queue,
+            &scene,
+        )?)
+    }
+
+    /// Rebuild the active 3D camera's projection after its fields or the viewport changed,
+    /// rather than on the next `CameraSystem::tick`.
+    pub(crate) fn refresh_camera_projection(&mut self) {
+        if let Some(cid) = self.systems.camera.active_camera_component() {
+            self.systems
+                .camera
+                .update_camera_3d(&self.world, &mut self.visuals, cid);
+        }
+    }
+
     /// Initialize the renderer for a window.
     /// This must be called before rendering.
-    pub fn init_renderer_for_window(
-        &mut self,
-        window: &Arc<Window>,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        self.renderer.init_for_window(window)
+    #[cfg(feature = "windowed")]
+    pub fn init_renderer_for_window(&mut self, window: &Arc<Window>) -> Result<(), RendererError> {
+        let size = window.inner_size();
+        self.visuals
+            .set_viewport(size.width as f32, size.height as f32);
+        self.refresh_camera_projection();
+        match self.renderer.as_mut() {
+            Some(renderer) => renderer.init_for_window(window),
+            None => Ok(()),
+        }
     }
 
     /// Resize the renderer when the window is resized.
+    ///
+    /// A zero-sized window (minimized) suspends the universe; the next real size resumes it.
+    #[cfg(feature = "windowed")]
     pub fn resize_renderer(&mut self, size: winit::dpi::PhysicalSize<u32>) {
-        self.renderer.resize(size);
+        if size.width == 0 || size.height == 0 {
+            self.on_suspend();
+            return;
+        }
+        self.on_resume();
+        self.visuals
+            .set_viewport(size.width as f32, size.height as f32);
+        self.refresh_camera_projection();
+        if let Some(renderer) = self.renderer.as_mut() {
+            renderer.resize(size);
+        }
     }
 
-    fn build_demo_scene_7_shapes(&mut self) {
-        // Register CPU meshes once and reuse handles.
-        let tri_mesh = self.render_assets.register_mesh(MeshFactory::triangle_2d());
-        let square_mesh = self.render_assets.register_mesh(MeshFactory::quad_2d());
-
-        fn spawn(
-            world: &mut ecs::World,
-            queue: &mut ecs::CommandQueue,
-            mesh: crate::engine::graphics::primitives::CpuMeshHandle,
-            x: f32,
-            y: f32,
-            s: f32,
-            r: f32,
-            color: [f32; 4],
-            input_driven: bool,
-        ) -> ecs::ComponentId {
-            let transform = world.add_component(
-                TransformComponent::new()
-                    .with_position(x, y, 0.0)
-                    .with_scale(s, s, 1.0)
-                    .with_rotation_euler(0.0, 0.0, r),
-            );
-            let renderable = world.add_component(RenderableComponent::new(
-                crate::engine::graphics::primitives::Renderable::new(
-                    mesh,
-                    MaterialHandle::TOON_MESH,
-                ),
-            ));
-            let color_c = world.add_component(ColorComponent { rgba: color });
-
-            // Topology: (optional Input) -> Transform -> Renderable
-            let _ = world.add_child(transform, renderable);
-            let _ = world.add_child(renderable, color_c);
-
-            if input_driven {
-                let input = world.add_component(InputComponent::new().with_speed(0.5));
-                let _ = world.add_child(input, transform);
-                world.init_component_tree(input, queue);
-            } else {
-                world.init_component_tree(transform, queue);
-            }
+    /// The window was minimized or hidden, or the app was sent to the background: stop
+    /// rendering until `on_resume`. Whether `update` keeps running is up to the caller (see
+    /// `updates_while_suspended`). Calling it again while suspended does nothing.
+    pub fn on_suspend(&mut self) {
+        if !self.suspended {
+            self.suspended = true;
+            crate::log_info!("suspended");
+        }
+    }
 
-            transform
+    pub fn on_resume(&mut self) {
+        if self.suspended {
+            self.suspended = false;
+            crate::log_info!("resumed");
         }
+    }
 
-        // Spawn shapes.
-        // One triangle is input-driven (WASD/QE). The point light is attached under the same
-        // transform so it moves with the triangle.
-        let tri_root_transform = self
-            .world
-            .add_component(TransformComponent::new().with_position(0.5, 0.50, 0.0));
-
-        // Visual transform under the root; this is where we apply rotation/scale.
-        // Rotating by PI should visually flip the triangle while leaving its input-driven
-        // movement (on the root transform) unchanged.
-        let tri_visual_transform = self.world.add_component(
-            TransformComponent::new()
-                .with_scale(0.30, 0.30, 1.0)
-                .with_rotation_euler(0.0, 0.0, (2.0 * 3.14159 / 3.0) + 3.14159),
-        );
-        let tri_renderable = self.world.add_component(RenderableComponent::new(
-            crate::engine::graphics::primitives::Renderable::new(
-                tri_mesh,
-                MaterialHandle::TOON_MESH,
-            ),
-        ));
-        let tri_color = self
-            .world
-            .add_component(ColorComponent::rgba(0.2, 1.0, 0.2, 1.0));
-        let tri_light = self.world.add_component(
-            PointLightComponent::new()
-                .with_distance(10.0)
-                .with_color(1.0, 0.0, 0.0),
-        );
+    pub fn is_suspended(&self) -> bool {
+        self.suspended
+    }
 
-        let _ = self
-            .world
-            .add_child(tri_root_transform, tri_visual_transform);
-        let _ = self.world.add_child(tri_visual_transform, tri_renderable);
-        let _ = self.world.add_child(tri_renderable, tri_color);
-        let _ = self.world.add_child(tri_root_transform, tri_light);
+    /// The window gained or lost keyboard focus. Losing it releases held keys and buttons
+    /// (their release events go elsewhere) and drops to the unfocused frame rate.
+    pub fn on_focus_changed(&mut self, focused: bool, input: &mut InputState) {
+        if self.pacing.focused() == focused {
+            return;
+        }
+        self.pacing.set_focused(focused);
+        if !focused {
+            input.release_all();
+        }
+        crate::log_debug!("focus {}", if focused { "gained" } else { "lost" });
+    }
 
-        let tri_input = self
-            .world
-            .add_component(InputComponent::new().with_speed(0.5));
-        let _ = self.world.add_child(tri_input, tri_root_transform);
-        self.world
-            .init_component_tree(tri_input, &mut self.command_queue);
+    /// Whether the event loop should keep calling `update` while suspended.
+    pub fn updates_while_suspended(&self) -> bool {
+        self.update_while_suspended
+    }
 
-        spawn(
-            &mut self.world,
-            &mut self.command_queue,
-            square_mesh,
-            -0.80,
-            -0.30,
-            0.25,
-            0.0,
-            [1.0, 0.2, 0.2, 1.0],
-            false,
-        );
-        spawn(
-            &mut self.world,
-            &mut self.command_queue,
-            square_mesh,
-            -0.40,
-            -0.30,
-            0.25,
-            0.0,
-            [1.0, 0.6, 0.2, 1.0],
-            false,
-        );
-        spawn(
-            &mut self.world,
-            &mut self.command_queue,
-            square_mesh,
-            0.00,
-            -0.30,
-            0.25,
-            0.0,
-            [1.0, 1.0, 0.2, 1.0],
-            false,
-        );
-        spawn(
-            &mut self.world,
-            &mut self.command_queue,
-            square_mesh,
-            0.40,
-            -0.30,
-            0.25,
-            0.0,
-            [0.2, 0.6, 1.0, 1.0],
-            false,
-        );
-        spawn(
-            &mut self.world,
-            &mut self.command_queue,
-            square_mesh,
-            0.80,
-            -0.30,
-            0.25,
-            0.0,
-            [0.8, 0.2, 1.0, 1.0],
-            false,
-        );
-        spawn(
-            &mut self.world,
-            &mut self.command_queue,
-            tri_mesh,
-            0.30,
-            0.35,
-            0.30,
-            -3.14159,
-            [1.0, 1.0, 1.0, 1.0],
-            false,
-        );
+    /// Device memory in use by the renderer, by category. All zero without a GPU renderer
+    /// (`RendererBackend::None`, headless server).
+    pub fn gpu_memory(&self) -> graphics::GpuMemoryUsage {
+        #[cfg(feature = "windowed")]
+        if let Some(renderer) = &self.renderer {
+            return renderer.memory_usage();
+        }
+        graphics::GpuMemoryUsage::default()
+    }
 
-        // Textured square.
-        let tex_transform = self.world.add_component(
-            TransformComponent::new()
-                .with_position(0.0, 0.10, 0.0)
-                .with_scale(0.45, 0.45, 1.0),
-        );
-        let tex_renderable = self.world.add_component(RenderableComponent::new(
-            crate::engine::graphics::primitives::Renderable::new(
-                square_mesh,
-                MaterialHandle::TOON_MESH,
-            ),
-        ));
-        let tex_color = self
-            .world
-            .add_component(ColorComponent::rgba(1.0, 1.0, 1.0, 1.0));
-        let tex = self
-            .world
-            .add_component(TextureComponent::from_png("assets/cat-face-neutral.png"));
+    /// Execute a REPL line (`;`-separated, aliases expanded), echoing output to stdout and
+    /// the console.
+    pub fn run_command(&mut self, line: &str) {
+        for command in self.repl.expand(line) {
+            let out = self.execute(&command);
+            self.print_output(out);
+        }
+    }
 
-        let _ = self.world.add_child(tex_transform, tex_renderable);
-        let _ = self.world.add_child(tex_renderable, tex_color);
-        let _ = self.world.add_child(tex_renderable, tex);
-        self.world
-            .init_component_tree(tex_transform, &mut self.command_queue);
+    /// Echo command output to stdout and the console.
+    fn print_output(&mut self, lines: Vec<String>) {
+        for line in lines {
+            println!("{line}");
+            self.console.push_line(line);
+        }
+    }
 
-        // NOTE: This demo intentionally does not spawn a camera.
-        // VisualWorld defaults to an identity 2D camera transform.
+    /// Run the user's startup script, if there is one.
+    fn run_rc_script(&mut self) {
+        let Some(path) = repl::rc_path() else {
+            return;
+        };
+        if !path.exists() {
+            return;
+        }
+        crate::log_info!("running {}", path.display());
+        let out = self.source(&path);
+        self.print_output(out);
+    }
+
+    /// Run the command lines systems queued this tick (state machine hooks). Their output goes
+    /// to the debug log rather than the console.
+    fn run_pending_commands(&mut self) {
+        let lines = self
+            .world
+            .resource_mut::<ecs::system::PendingCommands>()
+            .map(ecs::system::PendingCommands::take)
+            .unwrap_or_default();
+        for line in lines {
+            for command in self.repl.expand(&line) {
+                for out in self.execute(&command) {
+                    crate::log_debug!("{command}: {out}");
+                }
+            }
+        }
     }
 
     /// Game/update step
-    pub fn update(&mut self, dt_sec: f32, input: &InputState) {
+    ///
+    /// `input` is mutable so game code can take/release text focus (see
+    /// `InputState::set_text_focus`).
+    pub fn update(&mut self, dt_sec: f32, input: &mut InputState) {
+        let _span = trace::span("frame.update");
+        self.frame += 1;
+        crate::utils::logger::set_frame(self.frame);
+        if self.frame % crate::utils::crash_report::SCENE_SNAPSHOT_INTERVAL_FRAMES == 1 {
+            let scene = ComponentCodec::encode_world(&self.world).to_json();
+            crate::utils::crash_report::set_scene_snapshot(
+                self.frame,
+                serde_json::to_string_pretty(&scene).unwrap_or_default(),
+            );
+        }
+
+        let poll = trace::span("networking.poll");
+        if let Err(e) = self.networking.poll() {
+            crate::log_warn!("net: {e}");
+        }
+        drop(poll);
+
+        if input.key_pressed(&Key::Named(NamedKey::F3)) {
+            self.inspector.toggle();
+        }
+
+        // REPL: console line first, then anything typed into the terminal.
+        if self.repl_enabled {
+            let mut commands: Vec<String> = self.console.process_input(input).into_iter().collect();
+            commands.extend(self.repl.poll_stdin());
+            for line in commands {
+                self.run_command(&line);
+            }
+        }
+
         // 1. Process input events (handled inside systems for now).
         // 2. Let systems call methods on components,
         //      for example, to update transforms or renderables, which
@@ -255,21 +384,84 @@ impl Universe {
         // Process commands after tick so any commands queued during tick are processed in the same frame
         self.systems
             .process_commands(&mut self.world, &mut self.visuals, &mut self.command_queue);
+        self.run_pending_commands();
+
+        crate::utils::profiling::end_frame(self.frame, dt_sec);
     }
 
+    /// Upload pending assets and draw a frame; does nothing while suspended.
+    #[cfg(feature = "windowed")]
     pub fn render(&mut self) {
+        if self.suspended {
+            return;
+        }
+        let Some(renderer) = self.renderer.as_mut() else {
+            return;
+        };
+
         // Prepare render (mesh uploads) - cast renderer to trait
+        let prepare = trace::span("render.prepare");
         self.systems.prepare_render(
             &mut self.world,
             &mut self.visuals,
             &mut self.render_assets,
-            &mut self.renderer as &mut dyn graphics::RenderUploader,
+            &mut self.assets,
+            renderer as &mut dyn graphics::RenderUploader,
         );
+        self.console.draw(&mut self.visuals, renderer);
+
+        if self.inspector.enabled() {
+            self.visuals.prepare_draw_cache();
+            self.inspector.inspect(&self.world, &self.visuals);
+        }
 
-        // TODO: rebuild inspector around component graph instead of entities.
+        drop(prepare);
 
-        self.renderer
+        let _span = trace::span("render.draw");
+        renderer
             .render_visual_world(&mut self.visuals)
             .expect("render failed");
+        self.visuals.end_frame();
+        if let Some(frame_time) = renderer.last_frame_time() {
+            self.visuals.render_scale_mut().update(frame_time);
+        }
+    }
+
+    /// The upload half of `render` against any uploader: flushes pending renderables,
+    /// textures and static batches into `visuals` and lays out the console overlay. With
+    /// `graphics::NullUploader` this builds the full draw state without a GPU (benchmarks,
+    /// tools).
+    pub fn prepare_render_with(&mut self, uploader: &mut dyn graphics::RenderUploader) {
+        self.systems.prepare_render(
+            &mut self.world,
+            &mut self.visuals,
+            &mut self.render_assets,
+            &mut self.assets,
+            uploader,
+        );
+        self.console.draw(&mut self.visuals, uploader);
+    }
+
+    /// Dedicated-server loop: fixed-rate `update` with no window, renderer or local input.
+    ///
+    /// Commands still arrive through the terminal REPL. Only returns on error.
+    #[cfg(feature = "headless-server")]
+    pub fn run_server(&mut self, tick_hz: u32) -> EngineResult<()> {
+        let tick = Duration::from_secs_f64(1.0 / f64::from(tick_hz.max(1)));
+        let mut input = InputState::default();
+        crate::log_info!("server running at {tick_hz} Hz");
+
+        let mut next_tick = Instant::now();
+        loop {
+            self.update(tick.as_secs_f32(), &mut input);
+            input.end_frame();
+
+            next_tick += tick;
+            match next_tick.checked_duration_since(Instant::now()) {
+                Some(wait) => std::thread::sleep(wait),
+                // Fell behind (long tick): resync instead of bursting to catch up.
+                None => next_tick = Instant::now(),
+            }
+        }
     }
 }
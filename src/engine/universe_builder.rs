@@ -0,0 +1,163 @@
+//! `UniverseBuilder`: configure a `Universe` for a particular application.
+//!
+//! ```ignore
+//! let universe = UniverseBuilder::new()
+//!     .scene(InitialScene::custom(|u| build_level(u)))
+//!     .asset_root("/opt/game")
+//!     .repl(false)
+//!     .build();
+//! ```
+
+use std::path::PathBuf;
+
+use crate::engine::Universe;
+use crate::engine::ecs::{self, EnabledSystems};
+use crate::engine::graphics;
+use crate::engine::networking::Networking;
+
+/// What the world contains once `build` returns.
+#[derive(Default)]
+pub enum InitialScene {
+    #[default]
+    Empty,
+
+    /// A command script (same format as `source` / `~/.littlecatrc`), run after startup.
+    File(PathBuf),
+
+    /// Build the scene in code.
+    Custom(Box<dyn FnOnce(&mut Universe)>),
+}
+
+impl InitialScene {
+    pub fn custom(build: impl FnOnce(&mut Universe) + 'static) -> Self {
+        InitialScene::Custom(Box::new(build))
+    }
+}
+
+/// Which renderer `Universe` drives.
+///
+/// Defaults to `Vulkano` in `windowed` builds, `None` otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RendererBackend {
+    /// Vulkan through vulkano (requires the `windowed` feature).
+    #[cfg(feature = "windowed")]
+    #[default]
+    Vulkano,
+
+    /// No GPU work: `render` is a no-op. Use for tools and tests that only need the ECS.
+    #[cfg_attr(not(feature = "windowed"), default)]
+    None,
+}
+
+/// Defaults match `Universe::new`: empty world and scene, all systems, the default renderer,
+/// no asset root, REPL on.
+pub struct UniverseBuilder {
+    pub(crate) world: ecs::World,
+    pub(crate) scene: InitialScene,
+    pub(crate) systems: EnabledSystems,
+    pub(crate) renderer: RendererBackend,
+    pub(crate) asset_root: Option<PathBuf>,
+    pub(crate) repl: bool,
+    pub(crate) frames_in_flight: usize,
+    pub(crate) update_while_suspended: bool,
+    pub(crate) fps_cap: Option<u32>,
+    pub(crate) renderer_config: graphics::RendererConfig,
+    pub(crate) networking: Networking,
+}
+
+impl UniverseBuilder {
+    pub fn new() -> Self {
+        Self {
+            world: ecs::World::default(),
+            scene: InitialScene::Empty,
+            systems: EnabledSystems::ALL,
+            renderer: RendererBackend::default(),
+            asset_root: None,
+            repl: true,
+            frames_in_flight: graphics::DEFAULT_FRAMES_IN_FLIGHT,
+            update_while_suspended: true,
+            fps_cap: None,
+            renderer_config: graphics::RendererConfig::default(),
+            networking: Networking::new(),
+        }
+    }
+
+    /// Start from an existing world instead of an empty one.
+    pub fn world(mut self, world: ecs::World) -> Self {
+        self.world = world;
+        self
+    }
+
+    pub fn scene(mut self, scene: InitialScene) -> Self {
+        self.scene = scene;
+        self
+    }
+
+    pub fn systems(mut self, systems: EnabledSystems) -> Self {
+        self.systems = systems;
+        self
+    }
+
+    pub fn renderer(mut self, renderer: RendererBackend) -> Self {
+        self.renderer = renderer;
+        self
+    }
+
+    /// Directory that relative asset paths (e.g. `assets/cat-face-neutral.png`) resolve
+    /// against. Defaults to the working directory, then the crate root.
+    pub fn asset_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.asset_root = Some(root.into());
+        self
+    }
+
+    /// Enable the REPL (stdin reader, in-game console, rc script). On by default.
+    pub fn repl(mut self, enabled: bool) -> Self {
+        self.repl = enabled;
+        self
+    }
+
+    /// Frames the renderer records while the GPU still draws earlier ones (at least 1,
+    /// default 2). Ignored without a GPU renderer.
+    pub fn frames_in_flight(mut self, frames: usize) -> Self {
+        self.frames_in_flight = frames.max(1);
+        self
+    }
+
+    /// Keep running `update` (at a low rate) while the window is minimized or hidden. On by
+    /// default; turn off for single-player games that should fully idle in the background.
+    pub fn update_while_suspended(mut self, enabled: bool) -> Self {
+        self.update_while_suspended = enabled;
+        self
+    }
+
+    /// Frame rate cap while the window is focused (`None` or `Some(0)`: uncapped, the
+    /// default). Unfocused windows always run at `frame_pacing::DEFAULT_UNFOCUSED_FPS`.
+    pub fn fps_cap(mut self, fps: Option<u32>) -> Self {
+        self.fps_cap = fps;
+        self
+    }
+
+    /// Clear color and letterboxing (a fixed virtual resolution); the REPL's `render` command
+    /// changes them at runtime.
+    pub fn renderer_config(mut self, config: graphics::RendererConfig) -> Self {
+        self.renderer_config = config;
+        self
+    }
+
+    /// Serve or connect over a transport, e.g.
+    /// `Networking::server(UdpTransport::bind("0.0.0.0:7777")?)`. Offline by default.
+    pub fn networking(mut self, networking: Networking) -> Self {
+        self.networking = networking;
+        self
+    }
+
+    pub fn build(self) -> Universe {
+        Universe::from_builder(self)
+    }
+}
+
+impl Default for UniverseBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -4,10 +4,51 @@
 //! owns interpreting window events into a small, reusable `InputState`.
 
 use std::collections::HashSet;
+use std::path::Path;
 
-use winit::event::{ElementState, MouseButton, MouseScrollDelta, WindowEvent};
+use glam::Vec2;
+use winit::event::{ElementState, Ime, MouseButton, MouseScrollDelta, WindowEvent};
 use winit::keyboard::Key;
 
+use crate::engine::AssetError;
+use crate::engine::input_recording::{InputPlayback, InputRecorder};
+use crate::engine::xr::XrPose;
+
+/// Which hand holds an XR controller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum XrHand {
+    Left,
+    Right,
+}
+
+impl XrHand {
+    pub const ALL: [XrHand; 2] = [XrHand::Left, XrHand::Right];
+
+    pub(crate) fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// One XR controller as the runtime last reported it (see `XrActions::sync`).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct XrController {
+    /// The runtime tracks a controller in this hand; everything else reads as idle otherwise.
+    pub connected: bool,
+    /// `0..=1`.
+    pub trigger: f32,
+    /// Squeeze, `0..=1`.
+    pub grip: f32,
+    /// `-1..=1` on both axes, `+y` pushed away from the user.
+    pub thumbstick: Vec2,
+    pub thumbstick_click: bool,
+    /// A on the right controller, X on the left.
+    pub primary: bool,
+    /// B on the right controller, Y on the left.
+    pub secondary: bool,
+    /// Grip pose in stage space, while tracked.
+    pub pose: Option<XrPose>,
+}
+
 /// Snapshot of user input.
 ///
 /// This is intentionally minimal for now, but it already supports:
@@ -15,6 +56,8 @@ use winit::keyboard::Key;
 /// - per-frame transitions (`pressed`/`released`)
 /// - cursor position and wheel delta
 /// - mouse movement delta
+/// - committed text (typed characters / IME commits) for whoever holds text focus
+/// - XR controllers (buttons, analog values and poses)
 #[derive(Default, Debug, Clone)]
 pub struct InputState {
     pub keys_down: HashSet<Key>,
@@ -34,19 +77,31 @@ pub struct InputState {
     /// Mouse movement delta since last frame (current - previous).
     mouse_movement: (f32, f32),
 
-    /// Accumulated wheel delta since last `begin_frame`.
+    /// Accumulated wheel delta since last `end_frame`.
     pub wheel_delta: (f32, f32),
+
+    /// Text committed since last `end_frame` (typed characters and IME commits, no control
+    /// characters). Read through `text_for` so only the focused owner consumes it.
+    text: String,
+
+    /// In-progress IME composition (text, cursor byte range) to draw inline; not committed yet.
+    pub ime_preedit: Option<(String, Option<(usize, usize)>)>,
+
+    /// Owner of keyboard text input (e.g. `"console"`), if any.
+    text_focus: Option<String>,
+
+    /// XR controllers by `XrHand::index`, and as they were at the last `end_frame` (for
+    /// pressed transitions).
+    xr: [XrController; 2],
+    xr_prev: [XrController; 2],
 }
 
 impl InputState {
-    /// Clears per-frame transition state.
+    /// Starts a frame: computes the mouse movement delta.
+    ///
+    /// Transitions (`pressed`/`released`), wheel and text collected since the last
+    /// `end_frame` stay visible until this frame's `end_frame`.
     pub fn begin_frame(&mut self) {
-        self.keys_pressed.clear();
-        self.keys_released.clear();
-        self.mouse_pressed.clear();
-        self.mouse_released.clear();
-        self.wheel_delta = (0.0, 0.0);
-
         // Update mouse movement delta
         self.mouse_movement = match (self.cursor_pos, self.prev_cursor_pos) {
             (Some((cx, cy)), Some((px, py))) => (cx - px, cy - py),
@@ -55,6 +110,26 @@ impl InputState {
         self.prev_cursor_pos = self.cursor_pos;
     }
 
+    /// Clears per-frame transition state once the frame has consumed it.
+    pub fn end_frame(&mut self) {
+        self.keys_pressed.clear();
+        self.keys_released.clear();
+        self.mouse_pressed.clear();
+        self.mouse_released.clear();
+        self.wheel_delta = (0.0, 0.0);
+        self.text.clear();
+        self.xr_prev = self.xr;
+    }
+
+    /// Release every held key and mouse button (reported as `released` this frame).
+    ///
+    /// Used when the window loses focus: the matching release events go to whichever window
+    /// has focus instead, so without this keys would stay "down".
+    pub fn release_all(&mut self) {
+        self.keys_released.extend(self.keys_down.drain());
+        self.mouse_released.extend(self.mouse_down.drain());
+    }
+
     #[inline]
     pub fn key_down(&self, key: &Key) -> bool {
         self.keys_down.contains(key)
@@ -70,18 +145,81 @@ impl InputState {
         self.keys_released.contains(key)
     }
 
+    pub fn xr_controller(&self, hand: XrHand) -> &XrController {
+        &self.xr[hand.index()]
+    }
+
+    /// The controller as of the previous frame.
+    pub(crate) fn xr_controller_before(&self, hand: XrHand) -> &XrController {
+        &self.xr_prev[hand.index()]
+    }
+
+    /// Replace a controller's state (the XR runtime, or tests standing in for it).
+    pub fn set_xr_controller(&mut self, hand: XrHand, controller: XrController) {
+        self.xr[hand.index()] = controller;
+    }
+
     /// Returns the mouse movement delta (dx, dy) since the last frame.
     /// Returns (0, 0) if cursor position is not available.
     #[inline]
     pub fn mouse_movement(&self) -> (f32, f32) {
         self.mouse_movement
     }
+
+    /// Give keyboard text input to `owner`. While focused, character-key actions are
+    /// suppressed (see `ActionMap`) so typing doesn't also move the player.
+    pub fn set_text_focus(&mut self, owner: impl Into<String>) {
+        self.text_focus = Some(owner.into());
+    }
+
+    /// Release text focus if `owner` holds it.
+    pub fn release_text_focus(&mut self, owner: &str) {
+        if self.has_text_focus(owner) {
+            self.text_focus = None;
+            self.ime_preedit = None;
+        }
+    }
+
+    pub fn text_focus(&self) -> Option<&str> {
+        self.text_focus.as_deref()
+    }
+
+    pub fn has_text_focus(&self, owner: &str) -> bool {
+        self.text_focus.as_deref() == Some(owner)
+    }
+
+    /// Text committed this frame, if `owner` holds text focus (empty otherwise).
+    pub fn text_for(&self, owner: &str) -> &str {
+        if self.has_text_focus(owner) {
+            &self.text
+        } else {
+            ""
+        }
+    }
+
+    /// Text committed this frame, whoever holds text focus.
+    pub(crate) fn committed_text(&self) -> &str {
+        &self.text
+    }
+
+    pub(crate) fn push_text(&mut self, text: &str) {
+        self.text.extend(text.chars().filter(|c| !c.is_control()));
+    }
 }
 
 /// Stateful input event processor.
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug)]
 pub struct UserInput {
     state: InputState,
+
+    /// True between `Ime::Enabled` and `Ime::Disabled`; text then arrives as `Ime::Commit`
+    /// instead of through key events.
+    ime_enabled: bool,
+
+    /// Writes every frame's input to a file (`record`).
+    recorder: Option<InputRecorder>,
+    /// Replaces window input with a recording (`playback`).
+    playback: Option<InputPlayback>,
 }
 
 impl UserInput {
@@ -98,18 +236,59 @@ impl UserInput {
     }
 
     pub fn begin_frame(&mut self) {
+        if let Some(playback) = &mut self.playback {
+            playback.advance(&mut self.state);
+        }
         self.state.begin_frame();
     }
 
+    pub fn end_frame(&mut self) {
+        if let Some(recorder) = &mut self.recorder
+            && let Err(e) = recorder.capture(&self.state)
+        {
+            crate::log_error!("input recording stopped: {e}");
+            self.recorder = None;
+        }
+        self.state.end_frame();
+    }
+
+    /// Record input from the next frame on to `path` (see `input_recording`), until
+    /// `stop_recording` or drop.
+    pub fn record(&mut self, path: impl AsRef<Path>) -> Result<(), AssetError> {
+        self.recorder = Some(InputRecorder::create(path)?);
+        Ok(())
+    }
+
+    pub fn stop_recording(&mut self) {
+        self.recorder = None;
+    }
+
+    /// Replay a recording from the next frame on. Window input is ignored meanwhile.
+    pub fn playback(&mut self, path: impl AsRef<Path>) -> Result<(), AssetError> {
+        self.playback = Some(InputPlayback::load(path)?);
+        Ok(())
+    }
+
+    /// A playback was started and has played every recorded frame.
+    pub fn playback_finished(&self) -> bool {
+        self.playback.as_ref().is_some_and(InputPlayback::finished)
+    }
+
     /// Feed a winit event into this input handler.
     ///
-    /// Returns `true` if the event was recognized/consumed as input.
+    /// Returns `true` if the event was recognized/consumed as input. Ignored during playback.
     pub fn handle_window_event(&mut self, event: &WindowEvent) -> bool {
+        if self.playback.is_some() {
+            return false;
+        }
         match event {
             WindowEvent::KeyboardInput { event, .. } => {
                 let key = event.logical_key.clone();
                 match event.state {
                     ElementState::Pressed => {
+                        if let Some(text) = event.text.as_ref().filter(|_| !self.ime_enabled) {
+                            self.state.push_text(text);
+                        }
                         let was_down = self.state.keys_down.contains(&key);
                         self.state.keys_down.insert(key.clone());
                         if !was_down {
@@ -156,6 +335,25 @@ impl UserInput {
                 true
             }
 
+            WindowEvent::Ime(ime) => {
+                match ime {
+                    Ime::Enabled => self.ime_enabled = true,
+                    Ime::Disabled => {
+                        self.ime_enabled = false;
+                        self.state.ime_preedit = None;
+                    }
+                    Ime::Preedit(text, cursor) => {
+                        self.state.ime_preedit =
+                            (!text.is_empty()).then(|| (text.clone(), *cursor));
+                    }
+                    Ime::Commit(text) => {
+                        self.state.ime_preedit = None;
+                        self.state.push_text(text);
+                    }
+                }
+                true
+            }
+
             _ => false,
         }
     }
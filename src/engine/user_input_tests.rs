@@ -0,0 +1,27 @@
+#[cfg(test)]
+mod tests {
+    use winit::event::MouseButton;
+    use winit::keyboard::{Key, NamedKey};
+
+    use crate::engine::Universe;
+    use crate::engine::ecs::World;
+    use crate::engine::user_input::InputState;
+
+    #[test]
+    fn focus_loss_releases_held_input() {
+        let mut universe = Universe::new(World::default());
+        let mut input = InputState::default();
+        let key = Key::Named(NamedKey::ArrowUp);
+        input.keys_down.insert(key.clone());
+        input.mouse_down.insert(MouseButton::Left);
+
+        universe.on_focus_changed(false, &mut input);
+        assert!(!input.key_down(&key));
+        assert!(input.key_released(&key));
+        assert!(input.mouse_released.contains(&MouseButton::Left));
+        assert!(!universe.pacing.focused());
+
+        universe.on_focus_changed(true, &mut input);
+        assert!(universe.pacing.focused());
+    }
+}
@@ -1,8 +1,9 @@
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
+use crate::engine::EngineResult;
+use crate::engine::frame_pacing::PacingDecision;
 use crate::engine::user_input::UserInput;
-use crate::engine::{EngineError, EngineResult};
 
 use winit::application::ApplicationHandler;
 use winit::event::{ElementState, KeyEvent, WindowEvent};
@@ -10,24 +11,27 @@ use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop};
 use winit::keyboard::{Key, NamedKey};
 use winit::window::{Window, WindowAttributes, WindowId};
 
+/// Update interval while the universe is suspended (minimized, hidden or backgrounded).
+const SUSPENDED_TICK: Duration = Duration::from_millis(100);
+
 /// Minimal winit wrapper (2025 winit style: ApplicationHandler).
 pub struct Windowing;
 
 impl Windowing {
     pub fn run_app(universe: crate::engine::Universe, user_input: UserInput) -> EngineResult<()> {
-        let event_loop = EventLoop::new().map_err(|_| EngineError::NotImplemented)?;
-        event_loop.set_control_flow(ControlFlow::Poll);
+        let event_loop = EventLoop::new()?;
+        // Redraws are requested by `about_to_wait` as `FramePacing` allows.
+        event_loop.set_control_flow(ControlFlow::Wait);
 
         let mut app = App {
             window: None,
             universe: Some(universe),
             last_frame: None,
             user_input,
+            ime_allowed: false,
         };
 
-        event_loop
-            .run_app(&mut app)
-            .map_err(|_| EngineError::NotImplemented)?;
+        event_loop.run_app(&mut app)?;
 
         Ok(())
     }
@@ -38,11 +42,55 @@ struct App {
     universe: Option<crate::engine::Universe>,
     last_frame: Option<Instant>,
     user_input: UserInput,
+    /// Last value passed to `Window::set_ime_allowed` (follows text focus).
+    ime_allowed: bool,
+}
+
+impl App {
+    /// One frame: update, then render unless suspended.
+    fn frame(&mut self) {
+        // Start of our "frame" from an input perspective. Edge-triggered sets collected
+        // since the last frame stay visible until `end_frame` below.
+        self.user_input.begin_frame();
+
+        let now = Instant::now();
+        let dt = self
+            .last_frame
+            .replace(now)
+            .map(|prev| (now - prev).as_secs_f32())
+            .unwrap_or(0.0);
+
+        let universe = self.universe.as_mut().expect("universe missing");
+        universe.pacing.frame_started(now);
+
+        universe.update(dt, self.user_input.state_mut());
+
+        universe.render();
+
+        self.user_input.end_frame();
+    }
+
+    fn set_suspended(&mut self, suspended: bool) {
+        let Some(universe) = self.universe.as_mut() else {
+            return;
+        };
+        if suspended {
+            universe.on_suspend();
+        } else if universe.is_suspended() {
+            universe.on_resume();
+            // Don't report the time spent suspended as one huge frame.
+            self.last_frame = Some(Instant::now());
+            if let Some(w) = &self.window {
+                w.request_redraw();
+            }
+        }
+    }
 }
 
 impl ApplicationHandler for App {
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
         if self.window.is_some() {
+            self.set_suspended(false);
             return;
         }
 
@@ -71,6 +119,37 @@ impl ApplicationHandler for App {
         }
     }
 
+    fn suspended(&mut self, _event_loop: &ActiveEventLoop) {
+        self.set_suspended(true);
+    }
+
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        let Some(universe) = self.universe.as_ref() else {
+            return;
+        };
+        if !universe.is_suspended() {
+            match universe.pacing.next_frame(Instant::now()) {
+                PacingDecision::Redraw => {
+                    if let Some(w) = &self.window {
+                        w.request_redraw();
+                    }
+                    event_loop.set_control_flow(ControlFlow::Wait);
+                }
+                PacingDecision::WaitUntil(due) => {
+                    event_loop.set_control_flow(ControlFlow::WaitUntil(due));
+                }
+            }
+            return;
+        }
+        // No redraws arrive while minimized; keep the simulation going at a trickle instead.
+        if universe.updates_while_suspended() {
+            self.frame();
+            event_loop.set_control_flow(ControlFlow::WaitUntil(Instant::now() + SUSPENDED_TICK));
+        } else {
+            event_loop.set_control_flow(ControlFlow::Wait);
+        }
+    }
+
     fn window_event(&mut self, event_loop: &ActiveEventLoop, _id: WindowId, event: WindowEvent) {
         // Feed input events into our input handler, but keep window lifecycle/render events here.
         // This intentionally ignores resize/draw.
@@ -87,49 +166,59 @@ impl ApplicationHandler for App {
                         ..
                     },
                 ..
-            } => event_loop.exit(),
+            } if self.user_input.state().text_focus().is_none() => event_loop.exit(),
 
             WindowEvent::Resized(size) => {
-                println!("[Windowing] Resized event received: {:?}", size);
+                crate::log_debug!("resized: {:?}", size);
                 if let Some(w) = &self.window {
                     let actual_size = w.inner_size();
-                    println!("[Windowing] Window's actual inner_size: {:?}", actual_size);
+                    crate::log_debug!("window inner_size: {:?}", actual_size);
                     // Ensure window is still resizable (in case something changed it)
                     if !w.is_resizable() {
-                        println!("[Windowing] WARNING: Window is not resizable!");
+                        crate::log_warn!("window is not resizable");
                     }
                 }
+                // Minimized windows report a zero size.
+                self.set_suspended(size.width == 0 || size.height == 0);
                 if let Some(universe) = self.universe.as_mut() {
                     universe.resize_renderer(size);
                 }
                 if let Some(w) = &self.window {
-                    println!("[Windowing] resized; requesting redraw");
+                    crate::log_debug!("resized; requesting redraw");
                     // w.pre_present_notify();
                     w.request_redraw();
                 }
             }
 
-            WindowEvent::RedrawRequested => {
-                // Start of our "frame" from an input perspective: clear edge-triggered sets.
-                self.user_input.begin_frame();
-
-                let now = Instant::now();
-                let dt = self
-                    .last_frame
-                    .replace(now)
-                    .map(|prev| (now - prev).as_secs_f32())
-                    .unwrap_or(0.0);
-
-                let universe = self.universe.as_mut().expect("universe missing");
+            WindowEvent::Occluded(occluded) => self.set_suspended(occluded),
 
-                universe.update(dt, self.user_input.state());
+            WindowEvent::Focused(focused) => {
+                if let Some(universe) = self.universe.as_mut() {
+                    universe.on_focus_changed(focused, self.user_input.state_mut());
+                }
+            }
 
-                universe.render();
+            WindowEvent::RedrawRequested => {
+                // While suspended, `about_to_wait` drives updates.
+                if self.universe.as_ref().is_some_and(|u| u.is_suspended()) {
+                    return;
+                }
+                self.frame();
+                if self.user_input.playback_finished() {
+                    crate::log_info!("input playback finished");
+                    event_loop.exit();
+                    return;
+                }
 
-                if let Some(w) = &self.window {
-                    // w.pre_present_notify();
-                    w.request_redraw();
+                // Only ask the platform for IME composition while something wants text.
+                let wants_text = self.user_input.state().text_focus().is_some();
+                if wants_text != self.ime_allowed {
+                    if let Some(w) = &self.window {
+                        w.set_ime_allowed(wants_text);
+                    }
+                    self.ime_allowed = wants_text;
                 }
+                // The next redraw is requested by `about_to_wait` when pacing allows.
             }
 
             _ => {}
@@ -1,5 +1,37 @@
+//! OpenXR: session handling (placeholder) and controller input.
+//!
+//! `XrActions` is the engine's one action set. Each frame `sync` writes both controllers into
+//! `InputState`, where `ActionMap` bindings such as `XrLeftTrigger` or `XrRightThumbstickUp`
+//! read them like keys, so the same gameplay code serves desktop and XR.
+
+use glam::{Quat, Vec3};
+use openxr as xr;
+
+use crate::engine::user_input::{InputState, XrController, XrHand};
 use crate::engine::{EngineError, EngineResult};
 
+const HAND_PATHS: [&str; 2] = ["/user/hand/left", "/user/hand/right"];
+const TOUCH_PROFILE: &str = "/interaction_profiles/oculus/touch_controller";
+const SIMPLE_PROFILE: &str = "/interaction_profiles/khr/simple_controller";
+
+/// A tracked pose in stage space (meters, +Y up).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct XrPose {
+    pub position: Vec3,
+    pub orientation: Quat,
+}
+
+impl XrPose {
+    fn from_xr(pose: xr::Posef) -> Self {
+        let p = pose.position;
+        let o = pose.orientation;
+        Self {
+            position: Vec3::new(p.x, p.y, p.z),
+            orientation: Quat::from_xyzw(o.x, o.y, o.z, o.w),
+        }
+    }
+}
+
 /// OpenXR session handling placeholder.
 pub struct Xr;
 
@@ -9,7 +41,153 @@ impl Xr {
         Ok(Self)
     }
 
+    /// Once a session exists it gets an `XrActions` (`new`, then `attach`), synced every frame
+    /// before `Universe::update`.
     pub fn begin_session(&mut self) -> EngineResult<()> {
         Err(EngineError::NotImplemented)
     }
 }
+
+/// Controller actions for both hands, with suggested bindings for Touch-style controllers
+/// and the KHR simple controller (trigger from `select`, primary from `menu`).
+pub struct XrActions {
+    set: xr::ActionSet,
+    hands: [xr::Path; 2],
+    trigger: xr::Action<f32>,
+    grip: xr::Action<f32>,
+    thumbstick: xr::Action<xr::Vector2f>,
+    thumbstick_click: xr::Action<bool>,
+    primary: xr::Action<bool>,
+    secondary: xr::Action<bool>,
+    grip_pose: xr::Action<xr::Posef>,
+    /// Grip pose space per hand, once attached to a session.
+    spaces: Vec<xr::Space>,
+}
+
+impl XrActions {
+    pub fn new(instance: &xr::Instance) -> EngineResult<Self> {
+        let hands = [
+            instance.string_to_path(HAND_PATHS[0])?,
+            instance.string_to_path(HAND_PATHS[1])?,
+        ];
+        let set = instance.create_action_set("gameplay", "Gameplay", 0)?;
+        let actions = Self {
+            trigger: set.create_action("trigger", "Trigger", &hands)?,
+            grip: set.create_action("grip", "Grip", &hands)?,
+            thumbstick: set.create_action("thumbstick", "Thumbstick", &hands)?,
+            thumbstick_click: set.create_action("thumbstick_click", "Thumbstick click", &hands)?,
+            primary: set.create_action("primary", "Primary button", &hands)?,
+            secondary: set.create_action("secondary", "Secondary button", &hands)?,
+            grip_pose: set.create_action("grip_pose", "Grip pose", &hands)?,
+            set,
+            hands,
+            spaces: Vec::new(),
+        };
+
+        let mut touch = Vec::new();
+        let mut simple = Vec::new();
+        for (hand, primary, secondary) in [(HAND_PATHS[0], "x", "y"), (HAND_PATHS[1], "a", "b")] {
+            let input = |name: &str| format!("{hand}/input/{name}");
+            touch.extend([
+                binding(instance, &actions.trigger, &input("trigger/value"))?,
+                binding(instance, &actions.grip, &input("squeeze/value"))?,
+                binding(instance, &actions.thumbstick, &input("thumbstick"))?,
+                binding(
+                    instance,
+                    &actions.thumbstick_click,
+                    &input("thumbstick/click"),
+                )?,
+                binding(
+                    instance,
+                    &actions.primary,
+                    &input(&format!("{primary}/click")),
+                )?,
+                binding(
+                    instance,
+                    &actions.secondary,
+                    &input(&format!("{secondary}/click")),
+                )?,
+                binding(instance, &actions.grip_pose, &input("grip/pose"))?,
+            ]);
+            simple.extend([
+                binding(instance, &actions.trigger, &input("select/click"))?,
+                binding(instance, &actions.primary, &input("menu/click"))?,
+                binding(instance, &actions.grip_pose, &input("grip/pose"))?,
+            ]);
+        }
+        instance.suggest_interaction_profile_bindings(
+            instance.string_to_path(TOUCH_PROFILE)?,
+            &touch,
+        )?;
+        instance.suggest_interaction_profile_bindings(
+            instance.string_to_path(SIMPLE_PROFILE)?,
+            &simple,
+        )?;
+        Ok(actions)
+    }
+
+    /// Attach to `session`; once, before its first `sync`.
+    pub fn attach<G>(&mut self, session: &xr::Session<G>) -> EngineResult<()> {
+        session.attach_action_sets(&[&self.set])?;
+        self.spaces = self
+            .hands
+            .iter()
+            .map(|&hand| {
+                self.grip_pose
+                    .create_space(session.clone(), hand, xr::Posef::IDENTITY)
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(())
+    }
+
+    /// Read both controllers into `input`, with poses located in `stage` at `time` (the
+    /// frame's predicted display time).
+    pub fn sync<G>(
+        &self,
+        session: &xr::Session<G>,
+        stage: &xr::Space,
+        time: xr::Time,
+        input: &mut InputState,
+    ) -> EngineResult<()> {
+        session.sync_actions(&[xr::ActiveActionSet::new(&self.set)])?;
+        for (i, hand) in XrHand::ALL.into_iter().enumerate() {
+            let path = self.hands[i];
+            let trigger = self.trigger.state(session, path)?;
+            let stick = self.thumbstick.state(session, path)?.current_state;
+            let pose = match self.spaces.get(i) {
+                Some(space) => {
+                    let location = space.locate(stage, time)?;
+                    let tracked = xr::SpaceLocationFlags::POSITION_VALID
+                        | xr::SpaceLocationFlags::ORIENTATION_VALID;
+                    location
+                        .location_flags
+                        .contains(tracked)
+                        .then(|| XrPose::from_xr(location.pose))
+                }
+                None => None,
+            };
+            input.set_xr_controller(
+                hand,
+                XrController {
+                    connected: trigger.is_active || self.grip_pose.is_active(session, path)?,
+                    trigger: trigger.current_state,
+                    grip: self.grip.state(session, path)?.current_state,
+                    thumbstick: glam::Vec2::new(stick.x, stick.y),
+                    thumbstick_click: self.thumbstick_click.state(session, path)?.current_state,
+                    primary: self.primary.state(session, path)?.current_state,
+                    secondary: self.secondary.state(session, path)?.current_state,
+                    pose,
+                },
+            );
+        }
+        Ok(())
+    }
+}
+
+fn binding<'a, T: xr::ActionTy>(
+    instance: &xr::Instance,
+    action: &'a xr::Action<T>,
+    path: &str,
+) -> EngineResult<xr::Binding<'a>> {
+    Ok(xr::Binding::new(action, instance.string_to_path(path)?))
+}
@@ -1,2 +1,11 @@
+//! little-cat: a small component-graph game engine on Vulkan.
+//!
+//! Start from `engine::Universe` (world, systems, renderer, REPL) and run it with
+//! `engine::Windowing::run_app`, or `Universe::run_server` in `headless-server` builds.
+//! `examples/demo.rs` builds a complete scene.
+
+// Lets `#[derive(CatComponent)]` output (which names `::little_cat::...`) compile in here too.
+extern crate self as little_cat;
+
 pub mod engine;
 pub mod utils;
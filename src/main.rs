@@ -1,12 +1,105 @@
-mod engine;
-mod utils;
+//! `little-cat` player: an empty Universe driven by the REPL and `~/.littlecatrc`.
+//!
+//! The demo scene lives in `examples/demo.rs`.
+//!
+//! Options:
+//! - `--fps-cap <n>` caps the frame rate while the window is focused (0 = uncapped).
+//! - `--record <file>` writes every frame's input to `file`; `--replay <file>` plays such a
+//!   recording instead of reading the keyboard and mouse, then exits (demos, smoke tests).
+//! - `--listen <addr>` serves clients over UDP (e.g. `0.0.0.0:7777`); `--connect <addr>`
+//!   joins such a server. REPL `net status` lists the peers.
+
+use little_cat::engine;
+use little_cat::engine::networking::{Networking, UdpTransport};
+use little_cat::utils::{crash_report, logger};
+
+#[cfg(not(any(feature = "windowed", feature = "headless-server")))]
+compile_error!("enable the `windowed` (default) or `headless-server` feature");
+
+/// Tick rate of the dedicated server build.
+#[cfg(not(feature = "windowed"))]
+const SERVER_TICK_HZ: u32 = 60;
 
 fn main() {
-    utils::logger::init();
+    logger::init();
+    crash_report::install_panic_hook();
+
+    let universe = engine::UniverseBuilder::new()
+        .fps_cap(fps_cap_arg())
+        .networking(networking_arg())
+        .build();
+    run(universe);
+}
+
+/// Value of `--<name> <value>` or `--<name>=<value>`.
+fn arg_value(name: &str) -> Option<String> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let flag = format!("--{name}");
+    args.iter().enumerate().find_map(|(i, arg)| {
+        if *arg == flag {
+            args.get(i + 1).cloned()
+        } else {
+            arg.strip_prefix(&flag)?
+                .strip_prefix('=')
+                .map(str::to_string)
+        }
+    })
+}
+
+/// `--fps-cap <n>` or `--fps-cap=<n>`.
+fn fps_cap_arg() -> Option<u32> {
+    let value = arg_value("fps-cap")?;
+    match value.parse() {
+        Ok(fps) => Some(fps),
+        Err(_) => {
+            little_cat::log_warn!("--fps-cap: expected a number, got '{value}'");
+            None
+        }
+    }
+}
 
-    let world = engine::ecs::World::default();
-    let universe = engine::Universe::new(world);
-    let user_input = engine::user_input::UserInput::new();
+/// `--listen <addr>` or `--connect <addr>`; offline without either (or if binding fails).
+fn networking_arg() -> Networking {
+    let result = if let Some(addr) = arg_value("listen") {
+        UdpTransport::bind(addr.as_str()).map(Networking::server)
+    } else if let Some(addr) = arg_value("connect") {
+        match addr.parse() {
+            Ok(server) => UdpTransport::bind("0.0.0.0:0").map(|t| Networking::client(t, server)),
+            Err(_) => {
+                little_cat::log_warn!("--connect: expected <ip>:<port>, got '{addr}'");
+                return Networking::new();
+            }
+        }
+    } else {
+        return Networking::new();
+    };
+    result.unwrap_or_else(|e| {
+        little_cat::log_error!("networking: {e}");
+        Networking::new()
+    })
+}
 
+#[cfg(feature = "windowed")]
+fn run(universe: engine::Universe) {
+    let mut user_input = engine::user_input::UserInput::new();
+    if let Some(path) = arg_value("record")
+        && let Err(e) = user_input.record(&path)
+    {
+        little_cat::log_error!("--record: {e}");
+    }
+    if let Some(path) = arg_value("replay")
+        && let Err(e) = user_input.playback(&path)
+    {
+        little_cat::log_error!("--replay: {e}");
+        std::process::exit(1);
+    }
     engine::Windowing::run_app(universe, user_input).expect("Windowing failed");
 }
+
+#[cfg(not(feature = "windowed"))]
+fn run(mut universe: engine::Universe) {
+    if arg_value("record").or(arg_value("replay")).is_some() {
+        little_cat::log_warn!("--record and --replay need the windowed build");
+    }
+    universe.run_server(SERVER_TICK_HZ).expect("server failed");
+}
@@ -0,0 +1,119 @@
+//! System clipboard access for the REPL (`... | copy`) and the in-game console (Ctrl+V).
+//!
+//! Talks to the platform's clipboard tools instead of linking a clipboard library:
+//! `pbcopy`/`pbpaste` on macOS, `clip`/PowerShell on Windows, and `wl-copy`/`wl-paste`,
+//! `xclip` or `xsel` elsewhere (the first one that works; a tool that is installed but fails,
+//! like `wl-copy` outside a Wayland session, falls through to the next). Copied text is also
+//! kept in-process, so copy/paste inside the engine works even with no clipboard tool (e.g.
+//! over SSH).
+//!
+//! A clipboard crate such as `arboard` was left out on purpose: on Linux it links an X11
+//! client (and optionally Wayland) into every build, the headless server included, while the
+//! REPL only needs the odd copy and paste that these tools already provide.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClipboardError {
+    #[error("no clipboard tool found (tried {0})")]
+    Unavailable(String),
+
+    #[error("{tool}: {message}")]
+    Tool { tool: &'static str, message: String },
+}
+
+/// Last text passed to `copy`.
+static LOCAL: Mutex<String> = Mutex::new(String::new());
+
+#[cfg(target_os = "macos")]
+const COPY_TOOLS: &[(&str, &[&str])] = &[("pbcopy", &[])];
+#[cfg(target_os = "macos")]
+const PASTE_TOOLS: &[(&str, &[&str])] = &[("pbpaste", &[])];
+
+#[cfg(windows)]
+const COPY_TOOLS: &[(&str, &[&str])] = &[("clip", &[])];
+#[cfg(windows)]
+const PASTE_TOOLS: &[(&str, &[&str])] =
+    &[("powershell", &["-NoProfile", "-Command", "Get-Clipboard"])];
+
+#[cfg(not(any(target_os = "macos", windows)))]
+const COPY_TOOLS: &[(&str, &[&str])] = &[
+    ("wl-copy", &[]),
+    ("xclip", &["-selection", "clipboard"]),
+    ("xsel", &["--clipboard", "--input"]),
+];
+#[cfg(not(any(target_os = "macos", windows)))]
+const PASTE_TOOLS: &[(&str, &[&str])] = &[
+    ("wl-paste", &["--no-newline"]),
+    ("xclip", &["-selection", "clipboard", "-o"]),
+    ("xsel", &["--clipboard", "--output"]),
+];
+
+/// Put `text` on the system clipboard. The in-process copy is updated even when this fails.
+pub fn copy(text: &str) -> Result<(), ClipboardError> {
+    *LOCAL.lock().unwrap_or_else(|e| e.into_inner()) = text.to_string();
+    let mut failed = None;
+    for &(tool, args) in COPY_TOOLS {
+        let Ok(mut child) = Command::new(tool)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            continue;
+        };
+        let written = child
+            .stdin
+            .take()
+            .map(|mut stdin| stdin.write_all(text.as_bytes()));
+        let status = child.wait();
+        let message = match (written, status) {
+            (Some(Ok(())), Ok(s)) if s.success() => return Ok(()),
+            (Some(Err(e)), _) => e.to_string(),
+            (_, Ok(s)) => s.to_string(),
+            (_, Err(e)) => e.to_string(),
+        };
+        failed = Some(tool_error(tool, message));
+    }
+    Err(failed.unwrap_or_else(|| unavailable(COPY_TOOLS)))
+}
+
+/// Text on the system clipboard, or the last `copy` if no clipboard tool works.
+pub fn paste() -> Result<String, ClipboardError> {
+    let mut failed = None;
+    for &(tool, args) in PASTE_TOOLS {
+        let Ok(output) = Command::new(tool)
+            .args(args)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+        else {
+            continue;
+        };
+        if !output.status.success() {
+            failed = Some(tool_error(tool, output.status.to_string()));
+            continue;
+        }
+        match String::from_utf8(output.stdout) {
+            Ok(text) => return Ok(text),
+            Err(e) => failed = Some(tool_error(tool, e.to_string())),
+        }
+    }
+    let local = LOCAL.lock().unwrap_or_else(|e| e.into_inner());
+    if local.is_empty() {
+        return Err(failed.unwrap_or_else(|| unavailable(PASTE_TOOLS)));
+    }
+    Ok(local.clone())
+}
+
+fn tool_error(tool: &'static str, message: String) -> ClipboardError {
+    ClipboardError::Tool { tool, message }
+}
+
+fn unavailable(tools: &[(&str, &[&str])]) -> ClipboardError {
+    let names: Vec<&str> = tools.iter().map(|(tool, _)| *tool).collect();
+    ClipboardError::Unavailable(names.join(", "))
+}
@@ -0,0 +1,324 @@
+//! Colors and palettes.
+//!
+//! `Color` is linear RGBA, what shaders blend in and what scene files store (`[r, g, b, a]`).
+//! Hex codes and HSV are sRGB, as color pickers show them, and convert on the way in and out:
+//!
+//! ```ignore
+//! let accent: Color = "#ffcc00".parse()?;
+//! let hover = accent.lerp(Color::WHITE, 0.25);
+//! let team = Palette::debug().get(player_index);
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Linear RGBA, nominally in 0..1 (HDR colors may exceed 1).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+impl Color {
+    pub const WHITE: Color = Color::rgb(1.0, 1.0, 1.0);
+    pub const BLACK: Color = Color::rgb(0.0, 0.0, 0.0);
+    pub const TRANSPARENT: Color = Color::rgba(0.0, 0.0, 0.0, 0.0);
+    pub const RED: Color = Color::rgb(1.0, 0.0, 0.0);
+    pub const GREEN: Color = Color::rgb(0.0, 1.0, 0.0);
+    pub const BLUE: Color = Color::rgb(0.0, 0.0, 1.0);
+    pub const YELLOW: Color = Color::rgb(1.0, 1.0, 0.0);
+    pub const CYAN: Color = Color::rgb(0.0, 1.0, 1.0);
+    pub const MAGENTA: Color = Color::rgb(1.0, 0.0, 1.0);
+
+    /// Names `named` and `FromStr` accept.
+    pub const NAMES: &[(&'static str, Color)] = &[
+        ("white", Color::WHITE),
+        ("black", Color::BLACK),
+        ("transparent", Color::TRANSPARENT),
+        ("red", Color::RED),
+        ("green", Color::GREEN),
+        ("blue", Color::BLUE),
+        ("yellow", Color::YELLOW),
+        ("cyan", Color::CYAN),
+        ("magenta", Color::MAGENTA),
+    ];
+
+    /// Linear components.
+    pub const fn rgba(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// Linear components, opaque.
+    pub const fn rgb(r: f32, g: f32, b: f32) -> Self {
+        Self::rgba(r, g, b, 1.0)
+    }
+
+    pub const fn from_array([r, g, b, a]: [f32; 4]) -> Self {
+        Self::rgba(r, g, b, a)
+    }
+
+    pub const fn to_array(self) -> [f32; 4] {
+        [self.r, self.g, self.b, self.a]
+    }
+
+    /// sRGB-encoded components (alpha is linear either way).
+    pub fn from_srgb(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self::rgba(srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b), a)
+    }
+
+    pub fn from_srgb8(r: u8, g: u8, b: u8, a: u8) -> Self {
+        let f = |c: u8| f32::from(c) / 255.0;
+        Self::from_srgb(f(r), f(g), f(b), f(a))
+    }
+
+    pub fn to_srgb(self) -> [f32; 4] {
+        [
+            linear_to_srgb(self.r),
+            linear_to_srgb(self.g),
+            linear_to_srgb(self.b),
+            self.a,
+        ]
+    }
+
+    pub fn to_srgb8(self) -> [u8; 4] {
+        self.to_srgb()
+            .map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8)
+    }
+
+    /// `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa` in sRGB; the `#` is optional.
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if !hex.is_ascii() {
+            return None;
+        }
+        let digits: Vec<u8> = match hex.len() {
+            3 | 4 => hex
+                .chars()
+                .map(|c| u8::from_str_radix(&c.to_string(), 16).map(|d| d * 17))
+                .collect::<Result<_, _>>()
+                .ok()?,
+            6 | 8 => (0..hex.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+                .collect::<Result<_, _>>()
+                .ok()?,
+            _ => return None,
+        };
+        Some(Self::from_srgb8(
+            digits[0],
+            digits[1],
+            digits[2],
+            digits.get(3).copied().unwrap_or(255),
+        ))
+    }
+
+    /// `#rrggbb`, or `#rrggbbaa` when not opaque.
+    pub fn to_hex(self) -> String {
+        let [r, g, b, a] = self.to_srgb8();
+        if a == 255 {
+            format!("#{r:02x}{g:02x}{b:02x}")
+        } else {
+            format!("#{r:02x}{g:02x}{b:02x}{a:02x}")
+        }
+    }
+
+    /// Hue in degrees, saturation and value in 0..1, on sRGB-encoded components; opaque.
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> Self {
+        let h = hue.rem_euclid(360.0) / 60.0;
+        let c = value * saturation;
+        let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+        let (r, g, b) = match h as u32 {
+            0 => (c, x, 0.0),
+            1 => (x, c, 0.0),
+            2 => (0.0, c, x),
+            3 => (0.0, x, c),
+            4 => (x, 0.0, c),
+            _ => (c, 0.0, x),
+        };
+        let m = value - c;
+        Self::from_srgb(r + m, g + m, b + m, 1.0)
+    }
+
+    /// `[hue in degrees, saturation, value]` of the sRGB-encoded color.
+    pub fn to_hsv(self) -> [f32; 3] {
+        let [r, g, b, _] = self.to_srgb();
+        let max = r.max(g).max(b);
+        let delta = max - r.min(g).min(b);
+        let hue = if delta <= 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+        let saturation = if max > 0.0 { delta / max } else { 0.0 };
+        [hue, saturation, max]
+    }
+
+    pub fn named(name: &str) -> Option<Self> {
+        Self::NAMES
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, c)| *c)
+    }
+
+    pub const fn with_alpha(self, a: f32) -> Self {
+        Self { a, ..self }
+    }
+
+    /// Blend toward `other` in linear space (`t` = 0 is `self`, 1 is `other`).
+    pub fn lerp(self, other: Color, t: f32) -> Self {
+        let mix = |a: f32, b: f32| a + (b - a) * t;
+        Self::rgba(
+            mix(self.r, other.r),
+            mix(self.g, other.g),
+            mix(self.b, other.b),
+            mix(self.a, other.a),
+        )
+    }
+}
+
+impl From<[f32; 4]> for Color {
+    fn from(rgba: [f32; 4]) -> Self {
+        Self::from_array(rgba)
+    }
+}
+
+impl From<[f32; 3]> for Color {
+    fn from([r, g, b]: [f32; 3]) -> Self {
+        Self::rgb(r, g, b)
+    }
+}
+
+impl From<Color> for [f32; 4] {
+    fn from(color: Color) -> Self {
+        color.to_array()
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+impl FromStr for Color {
+    type Err = String;
+
+    /// A name (`red`), a hex code (`#ffcc00`, sRGB), or linear `r g b [a]` / `r,g,b[,a]`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(color) = Self::named(s) {
+            return Ok(color);
+        }
+        if s.starts_with('#') {
+            return Self::from_hex(s).ok_or_else(|| format!("'{s}' is not a hex color"));
+        }
+        let parts: Option<Vec<f32>> = s
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|p| !p.is_empty())
+            .map(|p| p.parse::<f32>().ok().filter(|v| v.is_finite()))
+            .collect();
+        match parts.as_deref() {
+            Some(&[r, g, b]) => Ok(Self::rgb(r, g, b)),
+            Some(&[r, g, b, a]) => Ok(Self::rgba(r, g, b, a)),
+            _ => Self::from_hex(s)
+                .ok_or_else(|| format!("'{s}' is not a color (name, #hex or r g b [a])")),
+        }
+    }
+}
+
+/// An ordered list of colors: player or team colors, categories in a debug view, the stops
+/// of a gradient.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Palette {
+    colors: Vec<Color>,
+}
+
+impl Palette {
+    pub fn new(colors: impl IntoIterator<Item = Color>) -> Self {
+        Self {
+            colors: colors.into_iter().collect(),
+        }
+    }
+
+    /// Colors separated by whitespace or commas, in any form `Color` parses except `r g b`.
+    pub fn parse(list: &str) -> Result<Self, String> {
+        list.split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|p| !p.is_empty())
+            .map(str::parse)
+            .collect::<Result<Vec<Color>, _>>()
+            .map(Self::new)
+    }
+
+    /// Eight hues that stay apart from each other on dark and light backgrounds.
+    pub fn debug() -> Self {
+        Self::new(
+            [
+                "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1",
+                "#ff9da7",
+            ]
+            .into_iter()
+            .filter_map(Color::from_hex),
+        )
+    }
+
+    /// The `i`-th color, wrapping around; white for an empty palette.
+    pub fn get(&self, i: usize) -> Color {
+        match self.colors.len() {
+            0 => Color::WHITE,
+            n => self.colors[i % n],
+        }
+    }
+
+    /// The palette as a gradient: `t` = 0 is the first color, 1 the last.
+    pub fn sample(&self, t: f32) -> Color {
+        let Some(last) = self.colors.len().checked_sub(1) else {
+            return Color::WHITE;
+        };
+        let x = t.clamp(0.0, 1.0) * last as f32;
+        let i = (x.floor() as usize).min(last);
+        let next = (i + 1).min(last);
+        self.colors[i].lerp(self.colors[next], x - i as f32)
+    }
+
+    /// Index of the color closest to `color` (linear RGB distance, alpha ignored).
+    pub fn nearest(&self, color: Color) -> Option<usize> {
+        let distance =
+            |c: &Color| (c.r - color.r).powi(2) + (c.g - color.g).powi(2) + (c.b - color.b).powi(2);
+        (0..self.colors.len())
+            .min_by(|&a, &b| distance(&self.colors[a]).total_cmp(&distance(&self.colors[b])))
+    }
+
+    pub fn colors(&self) -> &[Color] {
+        &self.colors
+    }
+
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+}
+
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
@@ -0,0 +1,55 @@
+#[cfg(test)]
+mod tests {
+    use crate::engine::ecs::codec::{FieldCodec, Value};
+    use crate::utils::color::{Color, Palette};
+
+    fn close(a: Color, b: Color) -> bool {
+        a.to_array()
+            .iter()
+            .zip(b.to_array())
+            .all(|(x, y)| (x - y).abs() < 1e-3)
+    }
+
+    #[test]
+    fn hex_and_hsv_are_srgb_and_round_trip() {
+        let amber = Color::from_hex("#ffcc00").unwrap();
+        assert_eq!(amber.to_hex(), "#ffcc00");
+        assert!((amber.g - 0.604).abs() < 1e-3, "stored linear: {amber:?}");
+        assert_eq!(Color::from_hex("f00"), Some(Color::RED));
+        assert_eq!(Color::from_hex("#00000080").unwrap().to_hex(), "#00000080");
+        assert_eq!(Color::from_hex("#ffcc0"), None);
+
+        let [h, s, v] = amber.to_hsv();
+        assert!((h - 48.0).abs() < 0.5 && (s - 1.0).abs() < 1e-3 && (v - 1.0).abs() < 1e-3);
+        assert!(close(Color::from_hsv(h, s, v), amber));
+        assert!(close(Color::from_hsv(240.0, 1.0, 1.0), Color::BLUE));
+
+        assert_eq!("red".parse(), Ok(Color::RED));
+        assert_eq!("0.5 0.5 0.5".parse(), Ok(Color::rgb(0.5, 0.5, 0.5)));
+        assert!("0.5 0.5".parse::<Color>().is_err());
+        assert_eq!(
+            Color::BLACK.lerp(Color::WHITE.with_alpha(0.0), 0.5),
+            Color::rgba(0.5, 0.5, 0.5, 0.5)
+        );
+
+        // Scenes keep the array form; strings decode too.
+        assert_eq!(Color::RED.encode(), serde_json::json!([1.0, 0.0, 0.0, 1.0]));
+        assert_eq!(Color::decode(&Value::from("#ff0000")), Some(Color::RED));
+        assert_eq!(
+            Color::decode(&serde_json::json!([0.0, 1.0, 0.0])),
+            Some(Color::GREEN)
+        );
+    }
+
+    #[test]
+    fn palettes_wrap_blend_and_match() {
+        let palette = Palette::parse("red, #00ff00 blue").unwrap();
+        assert_eq!(palette.len(), 3);
+        assert_eq!(palette.get(4), Color::GREEN);
+        assert_eq!(palette.sample(0.25), Color::rgb(0.5, 0.5, 0.0));
+        assert_eq!(palette.sample(1.0), Color::BLUE);
+        assert_eq!(palette.nearest(Color::rgb(0.1, 0.0, 0.9)), Some(2));
+        assert!(Palette::parse("red nope").is_err());
+        assert_eq!(Palette::debug().len(), 8);
+    }
+}
@@ -0,0 +1,122 @@
+//! Panic hook that writes a crash report before the process goes down.
+//!
+//! A report (`crash-reports/crash-<unix time>.txt`) contains the panic message and location,
+//! a backtrace, the most recent log records and the latest scene snapshot published with
+//! `set_scene_snapshot`. The previous hook still runs afterwards, so the usual panic output
+//! is unchanged.
+//!
+//! The hook can't reach the `Universe` (it may be mid-update on the panicking thread), so
+//! `Universe` publishes the world as scene JSON (what `ComponentCodec::encode_world` saves,
+//! loadable to reproduce the crash) every `SCENE_SNAPSHOT_INTERVAL_FRAMES` instead.
+
+use std::fmt::Write as _;
+use std::panic::PanicHookInfo;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::utils::logger;
+
+pub const CRASH_REPORT_DIR: &str = "crash-reports";
+
+/// How often `Universe` refreshes the scene snapshot.
+pub const SCENE_SNAPSHOT_INTERVAL_FRAMES: u64 = 120;
+
+/// (frame, scene JSON)
+static SCENE_SNAPSHOT: Mutex<Option<(u64, String)>> = Mutex::new(None);
+
+/// Publish the scene JSON included in crash reports.
+pub fn set_scene_snapshot(frame: u64, dump: String) {
+    let mut snapshot = SCENE_SNAPSHOT.lock().unwrap_or_else(|e| e.into_inner());
+    *snapshot = Some((frame, dump));
+}
+
+/// Install the crash-report hook in front of the current panic hook.
+pub fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        match write_report(info) {
+            Ok(path) => eprintln!("[crash_report] wrote {}", path.display()),
+            Err(e) => eprintln!("[crash_report] failed to write report: {e}"),
+        }
+        previous(info);
+    }));
+}
+
+fn write_report(info: &PanicHookInfo<'_>) -> std::io::Result<PathBuf> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let dir = PathBuf::from(CRASH_REPORT_DIR);
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("crash-{}.txt", now.as_secs()));
+
+    let (message, location) = describe_panic(info);
+    let snapshot = SCENE_SNAPSHOT.try_lock().ok().and_then(|s| s.clone());
+    std::fs::write(
+        &path,
+        build_report(&message, &location, now.as_secs(), snapshot),
+    )?;
+    Ok(path)
+}
+
+/// The panic's message and source location.
+fn describe_panic(info: &PanicHookInfo<'_>) -> (String, String) {
+    let message = info
+        .payload()
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "<non-string panic payload>".to_string());
+    let location = info
+        .location()
+        .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()))
+        .unwrap_or_else(|| "<unknown>".to_string());
+    (message, location)
+}
+
+/// The report text; `snapshot` is the (frame, scene JSON) last published, if any.
+pub(crate) fn build_report(
+    message: &str,
+    location: &str,
+    unix_secs: u64,
+    snapshot: Option<(u64, String)>,
+) -> String {
+    let mut out = String::new();
+    let thread = std::thread::current();
+
+    let _ = writeln!(out, "little-cat crash report");
+    let _ = writeln!(out, "version: {}", env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(out, "time: {unix_secs} (unix)");
+    let _ = writeln!(out, "frame: {}", logger::frame());
+    let _ = writeln!(out, "thread: {}", thread.name().unwrap_or("<unnamed>"));
+    let _ = writeln!(out, "panic: {message}");
+    let _ = writeln!(out, "location: {location}");
+
+    let _ = writeln!(out, "\n== backtrace ==");
+    let _ = writeln!(out, "{}", std::backtrace::Backtrace::force_capture());
+
+    let _ = writeln!(out, "== recent log ==");
+    match logger::recent_lines() {
+        Some(lines) => {
+            for line in lines {
+                let _ = writeln!(out, "{line}");
+            }
+        }
+        None => {
+            let _ = writeln!(out, "<logger busy on the panicking thread>");
+        }
+    }
+
+    let _ = writeln!(out, "\n== scene ==");
+    match snapshot {
+        Some((frame, scene)) => {
+            let _ = writeln!(out, "(snapshot from frame {frame})");
+            out.push_str(&scene);
+        }
+        None => {
+            let _ = writeln!(out, "<no snapshot>");
+        }
+    }
+    out
+}
@@ -0,0 +1,33 @@
+#[cfg(test)]
+mod tests {
+    use crate::engine::ecs::World;
+    use crate::engine::ecs::codec::{ComponentCodec, Scene};
+    use crate::engine::ecs::component::{ColorComponent, TransformComponent};
+    use crate::utils::crash_report::build_report;
+
+    #[test]
+    fn report_has_the_panic_and_a_loadable_scene() {
+        let mut w = World::default();
+        let t = w.add_component(TransformComponent::new());
+        let c = w.add_component(ColorComponent::rgba(1.0, 0.0, 0.0, 1.0));
+        w.add_child(t, c).unwrap();
+        let scene = ComponentCodec::encode_world(&w);
+        let json = serde_json::to_string_pretty(&scene.to_json()).unwrap();
+
+        let report = build_report("boom", "src/main.rs:1:2", 1234, Some((240, json)));
+        assert!(report.starts_with("little-cat crash report\n"));
+        assert!(report.contains("\ntime: 1234 (unix)\n"));
+        assert!(report.contains("\npanic: boom\n"));
+        assert!(report.contains("\nlocation: src/main.rs:1:2\n"));
+        assert!(report.contains("\n== backtrace ==\n"));
+        assert!(report.contains("\n== recent log ==\n"));
+
+        let (_, section) = report.split_once("\n== scene ==\n").unwrap();
+        let body = section.strip_prefix("(snapshot from frame 240)\n").unwrap();
+        let value: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(Scene::from_json(&value).unwrap(), scene);
+
+        let report = build_report("boom", "<unknown>", 0, None);
+        assert!(report.ends_with("== scene ==\n<no snapshot>\n"));
+    }
+}
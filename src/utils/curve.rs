@@ -0,0 +1,432 @@
+//! Easing functions and editable curves.
+//!
+//! `Easing` is the usual set of normalized tweening functions (`t` in 0..1 to 0..1, some
+//! overshooting in between). A `Curve` is a list of keys with a segment shape between each key
+//! and the next (linear, constant, an easing, or a cubic Bezier with CSS `cubic-bezier`
+//! handles), for parameters that change over time or over some other input:
+//!
+//! ```ignore
+//! let size = Curve::new([
+//!     Key::new(0.0, 0.0).with_segment(Segment::Ease(Easing::BackOut)),
+//!     Key::new(0.2, 1.0),
+//!     Key::new(1.0, 0.0),
+//! ]);
+//! let s = size.sample(age / lifetime);
+//! ```
+//!
+//! Both serialize with serde; a curve is its list of keys:
+//! `[{"t": 0, "value": 0, "segment": {"ease": "back_out"}}, {"t": 0.2, "value": 1}, ...]`.
+
+use std::f32::consts::{PI, TAU};
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// A normalized easing function: `apply(0) == 0`, `apply(1) == 1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Easing {
+    #[default]
+    Linear,
+    Smoothstep,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    QuartIn,
+    QuartOut,
+    QuartInOut,
+    SineIn,
+    SineOut,
+    SineInOut,
+    ExpoIn,
+    ExpoOut,
+    ExpoInOut,
+    /// Pulls back below 0 before going.
+    BackIn,
+    /// Overshoots past 1 and settles.
+    BackOut,
+    BackInOut,
+    ElasticIn,
+    ElasticOut,
+    ElasticInOut,
+    BounceIn,
+    BounceOut,
+    BounceInOut,
+}
+
+/// The "in" form of each family; "out" and "in-out" are built from it.
+#[derive(Clone, Copy)]
+enum Shape {
+    Quad,
+    Cubic,
+    Quart,
+    Sine,
+    Expo,
+    Back,
+    Elastic,
+    Bounce,
+}
+
+#[derive(Clone, Copy)]
+enum Mode {
+    In,
+    Out,
+    InOut,
+}
+
+impl Easing {
+    pub const ALL: [Easing; 26] = [
+        Easing::Linear,
+        Easing::Smoothstep,
+        Easing::QuadIn,
+        Easing::QuadOut,
+        Easing::QuadInOut,
+        Easing::CubicIn,
+        Easing::CubicOut,
+        Easing::CubicInOut,
+        Easing::QuartIn,
+        Easing::QuartOut,
+        Easing::QuartInOut,
+        Easing::SineIn,
+        Easing::SineOut,
+        Easing::SineInOut,
+        Easing::ExpoIn,
+        Easing::ExpoOut,
+        Easing::ExpoInOut,
+        Easing::BackIn,
+        Easing::BackOut,
+        Easing::BackInOut,
+        Easing::ElasticIn,
+        Easing::ElasticOut,
+        Easing::ElasticInOut,
+        Easing::BounceIn,
+        Easing::BounceOut,
+        Easing::BounceInOut,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Easing::Linear => "linear",
+            Easing::Smoothstep => "smoothstep",
+            Easing::QuadIn => "quad_in",
+            Easing::QuadOut => "quad_out",
+            Easing::QuadInOut => "quad_in_out",
+            Easing::CubicIn => "cubic_in",
+            Easing::CubicOut => "cubic_out",
+            Easing::CubicInOut => "cubic_in_out",
+            Easing::QuartIn => "quart_in",
+            Easing::QuartOut => "quart_out",
+            Easing::QuartInOut => "quart_in_out",
+            Easing::SineIn => "sine_in",
+            Easing::SineOut => "sine_out",
+            Easing::SineInOut => "sine_in_out",
+            Easing::ExpoIn => "expo_in",
+            Easing::ExpoOut => "expo_out",
+            Easing::ExpoInOut => "expo_in_out",
+            Easing::BackIn => "back_in",
+            Easing::BackOut => "back_out",
+            Easing::BackInOut => "back_in_out",
+            Easing::ElasticIn => "elastic_in",
+            Easing::ElasticOut => "elastic_out",
+            Easing::ElasticInOut => "elastic_in_out",
+            Easing::BounceIn => "bounce_in",
+            Easing::BounceOut => "bounce_out",
+            Easing::BounceInOut => "bounce_in_out",
+        }
+    }
+
+    /// Eased `t`; `t` is clamped to 0..1.
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        let (shape, mode) = match self {
+            Easing::Linear => return t,
+            Easing::Smoothstep => return t * t * (3.0 - 2.0 * t),
+            Easing::QuadIn => (Shape::Quad, Mode::In),
+            Easing::QuadOut => (Shape::Quad, Mode::Out),
+            Easing::QuadInOut => (Shape::Quad, Mode::InOut),
+            Easing::CubicIn => (Shape::Cubic, Mode::In),
+            Easing::CubicOut => (Shape::Cubic, Mode::Out),
+            Easing::CubicInOut => (Shape::Cubic, Mode::InOut),
+            Easing::QuartIn => (Shape::Quart, Mode::In),
+            Easing::QuartOut => (Shape::Quart, Mode::Out),
+            Easing::QuartInOut => (Shape::Quart, Mode::InOut),
+            Easing::SineIn => (Shape::Sine, Mode::In),
+            Easing::SineOut => (Shape::Sine, Mode::Out),
+            Easing::SineInOut => (Shape::Sine, Mode::InOut),
+            Easing::ExpoIn => (Shape::Expo, Mode::In),
+            Easing::ExpoOut => (Shape::Expo, Mode::Out),
+            Easing::ExpoInOut => (Shape::Expo, Mode::InOut),
+            Easing::BackIn => (Shape::Back, Mode::In),
+            Easing::BackOut => (Shape::Back, Mode::Out),
+            Easing::BackInOut => (Shape::Back, Mode::InOut),
+            Easing::ElasticIn => (Shape::Elastic, Mode::In),
+            Easing::ElasticOut => (Shape::Elastic, Mode::Out),
+            Easing::ElasticInOut => (Shape::Elastic, Mode::InOut),
+            Easing::BounceIn => (Shape::Bounce, Mode::In),
+            Easing::BounceOut => (Shape::Bounce, Mode::Out),
+            Easing::BounceInOut => (Shape::Bounce, Mode::InOut),
+        };
+        match mode {
+            Mode::In => shape.ease_in(t),
+            Mode::Out => 1.0 - shape.ease_in(1.0 - t),
+            Mode::InOut if t < 0.5 => shape.ease_in(2.0 * t) / 2.0,
+            Mode::InOut => 1.0 - shape.ease_in(2.0 - 2.0 * t) / 2.0,
+        }
+    }
+}
+
+impl Shape {
+    fn ease_in(self, t: f32) -> f32 {
+        match self {
+            Shape::Quad => t * t,
+            Shape::Cubic => t * t * t,
+            Shape::Quart => t * t * t * t,
+            Shape::Sine => 1.0 - (t * PI / 2.0).cos(),
+            Shape::Expo if t <= 0.0 => 0.0,
+            Shape::Expo => 2f32.powf(10.0 * t - 10.0),
+            Shape::Back => {
+                const OVERSHOOT: f32 = 1.701_58;
+                t * t * ((OVERSHOOT + 1.0) * t - OVERSHOOT)
+            }
+            Shape::Elastic if t <= 0.0 || t >= 1.0 => t,
+            Shape::Elastic => {
+                -(2f32.powf(10.0 * t - 10.0)) * ((10.0 * t - 10.75) * TAU / 3.0).sin()
+            }
+            Shape::Bounce => 1.0 - bounce_out(1.0 - t),
+        }
+    }
+}
+
+fn bounce_out(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+    if t < 1.0 / D {
+        N * t * t
+    } else if t < 2.0 / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984_375
+    }
+}
+
+impl fmt::Display for Easing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Easing {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|e| e.name() == s)
+            .ok_or_else(|| format!("unknown easing '{s}'"))
+    }
+}
+
+/// How a curve gets from one key to the next.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Segment {
+    #[default]
+    Linear,
+    /// Holds the key's value until the next key.
+    Constant,
+    Ease(Easing),
+    /// Cubic Bezier from (0, 0) to (1, 1) through two handles, in segment-relative time and
+    /// value like CSS `cubic-bezier(x1, y1, x2, y2)`; handle times are clamped to 0..1.
+    Bezier([f32; 2], [f32; 2]),
+}
+
+impl Segment {
+    fn is_linear(&self) -> bool {
+        *self == Segment::Linear
+    }
+
+    /// Progress 0..1 through the segment at relative time `u` in 0..1.
+    fn progress(self, u: f32) -> f32 {
+        match self {
+            Segment::Linear => u,
+            Segment::Constant => 0.0,
+            Segment::Ease(easing) => easing.apply(u),
+            Segment::Bezier([x1, y1], [x2, y2]) => {
+                let s = bezier_solve(x1.clamp(0.0, 1.0), x2.clamp(0.0, 1.0), u);
+                bezier(y1, y2, s)
+            }
+        }
+    }
+}
+
+/// One coordinate of a cubic Bezier from 0 to 1 with control values `c1`, `c2`.
+fn bezier(c1: f32, c2: f32, s: f32) -> f32 {
+    let r = 1.0 - s;
+    3.0 * r * r * s * c1 + 3.0 * r * s * s * c2 + s * s * s
+}
+
+/// The parameter where the (monotonic) x coordinate reaches `x`: Newton steps, then bisection
+/// if they don't converge.
+fn bezier_solve(x1: f32, x2: f32, x: f32) -> f32 {
+    let mut s = x;
+    for _ in 0..8 {
+        let error = bezier(x1, x2, s) - x;
+        if error.abs() < 1e-6 {
+            return s;
+        }
+        let r = 1.0 - s;
+        let slope = 3.0 * r * r * x1 + 6.0 * r * s * (x2 - x1) + 3.0 * s * s * (1.0 - x2);
+        if slope.abs() < 1e-6 {
+            break;
+        }
+        s = (s - error / slope).clamp(0.0, 1.0);
+    }
+    let (mut lo, mut hi) = (0.0, 1.0);
+    for _ in 0..32 {
+        s = (lo + hi) / 2.0;
+        if bezier(x1, x2, s) < x {
+            lo = s;
+        } else {
+            hi = s;
+        }
+    }
+    s
+}
+
+/// A point of a curve, and the shape of the segment to the next key.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Key {
+    pub t: f32,
+    pub value: f32,
+    #[serde(default, skip_serializing_if = "Segment::is_linear")]
+    pub segment: Segment,
+}
+
+impl Key {
+    pub fn new(t: f32, value: f32) -> Self {
+        Self {
+            t,
+            value,
+            segment: Segment::Linear,
+        }
+    }
+
+    pub fn with_segment(mut self, segment: Segment) -> Self {
+        self.segment = segment;
+        self
+    }
+}
+
+/// A piecewise function of one variable, through keys kept sorted by `t`.
+///
+/// Before the first key it holds the first value, after the last the last value; an empty
+/// curve is 0 everywhere.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "Vec<Key>", into = "Vec<Key>")]
+pub struct Curve {
+    keys: Vec<Key>,
+}
+
+impl Curve {
+    pub fn new(keys: impl IntoIterator<Item = Key>) -> Self {
+        let mut curve = Self::default();
+        for key in keys {
+            curve.insert(key);
+        }
+        curve
+    }
+
+    pub fn constant(value: f32) -> Self {
+        Self::new([Key::new(0.0, value)])
+    }
+
+    /// From `from` at t = 0 to `to` at t = 1.
+    pub fn linear(from: f32, to: f32) -> Self {
+        Self::new([Key::new(0.0, from), Key::new(1.0, to)])
+    }
+
+    /// From `from` at t = 0 to `to` at t = 1, eased.
+    pub fn eased(from: f32, to: f32, easing: Easing) -> Self {
+        Self::new([
+            Key::new(0.0, from).with_segment(Segment::Ease(easing)),
+            Key::new(1.0, to),
+        ])
+    }
+
+    pub fn with_key(mut self, key: Key) -> Self {
+        self.insert(key);
+        self
+    }
+
+    /// Add `key` in order, replacing a key at the same `t`. Returns its index.
+    pub fn insert(&mut self, key: Key) -> usize {
+        let i = self.keys.partition_point(|k| k.t < key.t);
+        match self.keys.get_mut(i) {
+            Some(existing) if existing.t == key.t => *existing = key,
+            _ => self.keys.insert(i, key),
+        }
+        i
+    }
+
+    /// Replace the key at `index` (which may move it). Returns its new index.
+    pub fn set_key(&mut self, index: usize, key: Key) -> Option<usize> {
+        self.remove(index)?;
+        Some(self.insert(key))
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<Key> {
+        (index < self.keys.len()).then(|| self.keys.remove(index))
+    }
+
+    pub fn keys(&self) -> &[Key] {
+        &self.keys
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// `t` of the first and last keys.
+    pub fn range(&self) -> Option<(f32, f32)> {
+        Some((self.keys.first()?.t, self.keys.last()?.t))
+    }
+
+    pub fn sample(&self, t: f32) -> f32 {
+        let i = self.keys.partition_point(|k| k.t <= t);
+        let (Some(a), Some(b)) = (i.checked_sub(1).map(|i| &self.keys[i]), self.keys.get(i)) else {
+            // Outside the keys, or none at all.
+            return self
+                .keys
+                .get(i)
+                .or(self.keys.last())
+                .map_or(0.0, |k| k.value);
+        };
+        let u = (t - a.t) / (b.t - a.t);
+        a.value + (b.value - a.value) * a.segment.progress(u)
+    }
+}
+
+impl From<Vec<Key>> for Curve {
+    fn from(keys: Vec<Key>) -> Self {
+        Self::new(keys)
+    }
+}
+
+impl From<Curve> for Vec<Key> {
+    fn from(curve: Curve) -> Self {
+        curve.keys
+    }
+}
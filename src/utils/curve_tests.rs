@@ -0,0 +1,49 @@
+#[cfg(test)]
+mod tests {
+    use crate::utils::curve::{Curve, Easing, Key, Segment};
+
+    #[test]
+    fn easings_start_at_zero_and_end_at_one() {
+        for easing in Easing::ALL {
+            assert!(easing.apply(0.0).abs() < 1e-4, "{easing}");
+            assert!((easing.apply(1.0) - 1.0).abs() < 1e-4, "{easing}");
+            assert_eq!(easing.name().parse(), Ok(easing));
+        }
+        assert_eq!(Easing::QuadIn.apply(0.5), 0.25);
+        assert_eq!(Easing::QuadOut.apply(0.5), 0.75);
+        assert_eq!(Easing::CubicInOut.apply(0.5), 0.5);
+        assert!(Easing::BackOut.apply(0.6) > 1.0, "overshoots");
+    }
+
+    #[test]
+    fn curves_interpolate_between_sorted_keys_and_serialize() {
+        let mut curve = Curve::new([
+            Key::new(1.0, 10.0),
+            Key::new(0.0, 0.0).with_segment(Segment::Ease(Easing::QuadIn)),
+            Key::new(2.0, 10.0).with_segment(Segment::Constant),
+        ]);
+        assert_eq!(curve.range(), Some((0.0, 2.0)));
+        assert_eq!(curve.sample(-1.0), 0.0);
+        assert_eq!(curve.sample(0.5), 2.5);
+        assert_eq!(curve.sample(1.5), 10.0);
+        assert_eq!(curve.sample(5.0), 10.0);
+
+        // A CSS `ease` handle pair: slow in and out, symmetric about the middle of the segment.
+        let ease = Key::new(2.0, 10.0).with_segment(Segment::Bezier([0.42, 0.0], [0.58, 1.0]));
+        assert_eq!(curve.set_key(2, ease), Some(2));
+        curve.insert(Key::new(3.0, 20.0));
+        assert!((curve.sample(2.0) - 10.0).abs() < 1e-4);
+        assert!((curve.sample(2.5) - 15.0).abs() < 1e-3);
+        assert!(curve.sample(2.1) < 11.0);
+
+        let json = serde_json::to_value(&curve).unwrap();
+        assert_eq!(
+            json[0],
+            serde_json::json!({"t": 0.0, "value": 0.0, "segment": {"ease": "quad_in"}})
+        );
+        assert_eq!(json[3], serde_json::json!({"t": 3.0, "value": 20.0}));
+        let back: Curve = serde_json::from_value(json).unwrap();
+        assert_eq!(back, curve);
+        assert_eq!(Curve::default().sample(1.0), 0.0);
+    }
+}
@@ -0,0 +1,61 @@
+//! Bump allocation for scratch data that lives for one frame.
+//!
+//! Rebuilding per-frame tables (sort keys, packed instance data, ...) in fresh `Vec`s means a
+//! malloc/free pair per table per frame. A `FrameArena` hands out vectors from one bump region
+//! instead and frees them all at once in `reset`, which the owner calls at the end of every
+//! frame. After a few frames the region settles at the largest frame's size and scratch
+//! allocation stops touching the system allocator.
+//!
+//! `reset` publishes profiling gauges under `frame_arena.` (`stats frame_arena`).
+
+use std::cell::Cell;
+
+use bumpalo::Bump;
+use bumpalo::collections::Vec as BumpVec;
+
+use crate::utils::profiling;
+
+#[derive(Debug, Default)]
+pub struct FrameArena {
+    bump: Bump,
+    /// Bytes requested through `vec_with_capacity` this frame.
+    requested_bytes: Cell<usize>,
+    /// Most bytes any frame requested so far.
+    peak_bytes: usize,
+}
+
+impl FrameArena {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// An empty vector allocated in the arena, valid until the next `reset`.
+    pub fn vec<T>(&self) -> BumpVec<'_, T> {
+        BumpVec::new_in(&self.bump)
+    }
+
+    pub fn vec_with_capacity<T>(&self, capacity: usize) -> BumpVec<'_, T> {
+        self.requested_bytes
+            .set(self.requested_bytes.get() + capacity * size_of::<T>());
+        BumpVec::with_capacity_in(capacity, &self.bump)
+    }
+
+    /// Bytes reserved from the system allocator (used or not).
+    pub fn capacity_bytes(&self) -> usize {
+        self.bump.allocated_bytes()
+    }
+
+    pub fn peak_bytes(&self) -> usize {
+        self.peak_bytes
+    }
+
+    /// Free everything allocated this frame and publish the frame's usage.
+    pub fn reset(&mut self) {
+        let requested = self.requested_bytes.replace(0);
+        self.peak_bytes = self.peak_bytes.max(requested);
+        self.bump.reset();
+        profiling::set_gauge("frame_arena.requested_bytes", requested as f64);
+        profiling::set_gauge("frame_arena.peak_bytes", self.peak_bytes as f64);
+        profiling::set_gauge("frame_arena.capacity_bytes", self.capacity_bytes() as f64);
+    }
+}
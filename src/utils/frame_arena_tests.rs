@@ -0,0 +1,28 @@
+#[cfg(test)]
+mod tests {
+    use crate::utils::frame_arena::FrameArena;
+
+    #[test]
+    fn reset_reuses_the_region_and_tracks_the_peak() {
+        let mut arena = FrameArena::new();
+        let frame = |arena: &FrameArena, n: u32| {
+            let mut keys = arena.vec_with_capacity(n as usize);
+            keys.extend((0..n).rev());
+            keys.sort_unstable();
+            assert_eq!(keys.first(), Some(&0));
+        };
+
+        frame(&arena, 4096);
+        arena.reset();
+        let settled = arena.capacity_bytes();
+        assert!(arena.peak_bytes() >= 4096 * 4);
+
+        // Same-sized frames fit in what the first one reserved.
+        for _ in 0..8 {
+            frame(&arena, 4096);
+            arena.reset();
+        }
+        assert_eq!(arena.capacity_bytes(), settled);
+        assert!(arena.vec::<u32>().is_empty());
+    }
+}
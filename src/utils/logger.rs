@@ -1,5 +1,351 @@
-/// Logging placeholder.
+//! Engine logging: structured records with per-module filters and a rotating file sink.
+//!
+//! Use the macros (`log_error!`, `log_warn!`, `log_info!`, `log_debug!`, `log_trace!`);
+//! each record carries its level, module path, message and the current frame number.
+//!
+//! Filters:
+//! - `LITTLECAT_LOG` at startup, e.g. `info,engine::graphics=debug,engine::ecs=warn`.
+//! - The REPL `log` command at runtime (see `engine::repl`).
+//!
+//! A module filter applies to every module whose path (without the crate name) starts with
+//! it; the longest matching filter wins, otherwise the default level applies.
+//!
+//! Records go to stdout and to `logs/little-cat.log`, which rotates to `.1`..`.3` when it
+//! grows past `LOG_FILE_MAX_BYTES`.
 
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+/// Environment variable holding the startup filter spec.
+pub const LOG_ENV_VAR: &str = "LITTLECAT_LOG";
+
+pub const LOG_DIR: &str = "logs";
+pub const LOG_FILE_NAME: &str = "little-cat.log";
+pub const LOG_FILE_MAX_BYTES: u64 = 4 * 1024 * 1024;
+/// Rotated files kept next to the live one (`.1` is the newest).
+pub const LOG_FILE_KEEP: usize = 3;
+/// Formatted records kept in memory for crash reports (see `recent_lines`).
+pub const LOG_RECENT_LINES: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "error" => Ok(Level::Error),
+            "warn" | "warning" => Ok(Level::Warn),
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            "trace" => Ok(Level::Trace),
+            _ => Err(format!("unknown log level '{s}'")),
+        }
+    }
+}
+
+/// One log record.
+#[derive(Debug, Clone)]
+pub struct Record<'a> {
+    pub level: Level,
+    /// Module path without the crate name (e.g. `engine::ecs::system::texture_system`).
+    pub module: &'a str,
+    pub frame: u64,
+    pub message: fmt::Arguments<'a>,
+}
+
+impl fmt::Display for Record<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[frame {}] {:<5} {}: {}",
+            self.frame, self.level, self.module, self.message
+        )
+    }
+}
+
+struct Logger {
+    default_level: Level,
+    /// (module prefix, level); longest prefix wins.
+    filters: Vec<(String, Level)>,
+    file: Option<FileSink>,
+    recent: VecDeque<String>,
+}
+
+static LOGGER: Mutex<Logger> = Mutex::new(Logger {
+    default_level: Level::Info,
+    filters: Vec::new(),
+    file: None,
+    recent: VecDeque::new(),
+});
+
+static FRAME: AtomicU64 = AtomicU64::new(0);
+
+fn logger() -> MutexGuard<'static, Logger> {
+    // A panic while logging must not disable logging for everyone else.
+    LOGGER.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Apply `LITTLECAT_LOG` and open the file sink. Call once at startup.
 pub fn init() {
-    // TODO: swap to `tracing` + `tracing_subscriber` when you want structured logs.
+    if let Ok(spec) = std::env::var(LOG_ENV_VAR)
+        && let Err(e) = apply_filter_spec(&spec)
+    {
+        eprintln!("[logger] {LOG_ENV_VAR}: {e}");
+    }
+    match FileSink::open(PathBuf::from(LOG_DIR).join(LOG_FILE_NAME)) {
+        Ok(sink) => logger().file = Some(sink),
+        Err(e) => eprintln!("[logger] file sink disabled: {e}"),
+    }
+}
+
+/// Set the frame number stamped on subsequent records.
+pub fn set_frame(frame: u64) {
+    FRAME.store(frame, Ordering::Relaxed);
+}
+
+pub fn frame() -> u64 {
+    FRAME.load(Ordering::Relaxed)
+}
+
+pub fn set_default_level(level: Level) {
+    logger().default_level = level;
+}
+
+pub fn default_level() -> Level {
+    logger().default_level
+}
+
+/// Set (or replace) the level of a module prefix, e.g. `engine::graphics`.
+pub fn set_module_level(module: &str, level: Level) {
+    let module = strip_crate(module).to_string();
+    let mut l = logger();
+    match l.filters.iter_mut().find(|(m, _)| *m == module) {
+        Some(entry) => entry.1 = level,
+        None => l.filters.push((module, level)),
+    }
+}
+
+/// Remove a module filter. Returns `false` if there was none.
+pub fn clear_module_level(module: &str) -> bool {
+    let module = strip_crate(module);
+    let mut l = logger();
+    let before = l.filters.len();
+    l.filters.retain(|(m, _)| m != module);
+    l.filters.len() != before
+}
+
+/// Current module filters, sorted by module.
+pub fn module_levels() -> Vec<(String, Level)> {
+    let mut filters = logger().filters.clone();
+    filters.sort();
+    filters
+}
+
+/// Apply a filter spec: comma-separated `level` (default) and `module=level` entries.
+pub fn apply_filter_spec(spec: &str) -> Result<(), String> {
+    for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        match entry.split_once('=') {
+            Some((module, level)) => set_module_level(module.trim(), level.trim().parse()?),
+            None => set_default_level(entry.parse()?),
+        }
+    }
+    Ok(())
+}
+
+/// Whether a record at `level` from `module` would be written.
+pub fn enabled(level: Level, module: &str) -> bool {
+    let module = strip_crate(module);
+    let l = logger();
+    let max = l
+        .filters
+        .iter()
+        .filter(|(m, _)| module_matches(module, m))
+        .max_by_key(|(m, _)| m.len())
+        .map(|(_, level)| *level)
+        .unwrap_or(l.default_level);
+    level <= max
+}
+
+/// Write a record. Prefer the `log_*!` macros, which fill in `module`.
+pub fn log(level: Level, module: &str, message: fmt::Arguments<'_>) {
+    if !enabled(level, module) {
+        return;
+    }
+    let record = Record {
+        level,
+        module: strip_crate(module),
+        frame: frame(),
+        message,
+    };
+    let line = record.to_string();
+    println!("{line}");
+
+    let mut l = logger();
+    if let Some(file) = l.file.as_mut()
+        && file.write_line(&line).is_err()
+    {
+        l.file = None;
+    }
+    if l.recent.len() == LOG_RECENT_LINES {
+        l.recent.pop_front();
+    }
+    l.recent.push_back(line);
+}
+
+/// The last `LOG_RECENT_LINES` records, oldest first.
+///
+/// Uses `try_lock`: this is called from the panic hook, possibly on a thread that panicked
+/// while holding the logger lock. Returns `None` if the lock isn't available.
+pub fn recent_lines() -> Option<Vec<String>> {
+    let l = match LOGGER.try_lock() {
+        Ok(l) => l,
+        Err(std::sync::TryLockError::Poisoned(e)) => e.into_inner(),
+        Err(std::sync::TryLockError::WouldBlock) => return None,
+    };
+    Some(l.recent.iter().cloned().collect())
+}
+
+/// `little_cat::engine::ecs` -> `engine::ecs`.
+fn strip_crate(module: &str) -> &str {
+    module
+        .strip_prefix(env!("CARGO_CRATE_NAME"))
+        .and_then(|m| m.strip_prefix("::"))
+        .unwrap_or(module)
+}
+
+fn module_matches(module: &str, prefix: &str) -> bool {
+    module == prefix
+        || module
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with("::"))
+}
+
+/// Size-rotated log file.
+struct FileSink {
+    path: PathBuf,
+    file: File,
+    len: u64,
+}
+
+impl FileSink {
+    fn open(path: PathBuf) -> std::io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let len = file.metadata()?.len();
+        Ok(Self { path, file, len })
+    }
+
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        if self.len >= LOG_FILE_MAX_BYTES {
+            self.rotate()?;
+        }
+        writeln!(self.file, "{line}")?;
+        self.len += line.len() as u64 + 1;
+        Ok(())
+    }
+
+    /// `log.2 -> log.3`, `log.1 -> log.2`, `log -> log.1`, then start a fresh file.
+    fn rotate(&mut self) -> std::io::Result<()> {
+        let rotated = |n: usize| PathBuf::from(format!("{}.{n}", self.path.display()));
+        for n in (1..LOG_FILE_KEEP).rev() {
+            let _ = std::fs::rename(rotated(n), rotated(n + 1));
+        }
+        std::fs::rename(&self.path, rotated(1))?;
+        self.file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&self.path)?;
+        self.len = 0;
+        Ok(())
+    }
+}
+
+#[macro_export]
+macro_rules! log_error {
+    ($($arg:tt)*) => {
+        $crate::utils::logger::log(
+            $crate::utils::logger::Level::Error,
+            module_path!(),
+            format_args!($($arg)*),
+        )
+    };
+}
+
+#[macro_export]
+macro_rules! log_warn {
+    ($($arg:tt)*) => {
+        $crate::utils::logger::log(
+            $crate::utils::logger::Level::Warn,
+            module_path!(),
+            format_args!($($arg)*),
+        )
+    };
+}
+
+#[macro_export]
+macro_rules! log_info {
+    ($($arg:tt)*) => {
+        $crate::utils::logger::log(
+            $crate::utils::logger::Level::Info,
+            module_path!(),
+            format_args!($($arg)*),
+        )
+    };
+}
+
+#[macro_export]
+macro_rules! log_debug {
+    ($($arg:tt)*) => {
+        $crate::utils::logger::log(
+            $crate::utils::logger::Level::Debug,
+            module_path!(),
+            format_args!($($arg)*),
+        )
+    };
+}
+
+#[macro_export]
+macro_rules! log_trace {
+    ($($arg:tt)*) => {
+        $crate::utils::logger::log(
+            $crate::utils::logger::Level::Trace,
+            module_path!(),
+            format_args!($($arg)*),
+        )
+    };
 }
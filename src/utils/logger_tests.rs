@@ -0,0 +1,21 @@
+#[cfg(test)]
+mod tests {
+    use crate::utils::logger::{self, Level};
+
+    #[test]
+    fn longest_module_filter_wins() {
+        logger::apply_filter_spec("warn,engine::graphics=debug,engine::graphics::vulkano=error")
+            .unwrap();
+
+        assert!(!logger::enabled(Level::Info, "little_cat::engine::ecs"));
+        assert!(logger::enabled(Level::Warn, "little_cat::engine::ecs"));
+        assert!(logger::enabled(Level::Debug, "engine::graphics::mesh"));
+        assert!(!logger::enabled(Level::Warn, "engine::graphics::vulkano"));
+        // Prefixes match whole path segments only.
+        assert!(!logger::enabled(Level::Debug, "engine::graphicsx"));
+
+        assert!(logger::clear_module_level("engine::graphics::vulkano"));
+        assert!(logger::enabled(Level::Warn, "engine::graphics::vulkano"));
+        assert!(logger::apply_filter_spec("loud").is_err());
+    }
+}
@@ -1 +1,21 @@
+pub mod clipboard;
+pub mod color;
+pub mod crash_report;
+pub mod curve;
+pub mod frame_arena;
 pub mod logger;
+pub mod profiling;
+pub mod trace;
+
+#[cfg(test)]
+mod color_tests;
+#[cfg(test)]
+mod crash_report_tests;
+#[cfg(test)]
+mod curve_tests;
+#[cfg(test)]
+mod frame_arena_tests;
+#[cfg(test)]
+mod logger_tests;
+#[cfg(test)]
+mod trace_tests;
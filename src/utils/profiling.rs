@@ -0,0 +1,90 @@
+//! Engine profiling: named gauges and per-frame counters, with a ring buffer of recent frames.
+//!
+//! - Gauges hold the latest value of something (`textures.resident_bytes`); set them whenever
+//!   the value changes.
+//! - Counters accumulate over one frame (`textures.uploads`); `Universe::update` closes the
+//!   frame with `end_frame`, which moves them into the history.
+//!
+//! Names are dot-separated, subsystem first, so the REPL `stats <prefix>` can filter them.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Mutex, MutexGuard};
+
+/// Frames kept by `history`.
+pub const PROFILE_HISTORY_FRAMES: usize = 240;
+
+/// Counters of one finished frame.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FrameProfile {
+    pub frame: u64,
+    pub dt_sec: f32,
+    /// Sorted by name.
+    pub counters: Vec<(&'static str, u64)>,
+}
+
+impl FrameProfile {
+    pub fn counter(&self, name: &str) -> u64 {
+        self.counters
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map_or(0, |(_, v)| *v)
+    }
+}
+
+struct Profiler {
+    gauges: BTreeMap<&'static str, f64>,
+    counters: BTreeMap<&'static str, u64>,
+    history: VecDeque<FrameProfile>,
+}
+
+static PROFILER: Mutex<Profiler> = Mutex::new(Profiler {
+    gauges: BTreeMap::new(),
+    counters: BTreeMap::new(),
+    history: VecDeque::new(),
+});
+
+fn profiler() -> MutexGuard<'static, Profiler> {
+    PROFILER.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+pub fn set_gauge(name: &'static str, value: f64) {
+    profiler().gauges.insert(name, value);
+}
+
+pub fn gauge(name: &str) -> Option<f64> {
+    profiler().gauges.get(name).copied()
+}
+
+/// Every gauge, sorted by name.
+pub fn gauges() -> Vec<(&'static str, f64)> {
+    profiler().gauges.iter().map(|(n, v)| (*n, *v)).collect()
+}
+
+/// Add to a counter of the current frame.
+pub fn count(name: &'static str, delta: u64) {
+    *profiler().counters.entry(name).or_insert(0) += delta;
+}
+
+/// Close the current frame: its counters move into the history and start again from zero.
+pub fn end_frame(frame: u64, dt_sec: f32) {
+    let mut p = profiler();
+    let counters = std::mem::take(&mut p.counters).into_iter().collect();
+    if p.history.len() == PROFILE_HISTORY_FRAMES {
+        p.history.pop_front();
+    }
+    p.history.push_back(FrameProfile {
+        frame,
+        dt_sec,
+        counters,
+    });
+}
+
+/// The last finished frame.
+pub fn last_frame() -> Option<FrameProfile> {
+    profiler().history.back().cloned()
+}
+
+/// Up to `PROFILE_HISTORY_FRAMES` finished frames, oldest first.
+pub fn history() -> Vec<FrameProfile> {
+    profiler().history.iter().cloned().collect()
+}
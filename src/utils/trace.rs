@@ -0,0 +1,171 @@
+//! Span capture in the Trace Event format of chrome://tracing, for finding the frame spikes
+//! that `profiling`'s per-frame counters average away.
+//!
+//! Capturing is off until `start` (REPL `trace start`); `stop` hands back the spans recorded
+//! since, and `TraceCapture::write` saves them as JSON for chrome://tracing or
+//! https://ui.perfetto.dev. Instrumented code holds a guard for the span's duration:
+//!
+//! ```ignore
+//! let _span = trace::span("systems.movement");
+//! ```
+//!
+//! While no capture runs a span costs one atomic load. Names are dot-separated like profiling
+//! names; the part before the first dot becomes the event category.
+
+use std::cell::Cell;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Instant;
+
+use crate::engine::ecs::codec::{Map, Value};
+
+/// Spans kept per capture; later ones are counted in `TraceCapture::dropped` instead.
+pub const MAX_TRACE_EVENTS: usize = 1 << 20;
+
+static CAPTURING: AtomicBool = AtomicBool::new(false);
+static NEXT_THREAD: AtomicU64 = AtomicU64::new(1);
+static CAPTURE: Mutex<Option<TraceCapture>> = Mutex::new(None);
+
+thread_local! {
+    /// Small stable id of this thread, 0 until its first span.
+    static THREAD: Cell<u64> = const { Cell::new(0) };
+}
+
+fn capture() -> MutexGuard<'static, Option<TraceCapture>> {
+    CAPTURE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// One finished span.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceEvent {
+    pub name: &'static str,
+    pub thread: u64,
+    /// Microseconds since the capture started.
+    pub start_us: f64,
+    pub duration_us: f64,
+}
+
+/// Spans recorded between `start` and `stop`.
+#[derive(Debug, Clone)]
+pub struct TraceCapture {
+    started: Instant,
+    pub events: Vec<TraceEvent>,
+    /// (thread id, thread name) of every thread that recorded a span.
+    pub threads: Vec<(u64, String)>,
+    /// Spans lost to `MAX_TRACE_EVENTS`.
+    pub dropped: usize,
+}
+
+impl TraceCapture {
+    /// The capture as a Trace Event JSON object.
+    pub fn to_json(&self) -> Value {
+        let mut events: Vec<Value> = self
+            .threads
+            .iter()
+            .map(|(tid, name)| {
+                let mut args = Map::new();
+                args.insert("name".into(), Value::from(name.as_str()));
+                let mut meta = Map::new();
+                meta.insert("name".into(), Value::from("thread_name"));
+                meta.insert("ph".into(), Value::from("M"));
+                meta.insert("pid".into(), Value::from(1));
+                meta.insert("tid".into(), Value::from(*tid));
+                meta.insert("args".into(), Value::Object(args));
+                Value::Object(meta)
+            })
+            .collect();
+        events.extend(self.events.iter().map(|e| {
+            let mut event = Map::new();
+            event.insert("name".into(), Value::from(e.name));
+            let category = e.name.split('.').next().unwrap_or(e.name);
+            event.insert("cat".into(), Value::from(category));
+            event.insert("ph".into(), Value::from("X"));
+            event.insert("ts".into(), Value::from(e.start_us));
+            event.insert("dur".into(), Value::from(e.duration_us));
+            event.insert("pid".into(), Value::from(1));
+            event.insert("tid".into(), Value::from(e.thread));
+            Value::Object(event)
+        }));
+        let mut out = Map::new();
+        out.insert("traceEvents".into(), Value::Array(events));
+        out.insert("displayTimeUnit".into(), Value::from("ms"));
+        Value::Object(out)
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.to_json().to_string())
+    }
+}
+
+/// Begin a capture, discarding one that was running.
+pub fn start() {
+    *capture() = Some(TraceCapture {
+        started: Instant::now(),
+        events: Vec::new(),
+        threads: Vec::new(),
+        dropped: 0,
+    });
+    CAPTURING.store(true, Ordering::Release);
+}
+
+/// End the capture; `None` if none was running.
+pub fn stop() -> Option<TraceCapture> {
+    CAPTURING.store(false, Ordering::Release);
+    capture().take()
+}
+
+pub fn is_capturing() -> bool {
+    CAPTURING.load(Ordering::Acquire)
+}
+
+/// Guard recording a span from now until it is dropped.
+#[must_use = "the span ends when the guard is dropped"]
+pub struct Span {
+    name: &'static str,
+    start: Option<Instant>,
+}
+
+/// Start a span named `name` (see the module docs).
+pub fn span(name: &'static str) -> Span {
+    Span {
+        name,
+        start: is_capturing().then(Instant::now),
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let Some(start) = self.start else {
+            return;
+        };
+        let end = Instant::now();
+        let mut guard = capture();
+        // The capture may have stopped (or restarted) since the span began.
+        let Some(capture) = guard.as_mut().filter(|c| c.started <= start) else {
+            return;
+        };
+        if capture.events.len() == MAX_TRACE_EVENTS {
+            capture.dropped += 1;
+            return;
+        }
+        let thread = THREAD.with(|id| {
+            if id.get() == 0 {
+                id.set(NEXT_THREAD.fetch_add(1, Ordering::Relaxed));
+            }
+            id.get()
+        });
+        if !capture.threads.iter().any(|(tid, _)| *tid == thread) {
+            let name = std::thread::current()
+                .name()
+                .map_or_else(|| format!("thread {thread}"), str::to_string);
+            capture.threads.push((thread, name));
+        }
+        capture.events.push(TraceEvent {
+            name: self.name,
+            thread,
+            start_us: start.duration_since(capture.started).as_secs_f64() * 1e6,
+            duration_us: end.duration_since(start).as_secs_f64() * 1e6,
+        });
+    }
+}
@@ -0,0 +1,44 @@
+#[cfg(test)]
+mod tests {
+    use crate::utils::trace;
+
+    #[test]
+    fn spans_are_captured_only_while_tracing() {
+        drop(trace::span("test.before"));
+        trace::start();
+        {
+            let _outer = trace::span("test.outer");
+            drop(trace::span("test.inner"));
+        }
+        let capture = trace::stop().unwrap();
+        drop(trace::span("test.after"));
+        assert!(trace::stop().is_none());
+
+        // Other tests may be running instrumented code at the same time.
+        let ours: Vec<_> = capture
+            .events
+            .iter()
+            .filter(|e| e.name.starts_with("test."))
+            .collect();
+        assert_eq!(
+            ours.iter().map(|e| e.name).collect::<Vec<_>>(),
+            vec!["test.inner", "test.outer"]
+        );
+        let (inner, outer) = (ours[0], ours[1]);
+        assert!(outer.start_us <= inner.start_us);
+        assert!(inner.start_us + inner.duration_us <= outer.start_us + outer.duration_us);
+
+        let json = capture.to_json();
+        let events = json["traceEvents"].as_array().unwrap();
+        let outer = events.iter().find(|e| e["name"] == "test.outer").unwrap();
+        assert_eq!(
+            (outer["ph"].as_str(), outer["cat"].as_str()),
+            (Some("X"), Some("test"))
+        );
+        assert!(
+            events
+                .iter()
+                .any(|e| e["ph"] == "M" && e["tid"] == outer["tid"])
+        );
+    }
+}